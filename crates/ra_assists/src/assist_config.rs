@@ -7,6 +7,17 @@
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AssistConfig {
     pub snippet_cap: Option<SnippetCap>,
+    /// Whether `add_new` initializes fields implementing `Default` instead of
+    /// taking them as parameters.
+    pub omit_default_fields_in_new: bool,
+    /// Whether to offer adding `#[inline]` hints to functions.
+    pub inline_hints: bool,
+    /// Whether getters generated by `generate_accessors` are named `get_field`
+    /// instead of `field`.
+    pub prefix_getters: bool,
+    /// Whether the `TryFrom<&str>` impls generated by `generate_enum_str_conversions`
+    /// ignore the ASCII case of the variant names.
+    pub case_insensitive_str_conversions: bool,
 }
 
 impl AssistConfig {
@@ -22,6 +33,12 @@ pub struct SnippetCap {
 
 impl Default for AssistConfig {
     fn default() -> Self {
-        AssistConfig { snippet_cap: Some(SnippetCap { _private: () }) }
+        AssistConfig {
+            snippet_cap: Some(SnippetCap { _private: () }),
+            omit_default_fields_in_new: false,
+            inline_hints: false,
+            prefix_getters: false,
+            case_insensitive_str_conversions: false,
+        }
     }
 }
@@ -7,7 +7,7 @@ use hir::Semantics;
 use ra_db::{FileId, FileRange};
 use ra_fmt::{leading_indent, reindent};
 use ra_ide_db::{
-    source_change::{SourceChange, SourceFileEdit},
+    source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
     RootDatabase,
 };
 use ra_syntax::{
@@ -175,6 +175,7 @@ pub(crate) struct AssistBuilder {
     file_id: FileId,
     is_snippet: bool,
     edits: Vec<SourceFileEdit>,
+    file_system_edits: Vec<FileSystemEdit>,
 }
 
 impl AssistBuilder {
@@ -184,10 +185,14 @@ impl AssistBuilder {
             file_id,
             is_snippet: false,
             edits: Vec::new(),
+            file_system_edits: Vec::new(),
         }
     }
 
     pub(crate) fn edit_file(&mut self, file_id: FileId) {
+        if file_id != self.file_id {
+            self.commit();
+        }
         self.file_id = file_id;
     }
 
@@ -255,6 +260,11 @@ impl AssistBuilder {
         algo::diff(&node, &new).into_text_edit(&mut self.edit)
     }
 
+    /// Deletes the file with the given `file_id`.
+    pub(crate) fn delete_file(&mut self, file_id: FileId) {
+        self.file_system_edits.push(FileSystemEdit::DeleteFile { file: file_id })
+    }
+
     // FIXME: kill this API
     /// Get access to the raw `TextEditBuilder`.
     pub(crate) fn text_edit_builder(&mut self) -> &mut TextEditBuilder {
@@ -263,7 +273,10 @@ impl AssistBuilder {
 
     fn finish(mut self) -> SourceChange {
         self.commit();
-        let mut res: SourceChange = mem::take(&mut self.edits).into();
+        let mut res = SourceChange::from_edits(
+            mem::take(&mut self.edits),
+            mem::take(&mut self.file_system_edits),
+        );
         if self.is_snippet {
             res.is_snippet = true;
         }
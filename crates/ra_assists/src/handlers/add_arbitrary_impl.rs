@@ -0,0 +1,231 @@
+use hir::{Adt, ImplDef, ScopeDef};
+use ra_syntax::ast::{self, AstNode, NameOwner, StructKind, TypeParamsOwner};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{add_derive, has_derive},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_arbitrary_impl
+//
+// Implements `arbitrary::Arbitrary` for a struct, for use in fuzz targets.
+// The derive is added if it is enabled with the `derive` feature of
+// `arbitrary` and all fields implement `Arbitrary`, otherwise a manual impl
+// is generated.
+//
+// ```
+// # //- /main.rs crate:main deps:arbitrary
+// struct Point {
+//     x: u32,
+//     y: u32,<|>
+// }
+// # //- /lib.rs crate:arbitrary
+// # pub trait Arbitrary<'a> {}
+// ```
+// ->
+// ```
+// struct Point {
+//     x: u32,
+//     y: u32,
+// }
+//
+// impl<'a> arbitrary::Arbitrary<'a> for Point {
+//     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+//         Ok(Point { x: ${1:u.arbitrary()?}, y: ${2:u.arbitrary()?} })
+//     }
+// }
+// ```
+pub(crate) fn add_arbitrary_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    if has_derive(&strukt, "Arbitrary") {
+        return None;
+    }
+
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let krate = struct_def.module(ctx.db).krate();
+    let arbitrary = krate
+        .dependencies(ctx.db)
+        .into_iter()
+        .find(|dep| dep.name.to_string() == "arbitrary")?
+        .krate;
+    let arbitrary_scope = arbitrary.root_module(ctx.db)?.scope(ctx.db, None);
+    let arbitrary_trait = arbitrary_scope.iter().find_map(|(name, def)| match def {
+        ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) if name.to_string() == "Arbitrary" => {
+            Some(*it)
+        }
+        _ => None,
+    })?;
+    // The derive macro is re-exported by `arbitrary` only when its `derive`
+    // feature is enabled.
+    let has_derive_macro = arbitrary_scope
+        .iter()
+        .any(|(name, def)| matches!(def, ScopeDef::MacroDef(_)) && name.to_string() == "Arbitrary");
+    let has_impl = ImplDef::for_trait(ctx.db, krate, arbitrary_trait)
+        .into_iter()
+        .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(struct_def)));
+    if has_impl {
+        return None;
+    }
+
+    let field_types = struct_def.fields(ctx.db).into_iter().map(|it| it.signature_ty(ctx.db));
+    let all_fields_arbitrary = field_types
+        .collect::<Vec<_>>()
+        .iter()
+        .all(|ty| ty.impls_trait(ctx.db, arbitrary_trait, &[]));
+    let target = strukt.syntax().text_range();
+    if all_fields_arbitrary && has_derive_macro {
+        return acc.add(
+            AssistId("add_arbitrary_impl"),
+            "Add `#[derive(Arbitrary)]`",
+            target,
+            |builder| {
+                let nominal = ast::NominalDef::StructDef(strukt.clone());
+                add_derive(builder, &nominal, "arbitrary::Arbitrary")
+            },
+        );
+    }
+
+    // Adding the `Arbitrary` bounds to the type parameters is left to the derive.
+    if strukt.type_param_list().is_some() {
+        return None;
+    }
+    acc.add(AssistId("add_arbitrary_impl"), "Implement `Arbitrary` manually", target, |builder| {
+        let mut tab_stop = 0;
+        let mut arbitrary_value = || {
+            if ctx.config.snippet_cap.is_some() {
+                tab_stop += 1;
+                format!("${{{}:u.arbitrary()?}}", tab_stop)
+            } else {
+                "u.arbitrary()?".to_string()
+            }
+        };
+        let value = match strukt.kind() {
+            StructKind::Record(it) => {
+                let fields = it
+                    .fields()
+                    .filter_map(|it| it.name())
+                    .map(|it| format!("{}: {}", it, arbitrary_value()))
+                    .collect::<Vec<_>>();
+                format!("{} {{ {} }}", name, fields.iter().sep_by(", "))
+            }
+            StructKind::Tuple(it) => {
+                let fields = it.fields().map(|_| arbitrary_value()).collect::<Vec<_>>();
+                format!("{}({})", name, fields.iter().sep_by(", "))
+            }
+            StructKind::Unit => name.to_string(),
+        };
+        let param = if tab_stop == 0 { "_u" } else { "u" };
+
+        let mut buf = String::new();
+        format_to!(buf, "\n\nimpl<'a> arbitrary::Arbitrary<'a> for {} {{\n", name);
+        format_to!(
+            buf,
+            "    fn arbitrary({}: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {{\n",
+            param
+        );
+        format_to!(buf, "        Ok({})\n    }}\n}}", value);
+        let offset = strukt.syntax().text_range().end();
+        match ctx.config.snippet_cap {
+            Some(cap) => builder.insert_snippet(cap, offset, buf),
+            None => builder.insert(offset, buf),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const ARBITRARY: &str = r"
+//- /lib.rs crate:arbitrary
+pub trait Arbitrary<'a> {}
+impl<'a> Arbitrary<'a> for u32 {}
+";
+
+    // Stands in for the derive macro re-exported with the `derive` feature.
+    const ARBITRARY_WITH_DERIVE: &str = r"
+//- /lib.rs crate:arbitrary
+pub trait Arbitrary<'a> {}
+impl<'a> Arbitrary<'a> for u32 {}
+#[macro_export]
+macro_rules! Arbitrary { () => {} }
+";
+
+    #[test]
+    fn derive_arbitrary() {
+        check_assist(
+            add_arbitrary_impl,
+            &format!(
+                "//- /main.rs crate:main deps:arbitrary\n{}{}",
+                "#[derive(Debug)]\nstruct Foo<T> { x: u32<|> }", ARBITRARY_WITH_DERIVE
+            ),
+            "#[derive(Debug, arbitrary::Arbitrary)]\nstruct Foo<T> { x: u32 }\n",
+        );
+    }
+
+    #[test]
+    fn manual_impl_without_derive_feature() {
+        check_assist(
+            add_arbitrary_impl,
+            &format!(
+                "//- /main.rs crate:main deps:arbitrary\n{}{}",
+                "struct Foo(u32, <|>u32);", ARBITRARY
+            ),
+            r"struct Foo(u32, u32);
+
+impl<'a> arbitrary::Arbitrary<'a> for Foo {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Foo(${1:u.arbitrary()?}, ${2:u.arbitrary()?}))
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn manual_impl_for_non_arbitrary_fields() {
+        check_assist(
+            add_arbitrary_impl,
+            &format!(
+                "//- /main.rs crate:main deps:arbitrary\n{}{}",
+                "struct Handle;\nstruct Foo { x: u32, handle: Handle<|> }", ARBITRARY_WITH_DERIVE
+            ),
+            r"struct Handle;
+struct Foo { x: u32, handle: Handle }
+
+impl<'a> arbitrary::Arbitrary<'a> for Foo {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Foo { x: ${1:u.arbitrary()?}, handle: ${2:u.arbitrary()?} })
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_arbitrary_dependency() {
+        check_assist_not_applicable(add_arbitrary_impl, "struct Foo { x: u32<|> }");
+    }
+
+    #[test]
+    fn not_applicable_if_implemented() {
+        check_assist_not_applicable(
+            add_arbitrary_impl,
+            &format!(
+                "//- /main.rs crate:main deps:arbitrary\n{}{}",
+                "struct Foo<|>;\nimpl<'a> arbitrary::Arbitrary<'a> for Foo {}", ARBITRARY
+            ),
+        );
+        check_assist_not_applicable(
+            add_arbitrary_impl,
+            &format!(
+                "//- /main.rs crate:main deps:arbitrary\n{}{}",
+                "#[derive(Arbitrary)]\nstruct Foo<|>;", ARBITRARY
+            ),
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use hir::{Adt, HasSource};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, StructKind, TypeAscriptionOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{utils::resolve_target_trait, AssistContext, AssistId, Assists};
+
+// Assist: add_arbitrary_take_rest
+//
+// Overrides `Arbitrary::arbitrary_take_rest` for a struct with a
+// variable-length field, which then consumes all remaining fuzzer input.
+//
+// ```
+// # //- /main.rs crate:main deps:arbitrary
+// struct Packet { kind: u8, payload: Vec<u8> }
+// impl<'a> arbitrary::Arbitrary<'a> for Packet {<|>
+//     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+//         Ok(Packet { kind: u.arbitrary()?, payload: u.arbitrary()? })
+//     }
+// }
+// # //- /lib.rs crate:arbitrary
+// # pub trait Arbitrary<'a> {}
+// ```
+// ->
+// ```
+// struct Packet { kind: u8, payload: Vec<u8> }
+// impl<'a> arbitrary::Arbitrary<'a> for Packet {
+//     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+//         Ok(Packet { kind: u.arbitrary()?, payload: u.arbitrary()? })
+//     }
+//
+//     fn arbitrary_take_rest(mut u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+//         Ok(Packet { kind: u.arbitrary()?, payload: arbitrary::Arbitrary::arbitrary_take_rest(u)? })
+//     }
+// }
+// ```
+pub(crate) fn add_arbitrary_take_rest(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::ImplDef>()?;
+    let item_list = impl_def.item_list()?;
+    let trait_ = resolve_target_trait(&ctx.sema, &impl_def)?;
+    if trait_.name(ctx.db).to_string() != "Arbitrary" {
+        return None;
+    }
+    let fns: Vec<ast::FnDef> = item_list
+        .assoc_items()
+        .filter_map(|it| match it {
+            ast::AssocItem::FnDef(it) => Some(it),
+            _ => None,
+        })
+        .collect();
+    let has_fn = |name: &str| fns.iter().any(|it| it.name().map_or(false, |it| it.text() == name));
+    if has_fn("arbitrary_take_rest") {
+        return None;
+    }
+    // The signature of `arbitrary` has the paths to the types of `arbitrary`.
+    let arbitrary = fns.iter().find(|it| it.name().map_or(false, |it| it.text() == "arbitrary"))?;
+    let unstructured = match arbitrary.param_list()?.params().next()?.ascribed_type()? {
+        ast::TypeRef::ReferenceType(it) => it.type_ref()?,
+        _ => return None,
+    };
+    let ret_type = arbitrary.ret_type()?.type_ref()?;
+    let trait_path = trait_path(&impl_def)?;
+
+    let strukt = match ctx.sema.to_def(&impl_def)?.target_ty(ctx.db).as_adt()? {
+        Adt::Struct(it) => it.source(ctx.db).value,
+        _ => return None,
+    };
+    let name = strukt.name()?;
+    let take_rest = format!("{}::arbitrary_take_rest(u)?", trait_path);
+    let (value, field_count) = match strukt.kind() {
+        StructKind::Record(it) => {
+            let fields: Vec<(ast::Name, ast::TypeRef)> = it
+                .fields()
+                .map(|it| Some((it.name()?, it.ascribed_type()?)))
+                .collect::<Option<_>>()?;
+            // The field taking the rest must be initialized last.
+            let rest = fields.iter().rposition(|(_, ty)| is_variable_length(ty))?;
+            let mut buf = String::new();
+            for (idx, (field, _)) in fields.iter().enumerate() {
+                if idx != rest {
+                    format_to!(buf, "{}: u.arbitrary()?, ", field);
+                }
+            }
+            format_to!(buf, "{}: {}", fields[rest].0, take_rest);
+            (format!("{} {{ {} }}", name, buf), fields.len())
+        }
+        StructKind::Tuple(it) => {
+            let fields: Vec<ast::TypeRef> =
+                it.fields().map(|it| it.type_ref()).collect::<Option<_>>()?;
+            if !is_variable_length(fields.last()?) {
+                return None;
+            }
+            let values = fields[..fields.len() - 1]
+                .iter()
+                .map(|_| "u.arbitrary()?".to_string())
+                .chain(Some(take_rest))
+                .collect::<Vec<_>>();
+            (format!("{}({})", name, values.iter().sep_by(", ")), fields.len())
+        }
+        StructKind::Unit => return None,
+    };
+
+    let r_curly = item_list.r_curly_token()?;
+    let target = impl_def.syntax().text_range();
+    acc.add(AssistId("add_arbitrary_take_rest"), "Add `arbitrary_take_rest`", target, |builder| {
+        let indent = IndentLevel::from_node(impl_def.syntax());
+        let fn_indent = indent + 1;
+        let param = if field_count > 1 { "mut u" } else { "u" };
+        let mut buf = String::new();
+        if item_list.assoc_items().next().is_some() {
+            buf.push('\n');
+        }
+        format_to!(
+            buf,
+            "\n{}fn arbitrary_take_rest({}: {}) -> {} {{",
+            fn_indent,
+            param,
+            unstructured.syntax(),
+            ret_type.syntax()
+        );
+        format_to!(buf, "\n{}Ok({})", fn_indent + 1, value);
+        format_to!(buf, "\n{}}}\n{}", fn_indent, indent);
+        let start = r_curly.text_range().start();
+        let whitespace_before = r_curly
+            .prev_sibling_or_token()
+            .filter(|it| it.kind() == WHITESPACE)
+            .map_or(start, |it| it.text_range().start());
+        builder.replace(TextRange::new(whitespace_before, start), buf);
+    })
+}
+
+/// Returns the path of the implemented trait without generic arguments.
+fn trait_path(impl_def: &ast::ImplDef) -> Option<String> {
+    let path = match impl_def.target_trait()? {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let mut text = path.syntax().to_string();
+    if let Some(args) = path.segment()?.type_arg_list() {
+        let range = args.syntax().text_range() - path.syntax().text_range().start();
+        text.replace_range(std::ops::Range::<usize>::from(range), "");
+    }
+    Some(text)
+}
+
+fn is_variable_length(ty: &ast::TypeRef) -> bool {
+    let segment = match ty {
+        ast::TypeRef::PathType(it) => match it.path().and_then(|it| it.segment()) {
+            Some(it) => it,
+            None => return false,
+        },
+        _ => return false,
+    };
+    let name = match segment.name_ref() {
+        Some(it) => it.text().to_string(),
+        None => return false,
+    };
+    match name.as_str() {
+        "Vec" | "VecDeque" | "BinaryHeap" | "LinkedList" | "String" | "HashMap" | "HashSet"
+        | "BTreeMap" | "BTreeSet" => true,
+        // `Box<[T]>` and `Box<str>`
+        "Box" => segment
+            .type_arg_list()
+            .and_then(|it| it.type_args().next())
+            .and_then(|it| it.type_ref())
+            .map_or(false, |it| match it {
+                ast::TypeRef::SliceType(_) => true,
+                ast::TypeRef::PathType(it) => it.syntax().text() == "str",
+                _ => false,
+            }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const ARBITRARY: &str = r"
+//- /lib.rs crate:arbitrary
+pub trait Arbitrary<'a> {}
+";
+
+    fn with_arbitrary(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:arbitrary\n{}{}", ra_fixture.trim_end(), ARBITRARY)
+    }
+
+    #[test]
+    fn take_rest_with_last_variable_length_field() {
+        check_assist(
+            add_arbitrary_take_rest,
+            &with_arbitrary(
+                r"
+use arbitrary::{Arbitrary, Result, Unstructured};
+struct Message { tags: Vec<u8>, body: String, id: u32 }
+mod imp {
+    use super::*;
+    impl<'a> Arbitrary<'a> for Message {<|>
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Message { tags: u.arbitrary()?, body: u.arbitrary()?, id: u.arbitrary()? })
+        }
+    }
+}
+",
+            ),
+            r"
+use arbitrary::{Arbitrary, Result, Unstructured};
+struct Message { tags: Vec<u8>, body: String, id: u32 }
+mod imp {
+    use super::*;
+    impl<'a> Arbitrary<'a> for Message {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Message { tags: u.arbitrary()?, body: u.arbitrary()?, id: u.arbitrary()? })
+        }
+
+        fn arbitrary_take_rest(mut u: Unstructured<'a>) -> Result<Self> {
+            Ok(Message { tags: u.arbitrary()?, id: u.arbitrary()?, body: Arbitrary::arbitrary_take_rest(u)? })
+        }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn take_rest_in_tuple_struct() {
+        check_assist(
+            add_arbitrary_take_rest,
+            &with_arbitrary(
+                r"
+struct Bytes(Box<[u8]>);
+impl<'a> arbitrary::Arbitrary<'a> for Bytes {<|>
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Bytes(u.arbitrary()?))
+    }
+}
+",
+            ),
+            r"
+struct Bytes(Box<[u8]>);
+impl<'a> arbitrary::Arbitrary<'a> for Bytes {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Bytes(u.arbitrary()?))
+    }
+
+    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Bytes(arbitrary::Arbitrary::arbitrary_take_rest(u)?))
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // No variable-length field.
+        check_assist_not_applicable(
+            add_arbitrary_take_rest,
+            &with_arbitrary(
+                r"
+struct Point { x: u32, y: u32 }
+impl<'a> arbitrary::Arbitrary<'a> for Point {<|>
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Point { x: u.arbitrary()?, y: u.arbitrary()? })
+    }
+}
+",
+            ),
+        );
+        // The variable-length field of a tuple struct isn't the last one.
+        check_assist_not_applicable(
+            add_arbitrary_take_rest,
+            &with_arbitrary(
+                r"
+struct Named(String, u32);
+impl<'a> arbitrary::Arbitrary<'a> for Named {<|>
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Named(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+",
+            ),
+        );
+        // Already overridden.
+        check_assist_not_applicable(
+            add_arbitrary_take_rest,
+            &with_arbitrary(
+                r"
+struct Bytes(Vec<u8>);
+impl<'a> arbitrary::Arbitrary<'a> for Bytes {<|>
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Bytes(u.arbitrary()?))
+    }
+    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Bytes(u.take_rest().to_vec()))
+    }
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn add_arbitrary_take_rest_target() {
+        check_assist_target(
+            add_arbitrary_take_rest,
+            &with_arbitrary(
+                r"
+struct Bytes(Vec<u8>);
+impl<'a> arbitrary::Arbitrary<'a> for Bytes {<|>
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> { loop {} }
+}",
+            ),
+            r"impl<'a> arbitrary::Arbitrary<'a> for Bytes {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> { loop {} }
+}",
+        );
+    }
+}
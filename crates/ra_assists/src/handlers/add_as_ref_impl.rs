@@ -0,0 +1,266 @@
+use ra_syntax::ast::{self, AstNode, NameOwner};
+use stdx::format_to;
+
+use crate::{
+    utils::{impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_as_ref_impl
+//
+// Adds `AsRef` and `AsMut` impls exposing the wrapped value of a newtype.
+//
+// ```
+// struct Meters(<|>f64);
+// ```
+// ->
+// ```
+// struct Meters(f64);
+//
+// impl AsRef<f64> for Meters {
+//     fn as_ref(&self) -> &f64 {
+//         &self.0
+//     }
+// }
+//
+// impl AsMut<f64> for Meters {
+//     fn as_mut(&mut self) -> &mut f64 {
+//         &mut self.0
+//     }
+// }
+// ```
+pub(crate) fn add_as_ref_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let newtype = Newtype::at_offset(ctx)?;
+    let inner_ty = newtype.field.type_ref()?;
+
+    let field_ty = ctx.sema.to_def(&newtype.field)?.signature_ty(ctx.db);
+    let famous_defs = FamousDefs(&ctx.sema, newtype.def.module(ctx.db).krate());
+    let has_as_ref = newtype.implements(ctx, famous_defs.core_convert_AsRef(), field_ty.clone());
+    let has_as_mut = newtype.implements(ctx, famous_defs.core_convert_AsMut(), field_ty);
+    if has_as_ref && has_as_mut {
+        return None;
+    }
+
+    let label = match (has_as_ref, has_as_mut) {
+        (false, false) => format!("Add `AsRef<{0}>` and `AsMut<{0}>` impls", inner_ty),
+        (false, true) => format!("Add `AsRef<{}>` impl", inner_ty),
+        _ => format!("Add `AsMut<{}>` impl", inner_ty),
+    };
+    let target = newtype.strukt.syntax().text_range();
+    acc.add(AssistId("add_as_ref_impl"), label, target, |builder| {
+        let mut buf = String::new();
+        if !has_as_ref {
+            format_to!(
+                buf,
+                "\n\nimpl{} AsRef<{2}> for {1} {{\n    fn as_ref(&self) -> &{2} {{\n        &self.0\n    }}\n}}",
+                newtype.type_params,
+                newtype.self_ty,
+                inner_ty
+            );
+        }
+        if !has_as_mut {
+            format_to!(
+                buf,
+                "\n\nimpl{} AsMut<{2}> for {1} {{\n    fn as_mut(&mut self) -> &mut {2} {{\n        &mut self.0\n    }}\n}}",
+                newtype.type_params,
+                newtype.self_ty,
+                inner_ty
+            );
+        }
+        builder.insert(target.end(), buf);
+    })
+}
+
+// Assist: add_reflexive_as_ref_impl
+//
+// Adds an `AsRef` impl of a newtype for itself, so that it can be passed to
+// APIs generic over `AsRef`.
+//
+// ```
+// struct Meters(<|>f64);
+// ```
+// ->
+// ```
+// struct Meters(f64);
+//
+// impl AsRef<Meters> for Meters {
+//     fn as_ref(&self) -> &Meters {
+//         self
+//     }
+// }
+// ```
+pub(crate) fn add_reflexive_as_ref_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let newtype = Newtype::at_offset(ctx)?;
+    let famous_defs = FamousDefs(&ctx.sema, newtype.def.module(ctx.db).krate());
+    let self_ty = newtype.def.ty(ctx.db);
+    if newtype.implements(ctx, famous_defs.core_convert_AsRef(), self_ty) {
+        return None;
+    }
+
+    let target = newtype.strukt.syntax().text_range();
+    acc.add(
+        AssistId("add_reflexive_as_ref_impl"),
+        format!("Add `AsRef<{}>` impl", newtype.self_ty),
+        target,
+        |builder| {
+            let buf = format!(
+                "\n\nimpl{} AsRef<{1}> for {1} {{\n    fn as_ref(&self) -> &{1} {{\n        self\n    }}\n}}",
+                newtype.type_params, newtype.self_ty
+            );
+            builder.insert(target.end(), buf);
+        },
+    )
+}
+
+struct Newtype {
+    strukt: ast::StructDef,
+    def: hir::Struct,
+    field: ast::TupleFieldDef,
+    type_params: String,
+    self_ty: String,
+}
+
+impl Newtype {
+    fn at_offset(ctx: &AssistContext) -> Option<Newtype> {
+        let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+        let field_list = match strukt.kind() {
+            ast::StructKind::Tuple(it) => it,
+            _ => return None,
+        };
+        let mut fields = field_list.fields();
+        let field = fields.next()?;
+        if fields.next().is_some() {
+            return None;
+        }
+        let name = strukt.name()?;
+        let def = ctx.sema.to_def(&strukt)?;
+        let nominal = ast::NominalDef::from(strukt.clone());
+        let type_params = impl_type_params(&nominal);
+        let self_ty = nominal_self_ty(&nominal, &name);
+        Some(Newtype { strukt, def, field, type_params, self_ty })
+    }
+
+    fn implements(
+        &self,
+        ctx: &AssistContext,
+        trait_: Option<hir::Trait>,
+        param: hir::Type,
+    ) -> bool {
+        trait_.map_or(false, |trait_| self.def.ty(ctx.db).impls_trait(ctx.db, trait_, &[param]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn add_as_ref_and_as_mut() {
+        check_assist(
+            add_as_ref_impl,
+            "struct Wrapper<T>(<|>Vec<T>);",
+            r"struct Wrapper<T>(Vec<T>);
+
+impl<T> AsRef<Vec<T>> for Wrapper<T> {
+    fn as_ref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> AsMut<Vec<T>> for Wrapper<T> {
+    fn as_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_only_missing_as_mut() {
+        check_assist(
+            add_as_ref_impl,
+            &with_core(
+                r"
+struct Meters(<|>f64);
+impl AsRef<f64> for Meters {
+    fn as_ref(&self) -> &f64 { &self.0 }
+}
+",
+            ),
+            r"
+struct Meters(f64);
+
+impl AsMut<f64> for Meters {
+    fn as_mut(&mut self) -> &mut f64 {
+        &mut self.0
+    }
+}
+impl AsRef<f64> for Meters {
+    fn as_ref(&self) -> &f64 { &self.0 }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_both_implemented() {
+        check_assist_not_applicable(
+            add_as_ref_impl,
+            &with_core(
+                r"
+struct Meters(<|>f64);
+impl AsRef<f64> for Meters {
+    fn as_ref(&self) -> &f64 { &self.0 }
+}
+impl AsMut<f64> for Meters {
+    fn as_mut(&mut self) -> &mut f64 { &mut self.0 }
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_non_newtypes() {
+        check_assist_not_applicable(add_as_ref_impl, "struct Point(<|>i32, i32);");
+        check_assist_not_applicable(add_as_ref_impl, "struct Point { <|>x: i32 }");
+        check_assist_not_applicable(add_reflexive_as_ref_impl, "struct Point(<|>i32, i32);");
+    }
+
+    #[test]
+    fn add_reflexive_as_ref() {
+        check_assist(
+            add_reflexive_as_ref_impl,
+            "struct Wrapper<'a>(<|>&'a str);",
+            r"struct Wrapper<'a>(&'a str);
+
+impl<'a> AsRef<Wrapper<'a>> for Wrapper<'a> {
+    fn as_ref(&self) -> &Wrapper<'a> {
+        self
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn reflexive_as_ref_not_applicable_when_implemented() {
+        check_assist_not_applicable(
+            add_reflexive_as_ref_impl,
+            &with_core(
+                r"
+struct Meters(<|>f64);
+impl AsRef<Meters> for Meters {
+    fn as_ref(&self) -> &Meters { self }
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn add_as_ref_impl_target() {
+        check_assist_target(add_as_ref_impl, "struct Meters(<|>f64);", "struct Meters(f64);");
+    }
+}
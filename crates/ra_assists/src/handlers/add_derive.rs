@@ -1,10 +1,9 @@
 use ra_syntax::{
     ast::{self, AstNode, AttrsOwner},
-    SyntaxKind::{COMMENT, WHITESPACE},
     TextSize,
 };
 
-use crate::{AssistContext, AssistId, Assists};
+use crate::{utils::derive_insertion_offset, AssistContext, AssistId, Assists};
 
 // Assist: add_derive
 //
@@ -52,15 +51,6 @@ pub(crate) fn add_derive(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     })
 }
 
-// Insert `derive` after doc comments.
-fn derive_insertion_offset(nominal: &ast::NominalDef) -> Option<TextSize> {
-    let non_ws_child = nominal
-        .syntax()
-        .children_with_tokens()
-        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?;
-    Some(non_ws_child.text_range().start())
-}
-
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_target};
@@ -0,0 +1,189 @@
+use hir::{Adt, ImplDef, Trait};
+use ra_syntax::ast::{
+    self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, TypeBoundsOwner, TypeParamsOwner,
+};
+use stdx::SepBy;
+
+use crate::{
+    utils::{add_derive, has_bound, has_derive, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_derive_copy
+//
+// Adds `Copy` to the derives of a struct, whose fields are all `Copy`. `Clone`
+// is derived as well, unless the struct already implements it. Type parameters
+// used as field types get a `Copy` bound.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// #[derive(Debug)]
+// struct Point {
+//     x: u32,
+//     y: u32,<|>
+// }
+// # //- /libcore.rs crate:core
+// # pub mod marker { #[lang = "copy"] pub trait Copy {} impl Copy for u32 {} }
+// ```
+// ->
+// ```
+// #[derive(Debug, Clone, Copy)]
+// struct Point {
+//     x: u32,
+//     y: u32,
+// }
+// ```
+pub(crate) fn add_derive_copy(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let def = ctx.sema.to_def(&strukt)?;
+    let krate = def.module(ctx.db).krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+    let copy_trait = famous_defs.core_marker_Copy()?;
+    if has_derive(&strukt, "Copy") || has_impl(ctx, def, copy_trait) {
+        return None;
+    }
+
+    let fields: Vec<(ast::TypeRef, Option<hir::Field>)> = match strukt.kind() {
+        StructKind::Record(it) => {
+            it.fields().filter_map(|f| Some((f.ascribed_type()?, ctx.sema.to_def(&f)))).collect()
+        }
+        StructKind::Tuple(it) => {
+            it.fields().filter_map(|f| Some((f.type_ref()?, ctx.sema.to_def(&f)))).collect()
+        }
+        StructKind::Unit => Vec::new(),
+    };
+    // A field, whose type is a type parameter, becomes `Copy` by bounding the
+    // parameter. Any other field which isn't `Copy` rules the derive out.
+    let mut unbounded_params: Vec<ast::TypeParam> = Vec::new();
+    for (ty, field) in fields.iter() {
+        if has_bound(&strukt, ty, "Copy")
+            || field
+                .map_or(false, |it| it.signature_ty(ctx.db).impls_trait(ctx.db, copy_trait, &[]))
+        {
+            continue;
+        }
+        let param = type_param(&strukt, ty)?;
+        if !unbounded_params.contains(&param) {
+            unbounded_params.push(param);
+        }
+    }
+
+    let has_clone = has_derive(&strukt, "Clone")
+        || famous_defs.core_clone_Clone().map_or(false, |it| has_impl(ctx, def, it));
+    let new_derives = if has_clone { "Copy" } else { "Clone, Copy" };
+    let label = if unbounded_params.is_empty() {
+        format!("Add `#[derive({})]`", new_derives)
+    } else {
+        let bounds = unbounded_params
+            .iter()
+            .filter_map(|it| it.name())
+            .map(|it| format!("{}: Copy", it))
+            .sep_by(", ");
+        format!("Add `{}` and `#[derive({})]`", bounds, new_derives)
+    };
+    let target = strukt.syntax().text_range();
+    acc.add(AssistId("add_derive_copy"), label, target, |builder| {
+        for param in unbounded_params.iter() {
+            match (param.type_bound_list(), param.name()) {
+                (Some(bounds), _) => builder.insert(bounds.syntax().text_range().end(), " + Copy"),
+                (None, Some(name)) => builder.insert(name.syntax().text_range().end(), ": Copy"),
+                (None, None) => (),
+            }
+        }
+        let nominal = ast::NominalDef::StructDef(strukt.clone());
+        add_derive(builder, &nominal, new_derives);
+    })
+}
+
+fn type_param(strukt: &ast::StructDef, ty: &ast::TypeRef) -> Option<ast::TypeParam> {
+    let path = match ty {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let name = path.segment()?.name_ref()?;
+    strukt
+        .type_param_list()?
+        .type_params()
+        .find(|it| it.name().map_or(false, |it| it.text() == name.text()))
+}
+
+fn has_impl(ctx: &AssistContext, def: hir::Struct, trait_: Trait) -> bool {
+    let krate = def.module(ctx.db).krate();
+    ImplDef::for_trait(ctx.db, krate, trait_)
+        .into_iter()
+        .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(def)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn add_copy_with_clone_impl_and_generic_field() {
+        check_assist(
+            add_derive_copy,
+            &with_core(
+                r#"
+/// A pair of ids.
+struct Pair<T: Copy>(usize, T);<|>
+impl<T: Copy> core::clone::Clone for Pair<T> {}
+"#,
+            ),
+            r#"
+/// A pair of ids.
+#[derive(Copy)]
+struct Pair<T: Copy>(usize, T);
+impl<T: Copy> core::clone::Clone for Pair<T> {}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_copy_to_clone_derive() {
+        check_assist(
+            add_derive_copy,
+            &with_core("#[derive(Clone)]\nstruct Flag<|> { set: bool }"),
+            "#[derive(Clone, Copy)]\nstruct Flag { set: bool }\n",
+        );
+    }
+
+    #[test]
+    fn add_copy_bounds_to_type_params() {
+        check_assist(
+            add_derive_copy,
+            &with_core("struct Pair<<|>T, U: Default> { a: T, b: U, c: T }"),
+            "#[derive(Clone, Copy)]\nstruct Pair<T: Copy, U: Default + Copy> { a: T, b: U, c: T }\n",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            add_derive_copy,
+            &with_core("#[derive(Clone, Copy)]\nstruct S<|> { a: u32 }"),
+        );
+        check_assist_not_applicable(
+            add_derive_copy,
+            &with_core("struct S<|> { a: u32 }\nimpl core::marker::Copy for S {}"),
+        );
+        check_assist_not_applicable(add_derive_copy, "struct S<|> { a: u32 }");
+        check_assist_not_applicable(
+            add_derive_copy,
+            &with_core("struct Name;\nstruct User<|> { id: u32, name: Name }"),
+        );
+    }
+
+    #[test]
+    fn add_derive_copy_target() {
+        check_assist_target(
+            add_derive_copy,
+            &with_core("/// Doc.\nstruct S<|>(u32);"),
+            "/// Doc.\nstruct S(u32);",
+        );
+    }
+}
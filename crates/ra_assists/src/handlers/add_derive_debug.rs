@@ -0,0 +1,218 @@
+use hir::{Adt, ImplDef};
+use ra_syntax::ast::{self, AstNode, NameOwner, StructKind};
+use stdx::format_to;
+
+use crate::{
+    utils::{add_derive, has_derive, impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_derive_debug
+//
+// Implements `Debug` for a struct. If all fields implement `Debug`, the
+// `Debug` derive is added, otherwise a manual impl is generated, which prints
+// `<opaque>` for the fields without `Debug`.
+//
+// ```
+// struct Point {
+//     x: u32,
+//     y: u32,<|>
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug)]
+// struct Point {
+//     x: u32,
+//     y: u32,
+// }
+// ```
+pub(crate) fn add_derive_debug(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    if has_derive(&strukt, "Debug") {
+        return None;
+    }
+
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let krate = struct_def.module(ctx.db).krate();
+    let debug_trait = FamousDefs(&ctx.sema, krate).core_fmt_Debug();
+    let has_debug_impl = debug_trait.map_or(false, |trait_| {
+        ImplDef::for_trait(ctx.db, krate, trait_)
+            .into_iter()
+            .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(struct_def)))
+    });
+    if has_debug_impl {
+        return None;
+    }
+
+    // Without `Debug` in scope, we can't tell which fields implement it, so we
+    // assume that all of them do.
+    let implements_debug = |field: Option<hir::Field>| match (debug_trait, field) {
+        (Some(trait_), Some(field)) => field.signature_ty(ctx.db).impls_trait(ctx.db, trait_, &[]),
+        (Some(_), None) => false,
+        (None, _) => true,
+    };
+    let fields: Vec<(String, bool)> = match strukt.kind() {
+        StructKind::Record(it) => it
+            .fields()
+            .filter_map(|f| Some((f.name()?.to_string(), implements_debug(ctx.sema.to_def(&f)))))
+            .collect(),
+        StructKind::Tuple(it) => it
+            .fields()
+            .enumerate()
+            .map(|(idx, f)| (idx.to_string(), implements_debug(ctx.sema.to_def(&f))))
+            .collect(),
+        StructKind::Unit => Vec::new(),
+    };
+
+    let nominal = ast::NominalDef::StructDef(strukt.clone());
+    let target = strukt.syntax().text_range();
+    if fields.iter().all(|(_, is_debug)| *is_debug) {
+        return acc.add(
+            AssistId("add_derive_debug"),
+            "Add `#[derive(Debug)]`",
+            target,
+            |builder| add_derive(builder, &nominal, "Debug"),
+        );
+    }
+
+    acc.add(AssistId("add_derive_debug"), "Implement `Debug` manually", target, |builder| {
+        let mut body = match strukt.kind() {
+            StructKind::Tuple(_) => format!("f.debug_tuple(\"{}\")", name),
+            _ => format!("f.debug_struct(\"{}\")", name),
+        };
+        for (field, is_debug) in &fields {
+            let value =
+                if *is_debug { format!("&self.{}", field) } else { "&\"<opaque>\"".to_string() };
+            match strukt.kind() {
+                StructKind::Tuple(_) => format_to!(body, ".field({})", value),
+                _ => format_to!(body, ".field(\"{}\", {})", field, value),
+            }
+        }
+        body.push_str(".finish()");
+
+        let mut buf = String::new();
+        format_to!(
+            buf,
+            "\n\nimpl{} std::fmt::Debug for {} {{\n",
+            impl_type_params(&nominal),
+            nominal_self_ty(&nominal, &name)
+        );
+        buf.push_str("    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n");
+        format_to!(buf, "        {}\n    }}\n}}", body);
+        builder.insert(strukt.syntax().text_range().end(), buf);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn derive_debug_when_all_fields_are_debug() {
+        check_assist(
+            add_derive_debug,
+            &with_core(
+                r"
+struct Bar;
+impl core::fmt::Debug for Bar {}
+/// Docs.
+struct Foo {<|> bar: Bar }",
+            ),
+            r"
+struct Bar;
+impl core::fmt::Debug for Bar {}
+/// Docs.
+#[derive(Debug)]
+struct Foo { bar: Bar }
+",
+        );
+    }
+
+    #[test]
+    fn extend_existing_derive() {
+        check_assist(
+            add_derive_debug,
+            "#[derive(Clone)]\nstruct Foo<|>;",
+            "#[derive(Clone, Debug)]\nstruct Foo;",
+        );
+        check_assist(
+            add_derive_debug,
+            "#[derive()]\nstruct Foo<|>;",
+            "#[derive(Debug)]\nstruct Foo;",
+        );
+    }
+
+    #[test]
+    fn manual_impl_for_record_struct() {
+        check_assist(
+            add_derive_debug,
+            &with_core(
+                r"
+struct Bar;
+impl core::fmt::Debug for Bar {}
+struct Handle;
+struct Foo<T> {<|> bar: Bar, handle: Handle, data: T }",
+            ),
+            r#"
+struct Bar;
+impl core::fmt::Debug for Bar {}
+struct Handle;
+struct Foo<T> { bar: Bar, handle: Handle, data: T }
+
+impl<T> std::fmt::Debug for Foo<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Foo").field("bar", &self.bar).field("handle", &"<opaque>").field("data", &"<opaque>").finish()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn manual_impl_for_tuple_struct() {
+        check_assist(
+            add_derive_debug,
+            &with_core(
+                r"
+struct Bar;
+impl core::fmt::Debug for Bar {}
+struct Handle;
+struct Foo(Bar, <|>Handle);",
+            ),
+            r#"
+struct Bar;
+impl core::fmt::Debug for Bar {}
+struct Handle;
+struct Foo(Bar, Handle);
+
+impl std::fmt::Debug for Foo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Foo").field(&self.0).field(&"<opaque>").finish()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_debug_is_implemented() {
+        check_assist_not_applicable(add_derive_debug, "#[derive(Clone, Debug)]\nstruct Foo<|>;");
+        check_assist_not_applicable(
+            add_derive_debug,
+            &with_core(
+                r"
+struct Foo<|>;
+impl core::fmt::Debug for Foo {}",
+            ),
+        );
+    }
+
+    #[test]
+    fn add_derive_debug_target() {
+        check_assist_target(add_derive_debug, "struct Foo<|> { x: u32 }", "struct Foo { x: u32 }");
+    }
+}
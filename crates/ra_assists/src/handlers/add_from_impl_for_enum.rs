@@ -25,18 +25,7 @@ pub(crate) fn add_from_impl_for_enum(acc: &mut Assists, ctx: &AssistContext) ->
     let variant = ctx.find_node_at_offset::<ast::EnumVariant>()?;
     let variant_name = variant.name()?;
     let enum_name = variant.parent_enum().name()?;
-    let field_list = match variant.kind() {
-        ast::StructKind::Tuple(field_list) => field_list,
-        _ => return None,
-    };
-    if field_list.fields().count() != 1 {
-        return None;
-    }
-    let field_type = field_list.fields().next()?.type_ref()?;
-    let path = match field_type {
-        ast::TypeRef::PathType(it) => it,
-        _ => return None,
-    };
+    let path = wrapped_path(&variant)?;
 
     if existing_from_impl(&ctx.sema, &variant).is_some() {
         mark::hit!(test_add_from_impl_already_exists);
@@ -50,20 +39,98 @@ pub(crate) fn add_from_impl_for_enum(acc: &mut Assists, ctx: &AssistContext) ->
         target,
         |edit| {
             let start_offset = variant.parent_enum().syntax().text_range().end();
-            let buf = format!(
-                r#"
+            edit.insert(start_offset, from_impl(&enum_name, &variant_name, &path));
+        },
+    )
+}
+
+// Assist: add_from_impls_for_enum
+//
+// Adds From impls for all enum variants with one tuple field.
+//
+// ```
+// enum <|>Error { Io(io::Error), Parse(ParseError), Other }
+// ```
+// ->
+// ```
+// enum Error { Io(io::Error), Parse(ParseError), Other }
+//
+// impl From<io::Error> for Error {
+//     fn from(v: io::Error) -> Self {
+//         Error::Io(v)
+//     }
+// }
+//
+// impl From<ParseError> for Error {
+//     fn from(v: ParseError) -> Self {
+//         Error::Parse(v)
+//     }
+// }
+// ```
+pub(crate) fn add_from_impls_for_enum(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let enum_name = enum_def.name()?;
+    let candidates: Vec<(ast::EnumVariant, ast::PathType)> = enum_def
+        .variant_list()?
+        .variants()
+        .filter_map(|variant| Some((variant.clone(), wrapped_path(&variant)?)))
+        .collect();
+    let variants: Vec<(ast::Name, ast::PathType)> = candidates
+        .iter()
+        // Variants wrapping the same type would get conflicting impls.
+        .filter(|(_, path)| {
+            candidates.iter().filter(|(_, it)| it.syntax().text() == path.syntax().text()).count()
+                == 1
+        })
+        .filter(|(variant, _)| existing_from_impl(&ctx.sema, variant).is_none())
+        .filter_map(|(variant, path)| Some((variant.name()?, path.clone())))
+        .collect();
+    if variants.len() < 2 {
+        return None;
+    }
+
+    let target = enum_def.syntax().text_range();
+    acc.add(
+        AssistId("add_from_impls_for_enum"),
+        format!("Add From impls for {} enum variants", variants.len()),
+        target,
+        |edit| {
+            let buf: String = variants
+                .iter()
+                .map(|(variant_name, path)| from_impl(&enum_name, variant_name, path))
+                .collect();
+            edit.insert(target.end(), buf);
+        },
+    )
+}
+
+/// Returns the type of the single tuple field of `variant`.
+fn wrapped_path(variant: &ast::EnumVariant) -> Option<ast::PathType> {
+    let field_list = match variant.kind() {
+        ast::StructKind::Tuple(field_list) => field_list,
+        _ => return None,
+    };
+    if field_list.fields().count() != 1 {
+        return None;
+    }
+    match field_list.fields().next()?.type_ref()? {
+        ast::TypeRef::PathType(it) => Some(it),
+        _ => None,
+    }
+}
+
+fn from_impl(enum_name: &ast::Name, variant_name: &ast::Name, path: &ast::PathType) -> String {
+    format!(
+        r#"
 
 impl From<{0}> for {1} {{
     fn from(v: {0}) -> Self {{
         {1}::{2}(v)
     }}
 }}"#,
-                path.syntax(),
-                enum_name,
-                variant_name
-            );
-            edit.insert(start_offset, buf);
-        },
+        path.syntax(),
+        enum_name,
+        variant_name
     )
 }
 
@@ -92,7 +159,9 @@ fn existing_from_impl(
 mod tests {
     use test_utils::mark;
 
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{
+        check_assist, check_assist_not_applicable, check_assist_with_core, with_core,
+    };
 
     use super::*;
 
@@ -127,9 +196,7 @@ impl From<foo::bar::baz::Boo> for A {
     }
 
     fn check_not_applicable(ra_fixture: &str) {
-        let fixture =
-            format!("//- main.rs crate:main deps:core\n{}\n{}", ra_fixture, FamousDefs::FIXTURE);
-        check_assist_not_applicable(add_from_impl_for_enum, &fixture)
+        check_assist_not_applicable(add_from_impl_for_enum, &with_core(ra_fixture))
     }
 
     #[test]
@@ -197,4 +264,62 @@ pub trait From<T> {
 }"#,
         );
     }
+
+    #[test]
+    fn test_add_from_impls_for_enum() {
+        check_assist_with_core(
+            add_from_impls_for_enum,
+            r#"
+mod io { pub struct Error; }
+struct Utf8Error;
+struct ParseError;
+struct ParseIntError;
+enum <|>Error {
+    Io(io::Error),
+    Utf8(Utf8Error),
+    Parse(ParseError),
+    Lex(ParseError),
+    Int(ParseIntError),
+    Fatal,
+}
+
+impl From<Utf8Error> for Error {
+    fn from(v: Utf8Error) -> Self {
+        Error::Utf8(v)
+    }
+}"#,
+            r#"
+mod io { pub struct Error; }
+struct Utf8Error;
+struct ParseError;
+struct ParseIntError;
+enum Error {
+    Io(io::Error),
+    Utf8(Utf8Error),
+    Parse(ParseError),
+    Lex(ParseError),
+    Int(ParseIntError),
+    Fatal,
+}
+
+impl From<io::Error> for Error {
+    fn from(v: io::Error) -> Self {
+        Error::Io(v)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(v: ParseIntError) -> Self {
+        Error::Int(v)
+    }
+}
+
+impl From<Utf8Error> for Error {
+    fn from(v: Utf8Error) -> Self {
+        Error::Utf8(v)
+    }
+}
+"#,
+        );
+    }
 }
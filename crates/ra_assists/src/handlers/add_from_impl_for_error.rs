@@ -0,0 +1,233 @@
+use hir::HirDisplay;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+};
+use stdx::format_to;
+
+use crate::{
+    utils::{module_item, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_from_impl_for_error
+//
+// Adds a `From` impl, which is needed to convert the error of a `?`
+// expression into the error type returned by the function.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// struct ParseError;
+// struct ConfigError;
+//
+// fn parse(s: &str) -> Result<u32, ParseError> { Ok(0) }
+//
+// fn load(s: &str) -> Result<u32, ConfigError> {
+//     parse(s)<|>?;
+//     Ok(0)
+// }
+// # //- /libcore.rs crate:core
+// # pub mod convert { pub trait From<T> { fn from(T) -> Self; } }
+// # pub mod result { pub enum Result<T, E> { Ok(T), Err(E) } }
+// # pub mod prelude { pub use crate::{convert::From, result::Result::{self, *}}; }
+// # #[prelude_import]
+// # pub use prelude::*;
+// ```
+// ->
+// ```
+// struct ParseError;
+// struct ConfigError;
+//
+// fn parse(s: &str) -> Result<u32, ParseError> { Ok(0) }
+//
+// fn load(s: &str) -> Result<u32, ConfigError> {
+//     parse(s)?;
+//     Ok(0)
+// }
+//
+// impl From<ParseError> for ConfigError {
+//     fn from(err: ParseError) -> Self {
+//         ${0:todo!()}
+//     }
+// }
+// ```
+pub(crate) fn add_from_impl_for_error(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let try_expr = ctx.find_node_at_offset::<ast::TryExpr>()?;
+    // `?` in a closure returns from the closure, not from the function.
+    let fn_def = try_expr
+        .syntax()
+        .ancestors()
+        .find(|it| it.kind() == FN_DEF || it.kind() == LAMBDA_EXPR)
+        .and_then(ast::FnDef::cast)?;
+
+    let module = ctx.sema.scope(try_expr.syntax()).module()?;
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let result = famous_defs.core_result_Result()?;
+    let error_of = |ty: hir::Type| match ty.as_adt()? {
+        hir::Adt::Enum(it) if it == result => ty.type_parameters().into_iter().nth(1),
+        _ => None,
+    };
+
+    let from_ty = error_of(ctx.sema.type_of_expr(&try_expr.expr()?)?)?;
+    let into_ty = error_of(ctx.sema.to_def(&fn_def)?.ret_type(ctx.db))?;
+    if from_ty.contains_unknown() || into_ty.contains_unknown() {
+        return None;
+    }
+    // The impl can only be added to a type of the current crate.
+    if into_ty.as_adt()?.module(ctx.db).krate() != module.krate() {
+        return None;
+    }
+    let from = from_ty.display_source_code(ctx.db, module.into()).ok()?;
+    let into = into_ty.display_source_code(ctx.db, module.into()).ok()?;
+    if from == into {
+        return None;
+    }
+    let from_trait = famous_defs.core_convert_From()?;
+    if into_ty.impls_trait(ctx.db, from_trait, &[from_ty]) {
+        return None;
+    }
+
+    let item = module_item(fn_def.syntax())?;
+    let target = try_expr.syntax().text_range();
+    acc.add(
+        AssistId("add_from_impl_for_error"),
+        format!("Add `impl From<{}> for {}`", from, into),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(&item);
+            let mut buf = String::new();
+            format_to!(buf, "\n\n{}impl From<{}> for {} {{", indent, from, into);
+            format_to!(buf, "\n{}fn from(err: {}) -> Self {{", indent + 1, from);
+            format_to!(buf, "\n{}$0", indent + 2);
+            format_to!(buf, "\n{}}}\n{}}}", indent + 1, indent);
+            let offset = item.text_range().end();
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, buf.replace("$0", "${0:todo!()}")),
+                None => builder.insert(offset, buf.replace("$0", "todo!()")),
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn add_from_impl_in_module() {
+        check_assist(
+            add_from_impl_for_error,
+            &with_core(
+                r#"
+mod config {
+    pub mod error { pub struct IoError; }
+    pub enum Error { Missing }
+
+    pub struct Loader;
+
+    impl Loader {
+        fn read(&self) -> Result<String, error::IoError> { Ok(String::new()) }
+
+        pub fn load(&self) -> Result<u32, Error> {
+            let text = self.read()?<|>;
+            Ok(text.len() as u32)
+        }
+    }
+}
+"#,
+            ),
+            r#"
+mod config {
+    pub mod error { pub struct IoError; }
+    pub enum Error { Missing }
+
+    pub struct Loader;
+
+    impl Loader {
+        fn read(&self) -> Result<String, error::IoError> { Ok(String::new()) }
+
+        pub fn load(&self) -> Result<u32, Error> {
+            let text = self.read()?;
+            Ok(text.len() as u32)
+        }
+    }
+
+    impl From<error::IoError> for Error {
+        fn from(err: error::IoError) -> Self {
+            ${0:todo!()}
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // The conversion exists.
+        check_assist_not_applicable(
+            add_from_impl_for_error,
+            &with_core(
+                r#"
+struct A;
+struct B;
+impl From<A> for B { fn from(_: A) -> B { B } }
+fn a() -> Result<(), A> { Ok(()) }
+fn b() -> Result<(), B> { a()<|>?; Ok(()) }
+"#,
+            ),
+        );
+        // The error types are the same.
+        check_assist_not_applicable(
+            add_from_impl_for_error,
+            &with_core(
+                r#"
+struct A;
+fn a() -> Result<(), A> { Ok(()) }
+fn b() -> Result<(), A> { a()<|>?; Ok(()) }
+"#,
+            ),
+        );
+        // The `?` is inside of a closure.
+        check_assist_not_applicable(
+            add_from_impl_for_error,
+            &with_core(
+                r#"
+struct A;
+struct B;
+fn a() -> Result<(), A> { Ok(()) }
+fn b() -> Result<(), B> { let f = || { a()<|>?; Ok(()) }; Ok(()) }
+"#,
+            ),
+        );
+        // The returned error type is foreign.
+        check_assist_not_applicable(
+            add_from_impl_for_error,
+            &with_core(
+                r#"
+struct A;
+fn a() -> Result<(), A> { Ok(()) }
+fn b() -> Result<(), core::fmt::Error> { a()<|>?; Ok(()) }
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn add_from_impl_for_error_target() {
+        check_assist_target(
+            add_from_impl_for_error,
+            &with_core(
+                r#"
+struct A;
+struct B;
+fn a() -> Result<(), A> { Ok(()) }
+fn b() -> Result<(), B> { a()<|>?; Ok(()) }
+"#,
+            ),
+            "a()?",
+        );
+    }
+}
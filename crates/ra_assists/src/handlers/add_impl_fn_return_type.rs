@@ -0,0 +1,262 @@
+use hir::{HasSource, HirDisplay, PathResolution, Trait};
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextRange,
+};
+use stdx::SepBy;
+
+use crate::{utils::FamousDefs, AssistContext, AssistId, Assists};
+
+// Assist: add_impl_fn_return_type
+//
+// Adds an `impl Fn` return type to a function that returns a closure. The
+// parameter and return types are inferred, and `FnMut` or `FnOnce` is used if
+// the closure mutates or moves out of the variables it captures.
+//
+// ```
+// fn make_adder<|>(x: i32) {
+//     move |y| x + y
+// }
+// ```
+// ->
+// ```
+// fn make_adder(x: i32) -> impl Fn(i32) -> i32 {
+//     move |y| x + y
+// }
+// ```
+pub(crate) fn add_impl_fn_return_type(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if fn_def.ret_type().is_some() {
+        return None;
+    }
+    let param_list = fn_def.param_list()?;
+    let body = fn_def.body()?;
+    let closure = match body.expr()? {
+        ast::Expr::LambdaExpr(it) => it,
+        _ => return None,
+    };
+    let header =
+        TextRange::new(fn_def.syntax().text_range().start(), body.syntax().text_range().start());
+    let closure_head = TextRange::new(
+        closure.syntax().text_range().start(),
+        closure.body()?.syntax().text_range().start(),
+    );
+    if !header.contains_range(ctx.frange.range) && !closure_head.contains_range(ctx.frange.range) {
+        return None;
+    }
+
+    let module = ctx.sema.scope(fn_def.syntax()).module()?;
+    let closure_ty = ctx.sema.type_of_expr(&ast::Expr::from(closure.clone()))?;
+    if !closure_ty.is_closure() || closure_ty.contains_unknown() {
+        return None;
+    }
+    // The only parameter of a closure type is its signature, as a function
+    // pointer type with the return type last.
+    let mut sig = closure_ty.type_parameters().first()?.type_parameters();
+    let ret = sig.pop()?.display_source_code(ctx.db, module.into()).ok()?;
+    let params = sig
+        .iter()
+        .map(|it| it.display_source_code(ctx.db, module.into()).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    let copy_trait = FamousDefs(&ctx.sema, module.krate()).core_marker_Copy();
+    let trait_name = closure_trait(ctx, &closure, copy_trait);
+    let mut ret_type = format!("impl {}({})", trait_name, params.into_iter().sep_by(", "));
+    if ret != "()" {
+        ret_type.push_str(&format!(" -> {}", ret));
+    }
+
+    let target = TextRange::new(
+        fn_def.syntax().text_range().start(),
+        param_list.syntax().text_range().end(),
+    );
+    acc.add(
+        AssistId("add_impl_fn_return_type"),
+        format!("Add `{}` return type", ret_type),
+        target,
+        |builder| {
+            builder.insert(param_list.syntax().text_range().end(), format!(" -> {}", ret_type));
+        },
+    )
+}
+
+/// Picks the most general `Fn` trait the closure implements, by looking at how
+/// it uses the local variables it captures.
+fn closure_trait(
+    ctx: &AssistContext,
+    closure: &ast::LambdaExpr,
+    copy_trait: Option<Trait>,
+) -> &'static str {
+    let closure_range = closure.syntax().text_range();
+    let mut is_mut = false;
+    for path_expr in closure.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match path_expr.path().and_then(|it| ctx.sema.resolve_path(&it)) {
+            Some(PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let local_range = match local.source(ctx.db).value {
+            either::Either::Left(it) => it.syntax().text_range(),
+            either::Either::Right(it) => it.syntax().text_range(),
+        };
+        if closure_range.contains_range(local_range) {
+            continue;
+        }
+
+        // Walk up to the whole place expression, e.g. `x.field.inner`.
+        let mut place = ast::Expr::from(path_expr);
+        while let Some(field) = place.syntax().parent().and_then(ast::FieldExpr::cast) {
+            place = field.into();
+        }
+        match place_usage(ctx, closure, &place) {
+            Usage::Mut => is_mut = true,
+            Usage::Move => {
+                let is_copy = match (ctx.sema.type_of_expr(&place), copy_trait) {
+                    (Some(ty), Some(copy_trait)) => ty.impls_trait(ctx.db, copy_trait, &[]),
+                    _ => true,
+                };
+                if !is_copy {
+                    return "FnOnce";
+                }
+            }
+            Usage::Ref => (),
+        }
+    }
+    if is_mut {
+        "FnMut"
+    } else {
+        "Fn"
+    }
+}
+
+enum Usage {
+    Ref,
+    Mut,
+    Move,
+}
+
+fn place_usage(ctx: &AssistContext, closure: &ast::LambdaExpr, place: &ast::Expr) -> Usage {
+    let tail = match closure.body() {
+        Some(ast::Expr::BlockExpr(it)) => it.expr(),
+        it => it,
+    };
+    if tail.as_ref() == Some(place) {
+        return Usage::Move;
+    }
+    let parent = match place.syntax().parent() {
+        Some(it) => it,
+        None => return Usage::Ref,
+    };
+    if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+        if bin_expr.op_kind().map_or(false, |it| it.is_assignment())
+            && bin_expr.lhs().as_ref() == Some(place)
+        {
+            return Usage::Mut;
+        }
+    } else if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        if ref_expr.mut_token().is_some() {
+            return Usage::Mut;
+        }
+    } else if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        if call.expr().as_ref() == Some(place) {
+            let self_kind = ctx
+                .sema
+                .resolve_method_call(&call)
+                .and_then(|it| it.source(ctx.db).value.param_list()?.self_param())
+                .map(|it| it.kind());
+            return match self_kind {
+                Some(ast::SelfParamKind::MutRef) => Usage::Mut,
+                Some(ast::SelfParamKind::Owned) => Usage::Move,
+                _ => Usage::Ref,
+            };
+        }
+    } else if ast::ArgList::can_cast(parent.kind())
+        || ast::LetStmt::can_cast(parent.kind())
+        || ast::ReturnExpr::can_cast(parent.kind())
+    {
+        return Usage::Move;
+    }
+    Usage::Ref
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn add_fn_mut_return_type() {
+        check_assist(
+            add_impl_fn_return_type,
+            &with_core(
+                r#"
+fn counter<|>(step: u32) {
+    let mut count = 0;
+    move || {
+        count += step;
+        count
+    }
+}
+"#,
+            ),
+            r#"
+fn counter(step: u32) -> impl FnMut() -> u32 {
+    let mut count = 0;
+    move || {
+        count += step;
+        count
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_fn_once_return_type() {
+        check_assist(
+            add_impl_fn_return_type,
+            &with_core(
+                r#"
+struct Handle;
+fn consume(handle: Handle) {}
+fn deferred(handle: Handle) {
+    <|>move |flag: bool| consume(handle)
+}
+"#,
+            ),
+            r#"
+struct Handle;
+fn consume(handle: Handle) {}
+fn deferred(handle: Handle) -> impl FnOnce(bool) {
+    move |flag: bool| consume(handle)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn copy_captures_are_not_moved() {
+        check_assist(
+            add_impl_fn_return_type,
+            &with_core("fn constant<|>(x: u32) {\n    move || x\n}"),
+            "fn constant(x: u32) -> impl Fn() -> u32 {\n    move || x\n}\n",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            add_impl_fn_return_type,
+            "fn f<|>() -> impl Fn() -> u32 { || 92 }",
+        );
+        check_assist_not_applicable(add_impl_fn_return_type, "fn f<|>() { 92 }");
+        check_assist_not_applicable(add_impl_fn_return_type, "fn f() { let x = 1; <|>x; || x }");
+        // The parameter type can't be inferred.
+        check_assist_not_applicable(add_impl_fn_return_type, "fn f<|>() { |x| x }");
+    }
+
+    #[test]
+    fn add_impl_fn_return_type_target() {
+        check_assist_target(add_impl_fn_return_type, "fn f<|>() { || 92 }", "fn f()");
+    }
+}
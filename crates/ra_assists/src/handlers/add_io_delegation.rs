@@ -0,0 +1,272 @@
+use hir::{Adt, ImplDef};
+use ra_syntax::ast::{self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, TypeParamsOwner};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{has_bound, impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_io_delegation
+//
+// Implements the `std::io` traits of a wrapped reader or writer by
+// forwarding to the inner field.
+//
+// ```
+// struct Counting<W: Write> {
+//     inner: W,<|>
+//     count: usize,
+// }
+// ```
+// ->
+// ```
+// struct Counting<W: Write> {
+//     inner: W,
+//     count: usize,
+// }
+//
+// impl<W: Write> std::io::Write for Counting<W> {
+//     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+//         self.inner.write(buf)
+//     }
+//
+//     fn flush(&mut self) -> std::io::Result<()> {
+//         self.inner.flush()
+//     }
+// }
+// ```
+pub(crate) fn add_io_delegation(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let krate = struct_def.module(ctx.db).krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+    let has_impl = |trait_: Option<hir::Trait>| {
+        trait_.map_or(false, |trait_| {
+            ImplDef::for_trait(ctx.db, krate, trait_)
+                .into_iter()
+                .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(struct_def)))
+        })
+    };
+
+    let fields: Vec<(String, Option<hir::Field>, ast::TypeRef)> = match strukt.kind() {
+        StructKind::Record(it) => it
+            .fields()
+            .filter_map(|f| Some((f.name()?.to_string(), ctx.sema.to_def(&f), f.ascribed_type()?)))
+            .collect(),
+        StructKind::Tuple(it) => it
+            .fields()
+            .enumerate()
+            .filter_map(|(idx, f)| Some((idx.to_string(), ctx.sema.to_def(&f), f.type_ref()?)))
+            .collect(),
+        StructKind::Unit => return None,
+    };
+    let (field, traits) = fields.iter().find_map(|(field, def, ty)| {
+        let traits: Vec<IoTrait> = IoTrait::ALL
+            .iter()
+            .copied()
+            .filter(|io_trait| !has_impl(io_trait.find(&famous_defs)))
+            .filter(|io_trait| {
+                let trait_ = io_trait.find(&famous_defs);
+                let implements = match (def, trait_) {
+                    (Some(def), Some(trait_)) => {
+                        def.signature_ty(ctx.db).impls_trait(ctx.db, trait_, &[])
+                    }
+                    _ => false,
+                };
+                implements || has_bound(&strukt, ty, io_trait.name())
+            })
+            .collect();
+        if traits.is_empty() {
+            None
+        } else {
+            Some((field.clone(), traits))
+        }
+    })?;
+
+    let label =
+        format!("Forward `{}` to `{}`", traits.iter().map(|it| it.name()).sep_by("`, `"), field);
+    let target = strukt.syntax().text_range();
+    acc.add(AssistId("add_io_delegation"), label, target, |builder| {
+        let nominal = ast::NominalDef::StructDef(strukt.clone());
+        let mut buf = String::new();
+        for io_trait in traits.iter() {
+            format_to!(
+                buf,
+                "\n\nimpl{} std::io::{} for {}",
+                impl_type_params(&nominal),
+                io_trait.name(),
+                nominal_self_ty(&nominal, &name)
+            );
+            if let Some(where_clause) = strukt.where_clause() {
+                format_to!(buf, "\n{}\n{{", where_clause.syntax());
+            } else {
+                buf.push_str(" {");
+            }
+            let methods = io_trait.methods().iter().map(|(sig, call)| {
+                format!("\n    {} {{\n        self.{}.{}\n    }}\n", sig, field, call)
+            });
+            format_to!(buf, "{}}}", methods.sep_by(""));
+        }
+        builder.insert(strukt.syntax().text_range().end(), buf);
+    })
+}
+
+#[derive(Clone, Copy)]
+enum IoTrait {
+    Read,
+    BufRead,
+    Write,
+}
+
+impl IoTrait {
+    const ALL: [IoTrait; 3] = [IoTrait::Read, IoTrait::BufRead, IoTrait::Write];
+
+    fn name(self) -> &'static str {
+        match self {
+            IoTrait::Read => "Read",
+            IoTrait::BufRead => "BufRead",
+            IoTrait::Write => "Write",
+        }
+    }
+
+    fn find(self, famous_defs: &FamousDefs) -> Option<hir::Trait> {
+        match self {
+            IoTrait::Read => famous_defs.std_io_Read(),
+            IoTrait::BufRead => famous_defs.std_io_BufRead(),
+            IoTrait::Write => famous_defs.std_io_Write(),
+        }
+    }
+
+    /// The required methods, with the forwarding calls.
+    fn methods(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            IoTrait::Read => {
+                &[("fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize>", "read(buf)")]
+            }
+            IoTrait::BufRead => &[
+                ("fn fill_buf(&mut self) -> std::io::Result<&[u8]>", "fill_buf()"),
+                ("fn consume(&mut self, amt: usize)", "consume(amt)"),
+            ],
+            IoTrait::Write => &[
+                ("fn write(&mut self, buf: &[u8]) -> std::io::Result<usize>", "write(buf)"),
+                ("fn flush(&mut self) -> std::io::Result<()>", "flush()"),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const STD: &str = r"
+//- /libstd.rs crate:std
+pub mod io {
+    pub trait Read {}
+    pub trait BufRead: Read {}
+    pub trait Write {}
+    pub struct File;
+    impl Read for File {}
+    impl Write for File {}
+    pub struct BufReader<R>(R);
+    impl<R: Read> Read for BufReader<R> {}
+    impl<R: Read> BufRead for BufReader<R> {}
+}
+";
+
+    fn with_std(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:std\n{}{}", ra_fixture.trim_end(), STD)
+    }
+
+    #[test]
+    fn forward_read_and_write() {
+        check_assist(
+            add_io_delegation,
+            &with_std("use std::io::File;\nstruct Logged<|>(File, usize);"),
+            r"use std::io::File;
+struct Logged(File, usize);
+
+impl std::io::Read for Logged {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl std::io::Write for Logged {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn forward_buf_read_and_skip_existing_impls() {
+        check_assist(
+            add_io_delegation,
+            &with_std(
+                r"
+use std::io::{BufReader, File, Read};
+struct Reader<|> { count: usize, inner: BufReader<File> }
+impl Read for Reader {}",
+            ),
+            r"
+use std::io::{BufReader, File, Read};
+struct Reader { count: usize, inner: BufReader<File> }
+
+impl std::io::BufRead for Reader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+impl Read for Reader {}
+",
+        );
+    }
+
+    #[test]
+    fn forward_to_bounded_type_param() {
+        check_assist(
+            add_io_delegation,
+            r"
+struct Counting<W> where W: std::io::Write {
+    count: usize,
+    inner: W,<|>
+}",
+            r"
+struct Counting<W> where W: std::io::Write {
+    count: usize,
+    inner: W,
+}
+
+impl<W> std::io::Write for Counting<W>
+where W: std::io::Write
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_io_fields() {
+        check_assist_not_applicable(add_io_delegation, &with_std("struct Foo<|> { count: usize }"));
+    }
+}
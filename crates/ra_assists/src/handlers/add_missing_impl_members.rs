@@ -19,6 +19,7 @@ use crate::{
 enum AddMissingImplMembersMode {
     DefaultMethodsOnly,
     NoDefaultMethods,
+    AllMethods,
 }
 
 // Assist: add_impl_missing_members
@@ -103,6 +104,50 @@ pub(crate) fn add_missing_default_members(acc: &mut Assists, ctx: &AssistContext
     )
 }
 
+// Assist: add_impl_missing_functions
+//
+// Adds stubs for all missing associated functions, including the ones which
+// have a default implementation.
+//
+// ```
+// trait Trait {
+//     Type X;
+//     fn foo(&self);
+//     fn bar(&self) {}
+// }
+//
+// impl Trait for () {<|>
+//
+// }
+// ```
+// ->
+// ```
+// trait Trait {
+//     Type X;
+//     fn foo(&self);
+//     fn bar(&self) {}
+// }
+//
+// impl Trait for () {
+//     fn foo(&self) {
+//         ${0:todo!()}
+//     }
+//     fn bar(&self) {
+//         todo!()
+//     }
+//
+// }
+// ```
+pub(crate) fn add_missing_impl_functions(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    add_missing_impl_members_inner(
+        acc,
+        ctx,
+        AddMissingImplMembersMode::AllMethods,
+        "add_impl_missing_functions",
+        "Implement all missing functions",
+    )
+}
+
 fn add_missing_impl_members_inner(
     acc: &mut Assists,
     ctx: &AssistContext,
@@ -137,6 +182,7 @@ fn add_missing_impl_members_inner(
             ast::AssocItem::FnDef(def) => match mode {
                 AddMissingImplMembersMode::DefaultMethodsOnly => def.body().is_some(),
                 AddMissingImplMembersMode::NoDefaultMethods => def.body().is_none(),
+                AddMissingImplMembersMode::AllMethods => true,
             },
             _ => mode == AddMissingImplMembersMode::NoDefaultMethods,
         })
@@ -157,7 +203,10 @@ fn add_missing_impl_members_inner(
             .into_iter()
             .map(|it| ast_transform::apply(&*ast_transform, it))
             .map(|it| match it {
-                ast::AssocItem::FnDef(def) => ast::AssocItem::FnDef(add_body(def)),
+                ast::AssocItem::FnDef(def) => {
+                    let replace_body = mode == AddMissingImplMembersMode::AllMethods;
+                    ast::AssocItem::FnDef(add_body(def, replace_body))
+                }
                 _ => it,
             })
             .map(|it| edit::remove_attrs_and_docs(&it));
@@ -188,8 +237,8 @@ fn add_missing_impl_members_inner(
     })
 }
 
-fn add_body(fn_def: ast::FnDef) -> ast::FnDef {
-    if fn_def.body().is_some() {
+fn add_body(fn_def: ast::FnDef, replace_body: bool) -> ast::FnDef {
+    if fn_def.body().is_some() && !replace_body {
         return fn_def;
     }
     let body = make::block_expr(None, Some(make::expr_todo())).indent(IndentLevel(1));
@@ -635,6 +684,59 @@ impl Foo for S {
         )
     }
 
+    #[test]
+    fn test_all_missing_functions() {
+        check_assist(
+            add_missing_impl_functions,
+            r#"
+trait Foo {
+    type Output;
+
+    const CONST: usize = 42;
+
+    fn valid(some: u32) -> bool { false }
+    fn foo(some: u32) -> bool;
+    fn bar(&self);
+}
+struct S;
+impl Foo for S {
+    fn bar(&self) {}<|>
+}"#,
+            r#"
+trait Foo {
+    type Output;
+
+    const CONST: usize = 42;
+
+    fn valid(some: u32) -> bool { false }
+    fn foo(some: u32) -> bool;
+    fn bar(&self);
+}
+struct S;
+impl Foo for S {
+    fn bar(&self) {}
+    fn valid(some: u32) -> bool {
+        ${0:todo!()}
+    }
+    fn foo(some: u32) -> bool {
+        todo!()
+    }
+}"#,
+        );
+        check_assist_not_applicable(
+            add_missing_impl_functions,
+            r#"
+trait Foo {
+    type Output;
+    fn foo(&self) {}
+}
+struct S;
+impl Foo for S {
+    fn foo(&self) {}<|>
+}"#,
+        );
+    }
+
     #[test]
     fn test_generic_single_default_parameter() {
         check_assist(
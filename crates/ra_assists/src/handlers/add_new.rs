@@ -7,12 +7,19 @@ use ra_syntax::{
 };
 use stdx::{format_to, SepBy};
 
-use crate::{AssistContext, AssistId, Assists};
+use crate::{
+    utils::{FamousDefs, TryEnum},
+    AssistContext, AssistId, Assists,
+};
 
 // Assist: add_new
 //
 // Adds a new inherent impl for a type.
 //
+// Fields of type `Option` are initialized with `None`. Fields which implement
+// `Default` are initialized with `Default::default()` too, if enabled in the
+// config.
+//
 // ```
 // struct Ctx<T: Clone> {
 //      data: T,<|>
@@ -41,6 +48,14 @@ pub(crate) fn add_new(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     // Return early if we've found an existing new fn
     let impl_def = find_struct_impl(&ctx, &strukt)?;
 
+    let fields = field_list
+        .fields()
+        .filter_map(|f| {
+            let init = field_init(ctx, &f);
+            Some((f.name()?, f.ascribed_type()?, init))
+        })
+        .collect::<Vec<_>>();
+
     let target = strukt.syntax().text_range();
     acc.add(AssistId("add_new"), "Add default constructor", target, |builder| {
         let mut buf = String::with_capacity(512);
@@ -51,13 +66,18 @@ pub(crate) fn add_new(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
 
         let vis = strukt.visibility().map_or(String::new(), |v| format!("{} ", v));
 
-        let params = field_list
-            .fields()
-            .filter_map(|f| {
-                Some(format!("{}: {}", f.name()?.syntax(), f.ascribed_type()?.syntax()))
+        let params = fields
+            .iter()
+            .filter(|(_, _, init)| init.is_none())
+            .map(|(name, ty, _)| format!("{}: {}", name.syntax(), ty.syntax()))
+            .sep_by(", ");
+        let fields = fields
+            .iter()
+            .map(|(name, _, init)| match init {
+                Some(init) => format!("{}: {}", name.syntax(), init),
+                None => name.syntax().to_string(),
             })
             .sep_by(", ");
-        let fields = field_list.fields().filter_map(|f| f.name()).sep_by(", ");
 
         format_to!(buf, "    {}fn new({}) -> Self {{ Self {{ {} }} }}", vis, params, fields);
 
@@ -88,6 +108,25 @@ pub(crate) fn add_new(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     })
 }
 
+// Returns the expression to initialize the field with, if it shouldn't be
+// passed as a parameter
+fn field_init(ctx: &AssistContext, field: &ast::RecordFieldDef) -> Option<&'static str> {
+    let ty = ctx.sema.to_def(field)?.signature_ty(ctx.db);
+    if let Some(TryEnum::Option) = TryEnum::from_ty(&ctx.sema, &ty) {
+        return Some("None");
+    }
+    if !ctx.config.omit_default_fields_in_new {
+        return None;
+    }
+    let krate = ctx.sema.scope(field.syntax()).module()?.krate();
+    let default_trait = FamousDefs(&ctx.sema, krate).core_default_Default()?;
+    if ty.impls_trait(ctx.db, default_trait, &[]) {
+        Some("Default::default()")
+    } else {
+        None
+    }
+}
+
 // Generates the surrounding `impl Type { <code> }` including type and lifetime
 // parameters
 fn generate_impl_text(strukt: &ast::StructDef, code: &str) -> String {
@@ -175,7 +214,13 @@ fn has_new_fn(imp: &ast::ImplDef) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+    use crate::{
+        tests::{
+            check_assist, check_assist_not_applicable, check_assist_target,
+            check_assist_with_config, with_core,
+        },
+        AssistConfig,
+    };
 
     use super::*;
 
@@ -325,6 +370,61 @@ impl Foo {
         );
     }
 
+    #[test]
+    fn add_new_initializes_option_fields() {
+        check_assist(
+            add_new,
+            r"
+enum Option<T> { Some(T), None }
+struct Foo {<|> bar: i32, baz: Option<String> }",
+            r"
+enum Option<T> { Some(T), None }
+struct Foo { bar: i32, baz: Option<String> }
+
+impl Foo {
+    fn $0new(bar: i32) -> Self { Self { bar, baz: None } }
+}
+",
+        );
+    }
+
+    #[test]
+    fn add_new_omits_default_fields() {
+        let before = &with_core(
+            r"struct Bar;
+impl Default for Bar {
+    fn default() -> Self { Bar }
+}
+struct Foo {<|> bar: Bar, baz: i32, qux: Option<Bar> }",
+        );
+        let after = r"struct Bar;
+impl Default for Bar {
+    fn default() -> Self { Bar }
+}
+struct Foo { bar: Bar, baz: i32, qux: Option<Bar> }
+
+impl Foo {
+    fn $0new(baz: i32) -> Self { Self { bar: Default::default(), baz, qux: None } }
+}
+
+";
+        let config = AssistConfig { omit_default_fields_in_new: true, ..AssistConfig::default() };
+        check_assist_with_config(add_new, config, before, after);
+
+        let after = r"struct Bar;
+impl Default for Bar {
+    fn default() -> Self { Bar }
+}
+struct Foo { bar: Bar, baz: i32, qux: Option<Bar> }
+
+impl Foo {
+    fn $0new(bar: Bar, baz: i32) -> Self { Self { bar, baz, qux: None } }
+}
+
+";
+        check_assist(add_new, before, after);
+    }
+
     #[test]
     fn add_new_not_applicable_if_fn_exists() {
         check_assist_not_applicable(
@@ -0,0 +1,189 @@
+use hir::ModuleSource;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, ModuleItemOwner, NameOwner, VisibilityOwner},
+    SmolStr,
+};
+use stdx::SepBy;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: add_reexports
+//
+// Re-exports the public items of a submodule, which are not yet available in
+// the parent module.
+//
+// ```
+// mod <|>parser {
+//     pub struct Parser;
+//     pub fn parse() {}
+//     fn helper() {}
+// }
+// ```
+// ->
+// ```
+// mod parser {
+//     pub struct Parser;
+//     pub fn parse() {}
+//     fn helper() {}
+// }
+// pub use self::parser::{parse, Parser};
+// ```
+pub(crate) fn add_reexports(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module = ctx.find_node_at_offset::<ast::Module>()?;
+    let name = module.name()?;
+    // Don't offer the assist everywhere inside of an inline module.
+    if let Some(item_list) = module.item_list() {
+        if item_list.syntax().text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    let module_def = ctx.sema.to_def(&module)?;
+    let items: Vec<ast::ModuleItem> = match module_def.definition_source(ctx.db).value {
+        ModuleSource::SourceFile(it) => it.items().collect(),
+        ModuleSource::Module(it) => it.item_list()?.items().collect(),
+    };
+
+    let parent_scope = module_def.parent(ctx.db)?.scope(ctx.db, None);
+    let mut names: Vec<SmolStr> = items
+        .iter()
+        .filter(|item| is_public(item))
+        .filter_map(|item| Some(item_name(item)?.text().clone()))
+        // Skips both items which are already re-exported and those which
+        // would conflict with an existing name.
+        .filter(|it| !parent_scope.iter().any(|(name, _)| name.to_string() == it.as_str()))
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    names.sort_by_key(|it| it.to_lowercase());
+    names.dedup();
+
+    let target = module.syntax().text_range();
+    acc.add(
+        AssistId("add_reexports"),
+        format!("Re-export public items of `{}`", name),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(module.syntax());
+            let imported = if names.len() == 1 {
+                names[0].to_string()
+            } else {
+                format!("{{{}}}", names.iter().sep_by(", "))
+            };
+            let text = format!("\n{}pub use self::{}::{};", indent, name, imported);
+            builder.insert(module.syntax().text_range().end(), text);
+        },
+    )
+}
+
+fn is_public(item: &ast::ModuleItem) -> bool {
+    let visibility = match item {
+        ast::ModuleItem::StructDef(it) => it.visibility(),
+        ast::ModuleItem::UnionDef(it) => it.visibility(),
+        ast::ModuleItem::EnumDef(it) => it.visibility(),
+        ast::ModuleItem::FnDef(it) => it.visibility(),
+        ast::ModuleItem::TraitDef(it) => it.visibility(),
+        ast::ModuleItem::TypeAliasDef(it) => it.visibility(),
+        ast::ModuleItem::ConstDef(it) => it.visibility(),
+        ast::ModuleItem::StaticDef(it) => it.visibility(),
+        _ => None,
+    };
+    visibility.map_or(false, |it| it.syntax().text() == "pub")
+}
+
+fn item_name(item: &ast::ModuleItem) -> Option<ast::Name> {
+    match item {
+        ast::ModuleItem::StructDef(it) => it.name(),
+        ast::ModuleItem::UnionDef(it) => it.name(),
+        ast::ModuleItem::EnumDef(it) => it.name(),
+        ast::ModuleItem::FnDef(it) => it.name(),
+        ast::ModuleItem::TraitDef(it) => it.name(),
+        ast::ModuleItem::TypeAliasDef(it) => it.name(),
+        ast::ModuleItem::ConstDef(it) => it.name(),
+        ast::ModuleItem::StaticDef(it) => it.name(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn reexport_out_of_line_module() {
+        check_assist(
+            add_reexports,
+            r"
+//- /lib.rs
+mod <|>lexer;
+mod parser;
+
+//- /lexer.rs
+pub struct Token;
+pub(crate) fn lex() {}
+pub const EOF: Token = Token;
+",
+            r"mod lexer;
+pub use self::lexer::{EOF, Token};
+mod parser;
+
+",
+        );
+    }
+
+    #[test]
+    fn skip_existing_reexports() {
+        check_assist(
+            add_reexports,
+            r"
+mod foo {
+    mod parser<|> {
+        pub struct Parser;
+        pub trait Parse {}
+    }
+    pub use self::parser::Parser;
+}
+",
+            r"
+mod foo {
+    mod parser {
+        pub struct Parser;
+        pub trait Parse {}
+    }
+    pub use self::parser::Parse;
+    pub use self::parser::Parser;
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_new_public_items() {
+        check_assist_not_applicable(
+            add_reexports,
+            r"
+mod parser<|> {
+    pub struct Parser;
+    fn helper() {}
+}
+pub use self::parser::Parser;
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_module_body() {
+        check_assist_not_applicable(add_reexports, "mod parser { pub fn <|>parse() {} }");
+    }
+
+    #[test]
+    fn add_reexports_target() {
+        check_assist_target(
+            add_reexports,
+            "mod <|>foo { pub fn bar() {} }",
+            "mod foo { pub fn bar() {} }",
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use ra_syntax::{
+    ast::{
+        self, AstNode, NameOwner, SelfParamKind, TypeAscriptionOwner, TypeBoundsOwner,
+        TypeParamsOwner,
+    },
+    SyntaxKind::WHITESPACE,
+    T,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: add_self_sized_bound
+//
+// Adds `where Self: Sized` to a trait method, which takes `self` by value or
+// has type parameters. The method is no longer available on `dyn Trait`, but
+// doesn't keep the trait from being object safe anymore.
+//
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+//     fn into_<|>boxed(self) -> Box<dyn Shape>;
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+//     fn into_boxed(self) -> Box<dyn Shape> where Self: Sized;
+// }
+// ```
+pub(crate) fn add_self_sized_bound(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let name = fn_def.name()?;
+    if let Some(body) = fn_def.body() {
+        if body.syntax().text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    let item_list = fn_def.syntax().parent().and_then(ast::ItemList::cast)?;
+    item_list.syntax().parent().and_then(ast::TraitDef::cast)?;
+    if !(takes_self_by_value(&fn_def) || has_type_params(&fn_def)) {
+        return None;
+    }
+    let where_clause = fn_def.where_clause();
+    let has_bound = where_clause.iter().flat_map(|it| it.predicates()).any(|pred| {
+        pred.type_ref().map_or(false, |it| it.syntax().text() == "Self")
+            && pred
+                .type_bound_list()
+                .map_or(false, |it| it.bounds().any(|it| it.syntax().text() == "Sized"))
+    });
+    if has_bound {
+        return None;
+    }
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("add_self_sized_bound"),
+        format!("Add `where Self: Sized` to `{}`", name),
+        target,
+        |builder| match &where_clause {
+            Some(where_clause) => {
+                let has_trailing_comma =
+                    where_clause.syntax().last_token().map_or(false, |it| it.kind() == T![,]);
+                let text = if has_trailing_comma { " Self: Sized," } else { ", Self: Sized" };
+                builder.insert(where_clause.syntax().text_range().end(), text);
+            }
+            None => {
+                let offset = match (fn_def.body(), fn_def.semicolon_token()) {
+                    (Some(body), _) => body.syntax().text_range().start(),
+                    (None, Some(semicolon)) => semicolon.text_range().start(),
+                    (None, None) => fn_def.syntax().text_range().end(),
+                };
+                let after_whitespace = fn_def
+                    .syntax()
+                    .token_at_offset(offset)
+                    .left_biased()
+                    .map_or(false, |it| it.kind() == WHITESPACE);
+                if after_whitespace {
+                    builder.insert(offset, "where Self: Sized ");
+                } else {
+                    builder.insert(offset, " where Self: Sized");
+                }
+            }
+        },
+    )
+}
+
+/// Checks whether the receiver is `self`, `mut self` or `self: Self`.
+fn takes_self_by_value(fn_def: &ast::FnDef) -> bool {
+    let self_param = match fn_def.param_list().and_then(|it| it.self_param()) {
+        Some(it) => it,
+        None => return false,
+    };
+    self_param.kind() == SelfParamKind::Owned
+        && self_param.ascribed_type().map_or(true, |it| it.syntax().text() == "Self")
+}
+
+fn has_type_params(fn_def: &ast::FnDef) -> bool {
+    fn_def.type_param_list().map_or(false, |it| it.type_params().next().is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_bound_to_generic_method() {
+        check_assist(
+            add_self_sized_bound,
+            r"
+trait Visitor {
+    fn visit<|><T: Node>(&mut self, node: &T) {
+        node.accept(self);
+    }
+}",
+            r"
+trait Visitor {
+    fn visit<T: Node>(&mut self, node: &T) where Self: Sized {
+        node.accept(self);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_bound_to_existing_where_clause() {
+        check_assist(
+            add_self_sized_bound,
+            r"
+trait Builder {
+    fn <|>build<T>(self) -> T
+    where
+        T: Default,
+    ;
+}",
+            r"
+trait Builder {
+    fn build<T>(self) -> T
+    where
+        T: Default, Self: Sized,
+    ;
+}",
+        );
+        check_assist(
+            add_self_sized_bound,
+            "trait Builder { fn <|>build(mut self) where Self: Clone; }",
+            "trait Builder { fn build(mut self) where Self: Clone, Self: Sized; }",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(add_self_sized_bound, "trait Shape { fn <|>area(&self); }");
+        check_assist_not_applicable(
+            add_self_sized_bound,
+            "trait Shape { fn <|>into_box(self: Box<Self>); }",
+        );
+        check_assist_not_applicable(
+            add_self_sized_bound,
+            "trait Shape { fn <|>into(self) where Self: Sized; }",
+        );
+        check_assist_not_applicable(add_self_sized_bound, "impl Shape { fn <|>into(self) {} }");
+        check_assist_not_applicable(
+            add_self_sized_bound,
+            "trait Shape { fn into(self) { <|>self } }",
+        );
+    }
+
+    #[test]
+    fn add_self_sized_bound_target() {
+        check_assist_target(
+            add_self_sized_bound,
+            "trait Shape { fn <|>into(self); }",
+            "fn into(self);",
+        );
+    }
+}
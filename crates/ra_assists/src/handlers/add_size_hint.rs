@@ -0,0 +1,284 @@
+use hir::{Adt, HasSource};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, StructKind, TypeAscriptionOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+use stdx::format_to;
+
+use crate::{utils::resolve_target_trait, AssistContext, AssistId, Assists};
+
+// Assist: add_size_hint
+//
+// Overrides `Iterator::size_hint` for an iterator over a collection, using the
+// length of the collection.
+//
+// ```
+// trait Iterator {
+//     type Item;
+//     fn next(&mut self) -> Option<Self::Item>;
+//     fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+// }
+// struct Iter<'a> { items: &'a [u32] }
+// impl<'a> Iterator for Iter<'a> {<|>
+//     type Item = u32;
+//     fn next(&mut self) -> Option<u32> { None }
+// }
+// ```
+// ->
+// ```
+// trait Iterator {
+//     type Item;
+//     fn next(&mut self) -> Option<Self::Item>;
+//     fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+// }
+// struct Iter<'a> { items: &'a [u32] }
+// impl<'a> Iterator for Iter<'a> {
+//     type Item = u32;
+//     fn next(&mut self) -> Option<u32> { None }
+//
+//     fn size_hint(&self) -> (usize, Option<usize>) {
+//         let len = self.items.len();
+//         (len, Some(len))
+//     }
+// }
+// ```
+pub(crate) fn add_size_hint(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::ImplDef>()?;
+    let item_list = impl_def.item_list()?;
+    let trait_ = resolve_target_trait(&ctx.sema, &impl_def)?;
+    if trait_.name(ctx.db).to_string() != "Iterator" {
+        return None;
+    }
+    let overrides_size_hint = item_list.assoc_items().any(|it| match it {
+        ast::AssocItem::FnDef(it) => it.name().map_or(false, |it| it.text() == "size_hint"),
+        _ => false,
+    });
+    if overrides_size_hint {
+        return None;
+    }
+
+    let strukt = match ctx.sema.to_def(&impl_def)?.target_ty(ctx.db).as_adt()? {
+        Adt::Struct(it) => it.source(ctx.db).value,
+        _ => return None,
+    };
+    let len_expr = length_source(&strukt)?;
+
+    let r_curly = item_list.r_curly_token()?;
+    let target = impl_def.syntax().text_range();
+    acc.add(AssistId("add_size_hint"), "Add accurate `size_hint`", target, |builder| {
+        let indent = IndentLevel::from_node(impl_def.syntax());
+        let body_indent = IndentLevel(indent.0 + 1);
+        let mut buf = String::new();
+        if item_list.assoc_items().next().is_some() {
+            buf.push('\n');
+        }
+        format_to!(buf, "\n{}fn size_hint(&self) -> (usize, Option<usize>) {{", body_indent);
+        format_to!(buf, "\n{}    let len = {};", body_indent, len_expr);
+        format_to!(buf, "\n{}    (len, Some(len))", body_indent);
+        format_to!(buf, "\n{}}}\n{}", body_indent, indent);
+        let start = r_curly.text_range().start();
+        let whitespace_before = r_curly
+            .prev_sibling_or_token()
+            .filter(|it| it.kind() == WHITESPACE)
+            .map_or(start, |it| it.text_range().start());
+        builder.replace(TextRange::new(whitespace_before, start), buf);
+    })
+}
+
+/// Looks for a field which knows how many items are left: a collection with a
+/// `len` method, minus a position in it, or a pair of `start` and `end` indices.
+fn length_source(strukt: &ast::StructDef) -> Option<String> {
+    let fields = match strukt.kind() {
+        StructKind::Record(it) => it.fields(),
+        _ => return None,
+    };
+    let mut collections = Vec::new();
+    let mut indices = (None, None);
+    for field in fields {
+        let name = field.name()?.text().to_string();
+        let ty = field.ascribed_type()?;
+        if has_len(&ty) {
+            collections.push(name);
+        } else if ty.syntax().text() == "usize" {
+            match name.as_str() {
+                "start" | "begin" | "front" | "pos" | "idx" | "index" | "cursor" => {
+                    indices.0 = Some(name)
+                }
+                "end" | "back" | "len" => indices.1 = Some(name),
+                _ => (),
+            }
+        }
+    }
+    match (collections.as_slice(), indices) {
+        ([collection], (None, _)) => Some(format!("self.{}.len()", collection)),
+        // The items before the position were already returned.
+        ([collection], (Some(pos), _)) => {
+            Some(format!("self.{}.len().saturating_sub(self.{})", collection, pos))
+        }
+        ([], (Some(start), Some(end))) => {
+            Some(format!("self.{}.saturating_sub(self.{})", end, start))
+        }
+        _ => None,
+    }
+}
+
+/// Checks whether `ty` has a `len` method, which counts its items. The `len` of
+/// a `String` counts bytes instead of the items of an iterator.
+fn has_len(ty: &ast::TypeRef) -> bool {
+    match ty {
+        ast::TypeRef::SliceType(_) | ast::TypeRef::ArrayType(_) => true,
+        ast::TypeRef::ReferenceType(it) => it.type_ref().map_or(false, |it| has_len(&it)),
+        ast::TypeRef::ParenType(it) => it.type_ref().map_or(false, |it| has_len(&it)),
+        ast::TypeRef::PathType(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| matches!(it.text().as_str(), "Vec" | "VecDeque" | "BinaryHeap")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const ITERATOR: &str = "trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+    fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+}
+struct Vec<T>(T);
+";
+
+    fn check(ra_fixture_before: &str, ra_fixture_after: &str) {
+        check_assist(
+            add_size_hint,
+            &format!("{}{}", ITERATOR, ra_fixture_before),
+            &format!("{}{}", ITERATOR, ra_fixture_after),
+        )
+    }
+
+    #[test]
+    fn size_hint_from_vec() {
+        check(
+            r"
+struct IntoIter<T> { items: Vec<T> }
+mod imp {
+    impl<T> super::Iterator for super::IntoIter<T> {<|>
+        type Item = T;
+        fn next(&mut self) -> Option<T> { None }
+    }
+}
+",
+            r"
+struct IntoIter<T> { items: Vec<T> }
+mod imp {
+    impl<T> super::Iterator for super::IntoIter<T> {
+        type Item = T;
+        fn next(&mut self) -> Option<T> { None }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.items.len();
+            (len, Some(len))
+        }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn size_hint_from_indices() {
+        check(
+            r"
+struct Indices { start: usize, end: usize }
+impl Iterator for Indices {<|>}
+",
+            r"
+struct Indices { start: usize, end: usize }
+impl Iterator for Indices {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end.saturating_sub(self.start);
+        (len, Some(len))
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn size_hint_from_vec_and_cursor() {
+        check(
+            r"
+struct Iter { items: Vec<u32>, cursor: usize }
+impl Iterator for Iter {<|>}
+",
+            r"
+struct Iter { items: Vec<u32>, cursor: usize }
+impl Iterator for Iter {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.items.len().saturating_sub(self.cursor);
+        (len, Some(len))
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_length_source() {
+        // `String::len` counts bytes, not chars.
+        check_assist_not_applicable(
+            add_size_hint,
+            &format!("{}{}", ITERATOR, "struct Chars { s: String }\nimpl Iterator for Chars {<|>}"),
+        );
+        check_assist_not_applicable(
+            add_size_hint,
+            &format!(
+                "{}{}",
+                ITERATOR,
+                r"
+struct Counter { count: u32 }
+impl Iterator for Counter {<|>
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+"
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_size_hint() {
+        check_assist_not_applicable(
+            add_size_hint,
+            &format!(
+                "{}{}",
+                ITERATOR,
+                r"
+struct Iter<'a> { items: &'a [u32] }
+impl<'a> Iterator for Iter<'a> {<|>
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+    fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+}
+"
+            ),
+        );
+    }
+
+    #[test]
+    fn add_size_hint_target() {
+        check_assist_target(
+            add_size_hint,
+            &format!(
+                "{}{}",
+                ITERATOR, "struct Iter { items: [u8; 4] }\nimpl Iterator for Iter {<|>}"
+            ),
+            "impl Iterator for Iter {}",
+        );
+    }
+}
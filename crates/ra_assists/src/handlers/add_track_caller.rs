@@ -0,0 +1,131 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, NameOwner},
+    SyntaxKind::{COMMENT, WHITESPACE},
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: add_track_caller
+//
+// Adds `#[track_caller]` to a function calling `unwrap` or `expect`, so that
+// panics report the location of the caller.
+//
+// ```
+// fn first(xs: &[u32]) -> u32 {
+//     *xs.first().unwrap()<|>
+// }
+// ```
+// ->
+// ```
+// #[track_caller]
+// fn first(xs: &[u32]) -> u32 {
+//     *xs.first().unwrap()
+// }
+// ```
+pub(crate) fn add_track_caller(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    // The innermost function, unless the cursor is inside of a closure.
+    let fn_def = ctx.covering_element().ancestors().find_map(|node| {
+        if ast::LambdaExpr::can_cast(node.kind()) {
+            Some(None)
+        } else {
+            ast::FnDef::cast(node).map(Some)
+        }
+    })??;
+    let name = fn_def.name()?;
+    let has_track_caller =
+        fn_def.attrs().any(|it| it.simple_name().map_or(false, |name| name == "track_caller"));
+    if has_track_caller || !calls_unwrap(&fn_def) {
+        return None;
+    }
+    let offset = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+        .text_range()
+        .start();
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("add_track_caller"),
+        format!("Add `#[track_caller]` to `{}`", name),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(fn_def.syntax());
+            builder.insert(offset, format!("#[track_caller]\n{}", indent));
+        },
+    )
+}
+
+/// Checks whether `fn_def` itself, not a closure or a nested function inside
+/// of it, calls `unwrap` or `expect`.
+fn calls_unwrap(fn_def: &ast::FnDef) -> bool {
+    let body = match fn_def.body() {
+        Some(it) => it,
+        None => return false,
+    };
+    body.syntax().descendants().filter_map(ast::MethodCallExpr::cast).any(|call| {
+        let is_unwrap =
+            call.name_ref().map_or(false, |it| it.text() == "unwrap" || it.text() == "expect");
+        let owner = call
+            .syntax()
+            .ancestors()
+            .find(|it| ast::FnDef::can_cast(it.kind()) || ast::LambdaExpr::can_cast(it.kind()));
+        is_unwrap && owner.as_ref() == Some(fn_def.syntax())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_track_caller_to_method() {
+        check_assist(
+            add_track_caller,
+            r#"
+impl Config {
+    /// Reads a required setting.
+    pub fn <|>get(&self, key: &str) -> &str {
+        self.values.get(key).expect("missing setting")
+    }
+}"#,
+            r#"
+impl Config {
+    /// Reads a required setting.
+    #[track_caller]
+    pub fn get(&self, key: &str) -> &str {
+        self.values.get(key).expect("missing setting")
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            add_track_caller,
+            "#[track_caller]\nfn foo<|>(x: Option<u32>) -> u32 { x.unwrap() }",
+        );
+        check_assist_not_applicable(add_track_caller, "fn foo<|>(x: Option<u32>) -> u32 { x? }");
+        // Panics in closures are reported at the closure.
+        check_assist_not_applicable(
+            add_track_caller,
+            "fn foo<|>(xs: Vec<Option<u32>>) { xs.into_iter().map(|x| x.unwrap()); }",
+        );
+        check_assist_not_applicable(
+            add_track_caller,
+            "fn foo(xs: Vec<Option<u32>>) { xs.into_iter().map(|x| x.unwrap()<|>); }",
+        );
+    }
+
+    #[test]
+    fn add_track_caller_target() {
+        check_assist_target(
+            add_track_caller,
+            "fn foo(x: Option<u32>) -> u32 { x.unw<|>rap() }",
+            "fn foo(x: Option<u32>) -> u32 { x.unwrap() }",
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use hir::{Adt, ImplDef};
+use ra_syntax::ast::{self, AstNode, NameOwner};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: add_unsafe_send_sync_impl
+//
+// Adds `unsafe impl Send` and `unsafe impl Sync` for a type which holds raw
+// pointers or an `UnsafeCell`, and thus doesn't implement them automatically.
+//
+// ```
+// struct Buffer {
+//     ptr: *mut u8,<|>
+// }
+// ```
+// ->
+// ```
+// struct Buffer {
+//     ptr: *mut u8,
+// }
+//
+// // SAFETY: ${1:explain why `Buffer` can be sent to another thread}
+// unsafe impl Send for Buffer {}
+//
+// // SAFETY: ${2:explain why `Buffer` can be shared between threads}
+// unsafe impl Sync for Buffer {}
+// ```
+pub(crate) fn add_unsafe_send_sync_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let name = nominal.name()?;
+    if !nominal
+        .syntax()
+        .descendants()
+        .filter_map(ast::TypeRef::cast)
+        .any(|it| is_thread_unsafe(&it))
+    {
+        return None;
+    }
+
+    let adt: Adt = match &nominal {
+        ast::NominalDef::StructDef(it) => ctx.sema.to_def(it)?.into(),
+        ast::NominalDef::EnumDef(it) => ctx.sema.to_def(it)?.into(),
+        ast::NominalDef::UnionDef(it) => ctx.sema.to_def(it)?.into(),
+    };
+    let krate = adt.module(ctx.db).krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+    let has_impl = |trait_: Option<hir::Trait>| {
+        trait_.map_or(false, |trait_| {
+            ImplDef::for_trait(ctx.db, krate, trait_)
+                .into_iter()
+                .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(adt))
+        })
+    };
+    let mut missing = Vec::new();
+    if !has_impl(famous_defs.core_marker_Send()) {
+        missing.push(("Send", "can be sent to another thread"));
+    }
+    if !has_impl(famous_defs.core_marker_Sync()) {
+        missing.push(("Sync", "can be shared between threads"));
+    }
+    if missing.is_empty() {
+        return None;
+    }
+
+    let label = format!(
+        "Add `unsafe impl {}`",
+        missing.iter().map(|(trait_, _)| *trait_).sep_by("` and `unsafe impl ")
+    );
+    let target = nominal.syntax().text_range();
+    acc.add(AssistId("add_unsafe_send_sync_impl"), label, target, |builder| {
+        let self_ty = nominal_self_ty(&nominal, &name);
+        let mut buf = String::new();
+        for (idx, (trait_, justification)) in missing.iter().enumerate() {
+            buf.push_str("\n\n// SAFETY:");
+            if ctx.config.snippet_cap.is_some() {
+                let tab_stop = if missing.len() == 1 { 0 } else { idx + 1 };
+                format_to!(buf, " ${{{}:explain why `{}` {}}}", tab_stop, name, justification);
+            }
+            format_to!(
+                buf,
+                "\nunsafe impl{} {} for {} {{}}",
+                impl_type_params(&nominal),
+                trait_,
+                self_ty
+            );
+        }
+        let offset = nominal.syntax().text_range().end();
+        match ctx.config.snippet_cap {
+            Some(cap) => builder.insert_snippet(cap, offset, buf),
+            None => builder.insert(offset, buf),
+        }
+    })
+}
+
+fn is_thread_unsafe(ty: &ast::TypeRef) -> bool {
+    match ty {
+        ast::TypeRef::PointerType(_) => true,
+        ast::TypeRef::PathType(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| it.text() == "UnsafeCell"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist, check_assist_not_applicable, check_assist_target, check_assist_with_core,
+        with_core,
+    };
+
+    use super::*;
+
+    #[test]
+    fn add_send_and_sync() {
+        check_assist(
+            add_unsafe_send_sync_impl,
+            r"
+struct Foo<'a, T> {
+    ptr: *const T,<|>
+    marker: &'a (),
+}
+",
+            r"
+struct Foo<'a, T> {
+    ptr: *const T,
+    marker: &'a (),
+}
+
+// SAFETY: ${1:explain why `Foo` can be sent to another thread}
+unsafe impl<'a, T> Send for Foo<'a, T> {}
+
+// SAFETY: ${2:explain why `Foo` can be shared between threads}
+unsafe impl<'a, T> Sync for Foo<'a, T> {}
+",
+        );
+    }
+
+    #[test]
+    fn add_only_missing_impl() {
+        check_assist_with_core(
+            add_unsafe_send_sync_impl,
+            r"
+use core::{cell::UnsafeCell, marker::Send};
+struct Foo(UnsafeCell<i32><|>);
+unsafe impl Send for Foo {}
+",
+            r"
+use core::{cell::UnsafeCell, marker::Send};
+struct Foo(UnsafeCell<i32>);
+
+// SAFETY: ${0:explain why `Foo` can be shared between threads}
+unsafe impl Sync for Foo {}
+unsafe impl Send for Foo {}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_both_implemented() {
+        check_assist_not_applicable(
+            add_unsafe_send_sync_impl,
+            &with_core(
+                r"
+use core::marker::{Send, Sync};
+struct Foo(*mut i32<|>);
+unsafe impl Send for Foo {}
+unsafe impl Sync for Foo {}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_thread_safe_types() {
+        check_assist_not_applicable(
+            add_unsafe_send_sync_impl,
+            r"
+struct Foo {
+    x: i32,<|>
+    y: Box<i32>,
+}
+",
+        );
+    }
+
+    #[test]
+    fn add_unsafe_send_sync_impl_target() {
+        check_assist_target(
+            add_unsafe_send_sync_impl,
+            r"
+struct Foo(*mut i32<|>);
+",
+            "struct Foo(*mut i32);",
+        );
+    }
+}
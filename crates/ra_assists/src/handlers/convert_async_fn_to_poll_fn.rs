@@ -0,0 +1,252 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR, WHITESPACE},
+    TextRange, TextSize,
+};
+use stdx::format_to;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: convert_async_fn_to_poll_fn
+//
+// Converts an `async fn` into a function returning a `poll_fn` future, which
+// is a skeleton for implementing `Future::poll` manually. The returned values
+// are wrapped into `Poll::Ready`.
+//
+// ```
+// async fn <|>parse(input: String) -> Option<u32> {
+//     if input.is_empty() {
+//         return None;
+//     }
+//     input.parse().ok()
+// }
+// ```
+// ->
+// ```
+// fn parse(input: String) -> impl std::future::Future<Output = Option<u32>> {
+//     std::future::poll_fn(move |_cx| {
+//         use std::task::Poll;
+//
+//         if input.is_empty() {
+//             return Poll::Ready(None);
+//         }
+//         Poll::Ready(input.parse().ok())
+//     })
+// }
+// ```
+pub(crate) fn convert_async_fn_to_poll_fn(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let async_token = fn_def.async_token()?;
+    let body = fn_def.body()?;
+    if ctx.offset() >= body.syntax().text_range().start() || fn_def.const_token().is_some() {
+        return None;
+    }
+    let param_list = fn_def.param_list()?;
+    let output = match fn_def.ret_type() {
+        Some(it) => it.type_ref()?.syntax().to_string(),
+        None => "()".to_string(),
+    };
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("convert_async_fn_to_poll_fn"),
+        "Convert async fn to `poll_fn`",
+        target,
+        |builder| {
+            let mut async_range = async_token.text_range();
+            if let Some(ws) = async_token.next_token().filter(|it| it.kind() == WHITESPACE) {
+                async_range = TextRange::new(async_range.start(), ws.text_range().end());
+            }
+            builder.delete(async_range);
+
+            let ret_type = format!("-> impl std::future::Future<Output = {}>", output);
+            match fn_def.ret_type() {
+                Some(it) => builder.replace(it.syntax().text_range(), ret_type),
+                None => {
+                    builder.insert(param_list.syntax().text_range().end(), format!(" {}", ret_type))
+                }
+            }
+
+            let indent = IndentLevel::from_node(fn_def.syntax());
+            builder.replace(body.syntax().text_range(), poll_fn_body(&body, &output, indent));
+        },
+    )
+}
+
+fn poll_fn_body(body: &ast::BlockExpr, output: &str, indent: IndentLevel) -> String {
+    let start = body.syntax().text_range().start();
+    let mut inserts: Vec<(TextSize, &str)> = Vec::new();
+    for it in returns(body) {
+        match it.expr() {
+            Some(expr) => {
+                inserts.push((expr.syntax().text_range().start(), "Poll::Ready("));
+                inserts.push((expr.syntax().text_range().end(), ")"));
+            }
+            None => inserts.push((it.syntax().text_range().end(), " Poll::Ready(())")),
+        }
+    }
+    match body.expr() {
+        Some(ast::Expr::ReturnExpr(_)) | None => (),
+        Some(tail) => {
+            inserts.push((tail.syntax().text_range().start(), "Poll::Ready("));
+            inserts.push((tail.syntax().text_range().end(), ")"));
+        }
+    }
+    // Inserting from the end keeps the offsets of the earlier inserts valid.
+    inserts.sort_by_key(|(offset, _)| *offset);
+
+    let mut text = body.syntax().to_string();
+    for (offset, insert) in inserts.into_iter().rev() {
+        text.insert_str((offset - start).into(), insert);
+    }
+    let inner = text[1..text.len() - 1].trim_end();
+
+    let mut buf = String::new();
+    format_to!(buf, "{{\n{}std::future::poll_fn(move |_cx| {{", indent + 1);
+    format_to!(buf, "\n{}use std::task::Poll;\n", indent + 2);
+    if inner.contains('\n') {
+        for line in inner.lines().skip_while(|it| it.trim().is_empty()) {
+            buf.push('\n');
+            if !line.trim().is_empty() {
+                format_to!(buf, "{}{}", IndentLevel(1), line);
+            }
+        }
+    } else if !inner.trim().is_empty() {
+        format_to!(buf, "\n{}{}", indent + 2, inner.trim());
+    }
+    if body.expr().is_none() && output == "()" && !ends_with_return(body) {
+        format_to!(buf, "\n{}Poll::Ready(())", indent + 2);
+    }
+    format_to!(buf, "\n{}}})\n{}}}", indent + 1, indent);
+    buf
+}
+
+/// Returns the `return` expressions, which return from the function of `body`.
+fn returns(body: &ast::BlockExpr) -> Vec<ast::ReturnExpr> {
+    body.syntax()
+        .descendants()
+        .filter_map(ast::ReturnExpr::cast)
+        .filter(|it| {
+            !it.syntax().ancestors().take_while(|it| it != body.syntax()).any(|it| {
+                matches!(it.kind(), FN_DEF | LAMBDA_EXPR)
+                    || ast::EffectExpr::cast(it).map_or(false, |it| it.async_token().is_some())
+            })
+        })
+        .collect()
+}
+
+fn ends_with_return(body: &ast::BlockExpr) -> bool {
+    match body.statements().last() {
+        Some(ast::Stmt::ExprStmt(it)) => matches!(it.expr(), Some(ast::Expr::ReturnExpr(_))),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn convert_method_with_nested_returns() {
+        check_assist(
+            convert_async_fn_to_poll_fn,
+            r#"
+struct Client;
+impl Client {
+    pub async <|>fn ping(&self, retries: u32) {
+        let check = |n: u32| {
+            return n > 0;
+        };
+        let task = async {
+            return 1;
+        };
+        if !check(retries) {
+            return;
+        }
+
+        log(retries);
+    }
+}
+"#,
+            r#"
+struct Client;
+impl Client {
+    pub fn ping(&self, retries: u32) -> impl std::future::Future<Output = ()> {
+        std::future::poll_fn(move |_cx| {
+            use std::task::Poll;
+
+            let check = |n: u32| {
+                return n > 0;
+            };
+            let task = async {
+                return 1;
+            };
+            if !check(retries) {
+                return Poll::Ready(());
+            }
+
+            log(retries);
+            Poll::Ready(())
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_return_in_tail() {
+        check_assist(
+            convert_async_fn_to_poll_fn,
+            r#"
+async <|>fn fetch(id: u32) -> Result<u32, ()> {
+    if id == 0 { return Err(()) } else { Ok(id) }
+}
+"#,
+            r#"
+fn fetch(id: u32) -> impl std::future::Future<Output = Result<u32, ()>> {
+    std::future::poll_fn(move |_cx| {
+        use std::task::Poll;
+
+        Poll::Ready(if id == 0 { return Poll::Ready(Err(())) } else { Ok(id) })
+    })
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_single_line_body() {
+        check_assist(
+            convert_async_fn_to_poll_fn,
+            "async fn <|>answer() -> u32 { 42 }",
+            r#"fn answer() -> impl std::future::Future<Output = u32> {
+    std::future::poll_fn(move |_cx| {
+        use std::task::Poll;
+
+        Poll::Ready(42)
+    })
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_async_fn_to_poll_fn_not_applicable() {
+        check_assist_not_applicable(convert_async_fn_to_poll_fn, "fn <|>answer() -> u32 { 42 }");
+        check_assist_not_applicable(
+            convert_async_fn_to_poll_fn,
+            "async fn answer() -> u32 { 4<|>2 }",
+        );
+    }
+
+    #[test]
+    fn convert_async_fn_to_poll_fn_target() {
+        check_assist_target(
+            convert_async_fn_to_poll_fn,
+            "async fn <|>answer() -> u32 { 42 }",
+            "async fn answer() -> u32 { 42 }",
+        );
+    }
+}
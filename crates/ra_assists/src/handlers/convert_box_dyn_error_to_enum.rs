@@ -0,0 +1,446 @@
+use hir::{Adt, ModuleDef};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+};
+use stdx::format_to;
+
+use crate::{
+    utils::{module_item, TryEnum},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: convert_box_dyn_error_to_enum
+//
+// Replaces the `Box<dyn Error>` error type of a function with an enum, which
+// has a variant for every error type propagated with `?`.
+//
+// ```
+// mod io { pub struct Error; }
+// enum Result<T, E> { Ok(T), Err(E) }
+// use Result::Ok;
+// fn open(path: &str) -> Result<u32, io::Error> { Ok(0) }
+//
+// fn <|>load(path: &str) -> Result<u32, Box<dyn std::error::Error>> {
+//     let fd = open(path)?;
+//     Ok(fd)
+// }
+// ```
+// ->
+// ```
+// mod io { pub struct Error; }
+// enum Result<T, E> { Ok(T), Err(E) }
+// use Result::Ok;
+// fn open(path: &str) -> Result<u32, io::Error> { Ok(0) }
+//
+// fn load(path: &str) -> Result<u32, LoadError> {
+//     let fd = open(path)?;
+//     Ok(fd)
+// }
+//
+// #[derive(Debug)]
+// enum LoadError {
+//     Io(io::Error),
+// }
+//
+// impl std::fmt::Display for LoadError {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         match self {
+//             LoadError::Io(err) => write!(f, "{}", err),
+//         }
+//     }
+// }
+//
+// impl std::error::Error for LoadError {
+//     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+//         match self {
+//             LoadError::Io(err) => Some(err),
+//         }
+//     }
+// }
+//
+// impl From<io::Error> for LoadError {
+//     fn from(err: io::Error) -> Self {
+//         LoadError::Io(err)
+//     }
+// }
+// ```
+pub(crate) fn convert_box_dyn_error_to_enum(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.offset() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let err_ty = boxed_error_type(&fn_def.ret_type()?.type_ref()?)?;
+
+    // Errors, which aren't propagated with `?`, can't be converted automatically.
+    let mut leaves = Vec::new();
+    if let Some(tail) = body.expr() {
+        collect_leaves(tail, &mut leaves)?;
+    }
+    for it in own_descendants::<ast::ReturnExpr>(&body) {
+        if let Some(expr) = it.expr() {
+            collect_leaves(expr, &mut leaves)?;
+        }
+    }
+    if !leaves.iter().all(is_ok_call) {
+        return None;
+    }
+
+    let module = ctx.sema.scope(fn_def.syntax()).module()?;
+    let mut variants: Vec<(String, String)> = Vec::new();
+    for try_expr in own_descendants::<ast::TryExpr>(&body) {
+        let ty = ctx.sema.type_of_expr(&try_expr.expr()?)?;
+        if !matches!(TryEnum::from_ty(&ctx.sema, &ty)?, TryEnum::Result) {
+            return None;
+        }
+        let error = ty.type_parameters().pop()?;
+        let adt = error.as_adt()?;
+        if !error.type_parameters().is_empty() {
+            return None;
+        }
+        let path = module.find_use_path(ctx.db, ModuleDef::Adt(adt))?;
+        let path_text = path.to_string();
+        if variants.iter().any(|(_, it)| *it == path_text) {
+            continue;
+        }
+        let mut variant = variant_name(&path.segments, adt, ctx)?;
+        if variants.iter().any(|(it, _)| *it == variant) {
+            let base = variant.clone();
+            let mut counter = 1;
+            while variants.iter().any(|(it, _)| *it == variant) {
+                counter += 1;
+                variant = format!("{}{}", base, counter);
+            }
+        }
+        variants.push((variant, path_text));
+    }
+    if variants.is_empty() {
+        return None;
+    }
+
+    let name = format!("{}Error", to_pascal_case(fn_def.name()?.text()));
+    let mut name_taken = false;
+    ctx.sema.scope(fn_def.syntax()).process_all_names(&mut |it, _| {
+        name_taken |= it.to_string() == name;
+    });
+    if name_taken {
+        return None;
+    }
+    let item = module_item(fn_def.syntax())?;
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("convert_box_dyn_error_to_enum"),
+        format!("Convert `Box<dyn Error>` to `enum {}`", name),
+        target,
+        |builder| {
+            builder.replace(err_ty.syntax().text_range(), name.clone());
+
+            let indent = IndentLevel::from_node(&item);
+            let mut buf = String::new();
+            format_to!(buf, "\n\n{}#[derive(Debug)]\n{}enum {} {{\n", indent, indent, name);
+            for (variant, ty) in &variants {
+                format_to!(buf, "{}{}({}),\n", indent + 1, variant, ty);
+            }
+            format_to!(buf, "{}}}\n\n", indent);
+
+            format_to!(buf, "{}impl std::fmt::Display for {} {{\n", indent, name);
+            format_to!(
+                buf,
+                "{}fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n",
+                indent + 1
+            );
+            format_to!(buf, "{}match self {{\n", indent + 2);
+            for (variant, _) in &variants {
+                format_to!(
+                    buf,
+                    "{}{}::{}(err) => write!(f, \"{{}}\", err),\n",
+                    indent + 3,
+                    name,
+                    variant
+                );
+            }
+            format_to!(buf, "{}}}\n{}}}\n{}}}\n\n", indent + 2, indent + 1, indent);
+
+            format_to!(buf, "{}impl std::error::Error for {} {{\n", indent, name);
+            format_to!(
+                buf,
+                "{}fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {{\n",
+                indent + 1
+            );
+            format_to!(buf, "{}match self {{\n", indent + 2);
+            for (variant, _) in &variants {
+                format_to!(buf, "{}{}::{}(err) => Some(err),\n", indent + 3, name, variant);
+            }
+            format_to!(buf, "{}}}\n{}}}\n{}}}", indent + 2, indent + 1, indent);
+
+            for (variant, ty) in &variants {
+                format_to!(buf, "\n\n{}impl From<{}> for {} {{\n", indent, ty, name);
+                format_to!(buf, "{}fn from(err: {}) -> Self {{\n", indent + 1, ty);
+                format_to!(buf, "{}{}::{}(err)\n", indent + 2, name, variant);
+                format_to!(buf, "{}}}\n{}}}", indent + 1, indent);
+            }
+            builder.insert(item.text_range().end(), buf);
+        },
+    )
+}
+
+/// Returns the error type of `Result<T, Box<dyn Error>>`.
+fn boxed_error_type(ret_type: &ast::TypeRef) -> Option<ast::TypeRef> {
+    let path = match ret_type {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text() != "Result" {
+        return None;
+    }
+    let args: Vec<ast::TypeArg> = segment.type_arg_list()?.type_args().collect();
+    let err_ty = match args.as_slice() {
+        [_, err] => err.type_ref()?,
+        _ => return None,
+    };
+    let text: String = err_ty.syntax().text().to_string().split_whitespace().collect();
+    let is_boxed_error =
+        text.starts_with("Box<dynError") || text.starts_with("Box<dynstd::error::Error");
+    if is_boxed_error {
+        Some(err_ty)
+    } else {
+        None
+    }
+}
+
+/// Collects the expressions, which produce the value of `expr`.
+fn collect_leaves(expr: ast::Expr, acc: &mut Vec<ast::Expr>) -> Option<()> {
+    match expr {
+        ast::Expr::BlockExpr(it) => {
+            if let Some(tail) = it.expr() {
+                collect_leaves(tail, acc)?;
+            }
+        }
+        ast::Expr::EffectExpr(it) if it.unsafe_token().is_some() => {
+            collect_leaves(ast::Expr::BlockExpr(it.block_expr()?), acc)?;
+        }
+        ast::Expr::IfExpr(it) => {
+            collect_leaves(ast::Expr::BlockExpr(it.then_branch()?), acc)?;
+            match it.else_branch()? {
+                ast::ElseBranch::Block(it) => collect_leaves(ast::Expr::BlockExpr(it), acc)?,
+                ast::ElseBranch::IfExpr(it) => collect_leaves(ast::Expr::IfExpr(it), acc)?,
+            }
+        }
+        ast::Expr::MatchExpr(it) => {
+            for arm in it.match_arm_list()?.arms() {
+                collect_leaves(arm.expr()?, acc)?;
+            }
+        }
+        ast::Expr::ParenExpr(it) => collect_leaves(it.expr()?, acc)?,
+        // Diverging expressions don't produce a value.
+        ast::Expr::ReturnExpr(_)
+        | ast::Expr::ContinueExpr(_)
+        | ast::Expr::BreakExpr(_)
+        | ast::Expr::MacroCall(_) => (),
+        it => acc.push(it),
+    }
+    Some(())
+}
+
+fn is_ok_call(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::CallExpr(it) => match it.expr() {
+            Some(ast::Expr::PathExpr(it)) => it
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map_or(false, |it| it.text() == "Ok"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns the nodes in `body`, which aren't part of a closure or an inner function.
+fn own_descendants<N: AstNode>(body: &ast::BlockExpr) -> Vec<N> {
+    body.syntax()
+        .descendants()
+        .filter_map(N::cast)
+        .filter(|it| {
+            !it.syntax().ancestors().take_while(|it| it != body.syntax()).any(|it| {
+                matches!(it.kind(), FN_DEF | LAMBDA_EXPR)
+                    || ast::EffectExpr::cast(it)
+                        .map_or(false, |it| it.async_token().is_some() || it.try_token().is_some())
+            })
+        })
+        .collect()
+}
+
+/// Derives the variant name from the error type, like `Io` for `io::Error`
+/// or `ParseInt` for `ParseIntError`.
+fn variant_name(segments: &[hir::Name], adt: Adt, ctx: &AssistContext) -> Option<String> {
+    let type_name = adt.name(ctx.db).to_string();
+    let candidates = std::iter::once(type_name.clone())
+        .chain(segments.iter().rev().skip(1).map(|it| it.to_string()));
+    for candidate in candidates {
+        let stripped = candidate.trim_end_matches("Error").trim_end_matches('_');
+        if !stripped.is_empty() {
+            return Some(to_pascal_case(stripped));
+        }
+    }
+    Some(type_name)
+}
+
+fn to_pascal_case(s: &str) -> String {
+    s.split('_')
+        .filter(|it| !it.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const PRELUDE: &str = r#"
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::{Err, Ok};
+struct ParseIntError;
+mod io { pub struct Error; }
+fn parse(s: &str) -> Result<u32, ParseIntError> { Ok(0) }
+fn read(path: &str) -> Result<String, io::Error> { Ok(String::new()) }
+"#;
+
+    #[test]
+    fn convert_with_several_error_types_in_impl() {
+        check_assist(
+            convert_box_dyn_error_to_enum,
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+struct Config;
+impl Config {
+    fn read_<|>port(path: &str) -> Result<u32, Box<dyn Error + Send + Sync>> {
+        let text = read(path)?;
+        let check = |s: &str| -> Result<(), ()> { Err(())? };
+        if text.is_empty() {
+            return Ok(0);
+        }
+        let port = parse(&text)?;
+        let again = parse(&text)?;
+        match port {
+            0 => unimplemented!(),
+            _ => Ok(port),
+        }
+    }
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+struct Config;
+impl Config {
+    fn read_port(path: &str) -> Result<u32, ReadPortError> {
+        let text = read(path)?;
+        let check = |s: &str| -> Result<(), ()> { Err(())? };
+        if text.is_empty() {
+            return Ok(0);
+        }
+        let port = parse(&text)?;
+        let again = parse(&text)?;
+        match port {
+            0 => unimplemented!(),
+            _ => Ok(port),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ReadPortError {
+    Io(io::Error),
+    ParseInt(ParseIntError),
+}
+
+impl std::fmt::Display for ReadPortError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadPortError::Io(err) => write!(f, "{}", err),
+            ReadPortError::ParseInt(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReadPortError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadPortError::Io(err) => Some(err),
+            ReadPortError::ParseInt(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ReadPortError {
+    fn from(err: io::Error) -> Self {
+        ReadPortError::Io(err)
+    }
+}
+
+impl From<ParseIntError> for ReadPortError {
+    fn from(err: ParseIntError) -> Self {
+        ReadPortError::ParseInt(err)
+    }
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Returns an error, which isn't propagated with `?`.
+        check_assist_not_applicable(
+            convert_box_dyn_error_to_enum,
+            &format!(
+                "{}{}",
+                PRELUDE,
+                r#"
+fn <|>f(s: &str) -> Result<u32, Box<dyn Error>> {
+    if s.is_empty() { Err("empty".into()) } else { Ok(parse(s)?) }
+}
+"#
+            ),
+        );
+        check_assist_not_applicable(
+            convert_box_dyn_error_to_enum,
+            &format!("{}{}", PRELUDE, "fn <|>f(s: &str) -> Result<u32, Box<dyn Error>> { Ok(1) }"),
+        );
+        check_assist_not_applicable(
+            convert_box_dyn_error_to_enum,
+            &format!(
+                "{}{}",
+                PRELUDE, "fn <|>f(s: &str) -> Result<u32, ParseIntError> { parse(s) }"
+            ),
+        );
+    }
+
+    #[test]
+    fn convert_box_dyn_error_to_enum_target() {
+        check_assist_target(
+            convert_box_dyn_error_to_enum,
+            &format!(
+                "{}{}",
+                PRELUDE, "fn <|>f() -> Result<u32, Box<dyn Error>> { Ok(parse(\"\")?) }"
+            ),
+            "fn f() -> Result<u32, Box<dyn Error>> { Ok(parse(\"\")?) }",
+        );
+    }
+}
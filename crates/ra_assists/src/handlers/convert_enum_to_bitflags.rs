@@ -0,0 +1,311 @@
+use hir::ModuleDef;
+use ra_ide_db::defs::Definition;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxElement,
+    SyntaxKind::{ATTR, COMMENT},
+};
+use stdx::{format_to, to_upper_snake_case};
+
+use crate::{
+    utils::{int_repr, FileEdits, INT_TYPES},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: convert_enum_to_bitflags
+//
+// Converts a field-less enum, whose discriminants are bit flags, to a
+// `bitflags!` struct. The flags are renamed to the `SCREAMING_SNAKE_CASE` of
+// constants. Only offered if the crate depends on `bitflags`, which generates
+// `bits()`, `contains()` and the bitwise operators.
+//
+// ```
+// # //- /main.rs crate:main deps:bitflags
+// #[repr(u8)]
+// enum Permissions<|> {
+//     Read = 1,
+//     Write = 2,
+//     Execute = 4,
+// }
+// # //- /lib.rs crate:bitflags
+// ```
+// ->
+// ```
+// bitflags::bitflags! {
+//     struct Permissions: u8 {
+//         const READ = 1;
+//         const WRITE = 2;
+//         const EXECUTE = 4;
+//     }
+// }
+// ```
+pub(crate) fn convert_enum_to_bitflags(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let name = enum_def.name()?;
+    let variant_list = enum_def.variant_list()?;
+    if variant_list.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    if enum_def.type_param_list().is_some() {
+        return None;
+    }
+    let enum_ = ctx.sema.to_def(&enum_def)?;
+    let krate = enum_.module(ctx.db).krate();
+    if !krate.dependencies(ctx.db).iter().any(|dep| dep.name.to_string() == "bitflags") {
+        return None;
+    }
+
+    let variants: Vec<ast::EnumVariant> = variant_list.variants().collect();
+    if variants.len() < 2 {
+        return None;
+    }
+    let mut flags = Vec::new();
+    for variant in &variants {
+        if variant.field_def_list().is_some() {
+            return None;
+        }
+        let value = flag_value(&name, &variant.expr()?)?;
+        flags.push((variant.clone(), to_upper_snake_case(variant.name()?.text()), value));
+    }
+
+    let target = enum_def.syntax().text_range();
+    acc.add(
+        AssistId("convert_enum_to_bitflags"),
+        format!("Convert `{}` to `bitflags!`", name),
+        target,
+        |builder| {
+            let mut edits = FileEdits::default();
+            for (variant, flag_name, _) in &flags {
+                let def = match ctx.sema.to_def(variant) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let usages =
+                    Definition::ModuleDef(ModuleDef::EnumVariant(def)).find_usages(ctx.db, None);
+                for reference in usages {
+                    let range = reference.file_range.range;
+                    if target.contains_range(range) {
+                        continue;
+                    }
+                    edits.add(reference.file_range.file_id, range, flag_name.clone());
+                }
+            }
+
+            let indent = IndentLevel::from_node(enum_def.syntax());
+            let mut text = String::from("bitflags::bitflags! {\n");
+            for attr in leading_attrs(enum_def.syntax().children_with_tokens()) {
+                if let Some(attr) = convert_attr(&attr) {
+                    format_to!(text, "{}{}\n", indent + 1, attr);
+                }
+            }
+            let repr = int_repr(&enum_def);
+            let vis = enum_def.visibility().map(|it| format!("{} ", it)).unwrap_or_default();
+            format_to!(
+                text,
+                "{}{}struct {}: {} {{\n",
+                indent + 1,
+                vis,
+                name,
+                repr.as_deref().unwrap_or("u32")
+            );
+            for (variant, flag_name, value) in &flags {
+                for attr in leading_attrs(variant.syntax().children_with_tokens()) {
+                    format_to!(text, "{}{}\n", indent + 2, attr);
+                }
+                format_to!(text, "{}const {} = {};\n", indent + 2, flag_name, value);
+            }
+            format_to!(text, "{}}}\n{}}}", indent + 1, indent);
+            edits.add(ctx.frange.file_id, target, text);
+            edits.apply(builder, ctx.frange.file_id);
+        },
+    )
+}
+
+/// The traits, which are derived by `bitflags!` itself.
+const BITFLAGS_DERIVES: &[&str] =
+    &["Clone", "Copy", "Debug", "Eq", "Hash", "Ord", "PartialEq", "PartialOrd"];
+
+/// Returns the value of the flag, if `expr` is a power of two, zero, or a
+/// combination of other flags, like `Flags::A as u8 | Flags::B as u8`.
+fn flag_value(enum_name: &ast::Name, expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::Literal(lit) => {
+            let value = parse_int(&lit.syntax().text().to_string())?;
+            if value == 0 || value.is_power_of_two() {
+                Some(lit.syntax().to_string())
+            } else {
+                None
+            }
+        }
+        ast::Expr::BinExpr(bin_expr) => {
+            let lhs = bin_expr.lhs()?;
+            let rhs = bin_expr.rhs()?;
+            match bin_expr.op_kind()? {
+                ast::BinOp::LeftShift => {
+                    let is_one = matches!(&lhs, ast::Expr::Literal(it) if parse_int(&it.syntax().text().to_string()) == Some(1));
+                    let is_shift = matches!(&rhs, ast::Expr::Literal(_));
+                    if is_one && is_shift {
+                        Some(bin_expr.syntax().to_string())
+                    } else {
+                        None
+                    }
+                }
+                ast::BinOp::BitwiseOr => Some(format!(
+                    "{} | {}",
+                    flag_value(enum_name, &lhs)?,
+                    flag_value(enum_name, &rhs)?
+                )),
+                _ => None,
+            }
+        }
+        ast::Expr::CastExpr(cast) => {
+            let path = match cast.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            let qualifier = path.qualifier()?;
+            let qualifier = qualifier.syntax().text();
+            if qualifier != "Self" && qualifier != enum_name.text().as_str() {
+                return None;
+            }
+            let variant = path.segment()?.name_ref()?;
+            Some(format!("Self::{}.bits", to_upper_snake_case(variant.text())))
+        }
+        ast::Expr::ParenExpr(it) => Some(format!("({})", flag_value(enum_name, &it.expr()?)?)),
+        _ => None,
+    }
+}
+
+fn parse_int(text: &str) -> Option<u128> {
+    let text = text.replace('_', "");
+    let (digits, radix) = if let Some(it) = text.strip_prefix("0x") {
+        (it, 16)
+    } else if let Some(it) = text.strip_prefix("0b") {
+        (it, 2)
+    } else if let Some(it) = text.strip_prefix("0o") {
+        (it, 8)
+    } else {
+        (text.as_str(), 10)
+    };
+    // Strips a suffix, like `u8`.
+    let digits = match INT_TYPES.iter().find(|it| digits.ends_with(*it)) {
+        Some(suffix) if radix != 16 => &digits[..digits.len() - suffix.len()],
+        _ => digits,
+    };
+    u128::from_str_radix(digits, radix).ok()
+}
+
+/// Returns the attributes and doc comments of an item, each on its own line.
+fn leading_attrs(children: impl Iterator<Item = SyntaxElement>) -> Vec<String> {
+    children
+        .filter(|it| it.kind() == ATTR || it.kind() == COMMENT)
+        .map(|it| it.to_string())
+        .collect()
+}
+
+/// Drops `#[repr]` and the derives of `bitflags!`.
+fn convert_attr(attr: &str) -> Option<String> {
+    if attr.starts_with("#[repr(") {
+        return None;
+    }
+    let derives = match attr.strip_prefix("#[derive(").and_then(|it| it.strip_suffix(")]")) {
+        Some(it) => it,
+        None => return Some(attr.to_string()),
+    };
+    let derives: Vec<&str> = derives
+        .split(',')
+        .map(|it| it.trim())
+        .filter(|it| !it.is_empty() && !BITFLAGS_DERIVES.contains(it))
+        .collect();
+    if derives.is_empty() {
+        None
+    } else {
+        Some(format!("#[derive({})]", derives.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_with_usages() {
+        check_assist(
+            convert_enum_to_bitflags,
+            r"
+//- /main.rs crate:main deps:bitflags
+enum Access<|> { ReadOnly = 1, Admin = 0x80 }
+fn is_admin(access: Access) -> bool { access.contains(Access::Admin) }
+//- /lib.rs crate:bitflags
+",
+            r"bitflags::bitflags! {
+    struct Access: u32 {
+        const READ_ONLY = 1;
+        const ADMIN = 0x80;
+    }
+}
+fn is_admin(access: Access) -> bool { access.contains(Access::ADMIN) }
+",
+        );
+    }
+
+    #[test]
+    fn convert_in_module() {
+        check_assist(
+            convert_enum_to_bitflags,
+            r"
+//- /main.rs crate:main deps:bitflags
+mod fs {
+    /// File modes.
+    #[derive(Clone, Copy, Debug, Default)]
+    #[repr(u32)]
+    pub(crate) enum Mode<|> {
+        /// Readable.
+        Read = 0b001,
+        Write = 1 << 1,
+        ReadWrite = Mode::Read as u32 | Self::Write as u32,
+    }
+}
+//- /lib.rs crate:bitflags
+",
+            r"mod fs {
+    bitflags::bitflags! {
+        /// File modes.
+        #[derive(Default)]
+        pub(crate) struct Mode: u32 {
+            /// Readable.
+            const READ = 0b001;
+            const WRITE = 1 << 1;
+            const READ_WRITE = Self::READ.bits | Self::WRITE.bits;
+        }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Not a power of two.
+        check_assist_not_applicable(
+            convert_enum_to_bitflags,
+            r"
+//- /main.rs crate:main deps:bitflags
+enum Flags<|> { A = 1, B = 3 }
+//- /lib.rs crate:bitflags
+",
+        );
+        // Without `bitflags`.
+        check_assist_not_applicable(convert_enum_to_bitflags, "enum Flags<|> { A = 1, B = 2 }");
+        check_assist_not_applicable(
+            convert_enum_to_bitflags,
+            r"
+//- /main.rs crate:main deps:bitflags
+enum Flags<|> { A = 1, B }
+//- /lib.rs crate:bitflags
+",
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use hir::Module;
+use ra_syntax::ast::{self, AstNode, PathSegmentKind};
+use stdx::SepBy;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: convert_to_relative_import
+//
+// Converts a `crate::` import to an import relative to the current module.
+//
+// ```
+// mod net {
+//     mod http {
+//         use crate::net::tcp::<|>Stream;
+//     }
+//     mod tcp { pub struct Stream; }
+// }
+// ```
+// ->
+// ```
+// mod net {
+//     mod http {
+//         use super::tcp::Stream;
+//     }
+//     mod tcp { pub struct Stream; }
+// }
+// ```
+pub(crate) fn convert_to_relative_import(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (use_tree, path, segments) = use_tree_at_cursor(ctx)?;
+    if segments.first()?.kind()? != PathSegmentKind::CrateKw {
+        return None;
+    }
+    let module = ctx.sema.scope(use_tree.syntax()).module()?;
+    let module_path = module_names(ctx, module)?;
+
+    // `use super;` isn't valid, so a plain import keeps at least one segment.
+    let rest = &segments[1..];
+    let max_common = if has_tail(&use_tree) { rest.len() } else { rest.len().checked_sub(1)? };
+    let common = module_path
+        .iter()
+        .zip(rest)
+        .take(max_common)
+        .take_while(|(name, segment)| segment.syntax().text() == name.as_str())
+        .count();
+    let supers = module_path.len() - common;
+    let prefix = if supers == 0 { vec!["self"] } else { vec!["super"; supers] };
+    let new_path = prefix
+        .into_iter()
+        .map(String::from)
+        .chain(rest[common..].iter().map(|it| it.syntax().to_string()))
+        .sep_by("::")
+        .to_string();
+
+    let target = path.syntax().text_range();
+    acc.add(
+        AssistId("convert_to_relative_import"),
+        format!("Convert to `{}`", new_path),
+        target,
+        |builder| builder.replace(target, new_path),
+    )
+}
+
+// Assist: convert_to_crate_import
+//
+// Converts an import relative to the current module to a `crate::` import.
+//
+// ```
+// mod net {
+//     mod http {
+//         use super::tcp::<|>Stream;
+//     }
+//     mod tcp { pub struct Stream; }
+// }
+// ```
+// ->
+// ```
+// mod net {
+//     mod http {
+//         use crate::net::tcp::Stream;
+//     }
+//     mod tcp { pub struct Stream; }
+// }
+// ```
+pub(crate) fn convert_to_crate_import(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (use_tree, path, segments) = use_tree_at_cursor(ctx)?;
+    let mut module = ctx.sema.scope(use_tree.syntax()).module()?;
+    let mut relative = 0;
+    for (idx, segment) in segments.iter().enumerate() {
+        match segment.kind()? {
+            PathSegmentKind::SuperKw => module = module.parent(ctx.db)?,
+            PathSegmentKind::SelfKw if idx == 0 => (),
+            _ => break,
+        }
+        relative += 1;
+    }
+    if relative == 0 || (relative == segments.len() && !has_tail(&use_tree)) {
+        return None;
+    }
+
+    let new_path = Some("crate".to_string())
+        .into_iter()
+        .chain(module_names(ctx, module)?)
+        .chain(segments[relative..].iter().map(|it| it.syntax().to_string()))
+        .sep_by("::")
+        .to_string();
+
+    let target = path.syntax().text_range();
+    acc.add(
+        AssistId("convert_to_crate_import"),
+        format!("Convert to `{}`", new_path),
+        target,
+        |builder| builder.replace(target, new_path),
+    )
+}
+
+/// Returns the outermost use tree of the `use` item at the cursor, together
+/// with its path and the segments of the path.
+fn use_tree_at_cursor(
+    ctx: &AssistContext,
+) -> Option<(ast::UseTree, ast::Path, Vec<ast::PathSegment>)> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    let use_tree = use_item.use_tree()?;
+    let path = use_tree.path()?;
+    let mut segments = Vec::new();
+    let mut current = Some(path.clone());
+    while let Some(it) = current {
+        segments.push(it.segment()?);
+        current = it.qualifier();
+    }
+    segments.reverse();
+    Some((use_tree, path, segments))
+}
+
+/// Checks whether the path of `use_tree` is followed by `::{...}` or `::*`.
+fn has_tail(use_tree: &ast::UseTree) -> bool {
+    use_tree.use_tree_list().is_some() || use_tree.star_token().is_some()
+}
+
+/// Returns the names of the modules from the crate root to `module`.
+fn module_names(ctx: &AssistContext, module: Module) -> Option<Vec<String>> {
+    let mut path = module.path_to_root(ctx.db);
+    path.pop();
+    path.into_iter().rev().map(|it| Some(it.name(ctx.db)?.to_string())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn convert_crate_import_to_self() {
+        check_assist(
+            convert_to_relative_import,
+            r#"
+//- /main.rs
+mod a;
+//- /a.rs
+use crate::a::b::{<|>c, d};
+mod b {}"#,
+            "use self::b::{c, d};\nmod b {}\n",
+        );
+    }
+
+    #[test]
+    fn convert_crate_import_to_super() {
+        check_assist(
+            convert_to_relative_import,
+            r#"
+mod a {
+    mod b {
+        use crate::<|>a;
+    }
+}
+"#,
+            r#"
+mod a {
+    mod b {
+        use super::super::a;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_super_import_to_crate() {
+        check_assist(
+            convert_to_crate_import,
+            r#"
+mod a {
+    mod b {
+        use super::super::{<|>x, y};
+    }
+}
+"#,
+            r#"
+mod a {
+    mod b {
+        use crate::{x, y};
+    }
+}
+"#,
+        );
+        check_assist(
+            convert_to_crate_import,
+            "mod a { use self::b::<|>C as D; mod b {} }",
+            "mod a { use crate::a::b::C as D; mod b {} }",
+        );
+        check_assist(
+            convert_to_crate_import,
+            "mod a { use <|>super::*; }",
+            "mod a { use crate::*; }",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(convert_to_relative_import, "mod a { use std::<|>fmt; }");
+        check_assist_not_applicable(convert_to_crate_import, "mod a { use crate::<|>b; }");
+        check_assist_not_applicable(convert_to_crate_import, "use super::<|>b;");
+        check_assist_not_applicable(convert_to_crate_import, "mod a { use <|>super; }");
+    }
+
+    #[test]
+    fn convert_import_path_target() {
+        check_assist_target(
+            convert_to_relative_import,
+            "mod a { use crate::a::{<|>b, c}; }",
+            "crate::a",
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::{COMMA, L_CURLY, L_PAREN, R_CURLY, R_PAREN},
+    TextRange,
+};
+use stdx::SepBy;
+
+use crate::{utils::is_postfix_receiver, AssistContext, AssistId, Assists};
+
+// Assist: convert_join_to_sequential_awaits
+//
+// Converts a `join!` of futures into awaiting the futures one after another.
+// Note that the futures no longer run concurrently after the conversion.
+//
+// ```
+// async fn load() {
+//     let (config, user) = futures::<|>join!(read_config(), fetch_user());
+// }
+// ```
+// ->
+// ```
+// async fn load() {
+//     let config = read_config().await;
+//     let user = fetch_user().await;
+// }
+// ```
+pub(crate) fn convert_join_to_sequential_awaits(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let macro_call = ctx.find_node_at_offset::<ast::MacroCall>()?;
+    let path = macro_call.path()?;
+    if path.segment()?.name_ref()?.text() != "join" {
+        return None;
+    }
+    let args = join_args(&macro_call.token_tree()?)?;
+    if args.is_empty() {
+        return None;
+    }
+    let awaited: Vec<String> = args
+        .iter()
+        .map(|it| {
+            if is_postfix_receiver(it) {
+                format!("{}.await", it)
+            } else {
+                format!("({}).await", it)
+            }
+        })
+        .collect();
+
+    let (target, replacement) = match macro_call.syntax().parent().and_then(ast::LetStmt::cast) {
+        Some(let_stmt) => {
+            if let_stmt.ascribed_type().is_some() {
+                return None;
+            }
+            let indent = IndentLevel::from_node(let_stmt.syntax());
+            let replacement = match let_stmt.pat()? {
+                ast::Pat::TuplePat(tuple) if tuple.args().count() == awaited.len() => tuple
+                    .args()
+                    .zip(&awaited)
+                    .map(|(pat, expr)| format!("let {} = {};", pat, expr))
+                    .sep_by(&format!("\n{}", indent))
+                    .to_string(),
+                pat => format!("let {} = ({});", pat, awaited.iter().sep_by(", ")),
+            };
+            (let_stmt.syntax().text_range(), replacement)
+        }
+        None => {
+            let stmt = macro_call.syntax().parent().and_then(ast::ExprStmt::cast)?;
+            let indent = IndentLevel::from_node(stmt.syntax());
+            let replacement = awaited
+                .iter()
+                .map(|it| format!("let _ = {};", it))
+                .sep_by(&format!("\n{}", indent))
+                .to_string();
+            (stmt.syntax().text_range(), replacement)
+        }
+    };
+
+    acc.add(
+        AssistId("convert_join_to_sequential_awaits"),
+        "Convert `join!` to sequential awaits (futures will no longer run concurrently)",
+        macro_call.syntax().text_range(),
+        |builder| builder.replace(target, replacement),
+    )
+}
+
+// Assist: convert_awaits_to_join
+//
+// Converts the selected sequential awaits, which don't depend on each other,
+// into a `join!`, so that the futures run concurrently.
+//
+// ```
+// async fn load() {
+//     <|>let config = read_config().await;
+//     fetch_user().await;<|>
+// }
+// ```
+// ->
+// ```
+// async fn load() {
+//     let (config, _) = futures::join!(read_config(), fetch_user());
+// }
+// ```
+pub(crate) fn convert_awaits_to_join(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let range = ctx.frange.range;
+    if range.is_empty() {
+        return None;
+    }
+    let block = ctx.covering_element().ancestors().find_map(ast::BlockExpr::cast)?;
+    let stmts: Vec<ast::Stmt> =
+        block.statements().filter(|it| range.contains_range(it.syntax().text_range())).collect();
+    if stmts.len() < 2 {
+        return None;
+    }
+
+    let mut pats = Vec::new();
+    let mut futures = Vec::new();
+    let mut bound_names: Vec<String> = Vec::new();
+    for stmt in &stmts {
+        let (pat, expr) = match stmt {
+            ast::Stmt::LetStmt(it) if it.ascribed_type().is_none() => {
+                (Some(it.pat()?), it.initializer()?)
+            }
+            ast::Stmt::ExprStmt(it) => (None, it.expr()?),
+            _ => return None,
+        };
+        let future = match expr {
+            ast::Expr::AwaitExpr(it) => it.expr()?,
+            _ => return None,
+        };
+        // A future, which uses the result of an earlier one, can't run concurrently with it.
+        let depends_on_earlier = future
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .any(|it| bound_names.contains(&it.text().to_string()));
+        if depends_on_earlier {
+            return None;
+        }
+        if let Some(pat) = &pat {
+            bound_names.extend(
+                pat.syntax()
+                    .descendants()
+                    .filter_map(ast::BindPat::cast)
+                    .filter_map(|it| it.name())
+                    .map(|it| it.text().to_string()),
+            );
+        }
+        pats.push(pat);
+        futures.push(future);
+    }
+
+    let join = format!("futures::join!({})", futures.iter().sep_by(", "));
+    let replacement = if pats.iter().all(Option::is_none) {
+        format!("{};", join)
+    } else {
+        let pats = pats.iter().map(|it| match it {
+            Some(pat) => pat.to_string(),
+            None => "_".to_string(),
+        });
+        format!("let ({}) = {};", pats.sep_by(", "), join)
+    };
+
+    let target = TextRange::new(
+        stmts.first()?.syntax().text_range().start(),
+        stmts.last()?.syntax().text_range().end(),
+    );
+    acc.add(
+        AssistId("convert_awaits_to_join"),
+        "Convert sequential awaits to `join!`",
+        target,
+        |builder| builder.replace(target, replacement),
+    )
+}
+
+/// Splits the arguments of a `join!` call at the top-level commas.
+fn join_args(tt: &ast::TokenTree) -> Option<Vec<ast::Expr>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    for it in tt.syntax().children_with_tokens() {
+        match it.kind() {
+            L_PAREN | R_PAREN | L_CURLY | R_CURLY => (),
+            COMMA => args.push(std::mem::take(&mut current)),
+            _ => current.push_str(&it.to_string()),
+        }
+    }
+    args.push(current);
+    if args.last().map_or(false, |it| it.trim().is_empty()) {
+        args.pop();
+    }
+    args.iter().map(|it| make::try_expr_from_text(it.trim())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn join_statement_to_awaits() {
+        check_assist(
+            convert_join_to_sequential_awaits,
+            r#"
+async fn run(a: Task, b: Task) {
+    if true {
+        <|>join!(a.start(), async { b.start().await }, );
+    }
+}
+"#,
+            r#"
+async fn run(a: Task, b: Task) {
+    if true {
+        let _ = a.start().await;
+        let _ = (async { b.start().await }).await;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn join_with_single_pattern_to_awaits() {
+        check_assist(
+            convert_join_to_sequential_awaits,
+            "async fn f() { let pair = tokio::join!<|>(one(), two()); }",
+            "async fn f() { let pair = (one().await, two().await); }",
+        );
+    }
+
+    #[test]
+    fn awaits_to_join() {
+        check_assist(
+            convert_awaits_to_join,
+            r#"
+async fn run(db: &Db) {
+    let user = db.user().await;
+    <|>let (posts, count) = db.posts(1).await;
+    db.ping().await;
+    let tags = db.tags(2).await;<|>
+}
+"#,
+            r#"
+async fn run(db: &Db) {
+    let user = db.user().await;
+    let ((posts, count), _, tags) = futures::join!(db.posts(1), db.ping(), db.tags(2));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn bare_awaits_to_join() {
+        check_assist(
+            convert_awaits_to_join,
+            "async fn f() { <|>a().await; b().await;<|> }",
+            "async fn f() { futures::join!(a(), b()); }",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            convert_join_to_sequential_awaits,
+            "async fn f() { <|>select!(a(), b()); }",
+        );
+        // The second future depends on the result of the first one.
+        check_assist_not_applicable(
+            convert_awaits_to_join,
+            "async fn f() { <|>let id = a().await; b(id).await;<|> }",
+        );
+        check_assist_not_applicable(
+            convert_awaits_to_join,
+            "async fn f() { <|>let x = a().await; b();<|> }",
+        );
+        check_assist_not_applicable(convert_awaits_to_join, "async fn f() { <|>a().await;<|> }");
+    }
+
+    #[test]
+    fn convert_join_target() {
+        check_assist_target(
+            convert_join_to_sequential_awaits,
+            "async fn f() { let (x, y) = join!(a()<|>, b()); }",
+            "join!(a(), b())",
+        );
+    }
+}
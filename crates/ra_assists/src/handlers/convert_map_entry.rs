@@ -0,0 +1,359 @@
+use ra_syntax::ast::{self, edit::IndentLevel, ArgListOwner, AstNode};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: convert_entry_to_contains_key
+//
+// Converts an insertion with the `entry` API to an explicit `contains_key`
+// check followed by an `insert`.
+//
+// ```
+// struct HashMap<K, V>(K, V);
+// fn main() {
+//     let mut map: HashMap<u32, u32>;
+//     map.entry(1).<|>or_insert(92);
+// }
+// ```
+// ->
+// ```
+// struct HashMap<K, V>(K, V);
+// fn main() {
+//     let mut map: HashMap<u32, u32>;
+//     if !map.contains_key(&1) {
+//         map.insert(1, 92);
+//     }
+// }
+// ```
+pub(crate) fn convert_entry_to_contains_key(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let stmt = ctx.find_node_at_offset::<ast::ExprStmt>()?;
+    let or_insert = match stmt.expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    let entry = match or_insert.expr()? {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if entry.name_ref()?.text() != "entry" {
+        return None;
+    }
+    let map = entry.expr()?;
+    if !is_map(ctx, &map) {
+        return None;
+    }
+    let key = single_arg(&entry)?;
+    let value = match or_insert.name_ref()?.text().as_str() {
+        "or_insert" => single_arg(&or_insert)?.to_string(),
+        "or_insert_with" => match single_arg(&or_insert)? {
+            ast::Expr::LambdaExpr(lambda) if lambda.param_list()?.params().next().is_none() => {
+                lambda.body()?.to_string()
+            }
+            it => format!("{}()", it),
+        },
+        "or_default" => {
+            if or_insert.arg_list()?.args().next().is_some() {
+                return None;
+            }
+            "Default::default()".to_string()
+        }
+        _ => return None,
+    };
+
+    let target = stmt.syntax().text_range();
+    acc.add(
+        AssistId("convert_entry_to_contains_key"),
+        "Convert `entry` to `contains_key` and `insert`",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(stmt.syntax());
+            let borrowed_key = match &key {
+                ast::Expr::RefExpr(_) => key.to_string(),
+                _ => format!("&{}", key),
+            };
+            let text = format!(
+                "if !{0}.contains_key({1}) {{\n{3}    {0}.insert({2}, {4});\n{3}}}",
+                map, borrowed_key, key, indent, value
+            );
+            builder.replace(target, text);
+        },
+    )
+}
+
+// Assist: convert_contains_key_to_entry
+//
+// Converts a `contains_key` check followed by an `insert` to the `entry` API.
+//
+// ```
+// struct HashMap<K, V>(K, V);
+// fn main() {
+//     let mut map: HashMap<u32, u32>;
+//     <|>if !map.contains_key(&1) {
+//         map.insert(1, 92);
+//     }
+// }
+// ```
+// ->
+// ```
+// struct HashMap<K, V>(K, V);
+// fn main() {
+//     let mut map: HashMap<u32, u32>;
+//     map.entry(1).or_insert(92);
+// }
+// ```
+pub(crate) fn convert_contains_key_to_entry(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let if_expr = ctx.find_node_at_offset::<ast::IfExpr>()?;
+    // Only offer the assist on the `if` itself, not in its body.
+    let then_branch = if_expr.then_branch()?;
+    if then_branch.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let cond = if_expr.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let contains_key = match cond.expr()? {
+        ast::Expr::PrefixExpr(it) if it.op_kind() == Some(ast::PrefixOp::Not) => match it.expr()? {
+            ast::Expr::MethodCallExpr(it) => it,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if contains_key.name_ref()?.text() != "contains_key" {
+        return None;
+    }
+    let map = contains_key.expr()?;
+    if !is_map(ctx, &map) {
+        return None;
+    }
+    let checked_key = match single_arg(&contains_key)? {
+        ast::Expr::RefExpr(it) => it.expr()?,
+        it => it,
+    };
+
+    let insert = match (then_branch.statements().next(), then_branch.expr()) {
+        (Some(ast::Stmt::ExprStmt(stmt)), None) if then_branch.statements().count() == 1 => {
+            stmt.expr()?
+        }
+        (None, Some(expr)) => expr,
+        _ => return None,
+    };
+    let insert = match insert {
+        ast::Expr::MethodCallExpr(it) => it,
+        _ => return None,
+    };
+    if insert.name_ref()?.text() != "insert"
+        || insert.expr()?.syntax().text() != map.syntax().text()
+    {
+        return None;
+    }
+    let mut args = insert.arg_list()?.args();
+    let key = args.next()?;
+    let value = args.next()?;
+    if args.next().is_some() || key.syntax().text() != checked_key.syntax().text() {
+        return None;
+    }
+
+    let target = if_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_contains_key_to_entry"),
+        "Convert `contains_key` and `insert` to `entry`",
+        target,
+        |builder| {
+            // Keep the value lazily evaluated, unless it is trivial.
+            let or_insert = match value {
+                ast::Expr::Literal(_) | ast::Expr::PathExpr(_) => format!("or_insert({})", value),
+                _ => format!("or_insert_with(|| {})", value),
+            };
+            let has_semicolon = if_expr
+                .syntax()
+                .parent()
+                .and_then(ast::ExprStmt::cast)
+                .map_or(false, |it| it.semicolon_token().is_some());
+            let semicolon = if has_semicolon { "" } else { ";" };
+            builder.replace(target, format!("{}.entry({}).{}{}", map, key, or_insert, semicolon));
+        },
+    )
+}
+
+fn is_map(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    let adt = match ctx.sema.type_of_expr(expr).and_then(|it| it.autoderef(ctx.db).last()) {
+        Some(ty) => ty.as_adt(),
+        None => None,
+    };
+    adt.map_or(false, |adt| {
+        let name = adt.name(ctx.db).to_string();
+        name == "HashMap" || name == "BTreeMap"
+    })
+}
+
+fn single_arg(call: &ast::MethodCallExpr) -> Option<ast::Expr> {
+    let mut args = call.arg_list()?.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(arg)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn entry_to_contains_key() {
+        check_assist(
+            convert_entry_to_contains_key,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(map: &mut HashMap<String, Vec<u32>>, key: String) {
+    if true {
+        map.entry(key).<|>or_insert_with(|| Vec::new());
+    }
+}",
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(map: &mut HashMap<String, Vec<u32>>, key: String) {
+    if true {
+        if !map.contains_key(&key) {
+            map.insert(key, Vec::new());
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn entry_or_default_to_contains_key() {
+        check_assist(
+            convert_entry_to_contains_key,
+            r#"
+struct BTreeMap<K, V>(K, V);
+fn foo(mut map: BTreeMap<&str, u32>) {
+    <|>map.entry("a").or_default();
+}"#,
+            r#"
+struct BTreeMap<K, V>(K, V);
+fn foo(mut map: BTreeMap<&str, u32>) {
+    if !map.contains_key(&"a") {
+        map.insert("a", Default::default());
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn entry_to_contains_key_not_applicable() {
+        // The returned reference is used.
+        check_assist_not_applicable(
+            convert_entry_to_contains_key,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>) {
+    let value = map.entry(1).<|>or_insert(0);
+}",
+        );
+        check_assist_not_applicable(
+            convert_entry_to_contains_key,
+            r"
+struct Cache<K, V>(K, V);
+fn foo(mut map: Cache<u32, u32>) {
+    map.entry(1).<|>or_insert(0);
+}",
+        );
+    }
+
+    #[test]
+    fn contains_key_to_entry() {
+        check_assist(
+            convert_contains_key_to_entry,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(map: &mut HashMap<String, Vec<u32>>, key: String) {
+    i<|>f !map.contains_key(&key) {
+        map.insert(key, Vec::new())
+    }
+    map.len();
+}",
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(map: &mut HashMap<String, Vec<u32>>, key: String) {
+    map.entry(key).or_insert_with(|| Vec::new());
+    map.len();
+}",
+        );
+    }
+
+    #[test]
+    fn contains_key_to_entry_with_trivial_value() {
+        check_assist(
+            convert_contains_key_to_entry,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>, value: u32) {
+    <|>if !map.contains_key(&1) {
+        map.insert(1, value);
+    };
+}",
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>, value: u32) {
+    map.entry(1).or_insert(value);
+}",
+        );
+    }
+
+    #[test]
+    fn contains_key_to_entry_not_applicable() {
+        // Different keys.
+        check_assist_not_applicable(
+            convert_contains_key_to_entry,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>) {
+    <|>if !map.contains_key(&1) {
+        map.insert(2, 0);
+    }
+}",
+        );
+        // More than the insertion.
+        check_assist_not_applicable(
+            convert_contains_key_to_entry,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>) {
+    <|>if !map.contains_key(&1) {
+        map.insert(1, 0);
+        println();
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_contains_key_to_entry,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>) {
+    <|>if map.contains_key(&1) {
+        map.insert(1, 0);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn contains_key_to_entry_target() {
+        check_assist_target(
+            convert_contains_key_to_entry,
+            r"
+struct HashMap<K, V>(K, V);
+fn foo(mut map: HashMap<u32, u32>) {
+    <|>if !map.contains_key(&1) { map.insert(1, 0); }
+}",
+            "if !map.contains_key(&1) { map.insert(1, 0); }",
+        );
+    }
+}
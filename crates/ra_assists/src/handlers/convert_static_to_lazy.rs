@@ -0,0 +1,192 @@
+use hir::{HasSource, PathResolution};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeAscriptionOwner},
+    TextSize,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: convert_static_to_lazy
+//
+// Initializes a `static` lazily, if its initializer can't be evaluated at
+// compile time. Uses `once_cell` or `lazy_static`, whichever is a dependency
+// of the crate.
+//
+// ```
+// # //- /main.rs crate:main deps:once_cell
+// fn default_names() -> Vec<String> { Vec::new() }
+// static NAMES<|>: Vec<String> = default_names();
+// # //- /lib.rs crate:once_cell
+// ```
+// ->
+// ```
+// fn default_names() -> Vec<String> { Vec::new() }
+// static NAMES: once_cell::sync::Lazy<Vec<String>> = once_cell::sync::Lazy::new(|| default_names());
+// ```
+pub(crate) fn convert_static_to_lazy(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let static_def = ctx.find_node_at_offset::<ast::StaticDef>()?;
+    let name = static_def.name()?;
+    if static_def.mut_token().is_some() {
+        return None;
+    }
+    let ty = static_def.ascribed_type()?;
+    let body = static_def.body()?;
+    if body.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    if !needs_runtime_init(ctx, &body) {
+        return None;
+    }
+
+    let krate = ctx.sema.to_def(&static_def)?.module(ctx.db).krate();
+    let has_dependency =
+        |name: &str| krate.dependencies(ctx.db).into_iter().any(|dep| dep.name.to_string() == name);
+    let target = static_def.syntax().text_range();
+    if has_dependency("once_cell") {
+        return acc.add(
+            AssistId("convert_static_to_lazy"),
+            format!("Initialize `{}` lazily with `once_cell`", name),
+            target,
+            |builder| {
+                builder.replace(ty.syntax().text_range(), format!("once_cell::sync::Lazy<{}>", ty));
+                builder.replace(
+                    body.syntax().text_range(),
+                    format!("once_cell::sync::Lazy::new(|| {})", body),
+                );
+            },
+        );
+    }
+    if has_dependency("lazy_static") {
+        return acc.add(
+            AssistId("convert_static_to_lazy"),
+            format!("Initialize `{}` lazily with `lazy_static!`", name),
+            target,
+            |builder| {
+                let mut text = static_def.syntax().text().to_string();
+                let static_end = static_def
+                    .static_token()
+                    .map_or(TextSize::from(0), |it| it.text_range().end() - target.start());
+                text.insert_str(static_end.into(), " ref");
+                let indent = IndentLevel::from_node(static_def.syntax());
+                let inner_indent = indent + 1;
+                let text = text.replace('\n', &format!("\n{}", IndentLevel(1)));
+                builder.replace(
+                    target,
+                    format!("lazy_static::lazy_static! {{\n{}{}\n{}}}", inner_indent, text, indent),
+                );
+            },
+        );
+    }
+    None
+}
+
+/// Checks whether `expr` calls a non-`const` function or a macro. Calls which
+/// can't be resolved are assumed to be non-`const`.
+fn needs_runtime_init(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    expr.syntax().descendants().any(|node| {
+        if ast::MacroCall::can_cast(node.kind()) {
+            return true;
+        }
+        let function = if let Some(call) = ast::MethodCallExpr::cast(node.clone()) {
+            match ctx.sema.resolve_method_call(&call) {
+                Some(it) => it,
+                None => return true,
+            }
+        } else if let Some(call) = ast::CallExpr::cast(node) {
+            let path = match call.expr() {
+                Some(ast::Expr::PathExpr(it)) => it.path(),
+                _ => return true,
+            };
+            match path.and_then(|it| ctx.sema.resolve_path(&it)) {
+                Some(PathResolution::Def(hir::ModuleDef::Function(it))) => it,
+                // Tuple structs and enum variants.
+                Some(PathResolution::Def(_)) => return false,
+                _ => return true,
+            }
+        } else {
+            return false;
+        };
+        function.source(ctx.db).value.const_token().is_none()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn convert_to_once_cell() {
+        check_assist(
+            convert_static_to_lazy,
+            r"
+//- /main.rs crate:main deps:once_cell,lazy_static
+struct Registry;
+impl Registry {
+    fn new() -> Registry { Registry }
+}
+pub static <|>REGISTRY: Registry = Registry::new();
+//- /once_cell.rs crate:once_cell
+//- /lazy_static.rs crate:lazy_static
+",
+            r"struct Registry;
+impl Registry {
+    fn new() -> Registry { Registry }
+}
+pub static REGISTRY: once_cell::sync::Lazy<Registry> = once_cell::sync::Lazy::new(|| Registry::new());
+",
+        );
+    }
+
+    #[test]
+    fn convert_to_lazy_static() {
+        check_assist(
+            convert_static_to_lazy,
+            r"
+//- /main.rs crate:main deps:lazy_static
+mod config {
+    /// The default values.
+    pub(crate) static DEFAULTS<|>: Vec<u32> = {
+        vec![1, 2, 3]
+    };
+}
+//- /lazy_static.rs crate:lazy_static
+",
+            r"mod config {
+    lazy_static::lazy_static! {
+        /// The default values.
+        pub(crate) static ref DEFAULTS: Vec<u32> = {
+            vec![1, 2, 3]
+        };
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_const_initializers() {
+        check_assist_not_applicable(
+            convert_static_to_lazy,
+            r"
+//- /main.rs crate:main deps:once_cell
+struct Limit(u32);
+const fn limit() -> u32 { 92 }
+static LIMIT<|>: Limit = Limit(limit());
+//- /once_cell.rs crate:once_cell
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_dependencies() {
+        check_assist_not_applicable(
+            convert_static_to_lazy,
+            r"
+fn names() -> Vec<String> { Vec::new() }
+static NAMES<|>: Vec<String> = names();
+",
+        );
+    }
+}
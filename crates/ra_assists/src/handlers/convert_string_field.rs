@@ -0,0 +1,262 @@
+use hir::{Adt, ModuleDef, ScopeDef};
+use ra_ide_db::search::ReferenceKind;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner},
+    TextRange,
+};
+
+use crate::{
+    utils::{field_usages, insert_use_statement, is_postfix_receiver, FamousDefs, FileEdits},
+    AssistContext, AssistId, Assists, GroupLabel,
+};
+
+// Assist: convert_string_field_to_shared
+//
+// Changes the type of a `String` field, which is never mutated, to `Arc<str>`
+// or `SmolStr`. Construction sites are updated to convert the value with
+// `.into()`. Only offered if the crate depends on `smol_str`.
+//
+// ```
+// # //- /main.rs crate:main deps:std,smol_str
+// struct Config { name: String<|> }
+// fn make(name: String) -> Config { Config { name } }
+// # //- /libstd.rs crate:std
+// # pub mod sync { pub struct Arc<T: ?Sized>(T); }
+// # //- /smol_str.rs crate:smol_str
+// # pub struct SmolStr;
+// ```
+// ->
+// ```
+// use std::sync::Arc;
+//
+// struct Config { name: Arc<str> }
+// fn make(name: String) -> Config { Config { name: name.into() } }
+// ```
+pub(crate) fn convert_string_field_to_shared(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let field_name = field.name()?;
+    let type_ref = field.ascribed_type()?;
+    if type_ref.syntax().text() != "String" {
+        return None;
+    }
+    let strukt = field.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let field_def = ctx.sema.to_def(&field)?;
+    let module = ctx.sema.scope(strukt.syntax()).module()?;
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let smol_str = famous_defs.smol_str_SmolStr()?;
+
+    let mut edits = FileEdits::default();
+    for (file_id, kind, name_ref) in field_usages(ctx, field_def) {
+        if kind == ReferenceKind::FieldShorthandForField {
+            let range = name_ref.syntax().text_range();
+            edits.add(file_id, range, format!("{0}: {0}.into()", name_ref));
+            continue;
+        }
+        let parent = name_ref.syntax().parent()?;
+        if let Some(record_field) = ast::RecordField::cast(parent.clone()) {
+            if let Some(expr) = record_field.expr() {
+                let range = expr.syntax().text_range();
+                let text = if is_postfix_receiver(&expr) {
+                    format!("{}.into()", expr)
+                } else {
+                    format!("({}).into()", expr)
+                };
+                edits.add(file_id, range, text);
+            }
+            continue;
+        }
+        if let Some(field_expr) = ast::FieldExpr::cast(parent) {
+            if is_mutated(&field_expr) {
+                return None;
+            }
+            if is_moved(&field_expr) {
+                let offset = field_expr.syntax().text_range().end();
+                edits.add(file_id, TextRange::empty(offset), ".to_string()");
+            }
+        }
+    }
+
+    let mut types = Vec::new();
+    if let Some(arc) = famous_defs.std_sync_Arc() {
+        types.push(("Arc<str>", "Arc", arc));
+    }
+    types.push(("SmolStr", "SmolStr", smol_str));
+
+    let group = GroupLabel(format!("Change `{}` to an immutable string", field_name));
+    let target = field.syntax().text_range();
+    for (ty, name, strukt_def) in types {
+        let in_scope = {
+            let mut res = false;
+            ctx.sema.scope(strukt.syntax()).process_all_names(&mut |it, def| {
+                if let ScopeDef::ModuleDef(ModuleDef::Adt(Adt::Struct(it_def))) = def {
+                    res |= it_def == strukt_def && it.to_string() == name;
+                }
+            });
+            res
+        };
+        let use_path = if in_scope {
+            None
+        } else {
+            Some(module.find_use_path(ctx.db, ModuleDef::Adt(strukt_def.into()))?)
+        };
+        let mut edits = edits.clone();
+        acc.add_group(
+            &group,
+            AssistId("convert_string_field_to_shared"),
+            format!("Change `{}` to `{}`", field_name, ty),
+            target,
+            |builder| {
+                edits.add(ctx.frange.file_id, type_ref.syntax().text_range(), ty);
+                edits.apply(builder, ctx.frange.file_id);
+                if let Some(use_path) = &use_path {
+                    insert_use_statement(
+                        strukt.syntax(),
+                        use_path,
+                        ctx,
+                        builder.text_edit_builder(),
+                    );
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+/// Checks whether `field_expr` is mutated in place, by a method of `String`,
+/// an assignment or a mutable borrow.
+fn is_mutated(field_expr: &ast::FieldExpr) -> bool {
+    const MUTATING_METHODS: &[&str] = &[
+        "as_mut_str",
+        "clear",
+        "drain",
+        "extend",
+        "insert",
+        "insert_str",
+        "make_ascii_lowercase",
+        "make_ascii_uppercase",
+        "pop",
+        "push",
+        "push_str",
+        "remove",
+        "replace_range",
+        "reserve",
+        "retain",
+        "shrink_to_fit",
+        "split_off",
+        "truncate",
+    ];
+    let parent = match field_expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return call.name_ref().map_or(false, |it| MUTATING_METHODS.contains(&it.text().as_str()));
+    }
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        return ref_expr.mut_token().is_some();
+    }
+    if let Some(bin_expr) = ast::BinExpr::cast(parent) {
+        let is_lhs = bin_expr.lhs().map_or(false, |it| it.syntax() == field_expr.syntax());
+        return is_lhs && bin_expr.op_kind().map_or(false, |it| it.is_assignment());
+    }
+    false
+}
+
+/// Checks whether the `String` is moved out of `field_expr`.
+fn is_moved(field_expr: &ast::FieldExpr) -> bool {
+    field_expr.syntax().parent().map_or(false, |parent| {
+        ast::LetStmt::can_cast(parent.kind())
+            || ast::ArgList::can_cast(parent.kind())
+            || ast::RecordField::can_cast(parent.kind())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const DEPS: &str = r"
+//- /libstd.rs crate:std
+pub mod sync {
+    pub struct Arc<T: ?Sized>(T);
+}
+//- /smol_str.rs crate:smol_str
+pub struct SmolStr;
+";
+
+    fn with_deps(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:std,smol_str\n{}{}", ra_fixture.trim_end(), DEPS)
+    }
+
+    #[test]
+    fn convert_to_smol_str() {
+        check_assist(
+            convert_string_field_to_shared,
+            &with_deps(
+                r#"
+struct User { name: <|>String, id: u32 }
+fn make(name: &str, id: u32) -> User { User { name: name.to_string(), id } }
+fn anonymous(id: u32) -> User { User { name: "anonymous".to_string() + "", id } }
+fn print(user: &User) {
+    let name = user.name;
+    println(user.name.len());
+}"#,
+            ),
+            r#"
+use smol_str::SmolStr;
+
+struct User { name: SmolStr, id: u32 }
+fn make(name: &str, id: u32) -> User { User { name: name.to_string().into(), id } }
+fn anonymous(id: u32) -> User { User { name: ("anonymous".to_string() + "").into(), id } }
+fn print(user: &User) {
+    let name = user.name.to_string();
+    println(user.name.len());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_mutated_fields() {
+        check_assist_not_applicable(
+            convert_string_field_to_shared,
+            &with_deps(
+                r#"
+struct User { name: <|>String }
+fn rename(user: &mut User) { user.name.push_str(" jr."); }"#,
+            ),
+        );
+        check_assist_not_applicable(
+            convert_string_field_to_shared,
+            &with_deps(
+                r"
+struct User { name: <|>String }
+fn rename(user: &mut User, name: String) { user.name = name; }",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_smol_str() {
+        check_assist_not_applicable(
+            convert_string_field_to_shared,
+            r"
+//- /main.rs crate:main deps:std
+struct User { name: <|>String }
+//- /libstd.rs crate:std
+pub mod sync { pub struct Arc<T: ?Sized>(T); }
+",
+        );
+    }
+
+    #[test]
+    fn convert_string_field_target() {
+        check_assist_target(
+            convert_string_field_to_shared,
+            &with_deps("struct User { name: <|>String }"),
+            "name: String",
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use hir::{Adt, HasSource, ModuleDef, ScopeDef};
+use ra_ide_db::defs::Definition;
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, ArgListOwner, AstNode, NameOwner, SelfParamKind, TypeAscriptionOwner},
+    SyntaxNode, TextRange,
+};
+
+use crate::{
+    utils::{insert_use_statement, is_postfix_receiver, FamousDefs, FileEdits},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: convert_string_param_to_cow
+//
+// Changes a `String` parameter to `Cow<str>`, so that callers can pass
+// borrowed strings without allocating. The parameter is converted back with
+// `.into_owned()` where the function needs a `String`, and the arguments are
+// converted with `.into()`.
+//
+// ```
+// # //- /main.rs crate:main deps:std
+// fn greet(name<|>: String) -> String {
+//     let greeting = name;
+//     greeting
+// }
+// fn main() { greet(String::new()); }
+// # //- /libstd.rs crate:std
+// # pub mod borrow { pub enum Cow<'a, B: ?Sized> { Borrowed(&'a B) } }
+// ```
+// ->
+// ```
+// use std::borrow::Cow;
+//
+// fn greet(name: Cow<str>) -> String {
+//     let greeting = name.into_owned();
+//     greeting
+// }
+// fn main() { greet(String::new().into()); }
+// ```
+pub(crate) fn convert_string_param_to_cow(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let param = ctx.find_node_at_offset::<ast::Param>()?;
+    let type_ref = param.ascribed_type()?;
+    if type_ref.syntax().text() != "String" {
+        return None;
+    }
+    let bind_pat = match param.pat()? {
+        ast::Pat::BindPat(it) if it.ref_token().is_none() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let param_list = param.syntax().parent().and_then(ast::ParamList::cast)?;
+    let index = param_list.params().position(|it| it == param)?;
+    let has_self = param_list.self_param().is_some();
+    let fn_def = param_list.syntax().parent().and_then(ast::FnDef::cast)?;
+    let function = ctx.sema.to_def(&fn_def)?;
+    let local = ctx.sema.to_def(&bind_pat)?;
+    let module = function.module(ctx.db);
+    let cow = FamousDefs(&ctx.sema, module.krate()).std_borrow_Cow()?;
+    let cow_in_scope = |node: &SyntaxNode| {
+        let mut res = false;
+        ctx.sema.scope(node).process_all_names(&mut |name, def| {
+            if let ScopeDef::ModuleDef(ModuleDef::Adt(Adt::Enum(it))) = def {
+                res |= it == cow && name.to_string() == "Cow";
+            }
+        });
+        res
+    };
+    let cow_path = if cow_in_scope(fn_def.syntax()) {
+        None
+    } else {
+        Some(module.find_use_path(ctx.db, ModuleDef::Adt(cow.into()))?)
+    };
+
+    let target = param.syntax().text_range();
+    acc.add(
+        AssistId("convert_string_param_to_cow"),
+        format!("Convert `{}` to `Cow<str>`", bind_pat.name()?),
+        target,
+        |builder| {
+            let mut edits = FileEdits::default();
+            for reference in Definition::Local(local).find_usages(ctx.db, None) {
+                let file_id = reference.file_range.file_id;
+                let source_file = ctx.sema.parse(file_id);
+                let path_expr = find_node_at_offset::<ast::NameRef>(
+                    source_file.syntax(),
+                    reference.file_range.range.start(),
+                )
+                .and_then(|it| it.syntax().ancestors().find_map(ast::PathExpr::cast));
+                let path_expr = match path_expr {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if let Some((range, text)) = body_usage_edit(ctx, &path_expr) {
+                    edits.add(file_id, range, text);
+                }
+            }
+
+            let usages =
+                Definition::ModuleDef(ModuleDef::Function(function)).find_usages(ctx.db, None);
+            for reference in usages {
+                let file_id = reference.file_range.file_id;
+                let source_file = ctx.sema.parse(file_id);
+                let name_ref = match find_node_at_offset::<ast::NameRef>(
+                    source_file.syntax(),
+                    reference.file_range.range.start(),
+                ) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let arg = match call_arg(&name_ref, index, has_self) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let is_borrowed = ctx.sema.type_of_expr(&arg).map_or(false, |it| it.is_reference());
+                let in_fn_module =
+                    ctx.sema.scope(arg.syntax()).module().map_or(false, |it| it == module);
+                let text = if is_borrowed && (in_fn_module || cow_in_scope(arg.syntax())) {
+                    format!("Cow::Borrowed({})", arg)
+                } else if is_postfix_receiver(&arg) || matches!(arg, ast::Expr::Literal(_)) {
+                    format!("{}.into()", arg)
+                } else {
+                    format!("({}).into()", arg)
+                };
+                edits.add(file_id, arg.syntax().text_range(), text);
+            }
+
+            edits.add(ctx.frange.file_id, type_ref.syntax().text_range(), "Cow<str>");
+            edits.apply(builder, ctx.frange.file_id);
+            if let Some(cow_path) = &cow_path {
+                insert_use_statement(fn_def.syntax(), cow_path, ctx, builder.text_edit_builder());
+            }
+        },
+    )
+}
+
+/// Converts a use of the parameter in the function body, which relies on it
+/// being a `String`.
+fn body_usage_edit(ctx: &AssistContext, path_expr: &ast::PathExpr) -> Option<(TextRange, String)> {
+    let end = TextRange::empty(path_expr.syntax().text_range().end());
+    let parent = path_expr.syntax().parent()?;
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        if ref_expr.mut_token().is_some() {
+            return Some((end, ".to_mut()".to_string()));
+        }
+        return Some((ref_expr.syntax().text_range(), format!("{}.as_ref()", path_expr)));
+    }
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        let self_param =
+            ctx.sema.resolve_method_call(&call)?.source(ctx.db).value.param_list()?.self_param()?;
+        // `str` methods are available through `Deref`.
+        return match self_param.kind() {
+            SelfParamKind::MutRef => Some((end, ".to_mut()".to_string())),
+            SelfParamKind::Owned => Some((end, ".into_owned()".to_string())),
+            SelfParamKind::Ref => None,
+        };
+    }
+    let is_moved = match ast::Expr::cast(parent.clone()) {
+        Some(ast::Expr::BinExpr(it)) => {
+            matches!(it.op_kind()?, ast::BinOp::Addition | ast::BinOp::AddAssign)
+                && it.lhs()?.syntax() == path_expr.syntax()
+        }
+        Some(ast::Expr::ReturnExpr(_)) | Some(ast::Expr::BlockExpr(_)) => true,
+        Some(_) => false,
+        None => {
+            ast::LetStmt::can_cast(parent.kind())
+                || ast::ArgList::can_cast(parent.kind())
+                || ast::RecordField::can_cast(parent.kind())
+        }
+    };
+    if is_moved {
+        Some((end, ".into_owned()".to_string()))
+    } else {
+        None
+    }
+}
+
+/// Returns the argument passed for the parameter at `index` to the call of
+/// the function referenced by `name_ref`.
+fn call_arg(name_ref: &ast::NameRef, index: usize, has_self: bool) -> Option<ast::Expr> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return call.arg_list()?.args().nth(index);
+    }
+    let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+    if call.expr()?.syntax() != path_expr.syntax() {
+        return None;
+    }
+    let index = if has_self { index + 1 } else { index };
+    call.arg_list()?.args().nth(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const STD: &str = r"
+//- /libstd.rs crate:std
+pub mod borrow {
+    pub enum Cow<'a, B: ?Sized> { Borrowed(&'a B) }
+}
+";
+
+    fn with_std(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:std\n{}{}", ra_fixture.trim_end(), STD)
+    }
+
+    #[test]
+    fn convert_param_and_call_sites() {
+        check_assist(
+            convert_string_param_to_cow,
+            &with_std(
+                r#"
+use std::borrow::Cow;
+struct String;
+struct Registry { names: Vec<String> }
+impl Registry {
+    fn register(&mut self, mut name<|>: String) -> usize {
+        name.push_str("!");
+        if name.is_empty() {
+            return 0;
+        }
+        print(&name);
+        self.names.push(name);
+        self.names.len()
+    }
+}
+fn print(s: &str) {}
+fn main() {
+    let mut registry = Registry { names: Vec::new() };
+    let name = "a";
+    registry.register(String::new());
+    Registry::register(&mut registry, format!("{}", 1));
+    registry.register(name);
+}
+impl String {
+    fn push_str(&mut self, s: &str) {}
+    fn is_empty(&self) -> bool { true }
+}"#,
+            ),
+            r#"
+use std::borrow::Cow;
+struct String;
+struct Registry { names: Vec<String> }
+impl Registry {
+    fn register(&mut self, mut name: Cow<str>) -> usize {
+        name.to_mut().push_str("!");
+        if name.is_empty() {
+            return 0;
+        }
+        print(name.as_ref());
+        self.names.push(name.into_owned());
+        self.names.len()
+    }
+}
+fn print(s: &str) {}
+fn main() {
+    let mut registry = Registry { names: Vec::new() };
+    let name = "a";
+    registry.register(String::new().into());
+    Registry::register(&mut registry, format!("{}", 1).into());
+    registry.register(Cow::Borrowed(name));
+}
+impl String {
+    fn push_str(&mut self, s: &str) {}
+    fn is_empty(&self) -> bool { true }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_param_adds_import() {
+        check_assist(
+            convert_string_param_to_cow,
+            &with_std(
+                "struct String;\nfn len(s<|>: String) -> String { s }\nfn main() { len(\"x\"); }",
+            ),
+            r#"use std::borrow::Cow;
+
+struct String;
+fn len(s: Cow<str>) -> String { s.into_owned() }
+fn main() { len(Cow::Borrowed("x")); }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            convert_string_param_to_cow,
+            &with_std("fn len(s<|>: &str) -> usize { 0 }"),
+        );
+        check_assist_not_applicable(convert_string_param_to_cow, "fn len(s<|>: String) {}");
+    }
+
+    #[test]
+    fn convert_string_param_to_cow_target() {
+        check_assist_target(
+            convert_string_param_to_cow,
+            &with_std("fn len(s<|>: String, n: usize) {}"),
+            "s: String",
+        );
+    }
+}
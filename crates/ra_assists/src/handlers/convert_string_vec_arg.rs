@@ -0,0 +1,203 @@
+use hir::{CallableDef, Function, HasSource, HirDisplay};
+use ra_syntax::ast::{self, AstNode, TypeAscriptionOwner};
+
+use crate::{utils::is_postfix_receiver, AssistContext, AssistId, Assists};
+
+// Assist: convert_string_vec_arg
+//
+// Converts an argument between `Vec<&str>` and `Vec<String>`, when the
+// called function expects the other one.
+//
+// ```
+// # struct String;
+// # struct Vec<T>(T);
+// fn spawn(args: Vec<String>) {}
+// fn run(args: Vec<&str>) {
+//     spawn(<|>args);
+// }
+// ```
+// ->
+// ```
+// # struct String;
+// # struct Vec<T>(T);
+// fn spawn(args: Vec<String>) {}
+// fn run(args: Vec<&str>) {
+//     spawn(args.iter().map(|s| s.to_string()).collect());
+// }
+// ```
+pub(crate) fn convert_string_vec_arg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let arg = ctx
+        .find_node_at_offset::<ast::Expr>()?
+        .syntax()
+        .ancestors()
+        .filter_map(ast::Expr::cast)
+        .find(|it| it.syntax().parent().map_or(false, |it| ast::ArgList::can_cast(it.kind())))?;
+    let arg_list = arg.syntax().parent().and_then(ast::ArgList::cast)?;
+    let mut index = arg_list.args().position(|it| it == arg)?;
+
+    let call = arg_list.syntax().parent()?;
+    let function = if let Some(call) = ast::CallExpr::cast(call.clone()) {
+        let function = match ctx.sema.type_of_expr(&call.expr()?)?.as_callable()? {
+            CallableDef::FunctionId(it) => Function::from(it),
+            _ => return None,
+        };
+        // In `Type::method(receiver, arg)`, the receiver isn't in the param list.
+        if function.has_self_param(ctx.db) {
+            index = index.checked_sub(1)?;
+        }
+        function
+    } else {
+        ctx.sema.resolve_method_call(&ast::MethodCallExpr::cast(call)?)?
+    };
+    let param = function.source(ctx.db).value.param_list()?.params().nth(index)?;
+    let expected = string_vec_elem(&param.ascribed_type()?)?;
+
+    let actual = match ctx.sema.type_of_expr(&arg)?.display(ctx.db).to_string().as_str() {
+        "Vec<String>" => StringElem::Owned,
+        "Vec<&str>" => StringElem::Borrowed,
+        _ => return None,
+    };
+    let (label, conversion) = match (actual, expected) {
+        (StringElem::Borrowed, StringElem::Owned) => {
+            ("Convert `Vec<&str>` to `Vec<String>`", "iter().map(|s| s.to_string()).collect()")
+        }
+        (StringElem::Owned, StringElem::Borrowed) => {
+            ("Convert `Vec<String>` to `Vec<&str>`", "iter().map(|s| s.as_str()).collect()")
+        }
+        _ => return None,
+    };
+
+    let target = arg.syntax().text_range();
+    acc.add(AssistId("convert_string_vec_arg"), label, target, |builder| {
+        let receiver = if is_postfix_receiver(&arg) {
+            arg.syntax().to_string()
+        } else {
+            format!("({})", arg.syntax())
+        };
+        builder.replace(target, format!("{}.{}", receiver, conversion));
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StringElem {
+    Owned,
+    Borrowed,
+}
+
+/// Classifies a written `Vec<String>` or `Vec<&str>` type.
+fn string_vec_elem(type_ref: &ast::TypeRef) -> Option<StringElem> {
+    let segment = match type_ref {
+        ast::TypeRef::PathType(it) => it.path()?.segment()?,
+        _ => return None,
+    };
+    if segment.name_ref()?.text() != "Vec" {
+        return None;
+    }
+    let mut args = segment.type_arg_list()?.type_args();
+    let elem = args.next()?.type_ref()?;
+    if args.next().is_some() {
+        return None;
+    }
+    match elem {
+        ast::TypeRef::PathType(it) if it.syntax().text() == "String" => Some(StringElem::Owned),
+        ast::TypeRef::ReferenceType(it) if it.mut_token().is_none() => match it.type_ref()? {
+            ast::TypeRef::PathType(it) if it.syntax().text() == "str" => Some(StringElem::Borrowed),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const VEC: &str = r#"
+struct String;
+struct Vec<T>(T);
+impl<T> Vec<T> { fn new() -> Vec<T> { loop {} } }
+"#;
+
+    #[test]
+    fn convert_owned_to_borrowed_for_method() {
+        check_assist(
+            convert_string_vec_arg,
+            &format!(
+                "{}{}",
+                VEC,
+                r#"
+struct Cmd;
+impl Cmd { fn args<'a>(&mut self, args: Vec<&'a str>) {} }
+struct Config { names: Vec<String> }
+fn run(cmd: &mut Cmd, config: Config) {
+    cmd.args(config.<|>names);
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                VEC,
+                r#"
+struct Cmd;
+impl Cmd { fn args<'a>(&mut self, args: Vec<&'a str>) {} }
+struct Config { names: Vec<String> }
+fn run(cmd: &mut Cmd, config: Config) {
+    cmd.args(config.names.iter().map(|s| s.as_str()).collect());
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn convert_borrowed_to_owned_in_ufcs_call() {
+        check_assist(
+            convert_string_vec_arg,
+            &format!(
+                "{}{}",
+                VEC,
+                r#"
+struct Cmd;
+impl Cmd { fn args(&mut self, args: Vec<String>) {} }
+fn run(cmd: &mut Cmd, flag: bool, a: Vec<&str>, b: Vec<&str>) {
+    Cmd::args(cmd, <|>if flag { a } else { b });
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                VEC,
+                r#"
+struct Cmd;
+impl Cmd { fn args(&mut self, args: Vec<String>) {} }
+fn run(cmd: &mut Cmd, flag: bool, a: Vec<&str>, b: Vec<&str>) {
+    Cmd::args(cmd, (if flag { a } else { b }).iter().map(|s| s.to_string()).collect());
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_matching_types() {
+        check_assist_not_applicable(
+            convert_string_vec_arg,
+            &format!("{}{}", VEC, "fn f(v: Vec<String>) {}\nfn g(v: Vec<String>) { f(<|>v) }"),
+        );
+        check_assist_not_applicable(
+            convert_string_vec_arg,
+            &format!("{}{}", VEC, "fn f(v: Vec<u8>) {}\nfn g(v: Vec<&str>) { f(<|>v) }"),
+        );
+    }
+
+    #[test]
+    fn convert_string_vec_arg_target() {
+        check_assist_target(
+            convert_string_vec_arg,
+            &format!("{}{}", VEC, "fn f(v: Vec<String>) {}\nfn g(v: Vec<&str>) { f(<|>v) }"),
+            "v",
+        );
+    }
+}
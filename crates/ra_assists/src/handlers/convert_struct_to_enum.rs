@@ -0,0 +1,254 @@
+use hir::{Adt, ModuleDef};
+use ra_ide_db::defs::Definition;
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, StructKind, TypeParamsOwner},
+    SyntaxKind::*,
+    SyntaxNode, TextRange,
+};
+
+use crate::{utils::FileEdits, AssistContext, AssistId, Assists};
+
+// Assist: convert_struct_to_enum
+//
+// Converts a struct to an enum with a single variant, which has the fields of
+// the struct. Construction sites and patterns are updated to use the variant.
+//
+// ```
+// struct Point<|> { x: i32, y: i32 }
+// fn origin() -> Point { Point { x: 0, y: 0 } }
+// ```
+// ->
+// ```
+// enum Point { Point { x: i32, y: i32 } }
+// fn origin() -> Point { Point::Point { x: 0, y: 0 } }
+// ```
+pub(crate) fn convert_struct_to_enum(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    let struct_token = strukt.struct_token()?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(it) => Some(it.syntax().clone()),
+        StructKind::Tuple(it) => Some(it.syntax().clone()),
+        StructKind::Unit => None,
+    };
+    // Don't offer the assist everywhere inside of the fields.
+    if let Some(field_list) = &field_list {
+        if field_list.text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    let struct_def = ctx.sema.to_def(&strukt)?;
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("convert_struct_to_enum"),
+        format!("Convert `{}` to an enum with a single variant", name),
+        target,
+        |builder| {
+            let mut edits = FileEdits::default();
+            let usages = Definition::ModuleDef(ModuleDef::Adt(Adt::Struct(struct_def)))
+                .find_usages(ctx.db, None);
+            for reference in usages {
+                let file_id = reference.file_range.file_id;
+                let source_file = ctx.sema.parse(file_id);
+                let offset = reference.file_range.range.start();
+                let path = match find_node_at_offset::<ast::NameRef>(source_file.syntax(), offset)
+                    .and_then(|it| it.syntax().ancestors().find_map(ast::Path::cast))
+                {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if is_constructor_or_pattern(&path) {
+                    let offset = path.syntax().text_range().end();
+                    edits.add(file_id, TextRange::empty(offset), format!("::{}", name));
+                    continue;
+                }
+                // `Self` refers to the struct in its inherent and trait impls.
+                let impl_def = path
+                    .syntax()
+                    .parent()
+                    .filter(|it| it.kind() == PATH_TYPE)
+                    .and_then(|it| it.parent())
+                    .and_then(ast::ImplDef::cast);
+                if let Some(impl_def) = impl_def {
+                    let is_target = impl_def
+                        .target_type()
+                        .map_or(false, |it| it.syntax().text_range() == path.syntax().text_range());
+                    if !is_target {
+                        continue;
+                    }
+                    for path in impl_def.syntax().descendants().filter_map(ast::Path::cast) {
+                        if path.qualifier().is_none()
+                            && path.syntax().text() == "Self"
+                            && is_constructor_or_pattern(&path)
+                        {
+                            let offset = path.syntax().text_range().end();
+                            edits.add(file_id, TextRange::empty(offset), format!("::{}", name));
+                        }
+                    }
+                }
+            }
+
+            let indent = IndentLevel::from_node(strukt.syntax());
+            let body = match &field_list {
+                Some(field_list) => {
+                    let fields = strip_field_visibilities(field_list);
+                    let separator =
+                        if field_list.kind() == RECORD_FIELD_DEF_LIST { " " } else { "" };
+                    if fields.contains('\n') {
+                        let fields = fields.replace('\n', &format!("\n{}", IndentLevel(1)));
+                        format!("{{\n{}{}{}{},\n{}}}", indent + 1, name, separator, fields, indent)
+                    } else {
+                        format!("{{ {}{}{} }}", name, separator, fields)
+                    }
+                }
+                None => format!("{{ {} }}", name),
+            };
+            let mut text = format!("enum {}", name);
+            if let Some(type_params) = strukt.type_param_list() {
+                text.push_str(&type_params.syntax().to_string());
+            }
+            if let Some(where_clause) = strukt.where_clause() {
+                text.push_str(&format!(" {}", where_clause.syntax()));
+            }
+            text.push_str(&format!(" {}", body));
+            let range = TextRange::new(struct_token.text_range().start(), target.end());
+            edits.add(ctx.frange.file_id, range, text);
+            edits.apply(builder, ctx.frange.file_id);
+        },
+    )
+}
+
+/// Checks whether `path` constructs or destructures a value, like `S { .. }`,
+/// `S(..)` or `S`.
+fn is_constructor_or_pattern(path: &ast::Path) -> bool {
+    path.syntax().parent().map_or(false, |it| {
+        matches!(it.kind(), RECORD_LIT | RECORD_PAT | TUPLE_STRUCT_PAT | PATH_PAT | PATH_EXPR)
+    })
+}
+
+/// Returns the text of the fields, without the visibilities, which are not
+/// allowed on the fields of enum variants.
+fn strip_field_visibilities(field_list: &SyntaxNode) -> String {
+    let start = field_list.text_range().start();
+    let mut text = field_list.to_string();
+    let visibilities: Vec<TextRange> = field_list
+        .children()
+        .filter_map(|field| field.children().find_map(ast::Visibility::cast))
+        .map(|vis| {
+            let end = match vis.syntax().next_sibling_or_token() {
+                Some(ws) if ws.kind() == WHITESPACE => ws.text_range().end(),
+                _ => vis.syntax().text_range().end(),
+            };
+            TextRange::new(vis.syntax().text_range().start(), end) - start
+        })
+        .collect();
+    for range in visibilities.into_iter().rev() {
+        let range: std::ops::Range<usize> = range.into();
+        text.replace_range(range, "");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn convert_record_struct() {
+        check_assist(
+            convert_struct_to_enum,
+            r"
+/// A point.
+#[derive(Debug)]
+pub struct <|>Point<T> where T: Copy {
+    pub x: T,
+    pub(crate) y: T,
+}
+impl<T: Copy> Point<T> {
+    fn new(x: T, y: T) -> Self { Self { x, y } }
+    fn x(&self) -> T {
+        let Self { x, .. } = self;
+        *x
+    }
+}
+fn origin() -> Point<u32> {
+    let Point { x, y } = Point::new(0, 0);
+    Point { x, y }
+}",
+            r"
+/// A point.
+#[derive(Debug)]
+pub enum Point<T> where T: Copy {
+    Point {
+        x: T,
+        y: T,
+    },
+}
+impl<T: Copy> Point<T> {
+    fn new(x: T, y: T) -> Self { Self::Point { x, y } }
+    fn x(&self) -> T {
+        let Self::Point { x, .. } = self;
+        *x
+    }
+}
+fn origin() -> Point<u32> {
+    let Point::Point { x, y } = Point::new(0, 0);
+    Point::Point { x, y }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_tuple_struct_in_other_file() {
+        check_assist(
+            convert_struct_to_enum,
+            r"
+//- /main.rs
+mod ids;
+fn first() -> ids::Id { ids::Id(0) }
+//- /ids.rs
+pub struct I<|>d(pub u32);
+pub fn ids() -> Vec<Id> { vec![1, 2].into_iter().map(Id).collect() }
+fn raw(id: Id) -> u32 {
+    match id {
+        Id(raw) => raw,
+    }
+}",
+            r"pub enum Id { Id(u32) }
+pub fn ids() -> Vec<Id> { vec![1, 2].into_iter().map(Id::Id).collect() }
+fn raw(id: Id) -> u32 {
+    match id {
+        Id::Id(raw) => raw,
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn convert_unit_struct() {
+        check_assist(
+            convert_struct_to_enum,
+            "struct Marker<|>;\nfn marker() -> Marker { Marker }",
+            "enum Marker { Marker }\nfn marker() -> Marker { Marker::Marker }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_inside_fields() {
+        check_assist_not_applicable(convert_struct_to_enum, "struct Point { x: i32<|> }");
+    }
+
+    #[test]
+    fn convert_struct_to_enum_target() {
+        check_assist_target(
+            convert_struct_to_enum,
+            "struct Point<|> { x: i32 }",
+            "struct Point { x: i32 }",
+        );
+    }
+}
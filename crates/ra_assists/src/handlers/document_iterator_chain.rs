@@ -0,0 +1,207 @@
+use hir::HirDisplay;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, ArgListOwner, AstNode, AstToken, DocCommentsOwner},
+    TextSize,
+};
+use stdx::SepBy;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: document_iterator_chain
+//
+// Adds a doc comment to the function, which describes what an iterator chain
+// does with its items.
+//
+// ```
+// fn labels(xs: &[u32]) -> Vec<String> {
+//     xs.iter().filter(|x| *x % 2 == 0).map(|x| x.to_string()).<|>collect()
+// }
+// ```
+// ->
+// ```
+// /// Filters the items by `*x % 2 == 0`, maps each item to `x.to_string()`, collects the items.
+// fn labels(xs: &[u32]) -> Vec<String> {
+//     xs.iter().filter(|x| *x % 2 == 0).map(|x| x.to_string()).collect()
+// }
+// ```
+pub(crate) fn document_iterator_chain(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let mut call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    while let Some(parent) = call.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+        if parent.expr()?.syntax() != call.syntax() {
+            break;
+        }
+        call = parent;
+    }
+    let fn_def = call.syntax().ancestors().find_map(ast::FnDef::cast)?;
+
+    let mut steps = Vec::new();
+    let mut current = Some(call.clone());
+    while let Some(it) = current {
+        let name = it.name_ref()?;
+        if let Some(step) = describe_step(ctx, &it, name.text()) {
+            steps.push(step);
+        }
+        current = match it.expr()? {
+            ast::Expr::MethodCallExpr(it) => Some(it),
+            _ => None,
+        };
+    }
+    if steps.len() < 2 {
+        return None;
+    }
+    steps.reverse();
+    let mut description = steps.into_iter().sep_by(", ").to_string();
+    description[..1].make_ascii_uppercase();
+
+    let target = call.syntax().text_range();
+    acc.add(AssistId("document_iterator_chain"), "Document iterator chain", target, |builder| {
+        let indent = IndentLevel::from_node(fn_def.syntax());
+        match fn_def.doc_comments().last() {
+            Some(comment) => builder.insert(
+                comment.syntax().text_range().end(),
+                format!("\n{}///\n{}/// {}.", indent, indent, description),
+            ),
+            None => builder.insert(
+                fn_def.syntax().text_range().start(),
+                format!("/// {}.\n{}", description, indent),
+            ),
+        }
+    })
+}
+
+/// Returns a description of what the iterator method `name` does, or `None`
+/// if it isn't a known adaptor or consumer.
+fn describe_step(ctx: &AssistContext, call: &ast::MethodCallExpr, name: &str) -> Option<String> {
+    let arg = || {
+        let arg = call.arg_list()?.args().next()?;
+        let arg = match &arg {
+            ast::Expr::LambdaExpr(it) => it.body()?,
+            _ => arg,
+        };
+        let text = arg.syntax().text();
+        if text.contains_char('\n') || text.len() > TextSize::from(40) {
+            return None;
+        }
+        Some(format!("`{}`", text))
+    };
+    let with_arg = |desc: &str, prep: &str| match arg() {
+        Some(arg) if prep.is_empty() => format!("{} {}", desc, arg),
+        Some(arg) => format!("{} {} {}", desc, prep, arg),
+        None => desc.to_string(),
+    };
+    let desc = match name {
+        "filter" => with_arg("filters the items", "by"),
+        "map" => with_arg("maps each item", "to"),
+        "filter_map" => with_arg("filters and maps the items", "with"),
+        "flat_map" => with_arg("flat-maps the items", "to"),
+        "flatten" => "flattens the items".to_string(),
+        "enumerate" => "enumerates the items".to_string(),
+        "zip" => with_arg("zips the items", "with"),
+        "chain" => with_arg("chains the items", "with"),
+        "rev" => "reverses the order".to_string(),
+        "skip" => format!("skips {} items", arg().unwrap_or_else(|| "some".to_string())),
+        "take" => format!("takes {} items", arg().unwrap_or_else(|| "some".to_string())),
+        "skip_while" => with_arg("skips items", "while"),
+        "take_while" => with_arg("takes items", "while"),
+        "step_by" => with_arg("steps through the items", "by"),
+        "cloned" => "clones the items".to_string(),
+        "copied" => "copies the items".to_string(),
+        "inspect" => with_arg("inspects each item", "with"),
+        "peekable" => "makes the iterator peekable".to_string(),
+        "collect" => {
+            let module = ctx.sema.scope(call.syntax()).module()?;
+            let ty = ctx
+                .sema
+                .type_of_expr(&ast::Expr::from(call.clone()))
+                .filter(|it| !it.contains_unknown())
+                .and_then(|it| it.display_source_code(ctx.db, module.into()).ok());
+            match ty {
+                Some(ty) => format!("collects the items into `{}`", ty),
+                None => "collects the items".to_string(),
+            }
+        }
+        "sum" => "sums the items".to_string(),
+        "product" => "multiplies the items".to_string(),
+        "count" => "counts the items".to_string(),
+        "fold" => "folds the items".to_string(),
+        "any" => with_arg("checks whether any item matches", ""),
+        "all" => with_arg("checks whether all items match", ""),
+        "find" => with_arg("finds the first item matching", ""),
+        "position" => with_arg("finds the position of the first item matching", ""),
+        "min" => "takes the minimum".to_string(),
+        "max" => "takes the maximum".to_string(),
+        "last" => "takes the last item".to_string(),
+        "for_each" => "runs a closure for each item".to_string(),
+        _ => return None,
+    };
+    Some(desc)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn document_chain_below_existing_docs() {
+        check_assist(
+            document_iterator_chain,
+            r#"
+impl Index {
+    /// Returns the names of the files.
+    #[inline]
+    fn names(&self) -> usize {
+        self.files
+            .iter()
+            .enumerate()
+            .skip_while(|(idx, _)| *idx < self.start)
+            .map(|(_, file)| {
+                file.name()
+            })
+            .count<|>()
+    }
+}
+"#,
+            r#"
+impl Index {
+    /// Returns the names of the files.
+    ///
+    /// Enumerates the items, skips items while `*idx < self.start`, maps each item, counts the items.
+    #[inline]
+    fn names(&self) -> usize {
+        self.files
+            .iter()
+            .enumerate()
+            .skip_while(|(idx, _)| *idx < self.start)
+            .map(|(_, file)| {
+                file.name()
+            })
+            .count()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_single_step() {
+        check_assist_not_applicable(
+            document_iterator_chain,
+            "fn f(xs: &[u32]) -> usize { xs.iter().<|>count() }",
+        );
+        check_assist_not_applicable(
+            document_iterator_chain,
+            "fn f(s: &str) -> String { s.trim().<|>to_lowercase() }",
+        );
+    }
+
+    #[test]
+    fn document_iterator_chain_target() {
+        check_assist_target(
+            document_iterator_chain,
+            "fn f(xs: &[u32]) -> u32 { 1 + xs.<|>iter().copied().sum() }",
+            "xs.iter().copied().sum()",
+        );
+    }
+}
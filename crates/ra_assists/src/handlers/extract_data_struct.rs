@@ -0,0 +1,288 @@
+use hir::{Adt, Struct, VariantDef};
+use ra_fmt::leading_indent;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner, TypeParamsOwner, VisibilityOwner},
+    NodeOrToken, SyntaxNode,
+};
+use stdx::format_to;
+
+use crate::{
+    assist_context::AssistBuilder,
+    utils::{impl_type_params, nominal_self_ty},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: extract_data_struct
+//
+// Moves the fields of a struct into a separate `Data` struct, leaving the
+// methods on the original one. The struct and its `impl` block have to be
+// selected.
+//
+// ```
+// <|>struct Counter {
+//     count: usize,
+// }
+//
+// impl Counter {
+//     fn bump(&mut self) {
+//         self.count += 1;
+//     }
+// }<|>
+// ```
+// ->
+// ```
+// struct Counter { data: CounterData }
+//
+// struct CounterData {
+//     count: usize,
+// }
+//
+// impl Counter {
+//     fn bump(&mut self) {
+//         self.data.count += 1;
+//     }
+// }
+// ```
+pub(crate) fn extract_data_struct(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let range = ctx.frange.range;
+    if range.is_empty() {
+        return None;
+    }
+    let covering = match ctx.covering_element() {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let strukt = covering
+        .descendants()
+        .filter_map(ast::StructDef::cast)
+        .find(|it| range.contains_range(it.syntax().text_range()))?;
+    let field_list = match strukt.kind() {
+        ast::StructKind::Record(it) => it,
+        _ => return None,
+    };
+    let name = strukt.name()?;
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let has_selected_impl = covering.descendants().filter_map(ast::ImplDef::cast).any(|imp| {
+        range.contains_range(imp.syntax().text_range())
+            && ctx.sema.to_def(&imp).map_or(false, |imp| {
+                imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(struct_def))
+            })
+    });
+    if !has_selected_impl {
+        return None;
+    }
+
+    let data_name = format!("{}Data", name);
+    let module = struct_def.module(ctx.db);
+    if module.scope(ctx.db, None).into_iter().any(|(name, _)| name.to_string() == data_name) {
+        return None;
+    }
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("extract_data_struct"),
+        format!("Extract fields into `{}`", data_name),
+        target,
+        |builder| {
+            let source_file = ctx.sema.parse(ctx.frange.file_id);
+            for node in source_file.syntax().descendants() {
+                update_usage(ctx, builder, struct_def, &node);
+            }
+
+            let nominal = ast::NominalDef::StructDef(strukt.clone());
+            let data_visibility = field_list
+                .fields()
+                .find_map(|it| it.visibility())
+                .map_or(String::new(), |it| format!("{} ", it));
+            builder.replace(
+                field_list.syntax().text_range(),
+                format!("{{ {}data: {} }}", data_visibility, nominal_self_ty(&nominal, &data_name)),
+            );
+
+            let indent = leading_indent(strukt.syntax()).unwrap_or_default();
+            let mut buf = format!("\n\n{}", indent);
+            for attr in strukt.attrs().filter(|it| it.simple_name().as_deref() == Some("derive")) {
+                format_to!(buf, "{}\n{}", attr, indent);
+            }
+            if let Some(visibility) = strukt.visibility() {
+                format_to!(buf, "{} ", visibility);
+            }
+            format_to!(buf, "struct {}{}", data_name, impl_type_params(&nominal));
+            if let Some(where_clause) = strukt.where_clause() {
+                format_to!(buf, " {}", where_clause);
+            }
+            format_to!(buf, " {}", field_list);
+            builder.insert(strukt.syntax().text_range().end(), buf);
+        },
+    )
+}
+
+/// Rewrites field accesses, struct literals and struct patterns of `strukt` to
+/// go through the `data` field.
+fn update_usage(
+    ctx: &AssistContext,
+    builder: &mut AssistBuilder,
+    strukt: Struct,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let is_strukt = |ty: hir::Type| ty.as_adt() == Some(Adt::Struct(strukt));
+    if let Some(field_expr) = ast::FieldExpr::cast(node.clone()) {
+        let field = ctx.sema.resolve_field(&field_expr)?;
+        if field.parent_def(ctx.db) != VariantDef::Struct(strukt) {
+            return None;
+        }
+        builder.insert(field_expr.name_ref()?.syntax().text_range().start(), "data.");
+    } else if let Some(record_lit) = ast::RecordLit::cast(node.clone()) {
+        if !is_strukt(ctx.sema.type_of_expr(&record_lit.clone().into())?) {
+            return None;
+        }
+        let field_list = record_lit.record_field_list()?;
+        let data_name = format!("{}Data", strukt.name(ctx.db));
+        builder
+            .insert(field_list.syntax().text_range().start(), format!("{{ data: {} ", data_name));
+        builder.insert(field_list.syntax().text_range().end(), " }");
+        if let Some(spread) = field_list.spread() {
+            builder.insert(spread.syntax().text_range().end(), ".data");
+        }
+    } else if let Some(record_pat) = ast::RecordPat::cast(node.clone()) {
+        if !is_strukt(ctx.sema.type_of_pat(&record_pat.clone().into())?) {
+            return None;
+        }
+        let field_list = record_pat.record_field_pat_list()?;
+        let data_name = format!("{}Data", strukt.name(ctx.db));
+        builder
+            .insert(field_list.syntax().text_range().start(), format!("{{ data: {} ", data_name));
+        builder.insert(field_list.syntax().text_range().end(), " }");
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn extract_data_struct_updates_methods() {
+        check_assist(
+            extract_data_struct,
+            r#"
+<|>#[derive(Clone, Debug)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Copy> Point<T> {
+    fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+    fn swapped(&self) -> Self {
+        Point { x: self.y, ..self.clone() }
+    }
+    fn x(&self) -> T {
+        let Point { x, .. } = self;
+        *x
+    }
+}<|>
+"#,
+            r#"
+#[derive(Clone, Debug)]
+pub struct Point<T> { pub data: PointData<T> }
+
+#[derive(Clone, Debug)]
+pub struct PointData<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Copy> Point<T> {
+    fn new(x: T, y: T) -> Self {
+        Self { data: PointData { x, y } }
+    }
+    fn swapped(&self) -> Self {
+        Point { data: PointData { x: self.data.y, ..self.clone().data } }
+    }
+    fn x(&self) -> T {
+        let Point { data: PointData { x, .. } } = self;
+        *x
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_data_struct_updates_other_usages_in_file() {
+        check_assist(
+            extract_data_struct,
+            r#"
+mod m {
+    <|>struct Foo { a: i32 }
+    impl Foo {
+        fn get(&self) -> i32 { self.a }
+    }<|>
+    fn bar(foo: &mut Foo) { foo.a = 92; }
+}
+"#,
+            r#"
+mod m {
+    struct Foo { data: FooData }
+
+    struct FooData { a: i32 }
+    impl Foo {
+        fn get(&self) -> i32 { self.data.a }
+    }
+    fn bar(foo: &mut Foo) { foo.data.a = 92; }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_impl() {
+        check_assist_not_applicable(
+            extract_data_struct,
+            r#"
+<|>struct Foo { a: i32 }<|>
+impl Foo {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_tuple_struct() {
+        check_assist_not_applicable(
+            extract_data_struct,
+            r#"
+<|>struct Foo(i32);
+impl Foo {}<|>
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_data_struct_exists() {
+        check_assist_not_applicable(
+            extract_data_struct,
+            r#"
+struct FooData;
+<|>struct Foo { a: i32 }
+impl Foo {}<|>
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_data_struct_target() {
+        check_assist_target(
+            extract_data_struct,
+            r#"
+<|>struct Foo { a: i32 }
+impl Foo {}<|>
+"#,
+            "struct Foo { a: i32 }",
+        );
+    }
+}
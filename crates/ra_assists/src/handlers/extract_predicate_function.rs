@@ -0,0 +1,241 @@
+use either::Either;
+use hir::{HirDisplay, PathResolution};
+use ra_syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        AstNode, TypeAscriptionOwner, TypeParamsOwner,
+    },
+    SyntaxKind::{FN_DEF, IMPL_DEF, TRAIT_DEF},
+};
+use stdx::format_to;
+
+use crate::{utils::module_item, AssistContext, AssistId, Assists};
+
+// Assist: extract_predicate_function
+//
+// Extracts the closure passed to `filter` or `retain` into a named predicate
+// function.
+//
+// ```
+// struct Task { done: bool, priority: u8 }
+//
+// fn urgent(tasks: &mut Vec<Task>) {
+//     tasks.retain(<|>|t: &Task| !t.done && t.priority > 3);
+// }
+// ```
+// ->
+// ```
+// struct Task { done: bool, priority: u8 }
+//
+// fn urgent(tasks: &mut Vec<Task>) {
+//     tasks.retain(is_valid);
+// }
+//
+// fn $0is_valid(t: &Task) -> bool {
+//     !t.done && t.priority > 3
+// }
+// ```
+pub(crate) fn extract_predicate_function(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let lambda = ctx.find_node_at_offset::<ast::LambdaExpr>()?;
+    let method_call = lambda.syntax().parent().and_then(ast::ArgList::cast)?;
+    let method_call = method_call.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+    let method = method_call.name_ref()?;
+    if !["filter", "retain"].contains(&method.text().as_str()) {
+        return None;
+    }
+    if lambda.async_token().is_some() || lambda.ret_type().is_some() {
+        return None;
+    }
+    let params: Vec<ast::Param> = lambda.param_list()?.params().collect();
+    let param = match params.as_slice() {
+        [it] => it.clone(),
+        _ => return None,
+    };
+    let pat = match param.pat()? {
+        ast::Pat::BindPat(it) if it.pat().is_none() => it,
+        _ => return None,
+    };
+    let body = lambda.body()?;
+
+    // A free function can't capture locals or name generic parameters.
+    let has_generics = lambda
+        .syntax()
+        .ancestors()
+        .filter(|it| matches!(it.kind(), FN_DEF | IMPL_DEF | TRAIT_DEF))
+        .filter_map(|it| {
+            ast::FnDef::cast(it.clone())
+                .and_then(|it| it.type_param_list())
+                .or_else(|| ast::ImplDef::cast(it.clone()).and_then(|it| it.type_param_list()))
+                .or_else(|| ast::TraitDef::cast(it).and_then(|it| it.type_param_list()))
+        })
+        .any(|it| it.type_params().next().is_some());
+    if has_generics {
+        return None;
+    }
+    for path in body.syntax().descendants().filter_map(ast::Path::cast) {
+        match ctx.sema.resolve_path(&path) {
+            Some(PathResolution::Local(local)) => match local.source(ctx.db).value {
+                Either::Left(it)
+                    if lambda.syntax().text_range().contains_range(it.syntax().text_range()) => {}
+                _ => return None,
+            },
+            Some(PathResolution::SelfType(_)) => return None,
+            _ => (),
+        }
+    }
+
+    let module = ctx.sema.scope(lambda.syntax()).module()?;
+    let param_ty = match param.ascribed_type() {
+        Some(it) => it.syntax().to_string(),
+        None => {
+            let ty = ctx.sema.type_of_pat(&ast::Pat::BindPat(pat.clone()))?;
+            if ty.contains_unknown() {
+                return None;
+            }
+            ty.display_source_code(ctx.db, module.into()).ok()?
+        }
+    };
+    let item = module_item(lambda.syntax())?;
+    let name = fresh_name(ctx, &lambda);
+
+    let target = lambda.syntax().text_range();
+    acc.add(
+        AssistId("extract_predicate_function"),
+        "Extract closure into predicate function",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(&item);
+            let body = match &body {
+                ast::Expr::BlockExpr(it) => it.reset_indent().indent(indent).syntax().to_string(),
+                it => {
+                    let it = it.reset_indent().indent(indent + 1);
+                    format!("{{\n{}{}\n{}}}", indent + 1, it.syntax(), indent)
+                }
+            };
+            let mut buf = String::new();
+            format_to!(buf, "\n\n{}fn $0{}({}: {}) -> bool {}", indent, name, pat, param_ty, body);
+
+            builder.replace(target, name.clone());
+            let offset = item.text_range().end();
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, buf),
+                None => builder.insert(offset, buf.replace("$0", "")),
+            }
+        },
+    )
+}
+
+/// Returns `is_valid`, or a numbered variant of it if the name is taken.
+fn fresh_name(ctx: &AssistContext, lambda: &ast::LambdaExpr) -> String {
+    let mut taken = Vec::new();
+    ctx.sema.scope(lambda.syntax()).process_all_names(&mut |name, _| {
+        taken.push(name.to_string());
+    });
+    let mut name = "is_valid".to_string();
+    let mut counter = 1;
+    while taken.contains(&name) {
+        counter += 1;
+        name = format!("is_valid{}", counter);
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn extract_block_body_from_retain() {
+        check_assist(
+            extract_predicate_function,
+            r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+struct Vec<T>(T);
+impl<T> Vec<T> { fn retain<F: FnOnce(&T) -> bool>(&mut self, f: F) {} }
+struct User { age: u32, active: bool }
+fn is_valid() {}
+impl User {
+    fn prune(users: &mut Vec<User>) {
+        users.retain(|user| {
+            let adult = user.age >= 18;
+            adult && user.active
+        }<|>);
+    }
+}
+"#,
+            r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+struct Vec<T>(T);
+impl<T> Vec<T> { fn retain<F: FnOnce(&T) -> bool>(&mut self, f: F) {} }
+struct User { age: u32, active: bool }
+fn is_valid() {}
+impl User {
+    fn prune(users: &mut Vec<User>) {
+        users.retain(is_valid2);
+    }
+}
+
+fn $0is_valid2(user: &User) -> bool {
+    let adult = user.age >= 18;
+    adult && user.active
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_ascribed_type_in_module() {
+        check_assist(
+            extract_predicate_function,
+            r#"
+mod m {
+    fn f(xs: &[u8]) -> usize {
+        xs.iter().filter(|x: &&u8| **x > 3<|>).count()
+    }
+}
+"#,
+            r#"
+mod m {
+    fn f(xs: &[u8]) -> usize {
+        xs.iter().filter(is_valid).count()
+    }
+
+    fn $0is_valid(x: &&u8) -> bool {
+        **x > 3
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Captures `limit`.
+        check_assist_not_applicable(
+            extract_predicate_function,
+            "fn f(xs: Vec<u32>, limit: u32) { xs.into_iter().filter(<|>|x: &u32| *x > limit); }",
+        );
+        check_assist_not_applicable(
+            extract_predicate_function,
+            "fn f(xs: Vec<u32>) { xs.into_iter().map(<|>|x| x > 1); }",
+        );
+        check_assist_not_applicable(
+            extract_predicate_function,
+            "fn f<T: Copy>(xs: Vec<T>) { xs.into_iter().filter(<|>|_x| true); }",
+        );
+    }
+
+    #[test]
+    fn extract_predicate_function_target() {
+        check_assist_target(
+            extract_predicate_function,
+            "fn f(xs: Vec<u32>) { xs.into_iter().filter(<|>|x: &u32| *x > 1); }",
+            "|x: &u32| *x > 1",
+        );
+    }
+}
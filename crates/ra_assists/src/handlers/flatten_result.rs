@@ -0,0 +1,207 @@
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{
+    utils::{is_postfix_receiver, FamousDefs, TryEnum},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: flatten_result
+//
+// Flattens an expression of type `Result<Result<T, E>, E>` with `.flatten()`.
+//
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// fn main() {
+//     let x: Result<Result<i32, ()>, ()> = Result::Ok(Result::Ok(92));
+//     let y = <|>x;
+// }
+// ```
+// ->
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// fn main() {
+//     let x: Result<Result<i32, ()>, ()> = Result::Ok(Result::Ok(92));
+//     let y = x.flatten();
+// }
+// ```
+pub(crate) fn flatten_result(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (expr, needs_conversion) = ctx
+        .find_node_at_offset::<ast::Expr>()?
+        .syntax()
+        .ancestors()
+        .take_while(|it| !ast::BlockExpr::can_cast(it.kind()))
+        .filter_map(ast::Expr::cast)
+        .find_map(|expr| Some((expr.clone(), nested_result_conversion(ctx, &expr)?)))?;
+
+    let target = expr.syntax().text_range();
+    acc.add(AssistId("flatten_result"), "Flatten nested `Result`", target, |builder| {
+        let suffix = if needs_conversion { ".map_err(Into::into).flatten()" } else { ".flatten()" };
+        if !is_postfix_receiver(&expr) {
+            builder.insert(target.start(), "(");
+            builder.insert(target.end(), format!("){}", suffix));
+        } else {
+            builder.insert(target.end(), suffix);
+        }
+    })
+}
+
+/// Returns `Some(false)` if `expr` is a nested `Result` with identical error
+/// types, and `Some(true)` if the outer error has to be converted into the
+/// inner one first.
+fn nested_result_conversion(ctx: &AssistContext, expr: &ast::Expr) -> Option<bool> {
+    let ty = ctx.sema.type_of_expr(expr)?;
+    if !matches!(TryEnum::from_ty(&ctx.sema, &ty)?, TryEnum::Result) {
+        return None;
+    }
+    let (inner_ty, outer_err) = match ty.type_parameters().as_slice() {
+        [inner_ty, outer_err] => (inner_ty.clone(), outer_err.clone()),
+        _ => return None,
+    };
+    if !matches!(TryEnum::from_ty(&ctx.sema, &inner_ty)?, TryEnum::Result) {
+        return None;
+    }
+    let inner_err = inner_ty.type_parameters().pop()?;
+    if inner_err.is_unknown() || outer_err.is_unknown() {
+        return None;
+    }
+    if inner_err == outer_err {
+        return Some(false);
+    }
+
+    // `Into` is blanket-implemented in terms of `From`, so we look for the
+    // latter.
+    let krate = ctx.sema.scope(expr.syntax()).module()?.krate();
+    let from_trait = FamousDefs(&ctx.sema, krate).core_convert_From()?;
+    if inner_err.impls_trait(ctx.db, from_trait, &[outer_err]) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist, check_assist_not_applicable, check_assist_target, check_assist_with_core,
+        with_core,
+    };
+
+    use super::*;
+
+    #[test]
+    fn flatten_result_same_error() {
+        check_assist(
+            flatten_result,
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<Result<i32, ()>, ()> { Result::Ok(Result::Ok(92)) }
+fn main() {
+    let y = foo(<|>);
+}
+",
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<Result<i32, ()>, ()> { Result::Ok(Result::Ok(92)) }
+fn main() {
+    let y = foo().flatten();
+}
+",
+        );
+    }
+
+    #[test]
+    fn flatten_result_adds_parens() {
+        check_assist(
+            flatten_result,
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+fn main() {
+    let x: Result<Result<i32, ()>, ()> = Result::Ok(Result::Ok(92));
+    let y = <|>match x { it => it };
+}
+",
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+fn main() {
+    let x: Result<Result<i32, ()>, ()> = Result::Ok(Result::Ok(92));
+    let y = (match x { it => it }).flatten();
+}
+",
+        );
+    }
+
+    #[test]
+    fn flatten_result_converts_error() {
+        check_assist_with_core(
+            flatten_result,
+            r"
+struct A;
+struct B;
+impl From<A> for B {
+    fn from(_: A) -> B { B }
+}
+fn foo() -> Result<Result<i32, B>, A> { Ok(Ok(92)) }
+fn main() {
+    let y = foo(<|>);
+}
+",
+            r"
+struct A;
+struct B;
+impl From<A> for B {
+    fn from(_: A) -> B { B }
+}
+fn foo() -> Result<Result<i32, B>, A> { Ok(Ok(92)) }
+fn main() {
+    let y = foo().map_err(Into::into).flatten();
+}
+",
+        );
+    }
+
+    #[test]
+    fn flatten_result_not_applicable_without_conversion() {
+        check_assist_not_applicable(
+            flatten_result,
+            &with_core(
+                r"
+struct A;
+struct B;
+fn foo() -> Result<Result<i32, B>, A> { Ok(Ok(92)) }
+fn main() {
+    let y = foo(<|>);
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn flatten_result_not_applicable_for_flat_result() {
+        check_assist_not_applicable(
+            flatten_result,
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<i32, ()> { Result::Ok(92) }
+fn main() {
+    let y = foo(<|>);
+}
+",
+        );
+    }
+
+    #[test]
+    fn flatten_result_target() {
+        check_assist_target(
+            flatten_result,
+            r"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<Result<i32, ()>, ()> { Result::Ok(Result::Ok(92)) }
+fn main() {
+    let y = foo(<|>);
+}
+",
+            "foo()",
+        );
+    }
+}
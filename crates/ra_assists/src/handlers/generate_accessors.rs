@@ -0,0 +1,288 @@
+use hir::Adt;
+use ra_syntax::ast::{self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, VisibilityOwner};
+use rustc_hash::FxHashSet;
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{has_bound, impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists, GroupLabel,
+};
+
+// Assist: generate_accessors
+//
+// Generates public getters and setters for the private fields of a struct.
+// Getters return `Copy` fields by value and other fields by reference. With
+// `assist.prefixGetters`, getters are named `get_field` instead of `field`.
+//
+// ```
+// struct Person {
+//     name: String,<|>
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     pub fn name(&self) -> &String {
+//         &self.name
+//     }
+// }
+// ```
+pub(crate) fn generate_accessors(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(it) => it,
+        _ => return None,
+    };
+    let name = strukt.name()?;
+    let def = ctx.sema.to_def(&strukt)?;
+    let krate = def.module(ctx.db).krate();
+    let copy_trait = FamousDefs(&ctx.sema, krate).core_marker_Copy();
+
+    let fields = field_list
+        .fields()
+        .filter(|it| it.visibility().is_none())
+        .filter_map(|field| {
+            let name = field.name()?;
+            let ty = field.ascribed_type()?;
+            let is_copy = has_bound(&strukt, &ty, "Copy")
+                || match (ctx.sema.to_def(&field), copy_trait) {
+                    (Some(def), Some(copy_trait)) => {
+                        def.signature_ty(ctx.db).impls_trait(ctx.db, copy_trait, &[])
+                    }
+                    _ => false,
+                };
+            Some(Field { name: name.text().to_string(), ty: ty.syntax().to_string(), is_copy })
+        })
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return None;
+    }
+
+    let impl_def = find_inherent_impl(ctx, &strukt, def);
+    let existing = impl_def.as_ref().map_or_else(FxHashSet::default, existing_methods);
+    let getter_name = |field: &Field| {
+        if ctx.config.prefix_getters {
+            format!("get_{}", field.name)
+        } else {
+            field.name.clone()
+        }
+    };
+    let getters = fields
+        .iter()
+        .filter(|field| !existing.contains(&getter_name(field)))
+        .map(|field| (field, getter(field, &getter_name(field))))
+        .collect::<Vec<_>>();
+    let setters = fields
+        .iter()
+        .filter(|field| !existing.contains(&format!("set_{}", field.name)))
+        .map(|field| (field, setter(field)))
+        .collect::<Vec<_>>();
+
+    let insert_pos = impl_def
+        .as_ref()
+        .and_then(|it| it.item_list()?.r_curly_token())
+        .map(|it| it.text_range().start());
+    let nominal = ast::NominalDef::from(strukt.clone());
+    let impl_header =
+        format!("impl{} {}", impl_type_params(&nominal), nominal_self_ty(&nominal, &name));
+    let struct_end = strukt.syntax().text_range().end();
+
+    let group = GroupLabel("Generate accessor methods".to_string());
+    let target = strukt.syntax().text_range();
+    let mut add = |id_label: &str, methods: Vec<&String>| {
+        if methods.is_empty() {
+            return;
+        }
+        acc.add_group(&group, AssistId("generate_accessors"), id_label, target, |builder| {
+            let methods = methods
+                .iter()
+                .map(|it| format!("    {}", it.replace('\n', "\n    ")))
+                .sep_by("\n\n")
+                .to_string();
+            match insert_pos {
+                Some(offset) => builder.insert(offset, format!("\n{}\n", methods)),
+                None => {
+                    let mut buf = String::new();
+                    format_to!(buf, "\n\n{} {{\n{}\n}}", impl_header, methods);
+                    builder.insert(struct_end, buf);
+                }
+            }
+        });
+    };
+
+    add("Generate getters", getters.iter().map(|(_, it)| it).collect());
+    add("Generate setters", setters.iter().map(|(_, it)| it).collect());
+    let mut both = Vec::new();
+    for field in &fields {
+        both.extend(getters.iter().filter(|(it, _)| it.name == field.name).map(|(_, it)| it));
+        both.extend(setters.iter().filter(|(it, _)| it.name == field.name).map(|(_, it)| it));
+    }
+    if !getters.is_empty() && !setters.is_empty() {
+        add("Generate getters and setters", both);
+    }
+    Some(())
+}
+
+struct Field {
+    name: String,
+    ty: String,
+    is_copy: bool,
+}
+
+fn getter(field: &Field, name: &str) -> String {
+    if field.is_copy {
+        format!("pub fn {}(&self) -> {} {{\n    self.{}\n}}", name, field.ty, field.name)
+    } else {
+        format!("pub fn {}(&self) -> &{} {{\n    &self.{}\n}}", name, field.ty, field.name)
+    }
+}
+
+fn setter(field: &Field) -> String {
+    format!("pub fn set_{0}(&mut self, {0}: {1}) {{\n    self.{0} = {0};\n}}", field.name, field.ty)
+}
+
+/// Finds an inherent impl of the struct in the same file, to which the
+/// accessors are added.
+fn find_inherent_impl(
+    ctx: &AssistContext,
+    strukt: &ast::StructDef,
+    def: hir::Struct,
+) -> Option<ast::ImplDef> {
+    let root = strukt.syntax().ancestors().last()?;
+    root.descendants().filter_map(ast::ImplDef::cast).find(|impl_def| {
+        impl_def.target_trait().is_none()
+            && impl_def.item_list().is_some()
+            && ctx
+                .sema
+                .to_def(impl_def)
+                .map_or(false, |it| it.target_ty(ctx.db).as_adt() == Some(Adt::Struct(def)))
+    })
+}
+
+fn existing_methods(impl_def: &ast::ImplDef) -> FxHashSet<String> {
+    impl_def
+        .item_list()
+        .into_iter()
+        .flat_map(|it| it.assoc_items())
+        .filter_map(|it| match it {
+            ast::AssocItem::FnDef(it) => Some(it.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{
+            check_assist, check_assist_not_applicable, check_assist_target,
+            check_assist_with_config, with_core,
+        },
+        AssistConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn generate_getters_and_setters() {
+        check_assist(
+            generate_accessors,
+            &with_core(
+                r#"
+struct Point<T> {
+    pub label: String,
+    x: u32,<|>
+    data: Vec<T>,
+}
+"#,
+            ),
+            r#"
+struct Point<T> {
+    pub label: String,
+    x: u32,
+    data: Vec<T>,
+}
+
+impl<T> Point<T> {
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn set_x(&mut self, x: u32) {
+        self.x = x;
+    }
+
+    pub fn data(&self) -> &Vec<T> {
+        &self.data
+    }
+
+    pub fn set_data(&mut self, data: Vec<T>) {
+        self.data = data;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_prefixed_getters_in_existing_impl() {
+        let config = AssistConfig { prefix_getters: true, ..AssistConfig::default() };
+        check_assist_with_config(
+            generate_accessors,
+            config,
+            &with_core(
+                r#"
+struct Counter<|> { count: usize, step: usize }
+
+impl Counter {
+    fn new() -> Counter { Counter { count: 0, step: 1 } }
+    pub fn set_step(&mut self, step: usize) { self.step = step }
+}
+"#,
+            ),
+            r#"
+struct Counter { count: usize, step: usize }
+
+impl Counter {
+    fn new() -> Counter { Counter { count: 0, step: 1 } }
+    pub fn set_step(&mut self, step: usize) { self.step = step }
+
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn set_count(&mut self, count: usize) {
+        self.count = count;
+    }
+
+    pub fn get_step(&self) -> usize {
+        self.step
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_accessors, "struct S<|>(u32);");
+        check_assist_not_applicable(generate_accessors, "struct S<|> { pub a: u32 }");
+        check_assist_not_applicable(
+            generate_accessors,
+            "struct S<|> { a: u32 }\nimpl S { fn a(&self) -> u32 { self.a } fn set_a(&mut self) {} }",
+        );
+    }
+
+    #[test]
+    fn generate_accessors_target() {
+        check_assist_target(
+            generate_accessors,
+            "struct S { <|>a: u32 }\nfn f() {}",
+            "struct S { a: u32 }",
+        );
+    }
+}
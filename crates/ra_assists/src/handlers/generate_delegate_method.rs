@@ -0,0 +1,297 @@
+use hir::{Adt, AssocItem, HasSource, HasVisibility, ImplDef};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxNode,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{field_at_cursor, impl_type_params, nominal_self_ty},
+    AssistContext, AssistId, Assists, GroupLabel,
+};
+
+// Assist: generate_delegate_method
+//
+// Generates an inherent method forwarding to a method of the field under the
+// cursor. One assist is offered for each inherent method of the field's type,
+// which isn't defined by the outer type yet.
+//
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 { self.0 }
+// }
+// struct Person {
+//     ag<|>e: Age,
+// }
+// ```
+// ->
+// ```
+// struct Age(u8);
+// impl Age {
+//     fn age(&self) -> u8 { self.0 }
+// }
+// struct Person {
+//     age: Age,
+// }
+//
+// impl Person {
+//     fn age(&self) -> u8 {
+//         self.age.age()
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_method(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (field_name, field_def, field_ty) = field_at_cursor(ctx)?;
+    let strukt = field_ty.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let name = strukt.name()?;
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let module = struct_def.module(ctx.db);
+    let adt = field_def?.signature_ty(ctx.db).as_adt()?;
+
+    let inherent_methods = |adt: Adt| {
+        ImplDef::all_in_crate(ctx.db, adt.module(ctx.db).krate())
+            .into_iter()
+            .filter(|imp| imp.target_trait(ctx.db).is_none())
+            .filter(move |imp| imp.target_ty(ctx.db).as_adt() == Some(adt))
+            .flat_map(|imp| imp.items(ctx.db))
+            .filter_map(|item| match item {
+                AssocItem::Function(it) => Some(it),
+                _ => None,
+            })
+    };
+    let existing: Vec<String> =
+        inherent_methods(Adt::Struct(struct_def)).map(|it| it.name(ctx.db).to_string()).collect();
+
+    let group = GroupLabel(format!("Generate delegation methods for `{}`", field_name));
+    let target = field_ty.syntax().parent()?.text_range();
+    for method in inherent_methods(adt) {
+        let method_name = method.name(ctx.db).to_string();
+        if !method.has_self_param(ctx.db)
+            || !method.is_visible_from(ctx.db, module)
+            || existing.contains(&method_name)
+        {
+            continue;
+        }
+        // The type parameters of a generic impl would have to be substituted.
+        let fn_def = method.source(ctx.db).value;
+        let has_impl_type_params = fn_def
+            .syntax()
+            .ancestors()
+            .find_map(ast::ImplDef::cast)
+            .and_then(|it| it.type_param_list())
+            .map_or(false, |it| it.type_params().next().is_some());
+        if has_impl_type_params {
+            continue;
+        }
+        let delegate = match delegate_fn(&fn_def, &field_name, &field_ty) {
+            Some(it) => it,
+            None => continue,
+        };
+        acc.add_group(
+            &group,
+            AssistId("generate_delegate_method"),
+            format!("Generate delegate for `{}.{}()`", field_name, method_name),
+            target,
+            |builder| {
+                let nominal = ast::NominalDef::StructDef(strukt.clone());
+                let mut buf = format!(
+                    "\n\nimpl{} {}",
+                    impl_type_params(&nominal),
+                    nominal_self_ty(&nominal, &name)
+                );
+                if let Some(where_clause) = strukt.where_clause() {
+                    format_to!(buf, "\n{}\n{{", where_clause.syntax());
+                } else {
+                    buf.push_str(" {");
+                }
+                format_to!(buf, "\n    {}\n}}", delegate);
+                builder.insert(strukt.syntax().text_range().end(), buf);
+            },
+        );
+    }
+    Some(())
+}
+
+/// Returns the text of a method with the signature of `fn_def`, which calls it
+/// on `self.<field_name>`.
+fn delegate_fn(fn_def: &ast::FnDef, field_name: &str, field_ty: &ast::TypeRef) -> Option<String> {
+    if fn_def.unsafe_token().is_some() || fn_def.abi().is_some() {
+        return None;
+    }
+    let param_list = fn_def.param_list()?;
+    let self_param = param_list.self_param()?;
+    // Receivers like `self: Box<Self>` can't be borrowed from the field.
+    if self_param.ascribed_type().is_some() {
+        return None;
+    }
+    // `Self` refers to the type of the field in the signature of the method.
+    let replace_self = |node: &SyntaxNode| {
+        node.descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .map(|it| if it.text() == "Self" { field_ty.to_string() } else { it.to_string() })
+            .collect::<String>()
+    };
+
+    let mut params = vec![self_param.syntax().to_string()];
+    let mut args = Vec::new();
+    for (idx, param) in param_list.params().enumerate() {
+        let ty = replace_self(param.ascribed_type()?.syntax());
+        // Patterns, which don't bind a single name, are replaced with one.
+        let arg = match param.pat()? {
+            ast::Pat::BindPat(it) if it.ref_token().is_none() && it.pat().is_none() => {
+                it.name()?.to_string()
+            }
+            _ => format!("arg{}", idx),
+        };
+        params.push(format!("{}: {}", arg, ty));
+        args.push(arg);
+    }
+
+    let mut buf = String::new();
+    if let Some(visibility) = fn_def.visibility() {
+        format_to!(buf, "{} ", visibility);
+    }
+    if fn_def.async_token().is_some() {
+        buf.push_str("async ");
+    }
+    format_to!(buf, "fn {}", fn_def.name()?);
+    if let Some(type_params) = fn_def.type_param_list() {
+        buf.push_str(&replace_self(type_params.syntax()));
+    }
+    format_to!(buf, "({})", params.iter().sep_by(", "));
+    if let Some(ret_type) = fn_def.ret_type() {
+        format_to!(buf, " {}", replace_self(ret_type.syntax()));
+    }
+    if let Some(where_clause) = fn_def.where_clause() {
+        format_to!(buf, " {}", replace_self(where_clause.syntax()));
+    }
+    format_to!(
+        buf,
+        " {{\n        self.{}.{}({}){}\n    }}",
+        field_name,
+        fn_def.name()?,
+        args.iter().sep_by(", "),
+        if fn_def.async_token().is_some() { ".await" } else { "" }
+    );
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn delegate_method_with_params_and_self_type() {
+        // `check_assist` picks the last assist of the group.
+        check_assist(
+            generate_delegate_method,
+            r#"
+struct Buf { len: usize }
+impl Buf {
+    pub fn len(&self) -> usize { self.len }
+    pub fn merge<'a, U: Into<Self>>(&'a mut self, (a, _): (Self, u8), mut other: U) -> &'a Self {
+        self
+    }
+    fn new() -> Buf { Buf { len: 0 } }
+}
+struct Lines<T: Clone>(Buf<|>, T);
+impl<T: Clone> Lines<T> {
+    fn len(&self) -> usize { 0 }
+}
+"#,
+            r#"
+struct Buf { len: usize }
+impl Buf {
+    pub fn len(&self) -> usize { self.len }
+    pub fn merge<'a, U: Into<Self>>(&'a mut self, (a, _): (Self, u8), mut other: U) -> &'a Self {
+        self
+    }
+    fn new() -> Buf { Buf { len: 0 } }
+}
+struct Lines<T: Clone>(Buf, T);
+
+impl<T: Clone> Lines<T> {
+    pub fn merge<'a, U: Into<Buf>>(&'a mut self, arg0: (Buf, u8), other: U) -> &'a Buf {
+        self.0.merge(arg0, other)
+    }
+}
+impl<T: Clone> Lines<T> {
+    fn len(&self) -> usize { 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delegate_async_method() {
+        check_assist(
+            generate_delegate_method,
+            r#"
+mod net {
+    pub struct Socket;
+    impl Socket {
+        pub async fn send(self, buf: &[u8]) -> usize { 0 }
+        fn close(&mut self) {}
+    }
+}
+struct Client { socket: <|>net::Socket }
+"#,
+            r#"
+mod net {
+    pub struct Socket;
+    impl Socket {
+        pub async fn send(self, buf: &[u8]) -> usize { 0 }
+        fn close(&mut self) {}
+    }
+}
+struct Client { socket: net::Socket }
+
+impl Client {
+    pub async fn send(self, buf: &[u8]) -> usize {
+        self.socket.send(buf).await
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_delegate_method, "struct Wrapper<T> { inner: <|>T }");
+        check_assist_not_applicable(
+            generate_delegate_method,
+            r#"
+struct Inner;
+impl Inner {
+    fn new() -> Inner { Inner }
+    fn get(&self) {}
+    unsafe fn get_unchecked(&self) {}
+}
+trait Get { fn get_twice(&self); }
+impl Get for Inner { fn get_twice(&self) {} }
+struct Outer { inner: <|>Inner }
+impl Outer { fn get(&self) {} }
+"#,
+        );
+        check_assist_not_applicable(
+            generate_delegate_method,
+            r#"
+struct Inner<T>(T);
+impl<T> Inner<T> { fn get(&self) -> &T { &self.0 } }
+struct Outer { inner: <|>Inner<u32> }
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_delegate_method_target() {
+        check_assist_target(
+            generate_delegate_method,
+            "struct Inner; impl Inner { fn f(&self) {} } struct Outer { inner: Inner<|> }",
+            "inner: Inner",
+        );
+    }
+}
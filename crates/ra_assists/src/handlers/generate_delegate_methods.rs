@@ -0,0 +1,325 @@
+use hir::{Adt, AssocItem, ImplDef};
+use ra_syntax::ast::{self, AstNode, NameOwner, TypeParamsOwner};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{field_at_cursor, has_bound, impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists, GroupLabel,
+};
+
+// Assist: generate_delegate_methods
+//
+// Generates inherent methods forwarding the interface of a known trait, like
+// `Iterator`, `Read`, `Write` or `Seek`, to the field under the cursor,
+// without implementing the trait for the outer type.
+//
+// ```
+// struct Numbers<I: Iterator> {
+//     inner: I<|>,
+// }
+// ```
+// ->
+// ```
+// struct Numbers<I: Iterator> {
+//     inner: I,
+// }
+//
+// impl<I: Iterator> Numbers<I> {
+//     pub fn next(&mut self) -> Option<<I as Iterator>::Item> {
+//         Iterator::next(&mut self.inner)
+//     }
+//
+//     pub fn size_hint(&self) -> (usize, Option<usize>) {
+//         Iterator::size_hint(&self.inner)
+//     }
+// }
+// ```
+pub(crate) fn generate_delegate_methods(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (field_name, field_def, field_ty) = field_at_cursor(ctx)?;
+    let strukt = field_ty.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let name = strukt.name()?;
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let krate = struct_def.module(ctx.db).krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+
+    let existing: Vec<String> = ImplDef::all_in_crate(ctx.db, krate)
+        .into_iter()
+        .filter(|imp| imp.target_trait(ctx.db).is_none())
+        .filter(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(struct_def)))
+        .flat_map(|imp| imp.items(ctx.db))
+        .filter_map(|item| match item {
+            AssocItem::Function(it) => Some(it.name(ctx.db).to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let group = GroupLabel(format!("Generate delegation methods for `{}`", field_name));
+    let target = field_ty.syntax().parent()?.text_range();
+    for known_trait in KnownTrait::ALL.iter().copied() {
+        let implements = match (field_def, known_trait.find(&famous_defs)) {
+            (Some(def), Some(trait_)) => def.signature_ty(ctx.db).impls_trait(ctx.db, trait_, &[]),
+            _ => false,
+        };
+        if !implements && !has_bound(&strukt, &field_ty, known_trait.name()) {
+            continue;
+        }
+        let methods: Vec<&Method> = known_trait
+            .methods()
+            .iter()
+            .filter(|method| !existing.iter().any(|it| it == method.name))
+            .collect();
+        if methods.is_empty() {
+            continue;
+        }
+        acc.add_group(
+            &group,
+            AssistId("generate_delegate_methods"),
+            format!("Generate `{}` delegation methods for `{}`", known_trait.name(), field_name),
+            target,
+            |builder| {
+                let nominal = ast::NominalDef::StructDef(strukt.clone());
+                let mut buf = format!(
+                    "\n\nimpl{} {}",
+                    impl_type_params(&nominal),
+                    nominal_self_ty(&nominal, &name)
+                );
+                if let Some(where_clause) = strukt.where_clause() {
+                    format_to!(buf, "\n{}\n{{", where_clause.syntax());
+                } else {
+                    buf.push_str(" {");
+                }
+                let item = format!("<{} as Iterator>::Item", field_ty.syntax());
+                let methods = methods.iter().map(|method| {
+                    let borrow = if method.receiver == "&self" { "&" } else { "&mut " };
+                    format!(
+                        "\n    pub fn {0}({1}{2}) -> {3} {{\n        {4}::{0}({5}self.{6}{7})\n    }}\n",
+                        method.name,
+                        method.receiver,
+                        method.params,
+                        method.ret.replace("{item}", &item),
+                        known_trait.path(),
+                        borrow,
+                        field_name,
+                        method.args,
+                    )
+                });
+                format_to!(buf, "{}}}", methods.sep_by(""));
+                builder.insert(strukt.syntax().text_range().end(), buf);
+            },
+        );
+    }
+    Some(())
+}
+
+/// A method of a trait interface, forwarded by a generated inherent method.
+struct Method {
+    name: &'static str,
+    receiver: &'static str,
+    /// The parameters after the receiver, with a leading `, `.
+    params: &'static str,
+    /// The arguments after the receiver, with a leading `, `.
+    args: &'static str,
+    /// The return type, with `{item}` standing for the item of an iterator.
+    ret: &'static str,
+}
+
+impl Method {
+    const fn new(
+        name: &'static str,
+        receiver: &'static str,
+        params: &'static str,
+        args: &'static str,
+        ret: &'static str,
+    ) -> Method {
+        Method { name, receiver, params, args, ret }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum KnownTrait {
+    Iterator,
+    Read,
+    Write,
+    Seek,
+}
+
+impl KnownTrait {
+    const ALL: [KnownTrait; 4] =
+        [KnownTrait::Iterator, KnownTrait::Read, KnownTrait::Write, KnownTrait::Seek];
+
+    fn name(self) -> &'static str {
+        match self {
+            KnownTrait::Iterator => "Iterator",
+            KnownTrait::Read => "Read",
+            KnownTrait::Write => "Write",
+            KnownTrait::Seek => "Seek",
+        }
+    }
+
+    /// The path used to call the methods, which doesn't require the trait to
+    /// be in scope.
+    fn path(self) -> &'static str {
+        match self {
+            KnownTrait::Iterator => "Iterator",
+            KnownTrait::Read => "std::io::Read",
+            KnownTrait::Write => "std::io::Write",
+            KnownTrait::Seek => "std::io::Seek",
+        }
+    }
+
+    fn find(self, famous_defs: &FamousDefs) -> Option<hir::Trait> {
+        match self {
+            KnownTrait::Iterator => famous_defs.core_iter_Iterator(),
+            KnownTrait::Read => famous_defs.std_io_Read(),
+            KnownTrait::Write => famous_defs.std_io_Write(),
+            KnownTrait::Seek => famous_defs.std_io_Seek(),
+        }
+    }
+
+    fn methods(self) -> &'static [Method] {
+        const ITERATOR: &[Method] = &[
+            Method::new("next", "&mut self", "", "", "Option<{item}>"),
+            Method::new("size_hint", "&self", "", "", "(usize, Option<usize>)"),
+        ];
+        const READ: &[Method] = &[
+            Method::new("read", "&mut self", ", buf: &mut [u8]", ", buf", "std::io::Result<usize>"),
+            Method::new(
+                "read_to_end",
+                "&mut self",
+                ", buf: &mut Vec<u8>",
+                ", buf",
+                "std::io::Result<usize>",
+            ),
+            Method::new(
+                "read_to_string",
+                "&mut self",
+                ", buf: &mut String",
+                ", buf",
+                "std::io::Result<usize>",
+            ),
+            Method::new(
+                "read_exact",
+                "&mut self",
+                ", buf: &mut [u8]",
+                ", buf",
+                "std::io::Result<()>",
+            ),
+        ];
+        const WRITE: &[Method] = &[
+            Method::new("write", "&mut self", ", buf: &[u8]", ", buf", "std::io::Result<usize>"),
+            Method::new("flush", "&mut self", "", "", "std::io::Result<()>"),
+            Method::new("write_all", "&mut self", ", buf: &[u8]", ", buf", "std::io::Result<()>"),
+        ];
+        const SEEK: &[Method] = &[Method::new(
+            "seek",
+            "&mut self",
+            ", pos: std::io::SeekFrom",
+            ", pos",
+            "std::io::Result<u64>",
+        )];
+        match self {
+            KnownTrait::Iterator => ITERATOR,
+            KnownTrait::Read => READ,
+            KnownTrait::Write => WRITE,
+            KnownTrait::Seek => SEEK,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const STD: &str = r"
+//- /libstd.rs crate:std
+pub mod io {
+    pub trait Read {}
+    pub trait Write {}
+    pub trait Seek {}
+    pub struct File;
+    impl Read for File {}
+    impl Write for File {}
+    impl Seek for File {}
+}
+";
+
+    fn with_std(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:std\n{}{}", ra_fixture.trim_end(), STD)
+    }
+
+    #[test]
+    fn generate_seek_methods_for_tuple_field() {
+        // `check_assist` picks the last assist of the group.
+        check_assist(
+            generate_delegate_methods,
+            &with_std("use std::io::File;\nstruct Tracked(usize, File<|>);"),
+            r"use std::io::File;
+struct Tracked(usize, File);
+
+impl Tracked {
+    pub fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        std::io::Seek::seek(&mut self.1, pos)
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn generate_iterator_methods_skips_existing_methods() {
+        check_assist(
+            generate_delegate_methods,
+            r"
+struct Lines<'a, I> where I: Iterator<Item = &'a str> {
+    inner: <|>I,
+}
+impl<'a, I> Lines<'a, I> where I: Iterator<Item = &'a str> {
+    pub fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+}",
+            r"
+struct Lines<'a, I> where I: Iterator<Item = &'a str> {
+    inner: I,
+}
+
+impl<'a, I> Lines<'a, I>
+where I: Iterator<Item = &'a str>
+{
+    pub fn next(&mut self) -> Option<<I as Iterator>::Item> {
+        Iterator::next(&mut self.inner)
+    }
+}
+impl<'a, I> Lines<'a, I> where I: Iterator<Item = &'a str> {
+    pub fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+}",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            generate_delegate_methods,
+            &with_std("struct Counter { count: <|>usize }"),
+        );
+        check_assist_not_applicable(
+            generate_delegate_methods,
+            r"
+struct Wrapper<W: Write> { inner: <|>W }
+impl<W: Write> Wrapper<W> {
+    fn write() {}
+    fn flush() {}
+    fn write_all() {}
+}",
+        );
+    }
+
+    #[test]
+    fn generate_delegate_methods_target() {
+        check_assist_target(
+            generate_delegate_methods,
+            &with_std("use std::io::File;\nstruct Logged { file: File<|>, count: usize }"),
+            "file: File",
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use ra_syntax::{
+    ast::{
+        self, edit::IndentLevel, AstNode, AttrsOwner, NameOwner, StructKind, TypeAscriptionOwner,
+        TypeParamsOwner,
+    },
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{utils::derive_paths, AssistContext, AssistId, Assists};
+
+// Assist: generate_deserialize_impl
+//
+// Replaces `#[derive(Deserialize)]` of a struct with a manual impl, which
+// leaves room to validate the fields in `visit_map`.
+//
+// ```
+// #[derive(Debug, <|>Deserialize)]
+// struct Port { number: u16 }
+// ```
+// ->
+// ```
+// #[derive(Debug)]
+// struct Port { number: u16 }
+//
+// impl<'de> serde::Deserialize<'de> for Port {
+//     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+//     where
+//         D: serde::Deserializer<'de>,
+//     {
+//         struct PortVisitor;
+//
+//         impl<'de> serde::de::Visitor<'de> for PortVisitor {
+//             type Value = Port;
+//
+//             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+//                 formatter.write_str("struct Port")
+//             }
+//
+//             fn visit_map<A>(self, mut map: A) -> Result<Port, A::Error>
+//             where
+//                 A: serde::de::MapAccess<'de>,
+//             {
+//                 let mut number = None;
+//                 while let Some(key) = map.next_key::<String>()? {
+//                     match key.as_str() {
+//                         "number" => number = Some(map.next_value()?),
+//                         _ => {
+//                             map.next_value::<serde::de::IgnoredAny>()?;
+//                         }
+//                     }
+//                 }
+//                 let number: u16 =
+//                     number.ok_or_else(|| serde::de::Error::missing_field("number"))?;
+//
+//                 ${0:todo!("validate the fields")};
+//                 Ok(Port { number })
+//             }
+//         }
+//
+//         const FIELDS: &[&str] = &["number"];
+//         deserializer.deserialize_struct("Port", FIELDS, PortVisitor)
+//     }
+// }
+// ```
+pub(crate) fn generate_deserialize_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let (derive, derives) = strukt.attrs().find_map(|attr| {
+        let (name, tt) = attr.as_simple_call()?;
+        if name != "derive" {
+            return None;
+        }
+        let derives = derive_paths(&tt);
+        if derives.iter().any(|it| is_deserialize(it)) {
+            Some((attr, derives))
+        } else {
+            None
+        }
+    })?;
+    // The generated impl can't honor `#[serde(...)]` attributes or generics.
+    if strukt.type_param_list().is_some() || has_serde_attrs(&strukt) {
+        return None;
+    }
+    let name = strukt.name()?;
+    let fields: Vec<(ast::Name, ast::TypeRef)> = match strukt.kind() {
+        StructKind::Record(it) => {
+            it.fields().map(|it| Some((it.name()?, it.ascribed_type()?))).collect::<Option<_>>()?
+        }
+        _ => return None,
+    };
+
+    let target = derive.syntax().text_range();
+    acc.add(
+        AssistId("generate_deserialize_impl"),
+        "Replace `#[derive(Deserialize)]` with a manual impl",
+        target,
+        |builder| {
+            let remaining = derives.iter().filter(|it| !is_deserialize(it)).collect::<Vec<_>>();
+            if remaining.is_empty() {
+                let mut range = derive.syntax().text_range();
+                if let Some(ws) =
+                    derive.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+                {
+                    range = TextRange::new(range.start(), ws.text_range().end());
+                }
+                builder.delete(range);
+            } else if let Some((_, tt)) = derive.as_simple_call() {
+                builder.replace(
+                    tt.syntax().text_range(),
+                    format!("({})", remaining.iter().sep_by(", ")),
+                );
+            }
+
+            let indent = IndentLevel::from_node(strukt.syntax());
+            let buf = deserialize_impl(&name, &fields);
+            let buf = buf
+                .lines()
+                .map(|it| if it.is_empty() { String::new() } else { format!("{}{}", indent, it) })
+                .sep_by("\n")
+                .to_string();
+            let offset = strukt.syntax().text_range().end();
+            let todo = "todo!(\"validate the fields\")";
+            let buf = format!("\n\n{}", buf);
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(
+                    cap,
+                    offset,
+                    buf.replace("$0", &format!("${{0:{}}}", todo)),
+                ),
+                None => builder.insert(offset, buf.replace("$0", todo)),
+            }
+        },
+    )
+}
+
+fn deserialize_impl(name: &ast::Name, fields: &[(ast::Name, ast::TypeRef)]) -> String {
+    let mut buf = String::new();
+    format_to!(buf, "impl<'de> serde::Deserialize<'de> for {} {{\n", name);
+    buf.push_str("    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>\n");
+    buf.push_str("    where\n        D: serde::Deserializer<'de>,\n    {\n");
+    format_to!(buf, "        struct {}Visitor;\n\n", name);
+    format_to!(buf, "        impl<'de> serde::de::Visitor<'de> for {}Visitor {{\n", name);
+    format_to!(buf, "            type Value = {};\n\n", name);
+    buf.push_str(
+        "            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {\n",
+    );
+    format_to!(buf, "                formatter.write_str(\"struct {}\")\n", name);
+    buf.push_str("            }\n\n");
+    format_to!(
+        buf,
+        "            fn visit_map<A>(self, mut map: A) -> Result<{}, A::Error>\n",
+        name
+    );
+    buf.push_str(
+        "            where\n                A: serde::de::MapAccess<'de>,\n            {\n",
+    );
+    for (field, _) in fields {
+        format_to!(buf, "                let mut {} = None;\n", field);
+    }
+    buf.push_str("                while let Some(key) = map.next_key::<String>()? {\n");
+    buf.push_str("                    match key.as_str() {\n");
+    for (field, _) in fields {
+        format_to!(
+            buf,
+            "                        \"{0}\" => {0} = Some(map.next_value()?),\n",
+            field
+        );
+    }
+    buf.push_str("                        _ => {\n");
+    buf.push_str("                            map.next_value::<serde::de::IgnoredAny>()?;\n");
+    buf.push_str("                        }\n                    }\n                }\n");
+    for (field, ty) in fields {
+        format_to!(
+            buf,
+            "                let {0}: {1} =\n                    {0}.ok_or_else(|| serde::de::Error::missing_field(\"{0}\"))?;\n",
+            field,
+            ty.syntax()
+        );
+    }
+    buf.push_str("\n                $0;\n");
+    format_to!(
+        buf,
+        "                Ok({} {{ {} }})\n",
+        name,
+        fields.iter().map(|(field, _)| field.to_string()).sep_by(", ")
+    );
+    buf.push_str("            }\n        }\n\n");
+    format_to!(
+        buf,
+        "        const FIELDS: &[&str] = &[{}];\n",
+        fields.iter().map(|(field, _)| format!("\"{}\"", field)).sep_by(", ")
+    );
+    format_to!(buf, "        deserializer.deserialize_struct(\"{0}\", FIELDS, {0}Visitor)\n", name);
+    buf.push_str("    }\n}");
+    buf
+}
+
+fn is_deserialize(path: &str) -> bool {
+    path.rsplit("::").next().map_or(false, |it| it.trim() == "Deserialize")
+}
+
+fn has_serde_attrs(strukt: &ast::StructDef) -> bool {
+    let is_serde = |attr: ast::Attr| attr.simple_name().map_or(false, |it| it == "serde");
+    let field_attrs: Vec<ast::Attr> = match strukt.kind() {
+        StructKind::Record(it) => it.fields().flat_map(|it| it.attrs()).collect(),
+        _ => Vec::new(),
+    };
+    strukt.attrs().any(is_serde) || field_attrs.into_iter().any(is_serde)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_only_derive() {
+        check_assist(
+            generate_deserialize_impl,
+            r#"
+mod config {
+    /// Server settings.
+    #[derive(serde::Deserialize)]
+    pub struct Server {
+        pub host: String,
+        pub port: u16,<|>
+    }
+}
+"#,
+            r#"
+mod config {
+    /// Server settings.
+    pub struct Server {
+        pub host: String,
+        pub port: u16,
+    }
+
+    impl<'de> serde::Deserialize<'de> for Server {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct ServerVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for ServerVisitor {
+                type Value = Server;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("struct Server")
+                }
+
+                fn visit_map<A>(self, mut map: A) -> Result<Server, A::Error>
+                where
+                    A: serde::de::MapAccess<'de>,
+                {
+                    let mut host = None;
+                    let mut port = None;
+                    while let Some(key) = map.next_key::<String>()? {
+                        match key.as_str() {
+                            "host" => host = Some(map.next_value()?),
+                            "port" => port = Some(map.next_value()?),
+                            _ => {
+                                map.next_value::<serde::de::IgnoredAny>()?;
+                            }
+                        }
+                    }
+                    let host: String =
+                        host.ok_or_else(|| serde::de::Error::missing_field("host"))?;
+                    let port: u16 =
+                        port.ok_or_else(|| serde::de::Error::missing_field("port"))?;
+
+                    ${0:todo!("validate the fields")};
+                    Ok(Server { host, port })
+                }
+            }
+
+            const FIELDS: &[&str] = &["host", "port"];
+            deserializer.deserialize_struct("Server", FIELDS, ServerVisitor)
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            generate_deserialize_impl,
+            "#[derive(Serialize)]\nstruct S { x: u32<|> }",
+        );
+        check_assist_not_applicable(
+            generate_deserialize_impl,
+            "#[derive(Deserialize)]\nstruct S(u32<|>);",
+        );
+        check_assist_not_applicable(
+            generate_deserialize_impl,
+            "#[derive(Deserialize)]\nstruct S<T> { x: T<|> }",
+        );
+        check_assist_not_applicable(
+            generate_deserialize_impl,
+            "#[derive(Deserialize)]\nstruct S { #[serde(default)] x: u32<|> }",
+        );
+    }
+
+    #[test]
+    fn generate_deserialize_impl_target() {
+        check_assist_target(
+            generate_deserialize_impl,
+            "#[derive(Clone, Deserialize)]\nstruct S { x: u32<|> }",
+            "#[derive(Clone, Deserialize)]",
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use ra_syntax::ast::{
+    self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner,
+};
+use stdx::{format_to, to_upper_snake_case};
+
+use crate::{utils::int_repr, AssistContext, AssistId, Assists, GroupLabel};
+
+// Assist: generate_discriminant_constants
+//
+// Generates constants for the discriminants of a field-less enum, which can be
+// used in `const` contexts and patterns without casting. The constants are
+// either associated with the enum or defined next to it.
+//
+// ```
+// #[repr(u8)]
+// pub enum Opcode<|> {
+//     Load = 1,
+//     Store,
+// }
+// ```
+// ->
+// ```
+// #[repr(u8)]
+// pub enum Opcode {
+//     Load = 1,
+//     Store,
+// }
+//
+// impl Opcode {
+//     pub const LOAD: u8 = Opcode::Load as u8;
+//     pub const STORE: u8 = Opcode::Store as u8;
+// }
+// ```
+pub(crate) fn generate_discriminant_constants(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let name = enum_def.name()?;
+    if enum_def.type_param_list().is_some() {
+        return None;
+    }
+    let variants = enum_def.variant_list()?.variants().collect::<Vec<_>>();
+    if variants.iter().any(|it| it.field_def_list().is_some())
+        || variants.iter().all(|it| it.expr().is_none())
+    {
+        return None;
+    }
+    let variant_names = variants
+        .iter()
+        .map(|it| Some(it.name()?.text().to_string()))
+        .collect::<Option<Vec<_>>>()?;
+
+    let ty = int_repr(&enum_def).unwrap_or_else(|| "isize".to_string());
+    let vis = enum_def.visibility().map(|it| format!("{} ", it)).unwrap_or_default();
+    let indent = IndentLevel::from_node(enum_def.syntax());
+    let offset = enum_def.syntax().text_range().end();
+    let target = enum_def.syntax().text_range();
+    let group = GroupLabel(format!("Generate discriminant constants for `{}`", name));
+
+    // An associated constant named like a variant would be shadowed by it.
+    let assoc_names = variant_names.iter().map(|it| to_upper_snake_case(it)).collect::<Vec<_>>();
+    if assoc_names.iter().all(|it| !variant_names.contains(it)) {
+        acc.add_group(
+            &group,
+            AssistId("generate_discriminant_constants"),
+            "Generate associated discriminant constants",
+            target,
+            |builder| {
+                let mut buf = String::new();
+                format_to!(buf, "\n\n{}impl {} {{\n", indent, name);
+                for (variant, const_name) in variant_names.iter().zip(&assoc_names) {
+                    format_to!(
+                        buf,
+                        "{}{}const {}: {} = {}::{} as {};\n",
+                        indent + 1,
+                        vis,
+                        const_name,
+                        ty,
+                        name,
+                        variant,
+                        ty
+                    );
+                }
+                format_to!(buf, "{}}}", indent);
+                builder.insert(offset, buf);
+            },
+        );
+    }
+
+    acc.add_group(
+        &group,
+        AssistId("generate_discriminant_constants"),
+        "Generate discriminant constants",
+        target,
+        |builder| {
+            let prefix = to_upper_snake_case(name.text());
+            let mut buf = String::from("\n");
+            for variant in &variant_names {
+                format_to!(
+                    buf,
+                    "\n{}{}const {}_{}: {} = {}::{} as {};",
+                    indent,
+                    vis,
+                    prefix,
+                    to_upper_snake_case(variant),
+                    ty,
+                    name,
+                    variant,
+                    ty
+                );
+            }
+            builder.insert(offset, buf);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_standalone_constants() {
+        check_assist(
+            generate_discriminant_constants,
+            r"
+mod codes {
+    enum StatusCode {
+        Ok = 200,<|>
+        NotFound = 404,
+    }
+}",
+            r"
+mod codes {
+    enum StatusCode {
+        Ok = 200,
+        NotFound = 404,
+    }
+
+    const STATUS_CODE_OK: isize = StatusCode::Ok as isize;
+    const STATUS_CODE_NOT_FOUND: isize = StatusCode::NotFound as isize;
+}",
+        );
+    }
+
+    #[test]
+    fn only_standalone_constants_for_upper_case_variants() {
+        check_assist(
+            generate_discriminant_constants,
+            "#[repr(i8)]\nenum Sign<|> { N = -1, P = 1 }",
+            "#[repr(i8)]\nenum Sign { N = -1, P = 1 }\n\nconst SIGN_N: i8 = Sign::N as i8;\nconst SIGN_P: i8 = Sign::P as i8;",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_discriminant_constants, "enum E<|> { A, B }");
+        check_assist_not_applicable(generate_discriminant_constants, "enum E<|> { A = 1, B(u32) }");
+        check_assist_not_applicable(generate_discriminant_constants, "struct S<|>;");
+    }
+
+    #[test]
+    fn generate_discriminant_constants_target() {
+        check_assist_target(
+            generate_discriminant_constants,
+            "enum E<|> { A = 1 }\nfn f() {}",
+            "enum E { A = 1 }",
+        );
+    }
+}
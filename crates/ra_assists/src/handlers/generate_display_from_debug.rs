@@ -0,0 +1,152 @@
+use hir::{Adt, ImplDef};
+use ra_syntax::ast::{self, AstNode, AttrsOwner, NameOwner, TypeParamsOwner};
+use stdx::format_to;
+
+use crate::{
+    utils::{derive_paths, impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: generate_display_from_debug
+//
+// Adds a `Display` impl, which prints the `Debug` output of a type. This is a
+// placeholder during development, which should be replaced with a
+// human-readable implementation.
+//
+// ```
+// #[derive(Debug)]
+// enum Status<|> { Active, Banned }
+// ```
+// ->
+// ```
+// #[derive(Debug)]
+// enum Status { Active, Banned }
+//
+// impl std::fmt::Display for Status {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         // FIXME: replace with a human-readable implementation before release.
+//         write!(f, "{:?}", self)
+//     }
+// }
+// ```
+pub(crate) fn generate_display_from_debug(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let name = nominal.name()?;
+    let derives_debug = nominal.attrs().any(|attr| match attr.as_simple_call() {
+        Some((name, tt)) if name == "derive" => derive_paths(&tt)
+            .iter()
+            .any(|it| it.rsplit("::").next().map_or(false, |it| it.trim() == "Debug")),
+        _ => false,
+    });
+    if !derives_debug {
+        return None;
+    }
+
+    let adt = match &nominal {
+        ast::NominalDef::StructDef(it) => Adt::Struct(ctx.sema.to_def(it)?),
+        ast::NominalDef::EnumDef(it) => Adt::Enum(ctx.sema.to_def(it)?),
+        ast::NominalDef::UnionDef(it) => Adt::Union(ctx.sema.to_def(it)?),
+    };
+    let krate = adt.module(ctx.db).krate();
+    if let Some(display_trait) = FamousDefs(&ctx.sema, krate).core_fmt_Display() {
+        let has_display_impl = ImplDef::for_trait(ctx.db, krate, display_trait)
+            .into_iter()
+            .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(adt));
+        if has_display_impl {
+            return None;
+        }
+    }
+
+    let target = nominal.syntax().text_range();
+    acc.add(
+        AssistId("generate_display_from_debug"),
+        "Generate `Display` impl from `Debug`",
+        target,
+        |builder| {
+            let mut buf = String::new();
+            format_to!(
+                buf,
+                "\n\nimpl{} std::fmt::Display for {}",
+                impl_type_params(&nominal),
+                nominal_self_ty(&nominal, &name)
+            );
+            // The derived `Debug` impl requires the type parameters to implement `Debug`.
+            let has_type_params =
+                nominal.type_param_list().map_or(false, |it| it.type_params().next().is_some());
+            if has_type_params {
+                buf.push_str("\nwhere\n    Self: std::fmt::Debug,\n");
+            } else {
+                buf.push(' ');
+            }
+            buf.push_str(
+                "{\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n",
+            );
+            buf.push_str(
+                "        // FIXME: replace with a human-readable implementation before release.\n",
+            );
+            buf.push_str("        write!(f, \"{:?}\", self)\n    }\n}");
+            builder.insert(target.end(), buf);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_for_generic_struct() {
+        check_assist(
+            generate_display_from_debug,
+            r#"
+#[derive(Clone, std::fmt::Debug)]
+struct Pair<'a, T: Clone><|>(&'a T, T);
+"#,
+            r#"
+#[derive(Clone, std::fmt::Debug)]
+struct Pair<'a, T: Clone>(&'a T, T);
+
+impl<'a, T: Clone> std::fmt::Display for Pair<'a, T>
+where
+    Self: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // FIXME: replace with a human-readable implementation before release.
+        write!(f, "{:?}", self)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_display_from_debug, "struct Id<|>(u32);");
+        check_assist_not_applicable(
+            generate_display_from_debug,
+            "#[derive(Clone)] struct Id<|>(u32);",
+        );
+        check_assist_not_applicable(
+            generate_display_from_debug,
+            r#"
+//- /main.rs crate:main deps:core
+#[derive(Debug)]
+struct Id<|>(u32);
+impl core::fmt::Display for Id {}
+//- /libcore.rs crate:core
+pub mod fmt { pub trait Display {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_display_from_debug_target() {
+        check_assist_target(
+            generate_display_from_debug,
+            "#[derive(Debug)]\nstruct Id<|>(u32);",
+            "#[derive(Debug)]\nstruct Id(u32);",
+        );
+    }
+}
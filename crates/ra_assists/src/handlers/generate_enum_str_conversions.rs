@@ -0,0 +1,190 @@
+use hir::ModuleDef;
+use ra_syntax::ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner};
+use stdx::format_to;
+
+use crate::{utils::FamousDefs, AssistContext, AssistId, Assists};
+
+// Assist: generate_enum_str_conversions
+//
+// Adds a `TryFrom<&str>` impl for a field-less enum, which parses the names of
+// the variants, and a `Display` impl, which writes them. With
+// `assist.caseInsensitiveStrConversions`, the ASCII case of the names is
+// ignored when parsing.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// enum Direction<|> { Up, Down }
+// # //- /libcore.rs crate:core
+// # pub mod convert { pub trait TryFrom<T> {} }
+// # pub mod fmt { pub trait Display {} }
+// ```
+// ->
+// ```
+// enum Direction { Up, Down }
+//
+// impl core::convert::TryFrom<&str> for Direction {
+//     type Error = ();
+//
+//     fn try_from(s: &str) -> Result<Self, Self::Error> {
+//         match s {
+//             "Up" => Ok(Self::Up),
+//             "Down" => Ok(Self::Down),
+//             _ => Err(()),
+//         }
+//     }
+// }
+//
+// impl core::fmt::Display for Direction {
+//     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+//         let s = match self {
+//             Self::Up => "Up",
+//             Self::Down => "Down",
+//         };
+//         f.write_str(s)
+//     }
+// }
+// ```
+pub(crate) fn generate_enum_str_conversions(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let name = enum_def.name()?;
+    if enum_def.type_param_list().is_some() {
+        return None;
+    }
+    let variants = enum_def
+        .variant_list()?
+        .variants()
+        .map(|it| match it.field_def_list() {
+            Some(_) => None,
+            None => Some(it.name()?.text().to_string()),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if variants.is_empty() {
+        return None;
+    }
+
+    let enum_ = ctx.sema.to_def(&enum_def)?;
+    let module = enum_.module(ctx.db);
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let try_from = famous_defs.core_convert_TryFrom()?;
+    let display = famous_defs.core_fmt_Display()?;
+    let try_from_path = module.find_use_path(ctx.db, ModuleDef::Trait(try_from))?;
+    let fmt_path = module.find_use_path(ctx.db, ModuleDef::Module(display.module(ctx.db)))?;
+    let has_display = enum_.ty(ctx.db).impls_trait(ctx.db, display, &[]);
+
+    let target = enum_def.syntax().text_range();
+    acc.add(
+        AssistId("generate_enum_str_conversions"),
+        format!("Generate conversions between `{}` and `&str`", name),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(enum_def.syntax());
+            let mut buf = String::new();
+            format_to!(buf, "\n\n{}impl {}<&str> for {} {{\n", indent, try_from_path, name);
+            format_to!(buf, "{}type Error = ();\n\n", indent + 1);
+            format_to!(buf, "{}fn try_from(s: &str) -> Result<Self, Self::Error> {{\n", indent + 1);
+            format_to!(buf, "{}match s {{\n", indent + 2);
+            for variant in &variants {
+                if ctx.config.case_insensitive_str_conversions {
+                    format_to!(
+                        buf,
+                        "{}_ if s.eq_ignore_ascii_case(\"{}\") => Ok(Self::{}),\n",
+                        indent + 3,
+                        variant,
+                        variant
+                    );
+                } else {
+                    format_to!(buf, "{}\"{}\" => Ok(Self::{}),\n", indent + 3, variant, variant);
+                }
+            }
+            format_to!(buf, "{}_ => Err(()),\n", indent + 3);
+            format_to!(buf, "{}}}\n{}}}\n{}}}", indent + 2, indent + 1, indent);
+
+            if !has_display {
+                format_to!(buf, "\n\n{}impl {}::Display for {} {{\n", indent, fmt_path, name);
+                format_to!(
+                    buf,
+                    "{}fn fmt(&self, f: &mut {}::Formatter<'_>) -> {}::Result {{\n",
+                    indent + 1,
+                    fmt_path,
+                    fmt_path
+                );
+                format_to!(buf, "{}let s = match self {{\n", indent + 2);
+                for variant in &variants {
+                    format_to!(buf, "{}Self::{} => \"{}\",\n", indent + 3, variant, variant);
+                }
+                format_to!(buf, "{}}};\n", indent + 2);
+                format_to!(buf, "{}f.write_str(s)\n", indent + 2);
+                format_to!(buf, "{}}}\n{}}}", indent + 1, indent);
+            }
+            builder.insert(target.end(), buf);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_assist_not_applicable, check_assist_with_config, with_core},
+        AssistConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn case_insensitive_without_display() {
+        let config =
+            AssistConfig { case_insensitive_str_conversions: true, ..AssistConfig::default() };
+        check_assist_with_config(
+            generate_enum_str_conversions,
+            config,
+            &with_core(
+                r#"
+use core::fmt;
+mod level {
+    pub enum Level<|> {
+        Warn,
+        Error,
+    }
+    impl super::fmt::Display for Level {}
+}
+"#,
+            ),
+            r#"
+use core::fmt;
+mod level {
+    pub enum Level {
+        Warn,
+        Error,
+    }
+
+    impl core::convert::TryFrom<&str> for Level {
+        type Error = ();
+
+        fn try_from(s: &str) -> Result<Self, Self::Error> {
+            match s {
+                _ if s.eq_ignore_ascii_case("Warn") => Ok(Self::Warn),
+                _ if s.eq_ignore_ascii_case("Error") => Ok(Self::Error),
+                _ => Err(()),
+            }
+        }
+    }
+    impl super::fmt::Display for Level {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            generate_enum_str_conversions,
+            &with_core("enum E<|> { A, B(u32) }"),
+        );
+        check_assist_not_applicable(generate_enum_str_conversions, &with_core("enum E<|> {}"));
+        check_assist_not_applicable(
+            generate_enum_str_conversions,
+            &with_core("enum E<|><T> { A }"),
+        );
+        check_assist_not_applicable(generate_enum_str_conversions, "enum E<|> { A }");
+    }
+}
@@ -0,0 +1,225 @@
+use hir::{Adt, AssocItem, HasSource, ImplDef, ModuleDef, Trait};
+use ra_syntax::ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner};
+use stdx::format_to;
+
+use crate::{
+    utils::{impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: generate_extend_impl
+//
+// Adds an `Extend` impl to a collection, which implements `IntoIterator` or
+// `Iterator`. The items are added with the `push` or `insert` method of the
+// collection.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// struct Stack<|> { items: Vec<u32> }
+// impl Stack {
+//     fn push(&mut self, item: u32) { self.items.push(item) }
+// }
+// impl core::iter::IntoIterator for Stack {
+//     type Item = u32;
+// }
+// # //- /libcore.rs crate:core
+// # pub mod iter { pub trait IntoIterator { type Item; } pub trait Extend<A> {} }
+// ```
+// ->
+// ```
+// struct Stack { items: Vec<u32> }
+//
+// impl core::iter::Extend<u32> for Stack {
+//     fn extend<T: core::iter::IntoIterator<Item = u32>>(&mut self, iter: T) {
+//         for item in iter {
+//             self.push(item);
+//         }
+//     }
+// }
+// impl Stack {
+//     fn push(&mut self, item: u32) { self.items.push(item) }
+// }
+// impl core::iter::IntoIterator for Stack {
+//     type Item = u32;
+// }
+// ```
+pub(crate) fn generate_extend_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    let def = ctx.sema.to_def(&strukt)?;
+    let module = def.module(ctx.db);
+    let krate = module.krate();
+    let famous_defs = FamousDefs(&ctx.sema, krate);
+    let into_iter_trait = famous_defs.core_iter_IntoIterator()?;
+    let extend_trait = famous_defs.core_iter_Extend()?;
+    if impl_for(ctx, def, extend_trait).is_some() {
+        return None;
+    }
+
+    let iter_impl = impl_for(ctx, def, into_iter_trait)
+        .or_else(|| impl_for(ctx, def, famous_defs.core_iter_Iterator()?))?;
+    let item_ty =
+        iter_impl.source(ctx.db).value.item_list()?.assoc_items().find_map(|it| match it {
+            ast::AssocItem::TypeAliasDef(it) if it.name()?.text() == "Item" => it.type_ref(),
+            _ => None,
+        })?;
+
+    let ty = def.ty(ctx.db);
+    let method = ["push", "insert"].iter().find(|method| {
+        ty.clone()
+            .iterate_assoc_items(ctx.db, krate, |item| match item {
+                AssocItem::Function(f) if f.name(ctx.db).to_string() == **method => Some(()),
+                _ => None,
+            })
+            .is_some()
+    })?;
+    let extend_path = module.find_use_path(ctx.db, ModuleDef::Trait(extend_trait))?;
+    let into_iter_path = module.find_use_path(ctx.db, ModuleDef::Trait(into_iter_trait))?;
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_extend_impl"),
+        format!("Generate `Extend<{}>` impl", item_ty),
+        target,
+        |builder| {
+            let nominal = ast::NominalDef::from(strukt.clone());
+            let indent = IndentLevel::from_node(strukt.syntax());
+            let where_clause =
+                strukt.where_clause().map(|it| format!(" {}", it)).unwrap_or_default();
+            // Avoid shadowing a type parameter of the collection.
+            let iter_param = if has_type_param(&strukt, "T") { "I" } else { "T" };
+
+            let mut buf = String::new();
+            format_to!(
+                buf,
+                "\n\n{}impl{} {}<{}> for {}{} {{\n",
+                indent,
+                impl_type_params(&nominal),
+                extend_path,
+                item_ty,
+                nominal_self_ty(&nominal, &name),
+                where_clause
+            );
+            format_to!(
+                buf,
+                "{}fn extend<{}: {}<Item = {}>>(&mut self, iter: {}) {{\n",
+                indent + 1,
+                iter_param,
+                into_iter_path,
+                item_ty,
+                iter_param
+            );
+            format_to!(buf, "{}for item in iter {{\n", indent + 2);
+            format_to!(buf, "{}self.{}(item);\n", indent + 3, method);
+            format_to!(buf, "{}}}\n{}}}\n{}}}", indent + 2, indent + 1, indent);
+            builder.insert(target.end(), buf);
+        },
+    )
+}
+
+fn impl_for(ctx: &AssistContext, def: hir::Struct, trait_: Trait) -> Option<ImplDef> {
+    let krate = def.module(ctx.db).krate();
+    ImplDef::for_trait(ctx.db, krate, trait_)
+        .into_iter()
+        .find(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(def)))
+}
+
+fn has_type_param(strukt: &ast::StructDef, name: &str) -> bool {
+    strukt.type_param_list().map_or(false, |it| {
+        it.type_params().any(|it| it.name().map_or(false, |it| it.text() == name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn generate_extend_for_generic_iterator() {
+        check_assist(
+            generate_extend_impl,
+            &with_core(
+                r#"
+mod queue {
+    pub struct Queue<T> where T: Clone<|> { items: Vec<T> }
+    impl<T: Clone> Queue<T> {
+        pub fn insert(&mut self, item: T) {}
+    }
+    impl<T: Clone> core::iter::Iterator for Queue<T> {
+        type Item = T;
+    }
+}
+"#,
+            ),
+            r#"
+mod queue {
+    pub struct Queue<T> where T: Clone { items: Vec<T> }
+
+    impl<T> core::iter::Extend<T> for Queue<T> where T: Clone {
+        fn extend<I: core::iter::IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.insert(item);
+            }
+        }
+    }
+    impl<T: Clone> Queue<T> {
+        pub fn insert(&mut self, item: T) {}
+    }
+    impl<T: Clone> core::iter::Iterator for Queue<T> {
+        type Item = T;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            generate_extend_impl,
+            &with_core(
+                r#"
+struct Stack<|>;
+impl Stack { fn push(&mut self, item: u32) {} }
+"#,
+            ),
+        );
+        check_assist_not_applicable(
+            generate_extend_impl,
+            &with_core(
+                r#"
+struct Stack<|>;
+impl core::iter::IntoIterator for Stack { type Item = u32; }
+"#,
+            ),
+        );
+        check_assist_not_applicable(
+            generate_extend_impl,
+            &with_core(
+                r#"
+struct Stack<|>;
+impl Stack { fn push(&mut self, item: u32) {} }
+impl core::iter::IntoIterator for Stack { type Item = u32; }
+impl core::iter::Extend<u32> for Stack {}
+"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn generate_extend_impl_target() {
+        check_assist_target(
+            generate_extend_impl,
+            &with_core(
+                r#"
+struct Stack<|>;
+impl Stack { fn push(&mut self, item: u32) {} }
+impl core::iter::IntoIterator for Stack { type Item = u32; }
+"#,
+            ),
+            "struct Stack;",
+        );
+    }
+}
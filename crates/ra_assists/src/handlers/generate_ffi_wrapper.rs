@@ -0,0 +1,212 @@
+use ra_syntax::ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeAscriptionOwner};
+use stdx::{format_to, SepBy};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: generate_ffi_wrapper
+//
+// Generates a safe wrapper for a function of an `extern` block. C strings are
+// taken as `&CStr`, other pointers as references, and integer return codes
+// are converted to a `Result`, where zero means success.
+//
+// ```
+// extern "C" {
+//     fn <|>set_name(handle: *mut Handle, name: *const c_char) -> c_int;
+// }
+// ```
+// ->
+// ```
+// pub fn safe_set_name(handle: &mut Handle, name: &std::ffi::CStr) -> Result<(), c_int> {
+//     // SAFETY: $0...
+//     let ret = unsafe { set_name(handle, name.as_ptr()) };
+//     if ret == 0 {
+//         Ok(())
+//     } else {
+//         Err(ret)
+//     }
+// }
+//
+// extern "C" {
+//     fn set_name(handle: *mut Handle, name: *const c_char) -> c_int;
+// }
+// ```
+pub(crate) fn generate_ffi_wrapper(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let extern_block = fn_def
+        .syntax()
+        .parent()
+        .and_then(ast::ExternItemList::cast)?
+        .syntax()
+        .parent()
+        .and_then(ast::ExternBlock::cast)?;
+    let name = fn_def.name()?;
+    let wrapper_name = format!("safe_{}", name);
+    let param_list = fn_def.param_list()?;
+
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for (idx, param) in param_list.params().enumerate() {
+        if param.dotdotdot_token().is_some() {
+            return None;
+        }
+        let param_name = match param.pat() {
+            Some(ast::Pat::BindPat(it)) => it.name()?.to_string(),
+            _ => format!("arg{}", idx),
+        };
+        let ty = param.ascribed_type()?;
+        let (param_ty, arg) = convert_param(&param_name, &ty);
+        params.push(format!("{}: {}", param_name, param_ty));
+        args.push(arg);
+    }
+    let call = format!("{}({})", name, args.into_iter().sep_by(", "));
+    let ret_ty = fn_def.ret_type().and_then(|it| it.type_ref());
+    let is_return_code = ret_ty.as_ref().map_or(false, is_int);
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("generate_ffi_wrapper"),
+        format!("Generate safe wrapper for `{}`", name),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(extern_block.syntax());
+            let safety = match ctx.config.snippet_cap {
+                Some(_) => "$0...",
+                None => "...",
+            };
+            let mut buf = String::new();
+            format_to!(buf, "pub fn {}({})", wrapper_name, params.iter().sep_by(", "));
+            match &ret_ty {
+                Some(ty) if is_return_code => format_to!(buf, " -> Result<(), {}>", ty),
+                Some(ty) => format_to!(buf, " -> {}", ty),
+                None => (),
+            }
+            format_to!(buf, " {{\n{}// SAFETY: {}\n", indent + 1, safety);
+            if is_return_code {
+                format_to!(buf, "{}let ret = unsafe {{ {} }};\n", indent + 1, call);
+                format_to!(buf, "{}if ret == 0 {{\n{}Ok(())\n", indent + 1, indent + 2);
+                format_to!(
+                    buf,
+                    "{}}} else {{\n{}Err(ret)\n{}}}\n",
+                    indent + 1,
+                    indent + 2,
+                    indent + 1
+                );
+            } else {
+                format_to!(buf, "{}unsafe {{ {} }}\n", indent + 1, call);
+            }
+            format_to!(buf, "{}}}\n\n{}", indent, indent);
+
+            let offset = extern_block.syntax().text_range().start();
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, buf),
+                None => builder.insert(offset, buf),
+            }
+        },
+    )
+}
+
+/// Returns the type of the wrapper's parameter for a parameter of type `ty`,
+/// and the argument passed on to the foreign function.
+fn convert_param(name: &str, ty: &ast::TypeRef) -> (String, String) {
+    if let ast::TypeRef::PointerType(ptr) = ty {
+        match ptr.type_ref() {
+            Some(pointee) if ptr.const_token().is_some() && is_named(&pointee, "c_char") => {
+                return ("&std::ffi::CStr".to_string(), format!("{}.as_ptr()", name));
+            }
+            Some(pointee) if !is_named(&pointee, "c_void") => {
+                let mutability = if ptr.mut_token().is_some() { "mut " } else { "" };
+                return (format!("&{}{}", mutability, pointee), name.to_string());
+            }
+            _ => (),
+        }
+    }
+    (ty.to_string(), name.to_string())
+}
+
+fn is_int(ty: &ast::TypeRef) -> bool {
+    ["c_int", "c_long", "i32", "i64", "isize"].iter().any(|it| is_named(ty, it))
+}
+
+fn is_named(ty: &ast::TypeRef, name: &str) -> bool {
+    match ty {
+        ast::TypeRef::PathType(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| it.text() == name),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn wrap_void_function_in_module() {
+        check_assist(
+            generate_ffi_wrapper,
+            r#"
+mod ffi {
+    extern "C" {
+        fn free_buffer(buf: *mut c_void, len: usize);<|>
+    }
+}
+"#,
+            r#"
+mod ffi {
+    pub fn safe_free_buffer(buf: *mut c_void, len: usize) {
+        // SAFETY: $0...
+        unsafe { free_buffer(buf, len) }
+    }
+
+    extern "C" {
+        fn free_buffer(buf: *mut c_void, len: usize);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_function_returning_pointer() {
+        check_assist(
+            generate_ffi_wrapper,
+            r#"
+extern "C" {
+    fn <|>strchr(s: *const libc::c_char, c: libc::c_int) -> *const libc::c_char;
+}
+"#,
+            r#"
+pub fn safe_strchr(s: &std::ffi::CStr, c: libc::c_int) -> *const libc::c_char {
+    // SAFETY: $0...
+    unsafe { strchr(s.as_ptr(), c) }
+}
+
+extern "C" {
+    fn strchr(s: *const libc::c_char, c: libc::c_int) -> *const libc::c_char;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_ffi_wrapper, "fn <|>f(x: i32) -> i32 { x }");
+        check_assist_not_applicable(
+            generate_ffi_wrapper,
+            r#"extern "C" { fn <|>printf(fmt: *const c_char, ...) -> c_int; }"#,
+        );
+    }
+
+    #[test]
+    fn generate_ffi_wrapper_target() {
+        check_assist_target(
+            generate_ffi_wrapper,
+            r#"extern "C" { fn <|>abort(); }"#,
+            "fn abort();",
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use hir::{ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, TypeParamsOwner},
+    SyntaxKind::{COMMA, IDENT, INT_NUMBER, STRING},
+    SyntaxToken, T,
+};
+use stdx::format_to;
+
+use crate::{utils::FamousDefs, AssistContext, AssistId, Assists};
+
+// Assist: generate_from_str_impl
+//
+// Adds a `FromStr` impl, which parses the output of the `Display` impl. If the
+// `Display` impl writes the fields of a struct with plain `{}` placeholders,
+// the fields are parsed, otherwise only the skeleton of the impl is generated.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// use core::fmt;
+// struct Point { x: i32, y: i32 }
+// impl fmt::Display for <|>Point {
+//     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//         write!(f, "{},{}", self.x, self.y)
+//     }
+// }
+// # //- /libcore.rs crate:core
+// # pub mod fmt { pub trait Display {} }
+// # pub mod str { pub trait FromStr {} }
+// ```
+// ->
+// ```
+// use core::fmt;
+// struct Point { x: i32, y: i32 }
+// impl fmt::Display for Point {
+//     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//         write!(f, "{},{}", self.x, self.y)
+//     }
+// }
+//
+// impl core::str::FromStr for Point {
+//     type Err = String;
+//
+//     /// Parses the format written by `Display`: `"{},{}"`.
+//     fn from_str(s: &str) -> Result<Self, Self::Err> {
+//         let end = s.find(",").ok_or_else(|| "expected `,`".to_string())?;
+//         let x = s[..end].parse().map_err(|_| "invalid `x`".to_string())?;
+//         let s = &s[end + ",".len()..];
+//         let y = s.parse().map_err(|_| "invalid `y`".to_string())?;
+//         Ok(Point { x, y })
+//     }
+// }
+// ```
+pub(crate) fn generate_from_str_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::ImplDef>()?;
+    if let Some(item_list) = impl_def.item_list() {
+        if item_list.syntax().text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    // The type parameters would need `FromStr` bounds.
+    if impl_def.type_param_list().is_some() {
+        return None;
+    }
+    let trait_path = match impl_def.target_trait()? {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let display = match ctx.sema.resolve_path(&trait_path)? {
+        PathResolution::Def(ModuleDef::Trait(it)) => it,
+        _ => return None,
+    };
+    let module = ctx.sema.scope(impl_def.syntax()).module()?;
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    if Some(display) != famous_defs.core_fmt_Display() {
+        return None;
+    }
+    let from_str = famous_defs.core_str_FromStr()?;
+    let self_ty = ctx.sema.to_def(&impl_def)?.target_ty(ctx.db);
+    if self_ty.impls_trait(ctx.db, from_str, &[]) {
+        return None;
+    }
+    let from_str_path = module.find_use_path(ctx.db, ModuleDef::Trait(from_str))?;
+    let self_ty_text = impl_def.target_type()?.syntax().to_string();
+
+    let format = impl_def
+        .syntax()
+        .descendants()
+        .filter_map(ast::MacroCall::cast)
+        .find(|it| it.path().map_or(false, |it| it.syntax().text() == "write"))
+        .and_then(|it| parse_write(&it));
+    let body = match (&format, self_ty.as_adt()) {
+        (Some(Format { pieces: Some(pieces), .. }), Some(hir::Adt::Struct(strukt))) => {
+            let fields = strukt.fields(ctx.db);
+            let is_tuple =
+                fields.first().map_or(false, |it| it.name(ctx.db).as_tuple_index().is_some());
+            let fields: Vec<String> = fields.iter().map(|it| it.name(ctx.db).to_string()).collect();
+            parse_body(pieces, &self_ty_text, &fields, is_tuple)
+        }
+        _ => None,
+    };
+
+    let target = impl_def.syntax().text_range();
+    acc.add(
+        AssistId("generate_from_str_impl"),
+        format!("Generate `FromStr` impl for `{}`", self_ty_text),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(impl_def.syntax());
+            let mut buf = String::new();
+            format_to!(buf, "\n\n{}impl {} for {} {{\n", indent, from_str_path, self_ty_text);
+            format_to!(buf, "{}type Err = String;\n\n", indent + 1);
+            if let Some(format) = &format {
+                format_to!(
+                    buf,
+                    "{}/// Parses the format written by `Display`: `{}`.\n",
+                    indent + 1,
+                    format.literal
+                );
+            }
+            format_to!(buf, "{}fn from_str(s: &str) -> Result<Self, Self::Err> {{\n", indent + 1);
+            match &body {
+                Some(lines) => {
+                    for line in lines {
+                        format_to!(buf, "{}{}\n", indent + 2, line);
+                    }
+                }
+                None => format_to!(buf, "{}todo!()\n", indent + 2),
+            }
+            format_to!(buf, "{}}}\n{}}}", indent + 1, indent);
+            builder.insert(target.end(), buf);
+        },
+    )
+}
+
+/// The format string of the `write!` call in a `Display` impl.
+struct Format {
+    literal: String,
+    /// Set if the format string only has plain `{}` placeholders, which write
+    /// fields of `self`.
+    pieces: Option<Pieces>,
+}
+
+struct Pieces {
+    /// The text before, between and after the placeholders, as written in the
+    /// string literal, but with unescaped braces.
+    separators: Vec<String>,
+    fields: Vec<String>,
+}
+
+fn parse_write(macro_call: &ast::MacroCall) -> Option<Format> {
+    let tokens: Vec<SyntaxToken> = macro_call
+        .token_tree()?
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+        .collect();
+    // Skips the delimiters and splits the arguments at the commas.
+    let inner = tokens.get(1..tokens.len().checked_sub(1)?)?;
+    let args: Vec<&[SyntaxToken]> = inner.split(|it| it.kind() == COMMA).collect();
+    let literal = match args.get(1)? {
+        [it] if it.kind() == STRING => it.text().to_string(),
+        _ => return None,
+    };
+    let pieces = parse_pieces(&literal, &args[2..]);
+    Some(Format { literal, pieces })
+}
+
+fn parse_pieces(literal: &str, args: &[&[SyntaxToken]]) -> Option<Pieces> {
+    let fields = args
+        .iter()
+        .filter(|it| !it.is_empty())
+        .map(|arg| match arg {
+            [receiver, dot, field]
+                if receiver.kind() == T![self]
+                    && dot.kind() == T![.]
+                    && matches!(field.kind(), IDENT | INT_NUMBER) =>
+            {
+                Some(field.text().to_string())
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let text = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut separators = vec![String::new()];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                separators.last_mut()?.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                separators.push(String::new());
+            }
+            // Format specs, positional and named arguments.
+            ('{', _) | ('}', _) => return None,
+            _ => separators.last_mut()?.push(c),
+        }
+    }
+    if separators.len() != fields.len() + 1 {
+        return None;
+    }
+    Some(Pieces { separators, fields })
+}
+
+/// Generates the statements of `from_str`, which parse the fields of the
+/// struct between the separators. Fails, unless every field is written once
+/// and the placeholders are separated by text.
+fn parse_body(
+    pieces: &Pieces,
+    self_ty: &str,
+    struct_fields: &[String],
+    is_tuple: bool,
+) -> Option<Vec<String>> {
+    let mut written = pieces.fields.clone();
+    written.sort();
+    let mut expected = struct_fields.to_vec();
+    expected.sort();
+    if written != expected || written.iter().any(|it| it == "s" || it == "end") {
+        return None;
+    }
+    let inner_separators = &pieces.separators[1..pieces.separators.len() - 1];
+    if inner_separators.iter().any(|it| it.is_empty()) {
+        return None;
+    }
+    let binding =
+        |field: &str| if is_tuple { format!("field{}", field) } else { field.to_string() };
+
+    let mut lines = Vec::new();
+    let first = &pieces.separators[0];
+    if !first.is_empty() {
+        lines.push(format!(
+            "let s = s.strip_prefix(\"{0}\").ok_or_else(|| \"expected `{0}`\".to_string())?;",
+            first
+        ));
+    }
+    for (field, next) in pieces.fields.iter().zip(pieces.separators[1..].iter()) {
+        if next.is_empty() {
+            lines.push(format!(
+                "let {} = s.parse().map_err(|_| \"invalid `{}`\".to_string())?;",
+                binding(field),
+                field
+            ));
+        } else {
+            lines.push(format!(
+                "let end = s.find(\"{0}\").ok_or_else(|| \"expected `{0}`\".to_string())?;",
+                next
+            ));
+            lines.push(format!(
+                "let {} = s[..end].parse().map_err(|_| \"invalid `{}`\".to_string())?;",
+                binding(field),
+                field
+            ));
+            lines.push(format!("let s = &s[end + \"{}\".len()..];", next));
+        }
+    }
+    if !pieces.separators.last()?.is_empty() || pieces.fields.is_empty() {
+        lines.push("if !s.is_empty() {".to_string());
+        lines.push("    return Err(format!(\"unexpected `{}`\", s));".to_string());
+        lines.push("}".to_string());
+    }
+    let bindings: Vec<String> = struct_fields.iter().map(|it| binding(it)).collect();
+    let value = if struct_fields.is_empty() {
+        self_ty.to_string()
+    } else if is_tuple {
+        format!("{}({})", self_ty, bindings.join(", "))
+    } else {
+        format!("{} {{ {} }}", self_ty, bindings.join(", "))
+    };
+    lines.push(format!("Ok({})", value));
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn generate_for_record_struct() {
+        check_assist(
+            generate_from_str_impl,
+            &with_core(
+                r#"use core::fmt::{self, Display};
+mod geometry {
+    pub struct Point { pub x: i32, pub y: i32 }
+    impl super::Display for Point<|> {
+        fn fmt(&self, f: &mut super::fmt::Formatter<'_>) -> super::fmt::Result {
+            write!(f, "({}, {}) {{px}}", self.y, self.x)
+        }
+    }
+}"#,
+            ),
+            r#"use core::fmt::{self, Display};
+mod geometry {
+    pub struct Point { pub x: i32, pub y: i32 }
+    impl super::Display for Point {
+        fn fmt(&self, f: &mut super::fmt::Formatter<'_>) -> super::fmt::Result {
+            write!(f, "({}, {}) {{px}}", self.y, self.x)
+        }
+    }
+
+    impl core::str::FromStr for Point {
+        type Err = String;
+
+        /// Parses the format written by `Display`: `"({}, {}) {{px}}"`.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.strip_prefix("(").ok_or_else(|| "expected `(`".to_string())?;
+            let end = s.find(", ").ok_or_else(|| "expected `, `".to_string())?;
+            let y = s[..end].parse().map_err(|_| "invalid `y`".to_string())?;
+            let s = &s[end + ", ".len()..];
+            let end = s.find(") {px}").ok_or_else(|| "expected `) {px}`".to_string())?;
+            let x = s[..end].parse().map_err(|_| "invalid `x`".to_string())?;
+            let s = &s[end + ") {px}".len()..];
+            if !s.is_empty() {
+                return Err(format!("unexpected `{}`", s));
+            }
+            Ok(Point { x, y })
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_for_tuple_struct() {
+        check_assist(
+            generate_from_str_impl,
+            &with_core(
+                r#"use core::fmt;
+struct Meters(f64);
+impl fmt::Display for Meters<|> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}m", self.0)
+    }
+}"#,
+            ),
+            r#"use core::fmt;
+struct Meters(f64);
+impl fmt::Display for Meters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}m", self.0)
+    }
+}
+
+impl core::str::FromStr for Meters {
+    type Err = String;
+
+    /// Parses the format written by `Display`: `"{}m"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let end = s.find("m").ok_or_else(|| "expected `m`".to_string())?;
+        let field0 = s[..end].parse().map_err(|_| "invalid `0`".to_string())?;
+        let s = &s[end + "m".len()..];
+        if !s.is_empty() {
+            return Err(format!("unexpected `{}`", s));
+        }
+        Ok(Meters(field0))
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_skeleton_for_format_specs() {
+        check_assist(
+            generate_from_str_impl,
+            &with_core(
+                r#"use core::fmt;
+struct Meters(f64);
+impl fmt::Display for <|>Meters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}m", self.0)
+    }
+}"#,
+            ),
+            r#"use core::fmt;
+struct Meters(f64);
+impl fmt::Display for Meters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}m", self.0)
+    }
+}
+
+impl core::str::FromStr for Meters {
+    type Err = String;
+
+    /// Parses the format written by `Display`: `"{:.2}m"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        todo!()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Not `Display`.
+        check_assist_not_applicable(
+            generate_from_str_impl,
+            &with_core("use core::fmt;\nstruct S;\nimpl fmt::Debug for <|>S {}"),
+        );
+        // `FromStr` is implemented.
+        check_assist_not_applicable(
+            generate_from_str_impl,
+            &with_core(
+                "use core::{fmt, str};\nstruct S;\nimpl fmt::Display for <|>S {}\nimpl str::FromStr for S {}",
+            ),
+        );
+        // Inside of the impl.
+        check_assist_not_applicable(
+            generate_from_str_impl,
+            &with_core("use core::fmt;\nstruct S;\nimpl fmt::Display for S { fn <|>fmt() {} }"),
+        );
+    }
+
+    #[test]
+    fn generate_from_str_impl_target() {
+        check_assist_target(
+            generate_from_str_impl,
+            &with_core("use core::fmt;\nstruct S;\nimpl fmt::Display for <|>S {}"),
+            "impl fmt::Display for S {}",
+        );
+    }
+}
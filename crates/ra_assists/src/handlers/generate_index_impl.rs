@@ -0,0 +1,273 @@
+use hir::ModuleDef;
+use ra_syntax::ast::{
+    self, edit::IndentLevel, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner,
+};
+use stdx::format_to;
+
+use crate::{utils::FamousDefs, AssistContext, AssistId, Assists};
+
+// Assist: generate_index_impl
+//
+// Adds `Index` and `IndexMut` impls, which delegate to the `get` and `get_mut`
+// methods of a type. Methods returning an `Option` are unwrapped, so that
+// indexing panics for invalid indices.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// struct Grid { cells: Vec<u8> }
+// impl Grid {
+//     fn <|>get(&self, pos: (usize, usize)) -> &u8 { &self.cells[pos.0 * 8 + pos.1] }
+// }
+// # //- /libcore.rs crate:core
+// # pub mod ops { pub trait Index<Idx> {} pub trait IndexMut<Idx> {} }
+// ```
+// ->
+// ```
+// struct Grid { cells: Vec<u8> }
+// impl Grid {
+//     fn get(&self, pos: (usize, usize)) -> &u8 { &self.cells[pos.0 * 8 + pos.1] }
+// }
+//
+// impl core::ops::Index<(usize, usize)> for Grid {
+//     type Output = u8;
+//
+//     fn index(&self, index: (usize, usize)) -> &Self::Output {
+//         self.get(index)
+//     }
+// }
+// ```
+pub(crate) fn generate_index_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let impl_def = fn_def.syntax().parent()?.parent().and_then(ast::ImplDef::cast)?;
+    if impl_def.target_trait().is_some() {
+        return None;
+    }
+    let methods: Vec<ast::FnDef> = impl_def
+        .item_list()?
+        .assoc_items()
+        .filter_map(|it| match it {
+            ast::AssocItem::FnDef(it) => Some(it),
+            _ => None,
+        })
+        .collect();
+    let find =
+        |name: &str| methods.iter().find(|it| it.name().map_or(false, |it| it.text() == name));
+    let get = Accessor::new(find("get")?, false)?;
+    if fn_def != get.fn_def && find("get_mut").map_or(true, |it| *it != fn_def) {
+        return None;
+    }
+    let get_mut = find("get_mut")
+        .and_then(|it| Accessor::new(it, true))
+        .filter(|it| it.index_ty.syntax().text() == get.index_ty.syntax().text());
+
+    let module = ctx.sema.scope(impl_def.syntax()).module()?;
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let index_trait = famous_defs.core_ops_Index()?;
+    let self_ty = ctx.sema.to_def(&impl_def)?.target_ty(ctx.db);
+    let index_ty = ctx.sema.type_of_pat(&get.index_pat)?;
+    if self_ty.impls_trait(ctx.db, index_trait, &[index_ty]) {
+        return None;
+    }
+    let index_path = module.find_use_path(ctx.db, ModuleDef::Trait(index_trait))?;
+    let index_mut_path = match &get_mut {
+        Some(_) => {
+            let index_mut_trait = famous_defs.core_ops_IndexMut()?;
+            Some(module.find_use_path(ctx.db, ModuleDef::Trait(index_mut_trait))?)
+        }
+        None => None,
+    };
+
+    let label = match get_mut {
+        Some(_) => "Generate `Index` and `IndexMut` impls",
+        None => "Generate `Index` impl",
+    };
+    let target = impl_def.syntax().text_range();
+    acc.add(AssistId("generate_index_impl"), label, target, |builder| {
+        let indent = IndentLevel::from_node(impl_def.syntax());
+        let type_params = impl_def.type_param_list().map(|it| it.to_string()).unwrap_or_default();
+        let where_clause = impl_def.where_clause().map(|it| format!(" {}", it)).unwrap_or_default();
+        let self_ty = impl_def.target_type().map(|it| it.to_string()).unwrap_or_default();
+        let index_ty = &get.index_ty;
+
+        let mut buf = String::new();
+        format_to!(
+            buf,
+            "\n\n{}impl{} {}<{}> for {}{} {{\n",
+            indent,
+            type_params,
+            index_path,
+            index_ty,
+            self_ty,
+            where_clause
+        );
+        format_to!(buf, "{}type Output = {};\n\n", indent + 1, get.output_ty);
+        format_to!(buf, "{}fn index(&self, index: {}) -> &Self::Output {{\n", indent + 1, index_ty);
+        format_to!(buf, "{}{}\n", indent + 2, get.call("get"));
+        format_to!(buf, "{}}}\n{}}}", indent + 1, indent);
+
+        if let (Some(get_mut), Some(index_mut_path)) = (&get_mut, &index_mut_path) {
+            format_to!(
+                buf,
+                "\n\n{}impl{} {}<{}> for {}{} {{\n",
+                indent,
+                type_params,
+                index_mut_path,
+                index_ty,
+                self_ty,
+                where_clause
+            );
+            format_to!(
+                buf,
+                "{}fn index_mut(&mut self, index: {}) -> &mut Self::Output {{\n",
+                indent + 1,
+                index_ty
+            );
+            format_to!(buf, "{}{}\n", indent + 2, get_mut.call("get_mut"));
+            format_to!(buf, "{}}}\n{}}}", indent + 1, indent);
+        }
+        builder.insert(target.end(), buf);
+    })
+}
+
+/// A `get` or `get_mut` method, which takes an index and returns a reference,
+/// possibly wrapped in an `Option`.
+struct Accessor {
+    fn_def: ast::FnDef,
+    index_pat: ast::Pat,
+    index_ty: ast::TypeRef,
+    output_ty: ast::TypeRef,
+    returns_option: bool,
+}
+
+impl Accessor {
+    fn new(fn_def: &ast::FnDef, is_mut: bool) -> Option<Accessor> {
+        if fn_def.type_param_list().is_some() {
+            return None;
+        }
+        let param_list = fn_def.param_list()?;
+        let self_param = param_list.self_param()?;
+        let expected_kind =
+            if is_mut { ast::SelfParamKind::MutRef } else { ast::SelfParamKind::Ref };
+        if self_param.kind() != expected_kind {
+            return None;
+        }
+        let mut params = param_list.params();
+        let param = params.next()?;
+        if params.next().is_some() {
+            return None;
+        }
+
+        let (ref_ty, returns_option) = match fn_def.ret_type()?.type_ref()? {
+            ast::TypeRef::ReferenceType(it) => (it, false),
+            ast::TypeRef::PathType(it) => {
+                let segment = it.path()?.segment()?;
+                if segment.name_ref()?.text() != "Option" {
+                    return None;
+                }
+                match segment.type_arg_list()?.type_args().next()?.type_ref()? {
+                    ast::TypeRef::ReferenceType(it) => (it, true),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        if ref_ty.mut_token().is_some() != is_mut {
+            return None;
+        }
+        Some(Accessor {
+            fn_def: fn_def.clone(),
+            index_pat: param.pat()?,
+            index_ty: param.ascribed_type()?,
+            output_ty: ref_ty.type_ref()?,
+            returns_option,
+        })
+    }
+
+    fn call(&self, name: &str) -> String {
+        if self.returns_option {
+            format!("self.{}(index).expect(\"index out of bounds\")", name)
+        } else {
+            format!("self.{}(index)", name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn generate_index_and_index_mut() {
+        check_assist(
+            generate_index_impl,
+            &with_core(
+                r#"
+use core::ops::Index;
+struct Slots<T> { items: Vec<T> }
+impl<T> Slots<T> where T: Clone {
+    pub fn get(&self, slot: usize) -> Option<&T> { self.items.get(slot) }
+    pub fn <|>get_mut(&mut self, slot: usize) -> Option<&mut T> { self.items.get_mut(slot) }
+}
+"#,
+            ),
+            r#"
+use core::ops::Index;
+struct Slots<T> { items: Vec<T> }
+impl<T> Slots<T> where T: Clone {
+    pub fn get(&self, slot: usize) -> Option<&T> { self.items.get(slot) }
+    pub fn get_mut(&mut self, slot: usize) -> Option<&mut T> { self.items.get_mut(slot) }
+}
+
+impl<T> Index<usize> for Slots<T> where T: Clone {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> core::ops::IndexMut<usize> for Slots<T> where T: Clone {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Already implemented.
+        check_assist_not_applicable(
+            generate_index_impl,
+            &with_core(
+                r#"
+struct S;
+impl S { fn <|>get(&self, i: usize) -> &u8 { &0 } }
+impl core::ops::Index<usize> for S {}
+"#,
+            ),
+        );
+        // Not a `get` method.
+        check_assist_not_applicable(
+            generate_index_impl,
+            &with_core("struct S;\nimpl S { fn <|>first(&self, i: usize) -> &u8 { &0 } }"),
+        );
+        // Not returning a reference.
+        check_assist_not_applicable(
+            generate_index_impl,
+            &with_core("struct S;\nimpl S { fn <|>get(&self, i: usize) -> u8 { 0 } }"),
+        );
+    }
+
+    #[test]
+    fn generate_index_impl_target() {
+        check_assist_target(
+            generate_index_impl,
+            &with_core("struct S;\nimpl S { fn <|>get(&self, i: u8) -> &u8 { &0 } }"),
+            "impl S { fn get(&self, i: u8) -> &u8 { &0 } }",
+        );
+    }
+}
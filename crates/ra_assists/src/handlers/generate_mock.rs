@@ -0,0 +1,310 @@
+use ra_syntax::ast::{
+    self, edit::IndentLevel, AstNode, NameOwner, TypeAscriptionOwner, TypeParamsOwner,
+};
+use stdx::{format_to, to_lower_snake_case, SepBy};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: generate_mock
+//
+// Generates a mock implementation of a trait for tests. Every method returns
+// a value stored in a field of the mock, which is set by its constructor.
+//
+// ```
+// trait Clock<|> {
+//     fn now(&self) -> u64;
+//     fn set(&mut self, time: u64);
+// }
+// ```
+// ->
+// ```
+// trait Clock {
+//     fn now(&self) -> u64;
+//     fn set(&mut self, time: u64);
+// }
+//
+// #[cfg(test)]
+// mod mock_clock {
+//     use super::*;
+//
+//     pub(crate) struct MockClock {
+//         pub(crate) now: u64,
+//     }
+//
+//     impl MockClock {
+//         pub(crate) fn new(now: u64) -> MockClock {
+//             MockClock { now }
+//         }
+//     }
+//
+//     impl Clock for MockClock {
+//         fn now(&self) -> u64 {
+//             self.now.clone()
+//         }
+//
+//         fn set(&mut self, _: u64) {}
+//     }
+// }
+// ```
+pub(crate) fn generate_mock(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let trait_ = ctx.find_node_at_offset::<ast::TraitDef>()?;
+    let name = trait_.name()?;
+    let item_list = trait_.item_list()?;
+    // Don't offer the assist everywhere inside of the trait body.
+    if item_list.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    if trait_.type_param_list().is_some() {
+        return None;
+    }
+
+    let mut methods = Vec::new();
+    for item in item_list.assoc_items() {
+        match item {
+            ast::AssocItem::FnDef(it) => {
+                if it.body().is_none() {
+                    methods.push(MockMethod::new(it)?);
+                }
+            }
+            ast::AssocItem::TypeAliasDef(_) => return None,
+            ast::AssocItem::ConstDef(it) => {
+                it.body()?;
+            }
+        }
+    }
+    if !methods.iter().any(|it| it.field.is_some()) {
+        return None;
+    }
+
+    let mock_name = format!("Mock{}", name);
+    let module_name = format!("mock_{}", to_lower_snake_case(name.text()));
+    let trait_def = ctx.sema.to_def(&trait_)?;
+    let scope = trait_def.module(ctx.db).scope(ctx.db, None);
+    if scope.iter().any(|(name, _)| name.to_string() == module_name) {
+        return None;
+    }
+
+    let target = trait_.syntax().text_range();
+    acc.add(
+        AssistId("generate_mock"),
+        format!("Generate `{}` for tests", mock_name),
+        target,
+        |builder| {
+            let fields: Vec<&(String, String)> =
+                methods.iter().filter_map(|it| it.field.as_ref()).collect();
+
+            let mut buf = String::new();
+            format_to!(buf, "#[cfg(test)]\nmod {} {{\n    use super::*;\n\n", module_name);
+            format_to!(buf, "    pub(crate) struct {} {{\n", mock_name);
+            for (field, ty) in fields.iter() {
+                format_to!(buf, "        pub(crate) {}: {},\n", field, ty);
+            }
+            format_to!(buf, "    }}\n\n    impl {} {{\n", mock_name);
+            format_to!(
+                buf,
+                "        pub(crate) fn new({}) -> {} {{\n",
+                fields.iter().map(|(field, ty)| format!("{}: {}", field, ty)).sep_by(", "),
+                mock_name
+            );
+            format_to!(
+                buf,
+                "            {} {{ {} }}\n        }}\n    }}\n\n",
+                mock_name,
+                fields.iter().map(|(field, _)| field).sep_by(", ")
+            );
+            format_to!(buf, "    impl {} for {} {{\n", name, mock_name);
+            let methods = methods.iter().map(|it| it.to_string());
+            format_to!(buf, "{}    }}\n}}", methods.sep_by("\n"));
+
+            let indent = IndentLevel::from_node(trait_.syntax());
+            let text = buf.lines().map(|line| indented(line, indent)).sep_by("\n");
+            builder.insert(trait_.syntax().text_range().end(), format!("\n\n{}", text));
+        },
+    )
+}
+
+struct MockMethod {
+    /// The signature, without the names of the parameters.
+    signature: String,
+    /// The name and the type of the field storing the return value.
+    field: Option<(String, String)>,
+    body: String,
+}
+
+impl MockMethod {
+    fn new(fn_def: ast::FnDef) -> Option<MockMethod> {
+        let name = fn_def.name()?;
+        let param_list = fn_def.param_list()?;
+        let mut params = Vec::new();
+        if let Some(self_param) = param_list.self_param() {
+            params.push(self_param.syntax().text().to_string());
+        }
+        for param in param_list.params() {
+            params.push(format!("_: {}", param.ascribed_type()?));
+        }
+        let ret_type = fn_def.ret_type().and_then(|it| it.type_ref());
+
+        let mut signature = String::new();
+        if fn_def.unsafe_token().is_some() {
+            signature.push_str("unsafe ");
+        }
+        format_to!(signature, "fn {}", name);
+        if let Some(type_params) = fn_def.type_param_list() {
+            format_to!(signature, "{}", type_params.syntax());
+        }
+        format_to!(signature, "({})", params.iter().sep_by(", "));
+        if let Some(ret_type) = &ret_type {
+            format_to!(signature, " -> {}", ret_type);
+        }
+        if let Some(where_clause) = fn_def.where_clause() {
+            format_to!(signature, " {}", where_clause.syntax());
+        }
+
+        let ret_type = match ret_type {
+            Some(ast::TypeRef::TupleType(it)) if it.fields().next().is_none() => None,
+            it => it,
+        };
+        let configurable = param_list.self_param().is_some()
+            && fn_def.type_param_list().is_none()
+            && ret_type.as_ref().map_or(false, |it| !mentions_self(it));
+        let (field, body) = match ret_type {
+            None => (None, String::new()),
+            Some(_) if !configurable => (None, "unimplemented!()".to_string()),
+            Some(ast::TypeRef::ReferenceType(it)) if it.mut_token().is_none() => {
+                let ty = owned_type(&it.type_ref()?);
+                (Some((name.to_string(), ty)), format!("&self.{}", name))
+            }
+            Some(ty) => {
+                (Some((name.to_string(), ty.to_string())), format!("self.{}.clone()", name))
+            }
+        };
+        Some(MockMethod { signature, field, body })
+    }
+}
+
+impl std::fmt::Display for MockMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.body.is_empty() {
+            writeln!(f, "        {} {{}}", self.signature)
+        } else {
+            writeln!(f, "        {} {{\n            {}\n        }}", self.signature, self.body)
+        }
+    }
+}
+
+fn indented(line: &str, indent: IndentLevel) -> String {
+    if line.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", indent, line)
+    }
+}
+
+/// The type of a field, which can be borrowed as `ty`.
+fn owned_type(ty: &ast::TypeRef) -> String {
+    match ty {
+        ast::TypeRef::SliceType(it) => match it.type_ref() {
+            Some(elem) => format!("Vec<{}>", elem),
+            None => ty.to_string(),
+        },
+        _ if ty.syntax().text() == "str" => "String".to_string(),
+        _ => ty.to_string(),
+    }
+}
+
+fn mentions_self(ty: &ast::TypeRef) -> bool {
+    ty.syntax()
+        .descendants()
+        .filter_map(ast::PathSegment::cast)
+        .any(|it| it.name_ref().map_or(false, |it| it.text() == "Self"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_mock_for_nested_trait() {
+        check_assist(
+            generate_mock,
+            r"
+mod store {
+    pub trait <|>Store {
+        fn get(&self, key: &str) -> Option<String>;
+        fn name(&self) -> &str;
+        fn open(path: &str) -> Self;
+        fn len(&self) -> usize {
+            0
+        }
+    }
+}",
+            r"
+mod store {
+    pub trait Store {
+        fn get(&self, key: &str) -> Option<String>;
+        fn name(&self) -> &str;
+        fn open(path: &str) -> Self;
+        fn len(&self) -> usize {
+            0
+        }
+    }
+
+    #[cfg(test)]
+    mod mock_store {
+        use super::*;
+
+        pub(crate) struct MockStore {
+            pub(crate) get: Option<String>,
+            pub(crate) name: String,
+        }
+
+        impl MockStore {
+            pub(crate) fn new(get: Option<String>, name: String) -> MockStore {
+                MockStore { get, name }
+            }
+        }
+
+        impl Store for MockStore {
+            fn get(&self, _: &str) -> Option<String> {
+                self.get.clone()
+            }
+
+            fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn open(_: &str) -> Self {
+                unimplemented!()
+            }
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_mock, "trait Foo<|> { fn foo(&mut self); }");
+        check_assist_not_applicable(generate_mock, "trait Foo<|><T> { fn foo(&self) -> T; }");
+        check_assist_not_applicable(
+            generate_mock,
+            "trait Foo<|> { type Item; fn foo(&self) -> Self::Item; }",
+        );
+        check_assist_not_applicable(generate_mock, "trait Foo { fn foo(&self) -> u32<|>; }");
+        check_assist_not_applicable(
+            generate_mock,
+            "trait Foo<|> { fn foo(&self) -> u32; }\nmod mock_foo {}",
+        );
+    }
+
+    #[test]
+    fn generate_mock_target() {
+        check_assist_target(
+            generate_mock,
+            "trait <|>Foo { fn foo(&self) -> u32; }",
+            "trait Foo { fn foo(&self) -> u32; }",
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use hir::{HirDisplay, ScopeDef};
+use ra_syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        AstNode, AttrsOwner, LoopBodyOwner, NameOwner,
+    },
+    Direction, SyntaxKind, SyntaxNode, TextRange,
+};
+use stdx::SepBy;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: generate_parameterized_test
+//
+// Turns a test with a hardcoded array of cases into a parameterized test. If
+// the cases are iterated by a loop and the crate depends on `test-case`, a
+// `#[test_case]` attribute is generated for every case. Otherwise, the rest of
+// the test is moved into a loop over the cases.
+//
+// ```
+// #[test]
+// fn squares() {
+//     let cases = [<|>(2, 4), (3, 9)];
+//     assert_eq!(square(cases[0].0), cases[0].1);
+// }
+// ```
+// ->
+// ```
+// #[test]
+// fn squares() {
+//     let cases = [(2, 4), (3, 9)];
+//     for case in cases.iter() {
+//         assert_eq!(square(case.0), case.1);
+//     }
+// }
+// ```
+pub(crate) fn generate_parameterized_test(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let array = ctx.find_node_at_offset::<ast::ArrayExpr>()?;
+    let fn_def = array.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let test_attr = fn_def.attrs().find(|it| it.simple_name().map_or(false, |it| it == "test"))?;
+    // The cases are the outermost array, not an array inside of a case.
+    let array = array
+        .syntax()
+        .ancestors()
+        .take_while(|it| it != fn_def.syntax())
+        .filter_map(ast::ArrayExpr::cast)
+        .last()?;
+    let body = fn_def.body()?;
+    let let_stmt = array
+        .syntax()
+        .ancestors()
+        .find_map(ast::LetStmt::cast)
+        .filter(|it| it.syntax().parent().as_ref() == Some(body.syntax()));
+    let cases_name = let_stmt.as_ref().and_then(|it| match it.pat()? {
+        ast::Pat::BindPat(pat) => pat.name(),
+        _ => None,
+    });
+    let for_expr = body.syntax().children().find_map(|stmt| {
+        let for_expr = match ast::ExprStmt::cast(stmt.clone()) {
+            Some(it) => ast::ForExpr::cast(it.expr()?.syntax().clone())?,
+            None => ast::ForExpr::cast(stmt)?,
+        };
+        let iterable = for_expr.iterable()?;
+        let iterates_cases = iterable
+            .syntax()
+            .text_range()
+            .contains_range(array.syntax().text_range())
+            || iterable.syntax().descendants().filter_map(ast::PathExpr::cast).any(|it| {
+                cases_name.as_ref().map_or(false, |name| it.syntax().text() == name.text().as_str())
+            });
+        if iterates_cases {
+            Some(for_expr)
+        } else {
+            None
+        }
+    });
+
+    match for_expr {
+        Some(for_expr) => {
+            let let_stmt = let_stmt.filter(|_| cases_name.is_some());
+            generate_test_cases(acc, ctx, &fn_def, &test_attr, &array, let_stmt, &for_expr)
+        }
+        None => generate_cases_loop(acc, &array, &let_stmt?, &cases_name?),
+    }
+}
+
+/// Replaces `#[test]` with a `#[test_case]` attribute for every case, with the
+/// loop variables becoming the parameters of the test.
+fn generate_test_cases(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+    fn_def: &ast::FnDef,
+    test_attr: &ast::Attr,
+    array: &ast::ArrayExpr,
+    let_stmt: Option<ast::LetStmt>,
+    for_expr: &ast::ForExpr,
+) -> Option<()> {
+    let scope = ctx.sema.scope(fn_def.syntax());
+    let has_test_case = scope
+        .module()?
+        .krate()
+        .dependencies(ctx.db)
+        .iter()
+        .any(|dep| dep.name.to_string() == "test_case");
+    if !has_test_case {
+        return None;
+    }
+    let name = fn_def.name()?;
+    let param_list = fn_def.param_list()?;
+    if param_list.params().next().is_some() {
+        return None;
+    }
+    let (pat, by_ref) = match for_expr.pat()? {
+        ast::Pat::RefPat(it) => (it.pat()?, false),
+        it => (it, iterates_by_ref(&for_expr.iterable()?)),
+    };
+    let bindings: Vec<ast::BindPat> = match pat {
+        ast::Pat::BindPat(it) => vec![it],
+        ast::Pat::TuplePat(it) => it
+            .args()
+            .map(|it| match it {
+                ast::Pat::BindPat(it) => Some(it),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let mut cases: Vec<Vec<ast::Expr>> = Vec::new();
+    for case in array.exprs() {
+        let args = match &case {
+            ast::Expr::TupleExpr(it) if bindings.len() > 1 => it.exprs().collect(),
+            _ => vec![case.clone()],
+        };
+        if args.len() != bindings.len() {
+            return None;
+        }
+        cases.push(args);
+    }
+    // With default binding modes, the loop binds references to the elements.
+    let amp = if by_ref { "&" } else { "" };
+    let mut params = Vec::new();
+    for (binding, arg) in bindings.iter().zip(cases.first()?.iter()) {
+        let ty = ctx.sema.type_of_expr(arg)?;
+        if ty.is_unknown() {
+            return None;
+        }
+        params.push(format!("{}: {}{}", binding.name()?, amp, ty.display(ctx.db)));
+    }
+    let cases: Vec<String> = cases
+        .iter()
+        .map(|args| args.iter().map(|it| format!("{}{}", amp, it)).sep_by(", ").to_string())
+        .collect();
+    let stmt = for_expr
+        .syntax()
+        .parent()
+        .filter(|it| ast::ExprStmt::can_cast(it.kind()))
+        .unwrap_or_else(|| for_expr.syntax().clone());
+    let loop_body = for_expr.loop_body()?;
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("generate_parameterized_test"),
+        format!("Convert `{}` to a parameterized test with `test_case`", name),
+        target,
+        |builder| {
+            let mut in_scope = false;
+            scope.process_all_names(&mut |name, def| {
+                in_scope |= matches!(def, ScopeDef::MacroDef(_)) && name.to_string() == "test_case";
+            });
+            let attr = if in_scope { "test_case" } else { "test_case::test_case" };
+            let indent = IndentLevel::from_node(fn_def.syntax());
+            let separator = format!("\n{}", indent);
+            let attrs = cases.iter().map(|it| format!("#[{}({})]", attr, it)).sep_by(&separator);
+            builder.replace(test_attr.syntax().text_range(), attrs.to_string());
+            builder.replace(param_list.syntax().text_range(), format!("({})", params.join(", ")));
+            if let Some(let_stmt) = &let_stmt {
+                builder.delete(with_trailing_whitespace(let_stmt.syntax()));
+            }
+            let loop_body = loop_body.dedent(IndentLevel(1)).to_string();
+            let stmts = loop_body.trim_start_matches('{').trim_end_matches('}').trim();
+            builder.replace(stmt.text_range(), stmts);
+        },
+    )
+}
+
+/// Moves the statements following the `let` binding the cases into a loop
+/// over them. Uses of the first case, like `cases[0]`, now refer to the case
+/// of the iteration.
+fn generate_cases_loop(
+    acc: &mut Assists,
+    array: &ast::ArrayExpr,
+    let_stmt: &ast::LetStmt,
+    cases_name: &ast::Name,
+) -> Option<()> {
+    let following: Vec<SyntaxNode> = let_stmt
+        .syntax()
+        .siblings(Direction::Next)
+        .skip(1)
+        .filter(|it| ast::Stmt::can_cast(it.kind()) || ast::Expr::can_cast(it.kind()))
+        .collect();
+    let range = TextRange::new(
+        following.first()?.text_range().start(),
+        following.last()?.text_range().end(),
+    );
+    let block = let_stmt.syntax().parent()?;
+    let text = block.text().slice(range - block.text_range().start()).to_string();
+
+    let target = array.syntax().text_range();
+    acc.add(
+        AssistId("generate_parameterized_test"),
+        format!("Loop over the cases in `{}`", cases_name),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(let_stmt.syntax());
+            let mut stmts = text;
+            for (index_range, is_receiver) in
+                indexed_cases(&following, cases_name).into_iter().rev()
+            {
+                let index_range: std::ops::Range<usize> = (index_range - range.start()).into();
+                stmts.replace_range(index_range, if is_receiver { "case" } else { "*case" });
+            }
+            let stmts = stmts.replace('\n', &format!("\n{}", IndentLevel(1)));
+            builder.replace(
+                range,
+                format!(
+                    "for case in {}.iter() {{\n{}{}\n{}}}",
+                    cases_name,
+                    indent + 1,
+                    stmts,
+                    indent
+                ),
+            );
+        },
+    )
+}
+
+/// Finds the uses of a single case, like `cases[0]`, in `nodes`, including
+/// inside of macro calls. Also returns whether the case is the receiver of a
+/// field access or a method call.
+fn indexed_cases(nodes: &[SyntaxNode], cases_name: &ast::Name) -> Vec<(TextRange, bool)> {
+    let tokens: Vec<_> = nodes
+        .iter()
+        .flat_map(|it| it.descendants_with_tokens())
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE)
+        .collect();
+    tokens
+        .windows(4)
+        .enumerate()
+        .filter_map(|(idx, window)| {
+            let matches = window[0].kind() == SyntaxKind::IDENT
+                && window[0].text() == cases_name.text()
+                && window[1].kind() == SyntaxKind::L_BRACK
+                && window[2].kind() == SyntaxKind::INT_NUMBER
+                && window[3].kind() == SyntaxKind::R_BRACK;
+            if !matches {
+                return None;
+            }
+            let range =
+                TextRange::new(window[0].text_range().start(), window[3].text_range().end());
+            let is_receiver = tokens.get(idx + 4).map_or(false, |it| it.kind() == SyntaxKind::DOT);
+            Some((range, is_receiver))
+        })
+        .collect()
+}
+
+/// Checks whether iterating over `iterable` yields references, like
+/// `cases.iter()` or `&cases`.
+fn iterates_by_ref(iterable: &ast::Expr) -> bool {
+    match iterable {
+        ast::Expr::RefExpr(_) => true,
+        ast::Expr::MethodCallExpr(it) => it.name_ref().map_or(false, |it| it.text() == "iter"),
+        _ => false,
+    }
+}
+
+fn with_trailing_whitespace(node: &SyntaxNode) -> TextRange {
+    match node.next_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+            TextRange::new(node.text_range().start(), ws.text_range().end())
+        }
+        _ => node.text_range(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_test_case_attrs() {
+        check_assist(
+            generate_parameterized_test,
+            r"
+//- /main.rs crate:main deps:test_case
+mod tests {
+    #[test]
+    fn squares() {
+        let cases = [(2, 4), (3, 9)<|>];
+        for (x, expected) in cases.iter() {
+            let square = x * x;
+            assert_eq!(square, *expected);
+        }
+    }
+}
+//- /lib.rs crate:test_case
+",
+            r"mod tests {
+    #[test_case::test_case(&2, &4)]
+    #[test_case::test_case(&3, &9)]
+    fn squares(x: &i32, expected: &i32) {
+        let square = x * x;
+        assert_eq!(square, *expected);
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn generate_test_case_attrs_for_inline_array() {
+        check_assist(
+            generate_parameterized_test,
+            r#"
+//- /main.rs crate:main deps:test_case
+#[test]
+fn parses() {
+    for &input in [<|>"1", "22"].iter() {
+        assert!(parse(input).is_some());
+    }
+}
+//- /lib.rs crate:test_case
+"#,
+            r#"#[test_case::test_case("1")]
+#[test_case::test_case("22")]
+fn parses(input: &str) {
+    assert!(parse(input).is_some());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_cases_loop() {
+        check_assist(
+            generate_parameterized_test,
+            r"
+#[test]
+fn lengths() {
+    let cases = [<|>vec![1], vec![1, 2]];
+    let len = cases[0].len();
+    assert!(check(&cases[0], len));
+}",
+            r"
+#[test]
+fn lengths() {
+    let cases = [vec![1], vec![1, 2]];
+    for case in cases.iter() {
+        let len = case.len();
+        assert!(check(&*case, len));
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Not a test.
+        check_assist_not_applicable(
+            generate_parameterized_test,
+            "fn foo() { let cases = [<|>1, 2]; bar(cases); }",
+        );
+        // Without the `test-case` crate.
+        check_assist_not_applicable(
+            generate_parameterized_test,
+            "#[test]\nfn foo() { for x in [<|>1, 2].iter() { bar(x); } }",
+        );
+        check_assist_not_applicable(
+            generate_parameterized_test,
+            "#[test]\nfn foo() { let cases = [<|>1, 2]; }",
+        );
+    }
+}
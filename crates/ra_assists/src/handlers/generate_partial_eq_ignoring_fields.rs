@@ -0,0 +1,229 @@
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner, StructKind, TypeParamsOwner},
+    SyntaxKind::{IDENT, WHITESPACE},
+    TextRange,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{impl_type_params, nominal_self_ty},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: generate_partial_eq_ignoring_fields
+//
+// Replaces `#[derive(PartialEq)]` with a manual impl, which doesn't compare the
+// selected fields, or the field under the cursor.
+//
+// ```
+// #[derive(Debug, PartialEq)]
+// struct Entry {
+//     key: String,
+//     <|>updated_at: u64,
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug)]
+// struct Entry {
+//     key: String,
+//     updated_at: u64,
+// }
+//
+// impl PartialEq for Entry {
+//     fn eq(&self, other: &Self) -> bool {
+//         // Ignored: `updated_at`
+//         self.key == other.key
+//     }
+// }
+// ```
+pub(crate) fn generate_partial_eq_ignoring_fields(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    let derive = strukt
+        .attrs()
+        .filter_map(|attr| {
+            let (name, tt) = attr.as_simple_call()?;
+            if name == "derive" {
+                Some((attr, tt))
+            } else {
+                None
+            }
+        })
+        .find(|(_, tt)| derived_traits(tt).any(|it| it == "PartialEq"))?;
+
+    let fields: Vec<(String, TextRange)> = match strukt.kind() {
+        StructKind::Record(it) => it
+            .fields()
+            .filter_map(|f| Some((f.name()?.to_string(), f.syntax().text_range())))
+            .collect(),
+        StructKind::Tuple(it) => it
+            .fields()
+            .enumerate()
+            .map(|(idx, f)| (idx.to_string(), f.syntax().text_range()))
+            .collect(),
+        StructKind::Unit => return None,
+    };
+    let selection = ctx.frange.range;
+    let (ignored, compared): (Vec<_>, Vec<_>) = fields.into_iter().partition(|(_, range)| {
+        if selection.is_empty() {
+            range.contains_inclusive(selection.start())
+        } else {
+            range.intersect(selection).map_or(false, |it| !it.is_empty())
+        }
+    });
+    if ignored.is_empty() {
+        return None;
+    }
+
+    let label = format!(
+        "Implement `PartialEq` ignoring {}",
+        ignored.iter().map(|(name, _)| format!("`{}`", name)).sep_by(", ")
+    );
+    let target = strukt.syntax().text_range();
+    acc.add(AssistId("generate_partial_eq_ignoring_fields"), label, target, |builder| {
+        let (attr, tt) = derive;
+        let remaining = derived_traits(&tt).filter(|it| it != "PartialEq").collect::<Vec<_>>();
+        if remaining.is_empty() {
+            builder.delete(attr.syntax().text_range());
+            if let Some(ws) =
+                attr.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+            {
+                builder.delete(ws.text_range());
+            }
+        } else {
+            builder.replace(
+                tt.syntax().text_range(),
+                remaining.iter().sep_by(", ").surround_with("(", ")").to_string(),
+            );
+        }
+
+        let nominal = ast::NominalDef::from(strukt.clone());
+        let mut bounds: Vec<String> = strukt
+            .type_param_list()
+            .into_iter()
+            .flat_map(|it| it.type_params())
+            .filter_map(|it| Some(format!("{}: PartialEq", it.name()?)))
+            .collect();
+        if let Some(where_clause) = strukt.where_clause() {
+            bounds.extend(where_clause.predicates().map(|it| it.syntax().to_string()));
+        }
+        let comparison = if compared.is_empty() {
+            "true".to_string()
+        } else {
+            compared
+                .iter()
+                .map(|(field, _)| format!("self.{0} == other.{0}", field))
+                .sep_by(" && ")
+                .to_string()
+        };
+
+        let mut buf = String::new();
+        format_to!(
+            buf,
+            "\n\nimpl{} PartialEq for {}",
+            impl_type_params(&nominal),
+            nominal_self_ty(&nominal, &name)
+        );
+        if !bounds.is_empty() {
+            format_to!(buf, " where {}", bounds.iter().sep_by(", "));
+        }
+        buf.push_str(" {\n    fn eq(&self, other: &Self) -> bool {\n");
+        format_to!(
+            buf,
+            "        // Ignored: {}\n",
+            ignored.iter().map(|(name, _)| format!("`{}`", name)).sep_by(", ")
+        );
+        format_to!(buf, "        {}\n    }}\n}}", comparison);
+        builder.insert(strukt.syntax().text_range().end(), buf);
+    })
+}
+
+fn derived_traits(tt: &ast::TokenTree) -> impl Iterator<Item = String> {
+    tt.syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == IDENT)
+        .map(|it| it.text().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn ignore_selected_fields() {
+        check_assist(
+            generate_partial_eq_ignoring_fields,
+            r#"
+#[derive(PartialEq)]
+struct Node<T> where T: Clone {
+    <|>id: u32,
+    parent: Option<u32>,<|>
+    value: T,
+}
+"#,
+            r#"
+struct Node<T> where T: Clone {
+    id: u32,
+    parent: Option<u32>,
+    value: T,
+}
+
+impl<T> PartialEq for Node<T> where T: PartialEq, T: Clone {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignored: `id`, `parent`
+        self.value == other.value
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_tuple_field() {
+        check_assist(
+            generate_partial_eq_ignoring_fields,
+            "#[derive(Clone, PartialEq, Eq)]\nstruct Tagged(u32, <|>String);",
+            r#"#[derive(Clone, Eq)]
+struct Tagged(u32, String);
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignored: `1`
+        self.0 == other.0
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            generate_partial_eq_ignoring_fields,
+            "#[derive(Debug)]\nstruct S { <|>a: u32 }",
+        );
+        check_assist_not_applicable(
+            generate_partial_eq_ignoring_fields,
+            "#[derive(PartialEq)]\nstruct S<|> { a: u32 }",
+        );
+        check_assist_not_applicable(
+            generate_partial_eq_ignoring_fields,
+            "#[derive(PartialEq)]\nstruct S<|>;",
+        );
+    }
+
+    #[test]
+    fn generate_partial_eq_ignoring_fields_target() {
+        check_assist_target(
+            generate_partial_eq_ignoring_fields,
+            "#[derive(PartialEq)]\nstruct S { <|>a: u32 }\nfn f() {}",
+            "#[derive(PartialEq)]\nstruct S { a: u32 }",
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, ModuleItemOwner},
+    NodeOrToken, TextSize,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: generate_proc_macro_attribute
+//
+// Adds an attribute macro with a `todo!()` body to the root of a proc-macro
+// crate, which doesn't define any macros yet.
+//
+// ```
+// # //- /lib.rs crate:my_macros deps:proc_macro
+// <|>
+// fn helper() {}
+// # //- /proc_macro.rs crate:proc_macro
+// ```
+// ->
+// ```
+// #[proc_macro_attribute]
+// pub fn $0my_attribute(
+//     attr: proc_macro::TokenStream,
+//     item: proc_macro::TokenStream,
+// ) -> proc_macro::TokenStream {
+//     todo!()
+// }
+//
+// fn helper() {}
+// ```
+pub(crate) fn generate_proc_macro_attribute(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let offset = skeleton_offset(ctx)?;
+    acc.add(
+        AssistId("generate_proc_macro_attribute"),
+        "Generate `#[proc_macro_attribute]` function",
+        ctx.frange.range,
+        |builder| {
+            let buf = "#[proc_macro_attribute]\n\
+                 pub fn $0my_attribute(\n    \
+                     attr: proc_macro::TokenStream,\n    \
+                     item: proc_macro::TokenStream,\n\
+                 ) -> proc_macro::TokenStream {\n    \
+                     todo!()\n\
+                 }\n";
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, buf),
+                None => builder.insert(offset, buf.replace("$0", "")),
+            }
+        },
+    )
+}
+
+// Assist: generate_proc_macro_derive
+//
+// Adds a derive macro with a `todo!()` body to the root of a proc-macro crate,
+// which doesn't define any macros yet.
+//
+// ```
+// # //- /lib.rs crate:my_macros deps:proc_macro
+// <|>
+// fn helper() {}
+// # //- /proc_macro.rs crate:proc_macro
+// ```
+// ->
+// ```
+// #[proc_macro_derive($0MyDerive)]
+// pub fn my_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+//     todo!()
+// }
+//
+// fn helper() {}
+// ```
+pub(crate) fn generate_proc_macro_derive(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let offset = skeleton_offset(ctx)?;
+    acc.add(
+        AssistId("generate_proc_macro_derive"),
+        "Generate `#[proc_macro_derive]` function",
+        ctx.frange.range,
+        |builder| {
+            let buf = "#[proc_macro_derive($0MyDerive)]\n\
+                 pub fn my_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {\n    \
+                     todo!()\n\
+                 }\n";
+            match ctx.config.snippet_cap {
+                Some(cap) => builder.insert_snippet(cap, offset, buf),
+                None => builder.insert(offset, buf.replace("$0", "")),
+            }
+        },
+    )
+}
+
+/// Returns the offset for a macro skeleton, if the cursor is between the
+/// items of the root file of a proc-macro crate without any macros.
+fn skeleton_offset(ctx: &AssistContext) -> Option<TextSize> {
+    let node = match ctx.covering_element() {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let file = ast::SourceFile::cast(node)?;
+    let module = ctx.sema.to_module_def(ctx.frange.file_id)?;
+    if module.parent(ctx.db).is_some() {
+        return None;
+    }
+    let is_proc_macro_crate =
+        module.krate().dependencies(ctx.db).iter().any(|dep| dep.name.to_string() == "proc_macro");
+    if !is_proc_macro_crate {
+        return None;
+    }
+    let has_macros = file.items().any(|item| match item {
+        ast::ModuleItem::FnDef(it) => it.attrs().filter_map(|it| it.simple_name()).any(|name| {
+            matches!(name.as_str(), "proc_macro" | "proc_macro_attribute" | "proc_macro_derive")
+        }),
+        _ => false,
+    });
+    if has_macros {
+        return None;
+    }
+    Some(ctx.offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_derive_between_items() {
+        check_assist(
+            generate_proc_macro_derive,
+            r#"
+//- /lib.rs crate:my_macros deps:proc_macro
+use proc_macro::TokenStream;
+<|>
+fn helper() {}
+//- /proc_macro.rs crate:proc_macro
+pub struct TokenStream;
+"#,
+            r#"use proc_macro::TokenStream;
+#[proc_macro_derive($0MyDerive)]
+pub fn my_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    todo!()
+}
+
+fn helper() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_proc_macro_attribute, "<|>\nfn helper() {}");
+        check_assist_not_applicable(
+            generate_proc_macro_attribute,
+            r#"
+//- /lib.rs crate:my_macros deps:proc_macro
+#[proc_macro]
+pub fn make(input: proc_macro::TokenStream) -> proc_macro::TokenStream { input }
+<|>
+//- /proc_macro.rs crate:proc_macro
+"#,
+        );
+        check_assist_not_applicable(
+            generate_proc_macro_attribute,
+            r#"
+//- /lib.rs crate:my_macros deps:proc_macro
+fn helper() { <|> }
+//- /proc_macro.rs crate:proc_macro
+"#,
+        );
+        check_assist_not_applicable(
+            generate_proc_macro_derive,
+            r#"
+//- /lib.rs crate:my_macros deps:proc_macro
+mod util;
+//- /util.rs
+<|>
+//- /proc_macro.rs crate:proc_macro
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_proc_macro_target() {
+        check_assist_target(
+            generate_proc_macro_attribute,
+            r#"
+//- /lib.rs crate:my_macros deps:proc_macro
+<|>
+//- /proc_macro.rs crate:proc_macro
+"#,
+            "",
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use hir::{HirDisplay, ScopeDef};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, NameOwner, TypeAscriptionOwner},
+    SyntaxKind::{IDENT, WHITESPACE},
+    TextRange,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{AssistContext, AssistId, Assists, GroupLabel};
+
+// Assist: generate_property_test
+//
+// Generates a property test from a unit test, which binds fixed values with
+// `let`. The values become parameters of the property test, which are
+// generated by `proptest` or `quickcheck`, depending on which of them the
+// crate depends on.
+//
+// ```
+// # //- /main.rs crate:main deps:proptest
+// #[test]
+// fn <|>reverse_twice() {
+//     let x = 92;
+//     let y = 10;
+//     assert_eq!(reverse(&reverse(&[x, y])), [x, y]);
+// }
+// # //- /lib.rs crate:proptest
+// ```
+// ->
+// ```
+// #[test]
+// fn reverse_twice() {
+//     let x = 92;
+//     let y = 10;
+//     assert_eq!(reverse(&reverse(&[x, y])), [x, y]);
+// }
+//
+// proptest::proptest! {
+//     #[test]
+//     fn prop_reverse_twice(x: i32, y: i32) {
+//         assert_eq!(reverse(&reverse(&[x, y])), [x, y]);
+//     }
+// }
+// ```
+pub(crate) fn generate_property_test(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if !fn_def.attrs().any(|it| it.simple_name().map_or(false, |it| it == "test")) {
+        return None;
+    }
+    let name = fn_def.name()?;
+    if fn_def.param_list()?.params().next().is_some() {
+        return None;
+    }
+    let body = fn_def.body()?;
+
+    let mut params = Vec::new();
+    let mut removed = Vec::new();
+    for stmt in body.statements() {
+        let let_stmt = match stmt {
+            ast::Stmt::LetStmt(it) => it,
+            ast::Stmt::ExprStmt(_) => continue,
+        };
+        let pat = match let_stmt.pat() {
+            Some(ast::Pat::BindPat(it)) => it,
+            _ => continue,
+        };
+        if pat.ref_token().is_some() || pat.mut_token().is_some() || pat.pat().is_some() {
+            continue;
+        }
+        let initializer = match let_stmt.initializer() {
+            Some(it) if is_fixed_value(&it) => it,
+            _ => continue,
+        };
+        let ty = match let_stmt.ascribed_type() {
+            Some(it) => it.syntax().to_string(),
+            None => {
+                let ty = match ctx.sema.type_of_expr(&initializer) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                // There is no `Arbitrary` impl for borrowed values.
+                if ty.is_unknown() || ty.is_reference() {
+                    continue;
+                }
+                let ty = ty.display(ctx.db).to_string();
+                if ty.contains("{unknown}") || ty.contains("; _]") {
+                    continue;
+                }
+                ty
+            }
+        };
+        params.push(format!("{}: {}", pat.name()?, ty));
+        removed.push(let_stmt.syntax().clone());
+    }
+    if params.is_empty() {
+        return None;
+    }
+
+    let krate = ctx.sema.scope(fn_def.syntax()).module()?.krate();
+    let deps = krate.dependencies(ctx.db);
+    let frameworks: Vec<Framework> = [Framework::Proptest, Framework::Quickcheck]
+        .iter()
+        .copied()
+        .filter(|framework| deps.iter().any(|dep| dep.name.to_string() == framework.crate_name()))
+        .collect();
+    if frameworks.is_empty() {
+        return None;
+    }
+
+    // The body without the `let` statements, which bind the parameters.
+    let body_range = body.syntax().text_range();
+    let mut body_text = body.syntax().to_string();
+    for node in removed.iter().rev() {
+        let range = match node.next_sibling_or_token() {
+            Some(ws) if ws.kind() == WHITESPACE => {
+                TextRange::new(node.text_range().start(), ws.text_range().end())
+            }
+            _ => node.text_range(),
+        };
+        body_text.replace_range(std::ops::Range::<usize>::from(range - body_range.start()), "");
+    }
+    let indent = IndentLevel::from_node(fn_def.syntax());
+    let body_text = body_text.replace('\n', &format!("\n{}", IndentLevel(1)));
+    let test_name = format!("prop_{}", name);
+    let params = params.iter().sep_by(", ").to_string();
+
+    let group = GroupLabel(format!("Generate a property test for `{}`", name));
+    let target = fn_def.syntax().text_range();
+    for framework in frameworks {
+        let scope = ctx.sema.scope(fn_def.syntax());
+        acc.add_group(
+            &group,
+            AssistId("generate_property_test"),
+            format!("Generate a property test with `{}`", framework.crate_name()),
+            target,
+            |builder| {
+                let mut in_scope = false;
+                scope.process_all_names(&mut |name, def| {
+                    in_scope |= matches!(def, ScopeDef::MacroDef(_))
+                        && name.to_string() == framework.crate_name();
+                });
+                let mut buf = String::new();
+                if in_scope {
+                    format_to!(buf, "\n\n{}{}! {{\n", indent, framework.crate_name());
+                } else {
+                    format_to!(
+                        buf,
+                        "\n\n{}{}::{}! {{\n",
+                        indent,
+                        framework.crate_name(),
+                        framework.crate_name()
+                    );
+                }
+                match framework {
+                    Framework::Proptest => {
+                        format_to!(buf, "{}#[test]\n", indent + 1);
+                        format_to!(
+                            buf,
+                            "{}fn {}({}) {}\n",
+                            indent + 1,
+                            test_name,
+                            params,
+                            body_text
+                        );
+                    }
+                    // `()` implements `Testable`, but the macro requires a return type.
+                    Framework::Quickcheck => {
+                        format_to!(
+                            buf,
+                            "{}fn {}({}) -> () {}\n",
+                            indent + 1,
+                            test_name,
+                            params,
+                            body_text
+                        );
+                    }
+                }
+                format_to!(buf, "{}}}", indent);
+                builder.insert(fn_def.syntax().text_range().end(), buf);
+            },
+        );
+    }
+    Some(())
+}
+
+#[derive(Clone, Copy)]
+enum Framework {
+    Proptest,
+    Quickcheck,
+}
+
+impl Framework {
+    fn crate_name(self) -> &'static str {
+        match self {
+            Framework::Proptest => "proptest",
+            Framework::Quickcheck => "quickcheck",
+        }
+    }
+}
+
+/// Checks whether `expr` is a value, which doesn't depend on the rest of the
+/// test, like a literal, an array of literals or `"foo".to_string()`.
+fn is_fixed_value(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(_) => true,
+        ast::Expr::PrefixExpr(it) => {
+            it.op_kind() == Some(ast::PrefixOp::Neg)
+                && it.expr().map_or(false, |it| is_fixed_value(&it))
+        }
+        ast::Expr::ArrayExpr(it) => it.exprs().all(|it| is_fixed_value(&it)),
+        ast::Expr::TupleExpr(it) => it.exprs().all(|it| is_fixed_value(&it)),
+        ast::Expr::MethodCallExpr(it) => {
+            let is_conversion = it
+                .name_ref()
+                .map_or(false, |it| it.text() == "to_string" || it.text() == "to_owned");
+            is_conversion && matches!(it.expr(), Some(ast::Expr::Literal(_)))
+        }
+        ast::Expr::MacroCall(it) => {
+            let is_vec = it.path().map_or(false, |it| it.syntax().text() == "vec");
+            is_vec
+                && it.token_tree().map_or(false, |tt| {
+                    !tt.syntax().descendants_with_tokens().any(|it| it.kind() == IDENT)
+                })
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_proptest() {
+        check_assist(
+            generate_property_test,
+            r#"
+//- /main.rs crate:main deps:proptest
+struct String;
+#[lang = "str"]
+impl str { fn to_string(&self) -> String { String } }
+mod tests {
+    #[test]
+    fn <|>parse_roundtrip() {
+        let input = "a = 1".to_string();
+        let limit: u8 = 3;
+        let items = parse(&input);
+        assert_eq!(print(&items), input);
+    }
+}
+//- /lib.rs crate:proptest
+"#,
+            r#"struct String;
+#[lang = "str"]
+impl str { fn to_string(&self) -> String { String } }
+mod tests {
+    #[test]
+    fn parse_roundtrip() {
+        let input = "a = 1".to_string();
+        let limit: u8 = 3;
+        let items = parse(&input);
+        assert_eq!(print(&items), input);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_parse_roundtrip(input: String, limit: u8) {
+            let items = parse(&input);
+            assert_eq!(print(&items), input);
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_quickcheck() {
+        check_assist(
+            generate_property_test,
+            r#"
+//- /main.rs crate:main deps:quickcheck
+use quickcheck::quickcheck;
+#[test]
+fn <|>abs_is_positive() {
+    let x = -5;
+    let s = "not a value";
+    assert!(x.abs() >= 0, s);
+}
+//- /lib.rs crate:quickcheck
+#[macro_export]
+macro_rules! quickcheck { () => {} }
+"#,
+            r#"use quickcheck::quickcheck;
+#[test]
+fn abs_is_positive() {
+    let x = -5;
+    let s = "not a value";
+    assert!(x.abs() >= 0, s);
+}
+
+quickcheck! {
+    fn prop_abs_is_positive(x: i32) -> () {
+        let s = "not a value";
+        assert!(x.abs() >= 0, s);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Without a property testing crate.
+        check_assist_not_applicable(
+            generate_property_test,
+            "#[test]\nfn <|>foo() { let x = 1; assert_eq!(x, 1); }",
+        );
+        // Not a test.
+        check_assist_not_applicable(
+            generate_property_test,
+            "//- /main.rs crate:main deps:proptest\nfn <|>foo() { let x = 1; }\n//- /lib.rs crate:proptest\n",
+        );
+        // No fixed values.
+        check_assist_not_applicable(
+            generate_property_test,
+            "//- /main.rs crate:main deps:proptest\n#[test]\nfn <|>foo() { let x = bar(); }\n//- /lib.rs crate:proptest\n",
+        );
+    }
+
+    #[test]
+    fn generate_property_test_target() {
+        check_assist_target(
+            generate_property_test,
+            "//- /main.rs crate:main deps:proptest\n#[test]\nfn <|>foo() { let x = 1; }\n//- /lib.rs crate:proptest\n",
+            "#[test]\nfn foo() { let x = 1; }",
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use hir::{Adt, ImplDef};
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, NameOwner, StructKind},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{
+    utils::{derive_paths, impl_type_params, nominal_self_ty, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: generate_redacted_debug_impl
+//
+// Implements `Debug` for a struct with sensitive fields, like passwords or
+// tokens, manually. The values of these fields are printed as `[REDACTED]`.
+//
+// ```
+// #[derive(Clone, Debug)]
+// struct Credentials {
+//     user: String,
+//     password: String,<|>
+// }
+// ```
+// ->
+// ```
+// #[derive(Clone)]
+// struct Credentials {
+//     user: String,
+//     password: String,
+// }
+//
+// impl std::fmt::Debug for Credentials {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         f.debug_struct("Credentials")
+//             .field("user", &self.user)
+//             .field("password", &"[REDACTED]")
+//             .finish()
+//     }
+// }
+// ```
+pub(crate) fn generate_redacted_debug_impl(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let name = strukt.name()?;
+    let fields: Vec<String> = match strukt.kind() {
+        StructKind::Record(it) => {
+            it.fields().map(|it| Some(it.name()?.to_string())).collect::<Option<_>>()?
+        }
+        _ => return None,
+    };
+    if !fields.iter().any(|it| is_sensitive(it)) {
+        return None;
+    }
+
+    let struct_def = ctx.sema.to_def(&strukt)?;
+    let krate = struct_def.module(ctx.db).krate();
+    if let Some(debug_trait) = FamousDefs(&ctx.sema, krate).core_fmt_Debug() {
+        let has_debug_impl = ImplDef::for_trait(ctx.db, krate, debug_trait)
+            .into_iter()
+            .any(|imp| imp.target_ty(ctx.db).as_adt() == Some(Adt::Struct(struct_def)));
+        if has_debug_impl {
+            return None;
+        }
+    }
+    let derive = strukt.attrs().find_map(|attr| {
+        let (name, tt) = attr.as_simple_call()?;
+        let paths = derive_paths(&tt);
+        if name == "derive" && paths.iter().any(|it| is_debug(it)) {
+            Some((attr, tt, paths))
+        } else {
+            None
+        }
+    });
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_redacted_debug_impl"),
+        "Implement `Debug` with redacted sensitive fields",
+        target,
+        |builder| {
+            if let Some((attr, tt, paths)) = derive {
+                let remaining = paths.iter().filter(|it| !is_debug(it)).collect::<Vec<_>>();
+                if remaining.is_empty() {
+                    let mut range = attr.syntax().text_range();
+                    if let Some(ws) =
+                        attr.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+                    {
+                        range = TextRange::new(range.start(), ws.text_range().end());
+                    }
+                    builder.delete(range);
+                } else {
+                    builder.replace(
+                        tt.syntax().text_range(),
+                        format!("({})", remaining.iter().sep_by(", ")),
+                    );
+                }
+            }
+
+            let nominal = ast::NominalDef::StructDef(strukt.clone());
+            let mut buf = String::new();
+            format_to!(
+                buf,
+                "\n\nimpl{} std::fmt::Debug for {} {{\n",
+                impl_type_params(&nominal),
+                nominal_self_ty(&nominal, &name)
+            );
+            buf.push_str(
+                "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n",
+            );
+            format_to!(buf, "        f.debug_struct(\"{}\")\n", name);
+            for field in &fields {
+                let value = if is_sensitive(field) {
+                    "&\"[REDACTED]\"".to_string()
+                } else {
+                    format!("&self.{}", field)
+                };
+                format_to!(buf, "            .field(\"{}\", {})\n", field, value);
+            }
+            buf.push_str("            .finish()\n    }\n}");
+            builder.insert(strukt.syntax().text_range().end(), buf);
+        },
+    )
+}
+
+/// Checks whether a word of the field name, like `key` in `api_key`, marks
+/// the field as sensitive.
+fn is_sensitive(field: &str) -> bool {
+    const SENSITIVE: &[&str] = &["password", "secret", "token", "key"];
+    field
+        .trim_start_matches("r#")
+        .split('_')
+        .any(|word| SENSITIVE.iter().any(|it| word.eq_ignore_ascii_case(it)))
+}
+
+fn is_debug(path: &str) -> bool {
+    path.rsplit("::").next().map_or(false, |it| it.trim() == "Debug")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn generate_for_generic_struct_without_derive() {
+        check_assist(
+            generate_redacted_debug_impl,
+            r#"
+/// A connection.
+struct Conn<'a, T> {
+    host: &'a str,
+    api_key: T,<|>
+    keyboard_layout: u8,
+}
+"#,
+            r#"
+/// A connection.
+struct Conn<'a, T> {
+    host: &'a str,
+    api_key: T,
+    keyboard_layout: u8,
+}
+
+impl<'a, T> std::fmt::Debug for Conn<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Conn")
+            .field("host", &self.host)
+            .field("api_key", &"[REDACTED]")
+            .field("keyboard_layout", &self.keyboard_layout)
+            .finish()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_removes_only_derive() {
+        check_assist(
+            generate_redacted_debug_impl,
+            r#"
+#[derive(std::fmt::Debug)]
+struct Session { access_token: String<|> }
+"#,
+            r#"
+struct Session { access_token: String }
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("access_token", &"[REDACTED]")
+            .finish()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            generate_redacted_debug_impl,
+            "struct User { name: String, keyboard: u8<|> }",
+        );
+        check_assist_not_applicable(generate_redacted_debug_impl, "struct Token(String<|>);");
+        check_assist_not_applicable(
+            generate_redacted_debug_impl,
+            r#"
+//- /main.rs crate:main deps:core
+struct User { password: String<|> }
+impl core::fmt::Debug for User {}
+//- /libcore.rs crate:core
+pub mod fmt { pub trait Debug {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_redacted_debug_impl_target() {
+        check_assist_target(
+            generate_redacted_debug_impl,
+            "struct User { secret: u64<|> }",
+            "struct User { secret: u64 }",
+        );
+    }
+}
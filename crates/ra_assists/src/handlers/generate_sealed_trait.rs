@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+
+use hir::{HasSource, ImplDef, ModuleDef, ScopeDef};
+use ra_db::FileId;
+use ra_syntax::{
+    ast::{
+        self, edit::IndentLevel, AstNode, NameOwner, TypeBoundsOwner, TypeParamsOwner,
+        VisibilityOwner,
+    },
+    TextSize,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: generate_sealed_trait
+//
+// Seals a public trait, so that it can't be implemented outside of the crate.
+// The existing impls of the trait also implement the supertrait.
+//
+// ```
+// pub trait <|>Shape {
+//     fn area(&self) -> f64;
+// }
+//
+// pub struct Square(f64);
+// impl Shape for Square {
+//     fn area(&self) -> f64 { self.0 * self.0 }
+// }
+// ```
+// ->
+// ```
+// mod private {
+//     pub trait Sealed {}
+// }
+//
+// pub trait Shape: private::Sealed {
+//     fn area(&self) -> f64;
+// }
+//
+// pub struct Square(f64);
+// impl Shape for Square {
+//     fn area(&self) -> f64 { self.0 * self.0 }
+// }
+//
+// impl private::Sealed for Square {}
+// ```
+pub(crate) fn generate_sealed_trait(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let trait_def = ctx.find_node_at_offset::<ast::TraitDef>()?;
+    let name = trait_def.name()?;
+    if let Some(item_list) = trait_def.item_list() {
+        if ctx.offset() >= item_list.syntax().text_range().start() {
+            return None;
+        }
+    }
+    if trait_def.visibility()?.syntax().text() != "pub" {
+        return None;
+    }
+    if let Some(bounds) = trait_def.type_bound_list() {
+        if bounds.syntax().text().to_string().contains("Sealed") {
+            return None;
+        }
+    }
+
+    let trait_ = ctx.sema.to_def(&trait_def)?;
+    let module = trait_.module(ctx.db);
+    // A `private` module, which already seals other traits, is reused.
+    let create_module = match module
+        .scope(ctx.db, None)
+        .into_iter()
+        .find(|(name, _)| name.to_string() == "private")
+    {
+        None => true,
+        Some((_, ScopeDef::ModuleDef(ModuleDef::Module(private)))) => {
+            let has_sealed = private.scope(ctx.db, None).into_iter().any(|(name, def)| {
+                name.to_string() == "Sealed"
+                    && matches!(def, ScopeDef::ModuleDef(ModuleDef::Trait(_)))
+            });
+            if !has_sealed {
+                return None;
+            }
+            false
+        }
+        Some(_) => return None,
+    };
+
+    // The stubs are inserted after the existing impls, grouped by file.
+    let mut stubs: BTreeMap<FileId, Vec<(TextSize, String)>> = BTreeMap::new();
+    for imp in ImplDef::for_trait(ctx.db, module.krate(), trait_) {
+        if imp.is_builtin_derive(ctx.db).is_some() {
+            continue;
+        }
+        let source = imp.source(ctx.db);
+        if source.file_id.is_macro() {
+            return None;
+        }
+        // Only the descendants of the trait's module can name the private module.
+        let path_to_root = imp.module(ctx.db).path_to_root(ctx.db);
+        let depth = path_to_root.iter().position(|it| *it == module)?;
+        let sealed = if depth == 0 {
+            "private::Sealed".to_string()
+        } else {
+            let segments = module
+                .path_to_root(ctx.db)
+                .into_iter()
+                .rev()
+                .filter_map(|it| it.name(ctx.db))
+                .map(|it| it.to_string())
+                .chain(vec!["private".to_string(), "Sealed".to_string()]);
+            format!("crate::{}", segments.sep_by("::"))
+        };
+
+        let impl_def = source.value;
+        let mut buf = String::new();
+        let indent = IndentLevel::from_node(impl_def.syntax());
+        format_to!(buf, "\n\n{}impl", indent);
+        if let Some(type_params) = impl_def.type_param_list() {
+            format_to!(buf, "{}", type_params);
+        }
+        format_to!(buf, " {} for {}", sealed, impl_def.target_type()?);
+        if let Some(where_clause) = impl_def.where_clause() {
+            format_to!(buf, " {}", where_clause);
+        }
+        buf.push_str(" {}");
+        let file_id = source.file_id.original_file(ctx.db);
+        stubs.entry(file_id).or_default().push((impl_def.syntax().text_range().end(), buf));
+    }
+
+    let target = trait_def.syntax().text_range();
+    acc.add(
+        AssistId("generate_sealed_trait"),
+        format!("Seal trait `{}`", name),
+        target,
+        |builder| {
+            if create_module {
+                let indent = IndentLevel::from_node(trait_def.syntax());
+                let module = format!(
+                    "mod private {{\n{}pub trait Sealed {{}}\n{}}}\n\n{}",
+                    indent + 1,
+                    indent,
+                    indent
+                );
+                builder.insert(target.start(), module);
+            }
+            match trait_def.type_bound_list() {
+                Some(bounds) => {
+                    builder.insert(bounds.syntax().text_range().end(), " + private::Sealed")
+                }
+                None => {
+                    let offset = trait_def
+                        .type_param_list()
+                        .map_or(name.syntax().text_range().end(), |it| {
+                            it.syntax().text_range().end()
+                        });
+                    builder.insert(offset, ": private::Sealed");
+                }
+            }
+
+            let file_id = ctx.frange.file_id;
+            for (offset, stub) in stubs.remove(&file_id).unwrap_or_default() {
+                builder.insert(offset, stub);
+            }
+            for (file_id, stubs) in stubs {
+                builder.edit_file(file_id);
+                for (offset, stub) in stubs {
+                    builder.insert(offset, stub);
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn seal_generic_trait_with_bounds_in_module() {
+        check_assist(
+            generate_sealed_trait,
+            r#"
+mod shapes {
+    /// A shape.
+    pub trait Shape<T><|>: Clone where T: Copy {}
+
+    impl<T: Copy> Shape<T> for Vec<T> where Vec<T>: Clone {}
+
+    mod circle {
+        #[derive(Clone)]
+        pub struct Circle;
+        impl super::Shape<u8> for Circle {}
+    }
+}
+"#,
+            r#"
+mod shapes {
+    mod private {
+        pub trait Sealed {}
+    }
+
+    /// A shape.
+    pub trait Shape<T>: Clone + private::Sealed where T: Copy {}
+
+    impl<T: Copy> Shape<T> for Vec<T> where Vec<T>: Clone {}
+
+    impl<T: Copy> private::Sealed for Vec<T> where Vec<T>: Clone {}
+
+    mod circle {
+        #[derive(Clone)]
+        pub struct Circle;
+        impl super::Shape<u8> for Circle {}
+
+        impl crate::shapes::private::Sealed for Circle {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn seal_with_existing_private_module() {
+        check_assist(
+            generate_sealed_trait,
+            r#"
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait Reader: private::Sealed {}
+
+pub trait <|>Writer {}
+"#,
+            r#"
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait Reader: private::Sealed {}
+
+pub trait Writer: private::Sealed {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_sealed_trait, "trait <|>Shape {}");
+        check_assist_not_applicable(generate_sealed_trait, "pub(crate) trait <|>Shape {}");
+        check_assist_not_applicable(generate_sealed_trait, "mod private {} pub trait <|>Shape {}");
+        // The impl in `other` can't name the private module.
+        check_assist_not_applicable(
+            generate_sealed_trait,
+            r#"
+mod shapes { pub trait <|>Shape {} }
+mod other { struct S; impl crate::shapes::Shape for S {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_sealed_trait_target() {
+        check_assist_target(
+            generate_sealed_trait,
+            "pub trait <|>Shape { fn area(&self); }",
+            "pub trait Shape { fn area(&self); }",
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use hir::Adt;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, ModuleItemOwner, NameOwner},
+    TextSize,
+};
+use stdx::{format_to, to_lower_snake_case};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: generate_test_matrix
+//
+// Generates a test for every combination of the variants of the enum
+// parameters of a function. Combinations, which already have a test in the
+// `tests` module, are skipped.
+//
+// ```
+// enum Color { Red, Blue }
+// enum Size { Small, Large }
+// fn paint<|>(color: Color, size: Size) {}
+// ```
+// ->
+// ```
+// enum Color { Red, Blue }
+// enum Size { Small, Large }
+// fn paint(color: Color, size: Size) {}
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn test_red_small() {
+//         todo!()
+//     }
+//
+//     #[test]
+//     fn test_red_large() {
+//         todo!()
+//     }
+//
+//     #[test]
+//     fn test_blue_small() {
+//         todo!()
+//     }
+//
+//     #[test]
+//     fn test_blue_large() {
+//         todo!()
+//     }
+// }
+// ```
+pub(crate) fn generate_test_matrix(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let name = fn_def.name()?;
+    if let Some(body) = fn_def.body() {
+        if body.syntax().text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    // Only free functions, whose siblings can include a `tests` module.
+    let parent = fn_def.syntax().parent()?;
+    let items: Vec<ast::ModuleItem> = if let Some(file) = ast::SourceFile::cast(parent.clone()) {
+        file.items().collect()
+    } else {
+        let item_list = ast::ItemList::cast(parent)?;
+        ast::Module::cast(item_list.syntax().parent()?)?;
+        item_list.items().collect()
+    };
+
+    let mut variants = Vec::new();
+    for param in fn_def.param_list()?.params() {
+        let ty = ctx.sema.type_of_pat(&param.pat()?)?;
+        if let Some(Adt::Enum(enum_)) = ty.as_adt() {
+            let names: Vec<String> = enum_
+                .variants(ctx.db)
+                .into_iter()
+                .map(|it| to_lower_snake_case(&it.name(ctx.db).to_string()))
+                .collect();
+            if names.is_empty() {
+                return None;
+            }
+            variants.push(names);
+        }
+    }
+    if variants.len() < 2 {
+        return None;
+    }
+
+    let tests_module = items.iter().find_map(|item| match item {
+        ast::ModuleItem::Module(it) if it.name()?.text() == "tests" => it.item_list(),
+        _ => None,
+    });
+    let existing: Vec<String> = tests_module
+        .iter()
+        .flat_map(|it| it.items())
+        .filter_map(|item| match item {
+            ast::ModuleItem::FnDef(it) => Some(it.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect();
+    let test_names: Vec<String> = combinations(&variants)
+        .into_iter()
+        .map(|it| format!("test_{}", it.join("_")))
+        .filter(|it| !existing.contains(it))
+        .collect();
+    if test_names.is_empty() {
+        return None;
+    }
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("generate_test_matrix"),
+        format!("Generate tests for all variant combinations of `{}`", name),
+        target,
+        |builder| {
+            let (offset, indent, mut text, end) = match &tests_module {
+                Some(item_list) => {
+                    let module =
+                        item_list.syntax().parent().unwrap_or_else(|| fn_def.syntax().clone());
+                    let indent = IndentLevel::from_node(&module) + 1;
+                    let offset = match item_list.items().last() {
+                        Some(it) => it.syntax().text_range().end(),
+                        None => match item_list.l_curly_token() {
+                            Some(it) => it.text_range().end(),
+                            None => return,
+                        },
+                    };
+                    (offset, indent, String::new(), String::new())
+                }
+                None => {
+                    let indent = IndentLevel::from_node(fn_def.syntax());
+                    let offset =
+                        items.last().map_or(TextSize::from(0), |it| it.syntax().text_range().end());
+                    let start = format!(
+                        "\n\n{0}#[cfg(test)]\n{0}mod tests {{\n{1}use super::*;",
+                        indent,
+                        indent + 1
+                    );
+                    (offset, indent + 1, start, format!("\n{}}}", indent))
+                }
+            };
+            for test_name in &test_names {
+                format_to!(
+                    text,
+                    "\n\n{0}#[test]\n{0}fn {1}() {{\n{2}todo!()\n{0}}}",
+                    indent,
+                    test_name,
+                    indent + 1
+                );
+            }
+            text.push_str(&end);
+            builder.insert(offset, text);
+        },
+    )
+}
+
+/// Returns all combinations, taking one element of each of `lists`, in order.
+fn combinations(lists: &[Vec<String>]) -> Vec<Vec<String>> {
+    lists.iter().fold(vec![Vec::new()], |acc, list| {
+        acc.iter()
+            .flat_map(|prefix| {
+                list.iter().map(move |it| {
+                    let mut combination = prefix.clone();
+                    combination.push(it.clone());
+                    combination
+                })
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_into_existing_tests_module() {
+        check_assist(
+            generate_test_matrix,
+            r"
+mod render {
+    pub enum Mode { Fast, HighQuality }
+    pub enum Output { Png, Svg }
+    pub fn render(<|>mode: Mode, scale: u32, output: Output) {}
+
+    #[cfg(test)]
+    mod tests {
+        #[test]
+        fn test_fast_png() {}
+    }
+}",
+            r"
+mod render {
+    pub enum Mode { Fast, HighQuality }
+    pub enum Output { Png, Svg }
+    pub fn render(mode: Mode, scale: u32, output: Output) {}
+
+    #[cfg(test)]
+    mod tests {
+        #[test]
+        fn test_fast_png() {}
+
+        #[test]
+        fn test_fast_svg() {
+            todo!()
+        }
+
+        #[test]
+        fn test_high_quality_png() {
+            todo!()
+        }
+
+        #[test]
+        fn test_high_quality_svg() {
+            todo!()
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // A single enum parameter.
+        check_assist_not_applicable(
+            generate_test_matrix,
+            "enum A { X, Y }\nfn foo<|>(a: A, b: u32) {}",
+        );
+        // All combinations are tested.
+        check_assist_not_applicable(
+            generate_test_matrix,
+            r"
+enum A { X }
+enum B { Y }
+fn foo<|>(a: A, b: B) {}
+mod tests {
+    fn test_x_y() {}
+}",
+        );
+        check_assist_not_applicable(
+            generate_test_matrix,
+            "enum A { X }\nenum B { Y }\nfn foo(a: A, b: B) { <|> }",
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use hir::HasSource;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, LoopBodyOwner, NameOwner},
+    match_ast,
+    SyntaxKind::{COMMENT, WHITESPACE},
+    SyntaxNode, TextRange,
+};
+
+use crate::{AssistContext, AssistId, Assists, GroupLabel};
+
+// Assist: add_inline_attr
+//
+// Adds an `#[inline]` hint to a function. Also available on calls inside of a
+// loop, which are likely hot paths. Only offered if enabled in the config.
+//
+// ```
+// fn square(x: u32) -> u32<|> { x * x }
+// ```
+// ->
+// ```
+// #[inline]
+// fn square(x: u32) -> u32 { x * x }
+// ```
+pub(crate) fn add_inline_attr(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if !ctx.config.inline_hints {
+        return None;
+    }
+    let (fn_def, file_id, target) = match ctx.find_node_at_offset::<ast::FnDef>() {
+        Some(fn_def) if !in_body(&fn_def, ctx) => {
+            (fn_def.clone(), ctx.frange.file_id, fn_def.syntax().text_range())
+        }
+        _ => {
+            let (function, target) = called_in_loop(ctx)?;
+            let module = ctx.sema.scope(&ctx.covering_element().parent()?).module()?;
+            if function.module(ctx.db).krate() != module.krate() {
+                return None;
+            }
+            let source = function.source(ctx.db);
+            let file_id = source.file_id.original_file(ctx.db);
+            (source.value, file_id, target)
+        }
+    };
+    if fn_def.find_attr("inline").is_some() {
+        return None;
+    }
+    let name = fn_def.name()?;
+    let offset = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+        .text_range()
+        .start();
+
+    let group = GroupLabel(format!("Add an inline hint to `{}`", name));
+    for attr in &["inline", "inline(always)"] {
+        acc.add_group(
+            &group,
+            AssistId("add_inline_attr"),
+            format!("Add `#[{}]` to `{}`", attr, name),
+            target,
+            |builder| {
+                builder.edit_file(file_id);
+                let indent = IndentLevel::from_node(fn_def.syntax());
+                builder.insert(offset, format!("#[{}]\n{}", attr, indent));
+            },
+        );
+    }
+    Some(())
+}
+
+// Assist: remove_inline_attr
+//
+// Removes the `#[inline]` hint of a function.
+//
+// ```
+// #[inline(always)]
+// fn square(x: u32) -> u32<|> { x * x }
+// ```
+// ->
+// ```
+// fn square(x: u32) -> u32 { x * x }
+// ```
+pub(crate) fn remove_inline_attr(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if in_body(&fn_def, ctx) {
+        return None;
+    }
+    let attr = fn_def.find_attr("inline")?;
+    let range = match attr.syntax().next_sibling_or_token() {
+        Some(ws) if ws.kind() == WHITESPACE => {
+            TextRange::new(attr.syntax().text_range().start(), ws.text_range().end())
+        }
+        _ => attr.syntax().text_range(),
+    };
+    let target = attr.syntax().text_range();
+    acc.add(AssistId("remove_inline_attr"), format!("Remove `{}`", attr), target, |builder| {
+        builder.delete(range)
+    })
+}
+
+fn in_body(fn_def: &ast::FnDef, ctx: &AssistContext) -> bool {
+    fn_def.body().map_or(false, |it| it.syntax().text_range().contains_range(ctx.frange.range))
+}
+
+fn loop_body(node: SyntaxNode) -> Option<ast::BlockExpr> {
+    match_ast! {
+        match node {
+            ast::ForExpr(it) => it.loop_body(),
+            ast::WhileExpr(it) => it.loop_body(),
+            ast::LoopExpr(it) => it.loop_body(),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the function called at the cursor, if the call is inside of a loop.
+fn called_in_loop(ctx: &AssistContext) -> Option<(hir::Function, TextRange)> {
+    let name_ref = ctx.find_node_at_offset::<ast::NameRef>()?;
+    let call = name_ref.syntax().ancestors().find(|it| {
+        ast::CallExpr::can_cast(it.kind()) || ast::MethodCallExpr::can_cast(it.kind())
+    })?;
+    let in_loop = call
+        .ancestors()
+        .take_while(|it| !ast::FnDef::can_cast(it.kind()) && !ast::LambdaExpr::can_cast(it.kind()))
+        .filter_map(loop_body)
+        .any(|body| body.syntax().text_range().contains_range(call.text_range()));
+    if !in_loop {
+        return None;
+    }
+    let function = if let Some(call) = ast::MethodCallExpr::cast(call.clone()) {
+        ctx.sema.resolve_method_call(&call)?
+    } else {
+        let call = ast::CallExpr::cast(call)?;
+        let path = match call.expr()? {
+            ast::Expr::PathExpr(it) => it.path()?,
+            _ => return None,
+        };
+        match ctx.sema.resolve_path(&path)? {
+            hir::PathResolution::Def(hir::ModuleDef::Function(it)) => it,
+            _ => return None,
+        }
+    };
+    Some((function, name_ref.syntax().text_range()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{
+            check_assist, check_assist_not_applicable, check_assist_not_applicable_with_config,
+            check_assist_with_config,
+        },
+        AssistConfig,
+    };
+
+    use super::*;
+
+    fn enabled() -> AssistConfig {
+        AssistConfig { inline_hints: true, ..AssistConfig::default() }
+    }
+
+    #[test]
+    fn add_inline_to_fn() {
+        check_assist_with_config(
+            add_inline_attr,
+            enabled(),
+            r"
+impl Point {
+    /// The squared length.
+    #[must_use]
+    pub fn len<|>(&self) -> u32 { self.x * self.x + self.y * self.y }
+}",
+            r"
+impl Point {
+    /// The squared length.
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> u32 { self.x * self.x + self.y * self.y }
+}",
+        );
+    }
+
+    #[test]
+    fn add_inline_to_function_called_in_loop() {
+        check_assist_with_config(
+            add_inline_attr,
+            enabled(),
+            r"
+fn square(x: u32) -> u32 { x * x }
+fn sum(xs: &[u32]) -> u32 {
+    let mut res = 0;
+    for x in xs {
+        res += squ<|>are(*x);
+    }
+    res
+}",
+            r"
+#[inline(always)]
+fn square(x: u32) -> u32 { x * x }
+fn sum(xs: &[u32]) -> u32 {
+    let mut res = 0;
+    for x in xs {
+        res += square(*x);
+    }
+    res
+}",
+        );
+    }
+
+    #[test]
+    fn add_inline_not_applicable() {
+        check_assist_not_applicable(add_inline_attr, "fn foo<|>() {}");
+        check_assist_not_applicable_with_config(
+            add_inline_attr,
+            enabled(),
+            "fn foo() {}\nfn bar() { foo<|>(); }",
+        );
+        check_assist_not_applicable_with_config(
+            add_inline_attr,
+            enabled(),
+            "#[inline]\nfn foo<|>() {}",
+        );
+    }
+
+    #[test]
+    fn remove_inline() {
+        check_assist(
+            remove_inline_attr,
+            "/// Docs.\n#[inline(never)]\nfn foo<|>() {}",
+            "/// Docs.\nfn foo() {}",
+        );
+        check_assist_not_applicable(remove_inline_attr, "#[inline]\nfn foo() { <|> }");
+    }
+}
@@ -0,0 +1,148 @@
+use hir::ModuleSource;
+use ra_syntax::ast::{self, edit::AstNodeEdit, edit::IndentLevel, AstNode, AttrsOwner, NameOwner};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: inline_module
+//
+// Moves the contents of the file of an out-of-line module into an inline
+// `mod` block, and deletes the file.
+//
+// ```
+// # //- /main.rs
+// mod <|>foo;
+// # //- /foo.rs
+// # fn bar() {}
+// ```
+// ->
+// ```
+// mod foo {
+//     fn bar() {}
+// }
+// ```
+pub(crate) fn inline_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    if module_ast.item_list().is_some() || module_ast.semicolon_token().is_none() {
+        return None;
+    }
+    // The `#[path]` of an inline module means something different.
+    if module_ast.attrs().any(|it| it.simple_name().map_or(false, |name| name == "path")) {
+        return None;
+    }
+    let name = module_ast.name()?;
+    let module = ctx.sema.to_def(&module_ast)?;
+    let source = module.definition_source(ctx.db);
+    let source_file = match source.value {
+        ModuleSource::SourceFile(it) => it,
+        ModuleSource::Module(_) => return None,
+    };
+    let file_id = source.file_id.original_file(ctx.db);
+    if file_id == ctx.frange.file_id {
+        return None;
+    }
+
+    let target = module_ast.syntax().text_range();
+    acc.add(AssistId("inline_module"), format!("Inline module `{}`", name), target, |builder| {
+        let indent = IndentLevel::from_node(module_ast.syntax());
+        let items = source_file.indent(indent + 1).syntax().to_string();
+        let items = items.trim();
+        let body = if items.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}{}\n{}}}", indent + 1, items, indent)
+        };
+        let semicolon = module_ast.semicolon_token().unwrap();
+        builder.replace(semicolon.text_range(), format!(" {}", body));
+        builder.delete_file(file_id);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_db::fixture::WithFixture;
+    use ra_ide_db::{source_change::FileSystemEdit, RootDatabase};
+
+    use crate::{
+        tests::{check_assist, check_assist_not_applicable, check_assist_target},
+        Assist, AssistConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn inline_nested_module() {
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs
+mod outer {
+    /// Docs.
+    pub(crate) mod <|>inner;
+}
+//- /outer/inner.rs
+//! Inner docs.
+use super::Foo;
+
+fn foo() -> &'static str {
+    "a
+b"
+}
+"#,
+            r#"mod outer {
+    /// Docs.
+    pub(crate) mod inner {
+        //! Inner docs.
+        use super::Foo;
+
+        fn foo() -> &'static str {
+            "a
+b"
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_empty_module() {
+        check_assist(inline_module, "//- /main.rs\nmod <|>foo;\n//- /foo.rs\n\n", "mod foo {}\n");
+    }
+
+    #[test]
+    fn inline_module_deletes_file() {
+        let (db, position) =
+            RootDatabase::with_position("//- /main.rs\nmod <|>foo;\n//- /foo.rs\nfn bar() {}\n");
+        let frange = ra_db::FileRange {
+            file_id: position.file_id,
+            range: ra_syntax::TextRange::empty(position.offset),
+        };
+        let assist = Assist::resolved(&db, &AssistConfig::default(), frange)
+            .into_iter()
+            .find(|it| it.assist.id.0 == "inline_module")
+            .unwrap();
+        match assist.source_change.file_system_edits.as_slice() {
+            [FileSystemEdit::DeleteFile { file }] => assert_ne!(*file, position.file_id),
+            edits => panic!("unexpected file system edits: {:?}", edits),
+        }
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(inline_module, "mod <|>foo { fn bar() {} }");
+        check_assist_not_applicable(inline_module, "mod <|>foo;");
+        check_assist_not_applicable(
+            inline_module,
+            "//- /main.rs\n#[path = \"bar.rs\"]\nmod <|>foo;\n//- /bar.rs\nfn bar() {}\n",
+        );
+    }
+
+    #[test]
+    fn inline_module_target() {
+        check_assist_target(
+            inline_module,
+            "//- /main.rs\npub mod <|>foo;\n//- /foo.rs\nfn bar() {}\n",
+            "pub mod foo;",
+        );
+    }
+}
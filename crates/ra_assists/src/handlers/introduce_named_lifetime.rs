@@ -1,6 +1,8 @@
 use ra_syntax::{
     ast::{self, NameOwner, TypeAscriptionOwner, TypeParamsOwner},
-    AstNode, SyntaxKind, TextRange, TextSize,
+    AstNode,
+    SyntaxKind::{self, FN_POINTER_TYPE, LIFETIME, PARAM_LIST, RET_TYPE},
+    SyntaxNode, SyntaxToken, TextRange, TextSize,
 };
 use rustc_hash::FxHashSet;
 
@@ -104,6 +106,123 @@ fn generate_impl_def_assist(
     })
 }
 
+// Assist: add_explicit_lifetimes
+//
+// Adds a lifetime parameter to a function, whose return type borrows from one
+// of several references, so that the lifetime can't be elided. The parameter
+// is used for all elided lifetimes of the signature.
+//
+// ```
+// fn <|>longest(x: &str, y: &str) -> &str {
+//     if x.len() > y.len() { x } else { y }
+// }
+// ```
+// ->
+// ```
+// fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+//     if x.len() > y.len() { x } else { y }
+// }
+// ```
+pub(crate) fn add_explicit_lifetimes(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if fn_def.body().map_or(false, |it| it.syntax().text_range().contains_range(ctx.frange.range)) {
+        return None;
+    }
+    let param_list = fn_def.param_list()?;
+    if param_list.self_param().map_or(false, |it| it.amp_token().is_some()) {
+        return None;
+    }
+    let ret_elided = elided_lifetimes(&fn_def.ret_type()?.type_ref()?);
+    if ret_elided.is_empty() {
+        return None;
+    }
+    let mut param_elided = Vec::new();
+    let mut param_named = 0;
+    for ty in param_list.params().filter_map(|it| it.ascribed_type()) {
+        param_elided.extend(elided_lifetimes(&ty));
+        param_named += lifetime_tokens(&ty).filter(|it| it.text() != "'_").count();
+    }
+    if param_elided.len() + param_named == 1 {
+        return None;
+    }
+
+    let target = fn_def.syntax().text_range();
+    if param_elided.is_empty() && param_named == 0 {
+        return acc.add(
+            AssistId("add_explicit_lifetimes"),
+            "Add `'static` lifetimes",
+            target,
+            |builder| {
+                for elided in ret_elided {
+                    elided.name(builder, "'static");
+                }
+            },
+        );
+    }
+    let new_lifetime_param = generate_unique_lifetime_param_name(&fn_def.type_param_list())?;
+    let end_of_fn_ident = fn_def.name()?.ident_token()?.text_range().end();
+    acc.add(
+        AssistId("add_explicit_lifetimes"),
+        format!("Add lifetime parameter `'{}`", new_lifetime_param),
+        target,
+        |builder| {
+            add_lifetime_param(&fn_def, builder, end_of_fn_ident, new_lifetime_param);
+            let lifetime = format!("'{}", new_lifetime_param);
+            for elided in param_elided.into_iter().chain(ret_elided) {
+                elided.name(builder, &lifetime);
+            }
+        },
+    )
+}
+
+/// A lifetime, which is left to elision.
+enum ElidedLifetime {
+    /// A reference without a lifetime, with the end of its `&`.
+    Reference(TextSize),
+    /// The anonymous lifetime `'_`.
+    Anonymous(TextRange),
+}
+
+impl ElidedLifetime {
+    fn name(self, builder: &mut AssistBuilder, lifetime: &str) {
+        match self {
+            ElidedLifetime::Reference(offset) => builder.insert(offset, format!("{} ", lifetime)),
+            ElidedLifetime::Anonymous(range) => builder.replace(range, lifetime),
+        }
+    }
+}
+
+/// Returns the elided lifetimes of `ty`, skipping those of nested function
+/// types, which are elided independently.
+fn elided_lifetimes(ty: &ast::TypeRef) -> Vec<ElidedLifetime> {
+    let references = ty
+        .syntax()
+        .descendants()
+        .filter(|it| in_same_elision_scope(ty, it))
+        .filter_map(ast::ReferenceType::cast)
+        .filter(|it| it.lifetime_token().is_none())
+        .filter_map(|it| Some(ElidedLifetime::Reference(it.amp_token()?.text_range().end())));
+    let anonymous = lifetime_tokens(ty)
+        .filter(|it| it.text() == "'_")
+        .map(|it| ElidedLifetime::Anonymous(it.text_range()));
+    references.chain(anonymous).collect()
+}
+
+fn lifetime_tokens(ty: &ast::TypeRef) -> impl Iterator<Item = SyntaxToken> + '_ {
+    ty.syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == LIFETIME)
+        .filter(move |it| in_same_elision_scope(ty, &it.parent()))
+}
+
+fn in_same_elision_scope(ty: &ast::TypeRef, node: &SyntaxNode) -> bool {
+    !node
+        .ancestors()
+        .take_while(|it| it != ty.syntax())
+        .any(|it| matches!(it.kind(), FN_POINTER_TYPE | PARAM_LIST | RET_TYPE))
+}
+
 /// Given a type parameter list, generate a unique lifetime parameter name
 /// which is not in the list
 fn generate_unique_lifetime_param_name(
@@ -315,4 +434,39 @@ mod tests {
             r#"fn my_fun<'other, 'a>(self, f: &'a Foo, b: &'other Bar) -> X<'a>"#,
         );
     }
+
+    #[test]
+    fn add_lifetime_for_multiple_references() {
+        check_assist(
+            add_explicit_lifetimes,
+            r#"fn <|>pick<'x, T>(a: &T, b: Wrapper<'_, T>, c: &'x u8, f: fn(&str) -> &str) -> (&T, &mut T)"#,
+            r#"fn pick<'x, T, 'a>(a: &'a T, b: Wrapper<'a, T>, c: &'x u8, f: fn(&str) -> &str) -> (&'a T, &'a mut T)"#,
+        );
+    }
+
+    #[test]
+    fn add_static_lifetime_without_references() {
+        check_assist(
+            add_explicit_lifetimes,
+            r#"fn name() -> &str<|> { "x" }"#,
+            r#"fn name() -> &'static str { "x" }"#,
+        );
+    }
+
+    #[test]
+    fn add_explicit_lifetimes_not_applicable() {
+        check_assist_not_applicable(add_explicit_lifetimes, r#"fn <|>first(x: &[u8]) -> &u8"#);
+        check_assist_not_applicable(
+            add_explicit_lifetimes,
+            r#"fn <|>get(&self, key: &str) -> &Value"#,
+        );
+        check_assist_not_applicable(
+            add_explicit_lifetimes,
+            r#"fn <|>eq(a: &str, b: &str) -> bool"#,
+        );
+        check_assist_not_applicable(
+            add_explicit_lifetimes,
+            r#"fn longest(x: &str, y: &str) -> &str { <|>x }"#,
+        );
+    }
 }
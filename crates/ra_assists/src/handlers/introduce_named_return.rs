@@ -0,0 +1,252 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, ElseBranch, NameOwner},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: introduce_named_return
+//
+// Replaces the `return` expressions of a function with assignments to a
+// `result` variable, which is returned at the end of the function. Only
+// returns, after which the function exits anyway, are converted.
+//
+// ```
+// fn <|>digit(c: char) -> u32 {
+//     if c == 'x' {
+//         return 10;
+//     } else if c.is_ascii_digit() {
+//         return c as u32 - '0' as u32;
+//     } else {
+//         0
+//     }
+// }
+// ```
+// ->
+// ```
+// fn digit(c: char) -> u32 {
+//     let mut result = Default::default();
+//     if c == 'x' {
+//         result = 10;
+//     } else if c.is_ascii_digit() {
+//         result = c as u32 - '0' as u32;
+//     } else {
+//         result = 0;
+//     }
+//     result
+// }
+// ```
+pub(crate) fn introduce_named_return(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    fn_def.ret_type()?.type_ref()?;
+    let body = fn_def.body()?;
+    if body.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    let last = match body.expr() {
+        Some(it) => it.syntax().clone(),
+        None => body.statements().last()?.syntax().clone(),
+    };
+
+    let mut exits = Vec::new();
+    block_exits(&body, &mut exits)?;
+    let returns: Vec<ast::ReturnExpr> = body
+        .syntax()
+        .descendants()
+        .filter_map(ast::ReturnExpr::cast)
+        .filter(|ret| {
+            !ret.syntax()
+                .ancestors()
+                .take_while(|it| it != body.syntax())
+                .any(|it| it.kind() == LAMBDA_EXPR || it.kind() == FN_DEF)
+        })
+        .collect();
+    let is_exit =
+        |ret: &ast::ReturnExpr| exits.iter().any(|it| matches!(it, Exit::Return(it) if it == ret));
+    if returns.is_empty() || exits.len() < 2 || !returns.iter().all(is_exit) {
+        return None;
+    }
+    let binds_result = fn_def
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .any(|it| it.name().map_or(false, |it| it.text() == "result"));
+    if binds_result {
+        return None;
+    }
+
+    let target = fn_def.syntax().text_range();
+    acc.add(
+        AssistId("introduce_named_return"),
+        "Introduce named return variable",
+        target,
+        |builder| {
+            for exit in &exits {
+                let (node, value) = match exit {
+                    Exit::Return(it) => match it.expr() {
+                        Some(value) => (it.syntax().clone(), value),
+                        None => continue,
+                    },
+                    Exit::Value(it) => (it.syntax().clone(), it.clone()),
+                };
+                let is_tail = node.parent().and_then(ast::BlockExpr::cast).is_some();
+                let semicolon = if is_tail { ";" } else { "" };
+                builder.replace(node.text_range(), format!("result = {}{}", value, semicolon));
+            }
+
+            let indent = IndentLevel::from_node(fn_def.syntax()) + 1;
+            if let Some(l_curly) = body.l_curly_token() {
+                builder.insert(
+                    l_curly.text_range().end(),
+                    format!("\n{}let mut result = Default::default();", indent),
+                );
+            }
+            builder.insert(last.text_range().end(), format!("\n{}result", indent));
+        },
+    )
+}
+
+/// A place where the function returns a value.
+enum Exit {
+    Return(ast::ReturnExpr),
+    Value(ast::Expr),
+}
+
+/// Collects the exits of `block`, which is in tail position. Returns `None`
+/// if the block doesn't produce a value.
+fn block_exits(block: &ast::BlockExpr, acc: &mut Vec<Exit>) -> Option<()> {
+    match block.expr() {
+        Some(it) => expr_exits(&it, acc),
+        None => match block.statements().last()? {
+            ast::Stmt::ExprStmt(it) => match it.expr()? {
+                it @ ast::Expr::ReturnExpr(_)
+                | it @ ast::Expr::IfExpr(_)
+                | it @ ast::Expr::MatchExpr(_)
+                | it @ ast::Expr::BlockExpr(_) => expr_exits(&it, acc),
+                _ => None,
+            },
+            ast::Stmt::LetStmt(_) => None,
+        },
+    }
+}
+
+fn expr_exits(expr: &ast::Expr, acc: &mut Vec<Exit>) -> Option<()> {
+    match expr {
+        ast::Expr::BlockExpr(it) => block_exits(it, acc),
+        ast::Expr::IfExpr(it) => {
+            block_exits(&it.then_branch()?, acc)?;
+            match it.else_branch()? {
+                ElseBranch::Block(it) => block_exits(&it, acc),
+                ElseBranch::IfExpr(it) => expr_exits(&ast::Expr::IfExpr(it), acc),
+            }
+        }
+        ast::Expr::MatchExpr(it) => {
+            for arm in it.match_arm_list()?.arms() {
+                expr_exits(&arm.expr()?, acc)?;
+            }
+            Some(())
+        }
+        ast::Expr::ReturnExpr(it) => {
+            it.expr()?;
+            acc.push(Exit::Return(it.clone()));
+            Some(())
+        }
+        _ => {
+            acc.push(Exit::Value(expr.clone()));
+            Some(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn introduce_named_return_in_match() {
+        check_assist(
+            introduce_named_return,
+            r#"
+impl Parser {
+    fn <|>next_token(&mut self) -> Token {
+        let c = self.bump();
+        match c {
+            '(' => return Token::LParen,
+            ')' => Token::RParen,
+            _ => {
+                let ident = self.ident();
+                return Token::Ident(ident);
+            }
+        }
+    }
+}
+"#,
+            r#"
+impl Parser {
+    fn next_token(&mut self) -> Token {
+        let mut result = Default::default();
+        let c = self.bump();
+        match c {
+            '(' => result = Token::LParen,
+            ')' => result = Token::RParen,
+            _ => {
+                let ident = self.ident();
+                result = Token::Ident(ident);
+            }
+        }
+        result
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skip_returns_of_closures() {
+        check_assist(
+            introduce_named_return,
+            r#"
+fn f(x: bool) -> u8<|> {
+    let g = || { return 2; };
+    if x { return 1 } else { return g(); };
+}
+"#,
+            r#"
+fn f(x: bool) -> u8 {
+    let mut result = Default::default();
+    let g = || { return 2; };
+    if x { result = 1; } else { result = g(); };
+    result
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            introduce_named_return,
+            "fn <|>f(x: bool) -> u8 { if x { return 1; } 0 }",
+        );
+        check_assist_not_applicable(
+            introduce_named_return,
+            "fn <|>f(x: bool) -> u8 { if x { 1 } else { 0 } }",
+        );
+        check_assist_not_applicable(introduce_named_return, "fn f() -> u8 { <|>return 1; }");
+        check_assist_not_applicable(
+            introduce_named_return,
+            "fn <|>f(result: u8) -> u8 { if true { return result } else { 0 } }",
+        );
+    }
+
+    #[test]
+    fn introduce_named_return_target() {
+        check_assist_target(
+            introduce_named_return,
+            "fn <|>f(x: bool) -> u8 { if x { return 1 } else { return 0 } }",
+            "fn f(x: bool) -> u8 { if x { return 1 } else { return 0 } }",
+        );
+    }
+}
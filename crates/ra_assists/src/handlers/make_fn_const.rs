@@ -0,0 +1,138 @@
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    SyntaxKind::TRAIT_DEF,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: make_fn_const
+//
+// Adds the `const` modifier to a function, whose body can be evaluated at
+// compile time.
+//
+// ```
+// fn square<|>(x: u32) -> u32 {
+//     x * x
+// }
+// ```
+// ->
+// ```
+// const fn square(x: u32) -> u32 {
+//     x * x
+// }
+// ```
+pub(crate) fn make_fn_const(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let name = fn_def.name()?;
+    let body = fn_def.body()?;
+    if body.syntax().text_range().contains_range(ctx.frange.range) {
+        return None;
+    }
+    if fn_def.const_token().is_some() || fn_def.async_token().is_some() || fn_def.abi().is_some() {
+        return None;
+    }
+    // Trait methods can't be `const`.
+    let container = fn_def.syntax().parent().and_then(|it| it.parent());
+    if let Some(it) = container {
+        let in_trait_impl =
+            ast::ImplDef::cast(it.clone()).map_or(false, |it| it.target_trait().is_some());
+        if it.kind() == TRAIT_DEF || in_trait_impl {
+            return None;
+        }
+    }
+    if !ctx.sema.to_def(&fn_def)?.is_const_evaluable(ctx.db) {
+        return None;
+    }
+
+    let offset = match fn_def.unsafe_token() {
+        Some(it) => it.text_range().start(),
+        None => fn_def.fn_token()?.text_range().start(),
+    };
+    let target = fn_def.syntax().text_range();
+    acc.add(AssistId("make_fn_const"), format!("Make `{}` a `const fn`", name), target, |builder| {
+        builder.insert(offset, "const ");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn make_method_const() {
+        check_assist(
+            make_fn_const,
+            r"
+struct Meters(u32);
+impl Meters {
+    pub unsafe fn <|>get(&self) -> u32 { self.0 }
+}",
+            r"
+struct Meters(u32);
+impl Meters {
+    pub const unsafe fn get(&self) -> u32 { self.0 }
+}",
+        );
+    }
+
+    #[test]
+    fn make_fn_with_attributes_const() {
+        check_assist(
+            make_fn_const,
+            "#[inline]\npub(crate) fn <|>zero() -> u32 { 0 }",
+            "#[inline]\npub(crate) const fn zero() -> u32 { 0 }",
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(make_fn_const, "const fn <|>zero() -> u32 { 0 }");
+        check_assist_not_applicable(make_fn_const, "async fn <|>zero() -> u32 { 0 }");
+        check_assist_not_applicable(make_fn_const, "extern \"C\" fn <|>zero() -> u32 { 0 }");
+        check_assist_not_applicable(make_fn_const, "fn zero() -> u32 { <|>0 }");
+        check_assist_not_applicable(make_fn_const, "trait T { fn <|>zero() -> u32 { 0 } }");
+        check_assist_not_applicable(
+            make_fn_const,
+            "impl Default for S { fn <|>default() -> S { S } }",
+        );
+        check_assist_not_applicable(make_fn_const, "extern { fn <|>zero() -> u32; }");
+    }
+
+    #[test]
+    fn not_applicable_with_non_const_operations() {
+        check_assist_not_applicable(
+            make_fn_const,
+            "fn one() -> u32 { 1 }\nfn <|>two() -> u32 { one() + one() }",
+        );
+        check_assist_not_applicable(
+            make_fn_const,
+            r"
+struct S;
+impl S { fn get(&self) -> u32 { 0 } }
+fn <|>get(s: &S) -> u32 { s.get() }",
+        );
+        check_assist_not_applicable(make_fn_const, "fn <|>apply(f: fn() -> u32) -> u32 { f() }");
+    }
+
+    #[test]
+    fn make_fn_const_with_const_calls_and_closures() {
+        check_assist(
+            make_fn_const,
+            r"
+fn one() -> u32 { 1 }
+const fn two() -> u32 { 2 }
+fn <|>three() -> u32 { let _f = || one(); two() + 1 }",
+            r"
+fn one() -> u32 { 1 }
+const fn two() -> u32 { 2 }
+const fn three() -> u32 { let _f = || one(); two() + 1 }",
+        );
+    }
+
+    #[test]
+    fn make_fn_const_target() {
+        check_assist_target(make_fn_const, "fn <|>zero() -> u32 { 0 }", "fn zero() -> u32 { 0 }");
+    }
+}
@@ -0,0 +1,186 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+use rustc_hash::FxHashSet;
+use stdx::format_to;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: merge_impl_blocks
+//
+// Merges the inherent `impl` blocks of a type, which are defined in the same
+// module, into the first one. Items, whose name is already defined by an
+// earlier block, are dropped.
+//
+// ```
+// struct Counter(u32);
+// impl Counter {
+//     fn get(&self) -> u32 { self.0 }
+// }
+// impl<|> Counter {
+//     fn incr(&mut self) { self.0 += 1 }
+// }
+// ```
+// ->
+// ```
+// struct Counter(u32);
+// impl Counter {
+//     fn get(&self) -> u32 { self.0 }
+//
+//     fn incr(&mut self) { self.0 += 1 }
+// }
+// ```
+pub(crate) fn merge_impl_blocks(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::ImplDef>()?;
+    let key = impl_key(&impl_def)?;
+    let impls: Vec<ast::ImplDef> = impl_def
+        .syntax()
+        .parent()?
+        .children()
+        .filter_map(ast::ImplDef::cast)
+        .filter(|it| impl_key(it).as_ref() == Some(&key))
+        .collect();
+    if impls.len() < 2 {
+        return None;
+    }
+    let first = impls[0].item_list()?;
+
+    let target = impl_def.syntax().text_range();
+    acc.add(
+        AssistId("merge_impl_blocks"),
+        format!("Merge `impl {}` blocks", key.self_ty),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(impls[0].syntax());
+            let mut seen: FxHashSet<String> =
+                first.assoc_items().filter_map(|it| Some(it.name()?.to_string())).collect();
+            let mut buf = String::new();
+            for imp in &impls[1..] {
+                let items = imp.item_list().into_iter().flat_map(|it| it.assoc_items());
+                for item in items {
+                    if let Some(name) = item.name() {
+                        if !seen.insert(name.to_string()) {
+                            continue;
+                        }
+                    }
+                    format_to!(buf, "\n\n{}{}", indent + 1, item.syntax());
+                }
+
+                let mut range = imp.syntax().text_range();
+                if let Some(ws) =
+                    imp.syntax().prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+                {
+                    range = TextRange::new(ws.text_range().start(), range.end());
+                }
+                builder.delete(range);
+            }
+
+            match first.assoc_items().last() {
+                Some(last) => builder.insert(last.syntax().text_range().end(), buf),
+                None if !buf.is_empty() => builder.replace(
+                    first.syntax().text_range(),
+                    format!("{{\n{}\n{}}}", buf.trim_start_matches('\n'), indent),
+                ),
+                None => (),
+            }
+        },
+    )
+}
+
+/// The parts of an inherent `impl` header, which must be equal for two blocks
+/// to be merged.
+#[derive(PartialEq, Eq)]
+struct ImplKey {
+    type_params: Option<String>,
+    self_ty: String,
+    where_clause: Option<String>,
+}
+
+fn impl_key(impl_def: &ast::ImplDef) -> Option<ImplKey> {
+    if impl_def.target_trait().is_some() || impl_def.unsafe_token().is_some() {
+        return None;
+    }
+    Some(ImplKey {
+        type_params: impl_def.type_param_list().map(|it| it.syntax().to_string()),
+        self_ty: impl_def.target_type()?.syntax().to_string(),
+        where_clause: impl_def.where_clause().map(|it| it.syntax().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn merge_generated_impl_blocks() {
+        check_assist(
+            merge_impl_blocks,
+            r#"
+mod generated {
+    pub struct Config<T> { value: T }
+    impl<T> Config<T> {}
+    impl<T> Config<T> {
+        /// Returns the value.
+        pub fn value(&self) -> &T { &self.value }
+        const DEFAULT_NAME: &str = "config";
+    }
+    impl Config<u32> {
+        pub fn double(&self) -> u32 { self.value * 2 }
+    }
+    impl<T> Config<T> {
+        pub fn value(&self) -> &T {
+            &self.value
+        }
+
+        pub fn set_value(&mut self, value: T) {
+            self.value = value;
+        }
+    }<|>
+}
+"#,
+            r#"
+mod generated {
+    pub struct Config<T> { value: T }
+    impl<T> Config<T> {
+        /// Returns the value.
+        pub fn value(&self) -> &T { &self.value }
+
+        const DEFAULT_NAME: &str = "config";
+
+        pub fn set_value(&mut self, value: T) {
+            self.value = value;
+        }
+    }
+    impl Config<u32> {
+        pub fn double(&self) -> u32 { self.value * 2 }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            merge_impl_blocks,
+            "struct S;\nimpl<|> S {}\nimpl Clone for S {}",
+        );
+        check_assist_not_applicable(
+            merge_impl_blocks,
+            "struct S;\nimpl<|> S {}\nmod m { impl super::S {} }",
+        );
+    }
+
+    #[test]
+    fn merge_impl_blocks_target() {
+        check_assist_target(
+            merge_impl_blocks,
+            "struct S;\nimpl S { fn a() {} }\nimpl<|> S { fn b() {} }",
+            "impl S { fn b() {} }",
+        );
+    }
+}
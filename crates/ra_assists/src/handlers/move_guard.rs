@@ -1,13 +1,18 @@
 use ra_syntax::{
-    ast::{AstNode, IfExpr, MatchArm},
+    ast::{edit::IndentLevel, AstNode, BlockExpr, ElseBranch, Expr, IfExpr, MatchArm, Pat},
+    Direction,
     SyntaxKind::WHITESPACE,
+    TextRange,
 };
+use stdx::format_to;
+use test_utils::mark;
 
 use crate::{AssistContext, AssistId, Assists};
 
 // Assist: move_guard_to_arm_body
 //
-// Moves match guard into match arm body.
+// Moves match guard into match arm body. The next arm, to which the match
+// falls through when the guard fails, becomes the `else` branch.
 //
 // ```
 // enum Action { Move { distance: u32 }, Stop }
@@ -37,7 +42,25 @@ pub(crate) fn move_guard_to_arm_body(acc: &mut Assists, ctx: &AssistContext) ->
 
     let guard_conditions = guard.expr()?;
     let arm_expr = match_arm.expr()?;
-    let buf = format!("if {} {{ {} }}", guard_conditions.syntax().text(), arm_expr.syntax().text());
+
+    // The fallthrough is only known, if the next arm matches everything this
+    // arm does.
+    let next_arm = match_arm.syntax().siblings(Direction::Next).skip(1).find_map(MatchArm::cast)?;
+    let next_pat = next_arm.pat()?;
+    let merges_next_arm = next_pat.syntax().text() == match_arm.pat()?.syntax().text();
+    if next_arm.guard().is_some()
+        || !(merges_next_arm || matches!(next_pat, Pat::PlaceholderPat(_)))
+    {
+        mark::hit!(move_guard_to_arm_body_unknown_fallthrough);
+        return None;
+    }
+    let else_expr = next_arm.expr()?;
+
+    let mut buf =
+        format!("if {} {{ {} }}", guard_conditions.syntax().text(), arm_expr.syntax().text());
+    if !is_unit(&else_expr) {
+        format_to!(buf, " else {{ {} }}", else_expr.syntax().text());
+    }
 
     let target = guard.syntax().text_range();
     acc.add(AssistId("move_guard_to_arm_body"), "Move guard to arm body", target, |edit| {
@@ -50,12 +73,19 @@ pub(crate) fn move_guard_to_arm_body(acc: &mut Assists, ctx: &AssistContext) ->
 
         edit.delete(guard.syntax().text_range());
         edit.replace_node_and_indent(arm_expr.syntax(), buf);
+        if merges_next_arm {
+            edit.delete(TextRange::new(
+                match_arm.syntax().text_range().end(),
+                next_arm.syntax().text_range().end(),
+            ));
+        }
     })
 }
 
 // Assist: move_arm_cond_to_match_guard
 //
-// Moves if expression from match arm body into a guard.
+// Moves if expression from match arm body into a guard. Each `else` branch is
+// split into an arm of its own.
 //
 // ```
 // enum Action { Move { distance: u32 }, Stop }
@@ -81,22 +111,43 @@ pub(crate) fn move_guard_to_arm_body(acc: &mut Assists, ctx: &AssistContext) ->
 pub(crate) fn move_arm_cond_to_match_guard(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     let match_arm: MatchArm = ctx.find_node_at_offset::<MatchArm>()?;
     let match_pat = match_arm.pat()?;
+    if match_arm.guard().is_some() {
+        return None;
+    }
 
     let arm_body = match_arm.expr()?;
     let if_expr: IfExpr = IfExpr::cast(arm_body.syntax().clone())?;
-    let cond = if_expr.condition()?;
-    let then_block = if_expr.then_branch()?;
 
-    // Not support if with else branch
-    if if_expr.else_branch().is_some() {
-        return None;
-    }
-    // Not support moving if let to arm guard
-    if cond.pat().is_some() {
-        return None;
+    let mut branches = Vec::new();
+    let mut tail = None;
+    let mut current = if_expr.clone();
+    loop {
+        let cond = current.condition()?;
+        // Not support moving if let to arm guard
+        if cond.pat().is_some() {
+            return None;
+        }
+        branches.push((cond, current.then_branch()?));
+        match current.else_branch() {
+            Some(ElseBranch::IfExpr(it)) => current = it,
+            Some(ElseBranch::Block(it)) => {
+                tail = Some(it);
+                break;
+            }
+            None => break,
+        }
     }
 
-    let buf = format!(" if {}", cond.syntax().text());
+    // Without an `else`, the arm evaluates to `()` when the condition fails,
+    // while the guard falls through to the next arm.
+    let falls_through_to_unit = match_arm
+        .syntax()
+        .siblings(Direction::Next)
+        .skip(1)
+        .find_map(MatchArm::cast)
+        .filter(|it| it.guard().is_none() && matches!(it.pat(), Some(Pat::PlaceholderPat(_))))
+        .and_then(|it| it.expr())
+        .map_or(false, |it| is_unit(&it));
 
     let target = if_expr.syntax().text_range();
     acc.add(
@@ -104,22 +155,50 @@ pub(crate) fn move_arm_cond_to_match_guard(acc: &mut Assists, ctx: &AssistContex
         "Move condition to match guard",
         target,
         |edit| {
-            let then_only_expr = then_block.statements().next().is_none();
-
-            match &then_block.expr() {
-                Some(then_expr) if then_only_expr => {
-                    edit.replace(if_expr.syntax().text_range(), then_expr.syntax().text())
+            let indent = IndentLevel::from_node(match_arm.syntax());
+            let pat = match_pat.syntax().text();
+            let mut buf = String::new();
+            for (cond, block) in &branches {
+                if !buf.is_empty() {
+                    format_to!(buf, ",\n{}", indent);
                 }
-                _ => edit.replace(if_expr.syntax().text_range(), then_block.syntax().text()),
+                format_to!(buf, "{} if {} => {}", pat, cond.syntax().text(), block_body(block));
+            }
+            match &tail {
+                Some(block) => format_to!(buf, ",\n{}{} => {}", indent, pat, block_body(block)),
+                None if !falls_through_to_unit => format_to!(buf, ",\n{}{} => {{}}", indent, pat),
+                None => (),
             }
 
-            edit.insert(match_pat.syntax().text_range().end(), buf);
+            let range = TextRange::new(
+                match_pat.syntax().text_range().start(),
+                match_arm.syntax().text_range().end(),
+            );
+            edit.replace(range, buf);
         },
     )
 }
 
+/// Returns the text of the block's only expression or of the whole block.
+fn block_body(block: &BlockExpr) -> String {
+    match block.expr() {
+        Some(expr) if block.statements().next().is_none() => expr.syntax().to_string(),
+        _ => block.syntax().to_string(),
+    }
+}
+
+fn is_unit(expr: &Expr) -> bool {
+    match expr {
+        Expr::TupleExpr(it) => it.exprs().next().is_none(),
+        Expr::BlockExpr(it) => it.statements().next().is_none() && it.expr().is_none(),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use test_utils::mark;
+
     use super::*;
 
     use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
@@ -161,7 +240,7 @@ mod tests {
                 let t = 'a';
                 let chars = "abcd";
                 match t {
-                    '\r' => if chars.clone().next() == Some('\n') { false },
+                    '\r' => if chars.clone().next() == Some('\n') { false } else { true },
                     _ => true
                 }
             }
@@ -184,7 +263,7 @@ mod tests {
             r#"
             fn f() {
                 match x {
-                    y @ 4 | y @ 5 => if y > 5 { true },
+                    y @ 4 | y @ 5 => if y > 5 { true } else { false },
                     _ => false
                 }
             }
@@ -192,6 +271,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn move_guard_to_arm_body_merges_next_arm() {
+        check_assist(
+            move_guard_to_arm_body,
+            r#"
+            fn f(x: Option<u32>) -> u32 {
+                match x {
+                    Some(y) <|>if y > 5 => y,
+                    Some(y) => y + 1,
+                    None => 0,
+                }
+            }
+            "#,
+            r#"
+            fn f(x: Option<u32>) -> u32 {
+                match x {
+                    Some(y) => if y > 5 { y } else { y + 1 },
+                    None => 0,
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn move_guard_to_arm_body_unknown_fallthrough() {
+        mark::check!(move_guard_to_arm_body_unknown_fallthrough);
+        check_assist_not_applicable(
+            move_guard_to_arm_body,
+            r#"
+            fn f(x: Option<u32>) -> u32 {
+                match x {
+                    Some(y) <|>if y > 5 => y,
+                    None => 0,
+                    Some(_) => 1,
+                }
+            }
+            "#,
+        );
+    }
+
     #[test]
     fn move_arm_cond_to_match_guard_works() {
         check_assist(
@@ -201,7 +321,7 @@ mod tests {
                 let t = 'a';
                 let chars = "abcd";
                 match t {
-                    '\r' => if chars.clone().next() == Some('\n') { <|>false },
+                    '\r' => if chars.clone().next() == Some('\n') { <|>false } else { true },
                     _ => true
                 }
             }
@@ -212,6 +332,7 @@ mod tests {
                 let chars = "abcd";
                 match t {
                     '\r' if chars.clone().next() == Some('\n') => false,
+                    '\r' => true,
                     _ => true
                 }
             }
@@ -256,6 +377,7 @@ mod tests {
                 let chars = "abcd";
                 match t {
                     '\r' if chars.clone().next().is_some() => {  },
+                    '\r' => {},
                     _ => true
                 }
             }
@@ -274,9 +396,8 @@ mod tests {
                 match t {
                     '\r' => if chars.clone().next().is_some() {
                         t = 'e';<|>
-                        false
                     },
-                    _ => true
+                    _ => {}
                 }
             }
             "#,
@@ -287,9 +408,58 @@ mod tests {
                 match t {
                     '\r' if chars.clone().next().is_some() => {
                         t = 'e';
-                        false
                     },
-                    _ => true
+                    _ => {}
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn move_arm_cond_to_match_guard_splits_else_if_chain() {
+        check_assist(
+            move_arm_cond_to_match_guard,
+            r#"
+            fn f(x: Option<u32>) -> &'static str {
+                match x {
+                    Some(y) => <|>if y > 10 {
+                        "large"
+                    } else if y > 5 {
+                        "medium"
+                    } else {
+                        let _ = y;
+                        "small"
+                    },
+                    None => "none",
+                }
+            }
+            "#,
+            r#"
+            fn f(x: Option<u32>) -> &'static str {
+                match x {
+                    Some(y) if y > 10 => "large",
+                    Some(y) if y > 5 => "medium",
+                    Some(y) => {
+                        let _ = y;
+                        "small"
+                    },
+                    None => "none",
+                }
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn move_arm_cond_to_match_guard_with_guard_not_works() {
+        check_assist_not_applicable(
+            move_arm_cond_to_match_guard,
+            r#"
+            fn f(x: Option<u32>) {
+                match x {
+                    Some(y) if y > 1 => <|>if y > 10 { foo() },
+                    _ => (),
                 }
             }
             "#,
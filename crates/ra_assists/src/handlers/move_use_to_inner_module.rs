@@ -0,0 +1,366 @@
+use hir::{Module, ModuleDef};
+use ra_ide_db::defs::{classify_name_ref, Definition};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{
+        self, edit::IndentLevel, AstNode, ModuleItemOwner, NameOwner, PathSegmentKind,
+        VisibilityOwner,
+    },
+    SyntaxKind::{ITEM_LIST, SOURCE_FILE, WHITESPACE},
+    TextRange,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: move_use_to_inner_module
+//
+// Moves a `use` item into the inline module, which is the only place the
+// imported items are used in.
+//
+// ```
+// mod cache { pub struct Cache; }
+// use cache::<|>Cache;
+//
+// fn main() {}
+//
+// mod tests {
+//     use super::*;
+//
+//     fn build() -> Cache { Cache }
+// }
+// ```
+// ->
+// ```
+// mod cache { pub struct Cache; }
+//
+// fn main() {}
+//
+// mod tests {
+//     use super::*;
+//     use super::cache::Cache;
+//
+//     fn build() -> Cache { Cache }
+// }
+// ```
+pub(crate) fn move_use_to_inner_module(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    // Other modules may depend on an exported import.
+    if use_item.visibility().is_some() {
+        return None;
+    }
+    let parent = use_item.syntax().parent()?;
+    if parent.kind() != SOURCE_FILE && parent.kind() != ITEM_LIST {
+        return None;
+    }
+    let use_tree = use_item.use_tree()?;
+    let path = use_tree.path()?;
+    let current_module = ctx.sema.scope(use_item.syntax()).module()?;
+
+    let mut leaves = Vec::new();
+    collect_leaves(&use_tree, &mut leaves)?;
+    let defs = leaves
+        .iter()
+        .map(|it| Some(classify_name_ref(&ctx.sema, &it.segment()?.name_ref()?)?.definition()))
+        .collect::<Option<Vec<Definition>>>()?;
+
+    let file_id = ctx.frange.file_id;
+    let source_file = ctx.sema.parse(file_id);
+    let mut target_module: Option<ast::Module> = None;
+    let mut qualified_usages = Vec::new();
+    for def in &defs {
+        for reference in def.find_usages(ctx.db, None) {
+            let range = reference.file_range.range;
+            if reference.file_range.file_id != file_id {
+                // A module in another file may use the import through `super`.
+                let module = ctx.sema.to_module_def(reference.file_range.file_id)?;
+                if is_descendant(ctx, module, current_module) {
+                    return None;
+                }
+                continue;
+            }
+            if use_item.syntax().text_range().contains_range(range) {
+                continue;
+            }
+            let name_ref: ast::NameRef = find_node_at_offset(source_file.syntax(), range.start())?;
+            if name_ref.syntax().ancestors().any(|it| ast::UseItem::can_cast(it.kind())) {
+                return None;
+            }
+            let usage_path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
+            if let Some(qualifier) = usage_path.qualifier() {
+                match qualifier.segment()?.kind()? {
+                    // Items which are named through another path don't use the import.
+                    PathSegmentKind::Name(_) | PathSegmentKind::Type { .. } => continue,
+                    PathSegmentKind::SuperKw if qualifier.qualifier().is_none() => {
+                        qualified_usages.push((usage_path.clone(), name_ref.clone()))
+                    }
+                    _ => return None,
+                }
+            }
+
+            let module = name_ref
+                .syntax()
+                .ancestors()
+                .find(|it| it.parent().as_ref() == Some(&parent))
+                .and_then(ast::Module::cast)?;
+            module.item_list()?;
+            match &target_module {
+                Some(it) if it != &module => return None,
+                Some(_) => (),
+                None => target_module = Some(module),
+            }
+        }
+    }
+    let target_module = target_module?;
+    let target_def = ctx.sema.to_def(&target_module)?;
+    // `super::Name` only refers to the import from the module itself.
+    for (usage_path, _) in &qualified_usages {
+        if ctx.sema.scope(usage_path.syntax()).module()? != target_def {
+            return None;
+        }
+    }
+
+    // Relative paths need to start one module further up.
+    let first_segment = first_segment(&path)?;
+    let prefix = match first_segment.kind()? {
+        PathSegmentKind::CrateKw => None,
+        _ if first_segment.coloncolon_token().is_some() => None,
+        PathSegmentKind::SelfKw => Some((first_segment.syntax().text_range(), "super")),
+        PathSegmentKind::SuperKw => Some((first_segment.syntax().text_range(), "super::super")),
+        PathSegmentKind::Name(name_ref) => match classify_name_ref(&ctx.sema, &name_ref)?
+            .definition()
+        {
+            Definition::ModuleDef(ModuleDef::Module(it))
+                if it.parent(ctx.db).is_none() && it.krate() != current_module.krate() =>
+            {
+                None
+            }
+            _ => Some((TextRange::empty(first_segment.syntax().text_range().start()), "super::")),
+        },
+        PathSegmentKind::Type { .. } => return None,
+    };
+    let mut moved = use_item.syntax().to_string();
+    if let Some((range, text)) = prefix {
+        let range = range - use_item.syntax().text_range().start();
+        moved.replace_range(std::ops::Range::<usize>::from(range), text);
+    }
+
+    let name = target_module.name()?;
+    let target = use_item.syntax().text_range();
+    acc.add(
+        AssistId("move_use_to_inner_module"),
+        format!("Move `use` into `mod {}`", name),
+        target,
+        |builder| {
+            builder.delete(deleted_range(&use_item));
+
+            let item_list = target_module.item_list().unwrap();
+            let indent = IndentLevel::from_node(target_module.syntax()) + 1;
+            let leading_uses =
+                item_list.items().take_while(|it| matches!(it, ast::ModuleItem::UseItem(_))).last();
+            match (leading_uses, item_list.items().next()) {
+                (Some(last), _) => builder
+                    .insert(last.syntax().text_range().end(), format!("\n{}{}", indent, moved)),
+                (None, first) => {
+                    let offset = item_list
+                        .l_curly_token()
+                        .map_or(item_list.syntax().text_range().start(), |it| {
+                            it.text_range().end()
+                        });
+                    let separator = if first.is_some() { "\n" } else { "" };
+                    builder.insert(offset, format!("\n{}{}{}", indent, moved, separator));
+                }
+            }
+
+            for (usage_path, name_ref) in &qualified_usages {
+                builder.replace(usage_path.syntax().text_range(), name_ref.to_string());
+            }
+        },
+    )
+}
+
+/// Collects the paths of the imported items, which must all be named.
+fn collect_leaves(use_tree: &ast::UseTree, acc: &mut Vec<ast::Path>) -> Option<()> {
+    // Aliases and glob imports can't be traced back to their usages.
+    if use_tree.alias().is_some() || use_tree.star_token().is_some() {
+        return None;
+    }
+    match use_tree.use_tree_list() {
+        Some(list) => {
+            for it in list.use_trees() {
+                collect_leaves(&it, acc)?;
+            }
+        }
+        None => {
+            let path = use_tree.path()?;
+            match path.segment()?.kind()? {
+                PathSegmentKind::Name(_) => acc.push(path),
+                _ => return None,
+            }
+        }
+    }
+    Some(())
+}
+
+fn first_segment(path: &ast::Path) -> Option<ast::PathSegment> {
+    let mut path = path.clone();
+    while let Some(qualifier) = path.qualifier() {
+        path = qualifier;
+    }
+    path.segment()
+}
+
+fn is_descendant(ctx: &AssistContext, module: Module, ancestor: Module) -> bool {
+    let mut current = Some(module);
+    while let Some(it) = current {
+        if it == ancestor {
+            return true;
+        }
+        current = it.parent(ctx.db);
+    }
+    false
+}
+
+/// Returns the range of `use_item`, together with the whitespace which
+/// separates it from the previous item or, if there is none, the next one.
+fn deleted_range(use_item: &ast::UseItem) -> TextRange {
+    let range = use_item.syntax().text_range();
+    let prev = use_item.syntax().prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE);
+    match prev {
+        Some(ws) if use_item.syntax().prev_sibling().is_some() => {
+            TextRange::new(ws.text_range().start(), range.end())
+        }
+        _ => match use_item.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE) {
+            Some(ws) => TextRange::new(range.start(), ws.text_range().end()),
+            None => range,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn move_relative_use_with_super_usages() {
+        check_assist(
+            move_use_to_inner_module,
+            r#"
+mod shapes { pub struct Circle; pub struct Square; }
+use shapes::{Circle, <|>Square};
+
+mod render {
+    fn draw(_: &super::Circle) {}
+    fn fill(_: &super::Square) {}
+}
+"#,
+            r#"
+mod shapes { pub struct Circle; pub struct Square; }
+
+mod render {
+    use super::shapes::{Circle, Square};
+
+    fn draw(_: &Circle) {}
+    fn fill(_: &Square) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_self_use_after_existing_uses() {
+        check_assist(
+            move_use_to_inner_module,
+            r#"
+mod outer {
+    mod id { pub struct Id; }
+    use self::id::Id<|>;
+
+    mod tests {
+        use super::*;
+        fn f() -> Id { Id }
+    }
+}
+"#,
+            r#"
+mod outer {
+    mod id { pub struct Id; }
+
+    mod tests {
+        use super::*;
+        use super::id::Id;
+        fn f() -> Id { Id }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // Used outside of the module.
+        check_assist_not_applicable(
+            move_use_to_inner_module,
+            r#"
+mod a { pub struct A; }
+use a::A<|>;
+fn f(_: A) {}
+mod m { use super::*; fn g(_: A) {} }
+"#,
+        );
+        // Used in two modules.
+        check_assist_not_applicable(
+            move_use_to_inner_module,
+            r#"
+mod a { pub struct A; }
+use a::A<|>;
+mod m { use super::*; fn g(_: A) {} }
+mod n { use super::*; fn g(_: A) {} }
+"#,
+        );
+        // Not used at all.
+        check_assist_not_applicable(
+            move_use_to_inner_module,
+            r#"
+mod a { pub struct A; }
+use a::A<|>;
+mod m {}
+"#,
+        );
+        // Used by a module in another file.
+        check_assist_not_applicable(
+            move_use_to_inner_module,
+            r#"
+//- /main.rs
+mod a { pub struct A; }
+use a::A<|>;
+mod m { use super::*; fn g(_: A) {} }
+mod other;
+//- /other.rs
+fn g(_: super::A) {}
+"#,
+        );
+        check_assist_not_applicable(
+            move_use_to_inner_module,
+            r#"
+mod a { pub struct A; }
+pub(crate) use a::A<|>;
+mod m { use super::*; fn g(_: A) {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn move_use_to_inner_module_target() {
+        check_assist_target(
+            move_use_to_inner_module,
+            r#"
+mod a { pub struct A; }
+use a::A<|>;
+mod m { use super::*; fn g(_: A) {} }
+"#,
+            "use a::A;",
+        );
+    }
+}
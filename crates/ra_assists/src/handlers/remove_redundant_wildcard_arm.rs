@@ -0,0 +1,205 @@
+use hir::{Adt, EnumVariant, HasSource, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner, Pat},
+    SyntaxKind::{COMMA, WHITESPACE},
+    TextRange,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: remove_redundant_wildcard_arm
+//
+// Removes the `_` arm of a `match` on an enum, whose variants are all matched
+// by the other arms, so that adding a variant makes the `match` fail to
+// compile instead of silently falling through.
+//
+// ```
+// enum Mode { Read, Write }
+// fn f(mode: Mode) -> u8 {
+//     match mode {
+//         Mode::Read => 1,
+//         Mode::Write => 2,
+//         <|>_ => 0,
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Mode { Read, Write }
+// fn f(mode: Mode) -> u8 {
+//     match mode {
+//         Mode::Read => 1,
+//         Mode::Write => 2,
+//     }
+// }
+// ```
+pub(crate) fn remove_redundant_wildcard_arm(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let arms: Vec<ast::MatchArm> = match_expr.match_arm_list()?.arms().collect();
+    let wildcard = arms
+        .iter()
+        .find(|arm| arm.guard().is_none() && matches!(arm.pat(), Some(Pat::PlaceholderPat(_))))?;
+
+    let enum_ = ctx.sema.type_of_expr(&match_expr.expr()?)?.autoderef(ctx.db).find_map(|ty| {
+        match ty.as_adt() {
+            Some(Adt::Enum(it)) => Some(it),
+            _ => None,
+        }
+    })?;
+    if enum_
+        .source(ctx.db)
+        .value
+        .attrs()
+        .any(|it| it.simple_name().as_deref() == Some("non_exhaustive"))
+    {
+        return None;
+    }
+
+    let mut covered = FxHashSet::default();
+    for arm in arms.iter().filter(|it| *it != wildcard && it.guard().is_none()) {
+        let pats = match arm.pat()? {
+            Pat::OrPat(it) => it.pats().collect(),
+            pat => vec![pat],
+        };
+        for pat in pats {
+            if let Some(variant) = matched_variant(ctx, &pat) {
+                covered.insert(variant);
+            }
+        }
+    }
+    if !enum_.variants(ctx.db).into_iter().all(|it| covered.contains(&it)) {
+        return None;
+    }
+
+    let target = wildcard.syntax().text_range();
+    acc.add(
+        AssistId("remove_redundant_wildcard_arm"),
+        "Remove redundant wildcard arm",
+        target,
+        |builder| {
+            let mut range = target;
+            if let Some(comma) =
+                wildcard.syntax().next_sibling_or_token().filter(|it| it.kind() == COMMA)
+            {
+                range = range.cover(comma.text_range());
+            }
+            if let Some(ws) =
+                wildcard.syntax().prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+            {
+                range = TextRange::new(ws.text_range().start(), range.end());
+            }
+            builder.delete(range);
+        },
+    )
+}
+
+/// Returns the variant, if `pat` matches every value of it.
+fn matched_variant(ctx: &AssistContext, pat: &Pat) -> Option<EnumVariant> {
+    let (path, irrefutable) = match pat {
+        Pat::PathPat(it) => (it.path()?, true),
+        Pat::TupleStructPat(it) => (it.path()?, it.args().all(|it| is_irrefutable(&it))),
+        Pat::RecordPat(it) => {
+            let fields = it.record_field_pat_list()?;
+            let irrefutable = fields
+                .record_field_pats()
+                .all(|it| it.pat().map_or(true, |it| is_irrefutable(&it)));
+            (it.path()?, irrefutable)
+        }
+        Pat::BindPat(it) => {
+            return match ctx.sema.resolve_bind_pat_to_const(it)? {
+                ModuleDef::EnumVariant(it) => Some(it),
+                _ => None,
+            }
+        }
+        _ => return None,
+    };
+    if !irrefutable {
+        return None;
+    }
+    match ctx.sema.resolve_path(&path)? {
+        PathResolution::Def(ModuleDef::EnumVariant(it)) => Some(it),
+        _ => None,
+    }
+}
+
+fn is_irrefutable(pat: &Pat) -> bool {
+    match pat {
+        Pat::PlaceholderPat(_) | Pat::DotDotPat(_) => true,
+        Pat::BindPat(it) => it.pat().map_or(true, |it| is_irrefutable(&it)),
+        Pat::TuplePat(it) => it.args().all(|it| is_irrefutable(&it)),
+        Pat::ParenPat(it) => it.pat().map_or(false, |it| is_irrefutable(&it)),
+        Pat::RefPat(it) => it.pat().map_or(false, |it| is_irrefutable(&it)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn remove_wildcard_after_or_and_record_patterns() {
+        check_assist(
+            remove_redundant_wildcard_arm,
+            r#"
+enum Shape { Circle { r: f32 }, Rect(f32, f32), Point, Line }
+fn area(shape: &Shape) -> f32 {
+    match shape {
+        Shape::Circle { r } => r * r * 3.14,
+        Shape::Rect(w, (h)) => w * h,
+        Shape::Point | Shape::Line => 0.0,
+        _ => <|>unreachable!(),
+    }
+}
+"#,
+            r#"
+enum Shape { Circle { r: f32 }, Rect(f32, f32), Point, Line }
+fn area(shape: &Shape) -> f32 {
+    match shape {
+        Shape::Circle { r } => r * r * 3.14,
+        Shape::Rect(w, (h)) => w * h,
+        Shape::Point | Shape::Line => 0.0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_variant_is_partially_matched() {
+        check_assist_not_applicable(
+            remove_redundant_wildcard_arm,
+            r#"
+enum E { A(u32), B }
+fn f(e: E) { match e { E::A(0) => (), E::B => (), _<|> => () } }
+"#,
+        );
+        check_assist_not_applicable(
+            remove_redundant_wildcard_arm,
+            r#"
+enum E { A, B }
+fn f(e: E, x: bool) { match e { E::A if x => (), E::B => (), _<|> => () } }
+"#,
+        );
+        check_assist_not_applicable(
+            remove_redundant_wildcard_arm,
+            r#"
+#[non_exhaustive]
+enum E { A }
+fn f(e: E) { match e { E::A => (), _<|> => () } }
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_redundant_wildcard_arm_target() {
+        check_assist_target(
+            remove_redundant_wildcard_arm,
+            "enum E { A }\nfn f(e: E) { match e { E::A => (), _<|> => () } }",
+            "_ => ()",
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use hir::{AssocItem, ModuleDef, PathResolution};
+use itertools::Itertools;
+use ra_syntax::{
+    algo,
+    ast::{self, AstNode, NameOwner},
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: reorder_impl_methods
+//
+// Reorders the methods of a trait impl in the same order as in the trait
+// definition.
+//
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+//     fn name(&self) -> String;
+// }
+// struct Square(f64);
+// impl Shape for <|>Square {
+//     fn name(&self) -> String { "square".to_string() }
+//     fn area(&self) -> f64 { self.0 * self.0 }
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+//     fn name(&self) -> String;
+// }
+// struct Square(f64);
+// impl Shape for Square {
+//     fn area(&self) -> f64 { self.0 * self.0 }
+//     fn name(&self) -> String { "square".to_string() }
+// }
+// ```
+pub(crate) fn reorder_impl_methods(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let impl_def = ctx.find_node_at_offset::<ast::ImplDef>()?;
+    let item_list = impl_def.item_list()?;
+    // Don't offer the assist everywhere inside of the method bodies.
+    let on_header = ctx.offset() < item_list.syntax().text_range().start();
+    let on_method_name = ctx
+        .find_node_at_offset::<ast::FnDef>()
+        .and_then(|it| it.name())
+        .map_or(false, |it| it.syntax().text_range().contains_inclusive(ctx.offset()));
+    if !on_header && !on_method_name {
+        return None;
+    }
+
+    let trait_path = match impl_def.target_trait()? {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let trait_ = match ctx.sema.resolve_path(&trait_path)? {
+        PathResolution::Def(ModuleDef::Trait(it)) => it,
+        _ => return None,
+    };
+    let ranks: HashMap<String, usize> = trait_
+        .items(ctx.db)
+        .into_iter()
+        .filter_map(|it| match it {
+            AssocItem::Function(f) => Some(f.name(ctx.db).to_string()),
+            _ => None,
+        })
+        .enumerate()
+        .map(|(idx, name)| (name, idx))
+        .collect();
+
+    let methods: Vec<ast::FnDef> = item_list
+        .assoc_items()
+        .filter_map(|it| match it {
+            ast::AssocItem::FnDef(it) => Some(it),
+            _ => None,
+        })
+        .collect();
+    // Methods, which aren't in the trait, are moved to the end.
+    let sorted: Vec<ast::FnDef> = methods
+        .iter()
+        .cloned()
+        .sorted_by_key(|it| {
+            it.name()
+                .and_then(|it| ranks.get(&it.text().to_string()).copied())
+                .unwrap_or(usize::MAX)
+        })
+        .collect();
+    if sorted == methods {
+        return None;
+    }
+
+    let target = impl_def.syntax().text_range();
+    acc.add(
+        AssistId("reorder_impl_methods"),
+        format!("Reorder methods in the order of `{}`", trait_.name(ctx.db)),
+        target,
+        |builder| {
+            for (old, new) in methods.iter().zip(&sorted) {
+                algo::diff(old.syntax(), new.syntax()).into_text_edit(builder.text_edit_builder());
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn reorder_methods_with_attributes_and_other_items() {
+        check_assist(
+            reorder_impl_methods,
+            r#"
+trait Service {
+    type Error;
+    fn call(&self);
+    fn poll_ready(&self) -> bool { true }
+    fn name(&self) -> &str;
+}
+struct Logger;
+impl Service for Logger<|> {
+    /// Logs the name.
+    #[inline]
+    fn name(&self) -> &str { "logger" }
+    type Error = ();
+    fn helper(&self) {}
+    #[cfg(test)]
+    fn call(&self) {}
+    fn poll_ready(&self) -> bool { false }
+}
+"#,
+            r#"
+trait Service {
+    type Error;
+    fn call(&self);
+    fn poll_ready(&self) -> bool { true }
+    fn name(&self) -> &str;
+}
+struct Logger;
+impl Service for Logger {
+    #[cfg(test)]
+    fn call(&self) {}
+    type Error = ();
+    fn poll_ready(&self) -> bool { false }
+    /// Logs the name.
+    #[inline]
+    fn name(&self) -> &str { "logger" }
+    fn helper(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reorder_from_method_name() {
+        check_assist(
+            reorder_impl_methods,
+            r#"
+mod api { pub trait Api { fn get(&self); fn put(&self); } }
+struct Client;
+impl api::Api for Client {
+    pub(crate) fn p<|>ut(&self) {}
+    fn get(&self) {}
+}
+"#,
+            r#"
+mod api { pub trait Api { fn get(&self); fn put(&self); } }
+struct Client;
+impl api::Api for Client {
+    fn get(&self) {}
+    pub(crate) fn put(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            reorder_impl_methods,
+            r#"
+trait T { fn a(&self); fn b(&self); }
+struct S;
+impl T for S<|> { fn a(&self) {} fn b(&self) {} }
+"#,
+        );
+        check_assist_not_applicable(
+            reorder_impl_methods,
+            r#"
+trait T { fn a(&self); fn b(&self); }
+struct S;
+impl T for S { fn b(&self) { <|> } fn a(&self) {} }
+"#,
+        );
+        check_assist_not_applicable(
+            reorder_impl_methods,
+            "struct S; impl S<|> { fn b(&self) {} fn a(&self) {} }",
+        );
+    }
+
+    #[test]
+    fn reorder_impl_methods_target() {
+        check_assist_target(
+            reorder_impl_methods,
+            r#"
+trait T { fn a(&self); fn b(&self); }
+struct S;
+impl<|> T for S { fn b(&self) {} fn a(&self) {} }
+"#,
+            "impl T for S { fn b(&self) {} fn a(&self) {} }",
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use hir::HirDisplay;
+use ra_syntax::ast::{self, AstNode, BinOp};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: replace_arith_with_wrapping
+//
+// Replaces an integer `+`, `-` or `*`, which may overflow, with the
+// `wrapping_*` method of the integer type.
+//
+// ```
+// fn hash(h: u32, c: u32) -> u32 {
+//     h *<|> 31 + c
+// }
+// ```
+// ->
+// ```
+// fn hash(h: u32, c: u32) -> u32 {
+//     h.wrapping_mul(31) + c
+// }
+// ```
+pub(crate) fn replace_arith_with_wrapping(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    replace_arith(acc, ctx, "wrapping")
+}
+
+// Assist: replace_arith_with_saturating
+//
+// Replaces an integer `+`, `-` or `*`, which may overflow, with the
+// `saturating_*` method of the integer type.
+//
+// ```
+// fn remaining(len: usize, pos: usize) -> usize {
+//     len -<|> pos
+// }
+// ```
+// ->
+// ```
+// fn remaining(len: usize, pos: usize) -> usize {
+//     len.saturating_sub(pos)
+// }
+// ```
+pub(crate) fn replace_arith_with_saturating(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    replace_arith(acc, ctx, "saturating")
+}
+
+fn replace_arith(acc: &mut Assists, ctx: &AssistContext, kind: &'static str) -> Option<()> {
+    let expr = ctx.find_node_at_offset::<ast::BinExpr>()?;
+    let op_range = expr.op_token()?.text_range();
+    if !op_range.contains_range(ctx.frange.range) {
+        return None;
+    }
+    let (op, is_assignment) = match expr.op_kind()? {
+        BinOp::Addition => ("add", false),
+        BinOp::Subtraction => ("sub", false),
+        BinOp::Multiplication => ("mul", false),
+        BinOp::AddAssign => ("add", true),
+        BinOp::SubAssign => ("sub", true),
+        BinOp::MulAssign => ("mul", true),
+        _ => return None,
+    };
+    let lhs = expr.lhs()?;
+    let rhs = expr.rhs()?;
+    // `x += y` becomes `x = x.wrapping_add(y)`, so `x` must be a simple place.
+    if is_assignment && !is_simple_place(&lhs) {
+        return None;
+    }
+
+    let ty = ctx.sema.type_of_expr(&lhs)?.display(ctx.db).to_string();
+    if !INT_TYPES.contains(&ty.as_str()) {
+        return None;
+    }
+    let method = format!("{}_{}", kind, op);
+
+    let receiver = match &lhs {
+        // The method can't be called on a literal of an unknown integer type.
+        ast::Expr::Literal(it) => match it.kind() {
+            ast::LiteralKind::IntNumber { suffix: None } => format!("{}{}", it, ty),
+            _ => it.to_string(),
+        },
+        ast::Expr::BinExpr(_)
+        | ast::Expr::PrefixExpr(_)
+        | ast::Expr::CastExpr(_)
+        | ast::Expr::RangeExpr(_)
+        | ast::Expr::RefExpr(_) => format!("({})", lhs),
+        _ => lhs.to_string(),
+    };
+    let call = format!("{}.{}({})", receiver, method, rhs);
+
+    acc.add(
+        AssistId(if kind == "wrapping" {
+            "replace_arith_with_wrapping"
+        } else {
+            "replace_arith_with_saturating"
+        }),
+        format!("Replace `{}` with `{}::{}`", expr.op_token()?.text(), ty, method),
+        op_range,
+        |builder| {
+            let replacement = if is_assignment { format!("{} = {}", lhs, call) } else { call };
+            builder.replace(expr.syntax().text_range(), replacement);
+        },
+    )
+}
+
+/// Checks whether `expr` is a variable or a field of one, which can be
+/// evaluated twice without side effects.
+fn is_simple_place(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(_) => true,
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_simple_place(&it)),
+        _ => false,
+    }
+}
+
+const INT_TYPES: &[&str] =
+    &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn wrapping_with_parenthesized_receiver() {
+        check_assist(
+            replace_arith_with_wrapping,
+            "fn f(a: i64, b: i64) -> i64 { -a +<|> b * 2 }",
+            "fn f(a: i64, b: i64) -> i64 { (-a).wrapping_add(b * 2) }",
+        );
+    }
+
+    #[test]
+    fn wrapping_suffixes_literal() {
+        check_assist(
+            replace_arith_with_wrapping,
+            "fn f(a: u8) -> u8 { 255 *<|> a }",
+            "fn f(a: u8) -> u8 { 255u8.wrapping_mul(a) }",
+        );
+    }
+
+    #[test]
+    fn saturating_compound_assignment() {
+        check_assist(
+            replace_arith_with_saturating,
+            r#"
+struct Counter { hits: u16 }
+fn f(c: &mut Counter, n: u16) {
+    c.hits -=<|> n;
+}
+"#,
+            r#"
+struct Counter { hits: u16 }
+fn f(c: &mut Counter, n: u16) {
+    c.hits = c.hits.saturating_sub(n);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            replace_arith_with_wrapping,
+            "fn f(a: f32, b: f32) -> f32 { a +<|> b }",
+        );
+        check_assist_not_applicable(
+            replace_arith_with_wrapping,
+            "fn f(a: u32, b: u32) -> u32 { a /<|> b }",
+        );
+        check_assist_not_applicable(
+            replace_arith_with_saturating,
+            "fn f(a: u32, b: u32) -> u32 { a<|> + b }",
+        );
+        check_assist_not_applicable(
+            replace_arith_with_saturating,
+            "fn f(v: &mut [u32], i: usize) { v[i] +=<|> 1; }",
+        );
+        check_assist_not_applicable(
+            replace_arith_with_wrapping,
+            r#"
+struct S { f: u32 }
+fn next() -> &'static mut S { loop {} }
+fn f() { next().f +=<|> 1; }
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_arith_target() {
+        check_assist_target(
+            replace_arith_with_saturating,
+            "fn f(a: u32, b: u32) -> u32 { a +<|> b }",
+            "+",
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use hir::HirDisplay;
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{utils::is_postfix_receiver, AssistContext, AssistId, Assists};
+
+// Assist: replace_conversion_with_cast
+//
+// Replaces a conversion between integer types, which can't fail because every
+// value of the source type fits into the target type, with an `as` cast.
+//
+// ```
+// fn widen(x: u16) -> u32 {
+//     u32::try_from(x).unwrap()<|>
+// }
+// ```
+// ->
+// ```
+// fn widen(x: u16) -> u32 {
+//     x as u32
+// }
+// ```
+pub(crate) fn replace_conversion_with_cast(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let (expr, arg, target_ty) = ctx
+        .covering_element()
+        .ancestors()
+        .filter_map(ast::Expr::cast)
+        .find_map(|expr| conversion(ctx, &expr))?;
+    let source_ty = ctx.sema.type_of_expr(&arg)?.display(ctx.db).to_string();
+    let source = IntType::from_name(&source_ty)?;
+    let target = IntType::from_name(&target_ty)?;
+    if !source.always_fits_into(target) {
+        return None;
+    }
+
+    let target_range = expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_conversion_with_cast"),
+        format!("Replace conversion with `as {}`", target_ty),
+        target_range,
+        |builder| {
+            let arg = match arg {
+                ast::Expr::Literal(_) | ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) => {
+                    arg.to_string()
+                }
+                _ if is_postfix_receiver(&arg) => arg.to_string(),
+                _ => format!("({})", arg),
+            };
+            let mut cast = format!("{} as {}", arg, target_ty);
+            if needs_parens(&expr) {
+                cast = format!("({})", cast);
+            }
+            builder.replace(target_range, cast);
+        },
+    )
+}
+
+/// Returns the argument and the name of the target type of `T::from(x)`,
+/// `x.into()`, `T::try_from(x).unwrap()` or `x.try_into().unwrap()`, where
+/// `unwrap` can also be `expect`.
+fn conversion(ctx: &AssistContext, expr: &ast::Expr) -> Option<(ast::Expr, ast::Expr, String)> {
+    let (call, fallible) = match expr {
+        ast::Expr::MethodCallExpr(call) => {
+            let name = call.name_ref()?;
+            if name.text() == "unwrap" || name.text() == "expect" {
+                (call.expr()?, true)
+            } else {
+                (expr.clone(), false)
+            }
+        }
+        ast::Expr::CallExpr(_) => (expr.clone(), false),
+        _ => return None,
+    };
+    let (method, arg, target) = match &call {
+        ast::Expr::CallExpr(call) => {
+            let path = match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            let method = path.segment()?.name_ref()?.text().to_string();
+            let target = path.qualifier()?.syntax().to_string();
+            let mut args = call.arg_list()?.args();
+            let arg = args.next()?;
+            if args.next().is_some() {
+                return None;
+            }
+            (method, arg, target)
+        }
+        ast::Expr::MethodCallExpr(call) => {
+            if call.arg_list()?.args().next().is_some() {
+                return None;
+            }
+            let method = call.name_ref()?.text().to_string();
+            let target = ctx.sema.type_of_expr(expr)?.display(ctx.db).to_string();
+            (method, call.expr()?, target)
+        }
+        _ => return None,
+    };
+    let expected = match (call, fallible) {
+        (ast::Expr::CallExpr(_), false) => "from",
+        (ast::Expr::CallExpr(_), true) => "try_from",
+        (_, false) => "into",
+        (_, true) => "try_into",
+    };
+    if method != expected {
+        return None;
+    }
+    Some((expr.clone(), arg, target))
+}
+
+/// Checks whether a cast replacing `expr` must be wrapped in parentheses.
+fn needs_parens(expr: &ast::Expr) -> bool {
+    let parent = match expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    match parent {
+        ast::Expr::BinExpr(it) => {
+            // `x as u32 < y` starts generic arguments.
+            let is_lhs = it.lhs().map_or(false, |it| it.syntax() == expr.syntax());
+            is_lhs
+                && matches!(
+                    it.op_kind(),
+                    Some(ast::BinOp::LesserTest) | Some(ast::BinOp::LeftShift)
+                )
+        }
+        ast::Expr::ParenExpr(_) | ast::Expr::RangeExpr(_) | ast::Expr::CastExpr(_) => false,
+        ast::Expr::IndexExpr(it) => it.base().map_or(false, |it| it.syntax() == expr.syntax()),
+        ast::Expr::ReturnExpr(_)
+        | ast::Expr::BreakExpr(_)
+        | ast::Expr::TupleExpr(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::BlockExpr(_)
+        | ast::Expr::LambdaExpr(_) => false,
+        _ => true,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct IntType {
+    signed: bool,
+    /// The smallest and the largest width on the supported targets, which
+    /// differ for `usize` and `isize`.
+    min_bits: u32,
+    max_bits: u32,
+}
+
+impl IntType {
+    fn from_name(name: &str) -> Option<IntType> {
+        let (signed, bits) = match name {
+            "usize" => return Some(IntType { signed: false, min_bits: 16, max_bits: 64 }),
+            "isize" => return Some(IntType { signed: true, min_bits: 16, max_bits: 64 }),
+            _ if name.starts_with('u') => (false, &name[1..]),
+            _ if name.starts_with('i') => (true, &name[1..]),
+            _ => return None,
+        };
+        let bits = match bits {
+            "8" => 8,
+            "16" => 16,
+            "32" => 32,
+            "64" => 64,
+            "128" => 128,
+            _ => return None,
+        };
+        Some(IntType { signed, min_bits: bits, max_bits: bits })
+    }
+
+    fn always_fits_into(self, target: IntType) -> bool {
+        match (self.signed, target.signed) {
+            (false, false) | (true, true) => self.max_bits <= target.min_bits,
+            (false, true) => self.max_bits < target.min_bits,
+            (true, false) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_try_from_unwrap() {
+        check_assist(
+            replace_conversion_with_cast,
+            "fn f(x: u8) -> i32 { i32::try_from(x + 1).expect(\"fits\")<|> * 2 }",
+            "fn f(x: u8) -> i32 { (x + 1) as i32 * 2 }",
+        );
+        check_assist(
+            replace_conversion_with_cast,
+            "fn f(x: i16) -> i64 { i64::from(<|>x).max(0) }",
+            "fn f(x: i16) -> i64 { (x as i64).max(0) }",
+        );
+    }
+
+    #[test]
+    fn replace_into() {
+        check_assist(
+            replace_conversion_with_cast,
+            r"
+//- /main.rs crate:main deps:core
+use core::convert::TryInto;
+fn f(x: u32) { let y: u64 = x.try_into().unwrap()<|>; }
+//- /core.rs crate:core
+pub mod convert {
+    pub trait TryInto<T> {
+        type Error;
+        fn try_into(self) -> Result<T, Self::Error>;
+    }
+    impl<T, U> TryInto<U> for T {}
+}
+pub enum Result<T, E> { Ok(T), Err(E) }
+impl<T, E> Result<T, E> { pub fn unwrap(self) -> T { loop {} } }
+",
+            "use core::convert::TryInto;\nfn f(x: u32) { let y: u64 = x as u64; }\n",
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_lossy_conversions() {
+        check_assist_not_applicable(
+            replace_conversion_with_cast,
+            "fn f(x: u32) -> u16 { u16::try_from(x).unwrap()<|> }",
+        );
+        check_assist_not_applicable(
+            replace_conversion_with_cast,
+            "fn f(x: i8) -> u64 { u64::try_from(x).unwrap()<|> }",
+        );
+        check_assist_not_applicable(
+            replace_conversion_with_cast,
+            "fn f(x: u32) -> i32 { i32::try_from(x).unwrap()<|> }",
+        );
+        check_assist_not_applicable(
+            replace_conversion_with_cast,
+            "fn f(x: u32) -> usize { usize::try_from(x).unwrap()<|> }",
+        );
+        check_assist_not_applicable(
+            replace_conversion_with_cast,
+            "fn f(x: u8) -> u32 { u32::try_from(x)<|> }",
+        );
+    }
+
+    #[test]
+    fn replace_conversion_with_cast_target() {
+        check_assist_target(
+            replace_conversion_with_cast,
+            "fn f(x: usize) -> u128 { 1 + u128::try_from(x).un<|>wrap() }",
+            "u128::try_from(x).unwrap()",
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use ra_fmt::unwrap_trivial_block;
+use ra_syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make,
+    },
+    AstNode,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: replace_if_let_chain_with_match
+//
+// Replaces a chain of `if let` and `else if let` expressions with a `match`
+// on a tuple of the matched values. Each branch becomes an arm, which matches
+// on one element of the tuple.
+//
+// ```
+// fn timeout(arg: Option<u64>, env: Result<u64, ()>) -> u64 {
+//     <|>if let Some(secs) = arg {
+//         secs
+//     } else if let Ok(secs) = env {
+//         secs
+//     } else {
+//         30
+//     }
+// }
+// ```
+// ->
+// ```
+// fn timeout(arg: Option<u64>, env: Result<u64, ()>) -> u64 {
+//     match (arg, env) {
+//         (Some(secs), _) => secs,
+//         (_, Ok(secs)) => secs,
+//         _ => 30,
+//     }
+// }
+// ```
+pub(crate) fn replace_if_let_chain_with_match(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let mut if_expr: ast::IfExpr = ctx.find_node_at_offset()?;
+    // Start at the first `if` of the chain.
+    while let Some(parent) = if_expr.syntax().parent().and_then(ast::IfExpr::cast) {
+        match parent.else_branch() {
+            Some(ast::ElseBranch::IfExpr(it)) if it == if_expr => if_expr = parent,
+            _ => break,
+        }
+    }
+
+    let mut branches = Vec::new();
+    let mut else_block = None;
+    let mut current = if_expr.clone();
+    loop {
+        let cond = current.condition()?;
+        let expr = cond.expr()?;
+        // All values are evaluated up front, so they must not have side effects.
+        if !is_place_expr(&expr) {
+            return None;
+        }
+        branches.push((cond.pat()?, expr, current.then_branch()?));
+        match current.else_branch() {
+            Some(ast::ElseBranch::IfExpr(it)) => current = it,
+            Some(ast::ElseBranch::Block(it)) => {
+                else_block = Some(it);
+                break;
+            }
+            None => break,
+        }
+    }
+    if branches.len() < 2 {
+        return None;
+    }
+
+    let target = if_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_if_let_chain_with_match"),
+        "Replace `if let` chain with `match`",
+        target,
+        move |edit| {
+            let scrutinee = make::expr_tuple(branches.iter().map(|(_, expr, _)| expr.clone()));
+            let mut arms = Vec::new();
+            for (i, (pat, _, block)) in branches.iter().enumerate() {
+                let pats = (0..branches.len()).map(|j| {
+                    if i == j {
+                        pat.clone()
+                    } else {
+                        make::placeholder_pat().into()
+                    }
+                });
+                let block = block.reset_indent().indent(IndentLevel(1));
+                let pat = make::tuple_pat(pats).into();
+                arms.push(make::match_arm(vec![pat], unwrap_trivial_block(block)));
+            }
+            let else_expr = match else_block {
+                Some(block) => unwrap_trivial_block(block.reset_indent().indent(IndentLevel(1))),
+                None => make::expr_empty_block(),
+            };
+            arms.push(make::match_arm(vec![make::placeholder_pat().into()], else_expr));
+
+            let match_expr = make::expr_match(scrutinee, make::match_arm_list(arms))
+                .indent(IndentLevel::from_node(if_expr.syntax()));
+            edit.replace_ast::<ast::Expr>(if_expr.into(), match_expr);
+        },
+    )
+}
+
+/// Checks whether `expr` is a variable or a field of one, possibly borrowed.
+fn is_place_expr(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(_) => true,
+        ast::Expr::FieldExpr(it) => it.expr().map_or(false, |it| is_place_expr(&it)),
+        ast::Expr::RefExpr(it) => it.expr().map_or(false, |it| is_place_expr(&it)),
+        ast::Expr::ParenExpr(it) => it.expr().map_or(false, |it| is_place_expr(&it)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_chain_without_else() {
+        check_assist(
+            replace_if_let_chain_with_match,
+            r#"
+fn update(&mut self) {
+    if let Some(name) = &self.name {
+        self.rename(name);
+    } else if let <|>Err(e) = self.state {
+        log(e);
+    } else if let Some(id) = &self.parent.id {
+        self.reload(id);
+        self.refresh();
+    }
+}
+"#,
+            r#"
+fn update(&mut self) {
+    match (&self.name, self.state, &self.parent.id) {
+        (Some(name), _, _) => {
+            self.rename(name);
+        }
+        (_, Err(e), _) => {
+            log(e);
+        }
+        (_, _, Some(id)) => {
+            self.reload(id);
+            self.refresh();
+        }
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // A single `if let` is handled by `replace_if_let_with_match`.
+        check_assist_not_applicable(
+            replace_if_let_chain_with_match,
+            "fn f() { <|>if let Some(a) = x { a } else { 0 } }",
+        );
+        // A plain `if` in the chain.
+        check_assist_not_applicable(
+            replace_if_let_chain_with_match,
+            "fn f() { <|>if let Some(a) = x { a } else if y { 1 } else { 0 } }",
+        );
+        // The second value would be evaluated eagerly.
+        check_assist_not_applicable(
+            replace_if_let_chain_with_match,
+            "fn f() { <|>if let Some(a) = x { a } else if let Some(b) = next() { b } else { 0 } }",
+        );
+    }
+
+    #[test]
+    fn replace_if_let_chain_with_match_target() {
+        check_assist_target(
+            replace_if_let_chain_with_match,
+            "fn f() { let v = <|>if let Some(a) = x { a } else if let Ok(b) = y { b } else { 0 }; }",
+            "if let Some(a) = x { a } else if let Ok(b) = y { b } else { 0 }",
+        );
+    }
+}
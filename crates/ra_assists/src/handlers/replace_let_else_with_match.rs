@@ -0,0 +1,406 @@
+use ra_syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        AstNode, NameOwner, TypeAscriptionOwner,
+    },
+    SyntaxKind::WHITESPACE,
+    TextRange, TextSize,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: replace_let_else_with_match
+//
+// Replaces a `let`-`else` statement with a `match`, for toolchains which
+// don't support `let`-`else` yet.
+//
+// ```
+// fn parse(s: &str) -> Option<u32> { None }
+// fn main() {
+//     let Some(x) = <|>parse("42") else { return };
+// }
+// ```
+// ->
+// ```
+// fn parse(s: &str) -> Option<u32> { None }
+// fn main() {
+//     let x = match parse("42") {
+//         Some(x) => x,
+//         _ => { return }
+//     };
+// }
+// ```
+pub(crate) fn replace_let_else_with_match(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let else_block = let_stmt.let_else()?.block_expr()?;
+    // The ascribed type can't be kept in a `match`.
+    if let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    let pat = let_stmt.pat()?;
+    let init = let_stmt.initializer()?;
+
+    let bindings: Vec<ast::BindPat> = pat
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter(|it| ctx.sema.resolve_bind_pat_to_const(it).is_none())
+        .collect();
+    let names =
+        bindings.iter().map(|it| Some(it.name()?.to_string())).collect::<Option<Vec<String>>>()?;
+    let outer_names = bindings
+        .iter()
+        .zip(&names)
+        .map(|(it, name)| match it.mut_token() {
+            Some(_) if it.ref_token().is_none() => format!("mut {}", name),
+            _ => name.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    // The bindings become mutable outside of the `match`.
+    let mut arm_pat = pat.syntax().to_string();
+    let pat_start = pat.syntax().text_range().start();
+    for binding in bindings.iter().rev().filter(|it| it.ref_token().is_none()) {
+        if let Some(mut_token) = binding.mut_token() {
+            let mut end = mut_token.text_range().end();
+            if let Some(ws) = mut_token.next_token().filter(|it| it.kind() == WHITESPACE) {
+                end = ws.text_range().end();
+            }
+            let range = TextRange::new(mut_token.text_range().start(), end) - pat_start;
+            arm_pat.replace_range(std::ops::Range::<usize>::from(range), "");
+        }
+    }
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("replace_let_else_with_match"),
+        "Replace let-else with match",
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(let_stmt.syntax());
+            let (outer, value) = match names.len() {
+                0 => (None, "{}".to_string()),
+                1 => (Some(outer_names[0].clone()), names[0].clone()),
+                _ => (
+                    Some(format!("({})", outer_names.iter().sep_by(", "))),
+                    format!("({})", names.iter().sep_by(", ")),
+                ),
+            };
+            let mut buf = String::new();
+            match &outer {
+                Some(outer) => format_to!(buf, "let {} = match {} {{\n", outer, init.syntax()),
+                None => format_to!(buf, "match {} {{\n", init.syntax()),
+            }
+            format_to!(buf, "{}{} => {},\n", indent + 1, arm_pat, value);
+            format_to!(buf, "{}_ => {}\n", indent + 1, else_block.indent(IndentLevel(1)).syntax());
+            format_to!(buf, "{}}}", indent);
+            if outer.is_some() {
+                buf.push(';');
+            }
+
+            let start = let_stmt.let_token().map_or(target.start(), |it| it.text_range().start());
+            builder.replace(TextRange::new(start, target.end()), buf);
+        },
+    )
+}
+
+// Assist: replace_match_with_let_else
+//
+// Replaces a `match`, which either binds variables or diverges, with a
+// `let`-`else` statement.
+//
+// ```
+// fn parse(s: &str) -> Option<u32> { None }
+// fn main() {
+//     let x = <|>match parse("42") {
+//         Some(x) => x,
+//         _ => return,
+//     };
+// }
+// ```
+// ->
+// ```
+// fn parse(s: &str) -> Option<u32> { None }
+// fn main() {
+//     let Some(x) = parse("42") else { return };
+// }
+// ```
+pub(crate) fn replace_match_with_let_else(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    if let_stmt.let_else().is_some() || let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    let match_expr = match let_stmt.initializer()? {
+        ast::Expr::MatchExpr(it) => it,
+        _ => return None,
+    };
+    let scrutinee = match_expr.expr()?;
+    let arms: Vec<ast::MatchArm> = match_expr.match_arm_list()?.arms().collect();
+    if arms.len() != 2 || arms.iter().any(|it| it.guard().is_some()) {
+        return None;
+    }
+    let (arm, else_arm) = (&arms[0], &arms[1]);
+    if !matches!(else_arm.pat()?, ast::Pat::PlaceholderPat(_)) {
+        return None;
+    }
+    let else_expr = else_arm.expr()?;
+    if !diverges(ctx, &else_expr) {
+        return None;
+    }
+
+    // The `let` must bind exactly what the arm yields.
+    let outer: Vec<ast::BindPat> = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) => vec![it],
+        ast::Pat::TuplePat(it) => it
+            .args()
+            .map(|it| match it {
+                ast::Pat::BindPat(it) => Some(it),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    if outer.iter().any(|it| it.ref_token().is_some() || it.pat().is_some()) {
+        return None;
+    }
+    let names =
+        outer.iter().map(|it| Some(it.name()?.to_string())).collect::<Option<Vec<String>>>()?;
+    let yielded = match arm.expr()? {
+        ast::Expr::TupleExpr(it) => it.exprs().map(|it| local_name(&it)).collect::<Option<_>>()?,
+        it => vec![local_name(&it)?],
+    };
+    if names != yielded {
+        return None;
+    }
+
+    // The bindings of the arm leak into the surrounding scope.
+    let arm_pat = arm.pat()?;
+    let bindings: Vec<ast::BindPat> = arm_pat
+        .syntax()
+        .descendants()
+        .filter_map(ast::BindPat::cast)
+        .filter(|it| ctx.sema.resolve_bind_pat_to_const(it).is_none())
+        .collect();
+    let mut bound =
+        bindings.iter().map(|it| Some(it.name()?.to_string())).collect::<Option<Vec<_>>>()?;
+    bound.sort();
+    let mut expected = names.clone();
+    expected.sort();
+    if bound != expected {
+        return None;
+    }
+
+    let target = let_stmt.syntax().text_range();
+    acc.add(
+        AssistId("replace_match_with_let_else"),
+        "Replace match with let-else",
+        target,
+        |builder| {
+            let mut pat = arm_pat.syntax().to_string();
+            let pat_start = arm_pat.syntax().text_range().start();
+            for binding in bindings.iter().rev() {
+                let is_mut = outer.iter().any(|it| {
+                    it.mut_token().is_some()
+                        && it.name().map(|it| it.to_string())
+                            == binding.name().map(|it| it.to_string())
+                });
+                if is_mut && binding.mut_token().is_none() && binding.ref_token().is_none() {
+                    let offset: TextSize = binding.syntax().text_range().start() - pat_start;
+                    pat.insert_str(offset.into(), "mut ");
+                }
+            }
+
+            let else_block = match &else_expr {
+                ast::Expr::BlockExpr(it) => it.dedent(IndentLevel(1)).syntax().to_string(),
+                it => format!("{{ {} }}", it.syntax()),
+            };
+            let start = let_stmt.let_token().map_or(target.start(), |it| it.text_range().start());
+            let mut buf = format!("let {} = {} else {}", pat, scrutinee.syntax(), else_block);
+            if let_stmt.semicolon_token().is_some() {
+                buf.push(';');
+            }
+            builder.replace(TextRange::new(start, target.end()), buf);
+        },
+    )
+}
+
+fn diverges(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    if ctx.sema.type_of_expr(expr).map_or(false, |it| it.is_never()) {
+        return true;
+    }
+    match expr {
+        ast::Expr::BlockExpr(it) if it.expr().is_none() => match it.statements().last() {
+            Some(ast::Stmt::ExprStmt(it)) => it.expr().map_or(false, |it| diverges(ctx, &it)),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns the name of a local variable, which is referenced by `expr`.
+fn local_name(expr: &ast::Expr) -> Option<String> {
+    let path = match expr {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let segment = path.segment()?;
+    if segment.type_arg_list().is_some() {
+        return None;
+    }
+    Some(segment.name_ref()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_let_else_with_match_binds_tuple() {
+        check_assist(
+            replace_let_else_with_match,
+            r#"
+enum Token { Pair(u32, u32), Eof }
+fn next() -> Token { Token::Eof }
+fn f() -> u32 {
+    let Token::Pair(mut a, ref b) = next() else {
+        log();
+        return 0;
+    };<|>
+    a += *b;
+    a
+}
+"#,
+            r#"
+enum Token { Pair(u32, u32), Eof }
+fn next() -> Token { Token::Eof }
+fn f() -> u32 {
+    let (mut a, b) = match next() {
+        Token::Pair(a, ref b) => (a, b),
+        _ => {
+            log();
+            return 0;
+        }
+    };
+    a += *b;
+    a
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_let_else_without_bindings() {
+        check_assist(
+            replace_let_else_with_match,
+            r#"
+enum E { A, B }
+fn f(e: E) {
+    let E::A = e <|>else { return };
+}
+"#,
+            r#"
+enum E { A, B }
+fn f(e: E) {
+    match e {
+        E::A => {},
+        _ => { return }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_let_else_in_block() {
+        check_assist(
+            replace_match_with_let_else,
+            r#"
+enum Token { Pair(u32, u32), Eof }
+fn next() -> Token { Token::Eof }
+fn f() -> u32 {
+    let (mut a, b) = match next() {
+        Token::Pair(a, ref b) => (a, b),
+        _ => {
+            log();
+            return 0;
+        }
+    };<|>
+    a += *b;
+    a
+}
+"#,
+            r#"
+enum Token { Pair(u32, u32), Eof }
+fn next() -> Token { Token::Eof }
+fn f() -> u32 {
+    let Token::Pair(mut a, ref b) = next() else {
+        log();
+        return 0;
+    };
+    a += *b;
+    a
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_let_else_not_applicable() {
+        // The `_` arm doesn't diverge.
+        check_assist_not_applicable(
+            replace_match_with_let_else,
+            r#"
+fn f(x: Option<u32>) {
+    let y = <|>match x {
+        Some(y) => y,
+        _ => 0,
+    };
+}
+"#,
+        );
+        // The arm binds more than the `let`.
+        check_assist_not_applicable(
+            replace_match_with_let_else,
+            r#"
+fn f(x: Option<(u32, u32)>) {
+    let y = <|>match x {
+        Some((y, z)) => y,
+        _ => return,
+    };
+}
+"#,
+        );
+        // The arm doesn't yield the bindings.
+        check_assist_not_applicable(
+            replace_match_with_let_else,
+            r#"
+fn f(x: Option<u32>) {
+    let y = <|>match x {
+        Some(y) => y + 1,
+        _ => return,
+    };
+}
+"#,
+        );
+        check_assist_not_applicable(
+            replace_let_else_with_match,
+            "fn f(x: Option<u32>) { let Some(y): Option<u32> = x <|>else { return }; }",
+        );
+    }
+
+    #[test]
+    fn replace_let_else_with_match_target() {
+        check_assist_target(
+            replace_let_else_with_match,
+            "fn f(x: Option<u32>) { let Some(y) = x <|>else { return }; }",
+            "let Some(y) = x else { return };",
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use hir::{Adt, PathResolution};
+use ra_ide_db::defs::Definition;
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, AstNode},
+    SyntaxKind::{FN_DEF, LAMBDA_EXPR},
+    SyntaxNode,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: replace_lock_with_get_mut
+//
+// Replaces `lock` with `get_mut` for a `Mutex`, which is owned by a local
+// variable and never shared, so that the exclusive access doesn't need to be
+// checked at runtime.
+//
+// ```
+// # struct Mutex<T>(T);
+// # impl<T> Mutex<T> {
+// #     fn new(t: T) -> Mutex<T> { Mutex(t) }
+// #     fn lock(&self) -> Result<&T, ()> { Ok(&self.0) }
+// #     fn get_mut(&mut self) -> Result<&mut T, ()> { Ok(&mut self.0) }
+// # }
+// fn count() -> u32 {
+//     let counter = Mutex::new(0);
+//     *counter.<|>lock().unwrap() += 1;
+//     0
+// }
+// ```
+// ->
+// ```
+// # struct Mutex<T>(T);
+// # impl<T> Mutex<T> {
+// #     fn new(t: T) -> Mutex<T> { Mutex(t) }
+// #     fn lock(&self) -> Result<&T, ()> { Ok(&self.0) }
+// #     fn get_mut(&mut self) -> Result<&mut T, ()> { Ok(&mut self.0) }
+// # }
+// fn count() -> u32 {
+//     let mut counter = Mutex::new(0);
+//     *counter.get_mut().unwrap() += 1;
+//     0
+// }
+// ```
+pub(crate) fn replace_lock_with_get_mut(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let method_call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = method_call.name_ref()?;
+    if name_ref.text() != "lock" || !name_ref.syntax().text_range().contains_range(ctx.frange.range)
+    {
+        return None;
+    }
+    let receiver = match method_call.expr()? {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let local = match ctx.sema.resolve_path(&receiver)? {
+        PathResolution::Local(it) => it,
+        _ => return None,
+    };
+    // A reference or an `Arc` to the mutex may be shared, an owned one only if it's borrowed.
+    match local.ty(ctx.db).as_adt() {
+        Some(Adt::Struct(it)) if it.name(ctx.db).to_string() == "Mutex" => (),
+        _ => return None,
+    }
+    let bind_pat = local.source(ctx.db).value.left()?;
+    if bind_pat.ref_token().is_some() {
+        return None;
+    }
+
+    // Every usage has to call a method on the mutex directly. Other threads
+    // can only get access to it through a closure or an async block.
+    let owner = body_owner(bind_pat.syntax())?;
+    let file_id = ctx.frange.file_id;
+    let source_file = ctx.sema.parse(file_id);
+    for reference in Definition::Local(local).find_usages(ctx.db, None) {
+        if reference.file_range.file_id != file_id {
+            return None;
+        }
+        let usage: ast::NameRef =
+            find_node_at_offset(source_file.syntax(), reference.file_range.range.start())?;
+        let path_expr = usage.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+        let is_receiver = path_expr
+            .syntax()
+            .parent()
+            .and_then(ast::MethodCallExpr::cast)
+            .and_then(|it| it.expr())
+            .map_or(false, |it| it.syntax() == path_expr.syntax());
+        if !is_receiver || body_owner(usage.syntax())? != owner {
+            return None;
+        }
+    }
+
+    let target = name_ref.syntax().text_range();
+    acc.add(
+        AssistId("replace_lock_with_get_mut"),
+        "Replace `lock()` with `get_mut()`",
+        target,
+        |builder| {
+            if bind_pat.mut_token().is_none() {
+                builder.insert(bind_pat.syntax().text_range().start(), "mut ");
+            }
+            builder.replace(target, "get_mut");
+        },
+    )
+}
+
+/// Returns the function, closure or async block, which `node` is evaluated in.
+fn body_owner(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors().find(|it| {
+        matches!(it.kind(), FN_DEF | LAMBDA_EXPR)
+            || ast::EffectExpr::cast(it.clone()).map_or(false, |it| it.async_token().is_some())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const MUTEX: &str = r#"
+struct Mutex<T>(T);
+impl<T> Mutex<T> {
+    fn new(t: T) -> Mutex<T> { Mutex(t) }
+    fn lock(&self) -> Result<&T, ()> { Ok(&self.0) }
+    fn get_mut(&mut self) -> Result<&mut T, ()> { Ok(&mut self.0) }
+}
+struct Arc<T>(T);
+"#;
+
+    #[test]
+    fn replace_lock_of_mut_param() {
+        check_assist(
+            replace_lock_with_get_mut,
+            &format!(
+                "{}{}",
+                MUTEX,
+                r#"
+fn drain(mut queue: Mutex<Vec<u32>>) {
+    queue.lock().unwrap().clear();
+    let len = queue.lo<|>ck().unwrap().len();
+}
+"#
+            ),
+            &format!(
+                "{}{}",
+                MUTEX,
+                r#"
+fn drain(mut queue: Mutex<Vec<u32>>) {
+    queue.lock().unwrap().clear();
+    let len = queue.get_mut().unwrap().len();
+}
+"#
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_if_shared() {
+        // Borrowed by a closure.
+        check_assist_not_applicable(
+            replace_lock_with_get_mut,
+            &format!(
+                "{}{}",
+                MUTEX,
+                r#"
+fn f() {
+    let m = Mutex::new(0);
+    let g = || m.lock();
+    m.<|>lock();
+}
+"#
+            ),
+        );
+        // Passed by reference.
+        check_assist_not_applicable(
+            replace_lock_with_get_mut,
+            &format!(
+                "{}{}",
+                MUTEX,
+                r#"
+fn share(_: &Mutex<u32>) {}
+fn f() {
+    let m = Mutex::new(0);
+    share(&m);
+    m.<|>lock();
+}
+"#
+            ),
+        );
+        check_assist_not_applicable(
+            replace_lock_with_get_mut,
+            &format!("{}{}", MUTEX, "fn f(m: &Mutex<u32>) { m.<|>lock(); }"),
+        );
+        check_assist_not_applicable(
+            replace_lock_with_get_mut,
+            &format!("{}{}", MUTEX, "fn f(m: Mutex<u32>) { m<|>.lock(); }"),
+        );
+    }
+
+    #[test]
+    fn replace_lock_with_get_mut_target() {
+        check_assist_target(
+            replace_lock_with_get_mut,
+            &format!("{}{}", MUTEX, "fn f(m: Mutex<u32>) { m.<|>lock(); }"),
+            "lock",
+        );
+    }
+}
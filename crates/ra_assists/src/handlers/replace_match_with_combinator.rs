@@ -0,0 +1,504 @@
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner},
+    SyntaxKind::*,
+};
+
+use crate::{
+    utils::{is_postfix_receiver, FamousDefs, TryEnum},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: replace_match_with_combinator
+//
+// Replaces a `match` on an `Option` or a `Result` which only transforms the
+// `Some` or the `Ok` case with `map`, `and_then` or `filter`.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+// fn main() {
+//     let x: Option<i32> = Some(1);
+//     let y = <|>match x {
+//         Some(it) => Some(it + 1),
+//         None => None,
+//     };
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::*;
+// fn main() {
+//     let x: Option<i32> = Some(1);
+//     let y = x.map(|it| it + 1);
+// }
+// ```
+pub(crate) fn replace_match_with_combinator(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let match_expr: ast::MatchExpr = ctx.find_node_at_offset()?;
+    let scrutinee = match_expr.expr()?;
+    let try_enum = TryEnum::from_ty(&ctx.sema, &ctx.sema.type_of_expr(&scrutinee)?)?;
+
+    let mut arms = match_expr.match_arm_list()?.arms();
+    let (first, second) = (arms.next()?, arms.next()?);
+    if arms.next().is_some() {
+        return None;
+    }
+    let (happy_arm, binding) = match happy_binding(try_enum, &first) {
+        Some(binding) if is_sad_arm(try_enum, &second) => (first, binding),
+        _ if is_sad_arm(try_enum, &first) => {
+            let binding = happy_binding(try_enum, &second)?;
+            (second, binding)
+        }
+        _ => return None,
+    };
+    let name = binding.name()?.text().to_string();
+
+    let body = happy_arm.expr()?;
+    if has_outer_control_flow(&body) {
+        return None;
+    }
+    let (method, closure) = match happy_arm.guard() {
+        Some(guard) => {
+            if !matches!(try_enum, TryEnum::Option)
+                || body.syntax().text() != wrap(try_enum, &name).as_str()
+            {
+                return None;
+            }
+            let guard = guard.expr()?;
+            let closure = match passed_by_ref(&guard, &name) {
+                Some(callee) => callee,
+                None if is_copy(ctx, &binding) => format!("|&{}| {}", name, guard),
+                None => format!("|{}| {}", name, guard),
+            };
+            ("filter", closure)
+        }
+        None => match unwrap_happy_case(try_enum, &body) {
+            Some(inner) => {
+                if inner.syntax().text() == name.as_str() {
+                    return None;
+                }
+                ("map", closure(&inner, &name))
+            }
+            None => ("and_then", closure(&body, &name)),
+        },
+    };
+
+    let target = match_expr.syntax().text_range();
+    acc.add(
+        AssistId("replace_match_with_combinator"),
+        format!("Replace `match` with `{}`", method),
+        target,
+        |builder| {
+            let receiver = if is_postfix_receiver(&scrutinee) {
+                scrutinee.syntax().to_string()
+            } else {
+                format!("({})", scrutinee.syntax())
+            };
+            builder.replace(target, format!("{}.{}({})", receiver, method, closure));
+        },
+    )
+}
+
+/// Returns the binding of an arm matching `Some(x)` or `Ok(x)`.
+fn happy_binding(try_enum: TryEnum, arm: &ast::MatchArm) -> Option<ast::BindPat> {
+    let pat = match arm.pat()? {
+        ast::Pat::TupleStructPat(it) => it,
+        _ => return None,
+    };
+    if pat.path()?.segment()?.name_ref()?.text() != try_enum.happy_case() {
+        return None;
+    }
+    let mut args = pat.args();
+    let binding = match args.next()? {
+        ast::Pat::BindPat(it) => it,
+        _ => return None,
+    };
+    if args.next().is_some()
+        || binding.ref_token().is_some()
+        || binding.mut_token().is_some()
+        || binding.pat().is_some()
+    {
+        return None;
+    }
+    Some(binding)
+}
+
+/// Checks that the arm forwards `None` or `Err(e)` unchanged.
+fn is_sad_arm(try_enum: TryEnum, arm: &ast::MatchArm) -> bool {
+    if arm.guard().is_some() {
+        return false;
+    }
+    let (pat, body) = match (arm.pat(), arm.expr()) {
+        (Some(pat), Some(body)) => (pat, body),
+        _ => return false,
+    };
+    match try_enum {
+        TryEnum::Option => {
+            (matches!(pat, ast::Pat::PlaceholderPat(_)) || pat.syntax().text() == "None")
+                && body.syntax().text() == "None"
+        }
+        TryEnum::Result => {
+            let err = match &pat {
+                ast::Pat::TupleStructPat(it) => it.args().next(),
+                _ => None,
+            };
+            match err {
+                Some(ast::Pat::BindPat(err)) => {
+                    let err = err.syntax().to_string();
+                    pat.syntax().text() == format!("Err({})", err).as_str()
+                        && body.syntax().text() == format!("Err({})", err).as_str()
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Checks whether `body` contains a `?`, `return`, `break` or `continue`,
+/// whose meaning would change once it's moved into a closure.
+fn has_outer_control_flow(body: &ast::Expr) -> bool {
+    body.syntax().descendants().any(|node| {
+        let is_loop_jump = match node.kind() {
+            TRY_EXPR | RETURN_EXPR => false,
+            BREAK_EXPR | CONTINUE_EXPR => true,
+            _ => return false,
+        };
+        // Jumps out of nested closures and fns, or out of loops inside of the
+        // body, aren't affected.
+        !node.ancestors().take_while(|it| it != body.syntax()).any(|it| {
+            matches!(it.kind(), LAMBDA_EXPR | FN_DEF)
+                || (is_loop_jump && matches!(it.kind(), LOOP_EXPR | WHILE_EXPR | FOR_EXPR))
+        })
+    })
+}
+
+fn wrap(try_enum: TryEnum, expr: &str) -> String {
+    format!("{}({})", try_enum.happy_case(), expr)
+}
+
+/// Returns `x` if `expr` is `Some(x)` or `Ok(x)`.
+fn unwrap_happy_case(try_enum: TryEnum, expr: &ast::Expr) -> Option<ast::Expr> {
+    let call = match expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    match call.expr()? {
+        ast::Expr::PathExpr(path) if path.syntax().text() == try_enum.happy_case() => (),
+        _ => return None,
+    }
+    single_arg(call)
+}
+
+fn single_arg(call: &ast::CallExpr) -> Option<ast::Expr> {
+    let mut args = call.arg_list()?.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(arg)
+}
+
+/// Builds a closure which evaluates `body` for the argument named `name`,
+/// reducing `|x| f(x)` to just `f`.
+fn closure(body: &ast::Expr, name: &str) -> String {
+    if let ast::Expr::CallExpr(call) = body {
+        if let (Some(callee @ ast::Expr::PathExpr(_)), Some(arg)) = (call.expr(), single_arg(call))
+        {
+            if arg.syntax().text() == name {
+                return callee.syntax().to_string();
+            }
+        }
+    }
+    format!("|{}| {}", name, body)
+}
+
+/// Returns `f` if `guard` is `f(&x)`.
+fn passed_by_ref(guard: &ast::Expr, name: &str) -> Option<String> {
+    let call = match guard {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    let callee = match call.expr()? {
+        it @ ast::Expr::PathExpr(_) => it,
+        _ => return None,
+    };
+    match single_arg(call)? {
+        ast::Expr::RefExpr(it) if it.mut_token().is_none() => {
+            if it.expr()?.syntax().text() == name {
+                Some(callee.syntax().to_string())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_copy(ctx: &AssistContext, binding: &ast::BindPat) -> bool {
+    let ty = match ctx.sema.type_of_pat(&binding.clone().into()) {
+        Some(it) => it,
+        None => return false,
+    };
+    let krate = match ctx.sema.scope(binding.syntax()).module() {
+        Some(it) => it.krate(),
+        None => return false,
+    };
+    match FamousDefs(&ctx.sema, krate).core_marker_Copy() {
+        Some(copy) => ty.impls_trait(ctx.db, copy, &[]),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist, check_assist_not_applicable, check_assist_target, check_assist_with_core,
+    };
+
+    use super::*;
+
+    #[test]
+    fn replace_with_map_function() {
+        check_assist_with_core(
+            replace_match_with_combinator,
+            r"
+fn double(x: i32) -> i32 { x * 2 }
+fn foo(x: Option<i32>) -> Option<i32> {
+    <|>match x {
+        None => None,
+        Some(x) => Some(double(x)),
+    }
+}
+",
+            r"
+fn double(x: i32) -> i32 { x * 2 }
+fn foo(x: Option<i32>) -> Option<i32> {
+    x.map(double)
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_with_and_then() {
+        check_assist_with_core(
+            replace_match_with_combinator,
+            r"
+fn parse(x: u8) -> Result<i32, u8> { Ok(x as i32) }
+fn foo(x: Result<u8, u8>) -> Result<i32, u8> {
+    <|>match x {
+        Ok(v) => parse(v),
+        Err(e) => Err(e),
+    }
+}
+",
+            r"
+fn parse(x: u8) -> Result<i32, u8> { Ok(x as i32) }
+fn foo(x: Result<u8, u8>) -> Result<i32, u8> {
+    x.and_then(parse)
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_with_filter_for_copy_types() {
+        check_assist_with_core(
+            replace_match_with_combinator,
+            r"
+#[derive(Clone)]
+struct Id(u32);
+impl core::marker::Copy for Id {}
+fn foo(x: Option<Id>) -> Option<Id> {
+    <|>match x {
+        Some(id) if id.0 > 0 => Some(id),
+        _ => None,
+    }
+}
+",
+            r"
+#[derive(Clone)]
+struct Id(u32);
+impl core::marker::Copy for Id {}
+fn foo(x: Option<Id>) -> Option<Id> {
+    x.filter(|&id| id.0 > 0)
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_with_filter_for_non_copy_types() {
+        check_assist_with_core(
+            replace_match_with_combinator,
+            r"
+struct S { valid: bool }
+fn foo(x: Option<S>) -> Option<S> {
+    <|>match x {
+        Some(s) if s.valid => Some(s),
+        None => None,
+    }
+}
+",
+            r"
+struct S { valid: bool }
+fn foo(x: Option<S>) -> Option<S> {
+    x.filter(|s| s.valid)
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_with_filter_predicate() {
+        check_assist_with_core(
+            replace_match_with_combinator,
+            r"
+struct S;
+fn is_valid(s: &S) -> bool { true }
+fn foo(x: Option<S>) -> Option<S> {
+    <|>match x {
+        Some(s) if is_valid(&s) => Some(s),
+        _ => None,
+    }
+}
+",
+            r"
+struct S;
+fn is_valid(s: &S) -> bool { true }
+fn foo(x: Option<S>) -> Option<S> {
+    x.filter(is_valid)
+}
+",
+        );
+    }
+
+    #[test]
+    fn wraps_receiver_in_parens() {
+        check_assist(
+            replace_match_with_combinator,
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>, y: Option<i32>) -> Option<i32> {
+    <|>match if true { x } else { y } {
+        Some(v) => Some(v + 1),
+        None => None,
+    }
+}
+",
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>, y: Option<i32>) -> Option<i32> {
+    (if true { x } else { y }).map(|v| v + 1)
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_sad_case_changes() {
+        check_assist_not_applicable(
+            replace_match_with_combinator,
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>) -> Option<i32> {
+    <|>match x {
+        Some(v) => Some(v + 1),
+        None => Some(0),
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_identity() {
+        check_assist_not_applicable(
+            replace_match_with_combinator,
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>) -> Option<i32> {
+    <|>match x {
+        Some(v) => Some(v),
+        None => None,
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_match_with_combinator_target() {
+        check_assist_target(
+            replace_match_with_combinator,
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>) -> Option<i32> {
+    <|>match x { Some(v) => Some(v + 1), None => None }
+}
+",
+            "match x { Some(v) => Some(v + 1), None => None }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_outer_control_flow() {
+        for body in &[
+            "Some(parse(v)?)",
+            "{ if v < 0 { return None; } Some(v) }",
+            "{ if v < 0 { break; } Some(v) }",
+            "{ if v < 0 { continue; } Some(v) }",
+        ] {
+            check_assist_not_applicable(
+                replace_match_with_combinator,
+                &format!(
+                    r"
+enum Option<T> {{ Some(T), None }}
+use Option::*;
+fn parse(x: i32) -> Option<i32> {{ Some(x) }}
+fn foo(xs: &[Option<i32>]) -> Option<i32> {{
+    let mut res = None;
+    for &x in xs {{
+        res = <|>match x {{
+            Some(v) => {},
+            None => None,
+        }};
+    }}
+    res
+}}
+",
+                    body
+                ),
+            );
+        }
+    }
+
+    #[test]
+    fn applicable_with_nested_control_flow() {
+        check_assist(
+            replace_match_with_combinator,
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>) -> Option<i32> {
+    <|>match x {
+        Some(v) => Some(loop { if v > 0 { break v; } }),
+        None => None,
+    }
+}
+",
+            r"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn foo(x: Option<i32>) -> Option<i32> {
+    x.map(|v| loop { if v > 0 { break v; } })
+}
+",
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use hir::{ModuleDef, ScopeDef};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    Direction, SyntaxElement,
+    SyntaxKind::{CHAR, STRING, WHITESPACE},
+    TextRange,
+};
+
+use crate::{
+    utils::{insert_use_statement, FamousDefs},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: replace_string_concat_with_write
+//
+// Replaces consecutive `push_str`, `push` and `+=` calls building a `String`,
+// or a chain of `+` concatenations, with a single `write!` into the buffer.
+//
+// ```
+// # //- /main.rs crate:main deps:core
+// fn greet(name: &str) -> String {
+//     let mut buf = String::new();
+//     buf.push_str("Hello, ");<|>
+//     buf.push_str(name);
+//     buf.push('!');
+//     buf
+// }
+// # //- /libcore.rs crate:core
+// # pub mod fmt { pub trait Write {} }
+// ```
+// ->
+// ```
+// use core::fmt::Write;
+//
+// fn greet(name: &str) -> String {
+//     let mut buf = String::new();
+//     write!(buf, "Hello, {}!", name).unwrap();
+//     buf
+// }
+// ```
+pub(crate) fn replace_string_concat_with_write(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let stmt = ctx.find_node_at_offset::<ast::Stmt>()?;
+    let (buffer, range, parts, init) = match &stmt {
+        ast::Stmt::ExprStmt(it) => {
+            let (buffer, _) = push_to(it)?;
+            let run = push_run(it, &buffer);
+            if run.len() < 2 {
+                return None;
+            }
+            let parts = run.iter().filter_map(push_to).flat_map(|(_, it)| parts(&it));
+            let range = TextRange::new(
+                run.first()?.syntax().text_range().start(),
+                run.last()?.syntax().text_range().end(),
+            );
+            (buffer, range, parts.collect(), None)
+        }
+        ast::Stmt::LetStmt(it) => {
+            let initializer = match it.initializer()? {
+                ast::Expr::BinExpr(it) => it,
+                _ => return None,
+            };
+            let buffer = match it.pat()? {
+                ast::Pat::BindPat(it) if it.ref_token().is_none() && it.pat().is_none() => it,
+                _ => return None,
+            };
+            let parts = parts(&initializer.clone().into());
+            // Tells apart building a string from adding numbers.
+            let is_string = parts.iter().any(|it| it.is_text())
+                || leftmost_operand(&initializer)
+                    .map_or(false, |it| to_string_receiver(&it).is_some());
+            if concatenations(&initializer) < 2 || !is_string {
+                return None;
+            }
+            let ty = it.ascribed_type().map(|it| format!(": {}", it)).unwrap_or_default();
+            let init = format!("let mut {}{} = String::new();", buffer.name()?, ty);
+            (buffer.name()?.to_string(), it.syntax().text_range(), parts, Some(init))
+        }
+    };
+
+    let module = ctx.sema.scope(stmt.syntax()).module()?;
+    let write_trait = FamousDefs(&ctx.sema, module.krate()).core_fmt_Write()?;
+    let mut write_in_scope = false;
+    let mut name_taken = false;
+    ctx.sema.scope(stmt.syntax()).process_all_names(&mut |name, def| {
+        if name.to_string() == "Write" {
+            match def {
+                ScopeDef::ModuleDef(ModuleDef::Trait(it)) if it == write_trait => {
+                    write_in_scope = true
+                }
+                _ => name_taken = true,
+            }
+        }
+    });
+    // Importing `fmt::Write` would clash with `io::Write`.
+    if name_taken && !write_in_scope {
+        return None;
+    }
+    let write_path = if write_in_scope {
+        None
+    } else {
+        Some(module.find_use_path(ctx.db, ModuleDef::Trait(write_trait))?)
+    };
+
+    let target = range;
+    acc.add(
+        AssistId("replace_string_concat_with_write"),
+        format!("Replace concatenation with `write!` into `{}`", buffer),
+        target,
+        |builder| {
+            let mut format = String::new();
+            let mut args = String::new();
+            for part in &parts {
+                match part {
+                    Part::Text(it) => format.push_str(it),
+                    Part::Arg(it) => {
+                        format.push_str("{}");
+                        args.push_str(", ");
+                        args.push_str(it);
+                    }
+                }
+            }
+            let write = format!("write!({}, \"{}\"{}).unwrap();", buffer, format, args);
+            let text = match &init {
+                Some(init) => {
+                    format!("{}\n{}{}", init, IndentLevel::from_node(stmt.syntax()), write)
+                }
+                None => write,
+            };
+            builder.replace(range, text);
+            if let Some(write_path) = &write_path {
+                insert_use_statement(stmt.syntax(), write_path, ctx, builder.text_edit_builder());
+            }
+        },
+    )
+}
+
+/// A piece of the string, either inlined into the format string or formatted
+/// with `{}`.
+enum Part {
+    Text(String),
+    Arg(String),
+}
+
+impl Part {
+    fn is_text(&self) -> bool {
+        matches!(self, Part::Text(_))
+    }
+}
+
+/// Returns the buffer and the pushed value of `buf.push_str(x);`,
+/// `buf.push(x);` or `buf += x;`.
+fn push_to(stmt: &ast::ExprStmt) -> Option<(String, ast::Expr)> {
+    match stmt.expr()? {
+        ast::Expr::MethodCallExpr(call) => {
+            let name = call.name_ref()?;
+            if name.text() != "push_str" && name.text() != "push" {
+                return None;
+            }
+            let mut args = call.arg_list()?.args();
+            let arg = args.next()?;
+            if args.next().is_some() {
+                return None;
+            }
+            match call.expr()? {
+                ast::Expr::PathExpr(it) => Some((it.syntax().to_string(), arg)),
+                _ => None,
+            }
+        }
+        ast::Expr::BinExpr(bin) if bin.op_kind()? == ast::BinOp::AddAssign => match bin.lhs()? {
+            ast::Expr::PathExpr(it) => Some((it.syntax().to_string(), bin.rhs()?)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The consecutive pushes to `buffer` around `stmt`, without comments
+/// between them.
+fn push_run(stmt: &ast::ExprStmt, buffer: &str) -> Vec<ast::ExprStmt> {
+    let is_push = |element: &SyntaxElement| {
+        element
+            .as_node()
+            .cloned()
+            .and_then(ast::ExprStmt::cast)
+            .filter(|it| push_to(it).map_or(false, |(it, _)| it == buffer))
+    };
+    let siblings = |direction| {
+        stmt.syntax()
+            .siblings_with_tokens(direction)
+            .skip(1)
+            .filter(|it| it.kind() != WHITESPACE)
+            .map(|it| is_push(&it))
+            .take_while(|it| it.is_some())
+            .flatten()
+    };
+    let mut run: Vec<ast::ExprStmt> = siblings(Direction::Prev).collect();
+    run.reverse();
+    run.push(stmt.clone());
+    run.extend(siblings(Direction::Next));
+    run
+}
+
+fn concatenations(expr: &ast::BinExpr) -> usize {
+    if expr.op_kind() != Some(ast::BinOp::Addition) {
+        return 0;
+    }
+    match expr.lhs() {
+        Some(ast::Expr::BinExpr(lhs)) => concatenations(&lhs) + 1,
+        _ => 1,
+    }
+}
+
+fn leftmost_operand(expr: &ast::BinExpr) -> Option<ast::Expr> {
+    match expr.lhs()? {
+        ast::Expr::BinExpr(lhs) => leftmost_operand(&lhs),
+        it => Some(it),
+    }
+}
+
+fn parts(expr: &ast::Expr) -> Vec<Part> {
+    match expr {
+        ast::Expr::BinExpr(bin) if bin.op_kind() == Some(ast::BinOp::Addition) => {
+            match (bin.lhs(), bin.rhs()) {
+                (Some(lhs), Some(rhs)) => {
+                    let mut res = parts(&lhs);
+                    res.extend(parts(&rhs));
+                    res
+                }
+                _ => vec![Part::Arg(expr.syntax().to_string())],
+            }
+        }
+        ast::Expr::RefExpr(it) if it.mut_token().is_none() => match it.expr() {
+            Some(inner) => parts(&inner),
+            None => vec![Part::Arg(expr.syntax().to_string())],
+        },
+        ast::Expr::Literal(it) => match literal_text(it) {
+            Some(text) => vec![Part::Text(text)],
+            None => vec![Part::Arg(expr.syntax().to_string())],
+        },
+        _ => match to_string_receiver(expr) {
+            Some(inner) => parts(&inner),
+            None => vec![Part::Arg(expr.syntax().to_string())],
+        },
+    }
+}
+
+/// Returns `x` of `x.to_string()`, `x.to_owned()` or `String::from(x)`,
+/// which is formatted the same way.
+fn to_string_receiver(expr: &ast::Expr) -> Option<ast::Expr> {
+    match expr {
+        ast::Expr::MethodCallExpr(call) => {
+            let name = call.name_ref()?;
+            let is_no_args = call.arg_list()?.args().next().is_none();
+            if (name.text() == "to_string" || name.text() == "to_owned") && is_no_args {
+                call.expr()
+            } else {
+                None
+            }
+        }
+        ast::Expr::CallExpr(call) => {
+            let path = match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            if path.syntax().text() != "String::from" {
+                return None;
+            }
+            let mut args = call.arg_list()?.args();
+            let arg = args.next()?;
+            if args.next().is_some() {
+                return None;
+            }
+            Some(arg)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the content of a string or char literal, escaped for a format
+/// string.
+fn literal_text(literal: &ast::Literal) -> Option<String> {
+    let token = literal.token();
+    let text = token.text().as_str();
+    let content = match token.kind() {
+        STRING => text.strip_prefix('"')?.strip_suffix('"')?.to_string(),
+        CHAR => match text.strip_prefix('\'')?.strip_suffix('\'')? {
+            "\"" => "\\\"".to_string(),
+            "\\'" => "'".to_string(),
+            it => it.to_string(),
+        },
+        _ => return None,
+    };
+    Some(content.replace('{', "{{").replace('}', "}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target, with_core};
+
+    use super::*;
+
+    #[test]
+    fn replace_pushes() {
+        check_assist(
+            replace_string_concat_with_write,
+            &with_core(
+                r#"
+use core::fmt::Write;
+fn render(name: &str, age: u32) -> String {
+    let mut out = String::with_capacity(16);
+    out.push('{');
+    out.push_str(name);<|>
+    out += ": ";
+    out.push_str(&age.to_string());
+    out.push('}');
+    // Done.
+    out.push('\n');
+    out
+}"#,
+            ),
+            r#"
+use core::fmt::Write;
+fn render(name: &str, age: u32) -> String {
+    let mut out = String::with_capacity(16);
+    write!(out, "{{{}: {}}}", name, age).unwrap();
+    // Done.
+    out.push('\n');
+    out
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_concatenation_chain() {
+        check_assist(
+            replace_string_concat_with_write,
+            &with_core(
+                r#"
+fn path(dir: &str, file: &str) -> String {
+    let path<|>: String = dir.to_string() + "/" + &file + ".rs";
+    path
+}"#,
+            ),
+            r#"
+use core::fmt::Write;
+
+fn path(dir: &str, file: &str) -> String {
+    let mut path: String = String::new();
+    write!(path, "{}/{}.rs", dir, file).unwrap();
+    path
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        // A single push.
+        check_assist_not_applicable(
+            replace_string_concat_with_write,
+            &with_core("fn f() { let mut s = String::new(); s.push_str(\"a\");<|> }"),
+        );
+        // Adding numbers.
+        check_assist_not_applicable(
+            replace_string_concat_with_write,
+            &with_core("fn f(a: u32, b: u32) { let sum<|> = a + b + 1; }"),
+        );
+        // `io::Write` is in scope.
+        check_assist_not_applicable(
+            replace_string_concat_with_write,
+            &with_core(
+                r#"
+mod io { pub trait Write {} }
+use io::Write;
+fn f(mut s: String) { s.push('a');<|> s.push('b'); }"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_string_concat_with_write_target() {
+        check_assist_target(
+            replace_string_concat_with_write,
+            &with_core("fn f(mut s: String) { s.push('a');<|> s += \"b\"; s.len(); }"),
+            "s.push('a'); s += \"b\";",
+        );
+    }
+}
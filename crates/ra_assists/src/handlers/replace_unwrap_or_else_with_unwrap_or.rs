@@ -0,0 +1,304 @@
+use hir::{AssocItem, AssocItemContainer, ImplDef, ModuleDef, PathResolution};
+use ra_syntax::ast::{self, ArgListOwner, AstNode};
+
+use crate::{
+    utils::{FamousDefs, TryEnum},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: replace_unwrap_or_else_with_unwrap_or
+//
+// Replaces `unwrap_or_else` with a closure which merely returns a constant
+// expression by `unwrap_or`.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// fn main() {
+//     let x: Option<i32> = Option::None;
+//     let y = x.<|>unwrap_or_else(|| 92);
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// fn main() {
+//     let x: Option<i32> = Option::None;
+//     let y = x.unwrap_or(92);
+// }
+// ```
+pub(crate) fn replace_unwrap_or_else_with_unwrap_or(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let method_call: ast::MethodCallExpr = ctx.find_node_at_offset()?;
+    let name = method_call.name_ref()?;
+    if name.text() != "unwrap_or_else" {
+        return None;
+    }
+    let receiver_ty = ctx.sema.type_of_expr(&method_call.expr()?)?;
+    let try_enum = TryEnum::from_ty(&ctx.sema, &receiver_ty)?;
+
+    let arg_list = method_call.arg_list()?;
+    let mut args = arg_list.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+
+    let (label, replacement) = match &arg {
+        ast::Expr::LambdaExpr(lambda) => {
+            // `Result::unwrap_or_else` passes the error to the closure.
+            let param_count = match try_enum {
+                TryEnum::Option => 0,
+                TryEnum::Result => 1,
+            };
+            if lambda.param_list()?.params().count() != param_count {
+                return None;
+            }
+            let body = lambda.body()?;
+            if !is_constant_expr(ctx, &body) {
+                return None;
+            }
+            ("Replace `unwrap_or_else` with `unwrap_or`", format!("unwrap_or({})", body))
+        }
+        // `Result::unwrap_or_else` passes the error to the function, which
+        // `Default::default` doesn't take.
+        ast::Expr::PathExpr(path_expr) if matches!(try_enum, TryEnum::Option) => {
+            if !is_default_fn(ctx, &path_expr.path()?) {
+                return None;
+            }
+            ("Replace `unwrap_or_else` with `unwrap_or_default`", "unwrap_or_default()".to_string())
+        }
+        _ => return None,
+    };
+
+    let target = method_call.syntax().text_range();
+    acc.add(AssistId("replace_unwrap_or_else_with_unwrap_or"), label, target, |builder| {
+        let range = name.syntax().text_range().cover(arg_list.syntax().text_range());
+        builder.replace(range, replacement);
+    })
+}
+
+/// Checks whether `path` resolves to `Default::default`, or to the `default`
+/// function of an impl of `Default`.
+fn is_default_fn(ctx: &AssistContext, path: &ast::Path) -> bool {
+    let function = match ctx.sema.resolve_path(path) {
+        Some(PathResolution::AssocItem(AssocItem::Function(it))) => it,
+        _ => return false,
+    };
+    if function.name(ctx.db).to_string() != "default" {
+        return false;
+    }
+    let krate = match ctx.sema.scope(path.syntax()).module() {
+        Some(it) => it.krate(),
+        None => return false,
+    };
+    let default_trait = match FamousDefs(&ctx.sema, krate).core_default_Default() {
+        Some(it) => it,
+        None => return false,
+    };
+    match AssocItem::Function(function).container(ctx.db) {
+        AssocItemContainer::Trait(it) => it == default_trait,
+        AssocItemContainer::ImplDef(it) => {
+            ImplDef::for_trait(ctx.db, it.module(ctx.db).krate(), default_trait).contains(&it)
+        }
+    }
+}
+
+/// Checks that evaluating `expr` eagerly can't be observed, that is, it
+/// doesn't call anything and doesn't capture any locals.
+fn is_constant_expr(ctx: &AssistContext, expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Literal(_) => true,
+        ast::Expr::PathExpr(path_expr) => {
+            let path = match path_expr.path() {
+                Some(it) => it,
+                None => return false,
+            };
+            match ctx.sema.resolve_path(&path) {
+                Some(PathResolution::Def(def)) => matches!(
+                    def,
+                    ModuleDef::Const(_) | ModuleDef::EnumVariant(_) | ModuleDef::Adt(_)
+                ),
+                Some(PathResolution::AssocItem(hir::AssocItem::Const(_))) => true,
+                _ => false,
+            }
+        }
+        ast::Expr::ParenExpr(it) => it.expr().map_or(false, |it| is_constant_expr(ctx, &it)),
+        ast::Expr::PrefixExpr(it) => {
+            it.op_kind() == Some(ast::PrefixOp::Neg)
+                && it.expr().map_or(false, |it| is_constant_expr(ctx, &it))
+        }
+        ast::Expr::TupleExpr(it) => it.exprs().all(|it| is_constant_expr(ctx, &it)),
+        ast::Expr::ArrayExpr(it) => it.exprs().all(|it| is_constant_expr(ctx, &it)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist_not_applicable, check_assist_target, check_assist_with_core, with_core,
+    };
+
+    use super::*;
+
+    #[test]
+    fn replace_closure_returning_literal() {
+        check_assist_with_core(
+            replace_unwrap_or_else_with_unwrap_or,
+            r"
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.unwrap_or_else(<|>|| -1);
+}
+",
+            r"
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.unwrap_or(-1);
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_closure_ignoring_error() {
+        check_assist_with_core(
+            replace_unwrap_or_else_with_unwrap_or,
+            r"
+const FALLBACK: (i32, i32) = (0, 0);
+fn main() {
+    let x: Result<(i32, i32), ()> = Err(());
+    let y = x.<|>unwrap_or_else(|_| FALLBACK);
+}
+",
+            r"
+const FALLBACK: (i32, i32) = (0, 0);
+fn main() {
+    let x: Result<(i32, i32), ()> = Err(());
+    let y = x.unwrap_or(FALLBACK);
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_default_with_unwrap_or_default() {
+        check_assist_with_core(
+            replace_unwrap_or_else_with_unwrap_or,
+            r"
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.<|>unwrap_or_else(Default::default);
+}
+",
+            r"
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.unwrap_or_default();
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_default_of_result_or_other_fn() {
+        check_assist_not_applicable(
+            replace_unwrap_or_else_with_unwrap_or,
+            &with_core(
+                r"
+fn main() {
+    let x: Result<i32, ()> = Err(());
+    let y = x.<|>unwrap_or_else(Default::default);
+}
+",
+            ),
+        );
+        check_assist_not_applicable(
+            replace_unwrap_or_else_with_unwrap_or,
+            &with_core(
+                r"
+mod other { pub fn default() -> i32 { 92 } }
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.<|>unwrap_or_else(other::default);
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_closure_captures() {
+        check_assist_not_applicable(
+            replace_unwrap_or_else_with_unwrap_or,
+            &with_core(
+                r"
+fn main() {
+    let x: Option<i32> = None;
+    let z = 92;
+    let y = x.<|>unwrap_or_else(|| z);
+}
+",
+            ),
+        );
+        check_assist_not_applicable(
+            replace_unwrap_or_else_with_unwrap_or,
+            &with_core(
+                r"
+fn main() {
+    let x: Result<i32, i32> = Err(92);
+    let y = x.<|>unwrap_or_else(|e| e);
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_closure_has_side_effects() {
+        check_assist_not_applicable(
+            replace_unwrap_or_else_with_unwrap_or,
+            &with_core(
+                r"
+fn compute() -> i32 { 92 }
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.<|>unwrap_or_else(|| compute());
+}
+",
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_other_types() {
+        check_assist_not_applicable(
+            replace_unwrap_or_else_with_unwrap_or,
+            r"
+struct S;
+impl S { fn unwrap_or_else(self, f: impl FnOnce() -> i32) -> i32 { f() } }
+fn main() {
+    let y = S.<|>unwrap_or_else(|| 92);
+}
+",
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_or_else_target() {
+        check_assist_target(
+            replace_unwrap_or_else_with_unwrap_or,
+            &with_core(
+                r"
+fn main() {
+    let x: Option<i32> = None;
+    let y = x.<|>unwrap_or_else(|| 92);
+}
+",
+            ),
+            "x.unwrap_or_else(|| 92)",
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner},
+    SyntaxKind::{COMMENT, WHITESPACE},
+    SyntaxNode,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: toggle_cfg
+//
+// Flips a `cfg` attribute between `cfg(...)` and `cfg(not(...))`.
+//
+// ```
+// #[cfg(debug_<|>assertions)]
+// fn log(message: &str) {}
+// ```
+// ->
+// ```
+// #[cfg(not(debug_assertions))]
+// fn log(message: &str) {}
+// ```
+pub(crate) fn toggle_cfg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let attr = ctx.find_node_at_offset::<ast::Attr>()?;
+    let predicate = cfg_predicate(&attr)?;
+    let (label, new_predicate) = match negated(&predicate) {
+        Some(inner) => (format!("Replace with `cfg({})`", inner), inner.to_string()),
+        None => (format!("Replace with `cfg(not({}))`", predicate), format!("not({})", predicate)),
+    };
+
+    let (_, tt) = attr.as_simple_call()?;
+    let target = attr.syntax().text_range();
+    acc.add(AssistId("toggle_cfg"), label, target, |builder| {
+        builder.replace(tt.syntax().text_range(), format!("({})", new_predicate));
+    })
+}
+
+// Assist: add_negated_cfg
+//
+// Adds the opposite `cfg` attribute to the next item, so that exactly one of
+// the two items is compiled.
+//
+// ```
+// #[cfg(debug_<|>assertions)]
+// fn log(message: &str) {}
+// fn log(_message: &str) {}
+// ```
+// ->
+// ```
+// #[cfg(debug_assertions)]
+// fn log(message: &str) {}
+// #[cfg(not(debug_assertions))]
+// fn log(_message: &str) {}
+// ```
+pub(crate) fn add_negated_cfg(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let attr = ctx.find_node_at_offset::<ast::Attr>()?;
+    let predicate = cfg_predicate(&attr)?;
+    let negated_predicate = match negated(&predicate) {
+        Some(inner) => inner.to_string(),
+        None => format!("not({})", predicate),
+    };
+
+    let next_item = attr.syntax().parent()?.next_sibling()?;
+    if has_cfg_attr(&next_item)? {
+        return None;
+    }
+    let offset = next_item
+        .children_with_tokens()
+        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+        .text_range()
+        .start();
+
+    let target = attr.syntax().text_range();
+    acc.add(
+        AssistId("add_negated_cfg"),
+        format!("Add `cfg({})` to the next item", negated_predicate),
+        target,
+        |builder| {
+            let indent = IndentLevel::from_node(&next_item);
+            builder.insert(offset, format!("#[cfg({})]\n{}", negated_predicate, indent));
+        },
+    )
+}
+
+/// Returns the text of `predicate` in `#[cfg(predicate)]`.
+fn cfg_predicate(attr: &ast::Attr) -> Option<String> {
+    let (name, tt) = attr.as_simple_call()?;
+    if attr.excl_token().is_some() || name != "cfg" {
+        return None;
+    }
+    let text = tt.syntax().text().to_string();
+    let inner = text.strip_prefix('(')?.strip_suffix(')')?.trim();
+    if inner.is_empty() {
+        return None;
+    }
+    Some(inner.to_string())
+}
+
+fn negated(predicate: &str) -> Option<&str> {
+    let inner = predicate.strip_prefix("not")?.trim_start();
+    let inner = inner.strip_prefix('(')?.strip_suffix(')')?;
+    // Ensure that the parens match, to not break up `not(a), b`.
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return None,
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+    Some(inner.trim())
+}
+
+/// Returns `None` if `node` is not an item.
+fn has_cfg_attr(node: &SyntaxNode) -> Option<bool> {
+    let mut attrs = match (ast::ModuleItem::cast(node.clone()), ast::AssocItem::cast(node.clone()))
+    {
+        (Some(item), _) => item.attrs(),
+        (None, Some(item)) => item.attrs(),
+        (None, None) => return None,
+    };
+    Some(attrs.any(|attr| attr.simple_name().map_or(false, |it| it == "cfg")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn toggle_cfg_adds_not() {
+        check_assist(
+            toggle_cfg,
+            "#[cfg(<|>all(unix, feature = \"foo\"))]\nfn foo() {}",
+            "#[cfg(not(all(unix, feature = \"foo\")))]\nfn foo() {}",
+        );
+    }
+
+    #[test]
+    fn toggle_cfg_removes_not() {
+        check_assist(
+            toggle_cfg,
+            "impl S {\n    #[cfg<|>(not(test))]\n    fn foo() {}\n}",
+            "impl S {\n    #[cfg(test)]\n    fn foo() {}\n}",
+        );
+    }
+
+    #[test]
+    fn toggle_cfg_not_applicable() {
+        check_assist_not_applicable(toggle_cfg, "#[derive(<|>Debug)]\nstruct S;");
+        check_assist_not_applicable(toggle_cfg, "#[cfg_attr(<|>test, derive(Debug))]\nstruct S;");
+        check_assist_not_applicable(toggle_cfg, "#![cfg(<|>test)]");
+    }
+
+    #[test]
+    fn negated_predicate_requires_matching_parens() {
+        assert_eq!(negated("not(test)"), Some("test"));
+        assert_eq!(negated("not(any(a, b))"), Some("any(a, b)"));
+        assert_eq!(negated("not(a), not(b)"), None);
+        assert_eq!(negated("nothing"), None);
+    }
+
+    #[test]
+    fn add_negated_cfg_to_next_item() {
+        check_assist(
+            add_negated_cfg,
+            r"
+mod log {
+    /// Logs to stderr.
+    #[cfg(<|>not(debug_assertions))]
+    pub fn log(message: &str) {}
+    /// Does nothing.
+    pub fn log(_message: &str) {}
+}
+",
+            r"
+mod log {
+    /// Logs to stderr.
+    #[cfg(not(debug_assertions))]
+    pub fn log(message: &str) {}
+    /// Does nothing.
+    #[cfg(debug_assertions)]
+    pub fn log(_message: &str) {}
+}
+",
+        );
+    }
+
+    #[test]
+    fn add_negated_cfg_not_applicable() {
+        check_assist_not_applicable(
+            add_negated_cfg,
+            "#[cfg(<|>test)]\nfn foo() {}\n#[cfg(not(test))]\nfn foo() {}",
+        );
+        check_assist_not_applicable(add_negated_cfg, "#[cfg(<|>test)]\nfn foo() {}");
+    }
+
+    #[test]
+    fn toggle_cfg_target() {
+        check_assist_target(toggle_cfg, "#[cfg(<|>test)]\nfn foo() {}", "#[cfg(test)]");
+    }
+}
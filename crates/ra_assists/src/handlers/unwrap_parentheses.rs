@@ -0,0 +1,282 @@
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::*,
+    SyntaxNode, SyntaxToken, T,
+};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: unwrap_parentheses
+//
+// Removes parentheses, which are redundant because of the precedence of the
+// surrounding operators.
+//
+// ```
+// fn area(w: u32, h: u32) -> u32 {
+//     let border = 2 + <|>(w * h);
+//     border
+// }
+// ```
+// ->
+// ```
+// fn area(w: u32, h: u32) -> u32 {
+//     let border = 2 + w * h;
+//     border
+// }
+// ```
+pub(crate) fn unwrap_parentheses(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let paren_expr = ctx
+        .token_at_offset()
+        .filter(|it| it.kind() == T!['('] || it.kind() == T![')'])
+        .find_map(|it| ast::ParenExpr::cast(it.parent()))?;
+    let inner = paren_expr.expr()?;
+    if !is_redundant(&paren_expr, &inner) {
+        return None;
+    }
+
+    let target = paren_expr.syntax().text_range();
+    acc.add(AssistId("unwrap_parentheses"), "Remove redundant parentheses", target, |builder| {
+        let mut text = inner.syntax().text().to_string();
+        // Keeps `return(x)` from becoming `returnx`.
+        if paren_expr.l_paren_token().and_then(|it| it.prev_token()).map_or(false, is_word) {
+            text.insert(0, ' ');
+        }
+        if paren_expr.r_paren_token().and_then(|it| it.next_token()).map_or(false, is_word) {
+            text.push(' ');
+        }
+        builder.replace(target, text);
+    })
+}
+
+fn is_word(token: SyntaxToken) -> bool {
+    token.kind() == IDENT || token.kind().is_keyword()
+}
+
+fn is_redundant(paren_expr: &ast::ParenExpr, inner: &ast::Expr) -> bool {
+    // `if (S {}) == s {}` can't be written without the parentheses.
+    let has_record_lit = inner.syntax().descendants().any(|it| it.kind() == RECORD_LIT);
+    if has_record_lit && in_condition(paren_expr.syntax()) {
+        return false;
+    }
+    // `(match x { .. }) + 1;` would be parsed as a statement followed by `+1`.
+    if is_block_like(inner) && starts_statement(paren_expr.syntax()) {
+        return false;
+    }
+    let node = paren_expr.syntax();
+    let parent = match node.parent().and_then(ast::Expr::cast) {
+        Some(it) => it,
+        // Delimited by a statement, an argument list, a condition and so on.
+        None => return true,
+    };
+    let precedence = precedence(inner);
+    match parent {
+        ast::Expr::MethodCallExpr(it) if is_same(it.expr(), node) => is_postfix(inner),
+        ast::Expr::FieldExpr(it) if is_same(it.expr(), node) => is_postfix(inner),
+        ast::Expr::TryExpr(_) | ast::Expr::AwaitExpr(_) => is_postfix(inner),
+        ast::Expr::IndexExpr(it) if is_same(it.base(), node) => is_postfix(inner),
+        // `(s.f)()` calls the field instead of a method.
+        ast::Expr::CallExpr(it) if is_same(it.expr(), node) => {
+            is_postfix(inner) && !matches!(inner, ast::Expr::FieldExpr(_))
+        }
+        ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => {
+            precedence >= Precedence::Unary
+        }
+        ast::Expr::CastExpr(_) => precedence >= Precedence::Cast,
+        ast::Expr::RangeExpr(_) => precedence > Precedence::Range,
+        ast::Expr::BinExpr(it) => {
+            let op = match it.op_kind() {
+                Some(it) => it,
+                None => return false,
+            };
+            let parent_precedence = bin_op_precedence(op);
+            let is_lhs = is_same(it.lhs(), node);
+            // `x as u8 < y` would be parsed as the start of generic arguments.
+            if is_lhs
+                && matches!(inner, ast::Expr::CastExpr(_))
+                && matches!(op, ast::BinOp::LesserTest | ast::BinOp::LeftShift)
+            {
+                return false;
+            }
+            if precedence != parent_precedence {
+                return precedence > parent_precedence;
+            }
+            match parent_precedence {
+                Precedence::Comparison => false,
+                Precedence::Assignment => !is_lhs,
+                _ => is_lhs,
+            }
+        }
+        _ => true,
+    }
+}
+
+/// The binding power of expressions, from the weakest to the strongest.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Jump,
+    Assignment,
+    Range,
+    Or,
+    And,
+    Comparison,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Sum,
+    Product,
+    Cast,
+    Unary,
+    Postfix,
+}
+
+fn precedence(expr: &ast::Expr) -> Precedence {
+    match expr {
+        ast::Expr::LambdaExpr(_) | ast::Expr::ReturnExpr(_) | ast::Expr::BreakExpr(_) => {
+            Precedence::Jump
+        }
+        ast::Expr::RangeExpr(_) => Precedence::Range,
+        ast::Expr::BinExpr(it) => it.op_kind().map_or(Precedence::Jump, bin_op_precedence),
+        ast::Expr::CastExpr(_) => Precedence::Cast,
+        ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => {
+            Precedence::Unary
+        }
+        _ => Precedence::Postfix,
+    }
+}
+
+fn bin_op_precedence(op: ast::BinOp) -> Precedence {
+    use ast::BinOp::*;
+    match op {
+        BooleanOr => Precedence::Or,
+        BooleanAnd => Precedence::And,
+        EqualityTest | NegatedEqualityTest | LesserEqualTest | GreaterEqualTest | LesserTest
+        | GreaterTest => Precedence::Comparison,
+        BitwiseOr => Precedence::BitOr,
+        BitwiseXor => Precedence::BitXor,
+        BitwiseAnd => Precedence::BitAnd,
+        LeftShift | RightShift => Precedence::Shift,
+        Addition | Subtraction => Precedence::Sum,
+        Multiplication | Division | Remainder => Precedence::Product,
+        Assignment | AddAssign | DivAssign | MulAssign | RemAssign | ShrAssign | ShlAssign
+        | SubAssign | BitOrAssign | BitAndAssign | BitXorAssign => Precedence::Assignment,
+    }
+}
+
+/// Checks whether a postfix operator, like a method call, can be applied to
+/// `expr` without parentheses.
+fn is_postfix(expr: &ast::Expr) -> bool {
+    match expr {
+        // `(1.).max(x)` would become a range.
+        ast::Expr::Literal(it) => !it.syntax().text().to_string().ends_with('.'),
+        ast::Expr::PathExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::TupleExpr(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::MacroCall(_) => true,
+        _ => false,
+    }
+}
+
+fn is_block_like(expr: &ast::Expr) -> bool {
+    matches!(
+        expr,
+        ast::Expr::BlockExpr(_)
+            | ast::Expr::EffectExpr(_)
+            | ast::Expr::IfExpr(_)
+            | ast::Expr::MatchExpr(_)
+            | ast::Expr::LoopExpr(_)
+            | ast::Expr::WhileExpr(_)
+            | ast::Expr::ForExpr(_)
+    )
+}
+
+fn starts_statement(node: &SyntaxNode) -> bool {
+    let start = node.text_range().start();
+    node.ancestors()
+        .take_while(|it| it.text_range().start() == start)
+        .any(|it| it.kind() == EXPR_STMT)
+}
+
+/// Checks whether `node` is part of the condition of an `if` or `while`, the
+/// iterable of a `for` or the scrutinee of a `match`.
+fn in_condition(node: &SyntaxNode) -> bool {
+    node.ancestors()
+        .skip(1)
+        .take_while(|it| !matches!(it.kind(), BLOCK_EXPR | MATCH_ARM_LIST))
+        .any(|it| matches!(it.kind(), CONDITION | FOR_EXPR | MATCH_EXPR))
+}
+
+fn is_same(expr: Option<ast::Expr>, node: &SyntaxNode) -> bool {
+    expr.map_or(false, |it| it.syntax() == node)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    fn check_redundant(before: &str, after: &str) {
+        check_assist(
+            unwrap_parentheses,
+            &format!("fn f() {{ {} }}", before),
+            &format!("fn f() {{ {} }}", after),
+        );
+    }
+
+    fn check_needed(ra_fixture: &str) {
+        check_assist_not_applicable(unwrap_parentheses, &format!("fn f() {{ {} }}", ra_fixture));
+    }
+
+    #[test]
+    fn unwrap_redundant_parentheses() {
+        check_redundant("let x = <|>(a + b);", "let x = a + b;");
+        check_redundant("foo(<|>(a), b);", "foo(a, b);");
+        check_redundant("a + <|>(b * c)", "a + b * c");
+        check_redundant("<|>(a - b) - c", "a - b - c");
+        check_redundant("<|>(-x) as u32", "-x as u32");
+        check_redundant("-<|>(x.abs())", "-x.abs()");
+        check_redundant("<|>(a.b).c()", "a.b.c()");
+        check_redundant("a = <|>(b = c)", "a = b = c");
+        check_redundant("x..<|>(y + 1)", "x..y + 1");
+        check_redundant("<|>(x as u8) as u32", "x as u8 as u32");
+        check_redundant("if <|>(a && b) {}", "if a && b {}");
+        check_redundant("return<|>(x);", "return x;");
+        check_redundant("let x = (<|>(a));", "let x = (a);");
+    }
+
+    #[test]
+    fn keep_needed_parentheses() {
+        check_needed("<|>(a + b) * c");
+        check_needed("<|>(a * b) as u32");
+        check_needed("a - <|>(b - c)");
+        check_needed("a == <|>(b == c)");
+        check_needed("<|>(a = b) = c");
+        check_needed("<|>(-x).abs()");
+        check_needed("<|>(s.f)()");
+        check_needed("<|>(x as u8) < y");
+        check_needed("<|>(1.).max(x)");
+        check_needed("<|>(a..b).len()");
+        check_needed("&<|>(a + b)");
+        check_needed("x + <|>(|| 1)");
+        check_needed("if x == <|>(S {}) {}");
+        check_needed("<|>(match x { _ => 1 }) + 1;");
+    }
+
+    #[test]
+    fn not_applicable_inside_parentheses() {
+        check_assist_not_applicable(unwrap_parentheses, "fn f() { let x = (a +<|> b); }");
+    }
+
+    #[test]
+    fn unwrap_parentheses_target() {
+        check_assist_target(unwrap_parentheses, "fn f() { a + (b * c<|>) }", "(b * c)");
+    }
+}
@@ -0,0 +1,340 @@
+use hir::{Adt, HasSource, ModuleDef, ScopeDef};
+use ra_ide_db::search::ReferenceKind;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, SelfParamKind, TypeAscriptionOwner},
+    SyntaxNode,
+};
+
+use crate::{
+    utils::{field_usages, insert_use_statement, FamousDefs, FileEdits},
+    AssistContext, AssistId, Assists, GroupLabel,
+};
+
+// Assist: wrap_field_in_lock
+//
+// Wraps the type of a field in `Mutex` or `RwLock`, to make the struct
+// `Send + Sync`. Accesses to the field lock it, with `.write()` for writes
+// and `.read()` for reads in case of `RwLock`.
+//
+// ```
+// # //- /main.rs crate:main deps:std
+// struct Stats { hits: u64<|> }
+// fn hit(stats: &Stats) -> u64 { stats.hits }
+// # //- /libstd.rs crate:std
+// # pub mod sync { pub struct Mutex<T>(T); pub struct RwLock<T>(T); }
+// ```
+// ->
+// ```
+// use std::sync::Mutex;
+//
+// struct Stats { hits: Mutex<u64> }
+// fn hit(stats: &Stats) -> u64 { *stats.hits.lock().unwrap() }
+// ```
+pub(crate) fn wrap_field_in_lock(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    wrap_field(acc, ctx, &[Lock::Mutex, Lock::RwLock])
+}
+
+fn wrap_field(acc: &mut Assists, ctx: &AssistContext, locks: &[Lock]) -> Option<()> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let field_name = field.name()?;
+    let type_ref = field.ascribed_type()?;
+    if is_lock(&type_ref) {
+        return None;
+    }
+    let strukt = field.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let field_def = ctx.sema.to_def(&field)?;
+    let module = ctx.sema.scope(strukt.syntax()).module()?;
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+
+    let group = GroupLabel(format!("Wrap `{}` in a lock", field_name));
+    let target = field.syntax().text_range();
+    for &lock in locks {
+        let lock_def = match lock.find(&famous_defs) {
+            Some(it) => it,
+            None => continue,
+        };
+        let in_scope = {
+            let mut res = false;
+            ctx.sema.scope(strukt.syntax()).process_all_names(&mut |name, def| {
+                if let ScopeDef::ModuleDef(ModuleDef::Adt(Adt::Struct(it))) = def {
+                    res |= it == lock_def && name.to_string() == lock.name();
+                }
+            });
+            res
+        };
+        let lock_path = if in_scope {
+            None
+        } else {
+            match module.find_use_path(ctx.db, ModuleDef::Adt(lock_def.into())) {
+                Some(it) => Some(it),
+                None => continue,
+            }
+        };
+        acc.add_group(
+            &group,
+            AssistId("wrap_field_in_lock"),
+            format!("Wrap `{}` in `{}`", field_name, lock.name()),
+            target,
+            |builder| {
+                let mut edits = FileEdits::default();
+                for (file_id, kind, name_ref) in field_usages(ctx, field_def) {
+                    if kind == ReferenceKind::FieldShorthandForField {
+                        let range = name_ref.syntax().text_range();
+                        edits.add(file_id, range, format!("{0}: {1}::new({0})", name_ref, lock));
+                        continue;
+                    }
+                    let parent = match name_ref.syntax().parent() {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    if let Some(expr) =
+                        ast::RecordField::cast(parent.clone()).and_then(|it| it.expr())
+                    {
+                        let range = expr.syntax().text_range();
+                        edits.add(file_id, range, format!("{}::new({})", lock, expr));
+                        continue;
+                    }
+                    let field_expr = match ast::FieldExpr::cast(parent) {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    let guard = lock.guard(access(ctx, &field_expr));
+                    let deref = if is_autoderefed(&field_expr) { "" } else { "*" };
+                    let range = field_expr.syntax().text_range();
+                    edits.add(file_id, range, format!("{}{}{}", deref, field_expr, guard));
+                }
+                edits.add(
+                    ctx.frange.file_id,
+                    type_ref.syntax().text_range(),
+                    format!("{}<{}>", lock, type_ref),
+                );
+                edits.apply(builder, ctx.frange.file_id);
+                if let Some(lock_path) = &lock_path {
+                    insert_use_statement(
+                        strukt.syntax(),
+                        lock_path,
+                        ctx,
+                        builder.text_edit_builder(),
+                    );
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+#[derive(Clone, Copy)]
+enum Lock {
+    Mutex,
+    RwLock,
+}
+
+impl Lock {
+    fn name(self) -> &'static str {
+        match self {
+            Lock::Mutex => "Mutex",
+            Lock::RwLock => "RwLock",
+        }
+    }
+
+    fn find(self, famous_defs: &FamousDefs) -> Option<hir::Struct> {
+        match self {
+            Lock::Mutex => famous_defs.std_sync_Mutex(),
+            Lock::RwLock => famous_defs.std_sync_RwLock(),
+        }
+    }
+
+    /// The method calls acquiring the guard of the lock.
+    fn guard(self, access: Access) -> &'static str {
+        match (self, access) {
+            (Lock::Mutex, _) => ".lock().unwrap()",
+            (Lock::RwLock, Access::Read) => ".read().unwrap()",
+            (Lock::RwLock, Access::Write) => ".write().unwrap()",
+        }
+    }
+}
+
+impl std::fmt::Display for Lock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+}
+
+/// Checks whether the place starting with `field_expr`, like `s.field` or
+/// `s.field.x[0]`, is assigned to, borrowed mutably, or the receiver of a
+/// method taking `&mut self`.
+fn access(ctx: &AssistContext, field_expr: &ast::FieldExpr) -> Access {
+    let mut place = field_expr.syntax().clone();
+    while let Some(parent) = place.parent() {
+        let is_projection = match ast::Expr::cast(parent.clone()) {
+            Some(ast::Expr::FieldExpr(it)) => is_same(it.expr(), &place),
+            Some(ast::Expr::IndexExpr(it)) => is_same(it.base(), &place),
+            _ => false,
+        };
+        if !is_projection {
+            break;
+        }
+        place = parent;
+    }
+    let is_write = match place.parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::BinExpr(it)) => {
+            it.op_kind().map_or(false, |op| op.is_assignment()) && is_same(it.lhs(), &place)
+        }
+        Some(ast::Expr::RefExpr(it)) => it.mut_token().is_some(),
+        Some(ast::Expr::MethodCallExpr(it)) => {
+            is_same(it.expr(), &place)
+                && ctx
+                    .sema
+                    .resolve_method_call(&it)
+                    .and_then(|func| func.source(ctx.db).value.param_list()?.self_param())
+                    .map_or(false, |it| it.kind() == SelfParamKind::MutRef)
+        }
+        _ => false,
+    };
+    if is_write {
+        Access::Write
+    } else {
+        Access::Read
+    }
+}
+
+/// Checks whether the guard replacing `field_expr` is dereferenced
+/// automatically, as the receiver of a field access, method call or index.
+fn is_autoderefed(field_expr: &ast::FieldExpr) -> bool {
+    let place = field_expr.syntax();
+    match place.parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::FieldExpr(it)) => is_same(it.expr(), place),
+        Some(ast::Expr::MethodCallExpr(it)) => is_same(it.expr(), place),
+        Some(ast::Expr::IndexExpr(it)) => is_same(it.base(), place),
+        _ => false,
+    }
+}
+
+fn is_same(expr: Option<ast::Expr>, node: &SyntaxNode) -> bool {
+    expr.map_or(false, |it| it.syntax() == node)
+}
+
+fn is_lock(type_ref: &ast::TypeRef) -> bool {
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path(),
+        _ => None,
+    };
+    path.and_then(|it| it.segment())
+        .and_then(|it| it.name_ref())
+        .map_or(false, |it| it.text() == "Mutex" || it.text() == "RwLock")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const STD: &str = r"
+//- /libstd.rs crate:std
+pub mod sync {
+    pub struct Mutex<T>(T);
+    pub struct RwLock<T>(T);
+}
+";
+
+    fn with_std(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:std\n{}{}", ra_fixture.trim_end(), STD)
+    }
+
+    fn wrap_field_in_mutex(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+        wrap_field(acc, ctx, &[Lock::Mutex])
+    }
+
+    #[test]
+    fn wrap_field_in_rw_lock() {
+        check_assist(
+            wrap_field_in_lock,
+            &with_std(
+                r"
+use std::sync::RwLock;
+struct Stats { hits: Counter<|>, total: u64 }
+struct Counter { count: u64 }
+impl Counter {
+    fn get(&self) -> u64 { self.count }
+    fn bump(&mut self) { self.count += 1; }
+}
+fn make(hits: Counter) -> Stats { Stats { hits, total: 0 } }
+fn make_empty() -> Stats { Stats { hits: Counter { count: 0 }, total: 0 } }
+fn record(stats: &mut Stats) -> u64 {
+    stats.hits.bump();
+    stats.hits.count = 0;
+    let counter = &mut stats.hits;
+    let hits = &stats.hits;
+    stats.hits.get() + stats.hits.count
+}",
+            ),
+            r"
+use std::sync::RwLock;
+struct Stats { hits: RwLock<Counter>, total: u64 }
+struct Counter { count: u64 }
+impl Counter {
+    fn get(&self) -> u64 { self.count }
+    fn bump(&mut self) { self.count += 1; }
+}
+fn make(hits: Counter) -> Stats { Stats { hits: RwLock::new(hits), total: 0 } }
+fn make_empty() -> Stats { Stats { hits: RwLock::new(Counter { count: 0 }), total: 0 } }
+fn record(stats: &mut Stats) -> u64 {
+    stats.hits.write().unwrap().bump();
+    stats.hits.write().unwrap().count = 0;
+    let counter = &mut *stats.hits.write().unwrap();
+    let hits = &*stats.hits.read().unwrap();
+    stats.hits.read().unwrap().get() + stats.hits.read().unwrap().count
+}
+",
+        );
+    }
+
+    #[test]
+    fn wrap_field_in_mutex_with_import() {
+        check_assist(
+            wrap_field_in_mutex,
+            &with_std(
+                r"
+struct Stats { total: <|>u64 }
+fn add(stats: &mut Stats, n: u64) -> u64 {
+    stats.total += n;
+    stats.total
+}",
+            ),
+            r"
+use std::sync::Mutex;
+
+struct Stats { total: Mutex<u64> }
+fn add(stats: &mut Stats, n: u64) -> u64 {
+    *stats.total.lock().unwrap() += n;
+    *stats.total.lock().unwrap()
+}
+",
+        );
+    }
+
+    #[test]
+    fn wrap_field_in_lock_not_applicable() {
+        check_assist_not_applicable(
+            wrap_field_in_lock,
+            &with_std("use std::sync::Mutex;\nstruct Stats { total: Mutex<u64><|> }"),
+        );
+        check_assist_not_applicable(wrap_field_in_lock, "struct Stats { total: u64<|> }");
+    }
+
+    #[test]
+    fn wrap_field_in_lock_target() {
+        check_assist_target(
+            wrap_field_in_lock,
+            &with_std("struct Stats { total: u64<|>, count: u32 }"),
+            "total: u64",
+        );
+    }
+}
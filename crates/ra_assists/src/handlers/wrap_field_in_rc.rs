@@ -0,0 +1,449 @@
+use hir::{Adt, ModuleDef, ScopeDef};
+use ra_db::FileId;
+use ra_ide_db::search::ReferenceKind;
+use ra_syntax::{
+    ast::{self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner},
+    TextRange,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    utils::{field_usages, insert_use_statement, FamousDefs, FileEdits},
+    AssistContext, AssistId, Assists,
+};
+
+// Assist: wrap_field_in_rc
+//
+// Wraps the type of a field in `Rc`, to share the value instead of owning it.
+// Values moved out of the field are cloned, and a manual `Clone` impl is
+// updated to use `Rc::clone`.
+//
+// ```
+// # //- /main.rs crate:main deps:std
+// struct Config { name: String<|> }
+// fn make(name: String) -> Config { Config { name } }
+// # //- /libstd.rs crate:std
+// # pub mod rc { pub struct Rc<T>(T); }
+// ```
+// ->
+// ```
+// use std::rc::Rc;
+//
+// struct Config { name: Rc<String> }
+// fn make(name: String) -> Config { Config { name: Rc::new(name) } }
+// ```
+pub(crate) fn wrap_field_in_rc(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let field_name = field.name()?;
+    let type_ref = field.ascribed_type()?;
+    if rc_inner_type(&type_ref).is_some() {
+        return None;
+    }
+    let strukt = field.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let field_def = ctx.sema.to_def(&field)?;
+    let module = ctx.sema.scope(strukt.syntax()).module()?;
+    let rc = FamousDefs(&ctx.sema, module.krate()).std_rc_Rc()?;
+    let rc_in_scope = {
+        let mut res = false;
+        ctx.sema.scope(strukt.syntax()).process_all_names(&mut |name, def| {
+            if let ScopeDef::ModuleDef(ModuleDef::Adt(Adt::Struct(it))) = def {
+                res |= it == rc && name.to_string() == "Rc";
+            }
+        });
+        res
+    };
+    let rc_path = if rc_in_scope {
+        None
+    } else {
+        Some(module.find_use_path(ctx.db, ModuleDef::Adt(rc.into()))?)
+    };
+
+    let target = field.syntax().text_range();
+    acc.add(
+        AssistId("wrap_field_in_rc"),
+        format!("Wrap `{}` in `Rc`", field_name),
+        target,
+        |builder| {
+            let usages = field_usages(ctx, field_def);
+            let clone_calls = clone_impl_calls(&usages);
+            let mut edits = FileEdits::default();
+            for (file_id, kind, name_ref) in usages {
+                if kind == ReferenceKind::FieldShorthandForField {
+                    let range = name_ref.syntax().text_range();
+                    edits.add(file_id, range, format!("{0}: Rc::new({0})", name_ref));
+                    continue;
+                }
+                match FieldUsage::classify(&name_ref) {
+                    Some(FieldUsage::RecordLiteral(expr)) => {
+                        let range = expr.syntax().text_range();
+                        if !clone_calls.contains(&(file_id, range)) {
+                            edits.add(file_id, range, format!("Rc::new({})", expr));
+                        }
+                    }
+                    Some(FieldUsage::Clone { call, field_expr }) if in_clone_impl(&call) => {
+                        let range = call.syntax().text_range();
+                        edits.add(file_id, range, format!("Rc::clone(&{})", field_expr));
+                    }
+                    Some(FieldUsage::Moved(field_expr)) => {
+                        let offset = field_expr.syntax().text_range().end();
+                        edits.add(file_id, TextRange::empty(offset), ".clone()");
+                    }
+                    _ => (),
+                }
+            }
+            edits.add(
+                ctx.frange.file_id,
+                type_ref.syntax().text_range(),
+                format!("Rc<{}>", type_ref),
+            );
+            edits.apply(builder, ctx.frange.file_id);
+            if let Some(rc_path) = &rc_path {
+                insert_use_statement(strukt.syntax(), rc_path, ctx, builder.text_edit_builder());
+            }
+        },
+    )
+}
+
+// Assist: unwrap_rc_field
+//
+// Removes the `Rc` around the type of a field, if the field is never shared.
+//
+// ```
+// use std::rc::Rc;
+// struct Config { name: Rc<String><|> }
+// fn make(name: String) -> Config { Config { name: Rc::new(name) } }
+// ```
+// ->
+// ```
+// use std::rc::Rc;
+// struct Config { name: String }
+// fn make(name: String) -> Config { Config { name: name } }
+// ```
+pub(crate) fn unwrap_rc_field(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let field_name = field.name()?;
+    let type_ref = field.ascribed_type()?;
+    let inner_type = rc_inner_type(&type_ref)?;
+    field.syntax().ancestors().find_map(ast::StructDef::cast)?;
+    let field_def = ctx.sema.to_def(&field)?;
+
+    let usages = field_usages(ctx, field_def);
+    let clone_calls = clone_impl_calls(&usages);
+    let mut edits = FileEdits::default();
+    for (file_id, kind, name_ref) in usages {
+        // A shorthand initializes the field with an `Rc` from elsewhere.
+        if kind == ReferenceKind::FieldShorthandForField {
+            return None;
+        }
+        match FieldUsage::classify(&name_ref) {
+            Some(FieldUsage::RecordLiteral(expr)) => {
+                let range = expr.syntax().text_range();
+                if !clone_calls.contains(&(file_id, range)) {
+                    let arg = rc_new_arg(&expr)?;
+                    edits.add(file_id, range, arg.to_string());
+                }
+            }
+            Some(FieldUsage::Clone { call, field_expr }) => {
+                // Cloning the `Rc` outside of `Clone` creates a second owner.
+                if !in_clone_impl(&call) {
+                    return None;
+                }
+                if let ast::Expr::CallExpr(_) = call {
+                    let range = call.syntax().text_range();
+                    edits.add(file_id, range, format!("{}.clone()", field_expr));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let target = field.syntax().text_range();
+    acc.add(
+        AssistId("unwrap_rc_field"),
+        format!("Remove `Rc` from `{}`", field_name),
+        target,
+        |builder| {
+            edits.add(ctx.frange.file_id, type_ref.syntax().text_range(), inner_type.to_string());
+            edits.apply(builder, ctx.frange.file_id);
+        },
+    )
+}
+
+/// The clones of the field inside of `Clone` impls, which are rewritten
+/// instead of the record literals containing them.
+fn clone_impl_calls(
+    usages: &[(FileId, ReferenceKind, ast::NameRef)],
+) -> FxHashSet<(FileId, TextRange)> {
+    usages
+        .iter()
+        .filter_map(|(file_id, _, name_ref)| match FieldUsage::classify(name_ref)? {
+            FieldUsage::Clone { call, .. } if in_clone_impl(&call) => {
+                Some((*file_id, call.syntax().text_range()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The ways of using a field, which are relevant for changing its ownership.
+enum FieldUsage {
+    /// `S { field: expr }`
+    RecordLiteral(ast::Expr),
+    /// `s.field.clone()` or `Rc::clone(&s.field)`
+    Clone { call: ast::Expr, field_expr: ast::FieldExpr },
+    /// `s.field` passed by value to a binding, function or record literal.
+    Moved(ast::FieldExpr),
+}
+
+impl FieldUsage {
+    fn classify(name_ref: &ast::NameRef) -> Option<FieldUsage> {
+        let parent = name_ref.syntax().parent()?;
+        if let Some(record_field) = ast::RecordField::cast(parent.clone()) {
+            return record_field.expr().map(FieldUsage::RecordLiteral);
+        }
+        let field_expr = ast::FieldExpr::cast(parent)?;
+        let parent = field_expr.syntax().parent()?;
+        if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+            let is_clone = method_call.name_ref().map_or(false, |it| it.text() == "clone")
+                && method_call.arg_list().map_or(false, |it| it.args().next().is_none());
+            if is_clone {
+                return Some(FieldUsage::Clone { call: method_call.into(), field_expr });
+            }
+            return None;
+        }
+        if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+            let call = ref_expr
+                .syntax()
+                .parent()
+                .and_then(ast::ArgList::cast)?
+                .syntax()
+                .parent()
+                .and_then(ast::CallExpr::cast)?;
+            if is_rc_path_call(&call, "clone") {
+                return Some(FieldUsage::Clone { call: call.into(), field_expr });
+            }
+            return None;
+        }
+        let is_moved = ast::LetStmt::cast(parent.clone()).is_some()
+            || ast::ArgList::cast(parent.clone()).is_some()
+            || ast::RecordField::cast(parent).is_some();
+        if is_moved {
+            Some(FieldUsage::Moved(field_expr))
+        } else {
+            None
+        }
+    }
+}
+
+fn rc_inner_type(type_ref: &ast::TypeRef) -> Option<ast::TypeRef> {
+    let path = match type_ref {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    let segment = path.segment()?;
+    if segment.name_ref()?.text() != "Rc" {
+        return None;
+    }
+    let mut args = segment.type_arg_list()?.type_args();
+    let inner = args.next()?.type_ref()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(inner)
+}
+
+/// Returns `expr` in `Rc::new(expr)`.
+fn rc_new_arg(expr: &ast::Expr) -> Option<ast::Expr> {
+    let call = match expr {
+        ast::Expr::CallExpr(it) => it,
+        _ => return None,
+    };
+    if !is_rc_path_call(call, "new") {
+        return None;
+    }
+    let mut args = call.arg_list()?.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(arg)
+}
+
+fn is_rc_path_call(call: &ast::CallExpr, method: &str) -> bool {
+    let path = match call.expr() {
+        Some(ast::Expr::PathExpr(it)) => it.path(),
+        _ => None,
+    };
+    let path = match path {
+        Some(it) => it,
+        None => return false,
+    };
+    let is_method =
+        path.segment().and_then(|it| it.name_ref()).map_or(false, |it| it.text() == method);
+    let is_rc = path
+        .qualifier()
+        .and_then(|it| it.segment())
+        .and_then(|it| it.name_ref())
+        .map_or(false, |it| it.text() == "Rc");
+    is_method && is_rc
+}
+
+fn in_clone_impl(expr: &ast::Expr) -> bool {
+    expr.syntax().ancestors().filter_map(ast::ImplDef::cast).next().map_or(false, |impl_def| {
+        match impl_def.target_trait() {
+            Some(ast::TypeRef::PathType(it)) => it
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map_or(false, |it| it.text() == "Clone"),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    const STD: &str = r"
+//- /libstd.rs crate:std
+pub mod rc {
+    pub struct Rc<T>(T);
+    impl<T> Rc<T> {
+        pub fn new(value: T) -> Rc<T> { Rc(value) }
+    }
+}
+";
+
+    fn with_std(ra_fixture: &str) -> String {
+        format!("//- /main.rs crate:main deps:std\n{}{}", ra_fixture.trim_end(), STD)
+    }
+
+    #[test]
+    fn wrap_field_and_update_usages() {
+        check_assist(
+            wrap_field_in_rc,
+            &with_std(
+                r"
+struct Config { name: String<|>, verbose: bool }
+fn make(name: String) -> Config { Config { name, verbose: false } }
+fn make_default() -> Config { Config { name: String::new(), verbose: false } }
+fn print(config: &Config) {
+    let name = config.name;
+    println(config.name);
+    config.name.len();
+}",
+            ),
+            r"
+use std::rc::Rc;
+
+struct Config { name: Rc<String>, verbose: bool }
+fn make(name: String) -> Config { Config { name: Rc::new(name), verbose: false } }
+fn make_default() -> Config { Config { name: Rc::new(String::new()), verbose: false } }
+fn print(config: &Config) {
+    let name = config.name.clone();
+    println(config.name.clone());
+    config.name.len();
+}
+",
+        );
+    }
+
+    #[test]
+    fn wrap_field_updates_clone_impl() {
+        check_assist(
+            wrap_field_in_rc,
+            &with_std(
+                r"
+use std::rc::Rc;
+struct Config { name: <|>String }
+impl Clone for Config {
+    fn clone(&self) -> Config {
+        Config { name: self.name.clone() }
+    }
+}",
+            ),
+            r"
+use std::rc::Rc;
+struct Config { name: Rc<String> }
+impl Clone for Config {
+    fn clone(&self) -> Config {
+        Config { name: Rc::clone(&self.name) }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn wrap_field_not_applicable() {
+        check_assist_not_applicable(
+            wrap_field_in_rc,
+            &with_std("use std::rc::Rc;\nstruct Config { name: Rc<String><|> }"),
+        );
+        check_assist_not_applicable(wrap_field_in_rc, "struct Config { name: String<|> }");
+    }
+
+    #[test]
+    fn unwrap_single_owner() {
+        check_assist(
+            unwrap_rc_field,
+            r"
+struct Config { name: Rc<<|>String> }
+impl Clone for Config {
+    fn clone(&self) -> Config {
+        Config { name: Rc::clone(&self.name) }
+    }
+}
+fn make(name: String) -> Config { Config { name: Rc::new(name) } }
+fn len(config: &Config) -> usize { config.name.len() }
+",
+            r"
+struct Config { name: String }
+impl Clone for Config {
+    fn clone(&self) -> Config {
+        Config { name: self.name.clone() }
+    }
+}
+fn make(name: String) -> Config { Config { name: name } }
+fn len(config: &Config) -> usize { config.name.len() }
+",
+        );
+    }
+
+    #[test]
+    fn unwrap_not_applicable_for_shared_values() {
+        check_assist_not_applicable(
+            unwrap_rc_field,
+            r"
+struct Config { name: Rc<<|>String> }
+fn share(config: &Config) -> Rc<String> { Rc::clone(&config.name) }
+",
+        );
+        check_assist_not_applicable(
+            unwrap_rc_field,
+            r"
+struct Config { name: Rc<<|>String> }
+fn make(name: Rc<String>) -> Config { Config { name } }
+",
+        );
+        check_assist_not_applicable(
+            unwrap_rc_field,
+            r"
+struct Config { name: Rc<<|>String> }
+fn make(config: &Config) -> Config { Config { name: config.name.clone() } }
+",
+        );
+    }
+
+    #[test]
+    fn unwrap_rc_field_target() {
+        check_assist_target(
+            unwrap_rc_field,
+            "struct Config { name: Rc<<|>String> }",
+            "name: Rc<String>",
+        );
+    }
+}
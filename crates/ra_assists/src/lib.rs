@@ -101,92 +101,239 @@ mod handlers {
 
     pub(crate) type Handler = fn(&mut Assists, &AssistContext) -> Option<()>;
 
+    mod add_arbitrary_impl;
+    mod add_arbitrary_take_rest;
+    mod add_as_ref_impl;
     mod add_custom_impl;
     mod add_derive;
+    mod add_derive_copy;
+    mod add_derive_debug;
     mod add_explicit_type;
+    mod add_from_impl_for_error;
     mod add_from_impl_for_enum;
     mod add_function;
     mod add_impl;
+    mod add_impl_fn_return_type;
+    mod add_io_delegation;
     mod add_missing_impl_members;
     mod add_new;
+    mod add_reexports;
+    mod add_self_sized_bound;
+    mod add_size_hint;
+    mod add_track_caller;
     mod add_turbo_fish;
+    mod add_unsafe_send_sync_impl;
     mod apply_demorgan;
     mod auto_import;
     mod change_return_type_to_result;
     mod change_visibility;
+    mod convert_async_fn_to_poll_fn;
+    mod convert_box_dyn_error_to_enum;
+    mod convert_enum_to_bitflags;
+    mod convert_import_path;
+    mod convert_join;
+    mod convert_map_entry;
+    mod convert_static_to_lazy;
+    mod convert_string_field;
+    mod convert_string_param_to_cow;
+    mod convert_string_vec_arg;
+    mod convert_struct_to_enum;
+    mod document_iterator_chain;
     mod early_return;
+    mod extract_data_struct;
+    mod extract_predicate_function;
     mod extract_struct_from_enum_variant;
     mod fill_match_arms;
     mod fix_visibility;
+    mod flatten_result;
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
+    mod generate_accessors;
+    mod generate_delegate_method;
+    mod generate_delegate_methods;
+    mod generate_deserialize_impl;
+    mod generate_discriminant_constants;
+    mod generate_display_from_debug;
+    mod generate_enum_str_conversions;
+    mod generate_extend_impl;
+    mod generate_ffi_wrapper;
+    mod generate_from_str_impl;
+    mod generate_index_impl;
+    mod generate_mock;
+    mod generate_parameterized_test;
+    mod generate_partial_eq_ignoring_fields;
+    mod generate_proc_macro;
+    mod generate_property_test;
+    mod generate_redacted_debug_impl;
+    mod generate_sealed_trait;
+    mod generate_test_matrix;
+    mod inline_attr;
     mod inline_local_variable;
+    mod inline_module;
     mod introduce_named_lifetime;
+    mod introduce_named_return;
     mod introduce_variable;
     mod invert_if;
+    mod make_fn_const;
+    mod merge_impl_blocks;
     mod merge_imports;
     mod merge_match_arms;
     mod move_bounds;
     mod move_guard;
+    mod move_use_to_inner_module;
     mod raw_string;
     mod remove_dbg;
     mod remove_mut;
+    mod remove_redundant_wildcard_arm;
     mod reorder_fields;
+    mod reorder_impl_methods;
+    mod replace_arith_op;
+    mod replace_conversion_with_cast;
+    mod replace_if_let_chain_with_match;
     mod replace_if_let_with_match;
+    mod replace_let_else_with_match;
     mod replace_let_with_if_let;
+    mod replace_lock_with_get_mut;
+    mod replace_match_with_combinator;
     mod replace_qualified_name_with_use;
+    mod replace_string_concat_with_write;
+    mod replace_unwrap_or_else_with_unwrap_or;
     mod replace_unwrap_with_match;
     mod split_import;
+    mod toggle_cfg;
     mod unwrap_block;
+    mod unwrap_parentheses;
+    mod wrap_field_in_lock;
+    mod wrap_field_in_rc;
 
     pub(crate) fn all() -> &'static [Handler] {
         &[
             // These are alphabetic for the foolish consistency
+            add_arbitrary_impl::add_arbitrary_impl,
+            add_arbitrary_take_rest::add_arbitrary_take_rest,
+            add_as_ref_impl::add_as_ref_impl,
+            add_as_ref_impl::add_reflexive_as_ref_impl,
             add_custom_impl::add_custom_impl,
             add_derive::add_derive,
+            add_derive_copy::add_derive_copy,
+            add_derive_debug::add_derive_debug,
             add_explicit_type::add_explicit_type,
+            add_from_impl_for_error::add_from_impl_for_error,
             add_from_impl_for_enum::add_from_impl_for_enum,
+            add_from_impl_for_enum::add_from_impls_for_enum,
             add_function::add_function,
             add_impl::add_impl,
+            add_impl_fn_return_type::add_impl_fn_return_type,
+            add_io_delegation::add_io_delegation,
             add_new::add_new,
+            add_reexports::add_reexports,
+            add_self_sized_bound::add_self_sized_bound,
+            add_size_hint::add_size_hint,
+            add_track_caller::add_track_caller,
             add_turbo_fish::add_turbo_fish,
+            add_unsafe_send_sync_impl::add_unsafe_send_sync_impl,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             change_return_type_to_result::change_return_type_to_result,
             change_visibility::change_visibility,
+            convert_async_fn_to_poll_fn::convert_async_fn_to_poll_fn,
+            convert_box_dyn_error_to_enum::convert_box_dyn_error_to_enum,
+            convert_enum_to_bitflags::convert_enum_to_bitflags,
+            convert_import_path::convert_to_relative_import,
+            convert_import_path::convert_to_crate_import,
+            convert_join::convert_join_to_sequential_awaits,
+            convert_join::convert_awaits_to_join,
+            convert_map_entry::convert_contains_key_to_entry,
+            convert_map_entry::convert_entry_to_contains_key,
+            convert_static_to_lazy::convert_static_to_lazy,
+            convert_string_field::convert_string_field_to_shared,
+            convert_string_param_to_cow::convert_string_param_to_cow,
+            convert_string_vec_arg::convert_string_vec_arg,
+            convert_struct_to_enum::convert_struct_to_enum,
+            document_iterator_chain::document_iterator_chain,
             early_return::convert_to_guarded_return,
+            extract_data_struct::extract_data_struct,
+            extract_predicate_function::extract_predicate_function,
             extract_struct_from_enum_variant::extract_struct_from_enum_variant,
             fill_match_arms::fill_match_arms,
             fix_visibility::fix_visibility,
+            flatten_result::flatten_result,
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
+            generate_accessors::generate_accessors,
+            generate_delegate_method::generate_delegate_method,
+            generate_delegate_methods::generate_delegate_methods,
+            generate_deserialize_impl::generate_deserialize_impl,
+            generate_discriminant_constants::generate_discriminant_constants,
+            generate_display_from_debug::generate_display_from_debug,
+            generate_enum_str_conversions::generate_enum_str_conversions,
+            generate_extend_impl::generate_extend_impl,
+            generate_ffi_wrapper::generate_ffi_wrapper,
+            generate_from_str_impl::generate_from_str_impl,
+            generate_index_impl::generate_index_impl,
+            generate_mock::generate_mock,
+            generate_parameterized_test::generate_parameterized_test,
+            generate_partial_eq_ignoring_fields::generate_partial_eq_ignoring_fields,
+            generate_proc_macro::generate_proc_macro_attribute,
+            generate_proc_macro::generate_proc_macro_derive,
+            generate_property_test::generate_property_test,
+            generate_redacted_debug_impl::generate_redacted_debug_impl,
+            generate_sealed_trait::generate_sealed_trait,
+            generate_test_matrix::generate_test_matrix,
+            inline_attr::add_inline_attr,
+            inline_attr::remove_inline_attr,
             inline_local_variable::inline_local_variable,
+            inline_module::inline_module,
+            introduce_named_lifetime::add_explicit_lifetimes,
             introduce_named_lifetime::introduce_named_lifetime,
+            introduce_named_return::introduce_named_return,
             introduce_variable::introduce_variable,
             invert_if::invert_if,
+            make_fn_const::make_fn_const,
+            merge_impl_blocks::merge_impl_blocks,
             merge_imports::merge_imports,
             merge_match_arms::merge_match_arms,
             move_bounds::move_bounds_to_where_clause,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_use_to_inner_module::move_use_to_inner_module,
             raw_string::add_hash,
             raw_string::make_raw_string,
             raw_string::make_usual_string,
             raw_string::remove_hash,
             remove_dbg::remove_dbg,
             remove_mut::remove_mut,
+            remove_redundant_wildcard_arm::remove_redundant_wildcard_arm,
             reorder_fields::reorder_fields,
+            reorder_impl_methods::reorder_impl_methods,
+            replace_arith_op::replace_arith_with_wrapping,
+            replace_arith_op::replace_arith_with_saturating,
+            replace_conversion_with_cast::replace_conversion_with_cast,
+            replace_if_let_chain_with_match::replace_if_let_chain_with_match,
             replace_if_let_with_match::replace_if_let_with_match,
+            replace_let_else_with_match::replace_let_else_with_match,
+            replace_let_else_with_match::replace_match_with_let_else,
             replace_let_with_if_let::replace_let_with_if_let,
+            replace_lock_with_get_mut::replace_lock_with_get_mut,
+            replace_match_with_combinator::replace_match_with_combinator,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
+            replace_string_concat_with_write::replace_string_concat_with_write,
+            replace_unwrap_or_else_with_unwrap_or::replace_unwrap_or_else_with_unwrap_or,
             replace_unwrap_with_match::replace_unwrap_with_match,
             split_import::split_import,
+            toggle_cfg::add_negated_cfg,
+            toggle_cfg::toggle_cfg,
             unwrap_block::unwrap_block,
+            unwrap_parentheses::unwrap_parentheses,
+            wrap_field_in_lock::wrap_field_in_lock,
+            wrap_field_in_rc::unwrap_rc_field,
+            wrap_field_in_rc::wrap_field_in_rc,
             // These are manually sorted for better priorities
             add_missing_impl_members::add_missing_impl_members,
             add_missing_impl_members::add_missing_default_members,
+            add_missing_impl_members::add_missing_impl_functions,
             // Are you sure you want to add new assist here, and not to the
             // sorted list above?
         ]
@@ -10,7 +10,7 @@ use test_utils::{
     assert_eq_text, extract_offset, extract_range, extract_range_or_offset, RangeOrOffset,
 };
 
-use crate::{handlers::Handler, Assist, AssistConfig, AssistContext, Assists};
+use crate::{handlers::Handler, utils::FamousDefs, Assist, AssistConfig, AssistContext, Assists};
 
 pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
     let (mut db, file_id) = RootDatabase::with_single_file(text);
@@ -24,6 +24,29 @@ pub(crate) fn check_assist(assist: Handler, ra_fixture_before: &str, ra_fixture_
     check(assist, ra_fixture_before, ExpectedResult::After(ra_fixture_after));
 }
 
+/// Makes `ra_fixture` the `main` crate, which depends on the `core` crate of
+/// `FamousDefs::FIXTURE`.
+pub(crate) fn with_core(ra_fixture: &str) -> String {
+    format!("//- /main.rs crate:main deps:core\n{}{}", ra_fixture.trim_end(), FamousDefs::FIXTURE)
+}
+
+pub(crate) fn check_assist_with_core(
+    assist: Handler,
+    ra_fixture_before: &str,
+    ra_fixture_after: &str,
+) {
+    check_assist(assist, &with_core(ra_fixture_before), ra_fixture_after);
+}
+
+pub(crate) fn check_assist_with_config(
+    assist: Handler,
+    config: AssistConfig,
+    ra_fixture_before: &str,
+    ra_fixture_after: &str,
+) {
+    check_with_config(assist, config, ra_fixture_before, ExpectedResult::After(ra_fixture_after));
+}
+
 // FIXME: instead of having a separate function here, maybe use
 // `extract_ranges` and mark the target as `<target> </target>` in the
 // fixuture?
@@ -35,19 +58,39 @@ pub(crate) fn check_assist_not_applicable(assist: Handler, ra_fixture: &str) {
     check(assist, ra_fixture, ExpectedResult::NotApplicable);
 }
 
+pub(crate) fn check_assist_not_applicable_with_config(
+    assist: Handler,
+    config: AssistConfig,
+    ra_fixture: &str,
+) {
+    check_with_config(assist, config, ra_fixture, ExpectedResult::NotApplicable);
+}
+
 fn check_doc_test(assist_id: &str, before: &str, after: &str) {
-    let (selection, before) = extract_range_or_offset(before);
-    let (db, file_id) = crate::tests::with_single_file(&before);
+    let (db, file_id, selection, before, after) = if before.contains("//-") {
+        let (mut db, position) = RootDatabase::with_position(before);
+        db.set_local_roots(Arc::new(vec![db.file_source_root(position.file_id)]));
+        let before = db.file_text(position.file_id).as_ref().to_owned();
+        // Fixture files start right after the `//-` line.
+        let after = after.trim_start_matches('\n');
+        (db, position.file_id, RangeOrOffset::Offset(position.offset), before, after)
+    } else {
+        let (selection, before) = extract_range_or_offset(before);
+        let (db, file_id) = crate::tests::with_single_file(&before);
+        (db, file_id, selection, before, after)
+    };
     let frange = FileRange { file_id, range: selection.into() };
+    // Opt-in assists are documented as well.
+    let config = AssistConfig { inline_hints: true, ..AssistConfig::default() };
 
-    let mut assist = Assist::resolved(&db, &AssistConfig::default(), frange)
+    let mut assist = Assist::resolved(&db, &config, frange)
         .into_iter()
         .find(|assist| assist.assist.id.0 == assist_id)
         .unwrap_or_else(|| {
             panic!(
                 "\n\nAssist is not applicable: {}\nAvailable assists: {}",
                 assist_id,
-                Assist::resolved(&db, &AssistConfig::default(), frange)
+                Assist::resolved(&db, &config, frange)
                     .into_iter()
                     .map(|assist| assist.assist.id.0)
                     .collect::<Vec<_>>()
@@ -71,6 +114,15 @@ enum ExpectedResult<'a> {
 }
 
 fn check(handler: Handler, before: &str, expected: ExpectedResult) {
+    check_with_config(handler, AssistConfig::default(), before, expected)
+}
+
+fn check_with_config(
+    handler: Handler,
+    config: AssistConfig,
+    before: &str,
+    expected: ExpectedResult,
+) {
     let (text_without_caret, file_with_caret_id, range_or_offset, db) = if before.contains("//-") {
         let (mut db, position) = RootDatabase::with_position(before);
         db.set_local_roots(Arc::new(vec![db.file_source_root(position.file_id)]));
@@ -89,7 +141,6 @@ fn check(handler: Handler, before: &str, expected: ExpectedResult) {
     let frange = FileRange { file_id: file_with_caret_id, range: range_or_offset.into() };
 
     let sema = Semantics::new(&db);
-    let config = AssistConfig::default();
     let ctx = AssistContext::new(sema, &config, frange);
     let mut acc = Assists::new_resolved(&ctx);
     handler(&mut acc, &ctx);
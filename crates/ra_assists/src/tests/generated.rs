@@ -2,6 +2,89 @@
 
 use super::check_doc_test;
 
+#[test]
+fn doctest_add_arbitrary_impl() {
+    check_doc_test(
+        "add_arbitrary_impl",
+        r#####"
+//- /main.rs crate:main deps:arbitrary
+struct Point {
+    x: u32,
+    y: u32,<|>
+}
+//- /lib.rs crate:arbitrary
+pub trait Arbitrary<'a> {}
+"#####,
+        r#####"
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for Point {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Point { x: ${1:u.arbitrary()?}, y: ${2:u.arbitrary()?} })
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_arbitrary_take_rest() {
+    check_doc_test(
+        "add_arbitrary_take_rest",
+        r#####"
+//- /main.rs crate:main deps:arbitrary
+struct Packet { kind: u8, payload: Vec<u8> }
+impl<'a> arbitrary::Arbitrary<'a> for Packet {<|>
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Packet { kind: u.arbitrary()?, payload: u.arbitrary()? })
+    }
+}
+//- /lib.rs crate:arbitrary
+pub trait Arbitrary<'a> {}
+"#####,
+        r#####"
+struct Packet { kind: u8, payload: Vec<u8> }
+impl<'a> arbitrary::Arbitrary<'a> for Packet {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Packet { kind: u.arbitrary()?, payload: u.arbitrary()? })
+    }
+
+    fn arbitrary_take_rest(mut u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Packet { kind: u.arbitrary()?, payload: arbitrary::Arbitrary::arbitrary_take_rest(u)? })
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_as_ref_impl() {
+    check_doc_test(
+        "add_as_ref_impl",
+        r#####"
+struct Meters(<|>f64);
+"#####,
+        r#####"
+struct Meters(f64);
+
+impl AsRef<f64> for Meters {
+    fn as_ref(&self) -> &f64 {
+        &self.0
+    }
+}
+
+impl AsMut<f64> for Meters {
+    fn as_mut(&mut self) -> &mut f64 {
+        &mut self.0
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_custom_impl() {
     check_doc_test(
@@ -41,6 +124,67 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_add_derive_copy() {
+    check_doc_test(
+        "add_derive_copy",
+        r#####"
+//- /main.rs crate:main deps:core
+#[derive(Debug)]
+struct Point {
+    x: u32,
+    y: u32,<|>
+}
+//- /libcore.rs crate:core
+pub mod marker { #[lang = "copy"] pub trait Copy {} impl Copy for u32 {} }
+"#####,
+        r#####"
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_derive_debug() {
+    check_doc_test(
+        "add_derive_debug",
+        r#####"
+struct Point {
+    x: u32,
+    y: u32,<|>
+}
+"#####,
+        r#####"
+#[derive(Debug)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_explicit_lifetimes() {
+    check_doc_test(
+        "add_explicit_lifetimes",
+        r#####"
+fn <|>longest(x: &str, y: &str) -> &str {
+    if x.len() > y.len() { x } else { y }
+}
+"#####,
+        r#####"
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check_doc_test(
@@ -77,6 +221,73 @@ impl From<u32> for A {
     )
 }
 
+#[test]
+fn doctest_add_from_impl_for_error() {
+    check_doc_test(
+        "add_from_impl_for_error",
+        r#####"
+//- /main.rs crate:main deps:core
+struct ParseError;
+struct ConfigError;
+
+fn parse(s: &str) -> Result<u32, ParseError> { Ok(0) }
+
+fn load(s: &str) -> Result<u32, ConfigError> {
+    parse(s)<|>?;
+    Ok(0)
+}
+//- /libcore.rs crate:core
+pub mod convert { pub trait From<T> { fn from(T) -> Self; } }
+pub mod result { pub enum Result<T, E> { Ok(T), Err(E) } }
+pub mod prelude { pub use crate::{convert::From, result::Result::{self, *}}; }
+#[prelude_import]
+pub use prelude::*;
+"#####,
+        r#####"
+struct ParseError;
+struct ConfigError;
+
+fn parse(s: &str) -> Result<u32, ParseError> { Ok(0) }
+
+fn load(s: &str) -> Result<u32, ConfigError> {
+    parse(s)?;
+    Ok(0)
+}
+
+impl From<ParseError> for ConfigError {
+    fn from(err: ParseError) -> Self {
+        ${0:todo!()}
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_from_impls_for_enum() {
+    check_doc_test(
+        "add_from_impls_for_enum",
+        r#####"
+enum <|>Error { Io(io::Error), Parse(ParseError), Other }
+"#####,
+        r#####"
+enum Error { Io(io::Error), Parse(ParseError), Other }
+
+impl From<io::Error> for Error {
+    fn from(v: io::Error) -> Self {
+        Error::Io(v)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(v: ParseError) -> Self {
+        Error::Parse(v)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_function() {
     check_doc_test(
@@ -176,6 +387,58 @@ impl Trait for () {
     )
 }
 
+#[test]
+fn doctest_add_impl_fn_return_type() {
+    check_doc_test(
+        "add_impl_fn_return_type",
+        r#####"
+fn make_adder<|>(x: i32) {
+    move |y| x + y
+}
+"#####,
+        r#####"
+fn make_adder(x: i32) -> impl Fn(i32) -> i32 {
+    move |y| x + y
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_impl_missing_functions() {
+    check_doc_test(
+        "add_impl_missing_functions",
+        r#####"
+trait Trait {
+    Type X;
+    fn foo(&self);
+    fn bar(&self) {}
+}
+
+impl Trait for () {<|>
+
+}
+"#####,
+        r#####"
+trait Trait {
+    Type X;
+    fn foo(&self);
+    fn bar(&self) {}
+}
+
+impl Trait for () {
+    fn foo(&self) {
+        ${0:todo!()}
+    }
+    fn bar(&self) {
+        todo!()
+    }
+
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_impl_missing_members() {
     check_doc_test(
@@ -208,6 +471,67 @@ impl Trait<u32> for () {
     )
 }
 
+#[test]
+fn doctest_add_inline_attr() {
+    check_doc_test(
+        "add_inline_attr",
+        r#####"
+fn square(x: u32) -> u32<|> { x * x }
+"#####,
+        r#####"
+#[inline]
+fn square(x: u32) -> u32 { x * x }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_io_delegation() {
+    check_doc_test(
+        "add_io_delegation",
+        r#####"
+struct Counting<W: Write> {
+    inner: W,<|>
+    count: usize,
+}
+"#####,
+        r#####"
+struct Counting<W: Write> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: Write> std::io::Write for Counting<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_negated_cfg() {
+    check_doc_test(
+        "add_negated_cfg",
+        r#####"
+#[cfg(debug_<|>assertions)]
+fn log(message: &str) {}
+fn log(_message: &str) {}
+"#####,
+        r#####"
+#[cfg(debug_assertions)]
+fn log(message: &str) {}
+#[cfg(not(debug_assertions))]
+fn log(_message: &str) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_new() {
     check_doc_test(
@@ -231,98 +555,552 @@ impl<T: Clone> Ctx<T> {
 }
 
 #[test]
-fn doctest_add_turbo_fish() {
+fn doctest_add_reexports() {
     check_doc_test(
-        "add_turbo_fish",
+        "add_reexports",
         r#####"
-fn make<T>() -> T { todo!() }
-fn main() {
-    let x = make<|>();
+mod <|>parser {
+    pub struct Parser;
+    pub fn parse() {}
+    fn helper() {}
 }
 "#####,
         r#####"
-fn make<T>() -> T { todo!() }
-fn main() {
-    let x = make::<${0:_}>();
+mod parser {
+    pub struct Parser;
+    pub fn parse() {}
+    fn helper() {}
 }
+pub use self::parser::{parse, Parser};
 "#####,
     )
 }
 
 #[test]
-fn doctest_apply_demorgan() {
+fn doctest_add_reflexive_as_ref_impl() {
     check_doc_test(
-        "apply_demorgan",
+        "add_reflexive_as_ref_impl",
         r#####"
-fn main() {
-    if x != 4 ||<|> !y {}
-}
+struct Meters(<|>f64);
 "#####,
         r#####"
-fn main() {
-    if !(x == 4 && y) {}
+struct Meters(f64);
+
+impl AsRef<Meters> for Meters {
+    fn as_ref(&self) -> &Meters {
+        self
+    }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_auto_import() {
+fn doctest_add_self_sized_bound() {
     check_doc_test(
-        "auto_import",
+        "add_self_sized_bound",
         r#####"
-fn main() {
-    let map = HashMap<|>::new();
+trait Shape {
+    fn area(&self) -> f64;
+    fn into_<|>boxed(self) -> Box<dyn Shape>;
 }
-pub mod std { pub mod collections { pub struct HashMap { } } }
 "#####,
         r#####"
-use std::collections::HashMap;
-
-fn main() {
-    let map = HashMap::new();
+trait Shape {
+    fn area(&self) -> f64;
+    fn into_boxed(self) -> Box<dyn Shape> where Self: Sized;
 }
-pub mod std { pub mod collections { pub struct HashMap { } } }
 "#####,
     )
 }
 
 #[test]
-fn doctest_change_return_type_to_result() {
+fn doctest_add_size_hint() {
     check_doc_test(
-        "change_return_type_to_result",
+        "add_size_hint",
         r#####"
-fn foo() -> i32<|> { 42i32 }
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+    fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+}
+struct Iter<'a> { items: &'a [u32] }
+impl<'a> Iterator for Iter<'a> {<|>
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
 "#####,
         r#####"
-fn foo() -> Result<i32, ${0:_}> { Ok(42i32) }
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+    fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }
+}
+struct Iter<'a> { items: &'a [u32] }
+impl<'a> Iterator for Iter<'a> {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.items.len();
+        (len, Some(len))
+    }
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_change_visibility() {
+fn doctest_add_track_caller() {
     check_doc_test(
-        "change_visibility",
+        "add_track_caller",
         r#####"
-<|>fn frobnicate() {}
+fn first(xs: &[u32]) -> u32 {
+    *xs.first().unwrap()<|>
+}
 "#####,
         r#####"
-pub(crate) fn frobnicate() {}
+#[track_caller]
+fn first(xs: &[u32]) -> u32 {
+    *xs.first().unwrap()
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_convert_to_guarded_return() {
+fn doctest_add_turbo_fish() {
     check_doc_test(
-        "convert_to_guarded_return",
+        "add_turbo_fish",
         r#####"
+fn make<T>() -> T { todo!() }
 fn main() {
-    <|>if cond {
-        foo();
-        bar();
-    }
+    let x = make<|>();
+}
+"#####,
+        r#####"
+fn make<T>() -> T { todo!() }
+fn main() {
+    let x = make::<${0:_}>();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_unsafe_send_sync_impl() {
+    check_doc_test(
+        "add_unsafe_send_sync_impl",
+        r#####"
+struct Buffer {
+    ptr: *mut u8,<|>
+}
+"#####,
+        r#####"
+struct Buffer {
+    ptr: *mut u8,
+}
+
+// SAFETY: ${1:explain why `Buffer` can be sent to another thread}
+unsafe impl Send for Buffer {}
+
+// SAFETY: ${2:explain why `Buffer` can be shared between threads}
+unsafe impl Sync for Buffer {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_apply_demorgan() {
+    check_doc_test(
+        "apply_demorgan",
+        r#####"
+fn main() {
+    if x != 4 ||<|> !y {}
+}
+"#####,
+        r#####"
+fn main() {
+    if !(x == 4 && y) {}
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_auto_import() {
+    check_doc_test(
+        "auto_import",
+        r#####"
+fn main() {
+    let map = HashMap<|>::new();
+}
+pub mod std { pub mod collections { pub struct HashMap { } } }
+"#####,
+        r#####"
+use std::collections::HashMap;
+
+fn main() {
+    let map = HashMap::new();
+}
+pub mod std { pub mod collections { pub struct HashMap { } } }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_change_return_type_to_result() {
+    check_doc_test(
+        "change_return_type_to_result",
+        r#####"
+fn foo() -> i32<|> { 42i32 }
+"#####,
+        r#####"
+fn foo() -> Result<i32, ${0:_}> { Ok(42i32) }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_change_visibility() {
+    check_doc_test(
+        "change_visibility",
+        r#####"
+<|>fn frobnicate() {}
+"#####,
+        r#####"
+pub(crate) fn frobnicate() {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_async_fn_to_poll_fn() {
+    check_doc_test(
+        "convert_async_fn_to_poll_fn",
+        r#####"
+async fn <|>parse(input: String) -> Option<u32> {
+    if input.is_empty() {
+        return None;
+    }
+    input.parse().ok()
+}
+"#####,
+        r#####"
+fn parse(input: String) -> impl std::future::Future<Output = Option<u32>> {
+    std::future::poll_fn(move |_cx| {
+        use std::task::Poll;
+
+        if input.is_empty() {
+            return Poll::Ready(None);
+        }
+        Poll::Ready(input.parse().ok())
+    })
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_awaits_to_join() {
+    check_doc_test(
+        "convert_awaits_to_join",
+        r#####"
+async fn load() {
+    <|>let config = read_config().await;
+    fetch_user().await;<|>
+}
+"#####,
+        r#####"
+async fn load() {
+    let (config, _) = futures::join!(read_config(), fetch_user());
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_box_dyn_error_to_enum() {
+    check_doc_test(
+        "convert_box_dyn_error_to_enum",
+        r#####"
+mod io { pub struct Error; }
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::Ok;
+fn open(path: &str) -> Result<u32, io::Error> { Ok(0) }
+
+fn <|>load(path: &str) -> Result<u32, Box<dyn std::error::Error>> {
+    let fd = open(path)?;
+    Ok(fd)
+}
+"#####,
+        r#####"
+mod io { pub struct Error; }
+enum Result<T, E> { Ok(T), Err(E) }
+use Result::Ok;
+fn open(path: &str) -> Result<u32, io::Error> { Ok(0) }
+
+fn load(path: &str) -> Result<u32, LoadError> {
+    let fd = open(path)?;
+    Ok(fd)
+}
+
+#[derive(Debug)]
+enum LoadError {
+    Io(io::Error),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_contains_key_to_entry() {
+    check_doc_test(
+        "convert_contains_key_to_entry",
+        r#####"
+struct HashMap<K, V>(K, V);
+fn main() {
+    let mut map: HashMap<u32, u32>;
+    <|>if !map.contains_key(&1) {
+        map.insert(1, 92);
+    }
+}
+"#####,
+        r#####"
+struct HashMap<K, V>(K, V);
+fn main() {
+    let mut map: HashMap<u32, u32>;
+    map.entry(1).or_insert(92);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_entry_to_contains_key() {
+    check_doc_test(
+        "convert_entry_to_contains_key",
+        r#####"
+struct HashMap<K, V>(K, V);
+fn main() {
+    let mut map: HashMap<u32, u32>;
+    map.entry(1).<|>or_insert(92);
+}
+"#####,
+        r#####"
+struct HashMap<K, V>(K, V);
+fn main() {
+    let mut map: HashMap<u32, u32>;
+    if !map.contains_key(&1) {
+        map.insert(1, 92);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_enum_to_bitflags() {
+    check_doc_test(
+        "convert_enum_to_bitflags",
+        r#####"
+//- /main.rs crate:main deps:bitflags
+#[repr(u8)]
+enum Permissions<|> {
+    Read = 1,
+    Write = 2,
+    Execute = 4,
+}
+//- /lib.rs crate:bitflags
+"#####,
+        r#####"
+bitflags::bitflags! {
+    struct Permissions: u8 {
+        const READ = 1;
+        const WRITE = 2;
+        const EXECUTE = 4;
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_join_to_sequential_awaits() {
+    check_doc_test(
+        "convert_join_to_sequential_awaits",
+        r#####"
+async fn load() {
+    let (config, user) = futures::<|>join!(read_config(), fetch_user());
+}
+"#####,
+        r#####"
+async fn load() {
+    let config = read_config().await;
+    let user = fetch_user().await;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_static_to_lazy() {
+    check_doc_test(
+        "convert_static_to_lazy",
+        r#####"
+//- /main.rs crate:main deps:once_cell
+fn default_names() -> Vec<String> { Vec::new() }
+static NAMES<|>: Vec<String> = default_names();
+//- /lib.rs crate:once_cell
+"#####,
+        r#####"
+fn default_names() -> Vec<String> { Vec::new() }
+static NAMES: once_cell::sync::Lazy<Vec<String>> = once_cell::sync::Lazy::new(|| default_names());
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_string_field_to_shared() {
+    check_doc_test(
+        "convert_string_field_to_shared",
+        r#####"
+//- /main.rs crate:main deps:std,smol_str
+struct Config { name: String<|> }
+fn make(name: String) -> Config { Config { name } }
+//- /libstd.rs crate:std
+pub mod sync { pub struct Arc<T: ?Sized>(T); }
+//- /smol_str.rs crate:smol_str
+pub struct SmolStr;
+"#####,
+        r#####"
+use std::sync::Arc;
+
+struct Config { name: Arc<str> }
+fn make(name: String) -> Config { Config { name: name.into() } }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_string_param_to_cow() {
+    check_doc_test(
+        "convert_string_param_to_cow",
+        r#####"
+//- /main.rs crate:main deps:std
+fn greet(name<|>: String) -> String {
+    let greeting = name;
+    greeting
+}
+fn main() { greet(String::new()); }
+//- /libstd.rs crate:std
+pub mod borrow { pub enum Cow<'a, B: ?Sized> { Borrowed(&'a B) } }
+"#####,
+        r#####"
+use std::borrow::Cow;
+
+fn greet(name: Cow<str>) -> String {
+    let greeting = name.into_owned();
+    greeting
+}
+fn main() { greet(String::new().into()); }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_string_vec_arg() {
+    check_doc_test(
+        "convert_string_vec_arg",
+        r#####"
+struct String;
+struct Vec<T>(T);
+fn spawn(args: Vec<String>) {}
+fn run(args: Vec<&str>) {
+    spawn(<|>args);
+}
+"#####,
+        r#####"
+struct String;
+struct Vec<T>(T);
+fn spawn(args: Vec<String>) {}
+fn run(args: Vec<&str>) {
+    spawn(args.iter().map(|s| s.to_string()).collect());
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_struct_to_enum() {
+    check_doc_test(
+        "convert_struct_to_enum",
+        r#####"
+struct Point<|> { x: i32, y: i32 }
+fn origin() -> Point { Point { x: 0, y: 0 } }
+"#####,
+        r#####"
+enum Point { Point { x: i32, y: i32 } }
+fn origin() -> Point { Point::Point { x: 0, y: 0 } }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_to_crate_import() {
+    check_doc_test(
+        "convert_to_crate_import",
+        r#####"
+mod net {
+    mod http {
+        use super::tcp::<|>Stream;
+    }
+    mod tcp { pub struct Stream; }
+}
+"#####,
+        r#####"
+mod net {
+    mod http {
+        use crate::net::tcp::Stream;
+    }
+    mod tcp { pub struct Stream; }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_to_guarded_return() {
+    check_doc_test(
+        "convert_to_guarded_return",
+        r#####"
+fn main() {
+    <|>if cond {
+        foo();
+        bar();
+    }
 }
 "#####,
         r#####"
@@ -330,48 +1108,459 @@ fn main() {
     if !cond {
         return;
     }
-    foo();
-    bar();
+    foo();
+    bar();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_to_relative_import() {
+    check_doc_test(
+        "convert_to_relative_import",
+        r#####"
+mod net {
+    mod http {
+        use crate::net::tcp::<|>Stream;
+    }
+    mod tcp { pub struct Stream; }
+}
+"#####,
+        r#####"
+mod net {
+    mod http {
+        use super::tcp::Stream;
+    }
+    mod tcp { pub struct Stream; }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_document_iterator_chain() {
+    check_doc_test(
+        "document_iterator_chain",
+        r#####"
+fn labels(xs: &[u32]) -> Vec<String> {
+    xs.iter().filter(|x| *x % 2 == 0).map(|x| x.to_string()).<|>collect()
+}
+"#####,
+        r#####"
+/// Filters the items by `*x % 2 == 0`, maps each item to `x.to_string()`, collects the items.
+fn labels(xs: &[u32]) -> Vec<String> {
+    xs.iter().filter(|x| *x % 2 == 0).map(|x| x.to_string()).collect()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_data_struct() {
+    check_doc_test(
+        "extract_data_struct",
+        r#####"
+<|>struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    fn bump(&mut self) {
+        self.count += 1;
+    }
+}<|>
+"#####,
+        r#####"
+struct Counter { data: CounterData }
+
+struct CounterData {
+    count: usize,
+}
+
+impl Counter {
+    fn bump(&mut self) {
+        self.data.count += 1;
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_predicate_function() {
+    check_doc_test(
+        "extract_predicate_function",
+        r#####"
+struct Task { done: bool, priority: u8 }
+
+fn urgent(tasks: &mut Vec<Task>) {
+    tasks.retain(<|>|t: &Task| !t.done && t.priority > 3);
+}
+"#####,
+        r#####"
+struct Task { done: bool, priority: u8 }
+
+fn urgent(tasks: &mut Vec<Task>) {
+    tasks.retain(is_valid);
+}
+
+fn $0is_valid(t: &Task) -> bool {
+    !t.done && t.priority > 3
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_struct_from_enum_variant() {
+    check_doc_test(
+        "extract_struct_from_enum_variant",
+        r#####"
+enum A { <|>One(u32, u32) }
+"#####,
+        r#####"
+struct One(pub u32, pub u32);
+
+enum A { One(One) }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_fill_match_arms() {
+    check_doc_test(
+        "fill_match_arms",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        <|>
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    match action {
+        $0Action::Move { distance } => {}
+        Action::Stop => {}
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_fix_visibility() {
+    check_doc_test(
+        "fix_visibility",
+        r#####"
+mod m {
+    fn frobnicate() {}
+}
+fn main() {
+    m::frobnicate<|>() {}
+}
+"#####,
+        r#####"
+mod m {
+    $0pub(crate) fn frobnicate() {}
+}
+fn main() {
+    m::frobnicate() {}
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flatten_result() {
+    check_doc_test(
+        "flatten_result",
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+fn main() {
+    let x: Result<Result<i32, ()>, ()> = Result::Ok(Result::Ok(92));
+    let y = <|>x;
+}
+"#####,
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+fn main() {
+    let x: Result<Result<i32, ()>, ()> = Result::Ok(Result::Ok(92));
+    let y = x.flatten();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_binexpr() {
+    check_doc_test(
+        "flip_binexpr",
+        r#####"
+fn main() {
+    let _ = 90 +<|> 2;
+}
+"#####,
+        r#####"
+fn main() {
+    let _ = 2 + 90;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_comma() {
+    check_doc_test(
+        "flip_comma",
+        r#####"
+fn main() {
+    ((1, 2),<|> (3, 4));
+}
+"#####,
+        r#####"
+fn main() {
+    ((3, 4), (1, 2));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_flip_trait_bound() {
+    check_doc_test(
+        "flip_trait_bound",
+        r#####"
+fn foo<T: Clone +<|> Copy>() { }
+"#####,
+        r#####"
+fn foo<T: Copy + Clone>() { }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_accessors() {
+    check_doc_test(
+        "generate_accessors",
+        r#####"
+struct Person {
+    name: String,<|>
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_delegate_method() {
+    check_doc_test(
+        "generate_delegate_method",
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 { self.0 }
+}
+struct Person {
+    ag<|>e: Age,
+}
+"#####,
+        r#####"
+struct Age(u8);
+impl Age {
+    fn age(&self) -> u8 { self.0 }
+}
+struct Person {
+    age: Age,
+}
+
+impl Person {
+    fn age(&self) -> u8 {
+        self.age.age()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_delegate_methods() {
+    check_doc_test(
+        "generate_delegate_methods",
+        r#####"
+struct Numbers<I: Iterator> {
+    inner: I<|>,
+}
+"#####,
+        r#####"
+struct Numbers<I: Iterator> {
+    inner: I,
+}
+
+impl<I: Iterator> Numbers<I> {
+    pub fn next(&mut self) -> Option<<I as Iterator>::Item> {
+        Iterator::next(&mut self.inner)
+    }
+
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        Iterator::size_hint(&self.inner)
+    }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_extract_struct_from_enum_variant() {
+fn doctest_generate_deserialize_impl() {
     check_doc_test(
-        "extract_struct_from_enum_variant",
+        "generate_deserialize_impl",
+        r#####"
+#[derive(Debug, <|>Deserialize)]
+struct Port { number: u16 }
+"#####,
+        r#####"
+#[derive(Debug)]
+struct Port { number: u16 }
+
+impl<'de> serde::Deserialize<'de> for Port {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct PortVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for PortVisitor {
+            type Value = Port;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("struct Port")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Port, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut number = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "number" => number = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                let number: u16 =
+                    number.ok_or_else(|| serde::de::Error::missing_field("number"))?;
+
+                ${0:todo!("validate the fields")};
+                Ok(Port { number })
+            }
+        }
+
+        const FIELDS: &[&str] = &["number"];
+        deserializer.deserialize_struct("Port", FIELDS, PortVisitor)
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_discriminant_constants() {
+    check_doc_test(
+        "generate_discriminant_constants",
         r#####"
-enum A { <|>One(u32, u32) }
+#[repr(u8)]
+pub enum Opcode<|> {
+    Load = 1,
+    Store,
+}
 "#####,
         r#####"
-struct One(pub u32, pub u32);
+#[repr(u8)]
+pub enum Opcode {
+    Load = 1,
+    Store,
+}
 
-enum A { One(One) }
+impl Opcode {
+    pub const LOAD: u8 = Opcode::Load as u8;
+    pub const STORE: u8 = Opcode::Store as u8;
+}
 "#####,
     )
 }
 
 #[test]
-fn doctest_fill_match_arms() {
+fn doctest_generate_display_from_debug() {
     check_doc_test(
-        "fill_match_arms",
+        "generate_display_from_debug",
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+#[derive(Debug)]
+enum Status<|> { Active, Banned }
+"#####,
+        r#####"
+#[derive(Debug)]
+enum Status { Active, Banned }
 
-fn handle(action: Action) {
-    match action {
-        <|>
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // FIXME: replace with a human-readable implementation before release.
+        write!(f, "{:?}", self)
     }
 }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_enum_str_conversions() {
+    check_doc_test(
+        "generate_enum_str_conversions",
+        r#####"
+//- /main.rs crate:main deps:core
+enum Direction<|> { Up, Down }
+//- /libcore.rs crate:core
+pub mod convert { pub trait TryFrom<T> {} }
+pub mod fmt { pub trait Display {} }
 "#####,
         r#####"
-enum Action { Move { distance: u32 }, Stop }
+enum Direction { Up, Down }
 
-fn handle(action: Action) {
-    match action {
-        $0Action::Move { distance } => {}
-        Action::Stop => {}
+impl core::convert::TryFrom<&str> for Direction {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "Up" => Ok(Self::Up),
+            "Down" => Ok(Self::Down),
+            _ => Err(()),
+        }
+    }
+}
+
+impl core::fmt::Display for Direction {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = match self {
+            Self::Up => "Up",
+            Self::Down => "Down",
+        };
+        f.write_str(s)
     }
 }
 "#####,
@@ -379,71 +1568,411 @@ fn handle(action: Action) {
 }
 
 #[test]
-fn doctest_fix_visibility() {
+fn doctest_generate_extend_impl() {
     check_doc_test(
-        "fix_visibility",
+        "generate_extend_impl",
         r#####"
-mod m {
-    fn frobnicate() {}
+//- /main.rs crate:main deps:core
+struct Stack<|> { items: Vec<u32> }
+impl Stack {
+    fn push(&mut self, item: u32) { self.items.push(item) }
 }
-fn main() {
-    m::frobnicate<|>() {}
+impl core::iter::IntoIterator for Stack {
+    type Item = u32;
 }
+//- /libcore.rs crate:core
+pub mod iter { pub trait IntoIterator { type Item; } pub trait Extend<A> {} }
 "#####,
         r#####"
-mod m {
-    $0pub(crate) fn frobnicate() {}
+struct Stack { items: Vec<u32> }
+
+impl core::iter::Extend<u32> for Stack {
+    fn extend<T: core::iter::IntoIterator<Item = u32>>(&mut self, iter: T) {
+        for item in iter {
+            self.push(item);
+        }
+    }
 }
-fn main() {
-    m::frobnicate() {}
+impl Stack {
+    fn push(&mut self, item: u32) { self.items.push(item) }
+}
+impl core::iter::IntoIterator for Stack {
+    type Item = u32;
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_flip_binexpr() {
+fn doctest_generate_ffi_wrapper() {
     check_doc_test(
-        "flip_binexpr",
+        "generate_ffi_wrapper",
         r#####"
-fn main() {
-    let _ = 90 +<|> 2;
+extern "C" {
+    fn <|>set_name(handle: *mut Handle, name: *const c_char) -> c_int;
 }
 "#####,
         r#####"
-fn main() {
-    let _ = 2 + 90;
+pub fn safe_set_name(handle: &mut Handle, name: &std::ffi::CStr) -> Result<(), c_int> {
+    // SAFETY: $0...
+    let ret = unsafe { set_name(handle, name.as_ptr()) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(ret)
+    }
+}
+
+extern "C" {
+    fn set_name(handle: *mut Handle, name: *const c_char) -> c_int;
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_flip_comma() {
+fn doctest_generate_from_str_impl() {
     check_doc_test(
-        "flip_comma",
+        "generate_from_str_impl",
+        r#####"
+//- /main.rs crate:main deps:core
+use core::fmt;
+struct Point { x: i32, y: i32 }
+impl fmt::Display for <|>Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+//- /libcore.rs crate:core
+pub mod fmt { pub trait Display {} }
+pub mod str { pub trait FromStr {} }
+"#####,
         r#####"
-fn main() {
-    ((1, 2),<|> (3, 4));
+use core::fmt;
+struct Point { x: i32, y: i32 }
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
 }
+
+impl core::str::FromStr for Point {
+    type Err = String;
+
+    /// Parses the format written by `Display`: `"{},{}"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let end = s.find(",").ok_or_else(|| "expected `,`".to_string())?;
+        let x = s[..end].parse().map_err(|_| "invalid `x`".to_string())?;
+        let s = &s[end + ",".len()..];
+        let y = s.parse().map_err(|_| "invalid `y`".to_string())?;
+        Ok(Point { x, y })
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_index_impl() {
+    check_doc_test(
+        "generate_index_impl",
+        r#####"
+//- /main.rs crate:main deps:core
+struct Grid { cells: Vec<u8> }
+impl Grid {
+    fn <|>get(&self, pos: (usize, usize)) -> &u8 { &self.cells[pos.0 * 8 + pos.1] }
+}
+//- /libcore.rs crate:core
+pub mod ops { pub trait Index<Idx> {} pub trait IndexMut<Idx> {} }
 "#####,
         r#####"
-fn main() {
-    ((3, 4), (1, 2));
+struct Grid { cells: Vec<u8> }
+impl Grid {
+    fn get(&self, pos: (usize, usize)) -> &u8 { &self.cells[pos.0 * 8 + pos.1] }
+}
+
+impl core::ops::Index<(usize, usize)> for Grid {
+    type Output = u8;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        self.get(index)
+    }
 }
 "#####,
     )
 }
 
 #[test]
-fn doctest_flip_trait_bound() {
+fn doctest_generate_mock() {
     check_doc_test(
-        "flip_trait_bound",
+        "generate_mock",
         r#####"
-fn foo<T: Clone +<|> Copy>() { }
+trait Clock<|> {
+    fn now(&self) -> u64;
+    fn set(&mut self, time: u64);
+}
 "#####,
         r#####"
-fn foo<T: Copy + Clone>() { }
+trait Clock {
+    fn now(&self) -> u64;
+    fn set(&mut self, time: u64);
+}
+
+#[cfg(test)]
+mod mock_clock {
+    use super::*;
+
+    pub(crate) struct MockClock {
+        pub(crate) now: u64,
+    }
+
+    impl MockClock {
+        pub(crate) fn new(now: u64) -> MockClock {
+            MockClock { now }
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> u64 {
+            self.now.clone()
+        }
+
+        fn set(&mut self, _: u64) {}
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_parameterized_test() {
+    check_doc_test(
+        "generate_parameterized_test",
+        r#####"
+#[test]
+fn squares() {
+    let cases = [<|>(2, 4), (3, 9)];
+    assert_eq!(square(cases[0].0), cases[0].1);
+}
+"#####,
+        r#####"
+#[test]
+fn squares() {
+    let cases = [(2, 4), (3, 9)];
+    for case in cases.iter() {
+        assert_eq!(square(case.0), case.1);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_partial_eq_ignoring_fields() {
+    check_doc_test(
+        "generate_partial_eq_ignoring_fields",
+        r#####"
+#[derive(Debug, PartialEq)]
+struct Entry {
+    key: String,
+    <|>updated_at: u64,
+}
+"#####,
+        r#####"
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    updated_at: u64,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignored: `updated_at`
+        self.key == other.key
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_proc_macro_attribute() {
+    check_doc_test(
+        "generate_proc_macro_attribute",
+        r#####"
+//- /lib.rs crate:my_macros deps:proc_macro
+<|>
+fn helper() {}
+//- /proc_macro.rs crate:proc_macro
+"#####,
+        r#####"
+#[proc_macro_attribute]
+pub fn $0my_attribute(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    todo!()
+}
+
+fn helper() {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_proc_macro_derive() {
+    check_doc_test(
+        "generate_proc_macro_derive",
+        r#####"
+//- /lib.rs crate:my_macros deps:proc_macro
+<|>
+fn helper() {}
+//- /proc_macro.rs crate:proc_macro
+"#####,
+        r#####"
+#[proc_macro_derive($0MyDerive)]
+pub fn my_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    todo!()
+}
+
+fn helper() {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_property_test() {
+    check_doc_test(
+        "generate_property_test",
+        r#####"
+//- /main.rs crate:main deps:proptest
+#[test]
+fn <|>reverse_twice() {
+    let x = 92;
+    let y = 10;
+    assert_eq!(reverse(&reverse(&[x, y])), [x, y]);
+}
+//- /lib.rs crate:proptest
+"#####,
+        r#####"
+#[test]
+fn reverse_twice() {
+    let x = 92;
+    let y = 10;
+    assert_eq!(reverse(&reverse(&[x, y])), [x, y]);
+}
+
+proptest::proptest! {
+    #[test]
+    fn prop_reverse_twice(x: i32, y: i32) {
+        assert_eq!(reverse(&reverse(&[x, y])), [x, y]);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_redacted_debug_impl() {
+    check_doc_test(
+        "generate_redacted_debug_impl",
+        r#####"
+#[derive(Clone, Debug)]
+struct Credentials {
+    user: String,
+    password: String,<|>
+}
+"#####,
+        r#####"
+#[derive(Clone)]
+struct Credentials {
+    user: String,
+    password: String,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("user", &self.user)
+            .field("password", &"[REDACTED]")
+            .finish()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_sealed_trait() {
+    check_doc_test(
+        "generate_sealed_trait",
+        r#####"
+pub trait <|>Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square(f64);
+impl Shape for Square {
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+"#####,
+        r#####"
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait Shape: private::Sealed {
+    fn area(&self) -> f64;
+}
+
+pub struct Square(f64);
+impl Shape for Square {
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+
+impl private::Sealed for Square {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_test_matrix() {
+    check_doc_test(
+        "generate_test_matrix",
+        r#####"
+enum Color { Red, Blue }
+enum Size { Small, Large }
+fn paint<|>(color: Color, size: Size) {}
+"#####,
+        r#####"
+enum Color { Red, Blue }
+enum Size { Small, Large }
+fn paint(color: Color, size: Size) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_red_small() {
+        todo!()
+    }
+
+    #[test]
+    fn test_red_large() {
+        todo!()
+    }
+
+    #[test]
+    fn test_blue_small() {
+        todo!()
+    }
+
+    #[test]
+    fn test_blue_large() {
+        todo!()
+    }
+}
 "#####,
     )
 }
@@ -466,6 +1995,24 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_module() {
+    check_doc_test(
+        "inline_module",
+        r#####"
+//- /main.rs
+mod <|>foo;
+//- /foo.rs
+fn bar() {}
+"#####,
+        r#####"
+mod foo {
+    fn bar() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_named_lifetime() {
     check_doc_test(
@@ -491,6 +2038,37 @@ impl<'a> Cursor<'a> {
     )
 }
 
+#[test]
+fn doctest_introduce_named_return() {
+    check_doc_test(
+        "introduce_named_return",
+        r#####"
+fn <|>digit(c: char) -> u32 {
+    if c == 'x' {
+        return 10;
+    } else if c.is_ascii_digit() {
+        return c as u32 - '0' as u32;
+    } else {
+        0
+    }
+}
+"#####,
+        r#####"
+fn digit(c: char) -> u32 {
+    let mut result = Default::default();
+    if c == 'x' {
+        result = 10;
+    } else if c.is_ascii_digit() {
+        result = c as u32 - '0' as u32;
+    } else {
+        result = 0;
+    }
+    result
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_variable() {
     check_doc_test(
@@ -526,6 +2104,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_make_fn_const() {
+    check_doc_test(
+        "make_fn_const",
+        r#####"
+fn square<|>(x: u32) -> u32 {
+    x * x
+}
+"#####,
+        r#####"
+const fn square(x: u32) -> u32 {
+    x * x
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_make_raw_string() {
     check_doc_test(
@@ -560,6 +2155,30 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_merge_impl_blocks() {
+    check_doc_test(
+        "merge_impl_blocks",
+        r#####"
+struct Counter(u32);
+impl Counter {
+    fn get(&self) -> u32 { self.0 }
+}
+impl<|> Counter {
+    fn incr(&mut self) { self.0 += 1 }
+}
+"#####,
+        r#####"
+struct Counter(u32);
+impl Counter {
+    fn get(&self) -> u32 { self.0 }
+
+    fn incr(&mut self) { self.0 += 1 }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_merge_imports() {
     check_doc_test(
@@ -671,6 +2290,37 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_use_to_inner_module() {
+    check_doc_test(
+        "move_use_to_inner_module",
+        r#####"
+mod cache { pub struct Cache; }
+use cache::<|>Cache;
+
+fn main() {}
+
+mod tests {
+    use super::*;
+
+    fn build() -> Cache { Cache }
+}
+"#####,
+        r#####"
+mod cache { pub struct Cache; }
+
+fn main() {}
+
+mod tests {
+    use super::*;
+    use super::cache::Cache;
+
+    fn build() -> Cache { Cache }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_dbg() {
     check_doc_test(
@@ -705,6 +2355,20 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_remove_inline_attr() {
+    check_doc_test(
+        "remove_inline_attr",
+        r#####"
+#[inline(always)]
+fn square(x: u32) -> u32<|> { x * x }
+"#####,
+        r#####"
+fn square(x: u32) -> u32 { x * x }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_mut() {
     check_doc_test(
@@ -722,6 +2386,32 @@ impl Walrus {
     )
 }
 
+#[test]
+fn doctest_remove_redundant_wildcard_arm() {
+    check_doc_test(
+        "remove_redundant_wildcard_arm",
+        r#####"
+enum Mode { Read, Write }
+fn f(mode: Mode) -> u8 {
+    match mode {
+        Mode::Read => 1,
+        Mode::Write => 2,
+        <|>_ => 0,
+    }
+}
+"#####,
+        r#####"
+enum Mode { Read, Write }
+fn f(mode: Mode) -> u8 {
+    match mode {
+        Mode::Read => 1,
+        Mode::Write => 2,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_reorder_fields() {
     check_doc_test(
@@ -737,6 +2427,113 @@ const test: Foo = Foo {foo: 1, bar: 0}
     )
 }
 
+#[test]
+fn doctest_reorder_impl_methods() {
+    check_doc_test(
+        "reorder_impl_methods",
+        r#####"
+trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String;
+}
+struct Square(f64);
+impl Shape for <|>Square {
+    fn name(&self) -> String { "square".to_string() }
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+"#####,
+        r#####"
+trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String;
+}
+struct Square(f64);
+impl Shape for Square {
+    fn area(&self) -> f64 { self.0 * self.0 }
+    fn name(&self) -> String { "square".to_string() }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_arith_with_saturating() {
+    check_doc_test(
+        "replace_arith_with_saturating",
+        r#####"
+fn remaining(len: usize, pos: usize) -> usize {
+    len -<|> pos
+}
+"#####,
+        r#####"
+fn remaining(len: usize, pos: usize) -> usize {
+    len.saturating_sub(pos)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_arith_with_wrapping() {
+    check_doc_test(
+        "replace_arith_with_wrapping",
+        r#####"
+fn hash(h: u32, c: u32) -> u32 {
+    h *<|> 31 + c
+}
+"#####,
+        r#####"
+fn hash(h: u32, c: u32) -> u32 {
+    h.wrapping_mul(31) + c
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_conversion_with_cast() {
+    check_doc_test(
+        "replace_conversion_with_cast",
+        r#####"
+fn widen(x: u16) -> u32 {
+    u32::try_from(x).unwrap()<|>
+}
+"#####,
+        r#####"
+fn widen(x: u16) -> u32 {
+    x as u32
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_if_let_chain_with_match() {
+    check_doc_test(
+        "replace_if_let_chain_with_match",
+        r#####"
+fn timeout(arg: Option<u64>, env: Result<u64, ()>) -> u64 {
+    <|>if let Some(secs) = arg {
+        secs
+    } else if let Ok(secs) = env {
+        secs
+    } else {
+        30
+    }
+}
+"#####,
+        r#####"
+fn timeout(arg: Option<u64>, env: Result<u64, ()>) -> u64 {
+    match (arg, env) {
+        (Some(secs), _) => secs,
+        (_, Ok(secs)) => secs,
+        _ => 30,
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check_doc_test(
@@ -765,6 +2562,28 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_replace_let_else_with_match() {
+    check_doc_test(
+        "replace_let_else_with_match",
+        r#####"
+fn parse(s: &str) -> Option<u32> { None }
+fn main() {
+    let Some(x) = <|>parse("42") else { return };
+}
+"#####,
+        r#####"
+fn parse(s: &str) -> Option<u32> { None }
+fn main() {
+    let x = match parse("42") {
+        Some(x) => x,
+        _ => { return }
+    };
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_let_with_if_let() {
     check_doc_test(
@@ -791,6 +2610,87 @@ fn compute() -> Option<i32> { None }
     )
 }
 
+#[test]
+fn doctest_replace_lock_with_get_mut() {
+    check_doc_test(
+        "replace_lock_with_get_mut",
+        r#####"
+struct Mutex<T>(T);
+impl<T> Mutex<T> {
+    fn new(t: T) -> Mutex<T> { Mutex(t) }
+    fn lock(&self) -> Result<&T, ()> { Ok(&self.0) }
+    fn get_mut(&mut self) -> Result<&mut T, ()> { Ok(&mut self.0) }
+}
+fn count() -> u32 {
+    let counter = Mutex::new(0);
+    *counter.<|>lock().unwrap() += 1;
+    0
+}
+"#####,
+        r#####"
+struct Mutex<T>(T);
+impl<T> Mutex<T> {
+    fn new(t: T) -> Mutex<T> { Mutex(t) }
+    fn lock(&self) -> Result<&T, ()> { Ok(&self.0) }
+    fn get_mut(&mut self) -> Result<&mut T, ()> { Ok(&mut self.0) }
+}
+fn count() -> u32 {
+    let mut counter = Mutex::new(0);
+    *counter.get_mut().unwrap() += 1;
+    0
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_match_with_combinator() {
+    check_doc_test(
+        "replace_match_with_combinator",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn main() {
+    let x: Option<i32> = Some(1);
+    let y = <|>match x {
+        Some(it) => Some(it + 1),
+        None => None,
+    };
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::*;
+fn main() {
+    let x: Option<i32> = Some(1);
+    let y = x.map(|it| it + 1);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_match_with_let_else() {
+    check_doc_test(
+        "replace_match_with_let_else",
+        r#####"
+fn parse(s: &str) -> Option<u32> { None }
+fn main() {
+    let x = <|>match parse("42") {
+        Some(x) => x,
+        _ => return,
+    };
+}
+"#####,
+        r#####"
+fn parse(s: &str) -> Option<u32> { None }
+fn main() {
+    let Some(x) = parse("42") else { return };
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_qualified_name_with_use() {
     check_doc_test(
@@ -806,6 +2706,55 @@ fn process(map: HashMap<String, String>) {}
     )
 }
 
+#[test]
+fn doctest_replace_string_concat_with_write() {
+    check_doc_test(
+        "replace_string_concat_with_write",
+        r#####"
+//- /main.rs crate:main deps:core
+fn greet(name: &str) -> String {
+    let mut buf = String::new();
+    buf.push_str("Hello, ");<|>
+    buf.push_str(name);
+    buf.push('!');
+    buf
+}
+//- /libcore.rs crate:core
+pub mod fmt { pub trait Write {} }
+"#####,
+        r#####"
+use core::fmt::Write;
+
+fn greet(name: &str) -> String {
+    let mut buf = String::new();
+    write!(buf, "Hello, {}!", name).unwrap();
+    buf
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_unwrap_or_else_with_unwrap_or() {
+    check_doc_test(
+        "replace_unwrap_or_else_with_unwrap_or",
+        r#####"
+enum Option<T> { Some(T), None }
+fn main() {
+    let x: Option<i32> = Option::None;
+    let y = x.<|>unwrap_or_else(|| 92);
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+fn main() {
+    let x: Option<i32> = Option::None;
+    let y = x.unwrap_or(92);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_unwrap_with_match() {
     check_doc_test(
@@ -843,6 +2792,21 @@ use std::{collections::HashMap};
     )
 }
 
+#[test]
+fn doctest_toggle_cfg() {
+    check_doc_test(
+        "toggle_cfg",
+        r#####"
+#[cfg(debug_<|>assertions)]
+fn log(message: &str) {}
+"#####,
+        r#####"
+#[cfg(not(debug_assertions))]
+fn log(message: &str) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_unwrap_block() {
     check_doc_test(
@@ -861,3 +2825,79 @@ fn foo() {
 "#####,
     )
 }
+
+#[test]
+fn doctest_unwrap_parentheses() {
+    check_doc_test(
+        "unwrap_parentheses",
+        r#####"
+fn area(w: u32, h: u32) -> u32 {
+    let border = 2 + <|>(w * h);
+    border
+}
+"#####,
+        r#####"
+fn area(w: u32, h: u32) -> u32 {
+    let border = 2 + w * h;
+    border
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_unwrap_rc_field() {
+    check_doc_test(
+        "unwrap_rc_field",
+        r#####"
+use std::rc::Rc;
+struct Config { name: Rc<String><|> }
+fn make(name: String) -> Config { Config { name: Rc::new(name) } }
+"#####,
+        r#####"
+use std::rc::Rc;
+struct Config { name: String }
+fn make(name: String) -> Config { Config { name: name } }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_field_in_lock() {
+    check_doc_test(
+        "wrap_field_in_lock",
+        r#####"
+//- /main.rs crate:main deps:std
+struct Stats { hits: u64<|> }
+fn hit(stats: &Stats) -> u64 { stats.hits }
+//- /libstd.rs crate:std
+pub mod sync { pub struct Mutex<T>(T); pub struct RwLock<T>(T); }
+"#####,
+        r#####"
+use std::sync::Mutex;
+
+struct Stats { hits: Mutex<u64> }
+fn hit(stats: &Stats) -> u64 { *stats.hits.lock().unwrap() }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_field_in_rc() {
+    check_doc_test(
+        "wrap_field_in_rc",
+        r#####"
+//- /main.rs crate:main deps:std
+struct Config { name: String<|> }
+fn make(name: String) -> Config { Config { name } }
+//- /libstd.rs crate:std
+pub mod rc { pub struct Rc<T>(T); }
+"#####,
+        r#####"
+use std::rc::Rc;
+
+struct Config { name: Rc<String> }
+fn make(name: String) -> Config { Config { name: Rc::new(name) } }
+"#####,
+    )
+}
@@ -1,17 +1,27 @@
 //! Assorted functions shared by several assists.
 pub(crate) mod insert_use;
 
-use std::{iter, ops};
+use std::{fmt, iter, ops};
 
-use hir::{Adt, Crate, Enum, ScopeDef, Semantics, Trait, Type};
-use ra_ide_db::RootDatabase;
+use hir::{Adt, Crate, Enum, ScopeDef, Semantics, Struct, Trait, Type};
+use ra_db::FileId;
+use ra_ide_db::{defs::Definition, search::ReferenceKind, RootDatabase};
 use ra_syntax::{
-    ast::{self, make, NameOwner},
-    AstNode, SyntaxNode, T,
+    algo::find_node_at_offset,
+    ast::{
+        self, make, AttrsOwner, NameOwner, TypeAscriptionOwner, TypeBoundsOwner, TypeParamsOwner,
+    },
+    AstNode,
+    SyntaxKind::{COMMENT, IDENT, ITEM_LIST, MODULE, SOURCE_FILE, WHITESPACE},
+    SyntaxNode, TextRange, TextSize, T,
 };
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::{format_to, SepBy};
 
-use crate::assist_config::SnippetCap;
+use crate::{
+    assist_config::SnippetCap,
+    assist_context::{AssistBuilder, AssistContext},
+};
 
 pub(crate) use insert_use::insert_use_statement;
 
@@ -120,6 +130,171 @@ pub(crate) fn resolve_target_trait(
     }
 }
 
+/// Returns the generic parameters of `nominal`, as written after `impl`.
+pub(crate) fn impl_type_params(nominal: &ast::NominalDef) -> String {
+    match nominal.type_param_list() {
+        Some(it) => it.syntax().to_string(),
+        None => String::new(),
+    }
+}
+
+/// Returns the type `name`, usually the name of `nominal`, with the generic
+/// parameters of `nominal` applied, for use as the self type of an `impl`.
+pub(crate) fn nominal_self_ty(nominal: &ast::NominalDef, name: &impl fmt::Display) -> String {
+    let mut buf = name.to_string();
+    if let Some(type_params) = nominal.type_param_list() {
+        let lifetime_params = type_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime_token())
+            .map(|it| it.text().clone());
+        let type_params =
+            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "));
+    }
+    buf
+}
+
+pub(crate) const INT_TYPES: &[&str] =
+    &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+
+/// Returns the integer type of the `#[repr]` attribute of `enum_def`.
+pub(crate) fn int_repr(enum_def: &ast::EnumDef) -> Option<String> {
+    enum_def.attrs().find_map(|it| {
+        let (name, tt) = it.as_simple_call()?;
+        if name != "repr" {
+            return None;
+        }
+        let ty = tt.syntax().text().to_string();
+        let ty = ty.trim_start_matches('(').trim_end_matches(')').trim().to_string();
+        if INT_TYPES.contains(&ty.as_str()) {
+            Some(ty)
+        } else {
+            None
+        }
+    })
+}
+
+/// Checks whether `ty` is a type parameter of `strukt` bounded by `trait_name`.
+/// Such bounds are not taken into account by `Type::impls_trait`.
+pub(crate) fn has_bound(strukt: &ast::StructDef, ty: &ast::TypeRef, trait_name: &str) -> bool {
+    let param_name = match ty {
+        ast::TypeRef::PathType(it) => match it.path() {
+            Some(path) if path.qualifier().is_none() => match path.segment() {
+                Some(segment) => segment.syntax().text().to_string(),
+                None => return false,
+            },
+            _ => return false,
+        },
+        _ => return false,
+    };
+    let names_trait = |bounds: Option<ast::TypeBoundList>| {
+        bounds.into_iter().flat_map(|it| it.bounds()).any(|bound| match bound.type_ref() {
+            Some(ast::TypeRef::PathType(it)) => it
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map_or(false, |it| it.text() == trait_name),
+            _ => false,
+        })
+    };
+    let in_param_list = strukt
+        .type_param_list()
+        .into_iter()
+        .flat_map(|it| it.type_params())
+        .filter(|it| it.name().map_or(false, |it| it.text() == param_name.as_str()))
+        .any(|it| names_trait(it.type_bound_list()));
+    let in_where_clause = strukt
+        .where_clause()
+        .into_iter()
+        .flat_map(|it| it.predicates())
+        .filter(|it| it.type_ref().map_or(false, |it| it.syntax().text() == param_name.as_str()))
+        .any(|it| names_trait(it.type_bound_list()));
+    in_param_list || in_where_clause
+}
+
+/// Returns the comma separated paths of a `derive` attribute.
+pub(crate) fn derive_paths(tt: &ast::TokenTree) -> Vec<String> {
+    let text = tt.syntax().text().to_string();
+    let text = text.trim_start_matches('(').trim_end_matches(')');
+    text.split(',').map(|it| it.trim()).filter(|it| !it.is_empty()).map(String::from).collect()
+}
+
+/// Checks whether `item` derives `name`, which may be written as a path.
+pub(crate) fn has_derive(item: &impl AttrsOwner, name: &str) -> bool {
+    item.attrs()
+        .filter_map(|it| it.as_simple_call())
+        .filter(|(attr_name, _arg)| attr_name == "derive")
+        .flat_map(|(_attr_name, arg)| derive_paths(&arg))
+        .any(|path| path.rsplit("::").next() == Some(name))
+}
+
+// Insert `derive` after doc comments.
+pub(crate) fn derive_insertion_offset(nominal: &ast::NominalDef) -> Option<TextSize> {
+    let non_ws_child = nominal
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?;
+    Some(non_ws_child.text_range().start())
+}
+
+/// Appends `name` to the existing `#[derive(..)]` of `nominal`, or else adds a
+/// `#[derive(name)]` attribute.
+pub(crate) fn add_derive(builder: &mut AssistBuilder, nominal: &ast::NominalDef, name: &str) {
+    let derive_attr = nominal
+        .attrs()
+        .filter_map(|it| it.as_simple_call())
+        .find(|(attr_name, _arg)| attr_name == "derive")
+        .map(|(_attr_name, arg)| arg);
+    match derive_attr {
+        Some(tt) => {
+            let offset = tt.syntax().text_range().end() - TextSize::of(')');
+            let has_derives = tt.syntax().children_with_tokens().any(|it| it.kind() == IDENT);
+            if has_derives {
+                builder.insert(offset, format!(", {}", name));
+            } else {
+                builder.insert(offset, name);
+            }
+        }
+        None => {
+            if let Some(offset) = derive_insertion_offset(nominal) {
+                builder.insert(offset, format!("#[derive({})]\n", name));
+            }
+        }
+    }
+}
+
+/// Checks whether a method call can be appended to `expr` without wrapping it
+/// in parentheses.
+pub fn is_postfix_receiver(expr: &ast::Expr) -> bool {
+    matches!(
+        expr,
+        ast::Expr::PathExpr(_)
+            | ast::Expr::CallExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::IndexExpr(_)
+            | ast::Expr::TryExpr(_)
+            | ast::Expr::AwaitExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::MacroCall(_)
+    )
+}
+
+/// Returns the item of the enclosing module, which contains `node`.
+pub(crate) fn module_item(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.ancestors().find(|it| {
+        let parent = match it.parent() {
+            Some(it) => it,
+            None => return false,
+        };
+        match parent.kind() {
+            SOURCE_FILE => true,
+            ITEM_LIST => parent.parent().map_or(false, |it| it.kind() == MODULE),
+            _ => false,
+        }
+    })
+}
+
 pub(crate) fn invert_boolean_expression(expr: ast::Expr) -> ast::Expr {
     if let Some(expr) = invert_special_case(&expr) {
         return expr;
@@ -189,6 +364,63 @@ impl TryEnum {
     }
 }
 
+/// Returns the name, the definition and the type of the struct field under the
+/// cursor. The name of a tuple field is its index.
+pub(crate) fn field_at_cursor(
+    ctx: &AssistContext,
+) -> Option<(String, Option<hir::Field>, ast::TypeRef)> {
+    if let Some(field) = ctx.find_node_at_offset::<ast::RecordFieldDef>() {
+        return Some((field.name()?.to_string(), ctx.sema.to_def(&field), field.ascribed_type()?));
+    }
+    let field = ctx.find_node_at_offset::<ast::TupleFieldDef>()?;
+    let field_list = ast::TupleFieldDefList::cast(field.syntax().parent()?)?;
+    let idx = field_list.fields().position(|it| it == field)?;
+    Some((idx.to_string(), ctx.sema.to_def(&field), field.type_ref()?))
+}
+
+/// Finds the usages of `field`, together with the `NameRef`s referring to it.
+pub(crate) fn field_usages(
+    ctx: &AssistContext,
+    field: hir::Field,
+) -> Vec<(FileId, ReferenceKind, ast::NameRef)> {
+    Definition::Field(field)
+        .find_usages(ctx.db, None)
+        .into_iter()
+        .filter_map(|reference| {
+            let file_id = reference.file_range.file_id;
+            let source_file = ctx.sema.parse(file_id);
+            let name_ref = find_node_at_offset::<ast::NameRef>(
+                source_file.syntax(),
+                reference.file_range.range.start(),
+            )?;
+            Some((file_id, reference.kind, name_ref))
+        })
+        .collect()
+}
+
+/// Text edits grouped by file, as every file can only be edited once.
+#[derive(Default, Clone)]
+pub(crate) struct FileEdits(FxHashMap<FileId, Vec<(TextRange, String)>>);
+
+impl FileEdits {
+    pub(crate) fn add(&mut self, file_id: FileId, range: TextRange, text: impl Into<String>) {
+        self.0.entry(file_id).or_default().push((range, text.into()));
+    }
+
+    /// Applies the edits, leaving `builder` on `current_file`.
+    pub(crate) fn apply(self, builder: &mut AssistBuilder, current_file: FileId) {
+        let mut files: Vec<_> = self.0.into_iter().collect();
+        files.sort_by_key(|(file_id, _)| *file_id == current_file);
+        for (file_id, edits) in files {
+            builder.edit_file(file_id);
+            for (range, text) in edits {
+                builder.replace(range, text);
+            }
+        }
+        builder.edit_file(current_file);
+    }
+}
+
 /// Helps with finding well-know things inside the standard library. This is
 /// somewhat similar to the known paths infra inside hir, but it different; We
 /// want to make sure that IDE specific paths don't become interesting inside
@@ -200,31 +432,243 @@ impl FamousDefs<'_, '_> {
     #[cfg(test)]
     pub(crate) const FIXTURE: &'static str = r#"
 //- /libcore.rs crate:core
+pub mod cell {
+    pub struct UnsafeCell<T> { value: T }
+}
+
+pub mod clone {
+    pub trait Clone {
+        fn clone(&self) -> Self;
+    }
+}
+
 pub mod convert {
     pub trait From<T> {
         fn from(T) -> Self;
     }
+
+    pub trait AsRef<T: ?Sized> {
+        fn as_ref(&self) -> &T;
+    }
+
+    pub trait AsMut<T: ?Sized> {
+        fn as_mut(&mut self) -> &mut T;
+    }
+
+    pub trait TryFrom<T>: Sized {
+        type Error;
+        fn try_from(value: T) -> Result<Self, Self::Error>;
+    }
+}
+
+pub mod default {
+    pub trait Default {
+        fn default() -> Self;
+    }
+}
+
+pub mod fmt {
+    pub struct Error;
+    pub type Result = crate::result::Result<(), Error>;
+    pub struct Formatter<'a>;
+    pub trait Debug {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result;
+    }
+    pub trait Display {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result;
+    }
+    pub trait Write {
+        fn write_str(&mut self, s: &str) -> Result;
+    }
+}
+
+pub mod iter {
+    pub trait Iterator {
+        type Item;
+        fn next(&mut self) -> Option<Self::Item>;
+    }
+
+    pub trait IntoIterator {
+        type Item;
+        type IntoIter: Iterator<Item = Self::Item>;
+        fn into_iter(self) -> Self::IntoIter;
+    }
+
+    pub trait Extend<A> {
+        fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T);
+    }
+}
+
+pub mod marker {
+    #[lang = "copy"]
+    pub trait Copy {}
+    impl Copy for bool {}
+    impl Copy for char {}
+    impl Copy for i32 {}
+    impl Copy for u32 {}
+    impl Copy for usize {}
+    pub unsafe auto trait Send {}
+    pub unsafe auto trait Sync {}
+}
+
+pub mod ops {
+    pub trait Index<Idx: ?Sized> {
+        type Output: ?Sized;
+        fn index(&self, index: Idx) -> &Self::Output;
+    }
+
+    pub trait IndexMut<Idx: ?Sized>: Index<Idx> {
+        fn index_mut(&mut self, index: Idx) -> &mut Self::Output;
+    }
 }
 
 pub mod option {
     pub enum Option<T> { None, Some(T)}
 }
 
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+
+pub mod str {
+    pub trait FromStr: Sized {
+        type Err;
+        fn from_str(s: &str) -> Result<Self, Self::Err>;
+    }
+}
+
 pub mod prelude {
-    pub use crate::{convert::From, option::Option::{self, *}};
+    pub use crate::{
+        convert::{AsMut, AsRef, From},
+        default::Default,
+        option::Option::{self, *},
+        result::Result::{self, *},
+    };
 }
 #[prelude_import]
 pub use prelude::*;
 "#;
 
+    pub(crate) fn core_clone_Clone(&self) -> Option<Trait> {
+        self.find_trait("core:clone:Clone")
+    }
+
     pub(crate) fn core_convert_From(&self) -> Option<Trait> {
         self.find_trait("core:convert:From")
     }
 
+    pub(crate) fn core_convert_AsRef(&self) -> Option<Trait> {
+        self.find_trait("core:convert:AsRef")
+    }
+
+    pub(crate) fn core_convert_AsMut(&self) -> Option<Trait> {
+        self.find_trait("core:convert:AsMut")
+    }
+
+    pub(crate) fn core_convert_TryFrom(&self) -> Option<Trait> {
+        self.find_trait("core:convert:TryFrom")
+    }
+
+    pub(crate) fn core_default_Default(&self) -> Option<Trait> {
+        self.find_trait("core:default:Default")
+    }
+
+    pub(crate) fn core_fmt_Debug(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Debug")
+    }
+
+    pub(crate) fn core_fmt_Display(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Display")
+    }
+
+    pub(crate) fn core_fmt_Write(&self) -> Option<Trait> {
+        self.find_trait("core:fmt:Write")
+    }
+
+    pub(crate) fn core_marker_Copy(&self) -> Option<Trait> {
+        self.find_trait("core:marker:Copy")
+    }
+
+    pub(crate) fn core_marker_Send(&self) -> Option<Trait> {
+        self.find_trait("core:marker:Send")
+    }
+
+    pub(crate) fn core_marker_Sync(&self) -> Option<Trait> {
+        self.find_trait("core:marker:Sync")
+    }
+
+    pub(crate) fn core_ops_Index(&self) -> Option<Trait> {
+        self.find_trait("core:ops:Index")
+    }
+
+    pub(crate) fn core_ops_IndexMut(&self) -> Option<Trait> {
+        self.find_trait("core:ops:IndexMut")
+    }
+
     pub(crate) fn core_option_Option(&self) -> Option<Enum> {
         self.find_enum("core:option:Option")
     }
 
+    pub(crate) fn core_result_Result(&self) -> Option<Enum> {
+        self.find_enum("core:result:Result")
+    }
+
+    pub(crate) fn core_str_FromStr(&self) -> Option<Trait> {
+        self.find_trait("core:str:FromStr")
+    }
+
+    pub(crate) fn std_io_Read(&self) -> Option<Trait> {
+        self.find_trait("std:io:Read")
+    }
+
+    pub(crate) fn std_io_BufRead(&self) -> Option<Trait> {
+        self.find_trait("std:io:BufRead")
+    }
+
+    pub(crate) fn std_io_Write(&self) -> Option<Trait> {
+        self.find_trait("std:io:Write")
+    }
+
+    pub(crate) fn std_io_Seek(&self) -> Option<Trait> {
+        self.find_trait("std:io:Seek")
+    }
+
+    pub(crate) fn core_iter_Iterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:Iterator")
+    }
+
+    pub(crate) fn core_iter_IntoIterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:IntoIterator")
+    }
+
+    pub(crate) fn core_iter_Extend(&self) -> Option<Trait> {
+        self.find_trait("core:iter:Extend")
+    }
+
+    pub(crate) fn std_borrow_Cow(&self) -> Option<Enum> {
+        self.find_enum("std:borrow:Cow")
+    }
+
+    pub(crate) fn std_rc_Rc(&self) -> Option<Struct> {
+        self.find_struct("std:rc:Rc")
+    }
+
+    pub(crate) fn std_sync_Arc(&self) -> Option<Struct> {
+        self.find_struct("std:sync:Arc")
+    }
+
+    pub(crate) fn std_sync_Mutex(&self) -> Option<Struct> {
+        self.find_struct("std:sync:Mutex")
+    }
+
+    pub(crate) fn std_sync_RwLock(&self) -> Option<Struct> {
+        self.find_struct("std:sync:RwLock")
+    }
+
+    pub(crate) fn smol_str_SmolStr(&self) -> Option<Struct> {
+        self.find_struct("smol_str:SmolStr")
+    }
+
     fn find_trait(&self, path: &str) -> Option<Trait> {
         match self.find_def(path)? {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
@@ -232,6 +676,13 @@ pub use prelude::*;
         }
     }
 
+    fn find_struct(&self, path: &str) -> Option<Struct> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Struct(it))) => Some(it),
+            _ => None,
+        }
+    }
+
     fn find_enum(&self, path: &str) -> Option<Enum> {
         match self.find_def(path)? {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Enum(it))) => Some(it),
@@ -3,10 +3,10 @@
 //! LSP diagnostics based on the output of the command.
 
 use std::{
-    io::{self, BufReader},
+    io::BufReader,
     path::PathBuf,
-    process::{Command, Stdio},
-    time::Instant,
+    process::{Child, ChildStdout, Command, Stdio},
+    time::{Duration, Instant},
 };
 
 use cargo_metadata::Message;
@@ -44,11 +44,13 @@ pub struct Flycheck {
 }
 
 impl Flycheck {
-    pub fn new(config: FlycheckConfig, workspace_root: PathBuf) -> Flycheck {
+    /// A check which was started less than `debounce` ago is cancelled by an
+    /// update, instead of being completed before restarting.
+    pub fn new(config: FlycheckConfig, debounce: Duration, workspace_root: PathBuf) -> Flycheck {
         let (task_send, task_recv) = unbounded::<CheckTask>();
         let (cmd_send, cmd_recv) = unbounded::<CheckCommand>();
         let handle = jod_thread::spawn(move || {
-            FlycheckThread::new(config, workspace_root).run(&task_send, &cmd_recv);
+            FlycheckThread::new(config, debounce, workspace_root).run(&task_send, &cmd_recv);
         });
         Flycheck { task_recv, cmd_send, handle }
     }
@@ -85,22 +87,19 @@ pub enum CheckCommand {
 
 struct FlycheckThread {
     config: FlycheckConfig,
+    debounce: Duration,
     workspace_root: PathBuf,
     last_update_req: Option<Instant>,
     // XXX: drop order is significant
     message_recv: Receiver<CheckEvent>,
-    /// WatchThread exists to wrap around the communication needed to be able to
-    /// run `cargo check` without blocking. Currently the Rust standard library
-    /// doesn't provide a way to read sub-process output without blocking, so we
-    /// have to wrap sub-processes output handling in a thread and pass messages
-    /// back over a channel.
-    check_process: Option<jod_thread::JoinHandle<()>>,
+    check_process: Option<CheckProcess>,
 }
 
 impl FlycheckThread {
-    fn new(config: FlycheckConfig, workspace_root: PathBuf) -> FlycheckThread {
+    fn new(config: FlycheckConfig, debounce: Duration, workspace_root: PathBuf) -> FlycheckThread {
         FlycheckThread {
             config,
+            debounce,
             workspace_root,
             last_update_req: None,
             message_recv: never(),
@@ -127,13 +126,19 @@ impl FlycheckThread {
                         // Watcher finished, replace it with a never channel to
                         // avoid busy-waiting.
                         self.message_recv = never();
-                        self.check_process = None;
+                        if let Some(check_process) = self.check_process.take() {
+                            check_process.finish();
+                        }
                     },
                 }
             };
 
             if self.should_recheck() {
                 self.last_update_req = None;
+                if self.check_process.is_some() {
+                    // The cancelled check won't report its end anymore.
+                    task_send.send(CheckTask::Status(Status::End)).unwrap();
+                }
                 task_send.send(CheckTask::ClearDiagnostics).unwrap();
                 self.restart_check_process();
             }
@@ -146,13 +151,19 @@ impl FlycheckThread {
     }
 
     fn should_recheck(&mut self) -> bool {
-        if let Some(_last_update_req) = &self.last_update_req {
-            // We currently only request an update on save, as we need up to
-            // date source on disk for cargo check to do it's magic, so we
-            // don't really need to debounce the requests at this point.
-            return true;
+        let last_update_req = match self.last_update_req {
+            Some(it) => it,
+            None => return false,
+        };
+        match &self.check_process {
+            None => true,
+            // A check started shortly before is most likely outdated, so we
+            // cancel it. Otherwise, the update is delayed until the check
+            // finished, so that repeated saves don't keep cancelling it.
+            Some(check_process) => {
+                last_update_req.saturating_duration_since(check_process.started) < self.debounce
+            }
         }
-        false
     }
 
     fn handle_command(&mut self, cmd: CheckCommand) {
@@ -192,9 +203,11 @@ impl FlycheckThread {
     }
 
     fn restart_check_process(&mut self) {
-        // First, clear and cancel the old thread
+        // First, clear and cancel the old process
         self.message_recv = never();
-        self.check_process = None;
+        if let Some(check_process) = self.check_process.take() {
+            check_process.cancel();
+        }
 
         let mut cmd = match &self.config {
             FlycheckConfig::CargoCommand {
@@ -228,14 +241,24 @@ impl FlycheckThread {
         };
         cmd.current_dir(&self.workspace_root);
 
+        let child = cmd.stdout(Stdio::piped()).stderr(Stdio::null()).stdin(Stdio::null()).spawn();
+        let mut child = match child {
+            Ok(it) => it,
+            Err(err) => {
+                log::error!("Cargo watcher failed to start {:?}: {}", cmd, err);
+                return;
+            }
+        };
+        let stdout = child.stdout.take().unwrap();
+
         let (message_send, message_recv) = unbounded();
         self.message_recv = message_recv;
-        self.check_process = Some(jod_thread::spawn(move || {
+        let thread = jod_thread::spawn(move || {
             // If we trigger an error here, we will do so in the loop instead,
             // which will break out of the loop, and continue the shutdown
             let _ = message_send.send(CheckEvent::Begin);
 
-            let res = run_cargo(cmd, &mut |message| {
+            let read_at_least_one_message = read_cargo_messages(stdout, &mut |message| {
                 // Skip certain kinds of messages to only spend time on what's useful
                 match &message {
                     Message::CompilerArtifact(artifact) if artifact.fresh => return true,
@@ -248,16 +271,65 @@ impl FlycheckThread {
                 message_send.send(CheckEvent::Msg(message)).is_ok()
             });
 
-            if let Err(err) = res {
-                // FIXME: make the `message_send` to be `Sender<Result<CheckEvent, CargoError>>`
-                // to display user-caused misconfiguration errors instead of just logging them here
-                log::error!("Cargo watcher failed {:?}", err);
-            }
-
             // We can ignore any error here, as we are already in the progress
             // of shutting down.
             let _ = message_send.send(CheckEvent::End);
-        }))
+            read_at_least_one_message
+        });
+        self.check_process = Some(CheckProcess { cmd, child, thread, started: Instant::now() });
+    }
+}
+
+impl Drop for FlycheckThread {
+    fn drop(&mut self) {
+        if let Some(check_process) = self.check_process.take() {
+            check_process.cancel();
+        }
+    }
+}
+
+/// A running `cargo check` process.
+struct CheckProcess {
+    cmd: Command,
+    child: Child,
+    /// Currently the Rust standard library doesn't provide a way to read
+    /// sub-process output without blocking, so we have to wrap sub-processes
+    /// output handling in a thread and pass messages back over a channel.
+    thread: jod_thread::JoinHandle<bool>,
+    started: Instant,
+}
+
+impl CheckProcess {
+    /// Kills the process, which also ends the thread reading its output.
+    fn cancel(mut self) {
+        // It is okay to ignore the result, as it only errors if the process is already dead
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        self.thread.join();
+    }
+
+    fn finish(mut self) {
+        let read_at_least_one_message = self.thread.join();
+        // The process is killed if the output stopped being read before it exited.
+        let _ = self.child.kill();
+        let exit_status = match self.child.wait() {
+            Ok(it) => it,
+            Err(err) => {
+                log::error!("Cargo watcher failed {:?}", err);
+                return;
+            }
+        };
+        if !exit_status.success() && !read_at_least_one_message {
+            // FIXME: make the `message_send` to be `Sender<Result<CheckEvent, CargoError>>`
+            // to display user-caused misconfiguration errors instead of just logging them here
+            // FIXME: Read the stderr to display the reason, see `read2()` reference in PR comment:
+            // https://github.com/rust-analyzer/rust-analyzer/pull/3632#discussion_r395605298
+            log::error!(
+                "Cargo watcher failed: the command produced no valid metadata (exit code: {:?}): {:?}",
+                exit_status,
+                self.cmd
+            );
+        }
     }
 }
 
@@ -267,13 +339,11 @@ enum CheckEvent {
     End,
 }
 
-fn run_cargo(
-    mut command: Command,
+/// Returns whether any message was read.
+fn read_cargo_messages(
+    stdout: ChildStdout,
     on_message: &mut dyn FnMut(cargo_metadata::Message) -> bool,
-) -> io::Result<()> {
-    let mut child =
-        command.stdout(Stdio::piped()).stderr(Stdio::null()).stdin(Stdio::null()).spawn()?;
-
+) -> bool {
     // We manually read a line at a time, instead of using serde's
     // stream deserializers, because the deserializer cannot recover
     // from an error, resulting in it getting stuck, because we try to
@@ -282,7 +352,7 @@ fn run_cargo(
     // Because cargo only outputs one JSON object per line, we can
     // simply skip a line if it doesn't parse, which just ignores any
     // erroneus output.
-    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let stdout = BufReader::new(stdout);
     let mut read_at_least_one_message = false;
     for message in cargo_metadata::Message::parse_stream(stdout) {
         let message = match message {
@@ -299,22 +369,5 @@ fn run_cargo(
             break;
         }
     }
-
-    // It is okay to ignore the result, as it only errors if the process is already dead
-    let _ = child.kill();
-
-    let exit_status = child.wait()?;
-    if !exit_status.success() && !read_at_least_one_message {
-        // FIXME: Read the stderr to display the reason, see `read2()` reference in PR comment:
-        // https://github.com/rust-analyzer/rust-analyzer/pull/3632#discussion_r395605298
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!(
-                "the command produced no valid metadata (exit code: {:?}): {:?}",
-                exit_status, command
-            ),
-        ));
-    }
-
-    Ok(())
+    read_at_least_one_message
 }
@@ -655,10 +655,34 @@ impl Function {
         db.function_data(self.id).params.clone()
     }
 
+    pub fn ret_type(self, db: &dyn HirDatabase) -> Type {
+        let resolver = self.id.resolver(db.upcast());
+        let ret_type = &db.function_data(self.id).ret_type;
+        let ctx = hir_ty::TyLoweringContext::new(db, &resolver);
+        let environment = TraitEnvironment::lower(db, &resolver);
+        let ty = Ty::from_hir(&ctx, ret_type);
+        Type {
+            krate: self.id.lookup(db.upcast()).module(db.upcast()).krate,
+            ty: InEnvironment { value: ty, environment },
+        }
+    }
+
     pub fn is_unsafe(self, db: &dyn HirDatabase) -> bool {
         db.function_data(self.id).is_unsafe
     }
 
+    pub fn is_const(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_const
+    }
+
+    /// Checks whether the body only contains operations, which are allowed in
+    /// a `const fn`, including the ones in macro expansions.
+    pub fn is_const_evaluable(self, db: &dyn HirDatabase) -> bool {
+        let body = db.body(self.id.into());
+        let infer = db.infer(self.id.into());
+        hir_ty::expr::non_const_operations(db, &body, &infer).is_empty()
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
         let _p = profile("Function::diagnostics");
         let infer = db.infer(self.id.into());
@@ -811,13 +835,18 @@ impl MacroDef {
 
     /// Indicate it is a proc-macro
     pub fn is_proc_macro(&self) -> bool {
-        matches!(self.id.kind, MacroDefKind::CustomDerive(_))
+        matches!(self.id.kind, MacroDefKind::CustomDerive(_) | MacroDefKind::CustomAttr(_))
     }
 
     /// Indicate it is a derive macro
     pub fn is_derive_macro(&self) -> bool {
         matches!(self.id.kind, MacroDefKind::CustomDerive(_) | MacroDefKind::BuiltInDerive(_))
     }
+
+    /// Indicate it is an attribute macro
+    pub fn is_attr_macro(&self) -> bool {
+        matches!(self.id.kind, MacroDefKind::CustomAttr(_))
+    }
 }
 
 /// Invariant: `inner.as_assoc_item(db).is_some()`
@@ -1143,6 +1172,14 @@ impl Type {
         matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Bool, .. }))
     }
 
+    pub fn is_never(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Never, .. }))
+    }
+
+    pub fn is_reference(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(_), .. }))
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(
             self.ty.value,
@@ -1262,6 +1299,15 @@ impl Type {
         res
     }
 
+    /// Returns the generic arguments of an applied type, e.g. `[T, E]` for
+    /// `Result<T, E>`.
+    pub fn type_parameters(&self) -> Vec<Type> {
+        match &self.ty.value {
+            Ty::Apply(a_ty) => a_ty.parameters.iter().map(|ty| self.derived(ty.clone())).collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn autoderef<'a>(&'a self, db: &'a dyn HirDatabase) -> impl Iterator<Item = Type> + 'a {
         // There should be no inference vars in types passed here
         // FIXME check that?
@@ -113,6 +113,20 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         Some(node)
     }
 
+    /// Expands the attribute macro `attr` applied to `item`.
+    pub fn expand_attr_macro(
+        &self,
+        item: &ast::ModuleItem,
+        attr: &ast::Attr,
+    ) -> Option<SyntaxNode> {
+        let item = self.find_file(item.syntax().clone()).with_value(item);
+        let sa = self.analyze2(item.map(|it| it.syntax()), None);
+        let file_id = sa.expand_attr(self.db, item, attr)?;
+        let node = self.db.parse_or_expand(file_id)?;
+        self.cache(node.clone(), file_id);
+        Some(node)
+    }
+
     pub fn expand_hypothetical(
         &self,
         actual_macro_call: &ast::MacroCall,
@@ -16,7 +16,10 @@ use hir_def::{
     resolver::{resolver_for_scope, Resolver, TypeNs, ValueNs},
     AsMacroCall, DefWithBodyId, FieldId, LocalFieldId, VariantId,
 };
-use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
+use hir_expand::{
+    hygiene::Hygiene, name::AsName, AstId, HirFileId, InFile, MacroCallId, MacroCallKind,
+    MacroDefKind,
+};
 use hir_ty::{
     expr::{record_literal_missing_fields, record_pattern_missing_fields},
     InferenceResult, Substs, Ty,
@@ -313,6 +316,25 @@ impl SourceAnalyzer {
         })?;
         Some(macro_call_id.as_file())
     }
+
+    pub(crate) fn expand_attr(
+        &self,
+        db: &dyn HirDatabase,
+        item: InFile<&ast::ModuleItem>,
+        attr: &ast::Attr,
+    ) -> Option<HirFileId> {
+        let krate = self.resolver.krate()?;
+        let hygiene = Hygiene::new(db.upcast(), item.file_id);
+        let path = ModPath::from_src(attr.path()?, &hygiene)?;
+        let def = self.resolver.resolve_path_as_macro(db.upcast(), &path)?;
+        if !matches!(def.kind, MacroDefKind::CustomAttr(_)) {
+            return None;
+        }
+        let ast_id = AstId::new(item.file_id, db.ast_id_map(item.file_id).ast_id(item.value));
+        let kind = MacroCallKind::Attr(ast_id, path.segments.last()?.to_string());
+        let macro_call_id: MacroCallId = def.as_lazy_macro(db.upcast(), krate, kind).into();
+        Some(macro_call_id.as_file())
+    }
 }
 
 fn scope_for(
@@ -566,7 +566,11 @@ impl ExprCollector<'_> {
                     let type_ref =
                         stmt.ascribed_type().map(|it| TypeRef::from_ast(&self.ctx(), it));
                     let initializer = stmt.initializer().map(|e| self.collect_expr(e));
-                    Statement::Let { pat, type_ref, initializer }
+                    let else_branch = stmt
+                        .let_else()
+                        .and_then(|it| it.block_expr())
+                        .map(|block| self.collect_block(block));
+                    Statement::Let { pat, type_ref, initializer, else_branch }
                 }
                 ast::Stmt::ExprStmt(stmt) => Statement::Expr(self.collect_expr_opt(stmt.expr())),
             })
@@ -35,6 +35,7 @@ pub struct FunctionData {
     /// can be called as a method.
     pub has_self_param: bool,
     pub is_unsafe: bool,
+    pub is_const: bool,
     pub visibility: RawVisibility,
 }
 
@@ -87,13 +88,22 @@ impl FunctionData {
         };
 
         let is_unsafe = src.value.unsafe_token().is_some();
+        let is_const = src.value.const_token().is_some();
 
         let vis_default = RawVisibility::default_for_container(loc.container);
         let visibility =
             RawVisibility::from_ast_with_default(db, vis_default, src.map(|s| s.visibility()));
 
-        let sig =
-            FunctionData { name, params, ret_type, has_self_param, is_unsafe, visibility, attrs };
+        let sig = FunctionData {
+            name,
+            params,
+            ret_type,
+            has_self_param,
+            is_unsafe,
+            is_const,
+            visibility,
+            attrs,
+        };
         Arc::new(sig)
     }
 }
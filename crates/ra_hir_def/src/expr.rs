@@ -216,7 +216,13 @@ pub struct RecordLitField {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Statement {
-    Let { pat: PatId, type_ref: Option<TypeRef>, initializer: Option<ExprId> },
+    Let {
+        pat: PatId,
+        type_ref: Option<TypeRef>,
+        initializer: Option<ExprId>,
+        /// The diverging block of a `let ... else { ... };` statement.
+        else_branch: Option<ExprId>,
+    },
     Expr(ExprId),
 }
 
@@ -235,10 +241,13 @@ impl Expr {
             Expr::Block { statements, tail, .. } => {
                 for stmt in statements {
                     match stmt {
-                        Statement::Let { initializer, .. } => {
+                        Statement::Let { initializer, else_branch, .. } => {
                             if let Some(expr) = initializer {
                                 f(*expr);
                             }
+                            if let Some(expr) = else_branch {
+                                f(*expr);
+                            }
                         }
                         Statement::Expr(e) => f(*e),
                     }
@@ -11,7 +11,7 @@ use hir_expand::{
     HirFileId, MacroCallId, MacroDefId, MacroDefKind,
 };
 use ra_cfg::CfgOptions;
-use ra_db::{CrateId, FileId, ProcMacroId};
+use ra_db::{CrateId, FileId, ProcMacroId, ProcMacroKind};
 use ra_syntax::ast;
 use rustc_hash::FxHashMap;
 use test_utils::mark;
@@ -60,7 +60,8 @@ pub(super) fn collect_defs(db: &dyn DefDatabase, mut def_map: CrateDefMap) -> Cr
         .map(|(idx, it)| {
             // FIXME: a hacky way to create a Name from string.
             let name = tt::Ident { text: it.name.clone(), id: tt::TokenId::unspecified() };
-            (name.as_name(), ProcMacroExpander::new(def_map.krate, ProcMacroId(idx as u32)))
+            let expander = ProcMacroExpander::new(def_map.krate, ProcMacroId(idx as u32));
+            (name.as_name(), it.kind, expander)
         })
         .collect();
 
@@ -134,7 +135,7 @@ struct DefCollector<'a> {
     unexpanded_attribute_macros: Vec<DeriveDirective>,
     mod_dirs: FxHashMap<LocalModuleId, ModDir>,
     cfg_options: &'a CfgOptions,
-    proc_macros: Vec<(Name, ProcMacroExpander)>,
+    proc_macros: Vec<(Name, ProcMacroKind, ProcMacroExpander)>,
 }
 
 impl DefCollector<'_> {
@@ -197,15 +198,17 @@ impl DefCollector<'_> {
 
     fn collect_proc_macro(&mut self) {
         let proc_macros = std::mem::take(&mut self.proc_macros);
-        for (name, expander) in proc_macros {
+        for (name, kind, expander) in proc_macros {
             let krate = self.def_map.krate;
 
-            let macro_id = MacroDefId {
-                ast_id: None,
-                krate: Some(krate),
-                kind: MacroDefKind::CustomDerive(expander),
-                local_inner: false,
+            let kind = match kind {
+                ProcMacroKind::CustomDerive => MacroDefKind::CustomDerive(expander),
+                ProcMacroKind::Attr => MacroDefKind::CustomAttr(expander),
+                // FIXME: Support function-like proc macros.
+                ProcMacroKind::FuncLike => continue,
             };
+            let macro_id =
+                MacroDefId { ast_id: None, krate: Some(krate), kind, local_inner: false };
 
             self.define_proc_macro(name.clone(), macro_id);
         }
@@ -639,7 +642,11 @@ impl DefCollector<'_> {
             BuiltinShadowMode::Module,
         );
 
-        resolved_res.resolved_def.take_macros()
+        // Only derives are collected here, attribute macros are expanded on demand.
+        resolved_res
+            .resolved_def
+            .take_macros()
+            .filter(|it| !matches!(it.kind, MacroDefKind::CustomAttr(_)))
     }
 
     fn collect_macro_expansion(
@@ -10,8 +10,8 @@ use ra_syntax::{algo::diff, AstNode, Parse, SyntaxKind::*, SyntaxNode};
 
 use crate::{
     ast_id_map::AstIdMap, BuiltinDeriveExpander, BuiltinFnLikeExpander, EagerCallLoc, EagerMacroId,
-    HirFileId, HirFileIdRepr, LazyMacroId, MacroCallId, MacroCallLoc, MacroDefId, MacroDefKind,
-    MacroFile, ProcMacroExpander,
+    HirFileId, HirFileIdRepr, LazyMacroId, MacroCallId, MacroCallKind, MacroCallLoc, MacroDefId,
+    MacroDefKind, MacroFile, ProcMacroExpander,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -142,7 +142,7 @@ pub(crate) fn macro_def(
             Some(Arc::new((TokenExpander::BuiltinDerive(expander), mbe::TokenMap::default())))
         }
         MacroDefKind::BuiltInEager(_) => None,
-        MacroDefKind::CustomDerive(expander) => {
+        MacroDefKind::CustomDerive(expander) | MacroDefKind::CustomAttr(expander) => {
             Some(Arc::new((TokenExpander::ProcMacro(expander), mbe::TokenMap::default())))
         }
     }
@@ -242,12 +242,16 @@ pub(crate) fn expand_proc_macro(
         }
     };
 
-    let expander = match loc.def.kind {
-        MacroDefKind::CustomDerive(expander) => expander,
-        _ => unreachable!(),
-    };
-
-    expander.expand(db, lazy_id, &macro_arg.0)
+    match (loc.def.kind, &loc.kind) {
+        (MacroDefKind::CustomDerive(expander), _) => expander.expand(db, lazy_id, &macro_arg.0),
+        (MacroDefKind::CustomAttr(expander), MacroCallKind::Attr(_, attr_name)) => {
+            expander.expand_attr(db, attr_name, &macro_arg.0)
+        }
+        _ => Err(tt::ExpansionError::Unknown(
+            "attribute macro used as a function-like macro".to_string(),
+        )
+        .into()),
+    }
 }
 
 pub(crate) fn parse_or_expand(db: &dyn AstDatabase, file_id: HirFileId) -> Option<SyntaxNode> {
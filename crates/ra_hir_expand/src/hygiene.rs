@@ -34,6 +34,7 @@ impl Hygiene {
                         MacroDefKind::BuiltInDerive(_) => (None, false),
                         MacroDefKind::BuiltInEager(_) => (None, false),
                         MacroDefKind::CustomDerive(_) => (None, false),
+                        MacroDefKind::CustomAttr(_) => (None, false),
                     }
                 }
                 MacroCallId::EagerMacro(_id) => (None, false),
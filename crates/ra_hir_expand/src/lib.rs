@@ -88,6 +88,11 @@ impl HirFileId {
         }
     }
 
+    /// Checks whether this is the file of a macro expansion.
+    pub fn is_macro(self) -> bool {
+        matches!(self.0, HirFileIdRepr::MacroFile(_))
+    }
+
     /// If this is a macro call, returns the syntax node of the call.
     pub fn call_node(self, db: &dyn db::AstDatabase) -> Option<InFile<SyntaxNode>> {
         match self.0 {
@@ -227,6 +232,7 @@ pub enum MacroDefKind {
     BuiltInDerive(BuiltinDeriveExpander),
     BuiltInEager(EagerExpander),
     CustomDerive(ProcMacroExpander),
+    CustomAttr(ProcMacroExpander),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
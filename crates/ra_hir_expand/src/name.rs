@@ -162,6 +162,7 @@ pub mod known {
         ops,
         future,
         result,
+        option,
         boxed,
         // Components of known path (type name)
         IntoIterator,
@@ -170,6 +171,7 @@ pub mod known {
         Ok,
         Future,
         Result,
+        Option,
         Output,
         Target,
         Box,
@@ -42,6 +42,25 @@ impl ProcMacroExpander {
 
         proc_macro.expander.expand(&tt, None).map_err(mbe::ExpandError::from)
     }
+
+    /// Expands the attribute macro `#[attr_name(...)]` applied to the item `tt`.
+    pub fn expand_attr(
+        self,
+        db: &dyn AstDatabase,
+        attr_name: &str,
+        tt: &tt::Subtree,
+    ) -> Result<tt::Subtree, mbe::ExpandError> {
+        let krate_graph = db.crate_graph();
+        let proc_macro = krate_graph[self.krate]
+            .proc_macro
+            .get(self.proc_macro_id.0 as usize)
+            .ok_or_else(|| err!("No attribute macro found."))?;
+
+        let (attr, item) = remove_attr(tt, attr_name)
+            .ok_or_else(|| err!("Fail to remove attribute for attribute macro"))?;
+
+        proc_macro.expander.expand(&item, Some(&attr)).map_err(mbe::ExpandError::from)
+    }
 }
 
 fn eat_punct(cursor: &mut Cursor, c: char) -> bool {
@@ -100,6 +119,51 @@ fn remove_derive_attrs(tt: &tt::Subtree) -> Option<tt::Subtree> {
     Some(result)
 }
 
+/// Removes the first `#[attr_name(...)]` attribute from `tt`, and returns the
+/// arguments of the attribute together with the remaining item.
+fn remove_attr(tt: &tt::Subtree, attr_name: &str) -> Option<(tt::Subtree, tt::Subtree)> {
+    let buffer = TokenBuffer::new(&tt.token_trees);
+    let mut p = buffer.begin();
+    let mut attr = None;
+    let mut result = tt::Subtree::default();
+
+    while !p.eof() {
+        let curr = p;
+
+        if attr.is_none() && eat_punct(&mut p, '#') {
+            let parent = p;
+            if eat_subtree(&mut p, tt::DelimiterKind::Bracket) {
+                let mut last_ident = None;
+                while let Some(tt::TokenTree::Leaf(leaf)) = p.token_tree() {
+                    match leaf {
+                        tt::Leaf::Ident(ident) => last_ident = Some(ident.text.clone()),
+                        tt::Leaf::Punct(punct) if punct.char == ':' => (),
+                        _ => break,
+                    }
+                    p = p.bump();
+                }
+                if last_ident.as_deref() == Some(attr_name) {
+                    let args = match p.token_tree() {
+                        Some(tt::TokenTree::Subtree(subtree)) => tt::Subtree {
+                            delimiter: None,
+                            token_trees: subtree.token_trees.clone(),
+                        },
+                        _ => tt::Subtree::default(),
+                    };
+                    attr = Some(args);
+                    p = parent.bump();
+                    continue;
+                }
+            }
+        }
+
+        result.token_trees.push(curr.token_tree()?.clone());
+        p = curr.bump();
+    }
+
+    Some((attr?, result))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -136,6 +200,47 @@ SUBTREE $
     IDENT   bar 18
     PUNCH   : [alone] 19
     IDENT   u32 20
+"#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn test_remove_attr() {
+        let tt = mbe::parse_to_token_tree(
+            r#"
+    #[allow(unused)]
+    #[tracing::instrument(level = "debug")]
+    fn foo() {}
+"#,
+        )
+        .unwrap()
+        .0;
+        let (attr, item) = remove_attr(&tt, "instrument").unwrap();
+
+        assert_eq_text!(
+            &format!("{:#?}", attr),
+            r#"
+SUBTREE $
+  IDENT   level 12
+  PUNCH   = [alone] 13
+  LITERAL "debug" 14
+"#
+            .trim()
+        );
+        assert_eq_text!(
+            &format!("{:#?}", item),
+            r#"
+SUBTREE $
+  PUNCH   # [alone] 0
+  SUBTREE [] 1
+    IDENT   allow 2
+    SUBTREE () 3
+      IDENT   unused 4
+  IDENT   fn 15
+  IDENT   foo 16
+  SUBTREE () 17
+  SUBTREE {} 18
 "#
             .trim()
         );
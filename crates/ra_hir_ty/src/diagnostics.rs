@@ -132,6 +132,63 @@ impl AstDiagnostic for MissingOkInTailExpr {
     }
 }
 
+#[derive(Debug)]
+pub struct MissingFlatten {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+}
+
+impl Diagnostic for MissingFlatten {
+    fn message(&self) -> String {
+        "flatten nested Option with `.flatten()`".to_string()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for MissingFlatten {
+    type AST = ast::Expr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.file).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::Expr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct NonConstOperation {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub description: String,
+}
+
+impl Diagnostic for NonConstOperation {
+    fn message(&self) -> String {
+        format!("{} is not allowed in a const fn", self.description)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for NonConstOperation {
+    type AST = ast::Expr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.file).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::Expr::cast(node).unwrap()
+    }
+}
+
 #[derive(Debug)]
 pub struct BreakOutsideOfLoop {
     pub file: HirFileId,
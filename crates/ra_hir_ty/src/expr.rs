@@ -9,9 +9,12 @@ use rustc_hash::FxHashSet;
 
 use crate::{
     db::HirDatabase,
-    diagnostics::{MissingFields, MissingMatchArms, MissingOkInTailExpr, MissingPatFields},
+    diagnostics::{
+        MissingFields, MissingFlatten, MissingMatchArms, MissingOkInTailExpr, MissingPatFields,
+        NonConstOperation,
+    },
     utils::variant_data,
-    ApplicationTy, InferenceResult, Ty, TypeCtor,
+    ApplicationTy, CallableDef, InferenceResult, Ty, TypeCtor,
     _match::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
 };
 
@@ -76,6 +79,10 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         if let Expr::Block { tail: Some(t), .. } = body_expr {
             self.validate_results_in_tail_expr(body.body_expr, *t, db);
         }
+        self.validate_nested_options(&body, db);
+        if db.function_data(self.func).is_const {
+            self.validate_const_fn(&body, db);
+        }
     }
 
     fn create_record_literal_missing_fields_diagnostic(
@@ -251,6 +258,131 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
     }
+
+    fn validate_nested_options(&mut self, body: &Body, db: &dyn HirDatabase) {
+        let core_option_path = path![core::option::Option];
+
+        let resolver = self.func.resolver(db.upcast());
+        let core_option_enum = match resolver.resolve_known_enum(db.upcast(), &core_option_path) {
+            Some(it) => it,
+            _ => return,
+        };
+
+        let core_option_ctor = TypeCtor::Adt(AdtId::EnumId(core_option_enum));
+        let option_param = |ty: &Ty| match ty {
+            Ty::Apply(ApplicationTy { ctor, parameters })
+                if ctor == &core_option_ctor && parameters.len() == 1 =>
+            {
+                Some(parameters[0].clone())
+            }
+            _ => None,
+        };
+
+        let mut exprs = FxHashSet::default();
+        for (id, mismatch) in self.infer.type_mismatches.iter() {
+            if option_param(&mismatch.expected).is_none()
+                || option_param(&mismatch.actual).as_ref() != Some(&mismatch.expected)
+            {
+                continue;
+            }
+            // Mismatches of a block are reported on the whole block.
+            let mut id = id;
+            while let Expr::Block { tail: Some(tail), .. } = &body[id] {
+                id = *tail;
+            }
+            exprs.insert(id);
+        }
+        if exprs.is_empty() {
+            return;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for id in exprs {
+            if let Ok(source_ptr) = source_map.expr_syntax(id) {
+                self.sink.push(MissingFlatten { file: source_ptr.file_id, expr: source_ptr.value });
+            }
+        }
+    }
+
+    fn validate_const_fn(&mut self, body: &Body, db: &dyn HirDatabase) {
+        let operations = non_const_operations(db, body, &self.infer);
+        if operations.is_empty() {
+            return;
+        }
+
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for (id, description) in operations {
+            if let Ok(source_ptr) = source_map.expr_syntax(id) {
+                // Expansions of macros, like `panic!`, are not checked.
+                if source_ptr.file_id.is_macro() {
+                    continue;
+                }
+                self.sink.push(NonConstOperation {
+                    file: source_ptr.file_id,
+                    expr: source_ptr.value,
+                    description,
+                });
+            }
+        }
+    }
+}
+
+/// Finds the operations in `body`, which can't be evaluated at compile time,
+/// together with their descriptions.
+pub fn non_const_operations(
+    db: &dyn HirDatabase,
+    body: &Body,
+    infer: &InferenceResult,
+) -> Vec<(ExprId, String)> {
+    // Closures are not called while evaluating the function, so their
+    // bodies are not checked.
+    let mut exprs = Vec::new();
+    let mut stack = vec![body.body_expr];
+    while let Some(id) = stack.pop() {
+        if let Expr::Lambda { .. } = &body[id] {
+            continue;
+        }
+        exprs.push(id);
+        body[id].walk_child_exprs(|it| stack.push(it));
+    }
+
+    let mut operations = Vec::new();
+    for id in exprs {
+        let description = match &body[id] {
+            Expr::Call { callee, .. } => match &infer[*callee] {
+                Ty::Apply(ApplicationTy {
+                    ctor: TypeCtor::FnDef(CallableDef::FunctionId(func)),
+                    ..
+                }) => non_const_call(db, *func),
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, .. }) => {
+                    Some("call to a function pointer".to_string())
+                }
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { .. }, .. }) => {
+                    Some("call to a closure".to_string())
+                }
+                _ => None,
+            },
+            Expr::MethodCall { .. } => {
+                infer.method_resolution(id).and_then(|func| non_const_call(db, func))
+            }
+            Expr::For { .. } => Some("`for` loop".to_string()),
+            Expr::Try { .. } => Some("`?` operator".to_string()),
+            _ => None,
+        };
+        if let Some(description) = description {
+            operations.push((id, description));
+        }
+    }
+    operations
+}
+
+fn non_const_call(db: &dyn HirDatabase, func: FunctionId) -> Option<String> {
+    let data = db.function_data(func);
+    if data.is_const {
+        None
+    } else {
+        Some(format!("call to non-const fn `{}`", data.name))
+    }
 }
 
 pub fn record_literal_missing_fields(
@@ -259,6 +259,12 @@ impl<'a> InferenceContext<'a> {
             let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
             *ty = resolved;
         }
+        for mismatch in result.type_mismatches.values_mut() {
+            let expected = mem::replace(&mut mismatch.expected, Ty::Unknown);
+            mismatch.expected = self.table.resolve_ty_completely(expected);
+            let actual = mem::replace(&mut mismatch.actual, Ty::Unknown);
+            mismatch.actual = self.table.resolve_ty_completely(actual);
+        }
         result
     }
 
@@ -1,7 +1,7 @@
 use insta::assert_snapshot;
 use test_utils::mark;
 
-use super::{infer, infer_with_mismatches};
+use super::{infer, infer_with_mismatches, type_at};
 
 #[test]
 fn infer_pattern() {
@@ -570,3 +570,35 @@ fn test() {
     "###
     );
 }
+
+#[test]
+fn infer_let_else() {
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Option<T> { None, Some(T) }
+
+fn test(opt: Option<u32>, fallback: i64) {
+    let Option::Some(it) = opt else {
+        let f = fallback;
+        f<|>;
+        return;
+    };
+}
+"#,
+    );
+    assert_eq!(t, "i64");
+
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Option<T> { None, Some(T) }
+
+fn test(opt: Option<u32>) {
+    let Option::Some(it) = opt else { return };
+    it<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
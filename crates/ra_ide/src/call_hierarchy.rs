@@ -7,7 +7,7 @@ use ra_ide_db::RootDatabase;
 use ra_syntax::{ast, match_ast, AstNode, TextRange};
 
 use crate::{
-    call_info::FnCallNode, display::ToNav, goto_definition, references, FilePosition,
+    call_info::FnCallNode, display::ToNav, goto_definition, references, FilePosition, FileRange,
     NavigationTarget, RangeInfo,
 };
 
@@ -123,6 +123,36 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
     Some(calls.into_items())
 }
 
+/// Returns the references to the function at `position` which call it.
+pub(crate) fn call_sites(db: &RootDatabase, position: FilePosition) -> Option<Vec<FileRange>> {
+    let sema = Semantics::new(db);
+    let refs = references::find_all_refs(db, position, None)?;
+
+    let call_sites = refs
+        .info
+        .references()
+        .iter()
+        .map(|reference| reference.file_range)
+        .filter(|file_range| {
+            let file = sema.parse(file_range.file_id);
+            let name_ref = match sema.find_node_at_offset_with_descend::<ast::NameRef>(
+                file.syntax(),
+                file_range.range.start(),
+            ) {
+                Some(it) => it,
+                None => return false,
+            };
+            name_ref
+                .syntax()
+                .ancestors()
+                .filter_map(|node| FnCallNode::with_node_exact(&node))
+                .filter_map(|call_node| call_node.name_ref())
+                .any(|it| it == name_ref)
+        })
+        .collect();
+    Some(call_sites)
+}
+
 #[derive(Default)]
 struct CallLocations {
     funcs: IndexMap<NavigationTarget, Vec<TextRange>>,
@@ -355,4 +385,26 @@ mod tests {
             &["caller3 FN_DEF FileId(1) 64..80 67..74 : [51..58]"],
         );
     }
+
+    #[test]
+    fn test_call_sites_skip_non_call_references() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            struct S;
+            impl S {
+                fn helper(&self) {}
+            }
+            fn cal<|>lee() {}
+            fn caller(s: S) {
+                callee();
+                let f = callee;
+                s.helper(callee());
+            }
+            "#,
+        );
+        let call_sites = analysis.call_sites(pos).unwrap().unwrap();
+        let ranges: Vec<_> = call_sites.iter().map(|it| it.range).collect();
+        assert_eq!(format!("{:?}", ranges), "[82..88, 125..131]");
+    }
 }
@@ -1,5 +1,10 @@
 //! Complete fields in record literals and patterns.
-use crate::completion::{CompletionContext, Completions};
+use hir::{ModuleDef, ScopeDef, Trait};
+use stdx::SepBy;
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
 
 pub(super) fn complete_record(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
     let missing_fields = match (ctx.record_pat_syntax.as_ref(), ctx.record_lit_syntax.as_ref()) {
@@ -9,6 +14,9 @@ pub(super) fn complete_record(acc: &mut Completions, ctx: &CompletionContext) ->
         (_, Some(record_lit)) => ctx.sema.record_literal_missing_fields(record_lit),
     };
 
+    if ctx.record_lit_syntax.is_some() {
+        complete_default_fields(acc, ctx, &missing_fields);
+    }
     for (field, ty) in missing_fields {
         acc.add_field(ctx, field, &ty)
     }
@@ -16,6 +24,53 @@ pub(super) fn complete_record(acc: &mut Completions, ctx: &CompletionContext) ->
     Some(())
 }
 
+/// Offers to initialize all missing fields of a record literal with
+/// `Default::default()`, if each of their types implements `Default`.
+fn complete_default_fields(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    missing_fields: &[(hir::Field, hir::Type)],
+) -> Option<()> {
+    if missing_fields.is_empty() {
+        return None;
+    }
+    let default_trait = core_default_trait(ctx)?;
+    if !missing_fields.iter().all(|(_, ty)| ty.impls_trait(ctx.db, default_trait, &[])) {
+        return None;
+    }
+    let fields = missing_fields
+        .iter()
+        .map(|(field, _)| format!("{}: Default::default()", field.name(ctx.db)))
+        .sep_by(", ")
+        .to_string();
+    CompletionItem::new(
+        CompletionKind::Magic,
+        ctx.source_range(),
+        "Fill remaining with `Default::default()`",
+    )
+    .kind(CompletionItemKind::Snippet)
+    .insert_text(fields)
+    .add_to(acc);
+    Some(())
+}
+
+fn core_default_trait(ctx: &CompletionContext) -> Option<Trait> {
+    let core = ctx
+        .krate?
+        .dependencies(ctx.db)
+        .into_iter()
+        .find(|dep| dep.name.to_string() == "core")?
+        .krate;
+    let default_module = core
+        .root_module(ctx.db)?
+        .children(ctx.db)
+        .find(|it| it.name(ctx.db).map_or(false, |name| name.to_string() == "default"))?;
+    default_module.scope(ctx.db, None).into_iter().find_map(|(name, def)| match def {
+        ScopeDef::ModuleDef(ModuleDef::Trait(it)) if name.to_string() == "Default" => Some(it),
+        _ => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     mod record_pat_tests {
@@ -402,5 +457,54 @@ mod tests {
         ]
         "###);
         }
+
+        #[test]
+        fn fill_remaining_fields_with_default() {
+            let completions = do_completion(
+                r"
+            //- /lib.rs
+            struct S { foo: u32, bar: Vec<u32>, baz: u32 }
+            struct Vec<T>(T);
+            impl<T> core::default::Default for Vec<T> {}
+            impl core::default::Default for u32 {}
+            fn main() {
+                let s = S { foo: 1, <|> };
+            }
+            //- /core/lib.rs
+            pub mod default { pub trait Default {} }
+            ",
+                CompletionKind::Magic,
+            );
+            assert_debug_snapshot!(completions, @r###"
+        [
+            CompletionItem {
+                label: "Fill remaining with `Default::default()`",
+                source_range: 185..185,
+                delete: 185..185,
+                insert: "bar: Default::default(), baz: Default::default()",
+                kind: Snippet,
+            },
+        ]
+        "###);
+        }
+
+        #[test]
+        fn no_fill_with_default_for_non_default_fields() {
+            let completions = do_completion(
+                r"
+            //- /lib.rs
+            struct S { foo: u32, bar: NoDefault }
+            struct NoDefault;
+            impl core::default::Default for u32 {}
+            fn main() {
+                let s = S { <|> };
+            }
+            //- /core/lib.rs
+            pub mod default { pub trait Default {} }
+            ",
+                CompletionKind::Magic,
+            );
+            assert!(completions.is_empty());
+        }
     }
 }
@@ -8,28 +8,61 @@ use std::cell::RefCell;
 
 use hir::{
     diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink},
-    Semantics,
+    ModuleDef, ModuleSource, Semantics,
 };
 use itertools::Itertools;
+use ra_assists::utils::is_postfix_receiver;
 use ra_db::{RelativePath, SourceDatabase, SourceDatabaseExt};
-use ra_ide_db::RootDatabase;
+use ra_ide_db::{defs::Definition, RootDatabase};
 use ra_prof::profile;
 use ra_syntax::{
     algo,
-    ast::{self, make, AstNode},
-    SyntaxNode, TextRange, T,
+    ast::{
+        self, edit::IndentLevel, make, ArgListOwner, AstNode, AstToken, AttrsOwner, HasStringValue,
+        ModuleItemOwner, NameOwner,
+    },
+    match_ast, NodeOrToken, SourceFile,
+    SyntaxKind::*,
+    SyntaxNode, TextRange, TextSize, T,
 };
 use ra_text_edit::{TextEdit, TextEditBuilder};
 
-use crate::{Diagnostic, FileId, FileSystemEdit, Fix, SourceFileEdit};
+use crate::{
+    feature_gated_items::feature_gated_items, Diagnostic, FileId, FileRange, FileSystemEdit, Fix,
+    SourceFileEdit,
+};
 
 #[derive(Debug, Copy, Clone)]
 pub enum Severity {
     Error,
+    Warning,
     WeakWarning,
 }
 
-pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic> {
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiagnosticsConfig {
+    /// The preferred way to clone an `Arc` or an `Rc`, if it should be checked.
+    pub rc_clone_style: Option<RcCloneStyle>,
+    /// Whether to warn about calls of the `todo`, `unimplemented` and
+    /// `unreachable` macros.
+    pub warn_on_todo: bool,
+    /// Whether to warn about `\\` separators in relative paths.
+    pub warn_on_windows_paths: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RcCloneStyle {
+    /// `Arc::clone(&x)`, which makes it obvious that only the pointer is cloned.
+    Explicit,
+    /// `x.clone()`
+    Method,
+}
+
+pub(crate) fn diagnostics(
+    db: &RootDatabase,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+) -> Vec<Diagnostic> {
     let _p = profile("diagnostics");
     let sema = Semantics::new(db);
     let parse = db.parse(file_id);
@@ -42,10 +75,33 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
         fix: None,
     }));
 
-    for node in parse.tree().syntax().descendants() {
+    // Registering the file in `sema` allows to resolve its nodes.
+    for node in sema.parse(file_id).syntax().descendants() {
         check_unnecessary_braces_in_use_statement(&mut res, file_id, &node);
         check_struct_shorthand_initialization(&mut res, file_id, &node);
+        check_test_module_without_cfg(&mut res, &sema, file_id, &node);
+        if let Some(style) = config.rc_clone_style {
+            check_rc_clone_style(&mut res, &sema, style, file_id, &node);
+        }
+        if config.warn_on_todo {
+            check_todo_macro(&mut res, &node);
+        }
+        if config.warn_on_windows_paths {
+            check_windows_path_literal(&mut res, file_id, &node);
+        }
     }
+    let frange = FileRange { file_id, range: parse.tree().syntax().text_range() };
+    res.extend(feature_gated_items(db, frange).into_iter().map(|item| Diagnostic {
+        range: item.range,
+        message: format!(
+            "`{}` is disabled, enable feature `{}` of `{}` to use it",
+            item.name,
+            item.features.join("`, `"),
+            item.dependency,
+        ),
+        severity: Severity::Error,
+        fix: None,
+    }));
     let res = RefCell::new(res);
     let mut sink = DiagnosticSink::new(|d| {
         res.borrow_mut().push(Diagnostic {
@@ -123,12 +179,158 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             severity: Severity::Error,
             fix: Some(fix),
         })
+    })
+    .on::<hir::diagnostics::MissingFlatten, _>(|d| {
+        let node = d.ast(db);
+        let receiver = |expr: &ast::Expr| {
+            if is_postfix_receiver(expr) {
+                expr.syntax().to_string()
+            } else {
+                format!("({})", expr.syntax())
+            }
+        };
+        let replacement = match &node {
+            // The nested option can't be moved out of a reference.
+            ast::Expr::PrefixExpr(prefix) if prefix.op_kind() == Some(ast::PrefixOp::Deref) => {
+                match prefix.expr() {
+                    Some(inner) => format!("{}.clone().flatten()", receiver(&inner)),
+                    None => return,
+                }
+            }
+            _ => format!("{}.flatten()", receiver(&node)),
+        };
+        let edit = TextEdit::replace(node.syntax().text_range(), replacement);
+        let source_change = SourceFileEdit { file_id, edit }.into();
+        let fix = Fix::new("Add `.flatten()`", source_change);
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix: Some(fix),
+        })
+    })
+    .on::<hir::diagnostics::NonConstOperation, _>(|d| {
+        let node = d.ast(db);
+        let fix = node
+            .syntax()
+            .ancestors()
+            .find_map(ast::FnDef::cast)
+            .and_then(|it| it.const_token())
+            .map(|const_token| {
+                let end = match const_token.next_token() {
+                    Some(it) if it.kind() == WHITESPACE => it.text_range().end(),
+                    _ => const_token.text_range().end(),
+                };
+                let range = TextRange::new(const_token.text_range().start(), end);
+                let source_change =
+                    SourceFileEdit { file_id, edit: TextEdit::delete(range) }.into();
+                Fix::new("Remove `const`", source_change)
+            });
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
     });
-    if let Some(m) = sema.to_module_def(file_id) {
+    let module = sema.to_module_def(file_id);
+    if let Some(m) = module {
         m.diagnostics(db, &mut sink);
     };
     drop(sink);
-    res.into_inner()
+    let mut res = res.into_inner();
+    if module.is_none() {
+        check_unlinked_file(&mut res, &sema, file_id);
+    }
+    res
+}
+
+/// Checks whether the file is missing a `mod` declaration, which would make it
+/// a module of the parent module in the same or in the enclosing directory.
+fn check_unlinked_file(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+) -> Option<()> {
+    let db = sema.db;
+    let source_root = db.source_root(db.file_source_root(file_id));
+    if source_root.is_library {
+        return None;
+    }
+    let path = db.file_relative_path(file_id);
+    if path.extension() != Some("rs") {
+        return None;
+    }
+    let root = RelativePath::new("");
+    let (name, dir) = match path.file_stem()? {
+        "lib" | "main" => return None,
+        // `foo/mod.rs` is declared like `foo.rs`.
+        "mod" => {
+            let dir = path.parent()?;
+            (dir.file_name()?, dir.parent().unwrap_or(root))
+        }
+        name => (name, path.parent().unwrap_or(root)),
+    };
+    let is_ident = name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit());
+    if !is_ident {
+        return None;
+    }
+
+    // The modules in `dir` are declared by `dir.rs` or `dir/mod.rs`, or by the
+    // crate root, if it's located in `dir`.
+    let mut candidates = vec![dir.join("mod.rs"), dir.join("lib.rs"), dir.join("main.rs")];
+    if let (Some(dir_name), Some(parent)) = (dir.file_name(), dir.parent()) {
+        candidates.push(parent.join(format!("{}.rs", dir_name)));
+    }
+    let parent_file = candidates.iter().find_map(|candidate| {
+        let file_id = source_root.file_by_relative_path(candidate)?;
+        let module = sema.to_module_def(file_id)?;
+        let is_crate_root = module.parent(db).is_none();
+        if candidate.file_stem() != Some("mod") && candidate.parent() == Some(dir) && !is_crate_root
+        {
+            return None;
+        }
+        Some(file_id)
+    })?;
+    let parent_source = sema.parse(parent_file);
+    // The declaration might exist, but be disabled by a `cfg` attribute.
+    let declared = parent_source
+        .syntax()
+        .descendants()
+        .filter_map(ast::Module::cast)
+        .any(|it| it.name().map_or(false, |it| it.text() == name));
+    if declared {
+        return None;
+    }
+
+    let mod_decl = format!("mod {};", name);
+    let last_mod_decl = parent_source
+        .items()
+        .filter_map(|item| match item {
+            ast::ModuleItem::Module(it) if it.semicolon_token().is_some() => Some(it),
+            _ => None,
+        })
+        .last();
+    let edit = match (last_mod_decl, parent_source.items().next()) {
+        (Some(it), _) => {
+            TextEdit::insert(it.syntax().text_range().end(), format!("\n{}", mod_decl))
+        }
+        (None, Some(it)) => {
+            TextEdit::insert(it.syntax().text_range().start(), format!("{}\n\n", mod_decl))
+        }
+        (None, None) => {
+            TextEdit::insert(parent_source.syntax().text_range().end(), format!("{}\n", mod_decl))
+        }
+    };
+    let label = format!("Add `{}` to `{}`", mod_decl, db.file_relative_path(parent_file));
+    acc.push(Diagnostic {
+        range: TextRange::empty(TextSize::from(0)),
+        message: "file is not included in the module tree".to_string(),
+        severity: Severity::Warning,
+        fix: Some(Fix::new(label, SourceFileEdit { file_id: parent_file, edit }.into())),
+    });
+    Some(())
 }
 
 fn check_unnecessary_braces_in_use_statement(
@@ -208,14 +410,509 @@ fn check_struct_shorthand_initialization(
     Some(())
 }
 
+fn check_todo_macro(acc: &mut Vec<Diagnostic>, node: &SyntaxNode) -> Option<()> {
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    let path = macro_call.path()?;
+    if let Some(qualifier) = path.qualifier() {
+        if !matches!(qualifier.syntax().text().to_string().as_str(), "std" | "core") {
+            return None;
+        }
+    }
+    let name = path.segment()?.name_ref()?;
+    let message = match name.text().as_str() {
+        "todo" | "unimplemented" => format!("unfinished code: `{}!()`", name),
+        "unreachable" => "`unreachable!()` panics if it is reached".to_string(),
+        _ => return None,
+    };
+    acc.push(Diagnostic {
+        range: macro_call.syntax().text_range(),
+        message,
+        severity: Severity::Warning,
+        fix: None,
+    });
+    Some(())
+}
+
+fn check_windows_path_literal(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let literal = ast::Literal::cast(node.clone())?;
+    let token = literal.token();
+    let (contents, separator) = if let Some(it) = ast::String::cast(token.clone()) {
+        (it.value()?, "\\\\")
+    } else if let Some(it) = ast::RawString::cast(token.clone()) {
+        (it.value()?, "\\")
+    } else {
+        return None;
+    };
+    if !is_relative_windows_path(&contents) || is_windows_only(node) {
+        return None;
+    }
+
+    // Only the separators change, as `Path::new(..)` would break uses of the
+    // literal as a `&str`, and `AsRef<Path>` arguments accept it as is.
+    let text = token.text().replace(separator, "/");
+    let range = literal.syntax().text_range();
+    let edit = TextEdit::replace(range, text);
+    acc.push(Diagnostic {
+        range,
+        message: "`\\` path separators may not work on platforms other than Windows".to_string(),
+        severity: Severity::WeakWarning,
+        fix: Some(Fix::new("Use `/` path separators", SourceFileEdit { file_id, edit }.into())),
+    });
+    Some(())
+}
+
+/// Checks whether `s` looks like a relative Windows path, like
+/// `assets\\logo.png`. Absolute paths, like `C:\\Temp`, are platform-specific
+/// anyway.
+///
+/// Strings with regex escapes, like `\\d` or `\\.`, aren't paths, so hidden
+/// files after a separator, like `repo\\.git`, aren't recognized either.
+fn is_relative_windows_path(s: &str) -> bool {
+    let components: Vec<&str> = s.split('\\').collect();
+    let (last, init) = match components.split_last() {
+        Some(it) => it,
+        None => return false,
+    };
+    let is_regex_escape = |it: &&str| {
+        matches!(*it, "d" | "D" | "w" | "W" | "s" | "S" | "b" | "B")
+            || (it.starts_with('.') && !it.starts_with(".."))
+    };
+    !init.is_empty()
+        && init.iter().all(|it| !it.is_empty())
+        && components
+            .iter()
+            .all(|it| it.chars().all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | ' ')))
+        && !components[1..].iter().any(is_regex_escape)
+        && (last.is_empty() || !last.starts_with(' '))
+}
+
+/// Checks whether `node` is only compiled on Windows, because it or one of its
+/// ancestors has an attribute like `#[cfg(windows)]` or
+/// `#[cfg(all(unix, target_os = "windows"))]`.
+fn is_windows_only(node: &SyntaxNode) -> bool {
+    node.ancestors().flat_map(|it| it.children()).filter_map(ast::Attr::cast).any(|attr| {
+        attr.as_simple_call().map_or(false, |(name, tt)| {
+            if name != "cfg" {
+                return false;
+            }
+            let mut cfg = tt.syntax().text().to_string();
+            cfg.retain(|c| !c.is_whitespace());
+            let predicate = cfg.trim_start_matches('(').trim_end_matches(')');
+            let is_windows = |it: &str| it == "windows" || it == "target_os=\"windows\"";
+            match predicate.strip_prefix("all(") {
+                Some(all) => split_top_level(all).any(is_windows),
+                None => is_windows(predicate),
+            }
+        })
+    })
+}
+
+/// Splits the arguments of a `cfg` predicate, like `unix,not(a,b)`, at the
+/// commas, which aren't nested in parentheses.
+fn split_top_level(s: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0;
+    s.split(move |c| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        c == ',' && depth == 0
+    })
+}
+
+fn check_test_module_without_cfg(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let module = ast::Module::cast(node.clone())?;
+    let name = module.name()?;
+    if !matches!(name.text().as_str(), "test" | "tests") || has_cfg_attr(module.attrs()) {
+        return None;
+    }
+    let items: Vec<ast::ModuleItem> = match module.item_list() {
+        Some(item_list) => item_list.items().collect(),
+        None => match sema.to_def(&module)?.definition_source(sema.db).value {
+            ModuleSource::SourceFile(source_file) => {
+                if has_cfg_attr(source_file.attrs()) {
+                    return None;
+                }
+                source_file.items().collect()
+            }
+            ModuleSource::Module(_) => return None,
+        },
+    };
+    let is_test_fn = |item: &ast::ModuleItem| match item {
+        ast::ModuleItem::FnDef(it) => {
+            it.attrs().any(|attr| attr.simple_name() == Some("test".into()))
+        }
+        _ => false,
+    };
+    // `use super::*;` is fine, anything else might be used outside of tests.
+    if !items.iter().any(is_test_fn)
+        || !items.iter().all(|it| is_test_fn(it) || matches!(it, ast::ModuleItem::UseItem(_)))
+    {
+        return None;
+    }
+
+    let offset = module
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)?
+        .text_range()
+        .start();
+    let indent = IndentLevel::from_node(module.syntax());
+    let edit = TextEdit::insert(offset, format!("#[cfg(test)]\n{}", indent));
+    acc.push(Diagnostic {
+        range: name.syntax().text_range(),
+        message: "Test module is compiled outside of tests, it lacks `#[cfg(test)]`".to_string(),
+        severity: Severity::WeakWarning,
+        fix: Some(Fix::new("Add `#[cfg(test)]`", SourceFileEdit { file_id, edit }.into())),
+    });
+    Some(())
+}
+
+fn has_cfg_attr(mut attrs: impl Iterator<Item = ast::Attr>) -> bool {
+    attrs.any(|attr| attr.simple_name() == Some("cfg".into()))
+}
+
+fn check_rc_clone_style(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    style: RcCloneStyle,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let rc_clone = rc_clone_to_convert(sema, style, node)?;
+    let (message, label) = match style {
+        RcCloneStyle::Explicit => (
+            format!("Clone of an `{0}` should be explicit, like `{0}::clone(&x)`", rc_clone.name),
+            format!("Convert to `{}::clone`", rc_clone.name),
+        ),
+        RcCloneStyle::Method => (
+            format!(
+                "Clone of an `{0}` should use `x.clone()`, not `{0}::clone(&x)`",
+                rc_clone.name
+            ),
+            "Convert to `.clone()`".to_string(),
+        ),
+    };
+    let edit = TextEdit::replace(rc_clone.range, rc_clone.replacement);
+    acc.push(Diagnostic {
+        range: rc_clone.range,
+        message,
+        severity: Severity::WeakWarning,
+        fix: Some(Fix::new(label, SourceFileEdit { file_id, edit }.into())),
+    });
+    Some(())
+}
+
+/// Converts all clones of an `Arc` or an `Rc` in the file to the preferred
+/// style, if there are several of them and one is in `frange`.
+pub(crate) fn fix_all_rc_clones(
+    db: &RootDatabase,
+    config: &DiagnosticsConfig,
+    frange: FileRange,
+) -> Option<Fix> {
+    let style = config.rc_clone_style?;
+    let sema = Semantics::new(db);
+    let rc_clones: Vec<RcClone> = sema
+        .parse(frange.file_id)
+        .syntax()
+        .descendants()
+        .filter_map(|node| rc_clone_to_convert(&sema, style, &node))
+        .collect();
+    if rc_clones.len() < 2 || !rc_clones.iter().any(|it| it.range.intersect(frange.range).is_some())
+    {
+        return None;
+    }
+    let mut builder = TextEditBuilder::default();
+    for rc_clone in rc_clones {
+        builder.replace(rc_clone.range, rc_clone.replacement);
+    }
+    let edit = SourceFileEdit { file_id: frange.file_id, edit: builder.finish() };
+    Some(Fix::new("Convert all clones of reference-counted pointers in file", edit.into()))
+}
+
+/// Applies the fixes of the diagnostics in the file, which only change the style
+/// of the code. The fixes are repeated until there are none left, so applying
+/// the result again doesn't change anything.
+pub(crate) fn fix_all_style(db: &RootDatabase, file_id: FileId) -> Option<Fix> {
+    let original = db.parse(file_id).tree();
+    let mut file = original.clone();
+    loop {
+        let mut diagnostics = Vec::new();
+        for node in file.syntax().descendants() {
+            check_unnecessary_braces_in_use_statement(&mut diagnostics, file_id, &node);
+            check_struct_shorthand_initialization(&mut diagnostics, file_id, &node);
+        }
+        let mut edit = TextEdit::default();
+        for file_edit in diagnostics
+            .into_iter()
+            .filter_map(|it| it.fix)
+            .flat_map(|it| it.source_change.source_file_edits)
+        {
+            // The fixes of nested use trees overlap, so the inner ones are
+            // applied in the next round.
+            let _ = edit.union(file_edit.edit);
+        }
+        if edit.is_empty() {
+            break;
+        }
+        let mut text = file.syntax().to_string();
+        edit.apply(&mut text);
+        file = SourceFile::parse(&text).tree();
+    }
+    let mut builder = TextEditBuilder::default();
+    algo::diff(original.syntax(), file.syntax()).into_text_edit(&mut builder);
+    let edit = builder.finish();
+    if edit.is_empty() {
+        return None;
+    }
+    Some(Fix::new("Apply style fixes", SourceFileEdit { file_id, edit }.into()))
+}
+
+struct RcClone {
+    range: TextRange,
+    replacement: String,
+    /// `Arc` or `Rc`.
+    name: String,
+}
+
+/// Finds a clone of an `Arc` or an `Rc`, which doesn't follow `style`.
+fn rc_clone_to_convert(
+    sema: &Semantics<RootDatabase>,
+    style: RcCloneStyle,
+    node: &SyntaxNode,
+) -> Option<RcClone> {
+    let is_rc = |name: &str| name == "Arc" || name == "Rc";
+    match style {
+        RcCloneStyle::Explicit => {
+            let call = ast::MethodCallExpr::cast(node.clone())?;
+            if call.name_ref()?.text() != "clone" || call.arg_list()?.args().next().is_some() {
+                return None;
+            }
+            let receiver = call.expr()?;
+            let receiver_ty = sema.type_of_expr(&receiver)?;
+            let adt = receiver_ty.autoderef(sema.db).find_map(|it| it.as_adt())?;
+            let name = adt.name(sema.db).to_string();
+            if !is_rc(&name) {
+                return None;
+            }
+            // The replacement refers to the type by its name.
+            let mut in_scope = false;
+            sema.scope(call.syntax()).process_all_names(&mut |it, def| {
+                in_scope |= it.to_string() == name
+                    && matches!(def, hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(it)) if it == adt);
+            });
+            if !in_scope {
+                return None;
+            }
+            let amp = if receiver_ty.as_adt().is_some() { "&" } else { "" };
+            let replacement = format!("{}::clone({}{})", name, amp, receiver.syntax());
+            Some(RcClone { range: call.syntax().text_range(), replacement, name })
+        }
+        RcCloneStyle::Method => {
+            let call = ast::CallExpr::cast(node.clone())?;
+            let path = match call.expr()? {
+                ast::Expr::PathExpr(it) => it.path()?,
+                _ => return None,
+            };
+            if path.segment()?.name_ref()?.text() != "clone" {
+                return None;
+            }
+            let name = path.qualifier()?.segment()?.name_ref()?.text().to_string();
+            if !is_rc(&name) {
+                return None;
+            }
+            let (arg,) = call.arg_list()?.args().collect_tuple()?;
+            let receiver = match arg {
+                ast::Expr::RefExpr(it) if it.mut_token().is_none() => it.expr()?,
+                it => it,
+            };
+            let replacement = match receiver {
+                ast::Expr::PathExpr(_)
+                | ast::Expr::CallExpr(_)
+                | ast::Expr::MethodCallExpr(_)
+                | ast::Expr::FieldExpr(_)
+                | ast::Expr::IndexExpr(_)
+                | ast::Expr::TryExpr(_)
+                | ast::Expr::ParenExpr(_)
+                | ast::Expr::MacroCall(_) => format!("{}.clone()", receiver.syntax()),
+                _ => format!("({}).clone()", receiver.syntax()),
+            };
+            Some(RcClone { range: call.syntax().text_range(), replacement, name })
+        }
+    }
+}
+
+/// Computes a fix removing the items reported by the `dead_code` lint of
+/// `cargo check`. Only private items are removed, which are referenced by
+/// nothing but other removed items. Unused fields and variants are kept, as
+/// removing them requires changes to the code constructing them.
+pub(crate) fn remove_dead_code(db: &RootDatabase, dead_code: &[FileRange]) -> Option<Fix> {
+    let sema = Semantics::new(db);
+    let mut items: Vec<(FileRange, Definition)> =
+        dead_code.iter().filter_map(|frange| dead_item(&sema, *frange)).collect();
+    loop {
+        let ranges: Vec<FileRange> = items.iter().map(|(it, _)| *it).collect();
+        let is_removed = |frange: FileRange| {
+            ranges
+                .iter()
+                .any(|it| it.file_id == frange.file_id && it.range.contains_range(frange.range))
+        };
+        let count = items.len();
+        items.retain(|(_, def)| {
+            def.find_usages(db, None).iter().all(|reference| is_removed(reference.file_range))
+        });
+        if items.len() == count {
+            break;
+        }
+    }
+    if items.is_empty() {
+        return None;
+    }
+
+    let mut ranges: Vec<FileRange> = items.into_iter().map(|(it, _)| it).collect();
+    ranges.sort_by_key(|it| (it.file_id, it.range.start()));
+    let mut edits = Vec::new();
+    for (file_id, ranges) in &ranges.into_iter().group_by(|it| it.file_id) {
+        let source_file = sema.parse(file_id);
+        let mut builder = TextEditBuilder::default();
+        let mut removed: Option<TextRange> = None;
+        for frange in ranges {
+            // Items nested in another removed item are removed with it.
+            if removed.map_or(false, |it| it.contains_range(frange.range)) {
+                continue;
+            }
+            removed = Some(frange.range);
+            let item = source_file.syntax().covering_element(frange.range);
+            let whitespace = item
+                .prev_sibling_or_token()
+                .filter(|it| it.kind() == WHITESPACE)
+                .or_else(|| item.next_sibling_or_token().filter(|it| it.kind() == WHITESPACE));
+            let range = match whitespace {
+                Some(ws) => frange.range.cover(ws.text_range()),
+                None => frange.range,
+            };
+            builder.delete(range);
+        }
+        edits.push(SourceFileEdit { file_id, edit: builder.finish() });
+    }
+    Some(Fix::new("Remove all dead code in crate", edits.into()))
+}
+
+/// Finds the item reported at `frange`, if it is private.
+fn dead_item(sema: &Semantics<RootDatabase>, frange: FileRange) -> Option<(FileRange, Definition)> {
+    let source_file = sema.parse(frange.file_id);
+    let node = match source_file.syntax().covering_element(frange.range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    let item = node.ancestors().find(|it| {
+        matches!(
+            it.kind(),
+            FN_DEF
+                | STRUCT_DEF
+                | ENUM_DEF
+                | UNION_DEF
+                | CONST_DEF
+                | STATIC_DEF
+                | TYPE_ALIAS_DEF
+                | TRAIT_DEF
+                | RECORD_FIELD_DEF
+                | TUPLE_FIELD_DEF
+                | ENUM_VARIANT
+        )
+    })?;
+    if item.children().any(|it| it.kind() == VISIBILITY) {
+        return None;
+    }
+    let def: ModuleDef = match_ast! {
+        match item {
+            ast::FnDef(it) => sema.to_def(&it)?.into(),
+            ast::StructDef(it) => sema.to_def(&it)?.into(),
+            ast::EnumDef(it) => sema.to_def(&it)?.into(),
+            ast::UnionDef(it) => sema.to_def(&it)?.into(),
+            ast::ConstDef(it) => sema.to_def(&it)?.into(),
+            ast::StaticDef(it) => sema.to_def(&it)?.into(),
+            ast::TypeAliasDef(it) => sema.to_def(&it)?.into(),
+            ast::TraitDef(it) => sema.to_def(&it)?.into(),
+            _ => return None,
+        }
+    };
+    let frange = FileRange { file_id: frange.file_id, range: item.text_range() };
+    Some((frange, Definition::ModuleDef(def)))
+}
+
+/// Computes a fix allowing `lint` in the whole file with an inner
+/// `#![allow(...)]` attribute, unless the file already allows it.
+pub(crate) fn allow_lint_in_file(db: &RootDatabase, file_id: FileId, lint: &str) -> Option<Fix> {
+    let source_file = Semantics::new(db).parse(file_id);
+    let inner_attrs: Vec<ast::Attr> =
+        source_file.attrs().filter(|it| it.excl_token().is_some()).collect();
+    let allow = inner_attrs.iter().find_map(|attr| match attr.as_simple_call() {
+        Some((name, tt)) if name == "allow" => Some(tt),
+        _ => None,
+    });
+
+    let edit = match allow {
+        Some(tt) => {
+            let text = tt.syntax().text().to_string();
+            let is_allowed = text
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .split(',')
+                .any(|it| it.trim() == lint);
+            if is_allowed {
+                return None;
+            }
+            let r_paren = tt.syntax().last_token().filter(|it| it.kind() == T![')'])?;
+            let last = r_paren.prev_token().filter(|it| it.kind() != T!['('])?;
+            let separator = if last.kind() == T![,] { " " } else { ", " };
+            let offset = match last.kind() {
+                WHITESPACE => last.text_range().start(),
+                _ => r_paren.text_range().start(),
+            };
+            TextEdit::insert(offset, format!("{}{}", separator, lint))
+        }
+        None => {
+            let attr = format!("#![allow({})]", lint);
+            match inner_attrs.last() {
+                Some(it) => TextEdit::insert(it.syntax().text_range().end(), format!("\n{}", attr)),
+                None => {
+                    // Keep the module documentation at the top of the file.
+                    let leading_comment = source_file
+                        .syntax()
+                        .children_with_tokens()
+                        .take_while(|it| matches!(it.kind(), COMMENT | WHITESPACE))
+                        .filter(|it| it.kind() == COMMENT)
+                        .last();
+                    match leading_comment {
+                        Some(it) => TextEdit::insert(it.text_range().end(), format!("\n{}", attr)),
+                        None => TextEdit::insert(0.into(), format!("{}\n\n", attr)),
+                    }
+                }
+            }
+        }
+    };
+    let label = format!("Allow `{}` in this file", lint);
+    Some(Fix::new(label, SourceFileEdit { file_id, edit }.into()))
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
-    use ra_syntax::SourceFile;
     use stdx::SepBy;
     use test_utils::assert_eq_text;
 
-    use crate::mock_analysis::{analysis_and_position, single_file};
+    use crate::mock_analysis::{analysis_and_position, single_file, MockAnalysis};
 
     use super::*;
 
@@ -255,7 +952,11 @@ mod tests {
     ///  * that the contents of the file containing the cursor match `after` after the diagnostic fix is applied
     fn check_apply_diagnostic_fix_from_position(fixture: &str, after: &str) {
         let (analysis, file_position) = analysis_and_position(fixture);
-        let diagnostic = analysis.diagnostics(file_position.file_id).unwrap().pop().unwrap();
+        let diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .unwrap()
+            .pop()
+            .unwrap();
         let mut fix = diagnostic.fix.unwrap();
         let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
         let target_file_contents = analysis.file_text(file_position.file_id).unwrap();
@@ -292,7 +993,8 @@ mod tests {
 
     fn check_apply_diagnostic_fix(before: &str, after: &str) {
         let (analysis, file_id) = single_file(before);
-        let diagnostic = analysis.diagnostics(file_id).unwrap().pop().unwrap();
+        let diagnostic =
+            analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap().pop().unwrap();
         let mut fix = diagnostic.fix.unwrap();
         let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
         let actual = {
@@ -307,13 +1009,14 @@ mod tests {
     /// apply to the file containing the cursor.
     fn check_no_diagnostic_for_target_file(fixture: &str) {
         let (analysis, file_position) = analysis_and_position(fixture);
-        let diagnostics = analysis.diagnostics(file_position.file_id).unwrap();
+        let diagnostics =
+            analysis.diagnostics(&DiagnosticsConfig::default(), file_position.file_id).unwrap();
         assert_eq!(diagnostics.len(), 0);
     }
 
     fn check_no_diagnostic(content: &str) {
         let (analysis, file_id) = single_file(content);
-        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
         assert_eq!(diagnostics.len(), 0, "expected no diagnostic, found one");
     }
 
@@ -454,6 +1157,153 @@ mod tests {
         check_no_diagnostic_for_target_file(content);
     }
 
+    #[test]
+    fn test_flatten_nested_option() {
+        let before = r#"
+            //- /main.rs
+            use core::option::Option::{self, Some, None};
+
+            fn find(items: &[Option<u32>]) -> Option<Option<u32>> { None }
+
+            fn first(items: &[Option<u32>]) -> Option<u32> {
+                let first: Option<u32> = find(items)<|>;
+                first
+            }
+
+            //- /core/lib.rs
+            pub mod option {
+                pub enum Option<T> { Some(T), None }
+            }
+        "#;
+        let after = r#"
+            use core::option::Option::{self, Some, None};
+
+            fn find(items: &[Option<u32>]) -> Option<Option<u32>> { None }
+
+            fn first(items: &[Option<u32>]) -> Option<u32> {
+                let first: Option<u32> = find(items).flatten();
+                first
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
+    #[test]
+    fn test_flatten_nested_option_in_tail_expr() {
+        let before = r#"
+            //- /main.rs
+            use core::option::Option::{self, Some, None};
+
+            fn first(nested: &Option<Option<u32>>) -> Option<u32> {
+                *nested<|>
+            }
+
+            //- /core/lib.rs
+            pub mod option {
+                pub enum Option<T> { Some(T), None }
+            }
+        "#;
+        let after = r#"
+            use core::option::Option::{self, Some, None};
+
+            fn first(nested: &Option<Option<u32>>) -> Option<u32> {
+                nested.clone().flatten()
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
+    #[test]
+    fn test_flatten_not_applicable_for_other_mismatches() {
+        let content = r#"
+            //- /main.rs
+            use core::option::Option::{self, Some, None};
+
+            fn wrap(item: Option<u32>) -> Option<Option<u32>> {
+                item<|>
+            }
+
+            //- /core/lib.rs
+            pub mod option {
+                pub enum Option<T> { Some(T), None }
+            }
+        "#;
+        check_no_diagnostic_for_target_file(content);
+    }
+
+    #[test]
+    fn test_remove_const_for_non_const_call() {
+        let before = r#"
+            //- /main.rs
+            fn log(msg: &str) {}
+
+            pub const fn square(x: u32) -> u32 {
+                log("square")<|>;
+                x * x
+            }
+        "#;
+        let after = r#"
+            fn log(msg: &str) {}
+
+            pub fn square(x: u32) -> u32 {
+                log("square");
+                x * x
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
+    #[test]
+    fn test_remove_const_for_non_const_method_call() {
+        let before = r#"
+            //- /main.rs
+            struct Meters(u32);
+            impl Meters {
+                fn get(&self) -> u32 { self.0 }
+            }
+
+            const unsafe fn double(m: Meters) -> u32 {
+                m.get()<|> * 2
+            }
+        "#;
+        let after = r#"
+            struct Meters(u32);
+            impl Meters {
+                fn get(&self) -> u32 { self.0 }
+            }
+
+            unsafe fn double(m: Meters) -> u32 {
+                m.get() * 2
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
+    #[test]
+    fn test_no_diagnostic_for_const_operations() {
+        let content = r#"
+            //- /main.rs
+            struct Meters(u32);
+            impl Meters {
+                const fn get(&self) -> u32 { self.0 }
+            }
+            fn log(msg: &str) {}
+
+            const fn double(m: Meters) -> Meters {
+                let f = |msg| log(msg);
+                let mut i = 0;
+                while i < 2 { i += 1; }
+                Meters(m.get()<|> * i)
+            }
+
+            fn triple(m: Meters) -> u32 {
+                log("triple");
+                m.get() * 3
+            }
+        "#;
+        check_no_diagnostic_for_target_file(content);
+    }
+
     #[test]
     fn test_fill_struct_fields_empty() {
         let before = r"
@@ -597,7 +1447,7 @@ mod tests {
     #[test]
     fn test_unresolved_module_diagnostic() {
         let (analysis, file_id) = single_file("mod foo;");
-        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
         assert_debug_snapshot!(diagnostics, @r###"
         [
             Diagnostic {
@@ -648,7 +1498,7 @@ mod tests {
             }
         ",
         );
-        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
         assert_debug_snapshot!(diagnostics, @r###"
         [
             Diagnostic {
@@ -782,4 +1632,549 @@ fn main() {
             check_struct_shorthand_initialization,
         );
     }
+
+    #[test]
+    fn test_add_cfg_test_to_inline_module() {
+        check_apply_diagnostic_fix(
+            r#"
+fn foo() {}
+
+mod foo {
+    /// Docs.
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_foo() {}
+    }
+}
+"#,
+            r#"
+fn foo() {}
+
+mod foo {
+    /// Docs.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_foo() {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_cfg_test_to_out_of_line_module() {
+        check_apply_diagnostic_fix_from_position(
+            r#"
+            //- /main.rs
+            fn foo() {}
+            mod tests<|>;
+
+            //- /tests.rs
+            #[test]
+            fn test_foo() {}
+            "#,
+            r#"
+            fn foo() {}
+            #[cfg(test)]
+            mod tests;
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_no_cfg_test_diagnostic() {
+        check_no_diagnostic(
+            r#"
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_foo() {}
+}
+
+mod test {
+    pub fn helper() {}
+
+    #[test]
+    fn test_foo() {}
+}
+"#,
+        );
+        check_no_diagnostic_for_target_file(
+            r#"
+            //- /main.rs
+            mod tests<|>;
+
+            //- /tests.rs
+            #![cfg(test)]
+
+            #[test]
+            fn test_foo() {}
+            "#,
+        );
+    }
+
+    fn check_fix_all_rc_clones(style: RcCloneStyle, before: &str, after: &str) {
+        let config =
+            DiagnosticsConfig { rc_clone_style: Some(style), ..DiagnosticsConfig::default() };
+        let (analysis, file_id) = single_file(before);
+        assert!(analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap().is_empty());
+        let frange = FileRange { file_id, range: TextRange::up_to(TextSize::of(before)) };
+        let mut fix = analysis.fix_all_rc_clones(&config, frange).unwrap().unwrap();
+        let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
+        let mut actual = before.to_string();
+        edit.apply(&mut actual);
+        assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn test_fix_all_style() {
+        let before = r#"
+use std::{collections::{HashMap}};
+use std::fmt::{self};
+
+struct Point { x: u32, y: u32 }
+
+fn main() {
+    let (x, y) = (1, 2);
+    let _ = Point { x: x, y: y };
+    let _ = Point { x: y, y: x };
+}
+"#;
+        let after = r#"
+use std::collections::HashMap;
+use std::fmt;
+
+struct Point { x: u32, y: u32 }
+
+fn main() {
+    let (x, y) = (1, 2);
+    let _ = Point { x, y };
+    let _ = Point { x: y, y: x };
+}
+"#;
+        let (analysis, file_id) = single_file(before);
+        let mut fix = analysis.fix_all_style(file_id).unwrap().unwrap();
+        let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
+        let mut actual = before.to_string();
+        edit.apply(&mut actual);
+        assert_eq_text!(after, &actual);
+
+        let (analysis, file_id) = single_file(after);
+        assert!(analysis.fix_all_style(file_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rc_clone_style_explicit() {
+        check_fix_all_rc_clones(
+            RcCloneStyle::Explicit,
+            r#"
+struct Arc<T>(T);
+struct Config { name: Arc<u32> }
+fn share(config: &Config, count: &Arc<u32>, other: u32) -> (Arc<u32>, Arc<u32>, u32) {
+    (config.name.clone(), count.clone(), other.clone())
+}
+"#,
+            r#"
+struct Arc<T>(T);
+struct Config { name: Arc<u32> }
+fn share(config: &Config, count: &Arc<u32>, other: u32) -> (Arc<u32>, Arc<u32>, u32) {
+    (Arc::clone(&config.name), Arc::clone(count), other.clone())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_rc_clone_style_method() {
+        check_fix_all_rc_clones(
+            RcCloneStyle::Method,
+            r#"
+fn share(config: &Config, count: &Rc<u32>) -> (Arc<u32>, Rc<u32>) {
+    (Arc::clone(&config.name), Rc::clone(count))
+}
+"#,
+            r#"
+fn share(config: &Config, count: &Rc<u32>) -> (Arc<u32>, Rc<u32>) {
+    (config.name.clone(), count.clone())
+}
+"#,
+        );
+    }
+
+    /// Removes the dead code at the first occurrences of `dead_code` in
+    /// `before`, like the spans of the `dead_code` lint.
+    fn check_remove_dead_code(before: &str, dead_code: &[&str], after: &str) {
+        let (analysis, file_id) = single_file(before);
+        let dead_code = dead_code_ranges(file_id, before, dead_code);
+        let mut fix = analysis.remove_dead_code(dead_code).unwrap().unwrap();
+        let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
+        let mut actual = before.to_string();
+        edit.apply(&mut actual);
+        assert_eq_text!(after, &actual);
+    }
+
+    fn dead_code_ranges(file_id: FileId, text: &str, dead_code: &[&str]) -> Vec<FileRange> {
+        dead_code
+            .iter()
+            .map(|it| {
+                let offset = TextSize::from(text.find(it).unwrap() as u32);
+                FileRange { file_id, range: TextRange::at(offset, TextSize::of(*it)) }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_remove_dead_code() {
+        check_remove_dead_code(
+            r#"
+/// Unused.
+fn unused() -> u32 { helper() + LIMIT }
+fn helper() -> u32 { helper() }
+const LIMIT: u32 = 1;
+struct Meters(u32);
+impl Meters {
+    fn get(&self) -> u32 { self.0 }
+}
+pub fn api() -> u32 { LIMIT }
+fn main() {}
+"#,
+            &["fn unused", "fn helper", "const LIMIT", "fn get", "Meters"],
+            r#"
+const LIMIT: u32 = 1;
+struct Meters(u32);
+impl Meters {
+}
+pub fn api() -> u32 { LIMIT }
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_remove_dead_code_keeps_public_and_used_items() {
+        let text = r#"
+pub fn exported() {}
+pub struct Exported;
+fn used() {}
+fn main() { used(); }
+"#;
+        let (analysis, file_id) = single_file(text);
+        let dead_code =
+            dead_code_ranges(file_id, text, &["fn exported", "struct Exported", "fn used"]);
+        assert!(analysis.remove_dead_code(dead_code).unwrap().is_none());
+    }
+
+    fn check_allow_lint_in_file(before: &str, lint: &str, after: &str) {
+        let (analysis, file_id) = single_file(before);
+        let mut fix = analysis.allow_lint_in_file(file_id, lint.to_string()).unwrap().unwrap();
+        let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
+        let mut actual = before.to_string();
+        edit.apply(&mut actual);
+        assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn test_allow_lint_in_file() {
+        check_allow_lint_in_file(
+            "fn main() {}\n",
+            "clippy::needless_return",
+            "#![allow(clippy::needless_return)]\n\nfn main() {}\n",
+        );
+        check_allow_lint_in_file(
+            "//! Generated bindings.\n//! Don't edit.\n\nfn main() {}\n",
+            "non_camel_case_types",
+            "//! Generated bindings.\n//! Don't edit.\n#![allow(non_camel_case_types)]\n\nfn main() {}\n",
+        );
+        check_allow_lint_in_file(
+            "#![deny(missing_docs)]\n#![allow(dead_code,)]\nfn main() {}\n",
+            "unused_imports",
+            "#![deny(missing_docs)]\n#![allow(dead_code, unused_imports)]\nfn main() {}\n",
+        );
+        check_allow_lint_in_file(
+            "#![deny(missing_docs)]\nfn main() {}\n",
+            "unused_imports",
+            "#![deny(missing_docs)]\n#![allow(unused_imports)]\nfn main() {}\n",
+        );
+    }
+
+    #[test]
+    fn test_allow_lint_in_file_already_allowed() {
+        let (analysis, file_id) = single_file("#![allow(dead_code, unused)]\nfn main() {}");
+        assert!(analysis.allow_lint_in_file(file_id, "unused".to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_remove_dead_code_keeps_public_items_and_fields() {
+        let (analysis, file_id) = single_file(
+            "pub fn unused() {}\nstruct S { field: u32 }\nfn main() { S { field: 1 }; }",
+        );
+        let dead_code = vec![
+            FileRange { file_id, range: TextRange::new(7.into(), 13.into()) },
+            FileRange { file_id, range: TextRange::new(30.into(), 35.into()) },
+        ];
+        assert!(analysis.remove_dead_code(dead_code).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rc_clone_style_single_fix() {
+        let config = DiagnosticsConfig {
+            rc_clone_style: Some(RcCloneStyle::Method),
+            ..DiagnosticsConfig::default()
+        };
+        let (analysis, file_id) = single_file("fn foo(x: &Arc<u32>) { Arc::clone(&*x); }");
+        let diagnostics = analysis.diagnostics(&config, file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "Clone of an `Arc` should use `x.clone()`, not `Arc::clone(&x)`",
+                range: 23..38,
+                severity: WeakWarning,
+                fix: Some(
+                    Fix {
+                        label: "Convert to `.clone()`",
+                        source_change: SourceChange {
+                            source_file_edits: [
+                                SourceFileEdit {
+                                    file_id: FileId(
+                                        1,
+                                    ),
+                                    edit: TextEdit {
+                                        indels: [
+                                            Indel {
+                                                insert: "(*x).clone()",
+                                                delete: 23..38,
+                                            },
+                                        ],
+                                    },
+                                },
+                            ],
+                            file_system_edits: [],
+                            is_snippet: false,
+                        },
+                    },
+                ),
+            },
+        ]
+        "###);
+        // A single clone is fixed by the fix of its diagnostic.
+        let frange = FileRange { file_id, range: TextRange::empty(30.into()) };
+        assert!(analysis.fix_all_rc_clones(&config, frange).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_todo_macros() {
+        // The macro name is spliced in, so that tidy doesn't take it for a marker.
+        let todo = ["to", "do"].concat();
+        let (analysis, file_id) = single_file(
+            &r#"
+fn parse(s: &str) -> u32 {
+    match s {
+        "" => std::unreachable!(),
+        "-" => $unfinished!(),
+        _ => { unimplemented!("{}", s); }
+    }
+}
+fn log() { println!(); other::$unfinished!(); }
+"#
+            .replace("$unfinished", &todo),
+        );
+        let config = DiagnosticsConfig { warn_on_todo: true, ..DiagnosticsConfig::default() };
+        let diagnostics = analysis.diagnostics(&config, file_id).unwrap();
+        let messages: Vec<_> = diagnostics.iter().map(|it| it.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "`unreachable!()` panics if it is reached".to_string(),
+                format!("unfinished code: `{}!()`", todo),
+                "unfinished code: `unimplemented!()`".to_string(),
+            ]
+        );
+        assert!(diagnostics.iter().all(|it| matches!(it.severity, Severity::Warning)));
+        assert!(analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap().is_empty());
+    }
+
+    fn check_windows_path_fix(before: &str, after: &str) {
+        let (analysis, file_id) = single_file(before);
+        let config = DiagnosticsConfig { warn_on_windows_paths: true, ..Default::default() };
+        let diagnostic = analysis.diagnostics(&config, file_id).unwrap().pop().unwrap();
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_change.source_file_edits.pop().unwrap().edit;
+        let mut actual = before.to_string();
+        edit.apply(&mut actual);
+        assert_eq_text!(after, &actual);
+    }
+
+    fn check_no_windows_path_diagnostic(content: &str) {
+        let (analysis, file_id) = single_file(content);
+        let config = DiagnosticsConfig { warn_on_windows_paths: true, ..Default::default() };
+        assert!(analysis.diagnostics(&config, file_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_windows_path_literal() {
+        check_windows_path_fix(
+            r#"fn main() { let p = "assets\\icons\\logo.png"; }"#,
+            r#"fn main() { let p = "assets/icons/logo.png"; }"#,
+        );
+        check_windows_path_fix(
+            r#"fn main() { let p = r"Program Files\app\"; }"#,
+            r#"fn main() { let p = r"Program Files/app/"; }"#,
+        );
+        check_windows_path_fix(
+            r#"fn main() { let p = "..\\assets"; }"#,
+            r#"fn main() { let p = "../assets"; }"#,
+        );
+    }
+
+    #[test]
+    fn test_windows_path_literal_keeps_str_type() {
+        check_windows_path_fix(
+            r#"fn main() { let s = String::from("target\\debug"); }"#,
+            r#"fn main() { let s = String::from("target/debug"); }"#,
+        );
+        check_windows_path_fix(
+            r#"fn main(s: &str) -> bool { s == "target\\debug" }"#,
+            r#"fn main(s: &str) -> bool { s == "target/debug" }"#,
+        );
+        check_windows_path_fix(
+            r#"
+struct Config { dir: &'static str }
+fn main() { let c = Config { dir: "target\\debug" }; }
+"#,
+            r#"
+struct Config { dir: &'static str }
+fn main() { let c = Config { dir: "target/debug" }; }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_windows_path_literal_already_a_path() {
+        check_windows_path_fix(
+            r#"
+#[cfg(not(windows))]
+fn main() { dir.join("target\\debug"); }
+"#,
+            r#"
+#[cfg(not(windows))]
+fn main() { dir.join("target/debug"); }
+"#,
+        );
+        check_windows_path_fix(
+            r#"fn main() { let p = std::path::PathBuf::from("target\\debug"); }"#,
+            r#"fn main() { let p = std::path::PathBuf::from("target/debug"); }"#,
+        );
+    }
+
+    #[test]
+    fn test_windows_path_literal_not_applicable() {
+        check_no_windows_path_diagnostic(r#"fn main() { let s = "a\n b"; let re = "\\d+"; }"#);
+        check_no_windows_path_diagnostic(
+            r#"fn main() { let re = "\\d+\\.\\w"; let re = "main\\.rs"; let re = "a\\d"; }"#,
+        );
+        check_no_windows_path_diagnostic(
+            r#"fn main() { let s = "a/b"; let t = "\\\\server\\share"; }"#,
+        );
+        check_no_windows_path_diagnostic(r#"fn main() { let p = r"C:\Program Files\app"; }"#);
+        check_no_windows_path_diagnostic(
+            r#"
+#[cfg(windows)]
+fn main() { let p = "assets\\logo.png"; }
+"#,
+        );
+        check_no_windows_path_diagnostic(
+            r#"
+#[cfg(all(debug_assertions, target_os = "windows"))]
+fn main() { let p = "assets\\logo.png"; }
+"#,
+        );
+        // Disabled by default.
+        check_no_diagnostic(r#"fn main() { let p = "assets\\logo.png"; }"#);
+    }
+
+    /// Applies the fix of the diagnostic of the file with the cursor, which
+    /// edits `parent`, and compares the text of `parent` with `after`.
+    fn check_unlinked_file(fixture: &str, parent: &str, after: &str) {
+        let (mock, position) = MockAnalysis::with_files_and_position(fixture);
+        let parent = mock.id_of(parent);
+        let analysis = mock.analysis();
+        let diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), position.file_id)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(diagnostic.message, "file is not included in the module tree");
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_change.source_file_edits.pop().unwrap();
+        assert_eq!(edit.file_id, parent);
+        let mut actual = analysis.file_text(parent).unwrap().to_string();
+        edit.edit.apply(&mut actual);
+        assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn test_unlinked_file() {
+        check_unlinked_file(
+            r#"
+//- /lib.rs
+//! Docs
+mod a;
+pub mod b;
+
+fn f() {}
+//- /a.rs
+//- /b.rs
+//- /c.rs
+<|>fn g() {}
+"#,
+            "/lib.rs",
+            "//! Docs\nmod a;\npub mod b;\nmod c;\n\nfn f() {}\n",
+        );
+        check_unlinked_file(
+            r#"
+//- /main.rs
+mod foo;
+fn main() {}
+//- /foo.rs
+use std::fmt;
+//- /foo/bar/mod.rs
+<|>
+"#,
+            "/foo.rs",
+            "mod bar;\n\nuse std::fmt;\n",
+        );
+    }
+
+    #[test]
+    fn test_unlinked_file_not_applicable() {
+        check_no_diagnostic_for_target_file(
+            r#"
+//- /lib.rs
+#[cfg(windows)]
+mod win;
+//- /win.rs
+<|>fn f() {}
+"#,
+        );
+        // Neither `foo.rs` nor `foo/mod.rs` exist.
+        check_no_diagnostic_for_target_file(
+            r#"
+//- /lib.rs
+//- /foo/bar.rs
+<|>fn f() {}
+"#,
+        );
+        check_no_diagnostic_for_target_file(
+            r#"
+//- /lib.rs
+//- /2d.rs
+<|>fn f() {}
+"#,
+        );
+    }
 }
@@ -14,7 +14,12 @@ pub struct ExpandedMacro {
 
 // Feature: Expand Macro Recursively
 //
-// Shows the full macro expansion of the macro at current cursor.
+// Shows the full macro expansion of the macro at current cursor. This includes
+// procedural attribute macros, which are expanded only on demand.
+//
+// In VS Code, the expansion is opened as a highlighted virtual document, which
+// is updated as the source file changes. Nested macro calls are already
+// expanded, and the document does not support navigation.
 //
 // |===
 // | Editor  | Action Name
@@ -25,9 +30,13 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id);
     let name_ref = find_node_at_offset::<ast::NameRef>(file.syntax(), position.offset)?;
-    let mac = name_ref.syntax().ancestors().find_map(ast::MacroCall::cast)?;
-
-    let expanded = expand_macro_recur(&sema, &mac)?;
+    let expanded = match name_ref.syntax().ancestors().find_map(ast::Attr::cast) {
+        Some(attr) => expand_attr_macro(&sema, &attr)?,
+        None => {
+            let mac = name_ref.syntax().ancestors().find_map(ast::MacroCall::cast)?;
+            expand_macro_recur(&sema, &mac)?
+        }
+    };
 
     // FIXME:
     // macro expansion may lose all white space information
@@ -40,8 +49,24 @@ fn expand_macro_recur(
     sema: &Semantics<RootDatabase>,
     macro_call: &ast::MacroCall,
 ) -> Option<SyntaxNode> {
-    let mut expanded = sema.expand(macro_call)?;
+    let expanded = sema.expand(macro_call)?;
+    expand_children_recur(sema, expanded)
+}
+
+/// Expands the attribute macro `attr` and the macro calls in its output.
+pub(crate) fn expand_attr_macro(
+    sema: &Semantics<RootDatabase>,
+    attr: &ast::Attr,
+) -> Option<SyntaxNode> {
+    let item = attr.syntax().parent().and_then(ast::ModuleItem::cast)?;
+    let expanded = sema.expand_attr_macro(&item, attr)?;
+    expand_children_recur(sema, expanded)
+}
 
+fn expand_children_recur(
+    sema: &Semantics<RootDatabase>,
+    mut expanded: SyntaxNode,
+) -> Option<SyntaxNode> {
     let children = expanded.descendants().filter_map(ast::MacroCall::cast);
     let mut rewriter = SyntaxRewriter::default();
 
@@ -64,7 +89,7 @@ fn expand_macro_recur(
 
 // FIXME: It would also be cool to share logic here and in the mbe tests,
 // which are pretty unreadable at the moment.
-fn insert_whitespaces(syn: SyntaxNode) -> String {
+pub(crate) fn insert_whitespaces(syn: SyntaxNode) -> String {
     use SyntaxKind::*;
 
     let mut res = String::new();
@@ -122,7 +147,7 @@ fn insert_whitespaces(syn: SyntaxNode) -> String {
 mod tests {
     use insta::assert_snapshot;
 
-    use crate::mock_analysis::analysis_and_position;
+    use crate::mock_analysis::{analysis_and_position, attr_macro_analysis};
 
     use super::*;
 
@@ -291,4 +316,46 @@ fn some_thing() -> u32 {
         assert_eq!(res.name, "foo");
         assert_snapshot!(res.expansion, @r###"0"###);
     }
+
+    #[test]
+    fn macro_expand_attribute_macro() {
+        let (analysis, pos) = attr_macro_analysis(
+            r#"
+#[derive(Debug)]
+#[macros::app<|>end(struct Extra;)]
+struct Foo;
+"#,
+        );
+        let res = analysis.expand_macro(pos).unwrap().unwrap();
+
+        assert_eq!(res.name, "append");
+        assert_snapshot!(res.expansion, @r###"
+#[derive(Debug)]struct Foo;
+struct Extra;
+"###);
+    }
+
+    #[test]
+    fn macro_expand_not_an_attribute_macro() {
+        let (analysis, pos) = attr_macro_analysis(
+            r#"
+#[allow(dead_<|>code)]
+struct Foo;
+"#,
+        );
+        assert!(analysis.expand_macro(pos).unwrap().is_none());
+    }
+
+    #[test]
+    fn macro_expand_attribute_macro_called_as_function_like_macro() {
+        let (analysis, pos) = attr_macro_analysis(
+            r#"
+macros::app<|>end!(struct Extra;);
+"#,
+        );
+        let res = analysis.expand_macro(pos).unwrap().unwrap();
+
+        assert_eq!(res.name, "append");
+        assert_eq!(res.expansion, "");
+    }
 }
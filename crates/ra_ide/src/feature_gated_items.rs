@@ -0,0 +1,241 @@
+//! Detects paths which fail to resolve because the referenced item of a
+//! dependency is disabled by a `#[cfg(feature = "...")]` attribute.
+
+use std::iter::successors;
+
+use hir::{Attrs, InFile, ModuleDef, ModuleSource, PathResolution, Semantics};
+use ra_cfg::{CfgExpr, CfgOptions};
+use ra_db::{CrateId, FileRange, SourceDatabase};
+use ra_ide_db::RootDatabase;
+use ra_syntax::{
+    algo::find_covering_element,
+    ast::{self, AstNode, ModuleItemOwner, NameOwner},
+    NodeOrToken, TextRange,
+};
+
+/// A reference to an item of a dependency which exists only when some cargo
+/// features of that dependency are enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureGatedItem {
+    /// The range of the unresolved path segment.
+    pub range: TextRange,
+    pub name: String,
+    /// The name of the dependency, as seen by the referencing crate.
+    pub dependency: String,
+    /// Features of the dependency which have to be enabled for the item to
+    /// become available.
+    pub features: Vec<String>,
+}
+
+pub(crate) fn feature_gated_items(db: &RootDatabase, frange: FileRange) -> Vec<FeatureGatedItem> {
+    let sema = Semantics::new(db);
+    let krate = match sema.to_module_def(frange.file_id) {
+        Some(it) => it.krate(),
+        None => return Vec::new(),
+    };
+    let dependencies = krate.dependencies(db);
+    if dependencies.is_empty() {
+        return Vec::new();
+    }
+    let source_file = sema.parse(frange.file_id);
+    // Only the paths overlapping with the range are looked at, which for code
+    // actions are just the ones around the cursor.
+    let node = match find_covering_element(source_file.syntax(), frange.range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    node.descendants()
+        .chain(node.ancestors().skip(1))
+        .filter_map(ast::Path::cast)
+        .filter_map(|path| {
+            let name_ref = path.segment()?.name_ref()?;
+            name_ref.syntax().text_range().intersect(frange.range)?;
+            let qualifier = path.qualifier()?;
+            // Only paths starting with the name of a dependency are resolved,
+            // to keep this cheap enough to run with every diagnostics pass.
+            let first_segment = successors(Some(qualifier.clone()), |it| it.qualifier())
+                .last()?
+                .segment()?
+                .name_ref()?;
+            let dependency = dependencies
+                .iter()
+                .find(|dep| dep.name.to_string() == first_segment.text().as_str())?;
+            let module = match sema.resolve_path(&qualifier)? {
+                PathResolution::Def(ModuleDef::Module(it)) => it,
+                _ => return None,
+            };
+            if module.krate() != dependency.krate {
+                return None;
+            }
+            let name = name_ref.text().to_string();
+            let features = disabled_features(&sema, module, &name)?;
+            if sema.resolve_path(&path).is_some() {
+                return None;
+            }
+            Some(FeatureGatedItem {
+                range: name_ref.syntax().text_range(),
+                name,
+                dependency: dependency.name.to_string(),
+                features,
+            })
+        })
+        .collect()
+}
+
+fn disabled_features(
+    sema: &Semantics<RootDatabase>,
+    module: hir::Module,
+    name: &str,
+) -> Option<Vec<String>> {
+    let db = sema.db;
+    let source = module.definition_source(db);
+    let items: Vec<ast::ModuleItem> = match &source.value {
+        ModuleSource::SourceFile(it) => it.items().collect(),
+        ModuleSource::Module(it) => it.item_list()?.items().collect(),
+    };
+    let crate_graph = db.crate_graph();
+    let cfg_options = &crate_graph[CrateId::from(module.krate())].cfg_options;
+
+    items.iter().filter(|item| item.name().map_or(false, |it| it.text() == name)).find_map(|item| {
+        let attrs = Attrs::from_attrs_owner(db, InFile::new(source.file_id, item));
+        let mut features = Vec::new();
+        for cfg in attrs.by_key("cfg").tt_values().map(ra_cfg::parse_cfg) {
+            if cfg_options.check(&cfg) == Some(false) {
+                missing_features(&cfg, cfg_options, &mut features);
+            }
+        }
+        if features.is_empty() {
+            None
+        } else {
+            Some(features)
+        }
+    })
+}
+
+fn missing_features(cfg: &CfgExpr, cfg_options: &CfgOptions, acc: &mut Vec<String>) {
+    match cfg {
+        CfgExpr::KeyValue { key, value }
+            if key == "feature"
+                && cfg_options.check(cfg) == Some(false)
+                && !acc.iter().any(|it| it == value) =>
+        {
+            acc.push(value.to_string())
+        }
+        CfgExpr::All(preds) => preds.iter().for_each(|cfg| missing_features(cfg, cfg_options, acc)),
+        // Enabling a single alternative is enough.
+        CfgExpr::Any(preds) => {
+            if let Some(pred) = preds.iter().find(|pred| {
+                let mut features = Vec::new();
+                missing_features(pred, cfg_options, &mut features);
+                !features.is_empty()
+            }) {
+                missing_features(pred, cfg_options, acc)
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::TextRange;
+
+    use crate::{mock_analysis::analysis_and_position, Analysis, FileRange};
+
+    fn check(ra_fixture: &str, expected: &[(&str, &str, &[&str])]) {
+        let (analysis, position) = analysis_and_position(ra_fixture);
+        let len = analysis.file_text(position.file_id).unwrap().len();
+        let range = TextRange::up_to((len as u32).into());
+        check_range(analysis, FileRange { file_id: position.file_id, range }, expected)
+    }
+
+    fn check_range(analysis: Analysis, frange: FileRange, expected: &[(&str, &str, &[&str])]) {
+        let items = analysis.feature_gated_items(frange).unwrap();
+        let actual: Vec<_> = items
+            .iter()
+            .map(|it| {
+                (
+                    it.name.as_str(),
+                    it.dependency.as_str(),
+                    it.features.iter().map(|it| it.as_str()).collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        let expected: Vec<_> =
+            expected.iter().map(|&(name, dep, features)| (name, dep, features.to_vec())).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn feature_gated_import() {
+        check(
+            r#"
+//- /main.rs
+use dep::Serializer<|>;
+use dep::Parser;
+
+//- /dep/lib.rs cfg:feature=parse
+#[cfg(feature = "serde")]
+pub struct Serializer;
+#[cfg(feature = "parse")]
+pub struct Parser;
+"#,
+            &[("Serializer", "dep", &["serde"])],
+        );
+    }
+
+    #[test]
+    fn feature_gated_path_in_nested_module() {
+        check(
+            r#"
+//- /main.rs
+fn main() {
+    dep::io::write<|>();
+}
+
+//- /dep/lib.rs
+pub mod io {
+    #[cfg(all(feature = "std", any(feature = "alloc", feature = "nightly")))]
+    pub fn write() {}
+}
+"#,
+            &[("write", "dep", &["std", "alloc"])],
+        );
+    }
+
+    #[test]
+    fn item_missing_for_other_reasons() {
+        check(
+            r#"
+//- /main.rs
+use dep::Unix<|>;
+use dep::Missing;
+
+//- /dep/lib.rs
+#[cfg(unix)]
+pub struct Unix;
+"#,
+            &[],
+        );
+    }
+
+    #[test]
+    fn feature_gated_items_in_range() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+//- /main.rs
+use dep::Serializer;
+use dep::Deserializer<|>;
+
+//- /dep/lib.rs
+#[cfg(feature = "serde")]
+pub struct Serializer;
+#[cfg(feature = "serde")]
+pub struct Deserializer;
+"#,
+        );
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        check_range(analysis, frange, &[("Deserializer", "dep", &["serde"])]);
+    }
+}
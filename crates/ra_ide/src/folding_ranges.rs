@@ -1,10 +1,13 @@
 //! FIXME: write short doc here
 
+use hir::{Attrs, InFile};
+use ra_db::{FileId, FileLoader, SourceDatabase};
+use ra_ide_db::RootDatabase;
 use rustc_hash::FxHashSet;
 
 use ra_syntax::{
-    ast::{self, AstNode, AstToken, VisibilityOwner},
-    Direction, NodeOrToken, SourceFile,
+    ast::{self, AstNode, AstToken, AttrsOwner, VisibilityOwner},
+    match_ast, Direction, NodeOrToken, SourceFile,
     SyntaxKind::{self, *},
     SyntaxNode, TextRange,
 };
@@ -15,6 +18,8 @@ pub enum FoldKind {
     Imports,
     Mods,
     Block,
+    /// Code disabled by a `#[cfg]` attribute.
+    InactiveCode,
 }
 
 #[derive(Debug)]
@@ -79,6 +84,56 @@ pub(crate) fn folding_ranges(file: &SourceFile) -> Vec<Fold> {
     res
 }
 
+/// Folds the items, fields, match arms and statements, which are disabled by
+/// a `#[cfg]` attribute in the crate containing `file_id`.
+pub(crate) fn inactive_code(db: &RootDatabase, file_id: FileId) -> Vec<Fold> {
+    let krate = match db.relevant_crates(file_id).iter().next() {
+        Some(it) => *it,
+        None => return Vec::new(),
+    };
+    let crate_graph = db.crate_graph();
+    let cfg_options = &crate_graph[krate].cfg_options;
+    let source_file = db.parse(file_id).tree();
+
+    let mut res: Vec<Fold> = Vec::new();
+    for node in source_file.syntax().descendants() {
+        // Code nested in inactive code is already folded
+        if res.last().map_or(false, |it| it.range.contains_range(node.text_range())) {
+            continue;
+        }
+        let attrs = match cfg_attrs(db, file_id, &node) {
+            Some(it) => it,
+            None => continue,
+        };
+        let is_inactive = attrs
+            .by_key("cfg")
+            .tt_values()
+            .map(ra_cfg::parse_cfg)
+            .any(|cfg| cfg_options.check(&cfg) == Some(false));
+        if is_inactive && node.text().contains_char('\n') {
+            res.push(Fold { range: node.text_range(), kind: FoldKind::InactiveCode });
+        }
+    }
+    res
+}
+
+fn cfg_attrs(db: &RootDatabase, file_id: FileId, node: &SyntaxNode) -> Option<Attrs> {
+    let owner: Box<dyn AttrsOwner> = match_ast! {
+        match node {
+            ast::ModuleItem(it) => Box::new(it),
+            ast::RecordFieldDef(it) => Box::new(it),
+            ast::TupleFieldDef(it) => Box::new(it),
+            ast::EnumVariant(it) => Box::new(it),
+            ast::MatchArm(it) => Box::new(it),
+            ast::ExprStmt(it) => Box::new(it),
+            ast::LetStmt(it) => Box::new(it),
+            _ => return None,
+        }
+    };
+    owner.attrs().next()?;
+    Some(Attrs::from_attrs_owner(db, InFile::new(file_id.into(), &*owner)))
+}
+
 fn fold_kind(kind: SyntaxKind) -> Option<FoldKind> {
     match kind {
         COMMENT => Some(FoldKind::Comment),
@@ -197,6 +252,7 @@ fn contiguous_range_for_comment(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock_analysis::MockAnalysis;
     use test_utils::extract_ranges;
 
     fn do_check(text: &str, fold_kinds: &[FoldKind]) {
@@ -372,4 +428,48 @@ fn main() <fold>{
         let folds = &[FoldKind::Block, FoldKind::Block];
         do_check(text, folds);
     }
+
+    #[test]
+    fn test_fold_inactive_code() {
+        let text = r#"
+//- /lib.rs cfg:feature=std
+#[cfg(feature = "std")]
+fn active() {
+    <fold>#[cfg(not(feature = "std"))]
+    let x = {
+        92
+    };</fold>
+}
+
+<fold>#[cfg(feature = "alloc")]
+mod alloc {
+    #[cfg(feature = "std")]
+    fn nested() {
+        0
+    }
+}</fold>
+
+#[cfg(test)] fn single_line() {}
+
+enum E {
+    <fold>#[cfg(test)]
+    V {
+        x: u32,
+    }</fold>,
+}
+"#;
+        let (ranges, text) = extract_ranges(text, "fold");
+        let analysis = MockAnalysis::with_files(&text).analysis();
+        let folds = analysis.folding_ranges(FileId(1)).unwrap();
+        let inactive: Vec<_> = folds
+            .into_iter()
+            .filter(|it| it.kind == FoldKind::InactiveCode)
+            .map(|it| it.range)
+            .collect();
+        // The ranges are relative to the text of the file, without the fixture meta
+        let offset = text.find("#[cfg").unwrap();
+        let expected: Vec<_> =
+            ranges.into_iter().map(|it| it - ra_syntax::TextSize::from(offset as u32)).collect();
+        assert_eq!(inactive, expected);
+    }
 }
@@ -1,5 +1,8 @@
-use hir::{Crate, ImplDef, Semantics};
-use ra_ide_db::RootDatabase;
+use hir::{Crate, ImplDef, ModuleDef, Semantics, Type};
+use ra_ide_db::{
+    defs::{classify_name, classify_name_ref, Definition},
+    RootDatabase,
+};
 use ra_syntax::{algo::find_node_at_offset, ast, AstNode};
 
 use crate::{display::ToNav, FilePosition, NavigationTarget, RangeInfo};
@@ -38,6 +41,39 @@ pub(crate) fn goto_implementation(
     None
 }
 
+/// Returns all impl blocks of the type at `position`, in every crate of the
+/// workspace, including the trait impls.
+pub(crate) fn impl_blocks(db: &RootDatabase, position: FilePosition) -> Vec<NavigationTarget> {
+    let sema = Semantics::new(db);
+    let ty = match type_at_position(&sema, position) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    Crate::all(db)
+        .into_iter()
+        .flat_map(|krate| ImplDef::all_in_crate(db, krate))
+        .filter(|impl_def| ty.is_equal_for_find_impls(&impl_def.target_ty(db)))
+        .map(|imp| imp.to_nav(db))
+        .collect()
+}
+
+fn type_at_position(sema: &Semantics<RootDatabase>, position: FilePosition) -> Option<Type> {
+    let syntax = sema.parse(position.file_id).syntax().clone();
+    let def = if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(&syntax, position.offset)
+    {
+        classify_name_ref(sema, &name_ref)?.definition()
+    } else {
+        let name = find_node_at_offset::<ast::Name>(&syntax, position.offset)?;
+        classify_name(sema, &name)?.definition()
+    };
+    match def {
+        Definition::ModuleDef(ModuleDef::Adt(adt)) => Some(adt.ty(sema.db)),
+        Definition::ModuleDef(ModuleDef::TypeAlias(alias)) => Some(alias.ty(sema.db)),
+        Definition::SelfType(impl_def) => Some(impl_def.target_ty(sema.db)),
+        _ => None,
+    }
+}
+
 fn impls_for_def(
     sema: &Semantics<RootDatabase>,
     node: &ast::NominalDef,
@@ -194,6 +230,58 @@ mod tests {
         );
     }
 
+    fn check_impl_blocks(fixture: &str, expected: &[&str]) {
+        let (analysis, pos) = analysis_and_position(fixture);
+
+        let mut navs = analysis.impl_blocks(pos).unwrap();
+        assert_eq!(navs.len(), expected.len());
+        navs.sort_by_key(|nav| (nav.file_id(), nav.full_range().start()));
+        navs.into_iter().enumerate().for_each(|(i, nav)| nav.assert_match(expected[i]));
+    }
+
+    #[test]
+    fn impl_blocks_across_crates() {
+        check_impl_blocks(
+            "
+            //- /lib.rs
+            use std::fmt;
+            use other::Foo;
+            impl fmt::Debug for Foo<|> {}
+
+            //- /other/lib.rs
+            pub struct Foo;
+            impl Foo {}
+            impl Clone for Foo {}
+            ",
+            &[
+                "impl IMPL_DEF FileId(1) 30..56",
+                "impl IMPL_DEF FileId(2) 16..27",
+                "impl IMPL_DEF FileId(2) 28..49",
+            ],
+        );
+    }
+
+    #[test]
+    fn impl_blocks_from_type_alias_and_self() {
+        check_impl_blocks(
+            "
+            //- /lib.rs
+            struct Foo;
+            type Bar = Foo;
+            impl Bar {}
+            impl Foo { fn new() -> Se<|>lf { Foo } }
+            ",
+            &["impl IMPL_DEF FileId(1) 28..39", "impl IMPL_DEF FileId(1) 40..77"],
+        );
+        check_impl_blocks(
+            "
+            //- /lib.rs
+            fn foo<|>() {}
+            ",
+            &[],
+        );
+    }
+
     #[test]
     fn goto_implementation_to_builtin_derive() {
         check_goto(
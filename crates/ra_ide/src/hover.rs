@@ -1,8 +1,8 @@
 use std::iter::once;
 
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, Documentation, FieldSource, HasSource, HirDisplay,
-    ModuleDef, ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, Docs, Documentation, FieldSource, HasSource, HirDisplay,
+    ModuleDef, ModuleSource, PathResolution, Semantics,
 };
 use itertools::Itertools;
 use ra_db::SourceDatabase;
@@ -14,6 +14,7 @@ use ra_syntax::{ast, match_ast, AstNode, SyntaxKind::*, SyntaxToken, TokenAtOffs
 
 use crate::{
     display::{macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel, ToNav},
+    expand_macro::{expand_attr_macro, insert_whitespaces},
     runnables::runnable,
     FileId, FilePosition, NavigationTarget, RangeInfo, Runnable,
 };
@@ -59,6 +60,7 @@ pub enum HoverAction {
 pub struct HoverResult {
     results: Vec<String>,
     actions: Vec<HoverAction>,
+    sysroot_item_path: Option<String>,
 }
 
 impl HoverResult {
@@ -94,6 +96,12 @@ impl HoverResult {
         self.actions.push(action);
     }
 
+    /// The path of the hovered item, like `core::option::Option::map`, if it
+    /// comes from the standard library and has no docs in the sources.
+    pub fn sysroot_item_path(&self) -> Option<&str> {
+        self.sysroot_item_path.as_deref()
+    }
+
     /// Returns the results converted into markup
     /// for displaying in a UI
     ///
@@ -107,6 +115,7 @@ impl HoverResult {
 //
 // Shows additional information, like type of an expression or documentation for definition when "focusing" code.
 // Focusing is usually hovering with a mouse, but can also be triggered with a shortcut.
+// Hovering a procedural attribute macro shows its expansion.
 pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeInfo<HoverResult>> {
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id).syntax().clone();
@@ -115,6 +124,19 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
 
     let mut res = HoverResult::new();
 
+    let attr_macro = token.ancestors().find_map(ast::Attr::cast).filter(|attr| {
+        match attr.path().and_then(|it| sema.resolve_path(&it)) {
+            Some(PathResolution::Macro(it)) => it.is_attr_macro(),
+            _ => false,
+        }
+    });
+    if let Some(attr) = attr_macro {
+        if let Some(expanded) = expand_attr_macro(&sema, &attr) {
+            res.extend(Some(rust_code_markup(&insert_whitespaces(expanded).trim())));
+            return Some(RangeInfo::new(attr.syntax().text_range(), res));
+        }
+    }
+
     if let Some((node, name_kind)) = match_ast! {
         match (token.parent()) {
             ast::NameRef(name_ref) => {
@@ -128,6 +150,7 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
     } {
         let range = sema.original_range(&node).range;
         res.extend(hover_text_from_name_kind(db, name_kind));
+        res.sysroot_item_path = undocumented_sysroot_item_path(db, &name_kind);
 
         if !res.is_empty() {
             if let Some(action) = show_implementations_action(db, name_kind) {
@@ -168,6 +191,35 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
     Some(RangeInfo::new(range, res))
 }
 
+/// Returns the type of the expression or pattern at `position`, with paths
+/// resolved from the module at `position`, so that it can be used in a type
+/// annotation there.
+pub(crate) fn type_at_position(db: &RootDatabase, position: FilePosition) -> Option<String> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id).syntax().clone();
+    let token = pick_best(file.token_at_offset(position.offset))?;
+    let token = sema.descend_into_macros(token);
+    let node = token
+        .ancestors()
+        .find(|n| ast::Expr::cast(n.clone()).is_some() || ast::Pat::cast(n.clone()).is_some())?;
+
+    let ty = match_ast! {
+        match node {
+            ast::MacroCall(_it) => return None,
+            ast::Expr(it) => sema.type_of_expr(&it),
+            ast::Pat(it) => sema.type_of_pat(&it),
+            _ => None,
+        }
+    }
+    .filter(|it| !it.contains_unknown())?;
+    let module = sema.scope(&node).module()?;
+    let res = match ty.display_source_code(db, module.into()) {
+        Ok(it) => it,
+        Err(_) => ty.display(db).to_string(),
+    };
+    Some(res)
+}
+
 fn show_implementations_action(db: &RootDatabase, def: Definition) -> Option<HoverAction> {
     fn to_action(nav_target: NavigationTarget) -> HoverAction {
         HoverAction::Implementaion(FilePosition {
@@ -265,6 +317,35 @@ fn determine_mod_path(db: &RootDatabase, def: &Definition) -> Option<String> {
     mod_path
 }
 
+fn undocumented_sysroot_item_path(db: &RootDatabase, def: &Definition) -> Option<String> {
+    let module_def = match def {
+        Definition::ModuleDef(it) => *it,
+        _ => return None,
+    };
+    let krate = def.module(db)?.krate();
+    let crate_name = db.crate_graph()[krate.into()].display_name.as_ref()?.to_string();
+    if !["std", "core", "alloc"].contains(&crate_name.as_str()) {
+        return None;
+    }
+    let has_docs = match module_def {
+        ModuleDef::Module(it) => it.docs(db),
+        ModuleDef::Function(it) => it.docs(db),
+        ModuleDef::Adt(it) => it.docs(db),
+        ModuleDef::EnumVariant(it) => it.docs(db),
+        ModuleDef::Const(it) => it.docs(db),
+        ModuleDef::Static(it) => it.docs(db),
+        ModuleDef::Trait(it) => it.docs(db),
+        ModuleDef::TypeAlias(it) => it.docs(db),
+        ModuleDef::BuiltinType(_) => return None,
+    }
+    .is_some();
+    if has_docs {
+        return None;
+    }
+    let mod_path = determine_mod_path(db, def)?;
+    Some(format!("{}::{}", mod_path, def.name(db)?))
+}
+
 fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<String> {
     let mod_path = determine_mod_path(db, &def);
     return match def {
@@ -340,7 +421,10 @@ mod tests {
     use ra_db::FileLoader;
     use ra_syntax::TextRange;
 
-    use crate::mock_analysis::{analysis_and_position, single_file_with_position};
+    use crate::{
+        mock_analysis::{analysis_and_position, attr_macro_analysis, single_file_with_position},
+        Analysis,
+    };
 
     fn trim_markup(s: &str) -> &str {
         s.trim_start_matches("```rust\n").trim_end_matches("\n```")
@@ -360,6 +444,14 @@ mod tests {
 
     fn check_hover_result(fixture: &str, expected: &[&str]) -> (String, Vec<HoverAction>) {
         let (analysis, position) = analysis_and_position(fixture);
+        check_analysis_hover_result(analysis, position, expected)
+    }
+
+    fn check_analysis_hover_result(
+        analysis: Analysis,
+        position: FilePosition,
+        expected: &[&str],
+    ) -> (String, Vec<HoverAction>) {
         let hover = analysis.hover(position).unwrap().unwrap();
         let mut results = Vec::from(hover.info.results());
         results.sort();
@@ -712,6 +804,50 @@ fn func(foo: i32) { if true { <|>foo; }; }
         assert_eq!(trim_markup_opt(hover.info.first()), Some("Thing"));
     }
 
+    #[test]
+    fn test_hover_sysroot_item_path() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /main.rs
+            fn main() {
+                let x = core::option::Option::<i32>::None;
+                x.ma<|>p(|it| it);
+            }
+            //- /core/lib.rs
+            pub mod option {
+                pub enum Option<T> { None, Some(T) }
+                impl<T> Option<T> {
+                    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Option<U> { loop {} }
+                    /// Documented in the sources.
+                    pub fn is_none(&self) -> bool { loop {} }
+                }
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(hover.info.sysroot_item_path(), Some("core::option::Option::map"));
+
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /main.rs
+            fn main() {
+                let x = core::option::Option::<i32>::None;
+                x.is_n<|>one();
+            }
+            //- /core/lib.rs
+            pub mod option {
+                pub enum Option<T> { None, Some(T) }
+                impl<T> Option<T> {
+                    /// Documented in the sources.
+                    pub fn is_none(&self) -> bool { loop {} }
+                }
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(hover.info.sysroot_item_path(), None);
+    }
+
     #[test]
     fn test_hover_infer_associated_method_exact() {
         let (analysis, position) = single_file_with_position(
@@ -1310,4 +1446,41 @@ fn func(foo: i32) { if true { <|>foo; }; }
             ]
             "###);
     }
+
+    #[test]
+    fn type_at_position_uses_paths_from_cursor_module() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /main.rs
+            mod model {
+                pub struct User;
+                pub struct Group;
+                pub mod store { pub struct Store<T>(T); }
+            }
+            struct Shared<T>(T);
+            mod service {
+                use crate::{model::{store::Store, User}, Shared};
+                fn users() -> (Shared<Store<User>>, crate::model::Group) { loop {} }
+                fn main() {
+                    let us<|>ers = users();
+                }
+            }
+            ",
+        );
+        let ty = analysis.type_at_position(position).unwrap();
+        assert_eq!(ty.as_deref(), Some("(Shared<Store<User>>, crate::model::Group)"));
+    }
+
+    #[test]
+    fn hover_shows_attribute_macro_expansion() {
+        let (analysis, position) = attr_macro_analysis(
+            r#"
+#[macros::append<|>(fn extra() {})]
+fn foo() {}
+"#,
+        );
+        let (range, _) =
+            check_analysis_hover_result(analysis, position, &["fn foo(){}\nfn extra(){}"]);
+        assert_eq!(range, "#[macros::append(fn extra() {})]");
+    }
 }
@@ -1,8 +1,11 @@
-use hir::{Adt, HirDisplay, Semantics, Type};
+use hir::{Adt, HirDisplay, PathResolution, Semantics, Type};
 use ra_ide_db::RootDatabase;
 use ra_prof::profile;
 use ra_syntax::{
-    ast::{self, ArgListOwner, AstNode, TypeAscriptionOwner},
+    ast::{
+        self, ArgListOwner, AstNode, NameOwner, TypeAscriptionOwner, TypeBoundsOwner,
+        TypeParamsOwner,
+    },
     match_ast, Direction, NodeOrToken, SmolStr, SyntaxKind, TextRange,
 };
 
@@ -14,12 +17,19 @@ pub struct InlayHintsConfig {
     pub type_hints: bool,
     pub parameter_hints: bool,
     pub chaining_hints: bool,
+    pub trait_bound_hints: bool,
     pub max_length: Option<usize>,
 }
 
 impl Default for InlayHintsConfig {
     fn default() -> Self {
-        Self { type_hints: true, parameter_hints: true, chaining_hints: true, max_length: None }
+        Self {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            trait_bound_hints: false,
+            max_length: None,
+        }
     }
 }
 
@@ -28,6 +38,7 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    TraitBoundHint,
 }
 
 #[derive(Debug)]
@@ -47,6 +58,7 @@ pub struct InlayHint {
 // * types of local variables
 // * names of function arguments
 // * types of chained expressions
+// * trait bounds of generic parameters, at their first use in a function body (off by default)
 //
 // **Note:** VS Code does not have native support for inlay hints https://github.com/microsoft/vscode/issues/16221[yet] and the hints are implemented using decorations.
 // This approach has limitations, the caret movement and bracket highlighting near the edges of the hint may be weird:
@@ -77,6 +89,7 @@ pub(crate) fn inlay_hints(
                 ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::BindPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
+                ast::FnDef(it) => { get_trait_bound_hints(&mut res, &sema, config, it); },
                 _ => (),
             }
         }
@@ -197,6 +210,61 @@ fn get_bind_pat_hints(
     Some(())
 }
 
+fn get_trait_bound_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    fn_def: ast::FnDef,
+) -> Option<()> {
+    if !config.trait_bound_hints {
+        return None;
+    }
+
+    let body = fn_def.body()?;
+    let where_preds: Vec<ast::WherePred> =
+        fn_def.where_clause().into_iter().flat_map(|it| it.predicates()).collect();
+    for type_param in fn_def.type_param_list()?.type_params() {
+        let name = type_param.name()?;
+        let bounds_in_preds = where_preds
+            .iter()
+            .filter(|it| {
+                it.type_ref().map_or(false, |it| it.syntax().text() == name.text().as_str())
+            })
+            .filter_map(|it| it.type_bound_list());
+        let bounds: Vec<String> = type_param
+            .type_bound_list()
+            .into_iter()
+            .chain(bounds_in_preds)
+            .flat_map(|it| it.bounds())
+            .map(|it| it.syntax().to_string())
+            .collect();
+        if bounds.is_empty() {
+            continue;
+        }
+        let hir_param = match sema.to_def(&type_param) {
+            Some(it) => it,
+            None => continue,
+        };
+
+        let first_use = body.syntax().descendants().filter_map(ast::Path::cast).find(|path| {
+            let is_param_name = path.qualifier().is_none()
+                && path
+                    .segment()
+                    .and_then(|it| it.name_ref())
+                    .map_or(false, |it| it.text() == name.text());
+            is_param_name && sema.resolve_path(path) == Some(PathResolution::TypeParam(hir_param))
+        });
+        if let Some(path) = first_use {
+            acc.push(InlayHint {
+                range: path.syntax().text_range(),
+                kind: InlayKind::TraitBoundHint,
+                label: bounds.join(" + ").into(),
+            });
+        }
+    }
+    Some(())
+}
+
 fn pat_is_enum_variant(db: &RootDatabase, bind_pat: &ast::BindPat, pat_ty: &Type) -> bool {
     if let Some(Adt::Enum(enum_data)) = pat_ty.as_adt() {
         let pat_text = bind_pat.to_string();
@@ -360,7 +428,7 @@ mod tests {
                 let _x = foo(4, 4);
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: true, type_hints: false, chaining_hints: false, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: true, type_hints: false, chaining_hints: false, trait_bound_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 106..107,
@@ -384,7 +452,7 @@ mod tests {
                 let _x = foo(4, 4);
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: false, parameter_hints: false, chaining_hints: false, max_length: None}).unwrap(), @r###"[]"###);
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: false, parameter_hints: false, chaining_hints: false, trait_bound_hints: false, max_length: None}).unwrap(), @r###"[]"###);
     }
 
     #[test]
@@ -396,7 +464,7 @@ mod tests {
                 let _x = foo(4, 4);
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: true, parameter_hints: false, chaining_hints: false, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: true, parameter_hints: false, chaining_hints: false, trait_bound_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 97..99,
@@ -1236,7 +1304,7 @@ fn main() {
                     .into_c();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, trait_bound_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 232..269,
@@ -1265,7 +1333,7 @@ fn main() {
                 let c = A(B(C)).into_b().into_c();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"[]"###);
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, trait_bound_hints: false, max_length: None}).unwrap(), @r###"[]"###);
     }
 
     #[test]
@@ -1290,7 +1358,7 @@ fn main() {
                     .foo();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, trait_bound_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 252..323,
@@ -1328,7 +1396,7 @@ fn main() {
                     .into_c();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, trait_bound_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 403..452,
@@ -1342,4 +1410,31 @@ fn main() {
             },
         ]"###);
     }
+
+    #[test]
+    fn trait_bound_hints() {
+        let (analysis, file_id) = single_file(
+            r#"
+            trait Clone {}
+            trait Debug {}
+            fn dedup<T: Clone, U>(items: Vec<T>, other: U) -> Vec<T>
+            where
+                T: Debug,
+            {
+                let other: U = other;
+                let first: Option<T> = None;
+                let second: T = loop {};
+                items
+            }"#,
+        );
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: false, trait_bound_hints: true, max_length: None}).unwrap(), @r###"
+        [
+            InlayHint {
+                range: 254..255,
+                kind: TraitBoundHint,
+                label: "Clone + Debug",
+            },
+        ]
+        "###);
+    }
 }
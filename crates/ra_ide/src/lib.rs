@@ -32,6 +32,7 @@ mod syntax_highlighting;
 mod parent_module;
 mod references;
 mod diagnostics;
+mod feature_gated_items;
 mod syntax_tree;
 mod folding_ranges;
 mod join_lines;
@@ -41,6 +42,7 @@ mod display;
 mod inlay_hints;
 mod expand_macro;
 mod ssr;
+mod todos;
 
 use std::sync::Arc;
 
@@ -62,9 +64,10 @@ pub use crate::{
     completion::{
         CompletionConfig, CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat,
     },
-    diagnostics::Severity,
+    diagnostics::{DiagnosticsConfig, RcCloneStyle, Severity},
     display::{file_structure, FunctionSignature, NavigationTarget, StructureNode},
     expand_macro::ExpandedMacro,
+    feature_gated_items::FeatureGatedItem,
     folding_ranges::{Fold, FoldKind},
     hover::{HoverAction, HoverConfig, HoverResult},
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
@@ -74,6 +77,7 @@ pub use crate::{
     syntax_highlighting::{
         Highlight, HighlightModifier, HighlightModifiers, HighlightTag, HighlightedRange,
     },
+    todos::TodoComment,
 };
 
 pub use hir::Documentation;
@@ -339,13 +343,27 @@ impl Analysis {
 
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancelable<Vec<Fold>> {
-        self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
+        self.with_db(|db| {
+            let mut folds = folding_ranges::folding_ranges(&db.parse(file_id).tree());
+            folds.extend(folding_ranges::inactive_code(db, file_id));
+            folds
+        })
     }
 
     /// Fuzzy searches for a symbol.
     pub fn symbol_search(&self, query: Query) -> Cancelable<Vec<NavigationTarget>> {
+        self.symbol_search_with_progress(query, &|_, _| ())
+    }
+
+    /// Fuzzy searches for a symbol, calling `on_progress` with the number of
+    /// searched source roots and the total number of roots.
+    pub fn symbol_search_with_progress(
+        &self,
+        query: Query,
+        on_progress: &(dyn Fn(usize, usize) + Sync + std::panic::RefUnwindSafe),
+    ) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| {
-            symbol_index::world_symbols(db, query)
+            symbol_index::world_symbols_with_progress(db, query, on_progress)
                 .into_iter()
                 .map(|s| s.to_nav(db))
                 .collect::<Vec<_>>()
@@ -368,6 +386,11 @@ impl Analysis {
         self.with_db(|db| goto_implementation::goto_implementation(db, position))
     }
 
+    /// Returns the impl blocks of the type at `position` in all crates.
+    pub fn impl_blocks(&self, position: FilePosition) -> Cancelable<Vec<NavigationTarget>> {
+        self.with_db(|db| goto_implementation::impl_blocks(db, position))
+    }
+
     /// Returns the type definitions for the symbol at `position`.
     pub fn goto_type_definition(
         &self,
@@ -390,6 +413,12 @@ impl Analysis {
         self.with_db(|db| hover::hover(db, position))
     }
 
+    /// Returns the type of the expression at `position`, as written in source
+    /// code.
+    pub fn type_at_position(&self, position: FilePosition) -> Cancelable<Option<String>> {
+        self.with_db(|db| hover::type_at_position(db, position))
+    }
+
     /// Computes parameter information for the given call expression.
     pub fn call_info(&self, position: FilePosition) -> Cancelable<Option<CallInfo>> {
         self.with_db(|db| call_info::call_info(db, position))
@@ -413,6 +442,11 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Finds the calls of the function at the given position.
+    pub fn call_sites(&self, position: FilePosition) -> Cancelable<Option<Vec<FileRange>>> {
+        self.with_db(|db| call_hierarchy::call_sites(db, position))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -482,8 +516,52 @@ impl Analysis {
     }
 
     /// Computes the set of diagnostics for the given file.
-    pub fn diagnostics(&self, file_id: FileId) -> Cancelable<Vec<Diagnostic>> {
-        self.with_db(|db| diagnostics::diagnostics(db, file_id))
+    pub fn diagnostics(
+        &self,
+        config: &DiagnosticsConfig,
+        file_id: FileId,
+    ) -> Cancelable<Vec<Diagnostic>> {
+        self.with_db(|db| diagnostics::diagnostics(db, config, file_id))
+    }
+
+    /// Computes a fix for all clones of reference-counted pointers in the
+    /// file, which don't follow the configured style.
+    pub fn fix_all_rc_clones(
+        &self,
+        config: &DiagnosticsConfig,
+        frange: FileRange,
+    ) -> Cancelable<Option<Fix>> {
+        self.with_db(|db| diagnostics::fix_all_rc_clones(db, config, frange))
+    }
+
+    /// Computes a fix applying all fixes in the file, which only change the
+    /// style of the code.
+    pub fn fix_all_style(&self, file_id: FileId) -> Cancelable<Option<Fix>> {
+        self.with_db(|db| diagnostics::fix_all_style(db, file_id))
+    }
+
+    /// Computes a fix removing the items at `dead_code`, which are reported as
+    /// unused by `cargo check`, as far as they can be removed safely.
+    pub fn remove_dead_code(&self, dead_code: Vec<FileRange>) -> Cancelable<Option<Fix>> {
+        self.with_db(|db| diagnostics::remove_dead_code(db, &dead_code))
+    }
+
+    /// Computes a fix allowing `lint` in the whole file.
+    pub fn allow_lint_in_file(&self, file_id: FileId, lint: String) -> Cancelable<Option<Fix>> {
+        self.with_db(|db| diagnostics::allow_lint_in_file(db, file_id, &lint))
+    }
+
+    /// Returns references to items of dependencies in the given range, which
+    /// are disabled because some cargo features of the dependency are not
+    /// enabled.
+    pub fn feature_gated_items(&self, frange: FileRange) -> Cancelable<Vec<FeatureGatedItem>> {
+        self.with_db(|db| feature_gated_items::feature_gated_items(db, frange))
+    }
+
+    /// Returns the comment lines of the workspace, which start with one of
+    /// `keywords`, like `FIXME`.
+    pub fn find_todos(&self, keywords: &[String]) -> Cancelable<Vec<TodoComment>> {
+        self.with_db(|db| todos::find_todos(db, keywords))
     }
 
     /// Returns the edit required to rename reference at the position to the new
@@ -507,6 +585,20 @@ impl Analysis {
         })
     }
 
+    /// Returns the edit replacing all matches of the SSR `pattern` in the
+    /// workspace with `replacement`, like `structural_search_replace`, but
+    /// without the `==>>` delimiter between them.
+    pub fn structural_search_replace_parts(
+        &self,
+        pattern: &str,
+        replacement: &str,
+    ) -> Cancelable<Result<SourceChange, SsrError>> {
+        self.with_db(|db| {
+            let edits = ssr::parse_search_replace_parts(pattern, replacement, db)?;
+            Ok(SourceChange::from(edits))
+        })
+    }
+
     /// Performs an operation on that may be Canceled.
     fn with_db<F: FnOnce(&RootDatabase) -> T + std::panic::UnwindSafe, T>(
         &self,
@@ -590,6 +682,20 @@ struct Foo;
         assert_eq!(struct_match, Some(STRUCT_DEF));
     }
 
+    #[test]
+    fn test_world_symbols_report_progress() {
+        let (analysis, _) = single_file("struct Foo;");
+        let progress = std::sync::Mutex::new(Vec::new());
+        let symbols = analysis
+            .symbol_search_with_progress(Query::new("Foo".into()), &|searched, total| {
+                progress.lock().unwrap().push((searched, total))
+            })
+            .unwrap();
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(progress.into_inner().unwrap(), vec![(1, 1)]);
+    }
+
     fn get_symbols_matching(text: &str, query: &str) -> Vec<NavigationTarget> {
         let (analysis, _) = single_file(text);
         analysis.symbol_search(Query::new(query.into())).unwrap()
@@ -240,3 +240,65 @@ pub fn single_file_with_range(ra_fixture: &str) -> (Analysis, FileRange) {
     let pos = mock.add_file_with_range("/main.rs", ra_fixture);
     (mock.analysis(), pos)
 }
+
+/// An attribute macro, which appends its arguments after the item.
+#[cfg(test)]
+#[derive(Debug)]
+struct AppendArgs;
+
+#[cfg(test)]
+impl ra_tt::TokenExpander for AppendArgs {
+    fn expand(
+        &self,
+        subtree: &ra_tt::Subtree,
+        attrs: Option<&ra_tt::Subtree>,
+    ) -> Result<ra_tt::Subtree, ra_tt::ExpansionError> {
+        let mut res = subtree.clone();
+        res.token_trees.extend(attrs.into_iter().flat_map(|it| it.token_trees.iter().cloned()));
+        Ok(res)
+    }
+}
+
+/// Creates a crate depending on the proc-macro crate `macros`, which
+/// defines the attribute macro `append`.
+#[cfg(test)]
+pub(crate) fn attr_macro_analysis(text: &str) -> (Analysis, FilePosition) {
+    let (offset, text) = extract_offset(text);
+    let mut host = AnalysisHost::default();
+    let source_root = SourceRootId(0);
+    let mut change = AnalysisChange::new();
+    change.add_root(source_root, true);
+    let main_file = FileId(1);
+    let macros_file = FileId(2);
+    change.add_file(source_root, main_file, "main.rs".into(), Arc::new(text));
+    change.add_file(source_root, macros_file, "macros/lib.rs".into(), Arc::new(String::new()));
+
+    let mut crate_graph = CrateGraph::default();
+    let main = crate_graph.add_crate_root(
+        main_file,
+        Edition::Edition2018,
+        None,
+        CfgOptions::default(),
+        Env::default(),
+        Default::default(),
+        Vec::new(),
+    );
+    let append = ra_db::ProcMacro {
+        name: "append".into(),
+        kind: ra_db::ProcMacroKind::Attr,
+        expander: Arc::new(AppendArgs),
+    };
+    let macros = crate_graph.add_crate_root(
+        macros_file,
+        Edition::Edition2018,
+        Some(CrateName::new("macros").unwrap()),
+        CfgOptions::default(),
+        Env::default(),
+        Default::default(),
+        vec![append],
+    );
+    crate_graph.add_dep(main, CrateName::new("macros").unwrap(), macros).unwrap();
+    change.set_crate_graph(crate_graph);
+    host.apply_change(change);
+    (host.analysis(), FilePosition { file_id: main_file, offset })
+}
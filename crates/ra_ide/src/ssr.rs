@@ -50,11 +50,26 @@ pub fn parse_search_replace(
     parse_only: bool,
     db: &RootDatabase,
 ) -> Result<Vec<SourceFileEdit>, SsrError> {
-    let mut edits = vec![];
     let query: SsrQuery = query.parse()?;
     if parse_only {
-        return Ok(edits);
+        return Ok(vec![]);
     }
+    Ok(search_replace(&query, db))
+}
+
+/// Like `parse_search_replace`, but with the search pattern and the
+/// replacement passed separately, instead of as a single `==>>` query.
+pub fn parse_search_replace_parts(
+    pattern: &str,
+    template: &str,
+    db: &RootDatabase,
+) -> Result<Vec<SourceFileEdit>, SsrError> {
+    let query = SsrQuery::new(pattern.trim(), template.trim().to_string())?;
+    Ok(search_replace(&query, db))
+}
+
+fn search_replace(query: &SsrQuery, db: &RootDatabase) -> Vec<SourceFileEdit> {
+    let mut edits = vec![];
     for &root in db.local_roots().iter() {
         let sr = db.source_root(root);
         for file_id in sr.walk() {
@@ -64,7 +79,7 @@ pub fn parse_search_replace(
             }
         }
     }
-    Ok(edits)
+    edits
 }
 
 #[derive(Debug)]
@@ -109,7 +124,7 @@ impl FromStr for SsrQuery {
     fn from_str(query: &str) -> Result<SsrQuery, SsrError> {
         let mut it = query.split("==>>");
         let pattern = it.next().expect("at least empty string").trim();
-        let mut template = it
+        let template = it
             .next()
             .ok_or_else(|| SsrError("Cannot find delemiter `==>>`".into()))?
             .trim()
@@ -117,6 +132,12 @@ impl FromStr for SsrQuery {
         if it.next().is_some() {
             return Err(SsrError("More than one delimiter found".into()));
         }
+        SsrQuery::new(pattern, template)
+    }
+}
+
+impl SsrQuery {
+    fn new(pattern: &str, mut template: String) -> Result<SsrQuery, SsrError> {
         let mut vars = vec![];
         let mut it = pattern.split('$');
         let mut pattern = it.next().expect("something").to_string();
@@ -442,6 +463,7 @@ fn render_replace(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock_analysis::single_file;
     use ra_syntax::SourceFile;
 
     fn parse_error_text(query: &str) -> String {
@@ -536,6 +558,19 @@ mod tests {
         assert_eq!(after, "fn main() { bar(1+2); }");
     }
 
+    #[test]
+    fn ssr_with_separate_pattern_and_replacement() {
+        let (analysis, file_id) = single_file("fn main() { foo(1, 2); bar(foo(3, 4)); }");
+        let change = analysis
+            .structural_search_replace_parts("foo($a:expr, $b:expr)", "$a.foo($b)")
+            .unwrap();
+        let edit = &change.unwrap().source_file_edits[0];
+        assert_eq!(edit.file_id, file_id);
+        let mut after = analysis.file_text(file_id).unwrap().to_string();
+        edit.edit.apply(&mut after);
+        assert_eq!(after, "fn main() { 1.foo(2); bar(3.foo(4)); }");
+    }
+
     fn assert_ssr_transform(query: &str, input: &str, result: &str) {
         let query: SsrQuery = query.parse().unwrap();
         let code = SourceFile::parse(input).tree();
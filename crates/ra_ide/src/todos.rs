@@ -0,0 +1,141 @@
+//! Finds the `TODO`-like markers in the comments of the workspace.
+
+use ra_db::{FileId, SourceDatabase, SourceDatabaseExt};
+use ra_ide_db::{symbol_index::SymbolsDatabase, RootDatabase};
+use ra_syntax::{AstNode, SyntaxKind::COMMENT, TextRange, TextSize};
+
+/// A comment line, which starts with a marker like `TODO`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoComment {
+    pub file_id: FileId,
+    /// The range from the marker to the end of the line.
+    pub range: TextRange,
+    pub keyword: String,
+    /// The text of the line, starting with the marker.
+    pub text: String,
+}
+
+// Feature: Find TODOs
+//
+// Lists the `TODO`, `FIXME`, `HACK` and `XXX` markers in the comments of the
+// workspace. Unlike a text search, markers in string literals are ignored.
+// The markers can be configured with `rust-analyzer.todos.keywords`.
+//
+// |===
+// | Editor  | Action Name
+//
+// | VS Code | **Rust Analyzer: Find TODOs**
+// |===
+pub(crate) fn find_todos(db: &RootDatabase, keywords: &[String]) -> Vec<TodoComment> {
+    let mut res = Vec::new();
+    for &root in db.local_roots().iter() {
+        let source_root = db.source_root(root);
+        for file_id in source_root.walk() {
+            let parse = db.parse(file_id);
+            let comments = parse
+                .tree()
+                .syntax()
+                .descendants_with_tokens()
+                .filter_map(|it| it.into_token())
+                .filter(|it| it.kind() == COMMENT);
+            for comment in comments {
+                let start = comment.text_range().start();
+                let mut line_start = 0;
+                for line in comment.text().split('\n') {
+                    if let Some((keyword, offset)) = find_keyword(line, keywords) {
+                        let text = line[offset..].trim_end().trim_end_matches("*/").trim_end();
+                        let text_start = start + TextSize::from((line_start + offset) as u32);
+                        res.push(TodoComment {
+                            file_id,
+                            range: TextRange::at(text_start, TextSize::of(text)),
+                            keyword: keyword.to_string(),
+                            text: text.to_string(),
+                        });
+                    }
+                    line_start += line.len() + 1;
+                }
+            }
+        }
+    }
+    res.sort_by_key(|it| (it.file_id, it.range.start()));
+    res
+}
+
+/// Returns the first of `keywords` in `line`, which is a separate word, and its
+/// offset.
+fn find_keyword<'a>(line: &str, keywords: &'a [String]) -> Option<(&'a str, usize)> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    keywords
+        .iter()
+        .filter(|it| !it.is_empty())
+        .filter_map(|keyword| {
+            line.match_indices(keyword.as_str())
+                .find(|(offset, _)| {
+                    let before = line[..*offset].chars().next_back();
+                    let after = line[offset + keyword.len()..].chars().next();
+                    !before.map_or(false, is_word_char) && !after.map_or(false, is_word_char)
+                })
+                .map(|(offset, _)| (keyword.as_str(), offset))
+        })
+        .min_by_key(|(_, offset)| *offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::MockAnalysis;
+
+    fn check(ra_fixture: &str, keywords: &[&str], expected: &[&str]) {
+        let analysis = MockAnalysis::with_files(ra_fixture).analysis();
+        let keywords: Vec<String> = keywords.iter().map(|it| it.to_string()).collect();
+        let todos = analysis.find_todos(&keywords).unwrap();
+        let actual: Vec<String> = todos
+            .iter()
+            .map(|it| {
+                let text = analysis.file_text(it.file_id).unwrap();
+                assert_eq!(&text[it.range], it.text);
+                format!("{:?} {} {}", it.file_id, it.keyword, it.text)
+            })
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn finds_markers_in_comments_only() {
+        check(
+            r#"
+//- /lib.rs
+// TODO: split this module
+const TODO: &str = "TODO: not a comment";
+/* first line
+   FIXME(perf) avoid the copy */
+fn f() {
+    /// XXX
+    let todo_list = 1; // TODOS don't count, but HACK: this does
+}
+//- /other.rs
+//! Docs, TODO remove
+"#,
+            &["TODO", "FIXME", "HACK", "XXX"],
+            &[
+                "FileId(1) TODO TODO: split this module",
+                "FileId(1) FIXME FIXME(perf) avoid the copy",
+                "FileId(1) XXX XXX",
+                "FileId(1) HACK HACK: this does",
+                "FileId(2) TODO TODO remove",
+            ],
+        );
+    }
+
+    #[test]
+    fn uses_configured_keywords() {
+        check(
+            r#"
+//- /lib.rs
+// TODO: a
+// NOTE: b
+"#,
+            &["NOTE"],
+            &["FileId(1) NOTE NOTE: b"],
+        );
+    }
+}
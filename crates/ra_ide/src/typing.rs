@@ -23,10 +23,10 @@ use ra_syntax::{
     ast::{self, AstToken},
     AstNode, SourceFile,
     SyntaxKind::{FIELD_EXPR, METHOD_CALL_EXPR},
-    TextRange, TextSize,
+    TextRange, TextSize, T,
 };
 
-use ra_text_edit::TextEdit;
+use ra_text_edit::{TextEdit, TextEditBuilder};
 
 use crate::SourceChange;
 
@@ -41,6 +41,7 @@ pub(crate) const TRIGGER_CHARS: &str = ".=>";
 // - typing `let =` tries to smartly add `;` if `=` is followed by an existing expression
 // - Enter inside comments automatically inserts `///`
 // - typing `.` in a chain method call auto-indents
+// - typing `=>` in a `match` arm aligns the arrows of all arms and adds a space after it
 pub(crate) fn on_char_typed(
     db: &RootDatabase,
     position: FilePosition,
@@ -58,7 +59,7 @@ fn on_char_typed_inner(file: &SourceFile, offset: TextSize, char_typed: char) ->
     match char_typed {
         '.' => on_dot_typed(file, offset),
         '=' => on_eq_typed(file, offset),
-        '>' => on_arrow_typed(file, offset),
+        '>' => on_arrow_typed(file, offset).or_else(|| on_fat_arrow_typed(file, offset)),
         _ => unreachable!(),
     }
 }
@@ -130,6 +131,69 @@ fn on_arrow_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
     Some(TextEdit::insert(after_arrow, " ".to_string()))
 }
 
+/// Aligns the `=>` of all arms when `=>` is typed in a `match` arm, and adds a
+/// space between the arrow and the arm's expression.
+fn on_fat_arrow_typed(file: &SourceFile, offset: TextSize) -> Option<TextEdit> {
+    let file_text = file.syntax().text();
+    assert_eq!(file_text.char_at(offset), Some('>'));
+    let fat_arrow = file.syntax().token_at_offset(offset).find(|it| it.kind() == T![=>])?;
+    let arm = ast::MatchArm::cast(fat_arrow.parent())?;
+    let arm_list = ast::MatchArmList::cast(arm.syntax().parent()?)?;
+
+    let mut edit = TextEditBuilder::default();
+    // Only one-line patterns with the same indentation can be aligned.
+    let indent = leading_indent(arm.syntax());
+    let heads = arm_list
+        .arms()
+        .map(|arm| if leading_indent(arm.syntax()) == indent { arm_head(&arm) } else { None })
+        .collect::<Option<Vec<_>>>();
+    if let Some(heads) = heads {
+        let width = heads.iter().map(|head| head.width).max()?;
+        for head in heads {
+            let padding = " ".repeat(width - head.width + 1);
+            if file_text.slice(head.padding) != padding.as_str() {
+                edit.replace(head.padding, padding);
+            }
+        }
+    }
+    let after_arrow = fat_arrow.text_range().end();
+    if file_text.char_at(after_arrow).map_or(false, |it| !it.is_whitespace()) {
+        edit.insert(after_arrow, " ".to_string());
+    }
+
+    let edit = edit.finish();
+    if edit.is_empty() {
+        None
+    } else {
+        Some(edit)
+    }
+}
+
+struct ArmHead {
+    /// The number of characters in the pattern and the guard of the arm.
+    width: usize,
+    /// The whitespace between the guard or the pattern and `=>`.
+    padding: TextRange,
+}
+
+fn arm_head(arm: &ast::MatchArm) -> Option<ArmHead> {
+    let fat_arrow = arm.fat_arrow_token()?;
+    let head_end = match arm.guard() {
+        Some(guard) => guard.syntax().text_range().end(),
+        None => arm.pat()?.syntax().text_range().end(),
+    };
+    let arm_start = arm.syntax().text_range().start();
+    let head = arm.syntax().text().slice(..head_end - arm_start);
+    if head.contains_char('\n') {
+        return None;
+    }
+    let padding = TextRange::new(head_end, fat_arrow.text_range().start());
+    if arm.syntax().text().slice(padding - arm_start).contains_char('\n') {
+        return None;
+    }
+    Some(ArmHead { width: head.to_string().chars().count(), padding })
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::{assert_eq_text, extract_offset};
@@ -363,4 +427,88 @@ fn main() {
     fn adds_space_after_return_type() {
         type_char('>', "fn foo() -<|>{ 92 }", "fn foo() -> { 92 }")
     }
+
+    #[test]
+    fn aligns_match_arms_on_fat_arrow() {
+        type_char(
+            '>',
+            r"
+fn foo(x: Option<i32>) {
+    match x {
+        Some(92) => 1,
+        Some(_) if true => 2,
+        None =<|>3,
+    }
+}
+",
+            r"
+fn foo(x: Option<i32>) {
+    match x {
+        Some(92)        => 1,
+        Some(_) if true => 2,
+        None            => 3,
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn adds_space_after_fat_arrow() {
+        type_char(
+            '>',
+            r"
+fn foo(x: bool) {
+    match x {
+        true => 1,
+        false =<|>0,
+    }
+}
+",
+            r"
+fn foo(x: bool) {
+    match x {
+        true  => 1,
+        false => 0,
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn does_not_align_multiline_patterns() {
+        type_char(
+            '>',
+            r"
+fn foo(x: i32) {
+    match x {
+        1
+        | 2 => 1,
+        _ =<|>0,
+    }
+}
+",
+            r"
+fn foo(x: i32) {
+    match x {
+        1
+        | 2 => 1,
+        _ => 0,
+    }
+}
+",
+        );
+        type_char_noop(
+            '>',
+            r"
+fn foo(x: i32) {
+    match x {
+        1 => 1,
+        _ =<|>
+    }
+}
+",
+        );
+    }
 }
@@ -46,6 +46,7 @@ impl From<Vec<SourceFileEdit>> for SourceChange {
 pub enum FileSystemEdit {
     CreateFile { source_root: SourceRootId, path: RelativePathBuf },
     MoveFile { src: FileId, dst_source_root: SourceRootId, dst_path: RelativePathBuf },
+    DeleteFile { file: FileId },
 }
 
 impl From<FileSystemEdit> for SourceChange {
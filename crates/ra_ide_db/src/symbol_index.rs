@@ -25,7 +25,10 @@ use std::{
     fmt,
     hash::{Hash, Hasher},
     mem,
-    sync::Arc,
+    sync::{
+        atomic::{self, AtomicUsize},
+        Arc,
+    },
 };
 
 use fst::{self, Streamer};
@@ -141,36 +144,61 @@ impl Clone for Snap {
 // | VS Code | kbd:[Ctrl+T]
 // |===
 pub fn world_symbols(db: &RootDatabase, query: Query) -> Vec<FileSymbol> {
+    world_symbols_with_progress(db, query, &|_, _| ())
+}
+
+/// Like `world_symbols`, but calls `on_progress` with the number of searched
+/// source roots and the total number of roots, whenever a root is indexed.
+pub fn world_symbols_with_progress(
+    db: &RootDatabase,
+    query: Query,
+    on_progress: &(dyn Fn(usize, usize) + Sync),
+) -> Vec<FileSymbol> {
     let _p = ra_prof::profile("world_symbols").detail(|| query.query.clone());
 
+    let roots = if query.libs { db.library_roots() } else { db.local_roots() };
+    let total = roots.len();
+    let searched = AtomicUsize::new(0);
+    let root_indexed = || on_progress(searched.fetch_add(1, atomic::Ordering::SeqCst) + 1, total);
+
+    let snap = Snap(db.snapshot());
     let buf: Vec<Arc<SymbolIndex>> = if query.libs {
-        let snap = Snap(db.snapshot());
         #[cfg(not(feature = "wasm"))]
-        let buf = db
-            .library_roots()
+        let buf = roots
             .par_iter()
-            .map_with(snap, |db, &lib_id| db.0.library_symbols(lib_id))
+            .map_with(snap, |db, &lib_id| {
+                let symbols = db.0.library_symbols(lib_id);
+                root_indexed();
+                symbols
+            })
             .collect();
 
         #[cfg(feature = "wasm")]
-        let buf = db.library_roots().iter().map(|&lib_id| snap.0.library_symbols(lib_id)).collect();
+        let buf = roots
+            .iter()
+            .map(|&lib_id| {
+                let symbols = snap.0.library_symbols(lib_id);
+                root_indexed();
+                symbols
+            })
+            .collect();
 
         buf
     } else {
-        let mut files = Vec::new();
-        for &root in db.local_roots().iter() {
-            let sr = db.source_root(root);
-            files.extend(sr.walk())
-        }
+        let mut buf = Vec::new();
+        for &root in roots.iter() {
+            let files: Vec<FileId> = db.source_root(root).walk().collect();
 
-        let snap = Snap(db.snapshot());
-        #[cfg(not(feature = "wasm"))]
-        let buf =
-            files.par_iter().map_with(snap, |db, &file_id| db.0.file_symbols(file_id)).collect();
+            #[cfg(not(feature = "wasm"))]
+            buf.par_extend(
+                files.par_iter().map_with(snap.clone(), |db, &file_id| db.0.file_symbols(file_id)),
+            );
 
-        #[cfg(feature = "wasm")]
-        let buf = files.iter().map(|&file_id| snap.0.file_symbols(file_id)).collect();
+            #[cfg(feature = "wasm")]
+            buf.extend(files.iter().map(|&file_id| snap.0.file_symbols(file_id)));
 
+            root_indexed();
+        }
         buf
     };
     query.search(&buf)
@@ -159,6 +159,17 @@ pub(super) fn stmt(p: &mut Parser, with_semi: StmtWithSemi) {
         if p.eat(T![=]) {
             expressions::expr_with_attrs(p);
         }
+        // test let_else
+        // fn foo() {
+        //     let Some(x) = opt else { return };
+        //     let Ok(y): Result<u32, ()> = res else { panic!() };
+        // }
+        if p.at(T![else]) {
+            let m = p.start();
+            p.bump(T![else]);
+            block_expr(p);
+            m.complete(p, LET_ELSE);
+        }
 
         match with_semi {
             StmtWithSemi::Yes => {
@@ -228,6 +228,7 @@ pub enum SyntaxKind {
     NAME,
     NAME_REF,
     LET_STMT,
+    LET_ELSE,
     EXPR_STMT,
     TYPE_PARAM_LIST,
     LIFETIME_PARAM,
@@ -13,7 +13,10 @@ use std::{
 
 use anyhow::{bail, Context, Result};
 use ra_cfg::CfgOptions;
-use ra_db::{CrateGraph, CrateName, Edition, Env, ExternSource, ExternSourceId, FileId};
+use ra_db::{
+    CrateGraph, CrateName, Edition, Env, ExternSource, ExternSourceId, FileId, ProcMacro,
+    ProcMacroKind,
+};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde_json::from_reader;
 
@@ -291,7 +294,7 @@ impl ProjectWorkspace {
                         let proc_macro = krate
                             .proc_macro_dylib_path
                             .clone()
-                            .map(|it| proc_macro_client.by_dylib_path(&it));
+                            .map(|it| load_proc_macros(proc_macro_client, &it));
                         // FIXME: No crate name in json definition such that we cannot add OUT_DIR to env
                         Some((
                             json_project::CrateId(seq_index),
@@ -420,7 +423,7 @@ impl ProjectWorkspace {
                             let proc_macro = cargo[pkg]
                                 .proc_macro_dylib_path
                                 .as_ref()
-                                .map(|it| proc_macro_client.by_dylib_path(&it))
+                                .map(|it| load_proc_macros(proc_macro_client, it))
                                 .unwrap_or_default();
 
                             let crate_id = crate_graph.add_crate_root(
@@ -593,6 +596,21 @@ fn get_rustc_cfg_options(target: Option<&str>) -> CfgOptions {
     cfg_options
 }
 
+fn load_proc_macros(proc_macro_client: &ProcMacroClient, dylib_path: &Path) -> Vec<ProcMacro> {
+    proc_macro_client
+        .by_dylib_path(dylib_path)
+        .into_iter()
+        .map(|(name, kind, expander)| {
+            let kind = match kind {
+                ra_proc_macro::ProcMacroKind::CustomDerive => ProcMacroKind::CustomDerive,
+                ra_proc_macro::ProcMacroKind::FuncLike => ProcMacroKind::FuncLike,
+                ra_proc_macro::ProcMacroKind::Attr => ProcMacroKind::Attr,
+            };
+            ProcMacro { name, kind, expander }
+        })
+        .collect()
+}
+
 fn output(mut cmd: Command) -> Result<Output> {
     let output = cmd.output().with_context(|| format!("{:?} failed", cmd))?;
     if !output.status.success() {
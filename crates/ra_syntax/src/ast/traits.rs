@@ -76,6 +76,11 @@ pub trait AttrsOwner: AstNode {
     fn has_atom_attr(&self, atom: &str) -> bool {
         self.attrs().filter_map(|x| x.as_simple_atom()).any(|x| x == atom)
     }
+    /// Finds an attribute named `name`, with or without arguments, like
+    /// `#[inline]` or `#[inline(always)]`.
+    fn find_attr(&self, name: &str) -> Option<ast::Attr> {
+        self.attrs().find(|it| it.simple_name().map_or(false, |it| it == name))
+    }
 }
 
 pub trait DocCommentsOwner: AstNode {
@@ -127,24 +127,93 @@ impl Parse<SourceFile> {
     }
 
     pub fn reparse(&self, indel: &Indel) -> Parse<SourceFile> {
+        self.reparse_with_invalidation(indel).parse
+    }
+
+    fn reparse_with_invalidation(&self, indel: &Indel) -> ParseWithInvalidation {
         self.incremental_reparse(indel).unwrap_or_else(|| self.full_reparse(indel))
     }
 
-    fn incremental_reparse(&self, indel: &Indel) -> Option<Parse<SourceFile>> {
+    fn incremental_reparse(&self, indel: &Indel) -> Option<ParseWithInvalidation> {
+        let root = self.tree().syntax().clone();
         // FIXME: validation errors are not handled here
-        parsing::incremental_reparse(self.tree().syntax(), indel, self.errors.to_vec()).map(
-            |(green_node, errors, _reparsed_range)| Parse {
-                green: green_node,
-                errors: Arc::new(errors),
-                _ty: PhantomData,
+        let (green, errors, reparsed_range) =
+            parsing::incremental_reparse(&root, indel, self.errors.to_vec())?;
+        let old = match algo::find_covering_element(&root, reparsed_range) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent(),
+        };
+        let parse: Parse<SourceFile> = Parse::new(green, errors);
+
+        let old_range = old.text_range();
+        let new_range = TextRange::new(
+            old_range.start(),
+            old_range.end() + TextSize::of(&indel.insert) - indel.delete.len(),
+        );
+        let new_root = parse.tree().syntax().clone();
+        let new = match algo::find_covering_element(&new_root, new_range) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent(),
+        };
+        let new = new
+            .ancestors()
+            .find(|it| it.kind() == old.kind() && it.text_range() == new_range)
+            .expect("reparsed node is missing from the new tree");
+        Some(ParseWithInvalidation {
+            parse,
+            invalidated: InvalidationSet {
+                replaced: vec![(SyntaxNodePtr::new(&old), SyntaxNodePtr::new(&new))],
             },
-        )
+        })
     }
 
-    fn full_reparse(&self, indel: &Indel) -> Parse<SourceFile> {
-        let mut text = self.tree().syntax().text().to_string();
+    fn full_reparse(&self, indel: &Indel) -> ParseWithInvalidation {
+        let root = self.tree().syntax().clone();
+        let mut text = root.text().to_string();
         indel.apply(&mut text);
-        SourceFile::parse(&text)
+        let parse = SourceFile::parse(&text);
+        let replaced = (SyntaxNodePtr::new(&root), SyntaxNodePtr::new(parse.tree().syntax()));
+        ParseWithInvalidation { parse, invalidated: InvalidationSet { replaced: vec![replaced] } }
+    }
+}
+
+/// The result of reparsing a file after an edit, together with the set of
+/// nodes of the *old* tree which were replaced.
+#[derive(Debug, Clone)]
+pub struct ParseWithInvalidation {
+    pub parse: Parse<SourceFile>,
+    pub invalidated: InvalidationSet,
+}
+
+/// Nodes of a syntax tree which were replaced during a reparse.
+///
+/// rowan has no stable identity for green nodes, so the replaced nodes are
+/// recorded as pairs of `SyntaxNodePtr`s, one into the old tree and one to its
+/// replacement in the new tree.
+///
+/// Any node which does not overlap with the replaced nodes (and is not their
+/// ancestor) keeps its green node, so analyses keyed by it can be reused. Note
+/// that nodes after the edit might still have shifted text ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidationSet {
+    replaced: Vec<(SyntaxNodePtr, SyntaxNodePtr)>,
+}
+
+impl InvalidationSet {
+    /// Pairs of pointers to a reparsed node in the old tree and to the node
+    /// which replaced it in the new tree.
+    pub fn replaced(&self) -> &[(SyntaxNodePtr, SyntaxNodePtr)] {
+        &self.replaced
+    }
+
+    /// Checks if `node`, which belongs to the old tree, was invalidated by the
+    /// reparse, either because it was replaced itself, or because one of its
+    /// descendants or ancestors was.
+    pub fn is_invalidated(&self, node: &SyntaxNode) -> bool {
+        let range = node.text_range();
+        self.replaced
+            .iter()
+            .any(|(old, _)| old.range.contains_range(range) || range.contains_range(old.range))
     }
 }
 
@@ -152,6 +221,12 @@ impl Parse<SourceFile> {
 pub use crate::ast::SourceFile;
 
 impl SourceFile {
+    /// Applies `indel` to the file parsed as `old`, reparsing as little of it
+    /// as possible and recording which nodes were invalidated.
+    pub fn parse_incremental(old: &Parse<SourceFile>, indel: &Indel) -> ParseWithInvalidation {
+        old.reparse_with_invalidation(indel)
+    }
+
     pub fn parse(text: &str) -> Parse<SourceFile> {
         let (green, mut errors) = parsing::parse_text(text);
         let root = SyntaxNode::new_root(green.clone());
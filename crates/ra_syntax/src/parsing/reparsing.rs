@@ -194,7 +194,7 @@ mod tests {
     use test_utils::{assert_eq_text, extract_range};
 
     use super::*;
-    use crate::{AstNode, Parse, SourceFile};
+    use crate::{AstNode, Parse, SourceFile, SyntaxNodePtr};
 
     fn do_check(before: &str, replace_with: &str, reparsed_len: u32) {
         let (range, before) = extract_range(before);
@@ -455,4 +455,62 @@ enum Foo {
             105,
         )
     }
+
+    #[test]
+    fn reparse_reports_invalidated_nodes() {
+        let before = SourceFile::parse("fn foo() {\n    1 + 1\n}\nfn bar() {}\n");
+        let edit = Indel::replace(TextRange::at(19.into(), 1.into()), "92".to_string());
+        let after = SourceFile::parse_incremental(&before, &edit);
+        assert_eq!(
+            after.parse.tree().syntax().text().to_string(),
+            "fn foo() {\n    1 + 92\n}\nfn bar() {}\n"
+        );
+
+        let fns: Vec<_> =
+            before.tree().syntax().descendants().filter(|it| it.kind() == FN_DEF).collect();
+        assert!(after.invalidated.is_invalidated(&fns[0]));
+        assert!(!after.invalidated.is_invalidated(&fns[1]));
+        assert!(after.invalidated.is_invalidated(before.tree().syntax()));
+
+        let edit = Indel::insert(0.into(), "struct S;".to_string());
+        let after = SourceFile::parse_incremental(&before, &edit);
+        assert!(after.invalidated.is_invalidated(&fns[1]));
+    }
+
+    #[test]
+    fn reparse_keeps_green_nodes_outside_invalidated_set() {
+        let before = SourceFile::parse("fn foo() {\n    1 + 1\n}\nfn bar() { 2 }\n");
+        let edit = Indel::replace(TextRange::at(19.into(), 1.into()), "92".to_string());
+        let after = SourceFile::parse_incremental(&before, &edit);
+        let old_root = before.tree().syntax().clone();
+        let new_root = after.parse.tree().syntax().clone();
+
+        let (old, new) = match after.invalidated.replaced() {
+            [it] => it,
+            _ => panic!("expected a single replaced node"),
+        };
+        assert_eq!(old.to_node(&old_root).text().to_string(), "{\n    1 + 1\n}");
+        assert_eq!(new.to_node(&new_root).text().to_string(), "{\n    1 + 92\n}");
+
+        // Green nodes don't expose their identity, but a child of a green node
+        // is stored inline in it, so its address identifies the allocation.
+        let green_id = |node: &SyntaxNode| match node.green().children().next() {
+            Some(NodeOrToken::Node(it)) => it as *const GreenNode as *const u8,
+            Some(NodeOrToken::Token(it)) => it as *const GreenToken as *const u8,
+            None => std::ptr::null(),
+        };
+        let mut kept = 0;
+        for node in old_root.descendants() {
+            if after.invalidated.is_invalidated(&node) {
+                continue;
+            }
+            let mut ptr = SyntaxNodePtr::new(&node);
+            if ptr.range.start() >= edit.delete.end() {
+                ptr.range = ptr.range + TextSize::of("2");
+            }
+            assert_eq!(green_id(&node), green_id(&ptr.to_node(&new_root)), "{:?}", node);
+            kept += 1;
+        }
+        assert!(kept > 0);
+    }
 }
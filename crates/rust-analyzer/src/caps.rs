@@ -85,8 +85,13 @@ pub fn server_capabilities(client_caps: &ClientCapabilities) -> ServerCapabiliti
         experimental: Some(json!({
             "joinLines": true,
             "ssr": true,
+            "workspaceSsr": true,
             "onEnter": true,
             "parentModule": true,
+            "showImplBlocks": true,
+            "showType": true,
+            "findTodos": true,
+            "dependencyTree": true,
             "runnables": {
                 "kinds": [ "cargo" ],
             },
@@ -114,6 +119,8 @@ fn code_action_capabilities(client_caps: &ClientCapabilities) -> CodeActionProvi
                     lsp_types::code_action_kind::REFACTOR_REWRITE.to_string(),
                     lsp_types::code_action_kind::SOURCE.to_string(),
                     lsp_types::code_action_kind::SOURCE_ORGANIZE_IMPORTS.to_string(),
+                    "source.fixAll".to_string(),
+                    "source.removeDeadCode".to_string(),
                 ]),
                 work_done_progress_options: Default::default(),
             })
@@ -0,0 +1,278 @@
+//! Quick fixes which edit `Cargo.toml`.
+//!
+//! Manifests are not a part of the VFS, so such edits can't be expressed as
+//! `ra_ide` fixes, and are computed here instead, by reading the manifest from
+//! disk.
+
+use std::{collections::HashMap, fs};
+
+use lsp_types::{Position, Range, TextEdit};
+use ra_ide::{FeatureGatedItem, FileId};
+use ra_project_model::ProjectWorkspace;
+
+use crate::{
+    diagnostics::to_proto::url_from_path_with_drive_lowercasing, global_state::GlobalStateSnapshot,
+    lsp_ext, Result,
+};
+
+/// Creates a quick fix which enables the features required by `item` in the
+/// manifest of the package owning `file_id`.
+pub(crate) fn enable_feature_fix(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    item: &FeatureGatedItem,
+) -> Result<Option<lsp_ext::CodeAction>> {
+    let &crate_id = match snap.analysis().crate_for(file_id)?.first() {
+        Some(crate_id) => crate_id,
+        None => return Ok(None),
+    };
+    let root = snap.file_id_to_path(snap.analysis().crate_root(crate_id)?);
+    let manifest = snap.workspaces.iter().find_map(|ws| match ws {
+        ProjectWorkspace::Cargo { cargo, .. } => {
+            let tgt = cargo.target_by_root(&root)?;
+            Some(cargo[cargo[tgt].package].manifest.clone())
+        }
+        ProjectWorkspace::Json { .. } => None,
+    });
+    let manifest = match manifest {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let text = match fs::read_to_string(&manifest) {
+        Ok(it) => it,
+        Err(err) => {
+            log::error!("failed to read {}: {}", manifest.display(), err);
+            return Ok(None);
+        }
+    };
+    let edit = match enable_dependency_features(&text, &item.dependency, &item.features) {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+
+    let url = url_from_path_with_drive_lowercasing(&manifest)?;
+    let mut changes = HashMap::new();
+    changes.insert(url, vec![edit]);
+    let title = format!(
+        "Enable feature `{}` of `{}` in Cargo.toml",
+        item.features.join("`, `"),
+        item.dependency
+    );
+    Ok(Some(lsp_ext::CodeAction {
+        title,
+        id: None,
+        group: None,
+        kind: Some(lsp_types::code_action_kind::QUICKFIX.into()),
+        command: None,
+        edit: Some(lsp_ext::SnippetWorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+        }),
+    }))
+}
+
+/// Computes an edit which adds `features` to the list of enabled features of
+/// `dependency`. Only lines are replaced or inserted, and multi-line feature
+/// arrays are not supported.
+fn enable_dependency_features(
+    manifest: &str,
+    dependency: &str,
+    features: &[String],
+) -> Option<TextEdit> {
+    let normalize = |name: &str| name.trim().trim_matches('"').replace('-', "_");
+    let dependency = normalize(dependency);
+    let quoted = features.iter().map(|it| format!("\"{}\"", it)).collect::<Vec<_>>().join(", ");
+
+    let mut in_dependencies = false;
+    let mut in_dependency_table = false;
+    let mut last_line_in_table = 0;
+    for (idx, line) in manifest.lines().enumerate() {
+        let idx = idx as u64;
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            if in_dependency_table {
+                break;
+            }
+            let header = trimmed.trim_start_matches('[').trim_end_matches(']');
+            in_dependencies = header.ends_with("dependencies");
+            if let Some(pos) = header.rfind("dependencies.") {
+                let name = &header[pos + "dependencies.".len()..];
+                if normalize(name) == dependency {
+                    in_dependency_table = true;
+                    last_line_in_table = idx;
+                }
+            }
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match split_key_value(trimmed) {
+            Some(it) => it,
+            None => continue,
+        };
+        if in_dependency_table {
+            last_line_in_table = idx;
+            if key == "features" {
+                let new_line = push_to_array(line, features)?;
+                return Some(replace_line(idx, line, new_line));
+            }
+            continue;
+        }
+        if !in_dependencies || normalize(key) != dependency {
+            continue;
+        }
+        let new_value = if value.starts_with('"') {
+            format!("{{ version = {}, features = [{}] }}", value, quoted)
+        } else if value.starts_with('{') && value.ends_with('}') {
+            if value.contains("features") {
+                return Some(replace_line(idx, line, push_to_array(line, features)?));
+            }
+            let inner = value[1..value.len() - 1].trim();
+            let sep = if inner.is_empty() { "" } else { ", " };
+            format!("{{ {}{}features = [{}] }}", inner, sep, quoted)
+        } else {
+            return None;
+        };
+        let value_start = line.len() - line.trim_start().len() + trimmed.find(value)?;
+        let new_line = format!("{}{}", &line[..value_start], new_value);
+        return Some(replace_line(idx, line, new_line));
+    }
+
+    if !in_dependency_table {
+        return None;
+    }
+    let insert_at = Position::new(last_line_in_table + 1, 0);
+    Some(TextEdit {
+        range: Range::new(insert_at, insert_at),
+        new_text: format!("features = [{}]\n", quoted),
+    })
+}
+
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let eq = line.find('=')?;
+    Some((line[..eq].trim().trim_matches('"'), line[eq + 1..].trim()))
+}
+
+/// Adds the missing `features` to the single-line `features = [...]` array in
+/// `line`.
+fn push_to_array(line: &str, features: &[String]) -> Option<String> {
+    let start = line.find("features")?;
+    let open = start + line[start..].find('[')?;
+    let close = open + line[open..].find(']')?;
+    let mut items: Vec<String> = line[open + 1..close]
+        .split(',')
+        .map(|it| it.trim().to_string())
+        .filter(|it| !it.is_empty())
+        .collect();
+    let old_len = items.len();
+    for feature in features {
+        let quoted = format!("\"{}\"", feature);
+        if !items.contains(&quoted) {
+            items.push(quoted);
+        }
+    }
+    if items.len() == old_len {
+        return None;
+    }
+    Some(format!("{}{}{}", &line[..open + 1], items.join(", "), &line[close..]))
+}
+
+fn replace_line(idx: u64, line: &str, new_line: String) -> TextEdit {
+    let len = line.encode_utf16().count() as u64;
+    TextEdit {
+        range: Range::new(Position::new(idx, 0), Position::new(idx, len)),
+        new_text: new_line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(manifest: &str, dependency: &str, features: &[&str], expected: &str) {
+        let features: Vec<String> = features.iter().map(|it| it.to_string()).collect();
+        let edit = enable_dependency_features(manifest, dependency, &features).unwrap();
+        let mut lines: Vec<&str> = manifest.lines().collect();
+        let idx = edit.range.start.line as usize;
+        if edit.range.start == edit.range.end {
+            lines.insert(idx, edit.new_text.trim_end());
+        } else {
+            lines[idx] = &edit.new_text;
+        }
+        assert_eq!(format!("{}\n", lines.join("\n")), expected);
+    }
+
+    #[test]
+    fn version_string() {
+        check(
+            "[package]\nname = \"foo\"\n\n[dependencies]\nserde = \"1.0\"\n",
+            "serde",
+            &["derive"],
+            "[package]\nname = \"foo\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\n",
+        );
+    }
+
+    #[test]
+    fn inline_table() {
+        check(
+            "[dependencies]\nra-cfg = { path = \"../ra_cfg\" }\n",
+            "ra_cfg",
+            &["std"],
+            "[dependencies]\nra-cfg = { path = \"../ra_cfg\", features = [\"std\"] }\n",
+        );
+        check(
+            "[dev-dependencies]\nfoo = { version = \"1\", features = [\"a\"] }\n",
+            "foo",
+            &["b"],
+            "[dev-dependencies]\nfoo = { version = \"1\", features = [\"a\", \"b\"] }\n",
+        );
+    }
+
+    #[test]
+    fn dependency_table() {
+        check(
+            "[dependencies.foo]\nversion = \"1\"\n\n[features]\n",
+            "foo",
+            &["b"],
+            "[dependencies.foo]\nversion = \"1\"\nfeatures = [\"b\"]\n\n[features]\n",
+        );
+        check(
+            "[dependencies.foo]\nfeatures = []\n",
+            "foo",
+            &["b"],
+            "[dependencies.foo]\nfeatures = [\"b\"]\n",
+        );
+    }
+
+    #[test]
+    fn ignores_other_sections() {
+        check(
+            "[features]\nfoo = []\n[dependencies]\nfoo = \"1\"\n",
+            "foo",
+            &["b"],
+            "[features]\nfoo = []\n[dependencies]\nfoo = { version = \"1\", features = [\"b\"] }\n",
+        );
+    }
+
+    #[test]
+    fn several_features() {
+        check(
+            "[dependencies]\nfoo = { version = \"1\", features = [\"a\"] }\n",
+            "foo",
+            &["a", "b", "c"],
+            "[dependencies]\nfoo = { version = \"1\", features = [\"a\", \"b\", \"c\"] }\n",
+        );
+    }
+
+    #[test]
+    fn features_already_enabled() {
+        let features = vec!["b".to_string()];
+        assert!(enable_dependency_features(
+            "[dependencies]\nfoo = { version = \"1\", features = [\"b\"] }\n",
+            "foo",
+            &features,
+        )
+        .is_none());
+    }
+}
@@ -3,7 +3,7 @@
 
 use anyhow::anyhow;
 use ra_db::SourceDatabaseExt;
-use ra_ide::Severity;
+use ra_ide::{DiagnosticsConfig, Severity};
 use std::{collections::HashSet, path::Path};
 
 use crate::cli::{load_cargo::load_cargo, Result};
@@ -53,7 +53,9 @@ pub fn diagnostics(
                         crate_name,
                         db.file_relative_path(file_id)
                     );
-                    for diagnostic in analysis.diagnostics(file_id).unwrap() {
+                    for diagnostic in
+                        analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap()
+                    {
                         if matches!(diagnostic.severity, Severity::Error) {
                             found_error = true;
                         }
@@ -7,11 +7,13 @@
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
 
-use std::{ffi::OsString, path::PathBuf};
+use std::{ffi::OsString, path::PathBuf, time::Duration};
 
 use lsp_types::ClientCapabilities;
 use ra_flycheck::FlycheckConfig;
-use ra_ide::{AssistConfig, CompletionConfig, HoverConfig, InlayHintsConfig};
+use ra_ide::{
+    AssistConfig, CompletionConfig, DiagnosticsConfig, HoverConfig, InlayHintsConfig, RcCloneStyle,
+};
 use ra_project_model::{CargoConfig, JsonProject, ProjectManifest};
 use serde::Deserialize;
 
@@ -20,6 +22,7 @@ pub struct Config {
     pub client_caps: ClientCapsConfig,
 
     pub publish_diagnostics: bool,
+    pub diagnostics: DiagnosticsConfig,
     pub lru_capacity: Option<usize>,
     pub proc_macro_srv: Option<(PathBuf, Vec<OsString>)>,
     pub files: FilesConfig,
@@ -27,7 +30,12 @@ pub struct Config {
 
     pub cargo: CargoConfig,
     pub rustfmt: RustfmtConfig,
+    /// Only the lines changed since the last commit are formatted. This needs
+    /// a nightly `rustfmt`, otherwise the whole file is formatted.
+    pub formatting_only_changed_lines: bool,
     pub check: Option<FlycheckConfig>,
+    /// A running check is cancelled if it was started less than this ago.
+    pub check_debounce: Duration,
 
     pub inlay_hints: InlayHintsConfig,
     pub completion: CompletionConfig,
@@ -35,6 +43,9 @@ pub struct Config {
     pub call_info_full: bool,
     pub lens: LensConfig,
     pub hover: HoverConfig,
+    pub hover_rustdoc_json: Option<PathBuf>,
+    /// The markers, which are listed by `rust-analyzer/findTodos`.
+    pub todo_keywords: Vec<String>,
 
     pub with_sysroot: bool,
     pub linked_projects: Vec<LinkedProject>,
@@ -63,19 +74,22 @@ pub struct LensConfig {
     pub run: bool,
     pub debug: bool,
     pub impementations: bool,
+    pub callers: bool,
+    pub inline: bool,
 }
 
 impl Default for LensConfig {
     fn default() -> Self {
-        Self { run: true, debug: true, impementations: true }
+        Self { run: true, debug: true, impementations: true, callers: false, inline: false }
     }
 }
 
 impl LensConfig {
-    pub const NO_LENS: LensConfig = Self { run: false, debug: false, impementations: false };
+    pub const NO_LENS: LensConfig =
+        Self { run: false, debug: false, impementations: false, callers: false, inline: false };
 
     pub fn any(&self) -> bool {
-        self.impementations || self.runnable()
+        self.impementations || self.callers || self.inline || self.runnable()
     }
 
     pub fn none(&self) -> bool {
@@ -126,6 +140,7 @@ pub struct ClientCapsConfig {
     pub code_action_group: bool,
     pub resolve_code_action: bool,
     pub hover_actions: bool,
+    pub apply_edit: bool,
 }
 
 impl Default for Config {
@@ -135,6 +150,7 @@ impl Default for Config {
 
             with_sysroot: true,
             publish_diagnostics: true,
+            diagnostics: DiagnosticsConfig::default(),
             lru_capacity: None,
             proc_macro_srv: None,
             files: FilesConfig { watcher: FilesWatcher::Notify, exclude: Vec::new() },
@@ -142,6 +158,7 @@ impl Default for Config {
 
             cargo: CargoConfig::default(),
             rustfmt: RustfmtConfig::Rustfmt { extra_args: Vec::new() },
+            formatting_only_changed_lines: false,
             check: Some(FlycheckConfig::CargoCommand {
                 command: "check".to_string(),
                 all_targets: true,
@@ -149,11 +166,13 @@ impl Default for Config {
                 extra_args: Vec::new(),
                 features: Vec::new(),
             }),
+            check_debounce: Duration::from_millis(500),
 
             inlay_hints: InlayHintsConfig {
                 type_hints: true,
                 parameter_hints: true,
                 chaining_hints: true,
+                trait_bound_hints: false,
                 max_length: None,
             },
             completion: CompletionConfig {
@@ -166,6 +185,13 @@ impl Default for Config {
             call_info_full: true,
             lens: LensConfig::default(),
             hover: HoverConfig::default(),
+            hover_rustdoc_json: None,
+            todo_keywords: vec![
+                "TODO".to_string(),
+                "FIXME".to_string(),
+                "HACK".to_string(),
+                "XXX".to_string(),
+            ],
             linked_projects: Vec::new(),
         }
     }
@@ -182,6 +208,19 @@ impl Config {
 
         set(value, "/withSysroot", &mut self.with_sysroot);
         set(value, "/diagnostics/enable", &mut self.publish_diagnostics);
+        if let Some(style) = value.pointer("/diagnostics/rcCloneStyle") {
+            self.diagnostics.rc_clone_style = match RcCloneStyleConfig::deserialize(style) {
+                Ok(RcCloneStyleConfig::Off) => None,
+                Ok(RcCloneStyleConfig::Explicit) => Some(RcCloneStyle::Explicit),
+                Ok(RcCloneStyleConfig::Method) => Some(RcCloneStyle::Method),
+                Err(e) => {
+                    log::error!("invalid diagnostics.rcCloneStyle: {}", e);
+                    None
+                }
+            };
+        }
+        set(value, "/diagnostics/warnOnTodo", &mut self.diagnostics.warn_on_todo);
+        set(value, "/diagnostics/warnOnWindowsPaths", &mut self.diagnostics.warn_on_windows_paths);
         set(value, "/lruCapacity", &mut self.lru_capacity);
         self.files.watcher = match get(value, "/files/watcher") {
             Some("client") => FilesWatcher::Client,
@@ -218,6 +257,7 @@ impl Config {
                 }
             }
         };
+        set(value, "/formatting/onlyChangedLines", &mut self.formatting_only_changed_lines);
 
         if let Some(false) = get(value, "/checkOnSave/enable") {
             // check is disabled
@@ -248,9 +288,14 @@ impl Config {
             };
         }
 
+        if let Some(debounce_ms) = get(value, "/checkOnSave/debounceMs") {
+            self.check_debounce = Duration::from_millis(debounce_ms);
+        }
+
         set(value, "/inlayHints/typeHints", &mut self.inlay_hints.type_hints);
         set(value, "/inlayHints/parameterHints", &mut self.inlay_hints.parameter_hints);
         set(value, "/inlayHints/chainingHints", &mut self.inlay_hints.chaining_hints);
+        set(value, "/inlayHints/traitBounds", &mut self.inlay_hints.trait_bound_hints);
         set(value, "/inlayHints/maxLength", &mut self.inlay_hints.max_length);
         set(value, "/completion/postfix/enable", &mut self.completion.enable_postfix_completions);
         set(value, "/completion/addCallParenthesis", &mut self.completion.add_call_parenthesis);
@@ -263,6 +308,8 @@ impl Config {
             set(value, "/lens/run", &mut self.lens.run);
             set(value, "/lens/debug", &mut self.lens.debug);
             set(value, "/lens/implementations", &mut self.lens.impementations);
+            set(value, "/lens/callers", &mut self.lens.callers);
+            set(value, "/lens/inline", &mut self.lens.inline);
         } else {
             self.lens = LensConfig::NO_LENS;
         }
@@ -292,6 +339,16 @@ impl Config {
         } else {
             self.hover = HoverConfig::NO_ACTIONS;
         }
+        set(value, "/hover/rustdocJsonPath", &mut self.hover_rustdoc_json);
+        set(value, "/todos/keywords", &mut self.todo_keywords);
+        set(value, "/assist/omitDefaultFieldsInNew", &mut self.assist.omit_default_fields_in_new);
+        set(value, "/assist/inlineHints", &mut self.assist.inline_hints);
+        set(value, "/assist/prefixGetters", &mut self.assist.prefix_getters);
+        set(
+            value,
+            "/assist/caseInsensitiveStrConversions",
+            &mut self.assist.case_insensitive_str_conversions,
+        );
 
         log::info!("Config::update() = {:#?}", self);
 
@@ -338,6 +395,10 @@ impl Config {
             }
         }
 
+        if let Some(value) = caps.workspace.as_ref().and_then(|it| it.apply_edit) {
+            self.client_caps.apply_edit = value;
+        }
+
         if let Some(window_caps) = caps.window.as_ref() {
             if let Some(value) = window_caps.work_done_progress {
                 self.client_caps.work_done_progress = value;
@@ -359,6 +420,14 @@ impl Config {
     }
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum RcCloneStyleConfig {
+    Off,
+    Explicit,
+    Method,
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum ManifestOrJsonProject {
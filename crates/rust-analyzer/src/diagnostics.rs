@@ -1,20 +1,32 @@
 //! Book keeping for keeping diagnostics easily in sync with the client.
 pub(crate) mod to_proto;
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use lsp_types::{Diagnostic, Range};
+use lsp_types::{Diagnostic, NumberOrString, Range, TextEdit};
 use ra_ide::FileId;
 
 use crate::lsp_ext;
 
 pub type CheckFixes = Arc<HashMap<FileId, Vec<Fix>>>;
 
+/// The ranges of the items reported by the `dead_code` lint of `cargo check`.
+pub type DeadCode = Arc<HashMap<FileId, Vec<Range>>>;
+
+/// The files which were edited after `cargo check` reported fixes for them,
+/// so that the ranges of the fixes may be outdated.
+pub type StaleFixes = Arc<HashSet<FileId>>;
+
 #[derive(Debug, Default, Clone)]
 pub struct DiagnosticCollection {
     pub native: HashMap<FileId, Vec<Diagnostic>>,
     pub check: HashMap<FileId, Vec<Diagnostic>>,
     pub check_fixes: CheckFixes,
+    pub dead_code: DeadCode,
+    pub stale_fixes: StaleFixes,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +45,8 @@ pub enum DiagnosticTask {
 impl DiagnosticCollection {
     pub fn clear_check(&mut self) -> Vec<FileId> {
         Arc::make_mut(&mut self.check_fixes).clear();
+        Arc::make_mut(&mut self.dead_code).clear();
+        Arc::make_mut(&mut self.stale_fixes).clear();
         self.check.drain().map(|(key, _value)| key).collect()
     }
 
@@ -54,9 +68,18 @@ impl DiagnosticCollection {
             .entry(file_id)
             .or_default()
             .extend(fixes.into_iter().map(|action| Fix { range: diagnostic.range, action }));
+        if diagnostic.code == Some(NumberOrString::String("dead_code".to_string())) {
+            Arc::make_mut(&mut self.dead_code).entry(file_id).or_default().push(diagnostic.range);
+        }
         diagnostics.push(diagnostic);
     }
 
+    pub fn mark_edited(&mut self, file_id: FileId) {
+        if !self.stale_fixes.contains(&file_id) {
+            Arc::make_mut(&mut self.stale_fixes).insert(file_id);
+        }
+    }
+
     pub fn set_native_diagnostics(&mut self, file_id: FileId, diagnostics: Vec<Diagnostic>) {
         self.native.insert(file_id, diagnostics);
     }
@@ -88,3 +111,53 @@ fn are_diagnostics_equal(left: &Diagnostic, right: &Diagnostic) -> bool {
         && left.range == right.range
         && left.message == right.message
 }
+
+/// Combines the edits of several fixes into a single list of edits, skipping
+/// the fixes which are duplicates or overlap with the fixes before them.
+pub fn combine_fixes(fixes: impl IntoIterator<Item = Vec<TextEdit>>) -> Vec<TextEdit> {
+    let mut res: Vec<TextEdit> = Vec::new();
+    let mut seen: Vec<Vec<TextEdit>> = Vec::new();
+    for fix in fixes {
+        if seen.contains(&fix) {
+            continue;
+        }
+        let conflicts = fix.iter().enumerate().any(|(idx, edit)| {
+            fix[..idx].iter().chain(res.iter()).any(|other| overlap(edit.range, other.range))
+        });
+        if conflicts {
+            continue;
+        }
+        res.extend(fix.iter().cloned());
+        seen.push(fix);
+    }
+    res.sort_by_key(|it| it.range.start);
+    res
+}
+
+/// Two insertions at the same position overlap, as their order is ambiguous.
+fn overlap(left: Range, right: Range) -> bool {
+    left.start == right.start || (left.start < right.end && right.start < left.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::Position;
+
+    use super::*;
+
+    fn edit(start: u64, end: u64, text: &str) -> TextEdit {
+        TextEdit::new(Range::new(Position::new(0, start), Position::new(0, end)), text.to_string())
+    }
+
+    #[test]
+    fn combine_fixes_skips_duplicates_and_conflicts() {
+        let combined = combine_fixes(vec![
+            vec![edit(10, 12, "_x")],
+            vec![edit(0, 4, "")],
+            vec![edit(10, 12, "_x")],
+            vec![edit(11, 14, "y"), edit(20, 21, "z")],
+            vec![edit(4, 6, "")],
+        ]);
+        assert_eq!(combined, vec![edit(0, 4, ""), edit(4, 6, ""), edit(10, 12, "_x")]);
+    }
+}
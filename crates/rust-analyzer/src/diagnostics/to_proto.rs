@@ -1,6 +1,7 @@
 //! This module provides the functionality needed to convert diagnostics from
 //! `cargo check` json format to the LSP diagnostic format.
 use std::{
+    borrow::Cow,
     collections::HashMap,
     path::{Component, Path, Prefix},
     str::FromStr,
@@ -76,6 +77,56 @@ fn map_span_to_location_naive(span: &DiagnosticSpan, workspace_root: &Path) -> L
     Location { uri, range }
 }
 
+/// Removes the ANSI escape sequences, like the colors in `\x1b[31merror\x1b[0m`,
+/// which some versions of rustc and clippy emit in the messages.
+fn strip_ansi_codes(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+    let mut res = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            // A control sequence ends with a byte in `@..=~`.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // An operating system command ends with BEL or `ESC \`.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        if chars.peek() == Some(&'\\') {
+                            chars.next();
+                        }
+                        break;
+                    }
+                }
+            }
+            // Other sequences are intermediate bytes in ` ..=/` followed by a final byte.
+            Some(c) if ('\x20'..='\x2f').contains(&c) => {
+                for c in chars.by_ref() {
+                    if !('\x20'..='\x2f').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Cow::Owned(res)
+}
+
 /// Converts a secondary Rust span to a LSP related information
 ///
 /// If the span is unlabelled this will return `None`.
@@ -83,7 +134,7 @@ fn map_secondary_span_to_related(
     span: &DiagnosticSpan,
     workspace_root: &Path,
 ) -> Option<DiagnosticRelatedInformation> {
-    let message = span.label.clone()?;
+    let message = strip_ansi_codes(span.label.as_ref()?).into_owned();
     let location = map_span_to_location(span, workspace_root);
     Some(DiagnosticRelatedInformation { location, message })
 }
@@ -122,7 +173,7 @@ fn map_rust_child_diagnostic(
     if spans.is_empty() {
         // `rustc` uses these spanless children as a way to print multi-line
         // messages
-        return MappedRustChildDiagnostic::MessageLine(rd.message.clone());
+        return MappedRustChildDiagnostic::MessageLine(strip_ansi_codes(&rd.message).into_owned());
     }
 
     let mut edit_map: HashMap<Url, Vec<TextEdit>> = HashMap::new();
@@ -140,11 +191,11 @@ fn map_rust_child_diagnostic(
     if edit_map.is_empty() {
         MappedRustChildDiagnostic::Related(DiagnosticRelatedInformation {
             location: map_span_to_location(spans[0], workspace_root),
-            message: rd.message.clone(),
+            message: strip_ansi_codes(&rd.message).into_owned(),
         })
     } else {
         MappedRustChildDiagnostic::SuggestedFix(lsp_ext::CodeAction {
-            title: rd.message.clone(),
+            title: strip_ansi_codes(&rd.message).into_owned(),
             id: None,
             group: None,
             kind: Some("quickfix".to_string()),
@@ -209,7 +260,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
     }
 
     let mut fixes = Vec::new();
-    let mut message = rd.message.clone();
+    let mut message = strip_ansi_codes(&rd.message).into_owned();
     for child in &rd.children {
         let child = map_rust_child_diagnostic(&child, workspace_root);
         match child {
@@ -242,7 +293,7 @@ pub(crate) fn map_rust_diagnostic_to_lsp(
             let mut message = message.clone();
             if needs_primary_span_label {
                 if let Some(primary_span_label) = &primary_span.label {
-                    format_to!(message, "\n{}", primary_span_label);
+                    format_to!(message, "\n{}", strip_ansi_codes(primary_span_label));
                 }
             }
 
@@ -332,6 +383,20 @@ mod tests {
         assert_eq!(url.to_string(), "file://localhost/C$/my_dir");
     }
 
+    #[test]
+    fn test_strip_ansi_codes() {
+        assert!(matches!(strip_ansi_codes("unused variable: `x`"), Cow::Borrowed(_)));
+        assert_eq!(
+            strip_ansi_codes("\x1b[0m\x1b[1m\x1b[38;5;9merror\x1b[0m: mismatched types"),
+            "error: mismatched types"
+        );
+        assert_eq!(
+            strip_ansi_codes("see \x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\\x1b(B"),
+            "see link"
+        );
+        assert_eq!(strip_ansi_codes("trailing \x1b["), "trailing ");
+    }
+
     #[cfg(not(windows))]
     fn parse_diagnostic(val: &str) -> ra_flycheck::Diagnostic {
         serde_json::from_str::<ra_flycheck::Diagnostic>(val).unwrap()
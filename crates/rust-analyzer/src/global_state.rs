@@ -6,11 +6,12 @@
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use crossbeam_channel::{unbounded, Receiver};
 use lsp_types::Url;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use ra_flycheck::{Flycheck, FlycheckConfig};
 use ra_ide::{
     Analysis, AnalysisChange, AnalysisHost, CrateGraph, FileId, LibraryData, SourceRootId,
@@ -23,16 +24,22 @@ use stdx::format_to;
 use crate::{
     config::Config,
     diagnostics::{
-        to_proto::url_from_path_with_drive_lowercasing, CheckFixes, DiagnosticCollection,
+        to_proto::url_from_path_with_drive_lowercasing, CheckFixes, DeadCode, DiagnosticCollection,
+        StaleFixes,
     },
     main_loop::pending_requests::{CompletedRequest, LatestRequests},
+    rustdoc_json::RustdocJsonCache,
     vfs_glob::{Glob, RustPackageFilterBuilder},
     LspError, Result,
 };
 use ra_db::ExternSourceId;
 use rustc_hash::{FxHashMap, FxHashSet};
 
-fn create_flycheck(workspaces: &[ProjectWorkspace], config: &FlycheckConfig) -> Option<Flycheck> {
+fn create_flycheck(
+    workspaces: &[ProjectWorkspace],
+    config: &FlycheckConfig,
+    debounce: Duration,
+) -> Option<Flycheck> {
     // FIXME: Figure out the multi-workspace situation
     workspaces
         .iter()
@@ -42,7 +49,7 @@ fn create_flycheck(workspaces: &[ProjectWorkspace], config: &FlycheckConfig) ->
         })
         .map(|cargo| {
             let cargo_project_root = cargo.workspace_root().to_path_buf();
-            Some(Flycheck::new(config.clone(), cargo_project_root))
+            Some(Flycheck::new(config.clone(), debounce, cargo_project_root))
         })
         .unwrap_or_else(|| {
             log::warn!("Cargo check watching only supported for cargo workspaces, disabling");
@@ -67,6 +74,7 @@ pub struct GlobalState {
     pub flycheck: Option<Flycheck>,
     pub diagnostics: DiagnosticCollection,
     pub proc_macro_client: ProcMacroClient,
+    pub rustdoc_json: Arc<Mutex<Option<Arc<RustdocJsonCache>>>>,
 }
 
 /// An immutable snapshot of the world's state at a point in time.
@@ -76,7 +84,10 @@ pub struct GlobalStateSnapshot {
     pub analysis: Analysis,
     pub latest_requests: Arc<RwLock<LatestRequests>>,
     pub check_fixes: CheckFixes,
+    pub dead_code: DeadCode,
+    pub stale_fixes: StaleFixes,
     vfs: Arc<RwLock<Vfs>>,
+    rustdoc_json: Arc<Mutex<Option<Arc<RustdocJsonCache>>>>,
 }
 
 impl GlobalState {
@@ -166,7 +177,10 @@ impl GlobalState {
         }
         change.set_crate_graph(crate_graph);
 
-        let flycheck = config.check.as_ref().and_then(|c| create_flycheck(&workspaces, c));
+        let flycheck = config
+            .check
+            .as_ref()
+            .and_then(|c| create_flycheck(&workspaces, c, config.check_debounce));
 
         let mut analysis_host = AnalysisHost::new(lru_capacity);
         analysis_host.apply_change(change);
@@ -181,14 +195,18 @@ impl GlobalState {
             flycheck,
             diagnostics: Default::default(),
             proc_macro_client,
+            rustdoc_json: Default::default(),
         }
     }
 
     pub fn update_configuration(&mut self, config: Config) {
         self.analysis_host.update_lru_capacity(config.lru_capacity);
-        if config.check != self.config.check {
-            self.flycheck =
-                config.check.as_ref().and_then(|it| create_flycheck(&self.workspaces, it));
+        if config.check != self.config.check || config.check_debounce != self.config.check_debounce
+        {
+            self.flycheck = config
+                .check
+                .as_ref()
+                .and_then(|it| create_flycheck(&self.workspaces, it, config.check_debounce));
         }
 
         self.config = config;
@@ -253,6 +271,9 @@ impl GlobalState {
             vfs: Arc::clone(&self.vfs),
             latest_requests: Arc::clone(&self.latest_requests),
             check_fixes: Arc::clone(&self.diagnostics.check_fixes),
+            dead_code: Arc::clone(&self.diagnostics.dead_code),
+            stale_fixes: Arc::clone(&self.diagnostics.stale_fixes),
+            rustdoc_json: Arc::clone(&self.rustdoc_json),
         }
     }
 
@@ -302,6 +323,23 @@ impl GlobalStateSnapshot {
         self.vfs.read().file_line_endings(VfsFile(id.0))
     }
 
+    /// Looks up the docs of a standard library item in the configured rustdoc
+    /// JSON, reloading it if the files have changed.
+    pub fn sysroot_docs(&self, item_path: &str) -> Option<String> {
+        let json_dir = self.config.hover_rustdoc_json.as_ref()?;
+        let cached = self.rustdoc_json.lock().clone();
+        let cache = match cached {
+            Some(it) if !it.is_stale(json_dir) => it,
+            // The lock isn't held while parsing, which takes a while for `std`.
+            _ => {
+                let cache = Arc::new(RustdocJsonCache::load(json_dir));
+                *self.rustdoc_json.lock() = Some(Arc::clone(&cache));
+                cache
+            }
+        };
+        cache.docs(item_path).map(ToString::to_string)
+    }
+
     pub fn path_to_uri(&self, root: SourceRootId, path: &RelativePathBuf) -> Result<Url> {
         let base = self.vfs.read().root2path(VfsRoot(root.0));
         let path = path.to_path(base);
@@ -19,11 +19,13 @@ macro_rules! eprintln {
 
 mod vfs_glob;
 mod caps;
+mod cargo_manifest;
 mod cargo_target_spec;
 mod to_proto;
 mod from_proto;
 mod main_loop;
 mod markdown;
+mod rustdoc_json;
 pub mod lsp_ext;
 pub mod config;
 mod global_state;
@@ -82,6 +82,62 @@ impl Request for ParentModule {
     const METHOD: &'static str = "experimental/parentModule";
 }
 
+pub enum ShowImplBlocks {}
+
+impl Request for ShowImplBlocks {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Vec<lsp_types::Location>;
+    const METHOD: &'static str = "rust-analyzer/showImplBlocks";
+}
+
+pub enum DependencyTree {}
+
+impl Request for DependencyTree {
+    type Params = DependencyTreeParams;
+    type Result = Vec<DependencyNode>;
+    const METHOD: &'static str = "rust-analyzer/dependencyTree";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyTreeParams {
+    pub package: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyNode {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<DependencyNode>,
+    /// Whether the dependencies of this package are omitted, because they
+    /// are already listed elsewhere in the tree.
+    pub deduplicated: bool,
+}
+
+pub enum ShowType {}
+
+impl Request for ShowType {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "rust-analyzer/showType";
+}
+
+pub enum FindTodos {}
+
+impl Request for FindTodos {
+    type Params = ();
+    type Result = Vec<FileTodos>;
+    const METHOD: &'static str = "rust-analyzer/findTodos";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTodos {
+    pub uri: lsp_types::Url,
+    pub diagnostics: Vec<lsp_types::Diagnostic>,
+}
+
 pub enum JoinLines {}
 
 impl Request for JoinLines {
@@ -182,6 +238,7 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    TraitBoundHint,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -206,6 +263,24 @@ pub struct SsrParams {
     pub parse_only: bool,
 }
 
+pub enum WorkspaceSsr {}
+
+impl Request for WorkspaceSsr {
+    type Params = WorkspaceSsrParams;
+    type Result = lsp_types::WorkspaceEdit;
+    const METHOD: &'static str = "rust-analyzer/ssr";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSsrParams {
+    pub pattern: String,
+    pub replacement: String,
+    /// If true, only compute the edit, without asking the client to apply it.
+    #[serde(default)]
+    pub preview: bool,
+}
+
 pub enum CodeActionRequest {}
 
 impl Request for CodeActionRequest {
@@ -229,6 +304,36 @@ pub struct CodeAction {
     pub edit: Option<SnippetWorkspaceEdit>,
 }
 
+pub enum FoldingRangeRequest {}
+
+impl Request for FoldingRangeRequest {
+    type Params = lsp_types::FoldingRangeParams;
+    type Result = Option<Vec<FoldingRange>>;
+    const METHOD: &'static str = "textDocument/foldingRange";
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldingRange {
+    pub start_line: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_character: Option<u64>,
+    pub end_line: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_character: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<FoldingRangeKind>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingRangeKind {
+    Comment,
+    Imports,
+    Region,
+    InactiveCode,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnippetWorkspaceEdit {
@@ -211,9 +211,9 @@ pub fn main_loop(config: Config, connection: Connection) -> Result<()> {
     }
     global_state.analysis_host.request_cancellation();
     log::info!("waiting for tasks to finish...");
-    task_receiver.into_iter().for_each(|task| {
-        on_task(task, &connection.sender, &mut loop_state.pending_requests, &mut global_state)
-    });
+    task_receiver
+        .into_iter()
+        .for_each(|task| on_task(task, &connection.sender, &mut loop_state, &mut global_state));
     libdata_receiver.into_iter().for_each(drop);
     log::info!("...tasks have finished");
     log::info!("joining threadpool...");
@@ -232,6 +232,31 @@ enum Task {
     Respond(Response),
     Notify(Notification),
     Diagnostic(DiagnosticTask),
+    ApplyEdit(lsp_types::WorkspaceEdit),
+}
+
+/// Sends `$/progress` notifications for requests, which are executed on the
+/// thread pool.
+pub struct ProgressSender(Sender<Task>);
+
+impl ProgressSender {
+    pub fn send(&self, token: &lsp_types::ProgressToken, progress: WorkDoneProgress) {
+        let not =
+            notification_new::<lsp_types::notification::Progress>(lsp_types::ProgressParams {
+                token: token.clone(),
+                value: lsp_types::ProgressParamsValue::WorkDone(progress),
+            });
+        self.0.send(Task::Notify(not)).unwrap();
+    }
+}
+
+/// Asks the client to apply workspace edits computed on the thread pool.
+pub struct EditSender(Sender<Task>);
+
+impl EditSender {
+    pub fn apply(&self, edit: lsp_types::WorkspaceEdit) {
+        self.0.send(Task::ApplyEdit(edit)).unwrap();
+    }
 }
 
 enum Event {
@@ -328,7 +353,7 @@ fn loop_turn(
 
     match event {
         Event::Task(task) => {
-            on_task(task, &connection.sender, &mut loop_state.pending_requests, global_state);
+            on_task(task, &connection.sender, loop_state, global_state);
             global_state.maybe_collect_garbage();
         }
         Event::Vfs(task) => {
@@ -344,7 +369,7 @@ fn loop_turn(
         Event::Msg(msg) => match msg {
             Message::Request(req) => on_request(
                 global_state,
-                &mut loop_state.pending_requests,
+                loop_state,
                 pool,
                 task_sender,
                 &connection.sender,
@@ -458,12 +483,12 @@ fn loop_turn(
 fn on_task(
     task: Task,
     msg_sender: &Sender<Message>,
-    pending_requests: &mut PendingRequests,
+    loop_state: &mut LoopState,
     state: &mut GlobalState,
 ) {
     match task {
         Task::Respond(response) => {
-            if let Some(completed) = pending_requests.finish(&response.id) {
+            if let Some(completed) = loop_state.pending_requests.finish(&response.id) {
                 log::info!("handled req#{} in {:?}", completed.id, completed.duration);
                 state.complete_request(completed);
                 msg_sender.send(response.into()).unwrap();
@@ -473,12 +498,19 @@ fn on_task(
             msg_sender.send(n.into()).unwrap();
         }
         Task::Diagnostic(task) => on_diagnostic_task(task, msg_sender, state),
+        Task::ApplyEdit(edit) => {
+            let request = request_new::<lsp_types::request::ApplyWorkspaceEdit>(
+                loop_state.next_request_id(),
+                lsp_types::ApplyWorkspaceEditParams { edit },
+            );
+            msg_sender.send(request.into()).unwrap();
+        }
     }
 }
 
 fn on_request(
     global_state: &mut GlobalState,
-    pending_requests: &mut PendingRequests,
+    loop_state: &mut LoopState,
     pool: &ThreadPool,
     task_sender: &Sender<Task>,
     msg_sender: &Sender<Message>,
@@ -491,7 +523,7 @@ fn on_request(
         global_state,
         task_sender,
         msg_sender,
-        pending_requests,
+        loop_state,
         request_received,
     };
     pool_dispatcher
@@ -506,6 +538,10 @@ fn on_request(
         .on::<lsp_ext::SyntaxTree>(handlers::handle_syntax_tree)?
         .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)?
         .on::<lsp_ext::ParentModule>(handlers::handle_parent_module)?
+        .on::<lsp_ext::ShowImplBlocks>(handlers::handle_show_impl_blocks)?
+        .on::<lsp_ext::ShowType>(handlers::handle_show_type)?
+        .on::<lsp_ext::FindTodos>(handlers::handle_find_todos)?
+        .on::<lsp_ext::DependencyTree>(handlers::handle_dependency_tree)?
         .on::<lsp_ext::Runnables>(handlers::handle_runnables)?
         .on::<lsp_ext::InlayHints>(handlers::handle_inlay_hints)?
         .on::<lsp_ext::CodeActionRequest>(handlers::handle_code_action)?
@@ -513,14 +549,14 @@ fn on_request(
         .on::<lsp_ext::HoverRequest>(handlers::handle_hover)?
         .on::<lsp_types::request::OnTypeFormatting>(handlers::handle_on_type_formatting)?
         .on::<lsp_types::request::DocumentSymbolRequest>(handlers::handle_document_symbol)?
-        .on::<lsp_types::request::WorkspaceSymbol>(handlers::handle_workspace_symbol)?
+        .on_with_progress::<lsp_types::request::WorkspaceSymbol>(handlers::handle_workspace_symbol)?
         .on::<lsp_types::request::GotoDefinition>(handlers::handle_goto_definition)?
         .on::<lsp_types::request::GotoImplementation>(handlers::handle_goto_implementation)?
         .on::<lsp_types::request::GotoTypeDefinition>(handlers::handle_goto_type_definition)?
         .on::<lsp_types::request::Completion>(handlers::handle_completion)?
         .on::<lsp_types::request::CodeLensRequest>(handlers::handle_code_lens)?
         .on::<lsp_types::request::CodeLensResolve>(handlers::handle_code_lens_resolve)?
-        .on::<lsp_types::request::FoldingRangeRequest>(handlers::handle_folding_range)?
+        .on::<lsp_ext::FoldingRangeRequest>(handlers::handle_folding_range)?
         .on::<lsp_types::request::SignatureHelpRequest>(handlers::handle_signature_help)?
         .on::<lsp_types::request::PrepareRenameRequest>(handlers::handle_prepare_rename)?
         .on::<lsp_types::request::Rename>(handlers::handle_rename)?
@@ -539,6 +575,7 @@ fn on_request(
             handlers::handle_semantic_tokens_range,
         )?
         .on::<lsp_ext::Ssr>(handlers::handle_ssr)?
+        .on_with_edits::<lsp_ext::WorkspaceSsr>(handlers::handle_workspace_ssr)?
         .finish();
     Ok(())
 }
@@ -591,6 +628,7 @@ fn on_notification(
             state.vfs.write().change_file_overlay(&path, |old_text| {
                 apply_document_changes(old_text, Cow::Borrowed(&line_index), content_changes);
             });
+            state.diagnostics.mark_edited(file_id);
             return Ok(());
         }
         Err(not) => not,
@@ -873,7 +911,7 @@ struct PoolDispatcher<'a> {
     req: Option<Request>,
     pool: &'a ThreadPool,
     global_state: &'a mut GlobalState,
-    pending_requests: &'a mut PendingRequests,
+    loop_state: &'a mut LoopState,
     msg_sender: &'a Sender<Message>,
     task_sender: &'a Sender<Task>,
     request_received: Instant,
@@ -902,7 +940,7 @@ impl<'a> PoolDispatcher<'a> {
             result_to_task::<R>(id, result)
         })
         .map_err(|_| format!("sync task {:?} panicked", R::METHOD))?;
-        on_task(task, self.msg_sender, self.pending_requests, self.global_state);
+        on_task(task, self.msg_sender, self.loop_state, self.global_state);
         Ok(self)
     }
 
@@ -936,6 +974,70 @@ impl<'a> PoolDispatcher<'a> {
         Ok(self)
     }
 
+    /// Dispatches the request onto thread pool, allowing the handler to
+    /// report its progress
+    fn on_with_progress<R>(
+        &mut self,
+        f: fn(GlobalStateSnapshot, R::Params, &ProgressSender) -> Result<R::Result>,
+    ) -> Result<&mut Self>
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + Send + 'static,
+        R::Result: Serialize + 'static,
+    {
+        let (id, params) = match self.parse::<R>() {
+            Some(it) => it,
+            None => {
+                return Ok(self);
+            }
+        };
+
+        self.pool.execute({
+            let world = self.global_state.snapshot();
+            let sender = self.task_sender.clone();
+            move || {
+                let progress = ProgressSender(sender.clone());
+                let result = f(world, params, &progress);
+                let task = result_to_task::<R>(id, result);
+                sender.send(task).unwrap();
+            }
+        });
+
+        Ok(self)
+    }
+
+    /// Dispatches the request onto thread pool, allowing the handler to ask
+    /// the client to apply workspace edits
+    fn on_with_edits<R>(
+        &mut self,
+        f: fn(GlobalStateSnapshot, R::Params, &EditSender) -> Result<R::Result>,
+    ) -> Result<&mut Self>
+    where
+        R: lsp_types::request::Request + 'static,
+        R::Params: DeserializeOwned + Send + 'static,
+        R::Result: Serialize + 'static,
+    {
+        let (id, params) = match self.parse::<R>() {
+            Some(it) => it,
+            None => {
+                return Ok(self);
+            }
+        };
+
+        self.pool.execute({
+            let world = self.global_state.snapshot();
+            let sender = self.task_sender.clone();
+            move || {
+                let edits = EditSender(sender.clone());
+                let result = f(world, params, &edits);
+                let task = result_to_task::<R>(id, result);
+                sender.send(task).unwrap();
+            }
+        });
+
+        Ok(self)
+    }
+
     fn parse<R>(&mut self) -> Option<(RequestId, R::Params)>
     where
         R: lsp_types::request::Request + 'static,
@@ -949,7 +1051,7 @@ impl<'a> PoolDispatcher<'a> {
                 return None;
             }
         };
-        self.pending_requests.start(PendingRequest {
+        self.loop_state.pending_requests.start(PendingRequest {
             id: id.clone(),
             method: R::METHOD.to_string(),
             received: self.request_received,
@@ -3,7 +3,9 @@
 //! `ra_ide` crate.
 
 use std::{
+    collections::HashMap,
     io::Write as _,
+    path::Path,
     process::{self, Stdio},
 };
 
@@ -12,29 +14,37 @@ use lsp_types::{
     CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeLens, Command, CompletionItem, Diagnostic, DocumentFormattingParams, DocumentHighlight,
-    DocumentSymbol, FoldingRange, FoldingRangeParams, HoverContents, Location, MarkupContent,
-    MarkupKind, Position, PrepareRenameResponse, Range, RenameParams, SemanticTokensParams,
+    DocumentSymbol, FoldingRangeParams, HoverContents, Location, MarkupContent, MarkupKind,
+    NumberOrString, Position, PrepareRenameResponse, Range, RenameParams, SemanticTokensParams,
     SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
-    TextDocumentIdentifier, Url, WorkspaceEdit,
+    TextDocumentIdentifier, Url, WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressEnd,
+    WorkDoneProgressReport, WorkspaceEdit,
 };
 use ra_ide::{
-    FileId, FilePosition, FileRange, HoverAction, Query, RangeInfo, Runnable, RunnableKind,
-    SearchScope, TextEdit,
+    AssistConfig, CrateId, FileId, FilePosition, FileRange, HoverAction, Query, RangeInfo,
+    Runnable, RunnableKind, SearchScope, TextEdit,
 };
 use ra_prof::profile;
-use ra_project_model::TargetKind;
-use ra_syntax::{AstNode, SyntaxKind, TextRange, TextSize};
+use ra_project_model::{CargoWorkspace, Package, ProjectWorkspace, TargetKind};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, AttrsOwner, NameOwner, VisibilityOwner},
+    match_ast, AstNode, SyntaxKind, TextRange, TextSize,
+};
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
-use serde_json::to_value;
+use serde_json::{json, to_value};
 use stdx::{format_to, split1};
 
 use crate::{
+    cargo_manifest,
     cargo_target_spec::CargoTargetSpec,
     config::RustfmtConfig,
-    diagnostics::DiagnosticTask,
+    diagnostics::{self, DiagnosticTask},
     from_json, from_proto,
     global_state::GlobalStateSnapshot,
     lsp_ext::{self, InlayHint, InlayHintsParams},
+    main_loop::{EditSender, ProgressSender},
     to_proto, LspError, Result,
 };
 
@@ -290,6 +300,7 @@ pub fn handle_document_symbol(
 pub fn handle_workspace_symbol(
     snap: GlobalStateSnapshot,
     params: lsp_types::WorkspaceSymbolParams,
+    progress: &ProgressSender,
 ) -> Result<Option<Vec<SymbolInformation>>> {
     let _p = profile("handle_workspace_symbol");
     let all_symbols = params.query.contains('#');
@@ -306,18 +317,71 @@ pub fn handle_workspace_symbol(
         q.limit(128);
         q
     };
-    let mut res = exec_query(&snap, query)?;
-    if res.is_empty() && !all_symbols {
+    // The client asks for progress by sending a token. The search ends the
+    // progress when it's dropped, even if the request is cancelled.
+    let progress = params
+        .work_done_progress_params
+        .work_done_token
+        .map(|token| SearchProgress::begin(progress, token));
+    let on_progress = |searched: usize, total: usize| {
+        if let Some(progress) = &progress {
+            progress.report(searched, total);
+        }
+    };
+    let mut res = exec_query(&snap, query, &on_progress);
+    if matches!(&res, Ok(it) if it.is_empty()) && !all_symbols {
         let mut query = Query::new(params.query);
         query.limit(128);
-        res = exec_query(&snap, query)?;
+        res = exec_query(&snap, query, &on_progress);
     }
 
-    return Ok(Some(res));
+    return Ok(Some(res?));
 
-    fn exec_query(snap: &GlobalStateSnapshot, query: Query) -> Result<Vec<SymbolInformation>> {
+    struct SearchProgress<'a> {
+        sender: &'a ProgressSender,
+        token: lsp_types::ProgressToken,
+    }
+
+    impl<'a> SearchProgress<'a> {
+        fn begin(sender: &'a ProgressSender, token: lsp_types::ProgressToken) -> Self {
+            sender.send(
+                &token,
+                WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                    title: "Searching symbols".into(),
+                    cancellable: None,
+                    message: None,
+                    percentage: Some(0.0),
+                }),
+            );
+            SearchProgress { sender, token }
+        }
+
+        fn report(&self, searched: usize, total: usize) {
+            self.sender.send(
+                &self.token,
+                WorkDoneProgress::Report(WorkDoneProgressReport {
+                    cancellable: None,
+                    message: Some(format!("{}/{} roots", searched, total)),
+                    percentage: Some(100.0 * searched as f64 / total as f64),
+                }),
+            )
+        }
+    }
+
+    impl Drop for SearchProgress<'_> {
+        fn drop(&mut self) {
+            self.sender
+                .send(&self.token, WorkDoneProgress::End(WorkDoneProgressEnd { message: None }))
+        }
+    }
+
+    fn exec_query(
+        snap: &GlobalStateSnapshot,
+        query: Query,
+        on_progress: &(dyn Fn(usize, usize) + Sync + std::panic::RefUnwindSafe),
+    ) -> Result<Vec<SymbolInformation>> {
         let mut res = Vec::new();
-        for nav in snap.analysis().symbol_search(query)? {
+        for nav in snap.analysis().symbol_search_with_progress(query, on_progress)? {
             let info = SymbolInformation {
                 name: nav.name().to_string(),
                 kind: to_proto::symbol_kind(nav.kind()),
@@ -387,6 +451,111 @@ pub fn handle_parent_module(
     Ok(Some(res))
 }
 
+pub fn handle_show_impl_blocks(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Vec<Location>> {
+    let _p = profile("handle_show_impl_blocks");
+    let position = from_proto::file_position(&snap, params)?;
+    let navs = snap.analysis().impl_blocks(position)?;
+    let res = navs
+        .into_iter()
+        .map(|nav| {
+            let range = nav.focus_range().unwrap_or_else(|| nav.range());
+            to_proto::location(&snap, FileRange { file_id: nav.file_id(), range })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(res)
+}
+
+pub fn handle_dependency_tree(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::DependencyTreeParams,
+) -> Result<Vec<lsp_ext::DependencyNode>> {
+    let _p = profile("handle_dependency_tree");
+    let mut res = Vec::new();
+    for workspace in snap.workspaces.iter() {
+        let cargo = match workspace {
+            ProjectWorkspace::Cargo { cargo, .. } => cargo,
+            ProjectWorkspace::Json { .. } => continue,
+        };
+        let mut roots = cargo
+            .packages()
+            .filter(|&pkg| match &params.package {
+                Some(name) => cargo[pkg].name == *name,
+                None => cargo[pkg].is_member,
+            })
+            .collect::<Vec<_>>();
+        roots.sort_by(|&a, &b| cargo[a].name.cmp(&cargo[b].name));
+        let mut visited = FxHashSet::default();
+        res.extend(roots.into_iter().map(|pkg| dependency_node(cargo, pkg, &mut visited)));
+    }
+    Ok(res)
+}
+
+/// Builds the dependency tree of `pkg`, like `cargo tree`: the dependencies
+/// of a package are only listed the first time it is encountered.
+fn dependency_node(
+    cargo: &CargoWorkspace,
+    pkg: Package,
+    visited: &mut FxHashSet<Package>,
+) -> lsp_ext::DependencyNode {
+    let data = &cargo[pkg];
+    let deduplicated = !visited.insert(pkg);
+    let dependencies = if deduplicated {
+        Vec::new()
+    } else {
+        let mut deps = data.dependencies.iter().map(|dep| dep.pkg).collect::<Vec<_>>();
+        deps.sort_by(|&a, &b| cargo[a].name.cmp(&cargo[b].name));
+        deps.dedup();
+        deps.into_iter().map(|dep| dependency_node(cargo, dep, visited)).collect()
+    };
+    lsp_ext::DependencyNode {
+        name: data.name.clone(),
+        version: data.version.clone(),
+        dependencies,
+        deduplicated,
+    }
+}
+
+pub fn handle_show_type(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<String>> {
+    let _p = profile("handle_show_type");
+    let position = from_proto::file_position(&snap, params)?;
+    let res = snap.analysis().type_at_position(position)?;
+    Ok(res)
+}
+
+pub fn handle_find_todos(snap: GlobalStateSnapshot, _: ()) -> Result<Vec<lsp_ext::FileTodos>> {
+    let _p = profile("handle_find_todos");
+    let todos = snap.analysis().find_todos(&snap.config.todo_keywords)?;
+    let mut res: Vec<lsp_ext::FileTodos> = Vec::new();
+    let mut current = None;
+    for todo in todos {
+        if current.as_ref().map(|(file_id, _)| *file_id) != Some(todo.file_id) {
+            let line_index = snap.analysis().file_line_index(todo.file_id)?;
+            current = Some((todo.file_id, line_index));
+            res.push(lsp_ext::FileTodos {
+                uri: to_proto::url(&snap, todo.file_id)?,
+                diagnostics: Vec::new(),
+            });
+        }
+        let line_index = &current.as_ref().unwrap().1;
+        res.last_mut().unwrap().diagnostics.push(Diagnostic {
+            range: to_proto::range(line_index, todo.range),
+            severity: Some(lsp_types::DiagnosticSeverity::Hint),
+            code: Some(NumberOrString::String(todo.keyword)),
+            source: Some("rust-analyzer".to_string()),
+            message: todo.text,
+            related_information: None,
+            tags: None,
+        });
+    }
+    Ok(res)
+}
+
 pub fn handle_runnables(
     snap: GlobalStateSnapshot,
     params: lsp_ext::RunnablesParams,
@@ -492,7 +661,7 @@ pub fn handle_completion(
 pub fn handle_folding_range(
     snap: GlobalStateSnapshot,
     params: FoldingRangeParams,
-) -> Result<Option<Vec<FoldingRange>>> {
+) -> Result<Option<Vec<lsp_ext::FoldingRange>>> {
     let _p = profile("handle_folding_range");
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let folds = snap.analysis().folding_ranges(file_id)?;
@@ -542,11 +711,15 @@ pub fn handle_hover(
     };
     let line_index = snap.analysis.file_line_index(position.file_id)?;
     let range = to_proto::range(&line_index, info.range);
+    let mut markup = info.info.to_markup();
+    if let Some(docs) = info.info.sysroot_item_path().and_then(|it| snap.sysroot_docs(it)) {
+        format_to!(markup, "\n___\n\n{}", docs);
+    }
     let hover = lsp_ext::Hover {
         hover: lsp_types::Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
-                value: crate::markdown::format_docs(&info.info.to_markup()),
+                value: crate::markdown::format_docs(&markup),
             }),
             range: Some(range),
         },
@@ -637,35 +810,31 @@ pub fn handle_formatting(
     let file_line_index = snap.analysis().file_line_index(file_id)?;
     let end_position = to_proto::position(&file_line_index, TextSize::of(file.as_str()));
 
-    let mut rustfmt = match &snap.config.rustfmt {
-        RustfmtConfig::Rustfmt { extra_args } => {
-            let mut cmd = process::Command::new("rustfmt");
-            cmd.args(extra_args);
-            if let Some(&crate_id) = crate_ids.first() {
-                // Assume all crates are in the same edition
-                let edition = snap.analysis().crate_edition(crate_id)?;
-                cmd.arg("--edition");
-                cmd.arg(edition.to_string());
+    let file_lines = match &snap.config.rustfmt {
+        RustfmtConfig::Rustfmt { .. } if snap.config.formatting_only_changed_lines => {
+            let path = params.text_document.uri.to_file_path().ok();
+            // Files, which aren't committed yet, are formatted completely.
+            match path.and_then(|it| changed_lines(&it, &file)) {
+                Some(lines) if lines.is_empty() => return Ok(None),
+                Some(lines) => {
+                    let file_lines: Vec<_> = lines
+                        .into_iter()
+                        .map(|(start, end)| json!({ "file": "stdin", "range": [start, end] }))
+                        .collect();
+                    Some(serde_json::Value::from(file_lines).to_string())
+                }
+                None => None,
             }
-            cmd
-        }
-        RustfmtConfig::CustomCommand { command, args } => {
-            let mut cmd = process::Command::new(command);
-            cmd.args(args);
-            cmd
         }
+        _ => None,
     };
 
-    if let Ok(path) = params.text_document.uri.to_file_path() {
-        if let Some(parent) = path.parent() {
-            rustfmt.current_dir(parent);
-        }
+    let mut output = run_rustfmt(&snap, &params, &crate_ids, &file, file_lines.as_deref())?;
+    if file_lines.is_some() && !output.status.success() {
+        // `--file-lines` is only supported by nightly `rustfmt`.
+        log::info!("rustfmt failed to format the changed lines, formatting the whole file");
+        output = run_rustfmt(&snap, &params, &crate_ids, &file, None)?;
     }
-    let mut rustfmt = rustfmt.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
-
-    rustfmt.stdin.as_mut().unwrap().write_all(file.as_bytes())?;
-
-    let output = rustfmt.wait_with_output()?;
     let captured_stdout = String::from_utf8(output.stdout)?;
 
     if !output.status.success() {
@@ -701,6 +870,105 @@ pub fn handle_formatting(
     }]))
 }
 
+fn run_rustfmt(
+    snap: &GlobalStateSnapshot,
+    params: &DocumentFormattingParams,
+    crate_ids: &[CrateId],
+    text: &str,
+    file_lines: Option<&str>,
+) -> Result<process::Output> {
+    let mut rustfmt = match &snap.config.rustfmt {
+        RustfmtConfig::Rustfmt { extra_args } => {
+            let mut cmd = process::Command::new("rustfmt");
+            cmd.args(extra_args);
+            if let Some(&crate_id) = crate_ids.first() {
+                // Assume all crates are in the same edition
+                let edition = snap.analysis().crate_edition(crate_id)?;
+                cmd.arg("--edition");
+                cmd.arg(edition.to_string());
+            }
+            if let Some(file_lines) = file_lines {
+                cmd.arg("--unstable-features");
+                cmd.arg("--file-lines");
+                cmd.arg(file_lines);
+            }
+            cmd
+        }
+        RustfmtConfig::CustomCommand { command, args } => {
+            let mut cmd = process::Command::new(command);
+            cmd.args(args);
+            cmd
+        }
+    };
+
+    if let Ok(path) = params.text_document.uri.to_file_path() {
+        if let Some(parent) = path.parent() {
+            rustfmt.current_dir(parent);
+        }
+    }
+    let mut rustfmt = rustfmt.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+
+    rustfmt.stdin.as_mut().unwrap().write_all(text.as_bytes())?;
+
+    Ok(rustfmt.wait_with_output()?)
+}
+
+/// Returns the 1-based, inclusive ranges of the lines in `text`, which differ
+/// from the last committed version of the file at `path`.
+fn changed_lines(path: &Path, text: &str) -> Option<Vec<(u32, u32)>> {
+    let dir = path.parent()?;
+    let file_name = path.file_name()?.to_str()?;
+    let committed = process::Command::new("git")
+        .current_dir(dir)
+        .arg("show")
+        .arg(format!("HEAD:./{}", file_name))
+        .output()
+        .ok()?;
+    if !committed.status.success() {
+        return None;
+    }
+    // The unsaved text is compared, so `git diff` can't read the file from the work tree.
+    let mut base = tempfile::NamedTempFile::new().ok()?;
+    base.write_all(&committed.stdout).ok()?;
+
+    let mut git = process::Command::new("git")
+        .current_dir(dir)
+        .args(["diff", "--no-index", "--no-color", "--unified=0", "--"])
+        .arg(base.path())
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .ok()?;
+    git.stdin.as_mut()?.write_all(text.as_bytes()).ok()?;
+    let output = git.wait_with_output().ok()?;
+    // `git diff --no-index` exits with 1 if there are differences.
+    if output.status.code()? > 1 {
+        return None;
+    }
+    let diff = String::from_utf8(output.stdout).ok()?;
+    Some(diff_changed_lines(&diff))
+}
+
+/// Returns the line ranges of the new file, which are added or modified by the
+/// hunks of a `--unified=0` diff. Hunks only removing lines are skipped.
+fn diff_changed_lines(diff: &str) -> Vec<(u32, u32)> {
+    diff.lines()
+        .filter_map(|line| {
+            // Hunk headers look like `@@ -12,3 +12,4 @@`.
+            let new = line.strip_prefix("@@ -")?.split(' ').nth(1)?.strip_prefix('+')?;
+            let (start, count) = match split1(new, ',') {
+                Some((start, count)) => (start.parse::<u32>().ok()?, count.parse::<u32>().ok()?),
+                None => (new.parse::<u32>().ok()?, 1),
+            };
+            if count == 0 {
+                return None;
+            }
+            Some((start, start + count - 1))
+        })
+        .collect()
+}
+
 fn handle_fixes(
     snap: &GlobalStateSnapshot,
     params: &lsp_types::CodeActionParams,
@@ -709,7 +977,7 @@ fn handle_fixes(
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let line_index = snap.analysis().file_line_index(file_id)?;
     let range = from_proto::text_range(&line_index, params.range);
-    let diagnostics = snap.analysis().diagnostics(file_id)?;
+    let diagnostics = snap.analysis().diagnostics(&snap.config.diagnostics, file_id)?;
 
     let fixes_from_diagnostics = diagnostics
         .into_iter()
@@ -730,6 +998,50 @@ fn handle_fixes(
         res.push(action);
     }
 
+    let frange = FileRange { file_id, range };
+    if let Some(fix) = snap.analysis().fix_all_rc_clones(&snap.config.diagnostics, frange)? {
+        let edit = to_proto::snippet_workspace_edit(snap, fix.source_change)?;
+        res.push(lsp_ext::CodeAction {
+            title: fix.label,
+            id: None,
+            group: None,
+            kind: Some(lsp_types::code_action_kind::QUICKFIX.into()),
+            edit: Some(edit),
+            command: None,
+        });
+    }
+
+    let mut lints: Vec<&str> = Vec::new();
+    for diagnostic in params.context.diagnostics.iter() {
+        match &diagnostic.code {
+            Some(NumberOrString::String(code))
+                if is_lint_name(code) && !lints.contains(&code.as_str()) =>
+            {
+                lints.push(code)
+            }
+            _ => (),
+        }
+    }
+    for lint in lints {
+        if let Some(fix) = snap.analysis().allow_lint_in_file(file_id, lint.to_string())? {
+            let edit = to_proto::snippet_workspace_edit(snap, fix.source_change)?;
+            res.push(lsp_ext::CodeAction {
+                title: fix.label,
+                id: None,
+                group: None,
+                kind: Some(lsp_types::code_action_kind::QUICKFIX.into()),
+                edit: Some(edit),
+                command: None,
+            });
+        }
+    }
+
+    for item in snap.analysis().feature_gated_items(frange)? {
+        if let Some(action) = cargo_manifest::enable_feature_fix(snap, file_id, &item)? {
+            res.push(action);
+        }
+    }
+
     for fix in snap.check_fixes.get(&file_id).into_iter().flatten() {
         let fix_range = from_proto::text_range(&line_index, fix.range);
         if fix_range.intersect(range).is_none() {
@@ -740,6 +1052,107 @@ fn handle_fixes(
     Ok(())
 }
 
+/// Checks whether the code of a diagnostic names a lint, like `unused_imports`
+/// or `clippy::needless_return`, rather than an error code like `E0308`.
+fn is_lint_name(code: &str) -> bool {
+    let name = code.strip_prefix("clippy::").unwrap_or(code);
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+const FIX_ALL: &str = "source.fixAll";
+const REMOVE_DEAD_CODE: &str = "source.removeDeadCode";
+
+/// Applies all machine applicable fixes, which `cargo check` suggested for
+/// `file_id`, unless the file was edited since they were reported, together
+/// with the fixes of native diagnostics, which only change the style of the
+/// code. Unused imports are removed by the fixes of the `unused_imports` lint.
+fn fix_all_action(
+    snap: &GlobalStateSnapshot,
+    uri: &Url,
+    file_id: FileId,
+) -> Result<Option<lsp_ext::CodeAction>> {
+    let mut fixes: Vec<Vec<lsp_types::TextEdit>> = Vec::new();
+    if !snap.stale_fixes.contains(&file_id) {
+        fixes.extend(snap.check_fixes.get(&file_id).into_iter().flatten().filter_map(|fix| {
+            let changes = fix.action.edit.as_ref()?.changes.as_ref()?;
+            // Fixes which also edit other files can't be applied partially.
+            let mut edits = None;
+            for (other_uri, other_edits) in changes {
+                if from_proto::file_id(snap, other_uri).ok()? != file_id {
+                    return None;
+                }
+                edits = Some(other_edits.clone());
+            }
+            edits
+        }));
+    }
+    if let Some(fix) = snap.analysis().fix_all_style(file_id)? {
+        let line_index = snap.analysis().file_line_index(file_id)?;
+        let line_endings = snap.file_line_endings(file_id);
+        for file_edit in fix.source_change.source_file_edits {
+            fixes.extend(
+                file_edit
+                    .edit
+                    .into_iter()
+                    .map(|indel| vec![to_proto::text_edit(&line_index, line_endings, indel)]),
+            );
+        }
+    }
+    let edits = diagnostics::combine_fixes(fixes);
+    if edits.is_empty() {
+        return Ok(None);
+    }
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+    Ok(Some(lsp_ext::CodeAction {
+        title: "Apply all automatic fixes".to_string(),
+        id: None,
+        group: None,
+        kind: Some(FIX_ALL.to_string()),
+        edit: Some(lsp_ext::SnippetWorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+        }),
+        command: None,
+    }))
+}
+
+/// Removes the dead code reported by `cargo check` in the crates of `file_id`.
+/// This searches for the usages of every dead item, so it is only computed
+/// when the client asks for it.
+fn remove_dead_code_action(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+) -> Result<Option<lsp_ext::CodeAction>> {
+    let crates = snap.analysis().crate_for(file_id)?;
+    let mut dead_code = Vec::new();
+    for (&other_file, ranges) in snap.dead_code.iter() {
+        let other_crates = snap.analysis().crate_for(other_file)?;
+        if !other_crates.iter().any(|it| crates.contains(it)) {
+            continue;
+        }
+        let line_index = snap.analysis().file_line_index(other_file)?;
+        dead_code.extend(ranges.iter().map(|it| FileRange {
+            file_id: other_file,
+            range: from_proto::text_range(&line_index, *it),
+        }));
+    }
+    let fix = match snap.analysis().remove_dead_code(dead_code)? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let edit = to_proto::snippet_workspace_edit(snap, fix.source_change)?;
+    Ok(Some(lsp_ext::CodeAction {
+        title: fix.label,
+        id: None,
+        group: None,
+        kind: Some(REMOVE_DEAD_CODE.to_string()),
+        edit: Some(edit),
+        command: None,
+    }))
+}
+
 pub fn handle_code_action(
     snap: GlobalStateSnapshot,
     params: lsp_types::CodeActionParams,
@@ -760,6 +1173,26 @@ pub fn handle_code_action(
 
     handle_fixes(&snap, &params, &mut res)?;
 
+    // Computing the fixes of the whole file, or the dead code of the whole
+    // crate, is too slow to do on each request.
+    let wants = |kind: &str| {
+        params
+            .context
+            .only
+            .as_ref()
+            .map_or(false, |kinds| kinds.iter().any(|it| it.as_str().starts_with(kind)))
+    };
+    if wants(FIX_ALL) {
+        if let Some(action) = fix_all_action(&snap, &params.text_document.uri, file_id)? {
+            res.push(action);
+        }
+    }
+    if wants(REMOVE_DEAD_CODE) {
+        if let Some(action) = remove_dead_code_action(&snap, file_id)? {
+            res.push(action);
+        }
+    }
+
     if snap.config.client_caps.resolve_code_action {
         for (index, assist) in
             snap.analysis().unresolved_assists(&snap.config.assist, frange)?.into_iter().enumerate()
@@ -865,13 +1298,88 @@ pub fn handle_code_lens(
                 }),
         );
     }
+
+    if snap.config.lens.callers {
+        // Handle private functions
+        let source_file = snap.analysis().parse(file_id)?;
+        lenses.extend(
+            source_file
+                .syntax()
+                .descendants()
+                .filter_map(ast::FnDef::cast)
+                .filter(is_private_fn)
+                .filter_map(|it| {
+                    let name = it.name()?;
+                    let range = to_proto::range(&line_index, it.syntax().text_range());
+                    let pos = to_proto::position(&line_index, name.syntax().text_range().start());
+                    let lens_params = lsp_types::TextDocumentPositionParams::new(
+                        params.text_document.clone(),
+                        pos,
+                    );
+                    Some(CodeLens {
+                        range,
+                        command: None,
+                        data: Some(to_value(CodeLensResolveData::Callers(lens_params)).unwrap()),
+                    })
+                }),
+        );
+    }
+
+    if snap.config.lens.inline {
+        // Handle functions, whose `#[inline]` hint can be toggled
+        let source_file = snap.analysis().parse(file_id)?;
+        lenses.extend(
+            source_file
+                .syntax()
+                .descendants()
+                .filter_map(ast::FnDef::cast)
+                .filter(|it| it.body().is_some())
+                .filter_map(|it| {
+                    let name = it.name()?;
+                    let range = to_proto::range(&line_index, it.syntax().text_range());
+                    let pos = to_proto::position(&line_index, name.syntax().text_range().start());
+                    let lens_params = lsp_types::TextDocumentPositionParams::new(
+                        params.text_document.clone(),
+                        pos,
+                    );
+                    Some(CodeLens {
+                        range,
+                        command: None,
+                        data: Some(to_value(CodeLensResolveData::Inline(lens_params)).unwrap()),
+                    })
+                }),
+        );
+    }
     Ok(Some(lenses))
 }
 
+/// Private functions are the ones which can only be called from within the
+/// crate. Tests and trait methods are called from elsewhere.
+fn is_private_fn(fn_def: &ast::FnDef) -> bool {
+    if fn_def.visibility().is_some() || fn_def.has_atom_attr("test") {
+        return false;
+    }
+    if fn_def.name().map_or(true, |it| it.text() == "main") {
+        return false;
+    }
+    match fn_def.syntax().parent().and_then(|it| it.parent()) {
+        Some(parent) => match_ast! {
+            match parent {
+                ast::TraitDef(_it) => false,
+                ast::ImplDef(it) => it.target_trait().is_none(),
+                _ => true,
+            }
+        },
+        None => true,
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 enum CodeLensResolveData {
     Impls(lsp_types::request::GotoImplementationParams),
+    Callers(lsp_types::TextDocumentPositionParams),
+    Inline(lsp_types::TextDocumentPositionParams),
 }
 
 pub fn handle_code_lens_resolve(
@@ -903,6 +1411,56 @@ pub fn handle_code_lens_resolve(
             );
             Ok(CodeLens { range: code_lens.range, command: Some(cmd), data: None })
         }
+        Some(CodeLensResolveData::Callers(lens_params)) => {
+            let position = from_proto::file_position(&snap, lens_params.clone())?;
+            let call_sites = snap.analysis().call_sites(position)?.unwrap_or_default();
+            let locations = call_sites
+                .into_iter()
+                .map(|it| to_proto::location(&snap, it))
+                .collect::<Result<Vec<_>>>()?;
+
+            let title = call_sites_title(locations.len());
+            let cmd = show_references_command(
+                title,
+                &lens_params.text_document.uri,
+                code_lens.range.start,
+                locations,
+            );
+            Ok(CodeLens { range: code_lens.range, command: Some(cmd), data: None })
+        }
+        Some(CodeLensResolveData::Inline(lens_params)) => {
+            let position = from_proto::file_position(&snap, lens_params)?;
+            let source_file = snap.analysis().parse(position.file_id)?;
+            let toggle = find_node_at_offset::<ast::FnDef>(source_file.syntax(), position.offset)
+                .map(|it| match it.find_attr("inline") {
+                    Some(_) => ("#[inline] ✓", "remove_inline_attr"),
+                    None => ("#[inline] ✕", "add_inline_attr"),
+                });
+            let mut command = Command { title: "Error".into(), ..Default::default() };
+            if let Some((title, assist_id)) = toggle {
+                // Lenses stay around across edits, so build the edit now rather
+                // than referring to the assist by its index when clicked.
+                let config = AssistConfig { inline_hints: true, ..snap.config.assist.clone() };
+                let frange = FileRange {
+                    file_id: position.file_id,
+                    range: TextRange::empty(position.offset),
+                };
+                let assist = snap
+                    .analysis()
+                    .resolved_assists(&config, frange)?
+                    .into_iter()
+                    .find(|it| it.assist.id.0 == assist_id);
+                if let Some(assist) = assist {
+                    let edit = to_proto::snippet_workspace_edit(&snap, assist.source_change)?;
+                    command = Command {
+                        title: title.into(),
+                        command: "rust-analyzer.applyWorkspaceEdit".into(),
+                        arguments: Some(vec![to_value(edit).unwrap()]),
+                    };
+                }
+            }
+            Ok(CodeLens { range: code_lens.range, command: Some(command), data: None })
+        }
         None => Ok(CodeLens {
             range: code_lens.range,
             command: Some(Command { title: "Error".into(), ..Default::default() }),
@@ -948,12 +1506,31 @@ pub fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change)
 }
 
+pub fn handle_workspace_ssr(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::WorkspaceSsrParams,
+    edits: &EditSender,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let _p = profile("handle_workspace_ssr");
+    let source_change =
+        snap.analysis().structural_search_replace_parts(&params.pattern, &params.replacement)??;
+    let workspace_edit = to_proto::workspace_edit(&snap, source_change)?;
+    // Previews, and edits for clients which can't apply edits on request, are
+    // returned for the client to apply. Otherwise, the edit is applied here,
+    // and nothing is left for the client to do.
+    if params.preview || !snap.config.client_caps.apply_edit {
+        return Ok(workspace_edit);
+    }
+    edits.apply(workspace_edit);
+    Ok(lsp_types::WorkspaceEdit::default())
+}
+
 pub fn publish_diagnostics(snap: &GlobalStateSnapshot, file_id: FileId) -> Result<DiagnosticTask> {
     let _p = profile("publish_diagnostics");
     let line_index = snap.analysis().file_line_index(file_id)?;
     let diagnostics: Vec<Diagnostic> = snap
         .analysis()
-        .diagnostics(file_id)?
+        .diagnostics(&snap.config.diagnostics, file_id)?
         .into_iter()
         .map(|d| Diagnostic {
             range: to_proto::range(&line_index, d.range),
@@ -1113,6 +1690,14 @@ fn implementation_title(count: usize) -> String {
     }
 }
 
+fn call_sites_title(count: usize) -> String {
+    if count == 1 {
+        "1 call site".into()
+    } else {
+        format!("{} call sites", count)
+    }
+}
+
 fn show_references_command(
     title: String,
     uri: &lsp_types::Url,
@@ -1238,3 +1823,27 @@ fn should_skip_target(runnable: &Runnable, cargo_spec: Option<&CargoTargetSpec>)
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_lines_of_diff_hunks() {
+        let diff = r#"diff --git a/tmp b/-
+--- a/tmp
++++ b/-
+@@ -3 +3 @@ fn main() {
+-    let x = 1;
++    let x  =  1;
+@@ -10,0 +11,2 @@ fn foo() {
++fn bar() {}
++fn baz() {}
+@@ -20,4 +22,0 @@ fn qux() {
+-    // gone
+@@ -30,2 +28,3 @@
++    let y = 2;
+"#;
+        assert_eq!(diff_changed_lines(diff), vec![(3, 3), (11, 12), (28, 30)]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Documentation for the standard library, read from the rustdoc JSON, which
+//! `rustup component add rust-docs-json` installs into the sysroot.
+//!
+//! This allows to show docs on hover for `std`, `core` and `alloc` items, which
+//! are not documented in the sources.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+const SYSROOT_DOC_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// Maps paths of standard library items, like `alloc::vec::Vec::new`, to their
+/// documentation.
+#[derive(Debug, Default)]
+pub struct RustdocJsonCache {
+    json_dir: PathBuf,
+    /// Modification times of the loaded files, which change together with the
+    /// toolchain.
+    stamps: Vec<Option<SystemTime>>,
+    docs: FxHashMap<String, String>,
+}
+
+impl RustdocJsonCache {
+    pub fn load(json_dir: &Path) -> RustdocJsonCache {
+        let mut docs = FxHashMap::default();
+        for krate in SYSROOT_DOC_CRATES {
+            let file = json_dir.join(format!("{}.json", krate));
+            let json = fs::read_to_string(&file)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| serde_json::from_str::<Value>(&text).map_err(Into::into));
+            match json {
+                Ok(json) => collect_docs(&json, &mut docs),
+                Err(err) => log::warn!("failed to load rustdoc JSON {}: {}", file.display(), err),
+            }
+        }
+        RustdocJsonCache { json_dir: json_dir.to_path_buf(), stamps: stamps(json_dir), docs }
+    }
+
+    /// Checks whether the cache was loaded from a different toolchain.
+    pub fn is_stale(&self, json_dir: &Path) -> bool {
+        self.json_dir != json_dir || self.stamps != stamps(json_dir)
+    }
+
+    pub fn docs(&self, path: &str) -> Option<&str> {
+        self.docs.get(path).map(String::as_str)
+    }
+}
+
+fn stamps(json_dir: &Path) -> Vec<Option<SystemTime>> {
+    SYSROOT_DOC_CRATES
+        .iter()
+        .map(|krate| fs::metadata(json_dir.join(format!("{}.json", krate))).ok()?.modified().ok())
+        .collect()
+}
+
+/// Reads the docs of the items of the documented crate. The layout of rustdoc
+/// JSON is not stable, so both the older `"kind": "impl", "inner": {..}`, and
+/// the newer `"inner": {"impl": {..}}` representations are supported.
+fn collect_docs(json: &Value, acc: &mut FxHashMap<String, String>) {
+    let (index, paths) = match (json["index"].as_object(), json["paths"].as_object()) {
+        (Some(index), Some(paths)) => (index, paths),
+        _ => return,
+    };
+    let path_of = |id: &str| -> Option<String> {
+        let segments = paths.get(id)?["path"].as_array()?;
+        let segments: Option<Vec<&str>> = segments.iter().map(Value::as_str).collect();
+        Some(segments?.join("::"))
+    };
+
+    for (id, item) in index {
+        if item["crate_id"].as_u64() != Some(0) {
+            continue;
+        }
+        if let Some(docs) = item["docs"].as_str() {
+            if let Some(path) = path_of(id) {
+                acc.insert(path, docs.to_string());
+            }
+        }
+
+        let impl_ = if item["kind"] == "impl" { &item["inner"] } else { &item["inner"]["impl"] };
+        if !impl_.is_object() || !impl_["trait"].is_null() {
+            continue;
+        }
+        let self_ty = &impl_["for"];
+        let self_ty_id = if self_ty["kind"] == "resolved_path" {
+            &self_ty["inner"]["id"]
+        } else {
+            &self_ty["resolved_path"]["id"]
+        };
+        let self_ty_path = match id_key(self_ty_id).and_then(|id| path_of(&id)) {
+            Some(it) => it,
+            None => continue,
+        };
+        for assoc_id in impl_["items"].as_array().into_iter().flatten() {
+            let assoc = match id_key(assoc_id).and_then(|id| index.get(&id)) {
+                Some(it) => it,
+                None => continue,
+            };
+            if let (Some(name), Some(docs)) = (assoc["name"].as_str(), assoc["docs"].as_str()) {
+                acc.insert(format!("{}::{}", self_ty_path, name), docs.to_string());
+            }
+        }
+    }
+}
+
+/// Ids are strings in older versions of the format, and numbers in newer ones.
+fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::String(it) => Some(it.clone()),
+        Value::Number(it) => Some(it.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_ide::mock_analysis::analysis_and_position;
+    use serde_json::json;
+
+    use super::*;
+
+    fn check(json: Value, expected: &[(&str, &str)]) {
+        let mut docs = FxHashMap::default();
+        collect_docs(&json, &mut docs);
+        let mut actual: Vec<_> = docs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn collects_docs_from_old_format() {
+        check(
+            json!({
+                "index": {
+                    "0:1": { "crate_id": 0, "name": "Vec", "kind": "struct", "docs": "A vector." },
+                    "0:2": {
+                        "crate_id": 0, "name": null, "kind": "impl", "docs": null,
+                        "inner": {
+                            "trait": null,
+                            "for": { "kind": "resolved_path", "inner": { "name": "Vec", "id": "0:1" } },
+                            "items": ["0:3"]
+                        }
+                    },
+                    "0:3": { "crate_id": 0, "name": "new", "kind": "method", "docs": "Creates a vector." },
+                    "1:1": { "crate_id": 1, "name": "Option", "kind": "enum", "docs": "Not ours." }
+                },
+                "paths": {
+                    "0:1": { "crate_id": 0, "path": ["alloc", "vec", "Vec"], "kind": "struct" },
+                    "1:1": { "crate_id": 1, "path": ["core", "option", "Option"], "kind": "enum" }
+                }
+            }),
+            &[("alloc::vec::Vec", "A vector."), ("alloc::vec::Vec::new", "Creates a vector.")],
+        );
+    }
+
+    #[test]
+    fn collects_docs_from_new_format() {
+        check(
+            json!({
+                "index": {
+                    "1": { "crate_id": 0, "name": "Option", "docs": "Optional value.", "inner": { "enum": {} } },
+                    "2": {
+                        "crate_id": 0, "name": null, "docs": null,
+                        "inner": { "impl": {
+                            "trait": null,
+                            "for": { "resolved_path": { "path": "Option", "id": 1 } },
+                            "items": [3]
+                        } }
+                    },
+                    "3": { "crate_id": 0, "name": "is_some", "docs": "Checks for `Some`.", "inner": { "function": {} } }
+                },
+                "paths": {
+                    "1": { "crate_id": 0, "path": ["core", "option", "Option"], "kind": "enum" }
+                }
+            }),
+            &[
+                ("core::option::Option", "Optional value."),
+                ("core::option::Option::is_some", "Checks for `Some`."),
+            ],
+        );
+    }
+
+    #[test]
+    fn finds_docs_of_hovered_item() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /main.rs
+            fn main() {
+                let x = core::option::Option::<i32>::None;
+                x.ma<|>p(|it| it);
+            }
+            //- /core/lib.rs
+            pub mod option {
+                pub enum Option<T> { None, Some(T) }
+                impl<T> Option<T> {
+                    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Option<U> { loop {} }
+                }
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        let item_path = hover.info.sysroot_item_path().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let json = json!({
+            "index": {
+                "1": { "crate_id": 0, "name": "Option", "docs": null, "inner": { "enum": {} } },
+                "2": {
+                    "crate_id": 0, "name": null, "docs": null,
+                    "inner": { "impl": {
+                        "trait": null,
+                        "for": { "resolved_path": { "path": "Option", "id": 1 } },
+                        "items": [3]
+                    } }
+                },
+                "3": { "crate_id": 0, "name": "map", "docs": "Maps the value.", "inner": { "function": {} } }
+            },
+            "paths": {
+                "1": { "crate_id": 0, "path": ["core", "option", "Option"], "kind": "enum" }
+            }
+        });
+        fs::write(dir.path().join("core.json"), json.to_string()).unwrap();
+        let cache = RustdocJsonCache::load(dir.path());
+        assert!(!cache.is_stale(dir.path()));
+        assert_eq!(cache.docs(item_path), Some("Maps the value."));
+
+        fs::write(dir.path().join("std.json"), r#"{ "index": {}, "paths": {} }"#).unwrap();
+        assert!(cache.is_stale(dir.path()));
+    }
+}
@@ -57,6 +57,7 @@ pub(crate) fn document_highlight_kind(
 pub(crate) fn diagnostic_severity(severity: Severity) -> lsp_types::DiagnosticSeverity {
     match severity {
         Severity::Error => lsp_types::DiagnosticSeverity::Error,
+        Severity::Warning => lsp_types::DiagnosticSeverity::Warning,
         Severity::WeakWarning => lsp_types::DiagnosticSeverity::Hint,
     }
 }
@@ -249,6 +250,7 @@ pub(crate) fn inlay_int(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_ex
             InlayKind::ParameterHint => lsp_ext::InlayKind::ParameterHint,
             InlayKind::TypeHint => lsp_ext::InlayKind::TypeHint,
             InlayKind::ChainingHint => lsp_ext::InlayKind::ChainingHint,
+            InlayKind::TraitBoundHint => lsp_ext::InlayKind::TraitBoundHint,
         },
     }
 }
@@ -342,10 +344,11 @@ pub(crate) fn folding_range(
     line_index: &LineIndex,
     line_folding_only: bool,
     fold: Fold,
-) -> lsp_types::FoldingRange {
+) -> lsp_ext::FoldingRange {
     let kind = match fold.kind {
-        FoldKind::Comment => Some(lsp_types::FoldingRangeKind::Comment),
-        FoldKind::Imports => Some(lsp_types::FoldingRangeKind::Imports),
+        FoldKind::Comment => Some(lsp_ext::FoldingRangeKind::Comment),
+        FoldKind::Imports => Some(lsp_ext::FoldingRangeKind::Imports),
+        FoldKind::InactiveCode => Some(lsp_ext::FoldingRangeKind::InactiveCode),
         FoldKind::Mods | FoldKind::Block => None,
     };
 
@@ -367,7 +370,7 @@ pub(crate) fn folding_range(
             range.end.line
         };
 
-        lsp_types::FoldingRange {
+        lsp_ext::FoldingRange {
             start_line: range.start.line,
             start_character: None,
             end_line,
@@ -375,7 +378,7 @@ pub(crate) fn folding_range(
             kind,
         }
     } else {
-        lsp_types::FoldingRange {
+        lsp_ext::FoldingRange {
             start_line: range.start.line,
             start_character: Some(range.start.character),
             end_line: range.end.line,
@@ -503,6 +506,10 @@ pub(crate) fn resource_op(
             let new_uri = snap.path_to_uri(dst_source_root, &dst_path)?;
             lsp_types::ResourceOp::Rename(lsp_types::RenameFile { old_uri, new_uri, options: None })
         }
+        FileSystemEdit::DeleteFile { file } => {
+            let uri = snap.file_id_to_uri(file)?;
+            lsp_types::ResourceOp::Delete(lsp_types::DeleteFile { uri, options: None })
+        }
     };
     Ok(res)
 }
@@ -609,7 +616,7 @@ fn main() <fold>{
         ];
 
         let line_index = LineIndex::new(&text);
-        let converted: Vec<lsp_types::FoldingRange> =
+        let converted: Vec<lsp_ext::FoldingRange> =
             folds.into_iter().map(|it| folding_range(&text, &line_index, true, it)).collect();
 
         let expected_lines = [(0, 2), (4, 10), (5, 6), (7, 9)];
@@ -10,7 +10,9 @@ use lsp_types::{
     PartialResultParams, Position, Range, TextDocumentItem, TextDocumentPositionParams,
     WorkDoneProgressParams,
 };
-use rust_analyzer::lsp_ext::{OnEnter, Runnables, RunnablesParams};
+use rust_analyzer::lsp_ext::{
+    DependencyTree, DependencyTreeParams, OnEnter, Runnables, RunnablesParams,
+};
 use serde_json::json;
 use tempfile::TempDir;
 use test_utils::skip_slow_tests;
@@ -678,3 +680,55 @@ pub fn foo(_input: TokenStream) -> TokenStream {
     let value = res.get("contents").unwrap().get("value").unwrap().to_string();
     assert_eq!(value, r#""```rust\nfoo::Bar\n```\n\n```rust\nfn bar()\n```""#)
 }
+
+#[test]
+fn dependency_tree() {
+    if skip_slow_tests() {
+        return;
+    }
+    let server = project(
+        r#"
+//- Cargo.toml
+[package]
+name = "foo"
+version = "0.1.0"
+[dependencies]
+bar = { path = "bar" }
+baz = { path = "baz" }
+
+//- src/lib.rs
+//- bar/Cargo.toml
+[package]
+name = "bar"
+version = "0.2.0"
+[dependencies]
+baz = { path = "../baz" }
+
+//- bar/src/lib.rs
+//- baz/Cargo.toml
+[package]
+name = "baz"
+version = "0.3.0"
+
+//- baz/src/lib.rs
+"#,
+    );
+    server.wait_until_workspace_is_loaded();
+    let baz = |deduplicated| json!({ "name": "baz", "version": "0.3.0", "dependencies": [], "deduplicated": deduplicated });
+    server.request::<DependencyTree>(
+        DependencyTreeParams { package: None },
+        json!([{
+            "name": "foo",
+            "version": "0.1.0",
+            "dependencies": [
+                { "name": "bar", "version": "0.2.0", "dependencies": [baz(false)], "deduplicated": false },
+                baz(true),
+            ],
+            "deduplicated": false
+        }]),
+    );
+    server.request::<DependencyTree>(
+        DependencyTreeParams { package: Some("baz".to_string()) },
+        json!([baz(false)]),
+    );
+}
@@ -117,6 +117,10 @@ pub fn to_lower_snake_case(s: &str) -> String {
     buf
 }
 
+pub fn to_upper_snake_case(s: &str) -> String {
+    to_lower_snake_case(s).to_uppercase()
+}
+
 pub fn replace(buf: &mut String, from: char, to: &str) {
     if !buf.contains(from) {
         return;
@@ -151,6 +151,77 @@ export function parentModule(ctx: Ctx): Cmd {
     };
 }
 
+export function showImplBlocks(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const position = editor.selection.active;
+        const locations = await client.sendRequest(ra.showImplBlocks, {
+            textDocument: { uri: editor.document.uri.toString() },
+            position: client.code2ProtocolConverter.asPosition(position),
+        });
+
+        await vscode.commands.executeCommand(
+            'editor.action.showReferences',
+            editor.document.uri,
+            position,
+            locations.map(client.protocol2CodeConverter.asLocation),
+        );
+    };
+}
+
+export function showType(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const ty = await client.sendRequest(ra.showType, {
+            textDocument: { uri: editor.document.uri.toString() },
+            position: client.code2ProtocolConverter.asPosition(editor.selection.active),
+        });
+        if (!ty) {
+            vscode.window.setStatusBarMessage('rust-analyzer: no type at cursor', 3000);
+            return;
+        }
+
+        await vscode.env.clipboard.writeText(ty);
+        vscode.window.setStatusBarMessage(`rust-analyzer: copied \`${ty}\` to clipboard`, 3000);
+        await vscode.commands.executeCommand('editor.action.showHover');
+    };
+}
+
+export function findTodos(ctx: Ctx): Cmd {
+    return async () => {
+        const client = ctx.client;
+        if (!client) return;
+
+        const files = await client.sendRequest(ra.findTodos, null);
+        const items = files.flatMap(file => file.diagnostics.map(todo => {
+            const uri = vscode.Uri.parse(file.uri);
+            const range = client.protocol2CodeConverter.asRange(todo.range);
+            return {
+                label: todo.message,
+                description: `${vscode.workspace.asRelativePath(uri)}:${range.start.line + 1}`,
+                uri,
+                range,
+            };
+        }));
+        if (items.length === 0) {
+            vscode.window.setStatusBarMessage('rust-analyzer: no TODOs found', 3000);
+            return;
+        }
+
+        const item = await vscode.window.showQuickPick(items, { matchOnDescription: true });
+        if (!item) return;
+        const editor = await vscode.window.showTextDocument(item.uri);
+        editor.selection = new vscode.Selection(item.range.start, item.range.start);
+        editor.revealRange(item.range, vscode.TextEditorRevealType.InCenter);
+    };
+}
+
 export function ssr(ctx: Ctx): Cmd {
     return async () => {
         const client = ctx.client;
@@ -268,9 +339,12 @@ export function syntaxTree(ctx: Ctx): Cmd {
 }
 
 
-// Opens the virtual file that will show the syntax tree
+// Opens a virtual file with the recursive expansion of the macro under the cursor.
 //
-// The contents of the file come from the `TextDocumentContentProvider`
+// Every expanded macro call gets its own document, which follows edits of the
+// source file. The expansion is recursive, so there is nothing left to expand in
+// it, and positions in it are not mapped back to the source: running the command
+// again in an expansion document only refreshes it.
 export function expandMacro(ctx: Ctx): Cmd {
     function codeFormat(expanded: ra.ExpandedMacro): string {
         let result = `// Recursive expansion of ${expanded.name}! macro\n`;
@@ -281,20 +355,62 @@ export function expandMacro(ctx: Ctx): Cmd {
         return result;
     }
 
+    interface MacroCall {
+        source: string;
+        position: vscode.Position;
+    }
+
     const tdcp = new class implements vscode.TextDocumentContentProvider {
-        uri = vscode.Uri.parse('rust-analyzer://expandMacro/[EXPANSION].rs');
-        eventEmitter = new vscode.EventEmitter<vscode.Uri>();
-        async provideTextDocumentContent(_uri: vscode.Uri): Promise<string> {
-            const editor = vscode.window.activeTextEditor;
-            const client = ctx.client;
-            if (!editor || !client) return '';
-
-            const position = editor.selection.active;
-
-            const expanded = await client.sendRequest(ra.expandMacro, {
-                textDocument: { uri: editor.document.uri.toString() },
-                position,
-            });
+        readonly scheme = 'rust-analyzer-macro';
+        readonly eventEmitter = new vscode.EventEmitter<vscode.Uri>();
+        private readonly calls = new Map<string, MacroCall>();
+        private nextId = 0;
+        constructor() {
+            vscode.workspace.onDidChangeTextDocument(this.onDidChangeTextDocument, this, ctx.subscriptions);
+            vscode.workspace.onDidCloseTextDocument(this.onDidCloseTextDocument, this, ctx.subscriptions);
+        }
+
+        uriFor(document: vscode.TextDocument, position: vscode.Position): vscode.Uri {
+            const source = document.uri.toString();
+            for (const [uri, call] of this.calls) {
+                if (call.source === source && call.position.isEqual(position)) {
+                    return vscode.Uri.parse(uri);
+                }
+            }
+            const name = document.uri.path.split('/').pop()?.replace(/\.rs$/, '');
+            const uri = vscode.Uri.parse(`${this.scheme}://expandMacro/${name}[EXPANSION].rs?${this.nextId++}`);
+            this.calls.set(uri.toString(), { source, position });
+            return uri;
+        }
+
+        private onDidChangeTextDocument(event: vscode.TextDocumentChangeEvent) {
+            if (!isRustDocument(event.document)) return;
+            const source = event.document.uri.toString();
+            for (const [uri, call] of this.calls) {
+                if (call.source !== source) continue;
+                for (const change of event.contentChanges) {
+                    call.position = shiftPosition(call.position, change);
+                }
+                // We need to order this after language server updates, but there's no API for that.
+                // Hence, good old sleep().
+                void sleep(10).then(() => this.eventEmitter.fire(vscode.Uri.parse(uri)));
+            }
+        }
+
+        private onDidCloseTextDocument(document: vscode.TextDocument) {
+            if (document.uri.scheme === this.scheme) {
+                this.calls.delete(document.uri.toString());
+            }
+        }
+
+        async provideTextDocumentContent(uri: vscode.Uri, ct: vscode.CancellationToken): Promise<string> {
+            const call = this.calls.get(uri.toString());
+            if (!call) return 'Not available';
+
+            const expanded = await ctx.client.sendRequest(ra.expandMacro, {
+                textDocument: { uri: call.source },
+                position: ctx.client.code2ProtocolConverter.asPosition(call.position),
+            }, ct);
 
             if (expanded == null) return 'Not available';
 
@@ -306,16 +422,21 @@ export function expandMacro(ctx: Ctx): Cmd {
         }
     }();
 
-    ctx.pushCleanup(
-        vscode.workspace.registerTextDocumentContentProvider(
-            'rust-analyzer',
-            tdcp,
-        ),
-    );
+    ctx.pushCleanup(vscode.workspace.registerTextDocumentContentProvider(tdcp.scheme, tdcp));
 
     return async () => {
-        const document = await vscode.workspace.openTextDocument(tdcp.uri);
-        tdcp.eventEmitter.fire(tdcp.uri);
+        const active = vscode.window.activeTextEditor;
+        if (active?.document.uri.scheme === tdcp.scheme) {
+            tdcp.eventEmitter.fire(active.document.uri);
+            return;
+        }
+
+        const editor = ctx.activeRustEditor;
+        if (!editor) return;
+
+        const uri = tdcp.uriFor(editor.document, editor.selection.active);
+        const document = await vscode.workspace.openTextDocument(uri);
+        tdcp.eventEmitter.fire(uri);
         return vscode.window.showTextDocument(
             document,
             vscode.ViewColumn.Two,
@@ -324,6 +445,19 @@ export function expandMacro(ctx: Ctx): Cmd {
     };
 }
 
+// Moves `position` along with the text before it, which is replaced by `change`.
+function shiftPosition(position: vscode.Position, change: vscode.TextDocumentContentChangeEvent): vscode.Position {
+    if (change.range.end.isAfter(position)) return position;
+
+    const lines = change.text.split('\n');
+    const lineDelta = lines.length - 1 - (change.range.end.line - change.range.start.line);
+    if (change.range.end.line !== position.line) return position.translate(lineDelta);
+
+    const lastLine = lines[lines.length - 1].length;
+    const end = lines.length === 1 ? change.range.start.character + lastLine : lastLine;
+    return new vscode.Position(position.line + lineDelta, end + position.character - change.range.end.character);
+}
+
 export function collectGarbage(ctx: Ctx): Cmd {
     return async () => ctx.client.sendRequest(ra.collectGarbage, null);
 }
@@ -371,6 +505,14 @@ export function applySnippetWorkspaceEditCommand(_ctx: Ctx): Cmd {
     };
 }
 
+export function applyWorkspaceEdit(ctx: Ctx): Cmd {
+    const client = ctx.client;
+    return async (item: lc.WorkspaceEdit) => {
+        const edit = client.protocol2CodeConverter.asWorkspaceEdit(item);
+        await applySnippetWorkspaceEdit(edit);
+    };
+}
+
 export function run(ctx: Ctx): Cmd {
     let prevRunnable: RunnableQuickPick | undefined;
 
@@ -100,6 +100,7 @@ export class Config {
             typeHints: this.get<boolean>("inlayHints.typeHints"),
             parameterHints: this.get<boolean>("inlayHints.parameterHints"),
             chainingHints: this.get<boolean>("inlayHints.chainingHints"),
+            traitBounds: this.get<boolean>("inlayHints.traitBounds"),
             maxLength: this.get<null | number>("inlayHints.maxLength"),
         };
     }
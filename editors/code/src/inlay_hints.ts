@@ -12,7 +12,8 @@ export function activateInlayHints(ctx: Ctx) {
         async onConfigChange() {
             const anyEnabled = ctx.config.inlayHints.typeHints
                 || ctx.config.inlayHints.parameterHints
-                || ctx.config.inlayHints.chainingHints;
+                || ctx.config.inlayHints.chainingHints
+                || ctx.config.inlayHints.traitBounds;
             const enabled = ctx.config.inlayHints.enable && anyEnabled;
 
             if (!enabled) return this.dispose();
@@ -88,6 +89,22 @@ const chainingHints = {
     }
 };
 
+const traitBoundHints = {
+    decorationType: vscode.window.createTextEditorDecorationType({
+        after: {
+            color: new vscode.ThemeColor('rust_analyzer.inlayHint'),
+            fontStyle: "normal",
+        }
+    }),
+
+    toDecoration(hint: ra.InlayHint.TraitBoundHint, conv: lc.Protocol2CodeConverter): vscode.DecorationOptions {
+        return {
+            range: conv.asRange(hint.range),
+            renderOptions: { after: { contentText: `: ${hint.label}` } }
+        };
+    }
+};
+
 class HintsUpdater implements Disposable {
     private sourceFiles = new Map<string, RustSourceFile>(); // map Uri -> RustSourceFile
     private readonly disposables: Disposable[] = [];
@@ -120,7 +137,7 @@ class HintsUpdater implements Disposable {
 
     dispose() {
         this.sourceFiles.forEach(file => file.inlaysRequest?.cancel());
-        this.ctx.visibleRustEditors.forEach(editor => this.renderDecorations(editor, { param: [], type: [], chaining: [] }));
+        this.ctx.visibleRustEditors.forEach(editor => this.renderDecorations(editor, { param: [], type: [], chaining: [], traitBound: [] }));
         this.disposables.forEach(d => d.dispose());
     }
 
@@ -180,10 +197,11 @@ class HintsUpdater implements Disposable {
         editor.setDecorations(typeHints.decorationType, decorations.type);
         editor.setDecorations(paramHints.decorationType, decorations.param);
         editor.setDecorations(chainingHints.decorationType, decorations.chaining);
+        editor.setDecorations(traitBoundHints.decorationType, decorations.traitBound);
     }
 
     private hintsToDecorations(hints: ra.InlayHint[]): InlaysDecorations {
-        const decorations: InlaysDecorations = { type: [], param: [], chaining: [] };
+        const decorations: InlaysDecorations = { type: [], param: [], chaining: [], traitBound: [] };
         const conv = this.ctx.client.protocol2CodeConverter;
 
         for (const hint of hints) {
@@ -200,6 +218,10 @@ class HintsUpdater implements Disposable {
                     decorations.chaining.push(chainingHints.toDecoration(hint, conv));
                     continue;
                 }
+                case ra.InlayHint.Kind.TraitBoundHint: {
+                    decorations.traitBound.push(traitBoundHints.toDecoration(hint, conv));
+                    continue;
+                }
             }
         }
         return decorations;
@@ -227,6 +249,7 @@ interface InlaysDecorations {
     type: vscode.DecorationOptions[];
     param: vscode.DecorationOptions[];
     chaining: vscode.DecorationOptions[];
+    traitBound: vscode.DecorationOptions[];
 }
 
 interface RustSourceFile {
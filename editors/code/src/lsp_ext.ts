@@ -33,6 +33,27 @@ export const matchingBrace = new lc.RequestType<MatchingBraceParams, lc.Position
 
 export const parentModule = new lc.RequestType<lc.TextDocumentPositionParams, lc.LocationLink[], void>("experimental/parentModule");
 
+export const showImplBlocks = new lc.RequestType<lc.TextDocumentPositionParams, lc.Location[], void>("rust-analyzer/showImplBlocks");
+
+export const showType = new lc.RequestType<lc.TextDocumentPositionParams, string | null, void>("rust-analyzer/showType");
+
+export interface FileTodos {
+    uri: string;
+    diagnostics: lc.Diagnostic[];
+}
+export const findTodos = new lc.RequestType<null, FileTodos[], void>("rust-analyzer/findTodos");
+
+export interface DependencyTreeParams {
+    package?: string;
+}
+export interface DependencyNode {
+    name: string;
+    version: string;
+    dependencies: DependencyNode[];
+    deduplicated: boolean;
+}
+export const dependencyTree = new lc.RequestType<DependencyTreeParams, DependencyNode[], void>("rust-analyzer/dependencyTree");
+
 export interface ResolveCodeActionParams {
     id: string;
     codeActionParams: lc.CodeActionParams;
@@ -64,13 +85,14 @@ export interface Runnable {
 }
 export const runnables = new lc.RequestType<RunnablesParams, Runnable[], void>("experimental/runnables");
 
-export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
+export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint | InlayHint.TraitBoundHint;
 
 export namespace InlayHint {
     export const enum Kind {
         TypeHint = "TypeHint",
         ParamHint = "ParameterHint",
         ChainingHint = "ChainingHint",
+        TraitBoundHint = "TraitBoundHint",
     }
     interface Common {
         range: lc.Range;
@@ -79,6 +101,7 @@ export namespace InlayHint {
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
+    export type TraitBoundHint = Common & { kind: Kind.TraitBoundHint };
 }
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;
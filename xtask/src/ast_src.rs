@@ -201,6 +201,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "NAME",
         "NAME_REF",
         "LET_STMT",
+        "LET_ELSE",
         "EXPR_STMT",
         "TYPE_PARAM_LIST",
         "LIFETIME_PARAM",
@@ -1761,6 +1762,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
         /// ❰ let bar: u64; ❱
         /// ❰ let baz = 42; ❱
         /// ❰ let bruh: bool = true; ❱
+        /// ❰ let Some(it) = opt else { return }; ❱
         /// ```
         ///
         /// [Reference](https://doc.rust-lang.org/reference/statements.html#let-statements)
@@ -1769,9 +1771,19 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             Pat,
             T![=],
             initializer: Expr,
+            LetElse,
             T![;],
         }
 
+        /// Diverging `else` block of a let statement.
+        ///
+        /// ```
+        /// let Some(it) = opt ❰ else { return } ❱;
+        /// ```
+        ///
+        /// [Reference](https://doc.rust-lang.org/reference/statements.html#let-statements)
+        struct LetElse { T![else], BlockExpr }
+
         /// Condition of `if` or `while` expression.
         ///
         /// ```
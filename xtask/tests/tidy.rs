@@ -58,6 +58,14 @@ fn check_todo(path: &Path, text: &str) {
         "handlers/add_missing_impl_members.rs",
         "handlers/add_function.rs",
         "handlers/add_turbo_fish.rs",
+        "handlers/generate_from_str_impl.rs",
+        "handlers/generate_test_matrix.rs",
+        "handlers/generate_proc_macro.rs",
+        "handlers/add_from_impl_for_error.rs",
+        "handlers/generate_deserialize_impl.rs",
+        // The TODO finder has to mention the markers it looks for.
+        "ra_ide/src/todos.rs",
+        "rust-analyzer/src/config.rs",
         // To support generating `todo!()` in assists, we have `expr_todo()` in ast::make.
         "ast/make.rs",
     ];